@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -22,7 +24,56 @@ impl ::std::default::Default for MyConfig {
 pub struct ConfigServer {
     pub log_file: String,
     pub bind_host: String,
+    /// The address of the PostgreSQL protocol, the server listens on it too for the clients like psql
+    #[serde(default)]
+    pub postgres_bind_host: Option<String>,
     pub engines: Vec<String>,
+    /// The rows of the tables with this column are isolated by the tenant id of the session
+    pub tenant_column: Option<String>,
+    /// The tenant ids of the users, the session of the user is bound to its tenant at auth.
+    /// The users without a tenant can not use the tables with the tenant column
+    #[serde(default)]
+    pub tenants: BTreeMap<String, String>,
+    /// Move the auto increment values past the largest values of the tables on startup
+    #[serde(default)]
+    pub repair_auto_increment: bool,
+    /// Move the keys of the tables created before the table ids to a table id on startup
+    #[serde(default)]
+    pub migrate_table_id: bool,
+    /// Order the GROUP BY results by the grouping columns when the query has no ORDER BY
+    #[serde(default)]
+    pub order_group_by: bool,
+    /// Check the metadata of the tables against the store on startup, and fail on the problems found
+    #[serde(default)]
+    pub check_meta: bool,
+    /// The milliseconds a SELECT may run before it is interrupted with the error 3024, 0 is no limit
+    #[serde(default)]
+    pub max_execution_time: u64,
+    /// The database of the sessions which do not select one, the unqualified tables are an error 1046 without it
+    #[serde(default)]
+    pub default_schema: Option<String>,
+    /// The directory LOAD DATA INFILE reads the files from, the statement is an error 1290 without it
+    #[serde(default)]
+    pub secure_file_priv: Option<String>,
+    /// Allow LOAD DATA LOCAL INFILE, the file is sent by the client which must allow it too
+    #[serde(default)]
+    pub local_infile: bool,
+    /// The session variables of the new connections before the first query, `sql_mode`, `autocommit`,
+    /// `time_zone` and `character_set`, SET changes them for the session
+    #[serde(default)]
+    pub session_variables: BTreeMap<String, String>,
+    /// The PEM certificate chain of the TLS connections, the server offers SSL to the clients with it and the key
+    #[serde(default)]
+    pub ssl_cert: Option<String>,
+    /// The PEM private key of the certificate, PKCS#8 or RSA
+    #[serde(default)]
+    pub ssl_key: Option<String>,
+    /// The PEM RSA private key of caching_sha2_password, the clients without TLS send the password encrypted by its public key
+    #[serde(default)]
+    pub caching_sha2_password_private_key_path: Option<String>,
+    /// The password of root when the data is initialized, a random one is written to the log without it
+    #[serde(default)]
+    pub initial_root_password: Option<String>,
 }
 
 /// `ConfigServer` implements `Default`
@@ -31,7 +82,23 @@ impl ::std::default::Default for ConfigServer {
         Self {
             log_file: "./log4rs.yaml".into(),
             bind_host: "0.0.0.0:3307".into(),
+            postgres_bind_host: None,
             engines: vec!["sled".to_string()],
+            tenant_column: None,
+            tenants: BTreeMap::new(),
+            repair_auto_increment: false,
+            migrate_table_id: false,
+            order_group_by: false,
+            check_meta: false,
+            max_execution_time: 0,
+            default_schema: None,
+            secure_file_priv: None,
+            local_infile: false,
+            session_variables: BTreeMap::new(),
+            ssl_cert: None,
+            ssl_key: None,
+            caching_sha2_password_private_key_path: None,
+            initial_root_password: None,
         }
     }
 }
@@ -65,12 +132,20 @@ impl ::std::default::Default for ConfigEngine {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EngineSled {
     pub data_path: String,
+    /// The number of record batches a scan reads ahead of the consumer, 0 reads them on demand
+    #[serde(default)]
+    pub read_ahead: usize,
+    /// The seconds between the removals of the row versions no snapshot reads, 0 never removes them
+    #[serde(default)]
+    pub gc_interval: u64,
 }
 
 impl ::std::default::Default for EngineSled {
     fn default() -> Self {
         Self {
             data_path: "./data/ebike/sled".into(),
+            read_ahead: 0,
+            gc_interval: 0,
         }
     }
 }
@@ -1,23 +1,39 @@
 use sqlparser::ast::Statement as SQLStatement;
 use datafusion::sql::parser::Statement;
 use std::ops::{DerefMut, Deref};
+use std::collections::{HashMap, VecDeque};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
+
+use crate::core::core_util;
 
 #[derive(Clone, Debug)]
 pub struct StmtCacheDef {
+    sql: String,
     num_params: usize,
     df_statements: Vec<Statement>,
     param_types: Vec<u8>,
+    /// The values of the parameters sent by COM_STMT_SEND_LONG_DATA, by the index of the parameter,
+    /// the chunks are appended until the next execution
+    long_data: HashMap<usize, Vec<u8>>,
+    /// The read only cursor opened by the last execution, the rows are fetched by COM_STMT_FETCH
+    cursor: Option<StmtCursor>,
 }
 
 impl StmtCacheDef {
-    pub fn new(df_statements: Vec<Statement>) -> Self {
+    pub fn new(sql: &str, df_statements: Vec<Statement>) -> Self {
         let num_params = 0;
         let param_types = vec![];
 
         Self {
+            sql: sql.to_string(),
             num_params,
             df_statements,
             param_types,
+            long_data: HashMap::new(),
+            cursor: None,
         }
     }
 }
@@ -31,6 +47,10 @@ impl StmtCacheDef {
         self.param_types = param_types.to_vec();
     }
 
+    pub fn get_sql(&self) -> String {
+        self.sql.clone()
+    }
+
     pub fn get_num_params(&self) -> usize {
         self.num_params
     }
@@ -42,4 +62,76 @@ impl StmtCacheDef {
     pub fn get_statements(&self) -> Vec<Statement> {
         self.df_statements.clone()
     }
+
+    pub fn append_long_data(&mut self, param_index: usize, data: &[u8]) {
+        self.long_data.entry(param_index).or_insert_with(Vec::new).extend_from_slice(data);
+    }
+
+    /// The long data is used by one execution only
+    pub fn take_long_data(&mut self) -> HashMap<usize, Vec<u8>> {
+        std::mem::take(&mut self.long_data)
+    }
+
+    pub fn clear_long_data(&mut self) {
+        self.long_data.clear();
+    }
+
+    /// The cursor of the new execution replaces the cursor of the one before it
+    pub fn open_cursor(&mut self, cursor: StmtCursor) {
+        self.cursor = Some(cursor);
+    }
+
+    pub fn get_cursor_mut(&mut self) -> Option<&mut StmtCursor> {
+        self.cursor.as_mut()
+    }
+
+    pub fn close_cursor(&mut self) {
+        self.cursor = None;
+    }
+}
+
+/// The result set of the execution with CURSOR_TYPE_READ_ONLY, the record batches are converted
+/// to rows one by one as they are fetched
+#[derive(Clone, Debug)]
+pub struct StmtCursor {
+    schema_ref: SchemaRef,
+    record_batches: VecDeque<RecordBatch>,
+    /// The rows of the current record batch which are not fetched yet
+    rows: VecDeque<Vec<ScalarValue>>,
+}
+
+impl StmtCursor {
+    pub fn new(schema_ref: SchemaRef, record_batches: Vec<RecordBatch>) -> Self {
+        Self {
+            schema_ref,
+            record_batches: record_batches.into_iter().collect(),
+            rows: VecDeque::new(),
+        }
+    }
+
+    pub fn get_schema_ref(&self) -> SchemaRef {
+        self.schema_ref.clone()
+    }
+
+    /// The next rows of the result set, at most num_rows
+    pub fn fetch(&mut self, num_rows: usize) -> Vec<Vec<ScalarValue>> {
+        let mut rows = Vec::with_capacity(num_rows.min(1024));
+        while rows.len() < num_rows {
+            match self.rows.pop_front() {
+                Some(row) => rows.push(row),
+                None => match self.record_batches.pop_front() {
+                    Some(record_batch) => {
+                        self.rows = core_util::convert_record_to_scalar_value(record_batch).into_iter().collect();
+                    }
+                    None => break,
+                },
+            }
+        }
+        rows
+    }
+
+    /// All the rows are fetched, the empty record batches left are not rows
+    pub fn is_exhausted(&self) -> bool {
+        self.rows.is_empty() && self.record_batches.iter().all(|record_batch| record_batch.num_rows() == 0)
+    }
 }
@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 
-use arrow::array::{as_primitive_array, as_string_array, Array, Float64Array};
-use arrow::array::{Int32Array, Int64Array, StringArray, UInt64Array};
-use arrow::datatypes::{DataType, Field, Schema};
+use arrow::array::{as_boolean_array, as_primitive_array, as_string_array, Array, ArrayRef, BinaryArray, DecimalArray, Float64Array, LargeBinaryArray};
+use arrow::array::{Int32Array, Int64Array, Int8Array, StringArray, UInt64Array};
+use arrow::datatypes::{ArrowPrimitiveType, DataType, Field, Schema};
+use arrow::datatypes::{Date32Type, Float32Type, Int16Type, Int32Type, UInt16Type, UInt32Type, UInt64Type, UInt8Type};
 use arrow::record_batch::RecordBatch;
+use chrono::NaiveDate;
 use datafusion::catalog::catalog::{CatalogProvider, MemoryCatalogProvider};
 use datafusion::catalog::schema::{MemorySchemaProvider, SchemaProvider};
 use datafusion::datasource::{MemTable, TableProvider};
@@ -13,92 +16,92 @@ use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::ExecutionContext;
 use datafusion::logical_plan::DFSchema;
 use datafusion::logical_plan::{Expr, LogicalPlan};
+use datafusion::physical_plan::functions::{make_scalar_function, ReturnTypeFunction, Signature, Volatility};
+use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::scalar::ScalarValue;
-use datafusion::sql::parser::{DFParser, Statement as DFStatement};
+use datafusion::sql::parser::DFParser;
+use datafusion::sql::planner::{ContextProvider, SqlToRel};
 use sqlparser::ast::{
-    Assignment, BinaryOperator, Expr as SQLExpr, Ident, ObjectName, Query, Select, SelectItem,
-    SetExpr, Statement as SQLStatement, TableFactor, TableWithJoins, Value, Values,
+    Assignment, BinaryOperator, ColumnDef as SQLColumnDef, DataType as SQLDataType, Expr as SQLExpr, Ident, ObjectName, Query, Select, SelectItem,
+    SetExpr, Statement as SQLStatement, TableFactor, TableWithJoins, UnaryOperator, Value,
 };
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Tokenizer;
 
 use crate::core::global_context::GlobalContext;
+use crate::core::plan_rewrite::PlanRewriteContext;
 use crate::core::session_context::SessionContext;
 use crate::datafusion_impl::catalog::information_schema::CatalogWithInformationSchemaProvider;
-use crate::meta::initial::read_all_table;
+use crate::datafusion_impl::datasource::temporary::TemporaryTable;
+use crate::datafusion_impl::datasource::view::ViewTable;
+use crate::meta::initial::{read_all_table, read_information_schema_views};
+use crate::meta::meta_def::{SparrowColumnDef, TableDef, TableIndexDef, ViewDef};
 use crate::meta::meta_util::read_all_schema;
 use crate::meta::{meta_const, meta_util};
 use crate::mysql::error::{MysqlError, MysqlResult};
-use crate::store::engine::engine_util::TableEngineFactory;
-
-pub fn stmt_value(stmt_values: Vec<SQLExpr>, df_statements: Vec<DFStatement>) -> Vec<DFStatement> {
-    let mut new_statements = vec![];
-    for sql_statement in df_statements {
-        let new_sql_statement = match sql_statement.clone() {
-            DFStatement::Statement(statement) => match statement {
-                SQLStatement::Insert {
-                    table_name,
-                    columns,
-                    overwrite,
-                    source,
-                    ..
-                } => {
-                    let source = stmt_value_insert(stmt_values.clone(), source);
-
-                    let insert = SQLStatement::Insert {
-                        or: None,
-                        table_name,
-                        columns,
-                        overwrite,
-                        source,
-                        partitioned: None,
-                        after_columns: vec![],
-                        table: false,
-                    };
-
-                    DFStatement::Statement(insert)
-                }
-                _ => sql_statement,
+use crate::mysql::mysql_error_code;
+use crate::store::compression;
+use crate::store::engine::engine_util::{StoreEngine, TableEngineFactory};
+use crate::store::lock::LockMode;
+use crate::util::convert::ToLowercase;
+use crate::util::dbkey::{create_column_key, create_table_index_key, scan_record_rowid};
+use crate::util::time_zone::MysqlTimeZone;
+use crate::util::{date_function, decimal, time_zone, user_variable};
+
+/// The LIMIT must be a non-negative integer, otherwise it is a syntax error as in mysql
+pub fn check_query_limit(query: &Query) -> MysqlResult<()> {
+    let limit = match &query.limit {
+        None => return Ok(()),
+        Some(limit) => limit,
+    };
+
+    let scalar_value = match limit {
+        SQLExpr::Value(Value::Number(value, _)) => match value.parse::<i64>() {
+            Ok(value) => Some(ScalarValue::Int64(Some(value))),
+            Err(_) => None,
+        },
+        SQLExpr::UnaryOp { op: UnaryOperator::Minus, expr } => match expr.as_ref() {
+            SQLExpr::Value(Value::Number(value, _)) => match value.parse::<i64>() {
+                Ok(value) => Some(ScalarValue::Int64(Some(-value))),
+                Err(_) => None,
             },
-            _ => sql_statement,
-        };
+            _ => None,
+        },
+        SQLExpr::Value(Value::SingleQuotedString(value)) => Some(ScalarValue::Utf8(Some(value.clone()))),
+        _ => None,
+    };
 
-        new_statements.push(new_sql_statement)
+    let result = match scalar_value {
+        Some(scalar_value) => get_real_value(Expr::Literal(scalar_value)),
+        None => Err(DataFusionError::Execution(format!("Limit only supports non-negative integer literals, expr: {:?}", limit))),
+    };
+    match result {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use near '{}'",
+                limit
+            );
+            log::error!("{}", message);
+            Err(MysqlError::new_server_error(1064, "42000", message.as_str()))
+        }
     }
-
-    new_statements
 }
 
-pub fn stmt_value_insert(stmt_values: Vec<SQLExpr>, source: Box<Query>) -> Box<Query> {
-    let mut new_source = source.clone();
-    match &source.body {
-        SetExpr::Values(values) => {
-            let mut current = 0;
-
-            let values = values
-                .0
-                .iter()
-                .map(|row| {
-                    row.iter()
-                        .map(|v| match v {
-                            SQLExpr::Value(Value::OnlyString(_)) => {
-                                let new_value = stmt_values.get(current).unwrap();
-                                current += 1;
-                                new_value.clone()
-                            }
-                            _ => v.clone(),
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>();
-
-            let new_values = Values(values);
-
-            new_source.body = SetExpr::Values(new_values);
-        }
-        _ => {}
+/// The query text without the surrounding whitespace and the trailing semicolons,
+/// a query with nothing left is the error "Query was empty" as in mysql
+pub fn normalize_query_sql(sql: &str) -> MysqlResult<&str> {
+    let mut query_sql = sql.trim();
+    while query_sql.ends_with(';') {
+        query_sql = query_sql.trim_end_matches(';').trim_end();
     }
-
-    new_source
+    if query_sql.is_empty() {
+        return Err(MysqlError::new_server_error(1065, "42000", "Query was empty"));
+    }
+    Ok(query_sql)
 }
+
 //
 // pub struct StmtPrepareValue {
 //     pub index: i64,
@@ -158,6 +161,14 @@ pub fn check_table_exists(
                             &name,
                         );
                     }
+                    TableFactor::Derived { subquery, .. } => {
+                        return check_table_exists(
+                            global_context.clone(),
+                            session_context,
+                            execution_context,
+                            &subquery,
+                        );
+                    }
                     _ => {}
                 }
 
@@ -176,6 +187,23 @@ pub fn check_table_exists(
                 }
             }
         }
+        // the tables of each SELECT of UNION, INTERSECT and EXCEPT
+        SetExpr::SetOperation { left, right, .. } => {
+            for set_expr in [left, right] {
+                let query = Query {
+                    with: None,
+                    body: set_expr.as_ref().clone(),
+                    order_by: vec![],
+                    limit: None,
+                    offset: None,
+                    fetch: None,
+                };
+                check_table_exists(global_context.clone(), session_context, execution_context, &query)?;
+            }
+        }
+        SetExpr::Query(query) => {
+            return check_table_exists(global_context.clone(), session_context, execution_context, query);
+        }
         _ => {}
     }
     Ok(())
@@ -196,6 +224,25 @@ pub fn check_table_exists_with_name(
     let schema_name = meta_util::cut_out_schema_name(full_table_name.clone());
     let table_name = meta_util::cut_out_table_name(full_table_name.clone());
 
+    let full_temporary_table_name = meta_util::object_name_remove_quote(full_table_name.clone()).to_lowercase();
+    if session_context.get_temporary_table(&full_temporary_table_name).is_some() {
+        return Ok(());
+    }
+
+    // the rows of a common table expression are registered while its query is planned
+    if schema_name
+        .to_string()
+        .eq(meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION)
+    {
+        let is_registered = get_catalog_provider(execution_context, meta_const::CATALOG_NAME)
+            .schema(meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION)
+            .map(|schema_provider| schema_provider.table_exist(table_name.to_string().as_str()))
+            .unwrap_or(false);
+        if is_registered {
+            return Ok(());
+        }
+    }
+
     if schema_name
         .to_string()
         .eq(meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA)
@@ -211,6 +258,27 @@ pub fn check_table_exists_with_name(
         }
     }
 
+    // the view is registered while the tables and the columns of its definition exist
+    let full_view_name = meta_util::object_name_remove_quote(full_table_name.clone());
+    let is_view = global_context.lock().unwrap().meta_data.get_view(full_view_name).is_some();
+    if is_view {
+        let is_registered = get_catalog_provider(execution_context, meta_const::CATALOG_NAME)
+            .schema(schema_name.to_string().as_str())
+            .map(|schema_provider| schema_provider.table_exist(table_name.to_string().as_str()))
+            .unwrap_or(false);
+        if is_registered {
+            return Ok(());
+        }
+        return Err(MysqlError::new_server_error(
+            mysql_error_code::ER_VIEW_INVALID as u16,
+            "HY000",
+            format!(
+                "View '{}.{}' references invalid table(s) or column(s) or function(s) or definer/invoker of view lack rights to use them",
+                schema_name, table_name,
+            ).as_str(),
+        ));
+    }
+
     let result = meta_util::get_table(global_context.clone(), full_table_name.clone());
     if let Err(mysql_error) = result {
         return Err(mysql_error);
@@ -219,8 +287,54 @@ pub fn check_table_exists_with_name(
     Ok(())
 }
 
+/// The plan of the definition of a view, optimized and rewritten as the plan of a SELECT.
+/// The tables of the definition must be registered in the execution context
+pub fn plan_view_definition(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    execution_context: &mut ExecutionContext,
+    definition: &str,
+) -> MysqlResult<LogicalPlan> {
+    let dialect = GenericDialect {};
+    let statements = Parser::parse_sql(&dialect, definition).map_err(|parser_error| {
+        let message = format!("The definition of the view is broken, definition: {}, error: {:?}", definition, parser_error);
+        log::error!("{}", message);
+        MysqlError::new_global_error(meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR, message.as_str())
+    })?;
+    let query = match statements.into_iter().next() {
+        Some(SQLStatement::Query(query)) => query,
+        _ => {
+            let message = format!("The definition of the view is not a SELECT, definition: {}", definition);
+            return Err(MysqlError::new_global_error(meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR, message.as_str()));
+        }
+    };
+
+    let state = execution_context.state.lock().unwrap().clone();
+    let logical_plan = SqlToRel::new(&state).query_to_plan(&query)?;
+    let logical_plan = execution_context.optimize(&logical_plan)?;
+
+    let plan_rewrite = global_context.lock().unwrap().plan_rewrite.clone();
+    let plan_rewrite_context = PlanRewriteContext {
+        session_context,
+        query: &query,
+    };
+    plan_rewrite.rewrite(&logical_plan, &plan_rewrite_context)
+}
+
+pub fn create_view_provider(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    execution_context: &mut ExecutionContext,
+    view_def: &ViewDef,
+) -> MysqlResult<Arc<dyn TableProvider>> {
+    let logical_plan = plan_view_definition(global_context, session_context, execution_context, view_def.definition.as_str())?;
+    let view_table = ViewTable::try_new(view_def.clone(), logical_plan)?;
+    Ok(Arc::new(view_table))
+}
+
 pub fn register_catalog(
     global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
     execution_context: &mut ExecutionContext,
     catalog_name: &str,
 ) {
@@ -228,6 +342,7 @@ pub fn register_catalog(
     let catalog_provider = Arc::new(MemoryCatalogProvider::new());
     let catalog = Arc::new(CatalogWithInformationSchemaProvider::new(
         global_context,
+        session_context.clone(),
         state.catalog_list.clone(),
         catalog_provider,
     ));
@@ -286,8 +401,10 @@ pub fn get_schema_provider(
     schema_provider
 }
 
+/// The tables of the session, their scans read the snapshot of the transaction of the session
 pub fn register_all_table(
     global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
     datafusion_context: &mut ExecutionContext,
 ) -> MysqlResult<()> {
     let mut catalog_map = HashMap::new();
@@ -315,6 +432,7 @@ pub fn register_all_table(
     for (catalog_name, schema_map) in catalog_map.iter() {
         register_catalog(
             global_context.clone(),
+            session_context,
             datafusion_context,
             catalog_name.as_str(),
         );
@@ -333,7 +451,7 @@ pub fn register_all_table(
                     full_table_name.clone(),
                 );
                 let table_provider = match engine {
-                    Ok(engine) => engine.table_provider(),
+                    Ok(engine) => engine.table_provider_in_session(session_context),
                     Err(mysql_error) => return Err(mysql_error),
                 };
 
@@ -351,11 +469,94 @@ pub fn register_all_table(
         }
     }
 
+    register_all_view(global_context, session_context, datafusion_context)?;
+    register_all_temporary_table(session_context, datafusion_context)
+}
+
+/// The temporary tables of the session are registered over the tables of the same names.
+/// The temporary table of a dropped schema is left out until the schema is created again
+fn register_all_temporary_table(
+    session_context: &SessionContext,
+    datafusion_context: &mut ExecutionContext,
+) -> MysqlResult<()> {
+    for temporary_table in session_context.get_temporary_table_list() {
+        let full_table_name = temporary_table.full_table_name();
+        let catalog_name = meta_util::cut_out_catalog_name(full_table_name.clone()).to_string();
+        let schema_name = meta_util::cut_out_schema_name(full_table_name.clone()).to_string();
+        let table_name = meta_util::cut_out_table_name(full_table_name.clone()).to_string();
+
+        let schema_exists = get_catalog_provider(datafusion_context, catalog_name.as_str())
+            .schema(schema_name.as_str())
+            .is_some();
+        if !schema_exists {
+            continue;
+        }
+        register_table(
+            datafusion_context,
+            catalog_name.as_str(),
+            schema_name.as_str(),
+            table_name.as_str(),
+            Arc::new(temporary_table),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The temporary table of the session with the table name, None if there is none
+pub fn get_temporary_table(session_context: &mut SessionContext, table_name: &ObjectName) -> Option<TemporaryTable> {
+    let full_table_name = meta_util::resolve_table_name(session_context, table_name).ok()?;
+    let full_table_name = meta_util::object_name_remove_quote(full_table_name).to_lowercase();
+    session_context.get_temporary_table(&full_table_name)
+}
+
+/// The views are planned over the tables, a view over other views is planned after them.
+/// The view whose tables or columns are gone is left out, selecting from it fails
+fn register_all_view(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    datafusion_context: &mut ExecutionContext,
+) -> MysqlResult<()> {
+    let view_map = read_information_schema_views(global_context.clone())?;
+    // the plans of the views read the tables registered just now, not the ones they were planned with
+    for view_def in view_map.values() {
+        let catalog_provider = get_catalog_provider(datafusion_context, view_def.catalog_name.as_str());
+        if let Some(schema_provider) = catalog_provider.schema(view_def.schema_name.as_str()) {
+            schema_provider.deregister_table(view_def.view_name.as_str())?;
+        }
+    }
+
+    let mut pending_view_list: Vec<ViewDef> = view_map.values().cloned().collect();
+    loop {
+        let mut failed_view_list = vec![];
+        for view_def in pending_view_list.iter() {
+            let result = create_view_provider(global_context.clone(), session_context, datafusion_context, view_def);
+            match result {
+                Ok(table_provider) => register_table(
+                    datafusion_context,
+                    view_def.catalog_name.as_str(),
+                    view_def.schema_name.as_str(),
+                    view_def.view_name.as_str(),
+                    table_provider,
+                )?,
+                Err(mysql_error) => failed_view_list.push((view_def.clone(), mysql_error)),
+            }
+        }
+
+        if failed_view_list.len() == pending_view_list.len() {
+            for (view_def, mysql_error) in failed_view_list.iter() {
+                log::warn!("The view {} is invalid, error: {}", view_def.full_view_name, mysql_error);
+            }
+            break;
+        }
+        pending_view_list = failed_view_list.into_iter().map(|(view_def, _)| view_def).collect();
+    }
+
     Ok(())
 }
 
 pub fn convert_record_to_scalar_value(record_batch: RecordBatch) -> Vec<Vec<ScalarValue>> {
-    let mut rows: Vec<Vec<ScalarValue>> = Vec::new();
+    let mut rows: Vec<Vec<ScalarValue>> = vec![Vec::with_capacity(record_batch.num_columns()); record_batch.num_rows()];
 
     let schema = record_batch.schema();
     for column_index in 0..record_batch.num_columns() {
@@ -370,13 +571,31 @@ pub fn convert_record_to_scalar_value(record_batch: RecordBatch) -> Vec<Vec<Scal
                         value = Some(column.value(row_index).to_string());
                     }
 
-                    if let Some(row) = rows.get_mut(row_index) {
-                        row.insert(column_index, ScalarValue::Utf8(value));
-                    } else {
-                        let mut row = vec![];
-                        row.insert(column_index, ScalarValue::Utf8(value));
-                        rows.insert(row_index, row);
+                    rows[row_index].push(ScalarValue::Utf8(value));
+                }
+            }
+            DataType::Boolean => {
+                let column = as_boolean_array(record_batch.column(column_index));
+
+                for row_index in 0..record_batch.num_rows() {
+                    let mut value = None;
+                    if !column.is_null(row_index) {
+                        value = Some(column.value(row_index));
+                    }
+
+                    rows[row_index].push(ScalarValue::Boolean(value));
+                }
+            }
+            DataType::Int8 => {
+                let column: &Int8Array = as_primitive_array(record_batch.column(column_index));
+
+                for row_index in 0..record_batch.num_rows() {
+                    let mut value = None;
+                    if !column.is_null(row_index) {
+                        value = Some(column.value(row_index));
                     }
+
+                    rows[row_index].push(ScalarValue::Int8(value));
                 }
             }
             DataType::Int64 => {
@@ -388,15 +607,16 @@ pub fn convert_record_to_scalar_value(record_batch: RecordBatch) -> Vec<Vec<Scal
                         value = Some(column.value(row_index));
                     }
 
-                    if let Some(row) = rows.get_mut(row_index) {
-                        row.insert(column_index, ScalarValue::Int64(value));
-                    } else {
-                        let mut row = vec![];
-                        row.insert(column_index, ScalarValue::Int64(value));
-                        rows.insert(row_index, row);
-                    }
+                    rows[row_index].push(ScalarValue::Int64(value));
                 }
             }
+            DataType::Int16 => push_primitive_values::<Int16Type>(&mut rows, record_batch.column(column_index), ScalarValue::Int16),
+            DataType::Int32 => push_primitive_values::<Int32Type>(&mut rows, record_batch.column(column_index), ScalarValue::Int32),
+            DataType::UInt8 => push_primitive_values::<UInt8Type>(&mut rows, record_batch.column(column_index), ScalarValue::UInt8),
+            DataType::UInt16 => push_primitive_values::<UInt16Type>(&mut rows, record_batch.column(column_index), ScalarValue::UInt16),
+            DataType::UInt32 => push_primitive_values::<UInt32Type>(&mut rows, record_batch.column(column_index), ScalarValue::UInt32),
+            DataType::UInt64 => push_primitive_values::<UInt64Type>(&mut rows, record_batch.column(column_index), ScalarValue::UInt64),
+            DataType::Float32 => push_primitive_values::<Float32Type>(&mut rows, record_batch.column(column_index), ScalarValue::Float32),
             DataType::Float64 => {
                 let column: &Float64Array = as_primitive_array(record_batch.column(column_index));
 
@@ -406,13 +626,51 @@ pub fn convert_record_to_scalar_value(record_batch: RecordBatch) -> Vec<Vec<Scal
                         value = Some(column.value(row_index));
                     }
 
-                    if let Some(row) = rows.get_mut(row_index) {
-                        row.insert(column_index, ScalarValue::Float64(value));
-                    } else {
-                        let mut row = vec![];
-                        row.insert(column_index, ScalarValue::Float64(value));
-                        rows.insert(row_index, row);
+                    rows[row_index].push(ScalarValue::Float64(value));
+                }
+            }
+            DataType::Decimal(precision, scale) => {
+                let column = record_batch.column(column_index).as_any().downcast_ref::<DecimalArray>().unwrap();
+
+                for row_index in 0..record_batch.num_rows() {
+                    let mut value = None;
+                    if !column.is_null(row_index) {
+                        value = Some(column.value(row_index));
+                    }
+
+                    rows[row_index].push(ScalarValue::Decimal128(value, *precision, *scale));
+                }
+            }
+            DataType::Binary => {
+                let column = record_batch.column(column_index).as_any().downcast_ref::<BinaryArray>().unwrap();
+
+                for row_index in 0..record_batch.num_rows() {
+                    let mut value = None;
+                    if !column.is_null(row_index) {
+                        value = Some(column.value(row_index).to_vec());
+                    }
+
+                    rows[row_index].push(ScalarValue::Binary(value));
+                }
+            }
+            DataType::LargeBinary => {
+                let column = record_batch.column(column_index).as_any().downcast_ref::<LargeBinaryArray>().unwrap();
+
+                for row_index in 0..record_batch.num_rows() {
+                    let mut value = None;
+                    if !column.is_null(row_index) {
+                        value = Some(column.value(row_index).to_vec());
                     }
+
+                    rows[row_index].push(ScalarValue::LargeBinary(value));
+                }
+            }
+            DataType::Date32 => push_primitive_values::<Date32Type>(&mut rows, record_batch.column(column_index), ScalarValue::Date32),
+            DataType::Timestamp(_, _) => {
+                let column = record_batch.column(column_index);
+
+                for row_index in 0..record_batch.num_rows() {
+                    rows[row_index].push(ScalarValue::try_from_array(column, row_index).unwrap());
                 }
             }
             _ => {
@@ -426,6 +684,23 @@ pub fn convert_record_to_scalar_value(record_batch: RecordBatch) -> Vec<Vec<Scal
     rows
 }
 
+/// Push the values of the primitive column to the rows, as the scalar values made by `to_scalar_value`
+fn push_primitive_values<T: ArrowPrimitiveType>(
+    rows: &mut [Vec<ScalarValue>],
+    column: &ArrayRef,
+    to_scalar_value: fn(Option<T::Native>) -> ScalarValue,
+) {
+    let column = as_primitive_array::<T>(column);
+    for (row_index, row) in rows.iter_mut().enumerate() {
+        let mut value = None;
+        if !column.is_null(row_index) {
+            value = Some(column.value(row_index));
+        }
+
+        row.push(to_scalar_value(value));
+    }
+}
+
 // pub fn query_to_plan<S: SchemaProvider>(query: &Query, query_planner: &SqlToRel<S>) -> Result<LogicalPlan> {
 //     let plan = match &query.body {
 //         SetExpr::Select(s) => query_planner.select_to_plan(s.as_ref()),
@@ -448,6 +723,753 @@ pub fn captured_name(current_db: Arc<Mutex<Option<String>>>) -> Option<String> {
     db_name
 }
 
+/// The tenant column of the table and the tenant id of the session, None if the table has no tenant column.
+/// The client session without a tenant can not use the tables with the tenant column
+pub fn get_table_tenant(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    table_def: &TableDef,
+) -> MysqlResult<Option<(SparrowColumnDef, String)>> {
+    let tenant_column = match global_context.lock().unwrap().my_config.server.tenant_column.clone() {
+        Some(tenant_column) => tenant_column,
+        None => return Ok(None),
+    };
+    let sparrow_column = match table_def.get_table_column().get_sparrow_column(Ident::new(tenant_column)) {
+        Ok(sparrow_column) => sparrow_column,
+        Err(_) => return Ok(None),
+    };
+    let tenant_id = session_context.tenant_id.lock().unwrap().clone();
+    match tenant_id {
+        Some(tenant_id) => Ok(Some((sparrow_column, tenant_id))),
+        // the sessions opened by the server itself are not isolated
+        None if session_context.current_user.lock().unwrap().is_none() => Ok(None),
+        None => Err(error_of_no_tenant(table_def.option.table_name.as_str())),
+    }
+}
+
+/// The client session without a tenant uses a table with the tenant column
+pub fn error_of_no_tenant(table_name: &str) -> MysqlError {
+    let message = format!("Access denied; the session has no tenant for table '{}'", table_name);
+    log::error!("{}", message);
+    MysqlError::new_server_error(
+        mysql_error_code::ER_SPECIFIC_ACCESS_DENIED_ERROR as u16,
+        "42000",
+        message.as_str(),
+    )
+}
+
+/// The tenant id as a value of the type of the tenant column
+pub fn convert_tenant_id_to_scalar_value(data_type: &DataType, column_name: &str, tenant_id: &str) -> MysqlResult<ScalarValue> {
+    match ScalarValue::try_from_string(tenant_id.to_string(), data_type) {
+        Ok(value) if !value.is_null() => Ok(value),
+        _ => {
+            let type_name = match data_type {
+                DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64
+                | DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => "integer".to_string(),
+                _ => data_type.to_string().to_lowercase(),
+            };
+            Err(MysqlError::new_server_error(
+                1366,
+                "HY000",
+                format!("Incorrect {} value: '{}' for column '{}'", type_name, tenant_id, column_name).as_str(),
+            ))
+        }
+    }
+}
+
+/// The rowids of all the rows of the table, the rowid keys are scanned in batches
+pub fn scan_rowids(store_engine: &dyn StoreEngine, table_def: &TableDef) -> MysqlResult<Vec<String>> {
+    const SCAN_BATCH_SIZE: usize = 1024;
+
+    let prefix = scan_record_rowid(&table_def.option);
+    let mut rowid_list = vec![];
+    let mut last_rowid_key = None;
+    loop {
+        let rowid_key_list = store_engine.scan_prefix_keys(prefix.clone(), last_rowid_key.clone(), SCAN_BATCH_SIZE)?;
+        for rowid_key in rowid_key_list.iter() {
+            rowid_list.push(rowid_key[prefix.len()..].to_string());
+        }
+        if rowid_key_list.len() < SCAN_BATCH_SIZE {
+            return Ok(rowid_list);
+        }
+        last_rowid_key = rowid_key_list.last().cloned();
+    }
+}
+
+/// The index keys of the row from the stored column values,
+/// none if the index does not contain the row
+pub fn read_index_keys(table_def: &TableDef, table_index_list: &[TableIndexDef], store_engine: &dyn StoreEngine, rowid: &str) -> MysqlResult<Vec<Option<String>>> {
+    read_index_keys_with(table_def, table_index_list, rowid, |key| store_engine.get_key(key))
+}
+
+/// The index keys of the row from the column values read by get_key, as the values staged by a write
+pub fn read_index_keys_with<F>(table_def: &TableDef, table_index_list: &[TableIndexDef], rowid: &str, get_key: F) -> MysqlResult<Vec<Option<String>>>
+where
+    F: Fn(String) -> MysqlResult<Option<Vec<u8>>>,
+{
+    let mut index_keys = vec![];
+    for table_index in table_index_list {
+        let mut column_value_map = HashMap::new();
+        let mut contains_null = false;
+        for column_name in table_index.column_name_list.iter() {
+            let sparrow_column = table_def.get_table_column().get_sparrow_column(column_name.clone()).unwrap();
+            let value = read_column_value_with(table_def, &sparrow_column, rowid, &get_key)?;
+            contains_null = contains_null || value.is_none();
+            column_value_map.insert(column_name.clone(), stored_value_to_scalar_value(&sparrow_column.sql_column.data_type, value));
+        }
+
+        if contains_null && !table_def.option.index_nulls {
+            index_keys.push(None);
+            continue;
+        }
+
+        let mut index_key = create_table_index_key(table_def.clone(), table_index.clone(), column_value_map)?;
+        if contains_null || table_index.level == meta_const::INDEX_LEVEL_OF_FOREIGN_KEY {
+            index_key.push_str(rowid);
+            index_key.push_str("/");
+        }
+        index_keys.push(Some(index_key));
+    }
+    Ok(index_keys)
+}
+
+/// The stored text of the value of the column as the value of the index key of the column
+pub fn stored_value_to_scalar_value(sql_data_type: &SQLDataType, value: Option<String>) -> ScalarValue {
+    match sql_data_type {
+        SQLDataType::TinyInt(_) => ScalarValue::Int8(value.and_then(|value| value.parse::<i8>().ok())),
+        SQLDataType::Int(_) => ScalarValue::Int64(value.and_then(|value| value.parse::<i64>().ok())),
+        _ if meta_util::is_binary_data_type(sql_data_type) => ScalarValue::Binary(value.and_then(|value| hex::decode(value).ok())),
+        _ => ScalarValue::Utf8(value),
+    }
+}
+
+/// The stored value of the column of the row as text, None for NULL
+pub fn read_column_value_with<F>(table_def: &TableDef, sparrow_column: &SparrowColumnDef, rowid: &str, get_key: &F) -> MysqlResult<Option<String>>
+where
+    F: Fn(String) -> MysqlResult<Option<Vec<u8>>>,
+{
+    let record_column_key = create_column_key(&table_def.option, sparrow_column.store_id, rowid);
+    match get_key(record_column_key)? {
+        Some(value) if !(value.len() == 1 && value[0] == 0x00) => {
+            let value = compression::decompress_column_value(value.as_slice())?;
+            Ok(Some(String::from_utf8_lossy(value.as_slice()).to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The values of the unique key of the row, read by get_key
+pub fn read_index_values_with<F>(table_def: &TableDef, table_index: &TableIndexDef, rowid: &str, get_key: &F) -> MysqlResult<Vec<Option<String>>>
+where
+    F: Fn(String) -> MysqlResult<Option<Vec<u8>>>,
+{
+    let mut values = vec![];
+    for column_name in table_index.column_name_list.iter() {
+        let sparrow_column = table_def.get_table_column().get_sparrow_column(column_name.clone()).unwrap();
+        values.push(read_column_value_with(table_def, &sparrow_column, rowid, get_key)?);
+    }
+    Ok(values)
+}
+
+/// The values of the key are joined by '-' as MySQL does, "Duplicate entry '1-a' for key 'user.PRIMARY'"
+pub fn duplicate_entry_error(table_name: &str, index_name: &str, values: Vec<Option<String>>) -> MysqlError {
+    let entry = values
+        .into_iter()
+        .map(|value| value.unwrap_or_else(|| "NULL".to_string()))
+        .collect::<Vec<String>>()
+        .join("-");
+    let message = format!("Duplicate entry '{}' for key '{}.{}'", entry, table_name, index_name);
+    log::error!("{}", message);
+    MysqlError::new_server_error(mysql_error_code::ER_DUP_ENTRY as u16, "23000", message.as_str())
+}
+
+/// Fill up the auto increment column of the rows which do not give it, NULL and 0 take the next value too.
+/// The next value is saved before the rows are written, so a value is never handed out twice.
+/// Returns the first value generated, None if all the rows give the value
+pub fn fill_auto_increment(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_def: &TableDef,
+    column_value_map_list: &mut Vec<HashMap<Ident, ScalarValue>>,
+) -> MysqlResult<Option<u64>> {
+    let sparrow_column = match table_def.get_auto_increment_column() {
+        None => return Ok(None),
+        Some(sparrow_column) => sparrow_column,
+    };
+    let column_name = sparrow_column.sql_column.name.clone();
+
+    let auto_increment_lock = global_context.lock().unwrap().auto_increment_lock.clone();
+    let _guard = auto_increment_lock.lock().unwrap();
+
+    let mut next_value = meta_util::read_auto_increment(global_context.clone(), table_def)?;
+    let mut first_generated_value = None;
+    for column_value_map in column_value_map_list.iter_mut() {
+        match column_value_map.get(&column_name) {
+            Some(ScalarValue::Int64(Some(value))) if *value > 0 => {
+                next_value = next_value.max(*value + 1);
+            }
+            _ => {
+                column_value_map.insert(column_name.clone(), ScalarValue::Int64(Some(next_value)));
+                first_generated_value.get_or_insert(next_value as u64);
+                next_value += 1;
+            }
+        }
+    }
+
+    meta_util::save_auto_increment(global_context.clone(), table_def, next_value)?;
+    Ok(first_generated_value)
+}
+
+/// The max execution time of SELECT of the session, None if the query is not limited
+pub fn get_max_execution_time(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+) -> Option<Duration> {
+    let max_execution_time = match *session_context.max_execution_time.lock().unwrap() {
+        Some(max_execution_time) => max_execution_time,
+        None => global_context.lock().unwrap().my_config.server.max_execution_time,
+    };
+    if max_execution_time == 0 {
+        return None;
+    }
+    Some(Duration::from_millis(max_execution_time))
+}
+
+/// The seconds a statement waits for the row locks of the other transactions, the session innodb_lock_wait_timeout
+pub fn get_lock_wait_timeout(global_context: Arc<Mutex<GlobalContext>>, session_context: &SessionContext) -> Duration {
+    let lock_wait_timeout = session_context
+        .get_session_variable(meta_const::SYSTEM_VARIABLE_INNODB_LOCK_WAIT_TIMEOUT)
+        .or_else(|| global_context.lock().unwrap().variable.get_variable_value(meta_const::SYSTEM_VARIABLE_INNODB_LOCK_WAIT_TIMEOUT))
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(50);
+    Duration::from_secs(lock_wait_timeout)
+}
+
+/// The iterations a recursive common table expression may run, the session cte_max_recursion_depth
+pub fn get_cte_max_recursion_depth(global_context: Arc<Mutex<GlobalContext>>, session_context: &SessionContext) -> u64 {
+    session_context
+        .get_session_variable(meta_const::SYSTEM_VARIABLE_CTE_MAX_RECURSION_DEPTH)
+        .or_else(|| global_context.lock().unwrap().variable.get_variable_value(meta_const::SYSTEM_VARIABLE_CTE_MAX_RECURSION_DEPTH))
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(1000)
+}
+
+/// The rowids in the first column of the record batches, as the rowid selects of UPDATE and DELETE return them
+pub fn get_rowids(record_batches: &[RecordBatch]) -> Vec<String> {
+    let mut rowids = vec![];
+    for record_batch in record_batches {
+        let rowid_array = as_string_array(record_batch.column(0));
+        for row_index in 0..rowid_array.len() {
+            rowids.push(rowid_array.value(row_index).to_string());
+        }
+    }
+    rowids
+}
+
+/// Lock the rows for the transaction of the session until it ends, the session outside a transaction locks nothing
+pub async fn lock_rows(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    full_table_name: &ObjectName,
+    rowids: Vec<String>,
+    lock_mode: LockMode,
+) -> MysqlResult<()> {
+    let lock_owner = session_context.transaction.lock().unwrap().as_ref().map(|transaction| transaction.lock_owner());
+    let lock_owner = match lock_owner {
+        Some(lock_owner) => lock_owner,
+        None => return Ok(()),
+    };
+    let lock_manager = global_context.lock().unwrap().lock_manager.clone();
+    let lock_wait_timeout = get_lock_wait_timeout(global_context.clone(), session_context);
+    lock_manager
+        .lock_rows(&lock_owner, full_table_name.to_string().as_str(), rowids, lock_mode, lock_wait_timeout)
+        .await
+}
+
+/// The statement is cancelled by KILL QUERY or KILL CONNECTION
+pub fn query_interrupted_error() -> MysqlError {
+    MysqlError::new_server_error(1317, "70100", "Query execution was interrupted")
+}
+
+pub fn execution_timeout_error() -> MysqlError {
+    MysqlError::new_server_error(
+        3024,
+        "70100",
+        "Query execution was interrupted, maximum statement execution time exceeded",
+    )
+}
+
+/// The statements reading the warnings and the errors of the last statement do not clear them
+pub fn is_diagnostics_statement(sql: &str) -> bool {
+    let sql = sql.trim().to_lowercase();
+    sql.starts_with("show warnings")
+        || sql.starts_with("show errors")
+        || sql.starts_with("show count(*)")
+        || sql.contains(format!("@@{}", meta_const::SYSTEM_VARIABLE_WARNING_COUNT).as_str())
+        || sql.contains(format!("@@{}", meta_const::SYSTEM_VARIABLE_ERROR_COUNT).as_str())
+        || sql.contains(format!("@@session.{}", meta_const::SYSTEM_VARIABLE_WARNING_COUNT).as_str())
+        || sql.contains(format!("@@session.{}", meta_const::SYSTEM_VARIABLE_ERROR_COUNT).as_str())
+}
+
+/// The rows of the record batches from the offset, at most limit rows,
+/// the batches past the limit are not looked at
+pub fn limit_record_batches(record_batches: Vec<RecordBatch>, offset: usize, limit: usize) -> Vec<RecordBatch> {
+    let mut skip_rows = offset;
+    let mut rest_rows = limit;
+    let mut new_record_batches = vec![];
+    for record_batch in record_batches {
+        if rest_rows == 0 {
+            break;
+        }
+        let num_rows = record_batch.num_rows();
+        if skip_rows >= num_rows {
+            skip_rows -= num_rows;
+            continue;
+        }
+        let length = (num_rows - skip_rows).min(rest_rows);
+        new_record_batches.push(record_batch.slice(skip_rows, length));
+        rest_rows -= length;
+        skip_rows = 0;
+    }
+    new_record_batches
+}
+
+/// STRICT_TRANS_TABLES or STRICT_ALL_TABLES is in the sql mode
+pub fn is_strict_sql_mode(sql_mode: &str) -> bool {
+    sql_mode.split(',').any(|mode| {
+        let mode = mode.trim().to_uppercase();
+        mode == meta_const::SQL_MODE_STRICT_TRANS_TABLES || mode == meta_const::SQL_MODE_STRICT_ALL_TABLES
+    })
+}
+
+/// The text of the value in the array, as it is sent to the client
+pub fn array_value_to_text(array: &ArrayRef, row_index: usize) -> Result<Option<String>> {
+    if array.is_null(row_index) {
+        return Ok(None);
+    }
+
+    match array.data_type() {
+        DataType::Utf8 => Ok(Some(as_string_array(array).value(row_index).to_string())),
+        DataType::Int8 => Ok(Some(as_primitive_array::<arrow::datatypes::Int8Type>(array).value(row_index).to_string())),
+        DataType::Int32 => Ok(Some(as_primitive_array::<arrow::datatypes::Int32Type>(array).value(row_index).to_string())),
+        DataType::Int64 => Ok(Some(as_primitive_array::<arrow::datatypes::Int64Type>(array).value(row_index).to_string())),
+        DataType::Float64 => Ok(Some(as_primitive_array::<arrow::datatypes::Float64Type>(array).value(row_index).to_string())),
+        DataType::Decimal(_, scale) => {
+            let column = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+            Ok(Some(decimal::format_decimal(column.value(row_index), *scale)))
+        }
+        DataType::Binary => {
+            let column = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+            Ok(Some(String::from_utf8_lossy(column.value(row_index)).to_string()))
+        }
+        DataType::LargeBinary => {
+            let column = array.as_any().downcast_ref::<LargeBinaryArray>().unwrap();
+            Ok(Some(String::from_utf8_lossy(column.value(row_index)).to_string()))
+        }
+        DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => {
+            Ok(date_function::format_temporal_value(&ScalarValue::try_from_array(array, row_index)?))
+        }
+        DataType::Null => Ok(None),
+        data_type => Err(DataFusionError::Execution(format!("Unsupported data type of the value: {:?}", data_type))),
+    }
+}
+
+/// The value kept by the user variable of SET @var and SELECT ... INTO @var,
+/// the integers, the floats, the decimals and the bytes keep their type, the other values are kept as text
+pub fn user_variable_value(array: &ArrayRef, row_index: usize) -> Result<ScalarValue> {
+    if array.is_null(row_index) {
+        return Ok(ScalarValue::Utf8(None));
+    }
+
+    let value = match ScalarValue::try_from_array(array, row_index)? {
+        ScalarValue::Boolean(Some(value)) => ScalarValue::Int64(Some(value as i64)),
+        ScalarValue::Int8(Some(value)) => ScalarValue::Int64(Some(value as i64)),
+        ScalarValue::Int16(Some(value)) => ScalarValue::Int64(Some(value as i64)),
+        ScalarValue::Int32(Some(value)) => ScalarValue::Int64(Some(value as i64)),
+        ScalarValue::UInt8(Some(value)) => ScalarValue::Int64(Some(value as i64)),
+        ScalarValue::UInt16(Some(value)) => ScalarValue::Int64(Some(value as i64)),
+        ScalarValue::UInt32(Some(value)) => ScalarValue::Int64(Some(value as i64)),
+        ScalarValue::Float32(Some(value)) => ScalarValue::Float64(Some(value as f64)),
+        value @ ScalarValue::Int64(_)
+        | value @ ScalarValue::UInt64(_)
+        | value @ ScalarValue::Float64(_)
+        | value @ ScalarValue::Decimal128(_, _, _)
+        | value @ ScalarValue::Binary(_) => value,
+        ScalarValue::LargeBinary(value) => ScalarValue::Binary(value),
+        _ => ScalarValue::Utf8(array_value_to_text(array, row_index)?),
+    };
+    Ok(value)
+}
+
+/// The rows of the record batches as the literals of VALUES, the values are read as the ones of the user variables
+pub fn record_batches_to_values(record_batches: &[RecordBatch]) -> MysqlResult<Vec<Vec<SQLExpr>>> {
+    let mut rows = vec![];
+    for record_batch in record_batches {
+        for row_index in 0..record_batch.num_rows() {
+            let mut row = vec![];
+            for column in record_batch.columns() {
+                let value = match user_variable_value(column, row_index)? {
+                    value if value.is_null() => Value::Null,
+                    ScalarValue::Utf8(Some(value)) => Value::SingleQuotedString(value),
+                    ScalarValue::Decimal128(Some(value), _, scale) => Value::Number(decimal::format_decimal(value, scale), false),
+                    ScalarValue::Binary(Some(value)) => Value::HexStringLiteral(hex::encode_upper(value)),
+                    value => Value::Number(value.to_string(), false),
+                };
+                row.push(SQLExpr::Value(value));
+            }
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+/// The sql literal of the value of the user variable, the negative numbers are in parentheses
+/// so that `1-@var` does not become a comment
+pub fn user_variable_literal(value: &ScalarValue) -> String {
+    let literal = match value {
+        _ if value.is_null() => return "NULL".to_string(),
+        ScalarValue::Int64(Some(value)) => value.to_string(),
+        ScalarValue::UInt64(Some(value)) => value.to_string(),
+        ScalarValue::Float64(Some(value)) => value.to_string(),
+        ScalarValue::Decimal128(Some(value), _, scale) => decimal::format_decimal(*value, *scale),
+        ScalarValue::Utf8(Some(value)) => return format!("'{}'", value.replace("'", "''")),
+        ScalarValue::Binary(Some(value)) => return format!("X'{}'", hex::encode_upper(value)),
+        _ => return format!("'{}'", value.to_string().replace("'", "''")),
+    };
+    if literal.starts_with('-') {
+        return format!("({})", literal);
+    }
+    literal
+}
+
+/// The sql literal of the stored value of the column, the numbers are not quoted
+pub fn column_value_literal(sql_column: &SQLColumnDef, value: Option<String>) -> String {
+    let value = match value {
+        None => return "NULL".to_string(),
+        Some(value) => value,
+    };
+    match sql_column.data_type {
+        SQLDataType::TinyInt(_) | SQLDataType::Int(_) | SQLDataType::Float(_) | SQLDataType::Double | SQLDataType::Decimal(_, _)
+            if value.starts_with('-') =>
+        {
+            format!("({})", value)
+        }
+        SQLDataType::TinyInt(_) | SQLDataType::Int(_) | SQLDataType::Float(_) | SQLDataType::Double | SQLDataType::Decimal(_, _) => value,
+        // the bytes are stored as the hex text
+        _ if meta_util::is_binary_data_type(&sql_column.data_type) => format!("X'{}'", value),
+        _ => format!("'{}'", value.replace("'", "''")),
+    }
+}
+
+/// The user variables of the sql are replaced by the literals of their values before the sql is parsed,
+/// the variables which are not set are NULL
+pub fn rewrite_user_variables(sql: &str, session_context: &SessionContext) -> String {
+    let dialect = GenericDialect {};
+    let tokens = match Tokenizer::new(&dialect, sql).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return sql.to_string(),
+    };
+    if !tokens.iter().any(|token| user_variable::user_variable_name(token).is_some()) {
+        return sql.to_string();
+    }
+
+    let user_variables = session_context.user_variables.lock().unwrap();
+    tokens
+        .iter()
+        .map(|token| match user_variable::user_variable_name(token) {
+            Some(name) => match user_variables.get(name.as_str()) {
+                Some(value) => user_variable_literal(value),
+                None => "NULL".to_string(),
+            },
+            None => user_variable::token_sql(token),
+        })
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+/// The number literal of a DECIMAL column is kept as its text,
+/// it would be planned as a float literal and lose the exact digits
+pub fn quote_decimal_literal(value: &SQLExpr) -> SQLExpr {
+    match value {
+        SQLExpr::Value(Value::Number(number, _)) => SQLExpr::Value(Value::SingleQuotedString(number.clone())),
+        SQLExpr::UnaryOp { op: UnaryOperator::Minus, expr } => match expr.as_ref() {
+            SQLExpr::Value(Value::Number(number, _)) => SQLExpr::Value(Value::SingleQuotedString(format!("-{}", number))),
+            _ => value.clone(),
+        },
+        _ => value.clone(),
+    }
+}
+
+/// Cast the values of the DECIMAL columns to the unscaled value of the column scale,
+/// rounded half away from zero as in mysql
+pub fn cast_decimal_column_values(
+    table_def: &TableDef,
+    column_value_map_list: &mut Vec<HashMap<Ident, ScalarValue>>,
+) -> MysqlResult<()> {
+    for sparrow_column in table_def.get_columns() {
+        let (precision, scale) = match &sparrow_column.sql_column.data_type {
+            SQLDataType::Decimal(precision, scale) => decimal::precision_scale(precision, scale),
+            _ => continue,
+        };
+        let column_name = sparrow_column.sql_column.name.clone();
+
+        for (row_index, column_value_map) in column_value_map_list.iter_mut().enumerate() {
+            let text = match column_value_map.get(&column_name) {
+                Some(ScalarValue::Utf8(Some(value))) => value.clone(),
+                Some(ScalarValue::Int64(Some(value))) => value.to_string(),
+                Some(ScalarValue::Float64(Some(value))) => value.to_string(),
+                Some(ScalarValue::Decimal128(Some(value), _, value_scale)) => decimal::format_decimal(*value, *value_scale),
+                _ => continue,
+            };
+
+            let value = match decimal::parse_decimal(text.as_str(), precision, scale) {
+                Ok(value) => value,
+                Err(decimal::DecimalError::Invalid) => {
+                    return Err(MysqlError::new_server_error(
+                        1366,
+                        "HY000",
+                        format!("Incorrect decimal value: '{}' for column '{}' at row {}", text, column_name, row_index + 1).as_str(),
+                    ));
+                }
+                Err(decimal::DecimalError::OutOfRange) => {
+                    return Err(MysqlError::new_server_error(
+                        1264,
+                        "22003",
+                        format!("Out of range value for column '{}' at row {}", column_name, row_index + 1).as_str(),
+                    ));
+                }
+            };
+            column_value_map.insert(column_name.clone(), ScalarValue::Decimal128(Some(value), precision, scale));
+        }
+    }
+
+    Ok(())
+}
+
+/// The bytes of the hex literal `X'ABCD'`, DataFusion has no hex literal
+pub fn hex_literal_to_scalar_value(hex_literal: &str) -> MysqlResult<ScalarValue> {
+    match hex::decode(hex_literal) {
+        Ok(bytes) => Ok(ScalarValue::Binary(Some(bytes))),
+        Err(_) => {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use near 'X'{}''",
+                hex_literal
+            );
+            log::error!("{}", message);
+            Err(MysqlError::new_server_error(1064, "42000", message.as_str()))
+        }
+    }
+}
+
+/// The logical expression of the value given to a column, the hex literal is the literal of its bytes
+pub fn column_value_to_logical_expr<S: ContextProvider>(query_planner: &SqlToRel<S>, value: &SQLExpr, dfschema: &DFSchema) -> MysqlResult<Expr> {
+    if let SQLExpr::Value(Value::HexStringLiteral(hex_literal)) = value {
+        return hex_literal_to_scalar_value(hex_literal).map(Expr::Literal);
+    }
+    query_planner.sql_expr_to_logical_expr(value, dfschema).map_err(|e| {
+        let message = e.to_string();
+        log::error!("{}", message);
+        MysqlError::new_server_error(1305, "42000", message.as_str())
+    })
+}
+
+/// Cast the values of the BINARY, VARBINARY, BLOB and LONGBLOB columns to the bytes, the text is its utf8 bytes.
+/// The bytes given to the other columns are taken as the utf8 text
+pub fn cast_binary_column_values(
+    table_def: &TableDef,
+    column_value_map_list: &mut Vec<HashMap<Ident, ScalarValue>>,
+) -> MysqlResult<()> {
+    for sparrow_column in table_def.get_columns() {
+        let is_binary_column = meta_util::is_binary_data_type(&sparrow_column.sql_column.data_type);
+        let column_name = sparrow_column.sql_column.name.clone();
+
+        for column_value_map in column_value_map_list.iter_mut() {
+            let scalar_value = match column_value_map.get(&column_name) {
+                Some(scalar_value) => scalar_value.clone(),
+                None => continue,
+            };
+            let scalar_value = match scalar_value {
+                ScalarValue::Binary(value) | ScalarValue::LargeBinary(value) if is_binary_column => ScalarValue::Binary(value),
+                ScalarValue::Binary(value) | ScalarValue::LargeBinary(value) => {
+                    ScalarValue::Utf8(value.map(|value| String::from_utf8_lossy(value.as_slice()).to_string()))
+                }
+                _ if !is_binary_column => continue,
+                scalar_value if scalar_value.is_null() => ScalarValue::Binary(None),
+                scalar_value => ScalarValue::Binary(convert_scalar_value(scalar_value)?.map(String::into_bytes)),
+            };
+            column_value_map.insert(column_name.clone(), scalar_value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Cast the values of the TINYINT(1) columns, TRUE and FALSE are 1 and 0 as in mysql
+pub fn cast_tinyint_column_values(
+    table_def: &TableDef,
+    column_value_map_list: &mut Vec<HashMap<Ident, ScalarValue>>,
+) -> MysqlResult<()> {
+    for sparrow_column in table_def.get_columns() {
+        if !matches!(sparrow_column.sql_column.data_type, SQLDataType::TinyInt(_)) {
+            continue;
+        }
+        let column_name = sparrow_column.sql_column.name.clone();
+
+        for (row_index, column_value_map) in column_value_map_list.iter_mut().enumerate() {
+            let value = match column_value_map.get(&column_name) {
+                Some(ScalarValue::Boolean(Some(value))) => *value as i64,
+                Some(ScalarValue::Int64(Some(value))) => *value,
+                Some(ScalarValue::Utf8(Some(value))) => match value.trim().parse::<i64>() {
+                    Ok(value) => value,
+                    Err(_) => {
+                        return Err(MysqlError::new_server_error(
+                            1366,
+                            "HY000",
+                            format!("Incorrect integer value: '{}' for column '{}' at row {}", value, column_name, row_index + 1).as_str(),
+                        ));
+                    }
+                },
+                Some(ScalarValue::Boolean(None)) | Some(ScalarValue::Int64(None)) => {
+                    column_value_map.insert(column_name.clone(), ScalarValue::Int8(None));
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if value < i8::MIN as i64 || value > i8::MAX as i64 {
+                return Err(MysqlError::new_server_error(
+                    1264,
+                    "22003",
+                    format!("Out of range value for column '{}' at row {}", column_name, row_index + 1).as_str(),
+                ));
+            }
+            column_value_map.insert(column_name.clone(), ScalarValue::Int8(Some(value as i8)));
+        }
+    }
+
+    Ok(())
+}
+
+/// The time zone of the session, the time zone of the server if it is not set
+pub fn get_session_time_zone(session_context: &SessionContext) -> MysqlTimeZone {
+    session_context
+        .system_variables
+        .lock()
+        .unwrap()
+        .get(meta_const::SYSTEM_VARIABLE_TIME_ZONE)
+        .and_then(|value| time_zone::parse_time_zone(value.as_str()))
+        .unwrap_or(MysqlTimeZone::System)
+}
+
+/// The text stored for the value of the DATETIME or the TIMESTAMP column, the TIMESTAMP value is
+/// in the time zone of the session and stored in UTC, the DATETIME value is stored as given.
+/// The DATE column keeps the day of the value and the TIME column its time
+pub fn convert_datetime_text(
+    sql_column: &SQLColumnDef,
+    text: &str,
+    session_time_zone: &MysqlTimeZone,
+    row_number: usize,
+) -> MysqlResult<String> {
+    let incorrect_value_error = |data_type: &str| {
+        MysqlError::new_server_error(
+            1292,
+            "22007",
+            format!("Incorrect {} value: '{}' for column '{}' at row {}", data_type, text, sql_column.name, row_number).as_str(),
+        )
+    };
+    match sql_column.data_type {
+        SQLDataType::Date => {
+            return date_function::parse_datetime(text)
+                .map(|(datetime, _)| date_function::format_date(datetime.date()))
+                .ok_or_else(|| incorrect_value_error(meta_const::MYSQL_DATA_TYPE_DATE));
+        }
+        SQLDataType::Time => {
+            return date_function::parse_time(text)
+                .map(date_function::format_time)
+                .ok_or_else(|| incorrect_value_error(meta_const::MYSQL_DATA_TYPE_TIME));
+        }
+        _ => {}
+    }
+
+    // the range of the TIMESTAMP of mysql in UTC
+    let timestamp_min = NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 1);
+    let timestamp_max = NaiveDate::from_ymd(2038, 1, 19).and_hms(3, 14, 7);
+
+    let mut datetime = date_function::parse_datetime(text).map(|(datetime, _)| datetime);
+    if sql_column.data_type == SQLDataType::Timestamp {
+        datetime = datetime
+            .and_then(|datetime| session_time_zone.to_utc(datetime))
+            .filter(|datetime| *datetime >= timestamp_min && *datetime <= timestamp_max);
+    }
+    match datetime {
+        Some(datetime) => Ok(date_function::format_datetime(datetime)),
+        None => Err(incorrect_value_error(meta_const::MYSQL_DATA_TYPE_DATETIME)),
+    }
+}
+
+/// The DATE, TIME, DATETIME and TIMESTAMP columns, their values are checked and stored as text
+pub fn is_datetime_column(sql_column: &SQLColumnDef) -> bool {
+    meta_util::is_temporal_data_type(&sql_column.data_type)
+}
+
+/// Convert the values of the DATE, TIME, DATETIME and TIMESTAMP columns to the text stored, see `convert_datetime_text`
+pub fn cast_datetime_column_values(
+    table_def: &TableDef,
+    column_value_map_list: &mut Vec<HashMap<Ident, ScalarValue>>,
+    session_time_zone: &MysqlTimeZone,
+) -> MysqlResult<()> {
+    for sparrow_column in table_def.get_columns() {
+        if !is_datetime_column(&sparrow_column.sql_column) {
+            continue;
+        }
+        let column_name = sparrow_column.sql_column.name.clone();
+
+        for (row_index, column_value_map) in column_value_map_list.iter_mut().enumerate() {
+            let text = match column_value_map.get(&column_name) {
+                Some(ScalarValue::Utf8(Some(text))) => text.clone(),
+                Some(scalar_value) if scalar_value.is_null() => {
+                    column_value_map.insert(column_name.clone(), ScalarValue::Utf8(None));
+                    continue;
+                }
+                Some(scalar_value) => date_function::format_temporal_value(scalar_value).unwrap_or_else(|| scalar_value.to_string()),
+                None => continue,
+            };
+
+            let text = convert_datetime_text(&sparrow_column.sql_column, text.as_str(), session_time_zone, row_index + 1)?;
+            column_value_map.insert(column_name.clone(), ScalarValue::Utf8(Some(text)));
+        }
+    }
+
+    Ok(())
+}
+
+/// CONVERT_TZ(dt, from_tz, to_tz), NULL if the datetime or a time zone is not valid
+pub fn create_convert_tz_udf() -> ScalarUDF {
+    let convert_tz_function = |args: &[ArrayRef]| {
+        let mut res = vec![];
+        for row_index in 0..args[0].len() {
+            let text = array_value_to_text(&args[0], row_index)?;
+            let from_time_zone = array_value_to_text(&args[1], row_index)?;
+            let to_time_zone = array_value_to_text(&args[2], row_index)?;
+            match (text, from_time_zone, to_time_zone) {
+                (Some(text), Some(from_time_zone), Some(to_time_zone)) => {
+                    res.push(time_zone::convert_tz(text.as_str(), from_time_zone.as_str(), to_time_zone.as_str()))
+                }
+                _ => res.push(None),
+            }
+        }
+        Ok(Arc::new(StringArray::from(res)) as ArrayRef)
+    };
+
+    let convert_tz_signature = Signature::any(3, Volatility::Immutable);
+    let convert_tz_return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Utf8)));
+    ScalarUDF::new(
+        meta_const::FUNCTION_NAME_CONVERT_TZ,
+        &convert_tz_signature,
+        &convert_tz_return_type,
+        &make_scalar_function(convert_tz_function),
+    )
+}
+
 pub fn projection_has_rowid(projection: Vec<SelectItem>) -> bool {
     let has_rowid = projection.iter().any(|x| match x {
         SelectItem::UnnamedExpr(expr) => match expr {
@@ -499,9 +1521,10 @@ pub fn build_table_dual() -> TableWithJoins {
     build_table_with_joins(object_name)
 }
 
+/// The rowid and the columns of the rows, the assignments of UPDATE and ORDER BY of DELETE are evaluated against the columns
 pub fn build_update_sqlselect(
     table_name: ObjectName,
-    assignments: Vec<Assignment>,
+    column_names: Vec<Ident>,
     selection: Option<SQLExpr>,
 ) -> Select {
     let table_with_joins = build_table_with_joins(table_name);
@@ -511,9 +1534,8 @@ pub fn build_update_sqlselect(
         quote_style: None,
     });
     let mut projection = vec![SelectItem::UnnamedExpr(sql_expr)];
-    for assignment in assignments.clone() {
-        let select_item = SelectItem::UnnamedExpr(assignment.value.to_owned());
-        projection.push(select_item);
+    for column_name in column_names {
+        projection.push(SelectItem::UnnamedExpr(SQLExpr::Identifier(column_name)));
     }
 
     let select = Select {
@@ -597,34 +1619,11 @@ pub fn build_table_with_joins(table_name: ObjectName) -> TableWithJoins {
 pub fn get_real_value(expr: Expr) -> Result<Option<String>> {
     match expr {
         Expr::Literal(scalar_value) => match scalar_value {
-            ScalarValue::Int32(limit) => {
-                if let Some(value) = limit {
-                    Ok(Some(value.to_string()))
-                } else {
-                    Ok(None)
-                }
-            }
-            ScalarValue::Int64(limit) => {
-                if let Some(value) = limit {
-                    Ok(Some(value.to_string()))
-                } else {
-                    Ok(None)
-                }
-            }
-            ScalarValue::UInt64(limit) => {
-                if let Some(value) = limit {
-                    Ok(Some(value.to_string()))
-                } else {
-                    Ok(None)
-                }
-            }
-            ScalarValue::Utf8(limit) => {
-                if let Some(value) = limit {
-                    Ok(Some(value.to_string()))
-                } else {
-                    Ok(None)
-                }
-            }
+            ScalarValue::Int32(Some(value)) if value >= 0 => Ok(Some(value.to_string())),
+            ScalarValue::Int64(Some(value)) if value >= 0 => Ok(Some(value.to_string())),
+            ScalarValue::UInt64(Some(value)) => Ok(Some(value.to_string())),
+            ScalarValue::Utf8(Some(value)) if value.parse::<u64>().is_ok() => Ok(Some(value)),
+            ScalarValue::Int32(None) | ScalarValue::Int64(None) | ScalarValue::UInt64(None) | ScalarValue::Utf8(None) => Ok(None),
             _ => {
                 let message = format!(
                     "Limit only supports non-negative integer literals, scalar_value: {:?}",
@@ -647,6 +1646,13 @@ pub fn get_real_value(expr: Expr) -> Result<Option<String>> {
 
 pub fn convert_scalar_value(scalar_value: ScalarValue) -> MysqlResult<Option<String>> {
     match scalar_value {
+        ScalarValue::Int8(limit) => {
+            if let Some(value) = limit {
+                Ok(Some(value.to_string()))
+            } else {
+                Ok(None)
+            }
+        }
         ScalarValue::Int64(limit) => {
             if let Some(value) = limit {
                 Ok(Some(value.to_string()))
@@ -668,6 +1674,22 @@ pub fn convert_scalar_value(scalar_value: ScalarValue) -> MysqlResult<Option<Str
                 Ok(None)
             }
         }
+        ScalarValue::Decimal128(limit, _, scale) => {
+            if let Some(value) = limit {
+                Ok(Some(decimal::format_decimal(value, scale)))
+            } else {
+                Ok(None)
+            }
+        }
+        // the bytes are stored as the hex text, see `cast_binary_column_values`
+        ScalarValue::Binary(limit) | ScalarValue::LargeBinary(limit) => Ok(limit.map(hex::encode_upper)),
+        // the dates and the timestamps are stored as the text, see `convert_datetime_text`
+        ScalarValue::Date32(_)
+        | ScalarValue::Date64(_)
+        | ScalarValue::TimestampSecond(..)
+        | ScalarValue::TimestampMillisecond(..)
+        | ScalarValue::TimestampMicrosecond(..)
+        | ScalarValue::TimestampNanosecond(..) => Ok(date_function::format_temporal_value(&scalar_value)),
         _ => {
             let message = format!(
                 "Limit only supports non-negative integer literals, scalar_value: {:?}",
@@ -784,6 +1806,35 @@ pub fn build_find_table_sqlwhere(
     selection
 }
 
+/// The rows whose two columns have the values, as the rows of the metadata tables without table_catalog
+pub fn build_find_pair_sqlwhere(
+    first_column_name: &str,
+    first_value: &str,
+    second_column_name: &str,
+    second_value: &str,
+) -> SQLExpr {
+    let selection_first = SQLExpr::BinaryOp {
+        left: Box::new(SQLExpr::Identifier(Ident::new(first_column_name))),
+        op: BinaryOperator::Eq,
+        right: Box::new(SQLExpr::Value(Value::SingleQuotedString(
+            first_value.to_string(),
+        ))),
+    };
+    let selection_second = SQLExpr::BinaryOp {
+        left: Box::new(SQLExpr::Identifier(Ident::new(second_column_name))),
+        op: BinaryOperator::Eq,
+        right: Box::new(SQLExpr::Value(Value::SingleQuotedString(
+            second_value.to_string(),
+        ))),
+    };
+    let selection = SQLExpr::BinaryOp {
+        left: Box::new(selection_first),
+        op: BinaryOperator::And,
+        right: Box::new(selection_second),
+    };
+    selection
+}
+
 pub fn selection_information_schema_schemata(catalog_name: &str, schema_name: &str) -> SQLExpr {
     let selection_catalog_name = SQLExpr::BinaryOp {
         left: Box::new(SQLExpr::Identifier(Ident::new(
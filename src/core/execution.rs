@@ -2,74 +2,150 @@
 
 use bstr::ByteSlice;
 use byteorder::{ByteOrder, LittleEndian};
+use chrono::{NaiveDateTime, Utc};
 use std::collections::HashMap;
+use std::io;
 use std::string::String;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use arrow::array::{ArrayRef, StringArray};
+use arrow::array::{Array, ArrayRef, BooleanArray, DecimalBuilder, Int64Array, StringArray};
 use arrow::datatypes::DataType;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
 use datafusion::execution::context::{ExecutionConfig, ExecutionContext};
 use datafusion::logical_plan::create_udf;
 use datafusion::logical_plan::LogicalPlan;
-use datafusion::physical_plan::functions::{make_scalar_function, Volatility};
+use datafusion::physical_plan::functions::{make_scalar_function, ReturnTypeFunction, Signature, TypeSignature, Volatility};
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::scalar::ScalarValue;
 use datafusion::sql::parser::{DFParser, Statement};
 use datafusion::sql::planner::{ContextProvider, SqlToRel};
 use datafusion::variable::VarType;
 use sqlparser::ast::{
-    AlterTableOperation, Assignment, BinaryOperator, ColumnDef, Expr as SQLExpr, JoinConstraint,
+    AlterTableOperation, Assignment, BinaryOperator, ColumnDef, ColumnOption, DataType as SQLDataType, Expr as SQLExpr, JoinConstraint,
     JoinOperator, ObjectName, ObjectType, Query, Select, SelectItem, SetExpr,
-    Statement as SQLStatement, TableFactor, Value,
+    Statement as SQLStatement, TableConstraint, TableFactor, Value, Values, With,
 };
-use sqlparser::ast::{FunctionArg, Ident, OrderByExpr, ShowCreateObject, ShowStatementFilter};
+use sqlparser::ast::{Function, FunctionArg, Ident, OrderByExpr, ShowCreateObject, ShowStatementFilter, SqlOption, UnaryOperator};
 use sqlparser::dialect::GenericDialect;
+use tokio::sync::mpsc::Receiver;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-use crate::core::core_def::StmtCacheDef;
+use crate::core::core_def::{StmtCacheDef, StmtCursor};
 use crate::core::core_util;
 use crate::core::core_util as CoreUtil;
 use crate::core::core_util::register_all_table;
-use crate::core::core_util::stmt_value;
 use crate::core::global_context::GlobalContext;
 use crate::core::logical_plan::{CoreLogicalPlan, CoreSelectFrom, CoreSelectFromWithAssignment};
-use crate::core::output::{CoreOutput, FinalCount, ResultSet, StmtPrepare};
+use crate::core::output::{CoreOutput, FinalCount, ResultSet, SessionStateChange, StmtFetch, StmtPrepare};
+use crate::core::privilege;
+use crate::core::privilege::{Account, Privilege};
 use crate::core::session_context::SessionContext;
 use crate::core::stmt_context::StmtContext;
 use crate::execute_impl::add_column::AddColumn;
+use crate::execute_impl::analyze_table::AnalyzeTable;
 use crate::execute_impl::com_field_list::ComFieldList;
 use crate::execute_impl::com_stmt_prepare::ComStmtPrepare;
 use crate::execute_impl::create_db::CreateDb;
+use crate::execute_impl::create_index::CreateIndex;
 use crate::execute_impl::create_table::CreateTable;
+use crate::execute_impl::create_temporary_table::CreateTemporaryTable;
+use crate::execute_impl::create_user::CreateUser;
+use crate::execute_impl::create_view::CreateView;
 use crate::execute_impl::delete::DeleteFrom;
 use crate::execute_impl::drop_column::DropColumn;
+use crate::execute_impl::drop_index::DropIndex;
 use crate::execute_impl::drop_schema::DropSchema;
 use crate::execute_impl::drop_table::DropTable;
+use crate::execute_impl::drop_temporary_table::DropTemporaryTable;
+use crate::execute_impl::drop_user::DropUser;
+use crate::execute_impl::drop_view::DropView;
 use crate::execute_impl::explain::Explain;
+use crate::execute_impl::grant::Grant;
 use crate::execute_impl::insert::Insert;
+use crate::execute_impl::load_data::{LoadData, LoadDataCount, LocalInfileReader};
+use crate::execute_impl::modify_column::ModifyColumn;
+use crate::execute_impl::rename_table::RenameTable;
 use crate::execute_impl::select::SelectFrom;
 use crate::execute_impl::set_default_schema::SetDefaultSchema;
+use crate::execute_impl::set_password::SetPassword;
 use crate::execute_impl::set_variable::SetVariable;
 use crate::execute_impl::show_charset::ShowCharset;
 use crate::execute_impl::show_collation::ShowCollation;
 use crate::execute_impl::show_columns_from_table::ShowColumns;
+use crate::execute_impl::show_config::ShowConfig;
+use crate::execute_impl::show_create_db::ShowCreateDb;
 use crate::execute_impl::show_create_table::ShowCreateTable;
 use crate::execute_impl::show_databases::ShowDatabases;
 use crate::execute_impl::show_engines::ShowEngines;
 use crate::execute_impl::show_grants::ShowGrants;
+use crate::execute_impl::show_prepared_statements::ShowPreparedStatements;
+use crate::execute_impl::show_profile::ShowProfile;
 use crate::execute_impl::show_privileges::ShowPrivileges;
 use crate::execute_impl::show_tables::ShowTables;
 use crate::execute_impl::show_variables::ShowVariables;
+use crate::execute_impl::show_warnings::ShowWarnings;
+use crate::execute_impl::truncate_table::TruncateTable;
 use crate::execute_impl::update::Update;
 use crate::meta::meta_util::load_all_table;
 use crate::meta::{meta_const, meta_util};
+use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::store::engine::transaction;
+use crate::store::engine::transaction::Transaction;
+use crate::store::lock::LockMode;
+use crate::mysql::auth;
 use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::metadata;
 use crate::mysql::mysql_error_code;
 use crate::mysql::mysql_type_code;
 use crate::mysql::mysql_util::parse_length_encoded_bytes;
 use crate::mysql::mysql_util::parse_stmt_execute_args;
+use crate::mysql::packet;
+use crate::mysql::request::malformed_packet_error;
+use crate::util::alter_column_statement;
+use crate::util::alter_column_statement::ModifyColumnStatement;
+use crate::util::collation;
+use crate::util::collation::Collation;
+use crate::util::common_table_expression;
 use crate::util::convert::{convert_ident_to_lowercase, ToIdent, ToLowercase, ToObjectName};
+use crate::util::create_table_statement::{self, ForeignKeyClause};
+use crate::util::date_function;
+use crate::util::decimal;
+use crate::util::delete_statement;
+use crate::util::delete_statement::DeleteLimitStatement;
+use crate::util::explain_statement;
+use crate::util::explain_statement::ExplainFormatStatement;
+use crate::util::index_statement;
+use crate::util::kill_statement;
+use crate::util::kill_statement::KillStatement;
+use crate::util::like;
+use crate::util::load_data_statement;
+use crate::util::load_data_statement::LoadDataStatement;
+use crate::util::multi_statement;
+use crate::util::placeholder;
+use crate::util::rename_table_statement;
+use crate::util::rowid_alias;
+use crate::util::schema_statement;
+use crate::util::set_statement;
+use crate::util::set_statement::{SetVariableStatement, SystemVariableAssignment};
+use crate::util::show_statement;
+use crate::util::show_statement::{ShowLimitStatement, ShowVariablesFilter, ShowVariablesStatement};
+use crate::util::string_function;
+use crate::util::temporary_table_statement;
+use crate::util::transaction_statement;
+use crate::util::transaction_statement::TransactionStatement;
+use crate::util::upsert_statement;
+use crate::util::upsert_statement::UpsertStatement;
+use crate::util::user_statement;
+use crate::util::user_statement::ShowGrantsStatement;
+use crate::util::user_variable;
+use crate::util::user_variable::{SelectIntoStatement, SetUserVariableStatement};
 use crate::variable::system::SystemVar;
+use crate::variable::system_variable;
 use crate::variable::user_defined::UserDefinedVar;
 
 /// Execution context for registering data sources and executing queries
@@ -79,6 +155,12 @@ pub struct Execution {
     stmt_context: StmtContext,
     datafusion_context: ExecutionContext,
     client_id: String,
+    /// The random scramble of the connection, sent in the handshake and the auth switch request
+    scramble: [u8; auth::SCRAMBLE_LENGTH],
+    /// The id of the connection in the process list, sent in the handshake
+    connection_id: u32,
+    /// Cancelled by KILL CONNECTION
+    connection_token: CancellationToken,
 }
 
 impl Execution {
@@ -102,34 +184,120 @@ impl Execution {
 
         let stmt_context = StmtContext::new();
 
+        let (connection_id, connection_token) = global_context.lock().unwrap().process_list.register();
+
         Self {
             global_context,
             session_context,
             datafusion_context,
             client_id,
+            scramble: auth::create_scramble(),
             stmt_context,
+            connection_id,
+            connection_token,
+        }
+    }
+}
+
+impl Drop for Execution {
+    fn drop(&mut self) {
+        if let Ok(global_context) = self.global_context.lock() {
+            global_context.process_list.unregister(self.connection_id);
         }
     }
 }
 
 impl Execution {
+    pub fn global_context(&self) -> Arc<Mutex<GlobalContext>> {
+        self.global_context.clone()
+    }
+
+    pub fn scramble(&self) -> &[u8] {
+        &self.scramble
+    }
+
     /// Create a new execution context for in-memory queries
     pub fn try_init(&mut self) -> MysqlResult<()> {
-        let variable = UserDefinedVar::new(self.global_context.clone());
+        let variable = UserDefinedVar::new(self.session_context.clone());
         self.datafusion_context
             .register_variable(VarType::UserDefined, Arc::new(variable));
-        let variable = SystemVar::new(self.global_context.clone());
+        let variable = SystemVar::new(self.global_context.clone(), self.session_context.clone());
         self.datafusion_context
             .register_variable(VarType::System, Arc::new(variable));
 
-        core_util::register_all_table(self.global_context.clone(), &mut self.datafusion_context)
+        // the definitions of the views are planned when they are registered, they may call the functions
+        self.init_udf();
+        core_util::register_all_table(self.global_context.clone(), &self.session_context, &mut self.datafusion_context)
             .unwrap();
 
-        self.init_udf();
+        self.init_session();
 
         Ok(())
     }
 
+    /// The session variables and the default schema of the config
+    fn init_session(&mut self) {
+        // the sql mode of the session starts with the global one, SET GLOBAL changes it for the new sessions
+        let sql_mode = self.global_context.lock().unwrap().variable.get_variable_value(meta_const::SYSTEM_VARIABLE_SQL_MODE);
+        if let Some(sql_mode) = sql_mode {
+            self.set_sql_mode(sql_mode.as_str());
+        }
+
+        // the interactive clients wait for the next command as long as the global interactive_timeout
+        if *self.session_context.interactive.lock().unwrap() {
+            let interactive_timeout = self
+                .global_context
+                .lock()
+                .unwrap()
+                .variable
+                .get_variable_value(meta_const::SYSTEM_VARIABLE_INTERACTIVE_TIMEOUT);
+            if let Some(interactive_timeout) = interactive_timeout {
+                if let Err(mysql_error) = self.set_session_variable(meta_const::SYSTEM_VARIABLE_WAIT_TIMEOUT, interactive_timeout.as_str()) {
+                    log::warn!("Can't set the session wait_timeout: {}", mysql_error.message());
+                }
+            }
+        }
+
+        let session_variables = self.global_context.lock().unwrap().my_config.server.session_variables.clone();
+        for (variable_name, value) in session_variables {
+            // the session keeps the server value if the configured one is wrong
+            if let Err(mysql_error) = self.set_session_variable(variable_name.as_str(), value.as_str()) {
+                log::warn!("Can't set the session variable '{}': {}", variable_name, mysql_error.message());
+            }
+        }
+
+        let default_schema = self.global_context.lock().unwrap().my_config.server.default_schema.clone();
+        if let Some(default_schema) = default_schema {
+            let mut set_default_schema = SetDefaultSchema::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            // the session works without a database if the configured one is dropped
+            if let Err(mysql_error) = set_default_schema.execute(meta_util::convert_to_object_name(default_schema.as_str())) {
+                log::warn!("Can't use the default schema '{}': {}", default_schema, mysql_error.message());
+            }
+        }
+    }
+
+    /// COM_RESET_CONNECTION, and COM_CHANGE_USER after the auth of the new user. The session state and the
+    /// prepared statements are cleared without closing the connection, the session starts as a new one
+    pub fn reset_connection(&mut self) -> MysqlResult<CoreOutput> {
+        // the temporary tables are dropped, the tables they hid are seen again
+        let temporary_table_list = self.session_context.get_temporary_table_list();
+        self.session_context.reset();
+        if !temporary_table_list.is_empty() {
+            for temporary_table in temporary_table_list {
+                self.datafusion_context.deregister_table(temporary_table.full_table_name().to_string().as_str())?;
+            }
+            core_util::register_all_table(self.global_context.clone(), &self.session_context, &mut self.datafusion_context)?;
+        }
+        self.stmt_context = StmtContext::new();
+        self.init_session();
+
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
     pub fn init_udf(&mut self) {
         let captured_name = self.session_context.current_schema.clone();
         let database_function = move |_args: &[ArrayRef]| {
@@ -152,184 +320,575 @@ impl Execution {
                 make_scalar_function(database_function),
             ), // function implementation
         );
+
+        let captured_last_insert_id = self.session_context.last_insert_id.clone();
+        let last_insert_id_function = move |_args: &[ArrayRef]| {
+            let last_insert_id = *captured_last_insert_id.lock().expect("mutex poisoned");
+            let res = Int64Array::from(vec![last_insert_id as i64]);
+            Ok(Arc::new(res) as ArrayRef)
+        };
+
+        self.datafusion_context.register_udf(
+            create_udf(
+                meta_const::FUNCTION_NAME_LAST_INSERT_ID,
+                vec![],
+                Arc::new(DataType::Int64),
+                Volatility::Volatile,
+                make_scalar_function(last_insert_id_function),
+            ),
+        );
+
+        let like_escape_function = |args: &[ArrayRef]| {
+            let values = args[0].as_any().downcast_ref::<StringArray>().unwrap();
+            let patterns = args[1].as_any().downcast_ref::<StringArray>().unwrap();
+            let res = (0..values.len())
+                .map(|i| {
+                    if values.is_null(i) || patterns.is_null(i) {
+                        None
+                    } else {
+                        Some(like::like_match(values.value(i), patterns.value(i), like::DEFAULT_ESCAPE_CHAR))
+                    }
+                })
+                .collect::<BooleanArray>();
+            Ok(Arc::new(res) as ArrayRef)
+        };
+
+        self.datafusion_context.register_udf(
+            create_udf(
+                meta_const::FUNCTION_NAME_LIKE_ESCAPE,
+                vec![DataType::Utf8, DataType::Utf8],
+                Arc::new(DataType::Boolean),
+                Volatility::Immutable,
+                make_scalar_function(like_escape_function),
+            ),
+        );
+
+        let collation_key_function = |args: &[ArrayRef]| {
+            let values = args[0].as_any().downcast_ref::<StringArray>().unwrap();
+            let collation_names = args[1].as_any().downcast_ref::<StringArray>().unwrap();
+            let res = (0..values.len())
+                .map(|i| {
+                    if values.is_null(i) || collation_names.is_null(i) {
+                        None
+                    } else {
+                        let value = values.value(i);
+                        match collation::find_collation(collation_names.value(i)) {
+                            Some(collation) => Some(collation::sort_key(collation, value)),
+                            None => Some(value.to_string()),
+                        }
+                    }
+                })
+                .collect::<StringArray>();
+            Ok(Arc::new(res) as ArrayRef)
+        };
+
+        self.datafusion_context.register_udf(
+            create_udf(
+                meta_const::FUNCTION_NAME_COLLATION_KEY,
+                vec![DataType::Utf8, DataType::Utf8],
+                Arc::new(DataType::Utf8),
+                Volatility::Immutable,
+                make_scalar_function(collation_key_function),
+            ),
+        );
+
+        let replace_function = |args: &[ArrayRef]| {
+            let values = args[0].as_any().downcast_ref::<StringArray>().unwrap();
+            let froms = args[1].as_any().downcast_ref::<StringArray>().unwrap();
+            let tos = args[2].as_any().downcast_ref::<StringArray>().unwrap();
+            let res = (0..values.len())
+                .map(|i| {
+                    if values.is_null(i) || froms.is_null(i) || tos.is_null(i) {
+                        None
+                    } else {
+                        Some(string_function::replace(values.value(i), froms.value(i), tos.value(i)))
+                    }
+                })
+                .collect::<StringArray>();
+            Ok(Arc::new(res) as ArrayRef)
+        };
+
+        self.datafusion_context.register_udf(
+            create_udf(
+                meta_const::FUNCTION_NAME_MYSQL_REPLACE,
+                vec![DataType::Utf8, DataType::Utf8, DataType::Utf8],
+                Arc::new(DataType::Utf8),
+                Volatility::Immutable,
+                make_scalar_function(replace_function),
+            ),
+        );
+
+        // LOCATE(substr, str) and LOCATE(substr, str, pos)
+        let locate_function = |args: &[ArrayRef]| {
+            let subs = args[0].as_any().downcast_ref::<StringArray>().unwrap();
+            let values = args[1].as_any().downcast_ref::<StringArray>().unwrap();
+            let positions = args.get(2).map(|arg| arg.as_any().downcast_ref::<Int64Array>().unwrap());
+            let res = (0..values.len())
+                .map(|i| {
+                    if subs.is_null(i) || values.is_null(i) {
+                        return None;
+                    }
+                    let position = match positions {
+                        None => 1,
+                        Some(positions) if positions.is_null(i) => return None,
+                        Some(positions) => positions.value(i),
+                    };
+                    Some(string_function::locate(subs.value(i), values.value(i), position))
+                })
+                .collect::<Int64Array>();
+            Ok(Arc::new(res) as ArrayRef)
+        };
+
+        let locate_signature = Signature::one_of(
+            vec![
+                TypeSignature::Exact(vec![DataType::Utf8, DataType::Utf8]),
+                TypeSignature::Exact(vec![DataType::Utf8, DataType::Utf8, DataType::Int64]),
+            ],
+            Volatility::Immutable,
+        );
+        let locate_return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+        self.datafusion_context.register_udf(ScalarUDF::new(
+            meta_const::FUNCTION_NAME_LOCATE,
+            &locate_signature,
+            &locate_return_type,
+            &make_scalar_function(locate_function),
+        ));
+
+        // INSTR(str, substr) is LOCATE(substr, str) with the arguments swapped
+        let instr_function = |args: &[ArrayRef]| {
+            let values = args[0].as_any().downcast_ref::<StringArray>().unwrap();
+            let subs = args[1].as_any().downcast_ref::<StringArray>().unwrap();
+            let res = (0..values.len())
+                .map(|i| {
+                    if values.is_null(i) || subs.is_null(i) {
+                        None
+                    } else {
+                        Some(string_function::locate(subs.value(i), values.value(i), 1))
+                    }
+                })
+                .collect::<Int64Array>();
+            Ok(Arc::new(res) as ArrayRef)
+        };
+
+        self.datafusion_context.register_udf(
+            create_udf(
+                meta_const::FUNCTION_NAME_INSTR,
+                vec![DataType::Utf8, DataType::Utf8],
+                Arc::new(DataType::Int64),
+                Volatility::Immutable,
+                make_scalar_function(instr_function),
+            ),
+        );
+
+        // DATE_ADD(date, INTERVAL value unit) and DATE_SUB, the interval is split into the value and the unit before parsing
+        for (function_name, sign) in vec![
+            (meta_const::FUNCTION_NAME_MYSQL_DATE_ADD, 1),
+            (meta_const::FUNCTION_NAME_MYSQL_DATE_SUB, -1),
+        ] {
+            let date_add_function = move |args: &[ArrayRef]| {
+                let mut res = vec![];
+                for row_index in 0..args[0].len() {
+                    let date = core_util::array_value_to_text(&args[0], row_index)?;
+                    let value = core_util::array_value_to_text(&args[1], row_index)?;
+                    let unit = core_util::array_value_to_text(&args[2], row_index)?;
+                    let (date, value, unit) = match (date, value, unit) {
+                        (Some(date), Some(value), Some(unit)) => (date, value, unit),
+                        _ => {
+                            res.push(None);
+                            continue;
+                        }
+                    };
+                    let unit = parse_interval_unit(unit.as_str())?;
+                    let value = value.trim().parse::<f64>().map(|value| value.round() as i64).ok();
+                    res.push(value.and_then(|value| date_function::date_add(date.as_str(), value * sign, unit)));
+                }
+                Ok(Arc::new(StringArray::from(res)) as ArrayRef)
+            };
+
+            let date_add_signature = Signature::any(3, Volatility::Immutable);
+            let date_add_return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Utf8)));
+            self.datafusion_context.register_udf(ScalarUDF::new(
+                function_name,
+                &date_add_signature,
+                &date_add_return_type,
+                &make_scalar_function(date_add_function),
+            ));
+        }
+
+        self.datafusion_context.register_udf(core_util::create_convert_tz_udf());
+
+        // DATEDIFF(a, b), the days from b to a
+        let datediff_function = |args: &[ArrayRef]| {
+            let mut res = vec![];
+            for row_index in 0..args[0].len() {
+                let a = core_util::array_value_to_text(&args[0], row_index)?;
+                let b = core_util::array_value_to_text(&args[1], row_index)?;
+                match (a, b) {
+                    (Some(a), Some(b)) => res.push(date_function::datediff(a.as_str(), b.as_str())),
+                    _ => res.push(None),
+                }
+            }
+            Ok(Arc::new(Int64Array::from(res)) as ArrayRef)
+        };
+
+        let datediff_signature = Signature::any(2, Volatility::Immutable);
+        let datediff_return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+        self.datafusion_context.register_udf(ScalarUDF::new(
+            meta_const::FUNCTION_NAME_DATEDIFF,
+            &datediff_signature,
+            &datediff_return_type,
+            &make_scalar_function(datediff_function),
+        ));
+
+        // TIMESTAMPDIFF(unit, a, b), the unit is quoted before parsing
+        let timestampdiff_function = |args: &[ArrayRef]| {
+            let mut res = vec![];
+            for row_index in 0..args[0].len() {
+                let unit = core_util::array_value_to_text(&args[0], row_index)?;
+                let a = core_util::array_value_to_text(&args[1], row_index)?;
+                let b = core_util::array_value_to_text(&args[2], row_index)?;
+                match (unit, a, b) {
+                    (Some(unit), Some(a), Some(b)) => {
+                        let unit = parse_interval_unit(unit.as_str())?;
+                        res.push(date_function::timestampdiff(unit, a.as_str(), b.as_str()))
+                    }
+                    _ => res.push(None),
+                }
+            }
+            Ok(Arc::new(Int64Array::from(res)) as ArrayRef)
+        };
+
+        let timestampdiff_signature = Signature::any(3, Volatility::Immutable);
+        let timestampdiff_return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+        self.datafusion_context.register_udf(ScalarUDF::new(
+            meta_const::FUNCTION_NAME_MYSQL_TIMESTAMPDIFF,
+            &timestampdiff_signature,
+            &timestampdiff_return_type,
+            &make_scalar_function(timestampdiff_function),
+        ));
+
+        // NOW(), CURDATE() and CURTIME(), the keywords CURRENT_TIMESTAMP, CURRENT_DATE and CURRENT_TIME are rewritten to them
+        let current_time_functions: Vec<(&str, fn(NaiveDateTime) -> String)> = vec![
+            (meta_const::FUNCTION_NAME_MYSQL_NOW, date_function::current_timestamp),
+            (meta_const::FUNCTION_NAME_MYSQL_CURRENT_DATE, date_function::current_date),
+            (meta_const::FUNCTION_NAME_MYSQL_CURRENT_TIME, date_function::current_time),
+        ];
+        for (function_name, format_function) in current_time_functions {
+            // the current time in the time zone of the session
+            let captured_session_context = self.session_context.clone();
+            let current_time_function = move |_args: &[ArrayRef]| {
+                let session_time_zone = core_util::get_session_time_zone(&captured_session_context);
+                let now = session_time_zone.from_utc(Utc::now().naive_utc());
+                let res = StringArray::from(vec![format_function(now)]);
+                Ok(Arc::new(res) as ArrayRef)
+            };
+            self.datafusion_context.register_udf(create_udf(
+                function_name,
+                vec![],
+                Arc::new(DataType::Utf8),
+                Volatility::Volatile,
+                make_scalar_function(current_time_function),
+            ));
+        }
+    }
+
+    /// DataFusion does not cast to DECIMAL, CAST(... AS DECIMAL(p,s)) is the udf of the precision and scale,
+    /// registered the first time it is used. The strict sql mode of the session is read when the udf runs.
+    pub fn register_cast_decimal_udf(&mut self, precision: &Option<u64>, scale: &Option<u64>) -> String {
+        let (precision, scale) = decimal::precision_scale(precision, scale);
+        let function_name = format!("{}_{}_{}", meta_const::FUNCTION_NAME_CAST_DECIMAL, precision, scale);
+
+        let sql_mode = self.session_context.sql_mode.clone();
+        let session_context = self.session_context.clone();
+        let cast_decimal_function = move |args: &[ArrayRef]| {
+            let strict = core_util::is_strict_sql_mode(sql_mode.lock().unwrap().as_str());
+            let mut builder = DecimalBuilder::new(args[0].len(), precision, scale);
+            for row_index in 0..args[0].len() {
+                let text = match core_util::array_value_to_text(&args[0], row_index)? {
+                    None => {
+                        builder.append_null()?;
+                        continue;
+                    }
+                    Some(text) => text,
+                };
+                match decimal::cast_decimal(text.as_str(), precision, scale, strict) {
+                    Ok(value) => {
+                        // the value out of the precision or not a number is a warning without the strict sql mode
+                        match decimal::parse_decimal(text.as_str(), precision, scale) {
+                            Err(decimal::DecimalError::OutOfRange) => session_context.push_warning(
+                                1264,
+                                format!("Out of range value for column 'cast_as_decimal' at row {}", row_index + 1).as_str(),
+                            ),
+                            Err(decimal::DecimalError::Invalid) => session_context.push_warning(
+                                1292,
+                                format!("Truncated incorrect DECIMAL value: '{}'", text).as_str(),
+                            ),
+                            Ok(_) => {}
+                        }
+                        builder.append_value(value)?
+                    }
+                    Err(decimal::DecimalError::OutOfRange) => {
+                        let mysql_error = MysqlError::new_server_error(
+                            1264,
+                            "22003",
+                            format!("Out of range value for column 'cast_as_decimal' at row {}", row_index + 1).as_str(),
+                        );
+                        return Err(DataFusionError::External(Box::new(mysql_error)));
+                    }
+                    Err(decimal::DecimalError::Invalid) => {
+                        let mysql_error = MysqlError::new_server_error(
+                            1292,
+                            "22007",
+                            format!("Truncated incorrect DECIMAL value: '{}'", text).as_str(),
+                        );
+                        return Err(DataFusionError::External(Box::new(mysql_error)));
+                    }
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        };
+
+        let cast_decimal_signature = Signature::any(1, Volatility::Immutable);
+        let cast_decimal_return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Decimal(precision, scale))));
+        self.datafusion_context.register_udf(ScalarUDF::new(
+            function_name.as_str(),
+            &cast_decimal_signature,
+            &cast_decimal_return_type,
+            &make_scalar_function(cast_decimal_function),
+        ));
+
+        function_name
     }
 
     pub fn fix_statement(&mut self, statement: SQLStatement) -> SQLStatement {
         match statement {
-            SQLStatement::Query(query) => {
-                let mut new_query = query.clone();
+            SQLStatement::Query(query) => SQLStatement::Query(Box::new(self.fix_query(&query))),
+            _ => statement.clone(),
+        }
+    }
+
+    /// Fill up the table names of the query and its derived tables with the current schema of the session,
+    /// so the nested queries resolve the unqualified names the same way the top-level query does
+    pub fn fix_query(&mut self, query: &Query) -> Query {
+        if let Some(with) = &query.with {
+            return self.fix_query_with(query, with);
+        }
 
-                let mut table_alias_vec = vec![];
+        let mut new_query = query.clone();
 
-                match &query.body {
-                    SetExpr::Select(select) => {
-                        let mut new_select = select.clone();
-
-                        // select database()
-                        if new_select.from.len() == 0 {
-                            let table_name = meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_DUAL
-                                .to_object_name();
-                            let table_with_joins = CoreUtil::build_table_with_joins(table_name);
-                            new_select.from = vec![table_with_joins];
+        let mut table_alias_vec = vec![];
+
+        match &query.body {
+            SetExpr::Select(select) => {
+                let mut new_select = select.clone();
+
+                // select database()
+                if new_select.from.len() == 0 {
+                    let table_name = meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_DUAL
+                        .to_object_name();
+                    let table_with_joins = CoreUtil::build_table_with_joins(table_name);
+                    new_select.from = vec![table_with_joins];
+                }
+
+                for i in 0..new_select.from.len() {
+                    let result = self
+                        .fix_table_factor(
+                            &mut table_alias_vec,
+                            new_select.from[i].relation.clone(),
+                        )
+                        .unwrap();
+                    if let Some(new_table_factor) = result {
+                        new_select.from[i].relation = new_table_factor;
+                    }
+
+                    for j in 0..new_select.from[i].joins.len() {
+                        let result = self
+                            .fix_table_factor(
+                                &mut table_alias_vec,
+                                new_select.from[i].joins[j].relation.clone(),
+                            )
+                            .unwrap();
+                        if let Some(new_table_factor) = result {
+                            new_select.from[i].joins[j].relation = new_table_factor;
                         }
 
-                        for i in 0..new_select.from.len() {
-                            let result = self
-                                .fix_table_factor(
-                                    &mut table_alias_vec,
-                                    new_select.from[i].relation.clone(),
-                                )
-                                .unwrap();
-                            if let Some(new_table_factor) = result {
-                                new_select.from[i].relation = new_table_factor;
-                            }
+                        let result = self
+                            .fix_join_operator(
+                                &mut table_alias_vec,
+                                &new_select.from[i].joins[j].join_operator,
+                            )
+                            .unwrap();
+                        if let Some(new_join_operator) = result {
+                            new_select.from[i].joins[j].join_operator = new_join_operator;
+                        }
+                    }
+                }
 
-                            for j in 0..new_select.from[i].joins.len() {
+                for i in 0..new_select.from.len() {
+                    for j in 0..new_select.from[i].joins.len() {
+                        match new_select.from[i].joins[j].join_operator.clone() {
+                            JoinOperator::Inner(join_constraint) => {
                                 let result = self
-                                    .fix_table_factor(
-                                        &mut table_alias_vec,
-                                        new_select.from[i].joins[j].relation.clone(),
+                                    .fix_join_column_name(
+                                        table_alias_vec.clone(),
+                                        &join_constraint,
                                     )
                                     .unwrap();
-                                if let Some(new_table_factor) = result {
-                                    new_select.from[i].joins[j].relation = new_table_factor;
+                                if let Some(new_join_constraint) = result {
+                                    new_select.from[i].joins[j].join_operator =
+                                        JoinOperator::Inner(new_join_constraint);
                                 }
-
+                            }
+                            JoinOperator::LeftOuter(join_constraint) => {
                                 let result = self
-                                    .fix_join_operator(
-                                        &mut table_alias_vec,
-                                        &new_select.from[i].joins[j].join_operator,
+                                    .fix_join_column_name(
+                                        table_alias_vec.clone(),
+                                        &join_constraint,
                                     )
                                     .unwrap();
-                                if let Some(new_join_operator) = result {
-                                    new_select.from[i].joins[j].join_operator = new_join_operator;
+                                if let Some(new_join_constraint) = result {
+                                    new_select.from[i].joins[j].join_operator =
+                                        JoinOperator::LeftOuter(new_join_constraint);
                                 }
                             }
-                        }
-
-                        for i in 0..new_select.from.len() {
-                            for j in 0..new_select.from[i].joins.len() {
-                                match new_select.from[i].joins[j].join_operator.clone() {
-                                    JoinOperator::Inner(join_constraint) => {
-                                        let result = self
-                                            .fix_join_column_name(
-                                                table_alias_vec.clone(),
-                                                &join_constraint,
-                                            )
-                                            .unwrap();
-                                        if let Some(new_join_constraint) = result {
-                                            new_select.from[i].joins[j].join_operator =
-                                                JoinOperator::Inner(new_join_constraint);
-                                        }
-                                    }
-                                    JoinOperator::LeftOuter(join_constraint) => {
-                                        let result = self
-                                            .fix_join_column_name(
-                                                table_alias_vec.clone(),
-                                                &join_constraint,
-                                            )
-                                            .unwrap();
-                                        if let Some(new_join_constraint) = result {
-                                            new_select.from[i].joins[j].join_operator =
-                                                JoinOperator::LeftOuter(new_join_constraint);
-                                        }
-                                    }
-                                    JoinOperator::RightOuter(join_constraint) => {
-                                        let result = self
-                                            .fix_join_column_name(
-                                                table_alias_vec.clone(),
-                                                &join_constraint,
-                                            )
-                                            .unwrap();
-                                        if let Some(new_join_constraint) = result {
-                                            new_select.from[i].joins[j].join_operator =
-                                                JoinOperator::RightOuter(new_join_constraint);
-                                        }
-                                    }
-                                    JoinOperator::FullOuter(join_constraint) => {
-                                        let result = self
-                                            .fix_join_column_name(
-                                                table_alias_vec.clone(),
-                                                &join_constraint,
-                                            )
-                                            .unwrap();
-                                        if let Some(new_join_constraint) = result {
-                                            new_select.from[i].joins[j].join_operator =
-                                                JoinOperator::FullOuter(new_join_constraint);
-                                        }
-                                    }
-                                    _ => {}
+                            JoinOperator::RightOuter(join_constraint) => {
+                                let result = self
+                                    .fix_join_column_name(
+                                        table_alias_vec.clone(),
+                                        &join_constraint,
+                                    )
+                                    .unwrap();
+                                if let Some(new_join_constraint) = result {
+                                    new_select.from[i].joins[j].join_operator =
+                                        JoinOperator::RightOuter(new_join_constraint);
                                 }
                             }
-                        }
-
-                        for i in 0..new_select.projection.len() {
-                            match new_select.projection[i].clone() {
-                                SelectItem::UnnamedExpr(sql_expr) => {
-                                    let result = self
-                                        .fix_column_name(table_alias_vec.clone(), &sql_expr)
-                                        .unwrap();
-                                    if let Some(new_sql_expr) = result {
-                                        new_select.projection[i] =
-                                            SelectItem::UnnamedExpr(new_sql_expr);
-                                    }
-                                }
-                                SelectItem::ExprWithAlias { expr, alias } => {
-                                    let result = self
-                                        .fix_column_name(table_alias_vec.clone(), &expr)
-                                        .unwrap();
-                                    if let Some(new_sql_expr) = result {
-                                        new_select.projection[i] = SelectItem::ExprWithAlias {
-                                            expr: new_sql_expr,
-                                            alias,
-                                        };
-                                    }
+                            JoinOperator::FullOuter(join_constraint) => {
+                                let result = self
+                                    .fix_join_column_name(
+                                        table_alias_vec.clone(),
+                                        &join_constraint,
+                                    )
+                                    .unwrap();
+                                if let Some(new_join_constraint) = result {
+                                    new_select.from[i].joins[j].join_operator =
+                                        JoinOperator::FullOuter(new_join_constraint);
                                 }
-                                _ => {}
                             }
+                            _ => {}
                         }
+                    }
+                }
 
-                        for i in 0..new_select.group_by.len() {
-                            let sql_expr = new_select.group_by[i].clone();
+                for i in 0..new_select.projection.len() {
+                    match new_select.projection[i].clone() {
+                        SelectItem::UnnamedExpr(sql_expr) => {
                             let result = self
                                 .fix_column_name(table_alias_vec.clone(), &sql_expr)
                                 .unwrap();
                             if let Some(new_sql_expr) = result {
-                                new_select.group_by[i] = new_sql_expr;
+                                new_select.projection[i] =
+                                    SelectItem::UnnamedExpr(new_sql_expr);
                             }
                         }
-
-                        match new_select.selection.clone() {
-                            None => {}
-                            Some(sql_expr) => {
-                                let result = self
-                                    .fix_column_name(table_alias_vec.clone(), &sql_expr)
-                                    .unwrap();
-                                if let Some(new_sql_expr) = result {
-                                    new_select.selection = Some(new_sql_expr)
-                                }
+                        SelectItem::ExprWithAlias { expr, alias } => {
+                            let result = self
+                                .fix_column_name(table_alias_vec.clone(), &expr)
+                                .unwrap();
+                            if let Some(new_sql_expr) = result {
+                                new_select.projection[i] = SelectItem::ExprWithAlias {
+                                    expr: new_sql_expr,
+                                    alias,
+                                };
                             }
                         }
-
-                        new_query.body = SetExpr::Select(new_select.clone());
+                        _ => {}
                     }
-                    _ => {}
-                };
+                }
 
-                for i in 0..new_query.order_by.len() {
-                    let sql_expr = new_query.order_by[i].expr.clone();
+                for i in 0..new_select.group_by.len() {
+                    let sql_expr = new_select.group_by[i].clone();
                     let result = self
                         .fix_column_name(table_alias_vec.clone(), &sql_expr)
                         .unwrap();
                     if let Some(new_sql_expr) = result {
-                        new_query.order_by[i].expr = new_sql_expr;
+                        new_select.group_by[i] = new_sql_expr;
+                    }
+                }
+
+                match new_select.selection.clone() {
+                    None => {}
+                    Some(sql_expr) => {
+                        let result = self
+                            .fix_column_name(table_alias_vec.clone(), &sql_expr)
+                            .unwrap();
+                        if let Some(new_sql_expr) = result {
+                            new_select.selection = Some(new_sql_expr)
+                        }
                     }
                 }
 
-                SQLStatement::Query(new_query)
+                new_query.body = SetExpr::Select(new_select.clone());
             }
-            _ => statement.clone(),
+            SetExpr::SetOperation { op, all, left, right } => {
+                new_query.body = SetExpr::SetOperation {
+                    op: op.clone(),
+                    all: *all,
+                    left: Box::new(self.fix_set_expr(left)),
+                    right: Box::new(self.fix_set_expr(right)),
+                };
+            }
+            _ => {}
+        };
+
+        for i in 0..new_query.order_by.len() {
+            let sql_expr = new_query.order_by[i].expr.clone();
+            let result = self
+                .fix_column_name(table_alias_vec.clone(), &sql_expr)
+                .unwrap();
+            if let Some(new_sql_expr) = result {
+                new_query.order_by[i].expr = new_sql_expr;
+            }
+        }
+
+        new_query
+    }
+
+    /// A side of UNION is filled up as a query of its own
+    fn fix_set_expr(&mut self, set_expr: &SetExpr) -> SetExpr {
+        let query = Query {
+            with: None,
+            body: set_expr.clone(),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+        };
+        self.fix_query(&query).body
+    }
+
+    /// The references to the common table expressions are renamed to the tables keeping their rows while the query runs,
+    /// the expressions are read from them by SelectFrom. A recursive expression reads itself, the other ones read the ones before them
+    fn fix_query_with(&mut self, query: &Query, with: &With) -> Query {
+        let mut cte_table_name_map = HashMap::new();
+        let mut new_with = with.clone();
+        for cte in new_with.cte_tables.iter_mut() {
+            let cte_name = cte.alias.name.value.to_lowercase();
+            let cte_table_name = common_table_expression::create_cte_table_name(cte_name.as_str());
+            if with.recursive {
+                cte_table_name_map.insert(cte_name.clone(), cte_table_name.clone());
+            }
+            let cte_query = common_table_expression::rename_cte_references(&cte.query, &cte_table_name_map);
+            cte.query = self.fix_query(&cte_query);
+            cte_table_name_map.insert(cte_name, cte_table_name.clone());
+            cte.alias.name = cte_table_name.0[2].clone();
         }
+
+        let query = Query { with: None, ..query.clone() };
+        let query = common_table_expression::rename_cte_references(&query, &cte_table_name_map);
+        let mut new_query = self.fix_query(&query);
+        new_query.with = Some(new_with);
+        new_query
     }
 
     pub fn fix_idents(
@@ -457,6 +1016,23 @@ impl Execution {
                 };
                 return Ok(Some(new_table_factor));
             }
+            // select t.id from (select id from table1) as t;
+            TableFactor::Derived {
+                lateral,
+                subquery,
+                alias,
+            } => {
+                if let Some(table_alias) = alias.clone() {
+                    table_alias_vec.push(table_alias.name);
+                }
+
+                let new_table_factor = TableFactor::Derived {
+                    lateral,
+                    subquery: Box::new(self.fix_query(&subquery)),
+                    alias,
+                };
+                return Ok(Some(new_table_factor));
+            }
             _ => {}
         }
 
@@ -541,7 +1117,32 @@ impl Execution {
                     return Ok(Some(SQLExpr::Function(new_function)));
                 }
             }
+            SQLExpr::Collate { expr, collation } => {
+                let new_expr = match self.fix_column_name(table_alias_vec.clone(), &*expr).unwrap() {
+                    Some(new_expr) => new_expr,
+                    None => *expr,
+                };
+                // the strings are compared and sorted by their keys in the collation,
+                // the unknown collations are left to the planner which rejects them
+                return match collation::find_collation(collation.to_string().as_str()) {
+                    Some(collation) => Ok(Some(collation_key_expr(new_expr, collation))),
+                    None => Ok(Some(SQLExpr::Collate { expr: Box::new(new_expr), collation })),
+                };
+            }
             SQLExpr::BinaryOp { left, op, right } => {
+                // the COLLATE of one side is the collation of the comparison
+                let comparison_collation = match op {
+                    BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+                    | BinaryOperator::Lt
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Gt
+                    | BinaryOperator::GtEq
+                    | BinaryOperator::Like
+                    | BinaryOperator::NotLike => collate_collation(&left).or_else(|| collate_collation(&right)),
+                    _ => None,
+                };
+
                 let result_left = self
                     .fix_column_name(table_alias_vec.clone(), &*left)
                     .unwrap();
@@ -549,7 +1150,15 @@ impl Execution {
                     .fix_column_name(table_alias_vec.clone(), &*right)
                     .unwrap();
 
-                if result_left.is_some() || result_right.is_some() {
+                let has_escape = match (&op, right.as_ref()) {
+                    (BinaryOperator::Like, SQLExpr::Value(Value::SingleQuotedString(pattern)))
+                    | (BinaryOperator::NotLike, SQLExpr::Value(Value::SingleQuotedString(pattern))) => {
+                        pattern.contains(like::DEFAULT_ESCAPE_CHAR)
+                    }
+                    _ => false,
+                };
+
+                if result_left.is_some() || result_right.is_some() || has_escape {
                     let mut new_left = left;
                     let mut new_right = right;
                     if let Some(expr) = result_left {
@@ -558,6 +1167,28 @@ impl Execution {
                     if let Some(expr) = result_right {
                         new_right = Box::new(expr);
                     }
+                    if let Some(comparison_collation) = comparison_collation {
+                        if collate_collation(&left).is_none() {
+                            new_left = Box::new(collation_key_expr(*new_left, comparison_collation));
+                        }
+                        if collate_collation(&right).is_none() {
+                            new_right = Box::new(collation_key_expr(*new_right, comparison_collation));
+                        }
+                    }
+
+                    // the like of datafusion does not know the escape char
+                    if has_escape {
+                        let function = SQLExpr::Function(Function {
+                            name: meta_const::FUNCTION_NAME_LIKE_ESCAPE.to_object_name(),
+                            args: vec![FunctionArg::Unnamed(*new_left), FunctionArg::Unnamed(*new_right)],
+                            over: None,
+                            distinct: false,
+                        });
+                        if op == BinaryOperator::NotLike {
+                            return Ok(Some(SQLExpr::UnaryOp { op: UnaryOperator::Not, expr: Box::new(function) }));
+                        }
+                        return Ok(Some(function));
+                    }
 
                     let new_sql_expr = SQLExpr::BinaryOp {
                         left: new_left,
@@ -576,6 +1207,48 @@ impl Execution {
                     return Ok(Some(SQLExpr::Nested(new_box_expr)));
                 }
             }
+            SQLExpr::Cast { expr, data_type } => {
+                let result = self
+                    .fix_column_name(table_alias_vec.clone(), &*expr)
+                    .unwrap();
+                let has_result = result.is_some();
+                let new_expr = match result {
+                    Some(new_expr) => new_expr,
+                    None => *expr,
+                };
+
+                if let SQLDataType::Decimal(precision, scale) = &data_type {
+                    let function_name = self.register_cast_decimal_udf(precision, scale);
+                    let function = SQLExpr::Function(Function {
+                        name: function_name.to_object_name(),
+                        args: vec![FunctionArg::Unnamed(new_expr)],
+                        over: None,
+                        distinct: false,
+                    });
+                    return Ok(Some(function));
+                }
+
+                if has_result {
+                    return Ok(Some(SQLExpr::Cast { expr: Box::new(new_expr), data_type }));
+                }
+            }
+            SQLExpr::InSubquery { expr, subquery, negated } => {
+                let new_expr = match self.fix_column_name(table_alias_vec.clone(), &*expr).unwrap() {
+                    Some(new_expr) => Box::new(new_expr),
+                    None => expr,
+                };
+                return Ok(Some(SQLExpr::InSubquery {
+                    expr: new_expr,
+                    subquery: Box::new(self.fix_query(&subquery)),
+                    negated,
+                }));
+            }
+            SQLExpr::Exists(subquery) => {
+                return Ok(Some(SQLExpr::Exists(Box::new(self.fix_query(&subquery)))));
+            }
+            SQLExpr::Subquery(subquery) => {
+                return Ok(Some(SQLExpr::Subquery(Box::new(self.fix_query(&subquery)))));
+            }
             SQLExpr::IsNull(box_expr) => {
                 let result = self
                     .fix_column_name(table_alias_vec.clone(), &*box_expr)
@@ -787,110 +1460,1076 @@ impl Execution {
         Ok(())
     }
 
+    /// COM_STMT_CLOSE has no response, the unknown statement is ignored
     pub async fn com_stmt_close(&mut self, bytes: &[u8]) -> MysqlResult<CoreOutput> {
-        let stmtID = LittleEndian::read_u32(&bytes);
-        self.stmt_context.remove_stmt(stmtID);
+        if bytes.len() >= 4 {
+            let stmt_id = LittleEndian::read_u32(bytes);
+            self.stmt_context.remove_stmt(stmt_id);
+        }
 
         Ok(CoreOutput::ComStmtClose)
     }
 
-    pub async fn com_stmt_execute(&mut self, bytes: Vec<u8>) -> MysqlResult<CoreOutput> {
-        let mut pos = 0;
-
-        // stmt id
-        let end = pos + 4;
-        let slice = bytes[pos..end].to_vec();
-        if end > bytes.len() {
-            return Err(MysqlError::new_global_error(
-                mysql_error_code::CR_MALFORMED_PACKET as u16,
-                format!("reading statement ID failed").as_str(),
-            ));
+    /// COM_STMT_RESET, the statement keeps its parameter types, the long data is discarded and the cursor is closed
+    pub async fn com_stmt_reset(&mut self, bytes: &[u8]) -> MysqlResult<CoreOutput> {
+        let stmt_id = read_stmt_id(bytes)?;
+        match self.stmt_context.stmts.get_mut(&stmt_id) {
+            Some(stmt_cache) => {
+                stmt_cache.clear_long_data();
+                stmt_cache.close_cursor();
+            }
+            None => return Err(unknown_stmt_error(stmt_id, "mysqld_stmt_reset")),
+        }
+
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
+    /// COM_STMT_SEND_LONG_DATA, a chunk of the value of the parameter, appended to the chunks before it.
+    /// It has no response, the unknown statement or parameter is ignored
+    pub async fn com_stmt_send_long_data(&mut self, bytes: &[u8]) -> MysqlResult<CoreOutput> {
+        // the statement id and the parameter id
+        if bytes.len() < 6 {
+            return Err(malformed_packet_error());
         }
-        let stmtID = LittleEndian::read_u32(&slice);
-        pos += 4;
+        let stmt_id = LittleEndian::read_u32(bytes);
+        let param_index = LittleEndian::read_u16(&bytes[4..6]) as usize;
 
-        // cursor type flag
-        let cursor_type_flag = bytes[pos];
-        pos += 1;
+        match self.stmt_context.stmts.get_mut(&stmt_id) {
+            Some(stmt_cache) if param_index < stmt_cache.get_num_params() => {
+                stmt_cache.append_long_data(param_index, &bytes[6..]);
+            }
+            _ => log::warn!("the long data of an unknown statement or parameter, statement: {}, parameter: {}", stmt_id, param_index),
+        }
 
-        // iteration-count, always 1
-        let iteration_count_offset = 4;
-        pos += iteration_count_offset;
+        Ok(CoreOutput::ComStmtSendLongData)
+    }
 
-        let mut stmt_cache = self.stmt_context.stmts.get_mut(&stmtID).unwrap();
+    /// COM_STMT_EXECUTE, the parameters are bound to the sql of the statement which is run as a query
+    pub async fn com_stmt_execute(&mut self, bytes: Vec<u8>) -> MysqlResult<CoreOutput> {
+        let stmt_id = read_stmt_id(&bytes)?;
+        // the statement id, the cursor type flag and the iteration count which is always 1
+        let mut pos = 4 + 1 + 4;
+        if bytes.len() < pos {
+            return Err(malformed_packet_error());
+        }
+        let cursor_flags = bytes[4];
 
-        let mut sql_statements = stmt_cache.get_statements();
+        let stmt_cache = match self.stmt_context.stmts.get_mut(&stmt_id) {
+            Some(stmt_cache) => stmt_cache,
+            None => return Err(unknown_stmt_error(stmt_id, "mysqld_stmt_execute")),
+        };
+        stmt_cache.close_cursor();
+        let sql = stmt_cache.get_sql();
         let num_params = stmt_cache.get_num_params();
+        let long_data = stmt_cache.take_long_data();
 
+        let mut stmt_values = vec![];
         if num_params > 0 {
-            let mut param_values;
-            let mut param_types;
-
             // null bitmaps
             let null_bitmap_len = (num_params + 7) / 8;
             if bytes.len() < (pos + null_bitmap_len + 1) {
-                return Err(MysqlError::new_global_error(
-                    mysql_error_code::CR_MALFORMED_PACKET as u16,
-                    format!("malformed packet error").as_str(),
-                ));
+                return Err(malformed_packet_error());
             }
             let end = pos + null_bitmap_len;
             let null_bitmap = bytes[pos..end].to_vec();
             pos += null_bitmap_len;
 
-            // new params bound flag
+            // new params bound flag, the types are sent at the first execution and kept for the next ones
             let new_params_bound_flag = bytes[pos];
             pos += 1;
-            if (new_params_bound_flag == 0x01) {
-                let length = num_params * 2;
-
-                let end = pos + length;
-                param_types = bytes[pos..end].to_vec();
-                pos += length;
+            let param_types = if new_params_bound_flag == 0x01 {
+                let end = pos + num_params * 2;
+                if bytes.len() < end {
+                    return Err(malformed_packet_error());
+                }
+                let param_types = bytes[pos..end].to_vec();
+                pos = end;
                 stmt_cache.set_param_types(param_types.as_slice());
-
-                param_values = bytes[pos..].to_vec();
+                param_types
             } else {
-                param_types = stmt_cache.get_param_types();
-                param_values = bytes[pos..].to_vec();
-            }
+                stmt_cache.get_param_types()
+            };
+            let param_values = bytes[pos..].to_vec();
 
-            let stmt_values =
-                parse_stmt_execute_args(null_bitmap, param_types, param_values).unwrap();
-            sql_statements = stmt_value(stmt_values, sql_statements);
+            stmt_values = parse_stmt_execute_args(num_params, null_bitmap, param_types, param_values, &long_data)?;
         }
 
-        self.execute_statement(sql_statements.clone()).await
+        let sql = placeholder::bind_placeholders(sql.as_str(), &stmt_values)?;
+        log::debug!("execute statement {}: {}", stmt_id, sql);
+        // with a read only cursor the rows are kept for COM_STMT_FETCH, they are collected first
+        let core_output = match self.execute_query(sql.as_str()).await? {
+            CoreOutput::ResultStream(result_stream) if cursor_flags & metadata::CURSOR_TYPE_READ_ONLY != 0 => {
+                CoreOutput::ResultSet(result_stream.collect().await?)
+            }
+            core_output => core_output,
+        };
+        match core_output {
+            CoreOutput::ResultSet(result_set) if cursor_flags & metadata::CURSOR_TYPE_READ_ONLY != 0 => {
+                let cursor = StmtCursor::new(result_set.schema_ref.clone(), result_set.record_batches);
+                if let Some(stmt_cache) = self.stmt_context.stmts.get_mut(&stmt_id) {
+                    stmt_cache.open_cursor(cursor);
+                }
+                Ok(CoreOutput::ComStmtCursor(result_set.schema_ref))
+            }
+            core_output => Ok(core_output),
+        }
     }
 
-    pub async fn com_stmt_prepare(&mut self, sql: &str) -> MysqlResult<CoreOutput> {
-        let dialect = &GenericDialect {};
-        let df_statements = DFParser::parse_sql_with_dialect(sql, dialect).unwrap();
+    /// COM_STMT_FETCH, the next rows of the cursor of the statement, the cursor is closed after its last row
+    pub async fn com_stmt_fetch(&mut self, bytes: &[u8]) -> MysqlResult<CoreOutput> {
+        // the statement id and the number of rows
+        if bytes.len() < 8 {
+            return Err(malformed_packet_error());
+        }
+        let stmt_id = LittleEndian::read_u32(bytes);
+        let num_rows = LittleEndian::read_u32(&bytes[4..8]) as usize;
+
+        let stmt_cache = match self.stmt_context.stmts.get_mut(&stmt_id) {
+            Some(stmt_cache) => stmt_cache,
+            None => return Err(unknown_stmt_error(stmt_id, "mysqld_stmt_fetch")),
+        };
+        let cursor = match stmt_cache.get_cursor_mut() {
+            Some(cursor) => cursor,
+            None => {
+                return Err(MysqlError::new_server_error(
+                    1421,
+                    "HY000",
+                    format!("The statement ({}) has no open cursor.", stmt_id).as_str(),
+                ))
+            }
+        };
+
+        let schema_ref = cursor.get_schema_ref();
+        let rows = cursor.fetch(num_rows);
+        let last_row_sent = cursor.is_exhausted();
+        if last_row_sent {
+            stmt_cache.close_cursor();
+        }
+        Ok(CoreOutput::ComStmtFetch(StmtFetch::new(schema_ref, rows, last_row_sent)))
+    }
+
+    /// COM_STMT_PREPARE, the sql is checked by the parser and kept with its placeholders
+    pub async fn com_stmt_prepare(&mut self, sql: &str) -> MysqlResult<CoreOutput> {
+        let dialect = &GenericDialect {};
+        let df_statements = DFParser::parse_sql_with_dialect(sql, dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
 
         let mut com_stmt_prepare = ComStmtPrepare::new(
             self.global_context.clone(),
             self.session_context.clone(),
             self.datafusion_context.clone(),
         );
-        let result = com_stmt_prepare.execute(&mut self.stmt_context, df_statements).await;
+        let result = com_stmt_prepare.execute(&mut self.stmt_context, sql, df_statements).await;
         match result {
             Ok(stmt_prepare) => Ok(CoreOutput::ComStmtPrepare(stmt_prepare)),
             Err(mysql_error) => Err(mysql_error),
         }
     }
 
+    /// Execute the query, with CLIENT_MULTI_STATEMENTS the statements of the query are executed one by one
+    /// until the first error
     pub async fn execute_query(&mut self, sql: &str) -> MysqlResult<CoreOutput> {
-        let mut new_sql = sql;
+        let multi_statements = *self.session_context.multi_statements.lock().unwrap();
+        if multi_statements {
+            let statements = multi_statement::split_statements(sql);
+            if statements.len() > 1 {
+                let mut outputs = vec![];
+                for statement in statements {
+                    let result = match self.execute_one_query(statement).await {
+                        // the rows are sent after all the statements are executed, so they are collected first
+                        Ok(CoreOutput::ResultStream(result_stream)) => result_stream.collect().await.map(CoreOutput::ResultSet),
+                        // the file is only requested from the client for a single statement query
+                        Ok(CoreOutput::LoadDataLocalInfile(_)) => Err(MysqlError::new_server_error(
+                            1235,
+                            "42000",
+                            "This version of MySQL doesn't yet support 'LOAD DATA LOCAL in a multi-statement query'",
+                        )),
+                        result => result,
+                    };
+                    let is_err = result.is_err();
+                    outputs.push(result);
+                    if is_err {
+                        break;
+                    }
+                }
+                return Ok(CoreOutput::MultiStatement(outputs));
+            }
+        }
+
+        self.execute_one_query(sql).await
+    }
+
+    /// Execute the statement, the warnings and the errors of the last statement are cleared first,
+    /// except for the statements which read them
+    async fn execute_one_query(&mut self, sql: &str) -> MysqlResult<CoreOutput> {
+        if !core_util::is_diagnostics_statement(sql) {
+            self.session_context.clear_warnings();
+        }
+
+        // the statement is shown by information_schema.PROCESSLIST until it is done, KILL QUERY cancels its token
+        let process_list = self.global_context.lock().unwrap().process_list.clone();
+        let db = self.session_context.current_schema.lock().unwrap().clone();
+        let query_token = process_list.start_query(self.connection_id, db, sql);
+        *self.session_context.query_token.lock().unwrap() = query_token;
+
+        let show_limit = core_util::normalize_query_sql(sql).ok().and_then(show_statement::parse_show_limit);
+        let result = match show_limit {
+            Some(statement) => self.execute_show_limit(statement).await,
+            None => self.execute_query_statement(sql).await,
+        };
+        process_list.end_query(self.connection_id);
+        match result {
+            Ok(core_output) => Ok(core_output.with_warning_count(self.session_context.warning_count())),
+            Err(mysql_error) => {
+                self.session_context.push_error(&mysql_error);
+                Err(mysql_error)
+            }
+        }
+    }
+
+    /// Execute the SHOW statement without the LIMIT, and keep the rows of the LIMIT
+    async fn execute_show_limit(&mut self, statement: ShowLimitStatement) -> MysqlResult<CoreOutput> {
+        let core_output = match self.execute_query_statement(statement.statement_sql.as_str()).await? {
+            CoreOutput::ResultStream(result_stream) => CoreOutput::ResultSet(result_stream.collect().await?),
+            core_output => core_output,
+        };
+        match core_output {
+            CoreOutput::ResultSet(result_set) => {
+                let record_batches = core_util::limit_record_batches(result_set.record_batches, statement.offset, statement.limit);
+                Ok(CoreOutput::ResultSet(ResultSet::new(result_set.schema_ref, record_batches)))
+            }
+            core_output => Ok(core_output),
+        }
+    }
+
+    async fn execute_query_statement(&mut self, sql: &str) -> MysqlResult<CoreOutput> {
+        let trim_sql = core_util::normalize_query_sql(sql)?;
+        if let Some(statement) = transaction_statement::parse_transaction(trim_sql) {
+            return match statement {
+                TransactionStatement::Begin => self.begin_transaction(),
+                TransactionStatement::Commit => self.commit_transaction(),
+                TransactionStatement::Rollback => self.rollback_transaction(),
+            };
+        }
+        // the statements of the meta data commit the transaction of the session before they run,
+        // with autocommit off the other statements open one if there is none
+        if transaction_statement::causes_implicit_commit(trim_sql) {
+            self.commit_transaction()?;
+        } else if !self.autocommit() && !self.in_transaction() {
+            self.open_transaction();
+        }
+
+        // outside a transaction the rows written by a statement are committed together at its end,
+        // the snapshots see all of them or none
+        if !self.in_transaction() && transaction_statement::is_row_write(trim_sql) {
+            self.open_transaction();
+            return match self.execute_session_statement(trim_sql).await {
+                Ok(core_output) => {
+                    self.commit_transaction()?;
+                    Ok(core_output)
+                }
+                Err(mysql_error) => {
+                    self.rollback_transaction()?;
+                    Err(mysql_error)
+                }
+            };
+        }
+
+        let result = match transaction_statement::parse_locking_read(trim_sql) {
+            Some((select_sql, lock_mode)) => self.execute_locking_read(select_sql.as_str(), lock_mode).await,
+            None => self.execute_session_statement(trim_sql).await,
+        };
+        // the transaction of the deadlock victim is rolled back, its locks are released for the other one
+        if let Err(mysql_error) = &result {
+            if mysql_error.error_number() == mysql_error_code::ER_LOCK_DEADLOCK as u16 {
+                self.rollback_transaction()?;
+            }
+        }
+        result
+    }
+
+    /// SELECT ... FOR UPDATE and LOCK IN SHARE MODE of one table, the rows matching the WHERE clause are locked
+    /// until the transaction ends before they are read. Outside a transaction it is the plain SELECT
+    async fn execute_locking_read(&mut self, select_sql: &str, lock_mode: LockMode) -> MysqlResult<CoreOutput> {
+        if !self.in_transaction() {
+            return self.execute_session_statement(select_sql).await;
+        }
+
+        let dialect = &GenericDialect {};
+        let statements = DFParser::parse_sql_with_dialect(select_sql, dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        let table_select = match statements.front() {
+            Some(Statement::Statement(SQLStatement::Query(query))) if statements.len() == 1 => match &query.body {
+                SetExpr::Select(select) if select.from.len() == 1 && select.from[0].joins.is_empty() => {
+                    match &select.from[0].relation {
+                        TableFactor::Table { name, .. } => Some((name.clone(), select.selection.clone())),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let (table_name, selection) = match table_select {
+            Some(table_select) => table_select,
+            None => {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                    "42000",
+                    "This version of MySQL doesn't yet support 'locking reads of more than one table'",
+                ));
+            }
+        };
+
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        let select = core_util::build_select_rowid_sqlselect(full_table_name.clone(), selection);
+        let query = Box::new(Query {
+            with: None,
+            body: SetExpr::Select(Box::new(select)),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+        });
+        let mut select_from = SelectFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        let result_set = select_from.execute(&query).await?;
+        let rowids = core_util::get_rowids(&result_set.record_batches);
+        core_util::lock_rows(self.global_context.clone(), &self.session_context, &full_table_name, rowids, lock_mode).await?;
+
+        self.execute_session_statement(select_sql).await
+    }
+
+    /// The statement which is not BEGIN, COMMIT nor ROLLBACK
+    async fn execute_session_statement(&mut self, trim_sql: &str) -> MysqlResult<CoreOutput> {
+        if let Some(statement) = schema_statement::parse_use(trim_sql) {
+            return self.set_default_schema(statement.schema_name.as_str()).await;
+        }
+        if let Some(statement) = schema_statement::parse_create_schema(trim_sql) {
+            let mut create_db = CreateDb::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = create_db.execute(
+                statement.schema_name,
+                statement.if_not_exists,
+                statement.character_set_name,
+                statement.collation_name,
+            )?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = schema_statement::parse_show_create_schema(trim_sql) {
+            let mut show_create_db = ShowCreateDb::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let result_set = show_create_db.execute(statement.schema_name, statement.if_not_exists)?;
+            return Ok(CoreOutput::ResultSet(result_set));
+        }
+        if let Some(statement) = explain_statement::parse_explain_format(trim_sql) {
+            return self.explain_format(statement).await;
+        }
+        if let Some(statement) = kill_statement::parse_kill(trim_sql) {
+            return self.kill(statement);
+        }
+        if let Some(statement) = index_statement::parse_drop_index(trim_sql) {
+            self.check_not_temporary_table(&statement.table_name, "DROP INDEX")?;
+            let mut drop_index = DropIndex::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = drop_index.execute(statement.index_name.as_str(), statement.table_name).await?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = upsert_statement::parse_upsert(trim_sql) {
+            return self.upsert(statement).await;
+        }
+        if let Some(statement) = delete_statement::parse_delete_limit(trim_sql) {
+            return self.delete_limit(statement).await;
+        }
+        if let Some(statement) = delete_statement::parse_truncate_table(trim_sql) {
+            let mut truncate_table = TruncateTable::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = truncate_table.execute(statement.table_name).await?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = temporary_table_statement::parse_drop_temporary_table(trim_sql) {
+            let mut drop_temporary_table = DropTemporaryTable::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = drop_temporary_table.execute(statement.table_names, statement.if_exists)?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = alter_column_statement::parse_modify_column(trim_sql) {
+            self.check_not_temporary_table(&statement.table_name, "ALTER TABLE")?;
+            return self.modify_column(statement).await;
+        }
+        if let Some(statement) = alter_column_statement::parse_add_column(trim_sql) {
+            self.check_not_temporary_table(&statement.table_name, "ALTER TABLE")?;
+            let column_def = self.parse_column_def(statement.column_sql.as_str())?;
+            let mut add_column = AddColumn::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = add_column.execute(statement.table_name, column_def, Some(statement.position)).await?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = rename_table_statement::parse_rename_table(trim_sql) {
+            for (table_name, _) in statement.table_names.iter() {
+                self.check_not_temporary_table(table_name, "RENAME TABLE")?;
+            }
+            let mut rename_table = RenameTable::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = rename_table.execute(statement.table_names).await?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = user_statement::parse_create_user(trim_sql) {
+            let create_user = CreateUser::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = create_user.execute(statement)?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = user_statement::parse_drop_user(trim_sql) {
+            let drop_user = DropUser::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = drop_user.execute(statement)?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = user_statement::parse_set_password(trim_sql) {
+            let set_password = SetPassword::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = set_password.execute(statement)?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = user_statement::parse_grant(trim_sql) {
+            let grant = Grant::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let count = grant.execute(statement)?;
+            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+        }
+        if let Some(statement) = user_statement::parse_show_grants(trim_sql) {
+            let show_grants = ShowGrants::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            return show_grants.execute(&statement).map(CoreOutput::ResultSet);
+        }
+        if let Some(statement) = user_variable::parse_set_user_variable(trim_sql) {
+            return self.set_user_variables(statement).await;
+        }
+        if let Some(statement) = user_variable::parse_select_into(trim_sql) {
+            return self.select_into_user_variables(statement).await;
+        }
+        if let Some(statement) = set_statement::parse_set_variable(trim_sql) {
+            // the other variables, as tenant_id, are set by the SET of the sql parser
+            let all_system_variables = statement
+                .assignments
+                .iter()
+                .all(|assignment| system_variable::get_system_variable_def(assignment.name.as_str()).is_some());
+            if all_system_variables {
+                return self.set_system_variables(statement);
+            }
+        }
+        if let Some(statement) = show_statement::parse_show_variables(trim_sql) {
+            return self.show_variables(statement).await;
+        }
+        if let Some(statement) = show_statement::parse_show_charset(trim_sql) {
+            let result = if statement.collation {
+                ShowCollation::new(self.global_context.clone(), self.session_context.clone(), self.datafusion_context.clone())
+                    .execute(statement.pattern.as_deref())
+            } else {
+                ShowCharset::new(self.global_context.clone(), self.session_context.clone(), self.datafusion_context.clone())
+                    .execute(statement.pattern.as_deref())
+            };
+            return result.map(CoreOutput::ResultSet);
+        }
+        if let Some(statement) = show_statement::parse_show_warnings(trim_sql) {
+            let show_warnings = ShowWarnings::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            return show_warnings.execute(&statement).map(CoreOutput::ResultSet);
+        }
+        if let Some(statement) = load_data_statement::parse_load_data(trim_sql) {
+            if statement.local {
+                // the file is requested from the client by the handle, then loaded by start_load_data_local
+                let local_infile = self.global_context.lock().unwrap().my_config.server.local_infile;
+                if !local_infile {
+                    return Err(MysqlError::new_server_error(
+                        3948,
+                        "42000",
+                        "Loading local data is disabled; this must be enabled on both the client and server sides",
+                    ));
+                }
+                return Ok(CoreOutput::LoadDataLocalInfile(statement));
+            }
+
+            let mut load_data = LoadData::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.datafusion_context.clone(),
+            );
+            let load_data_count = load_data.execute(statement)?;
+            return Ok(load_data_output(load_data_count));
+        }
+
+        // the ZEROFILL is added back to its columns after the parser
+        let zerofill_statement = create_table_statement::parse_zerofill(trim_sql);
+        let trim_sql = match &zerofill_statement {
+            Some(zerofill_statement) => zerofill_statement.statement_sql.as_str(),
+            None => trim_sql,
+        };
+        // the CHARACTER SET of the columns and the table is resolved to their collations after the parser
+        let charset_statement = create_table_statement::parse_charset(trim_sql);
+        let trim_sql = match &charset_statement {
+            Some(charset_statement) => charset_statement.statement_sql.as_str(),
+            None => trim_sql,
+        };
+        // the FOREIGN KEY constraints are checked and saved by the CREATE TABLE after the parser
+        let foreign_key_statement = create_table_statement::parse_foreign_key(trim_sql);
+        let trim_sql = match &foreign_key_statement {
+            Some(foreign_key_statement) => foreign_key_statement.statement_sql.as_str(),
+            None => trim_sql,
+        };
+
+        let sql = self.rewrite_query_sql(trim_sql);
+        let mut new_sql = sql.as_str();
         if sql.starts_with("SET NAMES") {
             new_sql = "SET NAMES = utf8mb4"
         }
 
         let dialect = &GenericDialect {};
-        let statements = DFParser::parse_sql_with_dialect(new_sql, dialect).unwrap();
+        let mut statements = DFParser::parse_sql_with_dialect(new_sql, dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        if statements.is_empty() {
+            return Err(MysqlError::new_server_error(1065, "42000", "Query was empty"));
+        }
+        if statements.len() > 1 {
+            // without CLIENT_MULTI_STATEMENTS mysql parses the query as one statement,
+            // the statements after the first are a syntax error
+            let multi_statements = *self.session_context.multi_statements.lock().unwrap();
+            if !multi_statements {
+                return Err(MysqlError::new_server_error(
+                    1064,
+                    "42000",
+                    "You have an error in your SQL syntax; multiple statements in one query need the CLIENT_MULTI_STATEMENTS capability",
+                ));
+            }
+            // the query is split before the parser, the parser found a statement in an unsplit part
+            return Err(MysqlError::new_server_error(
+                1064,
+                "42000",
+                "You have an error in your SQL syntax; the statements of the query could not be split",
+            ));
+        }
+
+        if let Some(zerofill_statement) = zerofill_statement {
+            for statement in statements.iter_mut() {
+                if let Statement::Statement(SQLStatement::CreateTable { columns, .. }) = statement {
+                    for sql_column in columns.iter_mut() {
+                        if zerofill_statement.column_names.contains(&sql_column.name.value) {
+                            sql_column.options.push(meta_util::create_zerofill_option());
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(charset_statement) = charset_statement {
+            let table_collation = collation::resolve_collation(
+                charset_statement.character_set_name.as_deref(),
+                charset_statement.collation_name.as_deref(),
+            )?;
+            for statement in statements.iter_mut() {
+                if let Statement::Statement(SQLStatement::CreateTable { columns, with_options, .. }) = statement {
+                    for (column_name, character_set_name) in charset_statement.column_character_sets.iter() {
+                        if let Some(sql_column) = columns.iter_mut().find(|sql_column| &sql_column.name.value == column_name) {
+                            let collation_name = sql_column.collation.as_ref().map(|collation_name| collation_name.to_string());
+                            let column_collation =
+                                collation::resolve_collation(Some(character_set_name.as_str()), collation_name.as_deref())?.unwrap();
+                            sql_column.collation = Some(ObjectName(vec![Ident::new(column_collation.name)]));
+                        }
+                    }
+                    if let Some(table_collation) = table_collation {
+                        with_options.push(SqlOption {
+                            name: Ident::new(meta_const::NAME_OF_TABLE_OPTION_COLLATION),
+                            value: Value::SingleQuotedString(table_collation.name.to_string()),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(foreign_key_statement) = foreign_key_statement {
+            return self.create_table_with_foreign_keys(statements, foreign_key_statement.foreign_keys);
+        }
 
         self.execute_statement(statements).await
     }
 
+    /// CREATE TABLE with the FOREIGN KEY constraints the sql parser does not know
+    fn create_table_with_foreign_keys(&mut self, statements: Vec<Statement>, foreign_keys: Vec<ForeignKeyClause>) -> MysqlResult<CoreOutput> {
+        let statement = match &statements[0] {
+            Statement::Statement(statement) => self.fix_statement(statement.clone()),
+            _ => return Err(MysqlError::new_global_error(1105, "Unknown error. The statement is not a CREATE TABLE")),
+        };
+        match statement {
+            SQLStatement::CreateTable { temporary, name, columns, constraints, with_options, without_rowid, query, .. } => {
+                if temporary {
+                    return Err(MysqlError::new_server_error(1215, "HY000", "Cannot add foreign key constraint"));
+                }
+                if query.is_some() {
+                    return Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                        "42000",
+                        "This version of MySQL doesn't yet support 'CREATE TABLE ... SELECT with FOREIGN KEY constraints'",
+                    ));
+                }
+
+                let mut with_options = with_options;
+                if without_rowid {
+                    with_options.push(SqlOption {
+                        name: Ident::new(meta_const::NAME_OF_TABLE_OPTION_WITHOUT_ROWID),
+                        value: Value::Boolean(true),
+                    });
+                }
+
+                let mut create_table = CreateTable::new(
+                    self.global_context.clone(),
+                    self.session_context.clone(),
+                    self.datafusion_context.clone(),
+                );
+                create_table.with_foreign_keys(foreign_keys);
+                let count = create_table.execute(name, columns, constraints, with_options)?;
+                Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)))
+            }
+            _ => Err(MysqlError::new_global_error(1105, "Unknown error. The statement is not a CREATE TABLE")),
+        }
+    }
+
+    /// The sql rewritten for what the sql parser does not know, the user variables are replaced by their values
+    fn rewrite_query_sql(&self, sql: &str) -> String {
+        let sql = like::rewrite_like_escape(sql);
+        let sql = string_function::rewrite_replace_function(sql.as_str());
+        let sql = date_function::rewrite_date_functions(sql.as_str());
+        let sql = rowid_alias::rewrite_rowid_alias(sql.as_str());
+        core_util::rewrite_user_variables(sql.as_str(), &self.session_context)
+    }
+
+    /// REPLACE and INSERT ... ON DUPLICATE KEY UPDATE, run as the plain INSERT resolving the duplicated keys
+    async fn upsert(&mut self, statement: UpsertStatement) -> MysqlResult<CoreOutput> {
+        let sql = self.rewrite_query_sql(statement.insert_sql.as_str());
+        let dialect = &GenericDialect {};
+        let statements = DFParser::parse_sql_with_dialect(sql.as_str(), dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        let (table_name, columns, source) = match statements.front() {
+            Some(Statement::Statement(SQLStatement::Insert { table_name, columns, source, .. })) if statements.len() == 1 => {
+                let source = match &source.body {
+                    SetExpr::Values(_) => source.clone(),
+                    _ => Box::new(self.fix_query(source)),
+                };
+                (table_name.clone(), columns.clone(), source)
+            }
+            _ => {
+                return Err(MysqlError::new_server_error(
+                    1064,
+                    "42000",
+                    "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use",
+                ));
+            }
+        };
+        self.check_not_temporary_table(&table_name, "REPLACE and INSERT ... ON DUPLICATE KEY UPDATE")?;
+        let mut insert = Insert::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        let count = insert.execute_on_duplicate(table_name, columns, source, statement.on_duplicate).await?;
+        let warning_count = self.session_context.warning_count();
+        let message = format!("Records: {}  Duplicates: {}  Warnings: {}", insert.records(), insert.duplicates(), warning_count);
+        let last_insert_id = insert.generated_insert_id().unwrap_or(0);
+        Ok(CoreOutput::FinalCount(FinalCount::new_with_message(count, last_insert_id, message.as_str())))
+    }
+
+    /// DELETE ... ORDER BY ... LIMIT, its WHERE clause, ORDER BY and LIMIT are parsed as the ones of a SELECT
+    async fn delete_limit(&mut self, statement: DeleteLimitStatement) -> MysqlResult<CoreOutput> {
+        let sql = self.rewrite_query_sql(statement.select_sql.as_str());
+        let dialect = &GenericDialect {};
+        let statements = DFParser::parse_sql_with_dialect(sql.as_str(), dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        let delete = match statements.front() {
+            // the LIMIT of DELETE has no offset
+            Some(Statement::Statement(SQLStatement::Query(query))) if statements.len() == 1 && query.offset.is_none() => match &query.body {
+                SetExpr::Select(select) if select.from.len() == 1 && select.from[0].joins.is_empty() => match &select.from[0].relation {
+                    TableFactor::Table { name, .. } => Some((name.clone(), select.selection.clone(), query.order_by.clone(), query.limit.clone())),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+        let (table_name, selection, order_by, limit) = match delete {
+            Some(delete) => delete,
+            None => {
+                return Err(MysqlError::new_server_error(
+                    1064,
+                    "42000",
+                    "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use near 'LIMIT'",
+                ));
+            }
+        };
+
+        let mut delete_from = DeleteFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        self.check_not_temporary_table(&table_name, "DELETE")?;
+        let count = delete_from.execute_with_limit(table_name, selection, order_by, limit).await?;
+        Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)))
+    }
+
+    /// The statements writing the tables of the storage do not run on the temporary tables,
+    /// they would write the table hidden by the temporary one
+    fn check_not_temporary_table(&mut self, table_name: &ObjectName, statement_name: &str) -> MysqlResult<()> {
+        if core_util::get_temporary_table(&mut self.session_context, table_name).is_none() {
+            return Ok(());
+        }
+        Err(MysqlError::new_server_error(
+            mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+            "42000",
+            format!("This version of MySQL doesn't yet support '{} of TEMPORARY tables'", statement_name).as_str(),
+        ))
+    }
+
+    /// ALTER TABLE ... MODIFY COLUMN and CHANGE COLUMN
+    async fn modify_column(&mut self, statement: ModifyColumnStatement) -> MysqlResult<CoreOutput> {
+        let column_def = self.parse_column_def(statement.column_sql.as_str())?;
+        let mut modify_column = ModifyColumn::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        let count = modify_column.execute(statement.table_name, Ident::new(statement.column_name), column_def).await?;
+        let warning_count = self.session_context.warning_count();
+        let message = format!("Records: {}  Duplicates: 0  Warnings: {}", count, warning_count);
+        Ok(CoreOutput::FinalCount(FinalCount::new_with_message(count, 0, message.as_str())))
+    }
+
+    /// The column definition of ALTER TABLE, it is parsed as the one of a CREATE TABLE
+    fn parse_column_def(&self, column_sql: &str) -> MysqlResult<ColumnDef> {
+        let sql = format!("CREATE TABLE t ({})", column_sql);
+        let dialect = &GenericDialect {};
+        let statements = DFParser::parse_sql_with_dialect(sql.as_str(), dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        match statements.front() {
+            Some(Statement::Statement(SQLStatement::CreateTable { columns, constraints, .. }))
+                if statements.len() == 1 && columns.len() == 1 && constraints.is_empty() =>
+            {
+                Ok(columns[0].clone())
+            }
+            _ => Err(MysqlError::new_server_error(
+                1064,
+                "42000",
+                format!(
+                    "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use near '{}'",
+                    column_sql
+                )
+                .as_str(),
+            )),
+        }
+    }
+
+    /// CREATE TABLE ... AS SELECT, the columns of the query output which are not defined are added with the
+    /// types of the output, then the rows of the query are inserted. The table is dropped if a row fails
+    async fn create_table_as_select(
+        &mut self,
+        table_name: ObjectName,
+        columns: Vec<ColumnDef>,
+        constraints: Vec<TableConstraint>,
+        with_options: Vec<SqlOption>,
+        query: Box<Query>,
+    ) -> MysqlResult<CoreOutput> {
+        let mut select_from = SelectFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        let result_set = select_from.execute(&self.fix_query(&query)).await?;
+
+        let mut columns = columns;
+        let mut column_names = vec![];
+        for field in result_set.schema_ref.fields() {
+            let defined = columns.iter().any(|sql_column| sql_column.name.value.to_lowercase() == field.name().to_lowercase());
+            if !defined {
+                let data_type = meta_util::convert_arrow_data_type_to_sql_data_type(field.data_type());
+                columns.push(meta_util::create_sql_column(field.name(), data_type, ColumnOption::Null));
+            }
+            column_names.push(Ident::new(field.name()));
+        }
+
+        let mut create_table = CreateTable::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        create_table.execute(table_name.clone(), columns, constraints, with_options)?;
+
+        let source = Box::new(Query {
+            with: None,
+            body: SetExpr::Values(Values(core_util::record_batches_to_values(&result_set.record_batches)?)),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+        });
+        let mut insert = Insert::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        match insert.execute(table_name.clone(), column_names, false, source) {
+            Ok(count) => {
+                let warning_count = self.session_context.warning_count();
+                let message = format!("Records: {}  Duplicates: {}  Warnings: {}", count, 0, warning_count);
+                Ok(CoreOutput::FinalCount(FinalCount::new_with_message(count, 0, message.as_str())))
+            }
+            Err(mysql_error) => {
+                let mut drop_table = DropTable::new(
+                    self.global_context.clone(),
+                    self.session_context.clone(),
+                    self.datafusion_context.clone(),
+                );
+                drop_table.execute(table_name).await?;
+                Err(mysql_error)
+            }
+        }
+    }
+
+    /// Run the SELECT of SET @var and SELECT ... INTO @var
+    async fn select_for_user_variables(&mut self, sql: &str) -> MysqlResult<ResultSet> {
+        let sql = self.rewrite_query_sql(sql);
+        let dialect = &GenericDialect {};
+        let statements = DFParser::parse_sql_with_dialect(sql.as_str(), dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        let query = match statements.first() {
+            Some(Statement::Statement(statement)) if statements.len() == 1 => match self.fix_statement(statement.clone()) {
+                SQLStatement::Query(query) => Some(query),
+                _ => None,
+            },
+            _ => None,
+        };
+        let query = query.ok_or_else(|| {
+            MysqlError::new_server_error(
+                1064,
+                "42000",
+                "You have an error in your SQL syntax; the value of a user variable must be a SELECT expression",
+            )
+        })?;
+
+        let mut select_from = SelectFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        select_from.execute(&query).await
+    }
+
+    /// SET @var = expr, the expressions are evaluated in order, an expression reads the variables set before it
+    async fn set_user_variables(&mut self, statement: SetUserVariableStatement) -> MysqlResult<CoreOutput> {
+        for assignment in statement.assignments {
+            let result_set = self.select_for_user_variables(format!("SELECT {}", assignment.expr_sql).as_str()).await?;
+            let mut value = ScalarValue::Utf8(None);
+            if let Some(record_batch) = result_set.record_batches.iter().find(|record_batch| record_batch.num_rows() > 0) {
+                value = core_util::user_variable_value(record_batch.column(0), 0)?;
+            }
+            self.session_context.user_variables.lock().unwrap().insert(assignment.name, value);
+        }
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
+    /// SELECT ... INTO @var, the SELECT must have one column for each variable and at most one row.
+    /// No row keeps the values of the variables, with a warning as in mysql
+    async fn select_into_user_variables(&mut self, statement: SelectIntoStatement) -> MysqlResult<CoreOutput> {
+        let result_set = self.select_for_user_variables(statement.select_sql.as_str()).await?;
+        if result_set.schema_ref.fields().len() != statement.variable_names.len() {
+            return Err(MysqlError::new_server_error(
+                1222,
+                "21000",
+                "The used SELECT statements have a different number of columns",
+            ));
+        }
+
+        let num_rows: usize = result_set.record_batches.iter().map(|record_batch| record_batch.num_rows()).sum();
+        if num_rows > 1 {
+            return Err(MysqlError::new_server_error(1172, "42000", "Result consisted of more than one row"));
+        }
+        let record_batch = match result_set.record_batches.iter().find(|record_batch| record_batch.num_rows() > 0) {
+            Some(record_batch) => record_batch,
+            None => {
+                self.session_context.push_warning(1329, "No data - zero rows fetched, selected, or processed");
+                return Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)));
+            }
+        };
+
+        let mut values = vec![];
+        for column_index in 0..record_batch.num_columns() {
+            values.push(core_util::user_variable_value(record_batch.column(column_index), 0)?);
+        }
+        let mut user_variables = self.session_context.user_variables.lock().unwrap();
+        for (variable_name, value) in statement.variable_names.into_iter().zip(values) {
+            user_variables.insert(variable_name, value);
+        }
+        Ok(CoreOutput::FinalCount(FinalCount::new(1, 0)))
+    }
+
+    /// Start to load the file of LOAD DATA LOCAL in a blocking task, the rows are loaded in batches
+    /// as the packets of the file are received from the client, the end of the packets ends the file
+    pub fn start_load_data_local(
+        &self,
+        statement: LoadDataStatement,
+        packets: Receiver<io::Result<Vec<u8>>>,
+    ) -> JoinHandle<MysqlResult<LoadDataCount>> {
+        let mut load_data = LoadData::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        tokio::task::spawn_blocking(move || load_data.execute_local(statement, LocalInfileReader::new(packets)))
+    }
+
+    /// The output of the LOAD DATA LOCAL started by start_load_data_local, once all the file is loaded
+    pub async fn finish_load_data_local(&mut self, load: JoinHandle<MysqlResult<LoadDataCount>>) -> MysqlResult<CoreOutput> {
+        let result = match load.await {
+            Ok(result) => result,
+            Err(error) => {
+                let message = format!("Unknown error. Loading the local infile failed: {:?}", error);
+                log::error!("{}", message);
+                Err(MysqlError::new_global_error(1105, message.as_str()))
+            }
+        };
+        match result {
+            Ok(load_data_count) => {
+                let warning_count = self.session_context.warning_count();
+                Ok(load_data_output(load_data_count).with_warning_count(warning_count))
+            }
+            Err(mysql_error) => {
+                self.session_context.push_error(&mysql_error);
+                Err(mysql_error)
+            }
+        }
+    }
+
+    /// EXPLAIN FORMAT = TREE, the other formats are not supported yet
+    async fn explain_format(&mut self, statement: ExplainFormatStatement) -> MysqlResult<CoreOutput> {
+        if statement.format != "TREE" {
+            return Err(MysqlError::new_server_error(
+                1235,
+                "42000",
+                format!("This version of MySQL doesn't yet support 'EXPLAIN FORMAT={}'", statement.format).as_str(),
+            ));
+        }
+
+        let sql = self.rewrite_query_sql(statement.statement_sql.as_str());
+        let dialect = &GenericDialect {};
+        let statements = DFParser::parse_sql_with_dialect(sql.as_str(), dialect).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            log::error!("{}", message);
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        let statement = match statements.first() {
+            Some(Statement::Statement(statement)) if statements.len() == 1 => self.fix_statement(statement.clone()),
+            _ => {
+                return Err(MysqlError::new_server_error(
+                    1064,
+                    "42000",
+                    "You have an error in your SQL syntax; EXPLAIN FORMAT needs one statement",
+                ));
+            }
+        };
+
+        let mut explain = Explain::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        let result_set = explain.execute_tree(&statement).await?;
+        Ok(CoreOutput::ResultSet(result_set))
+    }
+
     pub async fn execute_statement(
         &mut self,
         statements: Vec<Statement>,
@@ -901,6 +2540,7 @@ impl Execution {
                 match statement {
                     SQLStatement::AlterTable { name, operation } => {
                         let table_name = name;
+                        self.check_not_temporary_table(&table_name, "ALTER TABLE")?;
                         match operation.clone() {
                             AlterTableOperation::DropColumn { column_name, .. } => {
                                 let mut drop_column = DropColumn::new(
@@ -922,7 +2562,7 @@ impl Execution {
                                     self.session_context.clone(),
                                     self.datafusion_context.clone(),
                                 );
-                                let result = add_column.execute(table_name, column_def);
+                                let result = add_column.execute(table_name, column_def, None).await;
                                 match result {
                                     Ok(count) => {
                                         Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)))
@@ -940,7 +2580,7 @@ impl Execution {
                             }
                         }
                     }
-                    SQLStatement::CreateSchema { schema_name, .. } => {
+                    SQLStatement::CreateSchema { schema_name, if_not_exists } => {
                         let db_name = meta_util::object_name_remove_quote(schema_name.clone());
 
                         let mut create_db = CreateDb::new(
@@ -948,21 +2588,53 @@ impl Execution {
                             self.session_context.clone(),
                             self.datafusion_context.clone(),
                         );
-                        let result = create_db.execute(db_name);
+                        let result = create_db.execute(db_name, if_not_exists, None, None);
                         match result {
                             Ok(count) => Ok(CoreOutput::FinalCount(FinalCount::new(count, 0))),
                             Err(mysql_error) => Err(mysql_error),
                         }
                     }
                     SQLStatement::CreateTable {
+                        temporary,
+                        if_not_exists,
                         name,
                         columns,
                         constraints,
                         with_options,
+                        without_rowid,
+                        query,
                         ..
                     } => {
                         let table_name = name.clone();
 
+                        if temporary {
+                            if query.is_some() {
+                                return Err(MysqlError::new_server_error(
+                                    mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                                    "42000",
+                                    "This version of MySQL doesn't yet support 'CREATE TEMPORARY TABLE ... SELECT'",
+                                ));
+                            }
+                            let mut create_temporary_table = CreateTemporaryTable::new(
+                                self.global_context.clone(),
+                                self.session_context.clone(),
+                                self.datafusion_context.clone(),
+                            );
+                            let count = create_temporary_table.execute(table_name, columns, constraints, if_not_exists)?;
+                            return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+                        }
+
+                        let mut with_options = with_options;
+                        if without_rowid {
+                            with_options.push(SqlOption {
+                                name: Ident::new(meta_const::NAME_OF_TABLE_OPTION_WITHOUT_ROWID),
+                                value: Value::Boolean(true),
+                            });
+                        }
+                        if let Some(query) = query {
+                            return self.create_table_as_select(table_name, columns, constraints, with_options, query).await;
+                        }
+
                         let mut create_table = CreateTable::new(
                             self.global_context.clone(),
                             self.session_context.clone(),
@@ -975,6 +2647,57 @@ impl Execution {
                             Err(mysql_error) => Err(mysql_error),
                         }
                     }
+                    SQLStatement::CreateView {
+                        or_replace,
+                        materialized,
+                        name,
+                        columns,
+                        query,
+                        ..
+                    } => {
+                        if materialized {
+                            return Err(MysqlError::new_server_error(
+                                1235,
+                                "42000",
+                                "This version of MySQL doesn't yet support 'MATERIALIZED VIEW'",
+                            ));
+                        }
+                        // the definition is saved with the full table names, it reads the same tables in any schema
+                        let query = self.fix_query(&query);
+
+                        let mut create_view = CreateView::new(
+                            self.global_context.clone(),
+                            self.session_context.clone(),
+                            self.datafusion_context.clone(),
+                        );
+                        let result = create_view.execute(name, columns, query, or_replace).await;
+                        match result {
+                            Ok(count) => Ok(CoreOutput::FinalCount(FinalCount::new(count, 0))),
+                            Err(mysql_error) => Err(mysql_error),
+                        }
+                    }
+                    SQLStatement::CreateIndex {
+                        name,
+                        table_name,
+                        columns,
+                        unique,
+                        if_not_exists,
+                    } => {
+                        self.check_not_temporary_table(&table_name, "CREATE INDEX")?;
+                        let mut create_index = CreateIndex::new(
+                            self.global_context.clone(),
+                            self.session_context.clone(),
+                            self.datafusion_context.clone(),
+                        );
+                        let result = create_index.execute(name, table_name, columns, unique, if_not_exists).await;
+                        match result {
+                            Ok(count) => {
+                                let message = format!("Records: {}  Duplicates: {}  Warnings: {}", count, 0, 0);
+                                Ok(CoreOutput::FinalCount(FinalCount::new_with_message(count, 0, message.as_str())))
+                            }
+                            Err(mysql_error) => Err(mysql_error),
+                        }
+                    }
                     SQLStatement::Insert {
                         table_name,
                         columns,
@@ -987,9 +2710,22 @@ impl Execution {
                             self.session_context.clone(),
                             self.datafusion_context.clone(),
                         );
-                        let result = insert.execute(table_name, columns, overwrite, source);
+                        // the query of INSERT ... SELECT resolves its table names as the plain SELECT
+                        let source = match &source.body {
+                            SetExpr::Values(_) => source,
+                            _ => Box::new(self.fix_query(&source)),
+                        };
+                        let result = match core_util::get_temporary_table(&mut self.session_context, &table_name) {
+                            Some(temporary_table) => insert.execute_temporary_table(temporary_table, columns, source).await,
+                            None => insert.execute_select(table_name, columns, overwrite, source).await,
+                        };
                         match result {
-                            Ok(count) => Ok(CoreOutput::FinalCount(FinalCount::new(count, 0))),
+                            Ok(count) => {
+                                let warning_count = self.session_context.warning_count();
+                                let message = format!("Records: {}  Duplicates: {}  Warnings: {}", count, 0, warning_count);
+                                let last_insert_id = insert.generated_insert_id().unwrap_or(0);
+                                Ok(CoreOutput::FinalCount(FinalCount::new_with_message(count, last_insert_id, message.as_str())))
+                            }
                             Err(mysql_error) => Err(mysql_error),
                         }
                     }
@@ -998,6 +2734,7 @@ impl Execution {
                         assignments,
                         selection,
                     } => {
+                        self.check_not_temporary_table(&table_name, "UPDATE")?;
                         let mut update_set = Update::new(
                             self.global_context.clone(),
                             self.session_context.clone(),
@@ -1005,7 +2742,13 @@ impl Execution {
                         );
                         let result = update_set.execute(table_name, assignments, selection).await;
                         match result {
-                            Ok(count) => Ok(CoreOutput::FinalCount(FinalCount::new(count, 0))),
+                            Ok((matched, changed)) => {
+                                let warning_count = self.session_context.warning_count();
+                                let message = format!("Rows matched: {}  Changed: {}  Warnings: {}", matched, changed, warning_count);
+                                // with CLIENT_FOUND_ROWS the affected rows are the matched rows
+                                let affect_rows = if *self.session_context.found_rows.lock().unwrap() { matched } else { changed };
+                                Ok(CoreOutput::FinalCount(FinalCount::new_with_message(affect_rows, 0, message.as_str())))
+                            }
                             Err(mysql_error) => Err(mysql_error),
                         }
                     }
@@ -1013,6 +2756,7 @@ impl Execution {
                         table_name,
                         selection,
                     } => {
+                        self.check_not_temporary_table(&table_name, "DELETE")?;
                         let mut delete_from = DeleteFrom::new(
                             self.global_context.clone(),
                             self.session_context.clone(),
@@ -1032,7 +2776,14 @@ impl Execution {
                             self.session_context.clone(),
                             self.datafusion_context.clone(),
                         );
+                        // the handle sends the rows as they are produced, the profile is kept after the last row
+                        if *self.session_context.stream_results.lock().unwrap() {
+                            *self.session_context.last_profile.lock().unwrap() = None;
+                            return select_from.execute_stream(query).await.map(CoreOutput::ResultStream);
+                        }
                         let result = select_from.execute(query).await;
+                        // the SHOW statements run the queries too, only the SELECT of the client is profiled
+                        *self.session_context.last_profile.lock().unwrap() = select_from.take_profile();
                         match result {
                             Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
                             Err(mysql_error) => Err(mysql_error),
@@ -1056,11 +2807,22 @@ impl Execution {
                         }
                     }
                     SQLStatement::Drop {
-                        object_type, names, ..
+                        object_type, if_exists, names, ..
                     } => match object_type {
                         ObjectType::Table => {
                             let table_name = names[0].clone();
 
+                            // the temporary table hides the table of the same name, it is dropped first
+                            if core_util::get_temporary_table(&mut self.session_context, &table_name).is_some() {
+                                let mut drop_temporary_table = DropTemporaryTable::new(
+                                    self.global_context.clone(),
+                                    self.session_context.clone(),
+                                    self.datafusion_context.clone(),
+                                );
+                                let count = drop_temporary_table.execute(vec![table_name], if_exists)?;
+                                return Ok(CoreOutput::FinalCount(FinalCount::new(count, 0)));
+                            }
+
                             let mut drop_table = DropTable::new(
                                 self.global_context.clone(),
                                 self.session_context.clone(),
@@ -1072,6 +2834,18 @@ impl Execution {
                                 Err(mysql_error) => Err(mysql_error),
                             }
                         }
+                        ObjectType::View => {
+                            let mut drop_view = DropView::new(
+                                self.global_context.clone(),
+                                self.session_context.clone(),
+                                self.datafusion_context.clone(),
+                            );
+                            let result = drop_view.execute(names, if_exists).await;
+                            match result {
+                                Ok(count) => Ok(CoreOutput::FinalCount(FinalCount::new(count, 0))),
+                                Err(mysql_error) => Err(mysql_error),
+                            }
+                        }
                         ObjectType::Schema => {
                             let schema_name = names[0].clone();
 
@@ -1110,8 +2884,26 @@ impl Execution {
                     SQLStatement::SetVariable {
                         variable, value, ..
                     } => {
-                        let idents = vec![variable];
-                        let variable = ObjectName(idents);
+                        let variable_name = variable.value.to_lowercase();
+                        // the tenant is bound to the user at auth
+                        if variable_name == meta_const::SESSION_VARIABLE_TENANT_ID {
+                            return Err(MysqlError::new_server_error(
+                                mysql_error_code::ER_VARIABLE_IS_READONLY as u16,
+                                "HY000",
+                                format!("Variable '{}' is a read only variable", variable_name).as_str(),
+                            ));
+                        }
+                        if system_variable::get_system_variable_def(variable_name.as_str()).is_some() {
+                            let value = value.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+                            let statement = SetVariableStatement {
+                                assignments: vec![SystemVariableAssignment {
+                                    name: variable_name,
+                                    global: false,
+                                    value: Some(value.trim_matches('\'').trim_matches('"').to_string()),
+                                }],
+                            };
+                            return self.set_system_variables(statement);
+                        }
 
                         let mut drop_schema = SetVariable::new(
                             self.global_context.clone(),
@@ -1167,12 +2959,12 @@ impl Execution {
                         } else if first_variable.to_string().to_uppercase()
                             == meta_const::SHOW_VARIABLE_GRANTS.to_uppercase()
                         {
-                            let mut show_grants = ShowGrants::new(
+                            let show_grants = ShowGrants::new(
                                 self.global_context.clone(),
                                 self.session_context.clone(),
                                 self.datafusion_context.clone(),
                             );
-                            let result = show_grants.execute();
+                            let result = show_grants.execute(&ShowGrantsStatement { account: None });
                             match result {
                                 Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
                                 Err(mysql_error) => Err(mysql_error),
@@ -1211,7 +3003,7 @@ impl Execution {
                                 self.session_context.clone(),
                                 self.datafusion_context.clone(),
                             );
-                            let result = show_charset.execute();
+                            let result = show_charset.execute(None);
                             match result {
                                 Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
                                 Err(mysql_error) => Err(mysql_error),
@@ -1224,7 +3016,46 @@ impl Execution {
                                 self.session_context.clone(),
                                 self.datafusion_context.clone(),
                             );
-                            let result = show_collation.execute();
+                            let result = show_collation.execute(None);
+                            match result {
+                                Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
+                                Err(mysql_error) => Err(mysql_error),
+                            }
+                        } else if first_variable.to_string().to_uppercase()
+                            == meta_const::SHOW_VARIABLE_PREPARED.to_uppercase()
+                        {
+                            let show_prepared_statements = ShowPreparedStatements::new(
+                                self.global_context.clone(),
+                                self.session_context.clone(),
+                                self.datafusion_context.clone(),
+                            );
+                            let result = show_prepared_statements.execute(&self.stmt_context);
+                            match result {
+                                Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
+                                Err(mysql_error) => Err(mysql_error),
+                            }
+                        } else if first_variable.to_string().to_uppercase()
+                            == meta_const::SHOW_VARIABLE_CONFIG.to_uppercase()
+                        {
+                            let show_config = ShowConfig::new(
+                                self.global_context.clone(),
+                                self.session_context.clone(),
+                                self.datafusion_context.clone(),
+                            );
+                            let result = show_config.execute();
+                            match result {
+                                Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
+                                Err(mysql_error) => Err(mysql_error),
+                            }
+                        } else if first_variable.to_string().to_uppercase()
+                            == meta_const::SHOW_VARIABLE_PROFILE.to_uppercase()
+                        {
+                            let show_profile = ShowProfile::new(
+                                self.global_context.clone(),
+                                self.session_context.clone(),
+                                self.datafusion_context.clone(),
+                            );
+                            let result = show_profile.execute();
                             match result {
                                 Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
                                 Err(mysql_error) => Err(mysql_error),
@@ -1251,20 +3082,28 @@ impl Execution {
                         }
                     }
                     SQLStatement::ShowVariables { filter } => {
-                        let mut show_variables = ShowVariables::new(
+                        let filter = match filter {
+                            Some(ShowStatementFilter::Like(pattern)) | Some(ShowStatementFilter::ILike(pattern)) => {
+                                Some(ShowVariablesFilter::Like(pattern))
+                            }
+                            Some(ShowStatementFilter::Where(sql_expr)) => Some(ShowVariablesFilter::Where(sql_expr.to_string())),
+                            None => None,
+                        };
+                        self.show_variables(ShowVariablesStatement { global: false, filter }).await
+                    }
+                    SQLStatement::Commit { .. } => self.commit_transaction(),
+                    SQLStatement::Analyze { table_name, .. } => {
+                        let mut analyze_table = AnalyzeTable::new(
                             self.global_context.clone(),
                             self.session_context.clone(),
                             self.datafusion_context.clone(),
                         );
-                        let result = show_variables.execute(filter).await;
+                        let result = analyze_table.execute(table_name);
                         match result {
                             Ok(result_set) => Ok(CoreOutput::ResultSet(result_set)),
                             Err(mysql_error) => Err(mysql_error),
                         }
                     }
-                    SQLStatement::Commit { .. } => {
-                        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
-                    }
                     _ => Err(MysqlError::new_global_error(
                         1105,
                         "Unknown error. The statement is not supported",
@@ -1288,18 +3127,384 @@ impl Execution {
         );
         let result = set_default_schema.execute(schema_name);
         match result {
-            Ok(count) => Ok(CoreOutput::FinalCount(FinalCount::new_with_message(
-                count,
-                0,
-                "Database changed",
-            ))),
+            Ok(count) => {
+                let mut final_count = FinalCount::new_with_message(count, 0, "Database changed");
+                final_count.with_session_state_change(SessionStateChange::Schema(db_name.to_string()));
+                Ok(CoreOutput::FinalCount(final_count))
+            }
             Err(mysql_error) => Err(mysql_error),
         }
     }
 
-    pub async fn com_field_list(&mut self, table_name: &str) -> MysqlResult<CoreOutput> {
+    /// The max payload the client can send, the global max_allowed_packet when the connection starts
+    pub fn max_allowed_packet(&self) -> usize {
+        let global_context = self.global_context.lock().unwrap();
+        global_context
+            .variable
+            .get_variable_value(meta_const::SYSTEM_VARIABLE_MAX_ALLOWED_PACKET)
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(packet::DEFAULT_MAX_ALLOWED_PACKET)
+    }
+
+    /// The max packet size is the smaller one of the client and the server max_allowed_packet
+    pub fn set_client_max_packet_size(&mut self, client_max_packet_size: u32) -> u32 {
+        let mut max_packet_size = client_max_packet_size;
+        let global_context = self.global_context.lock().unwrap();
+        let result = global_context.variable.get_variable_value(meta_const::SYSTEM_VARIABLE_MAX_ALLOWED_PACKET);
+        if let Some(value) = result {
+            if let Ok(server_max_packet_size) = value.parse::<u32>() {
+                if max_packet_size == 0 || server_max_packet_size < max_packet_size {
+                    max_packet_size = server_max_packet_size;
+                }
+            }
+        }
+
+        *self.session_context.max_packet_size.lock().unwrap() = Some(max_packet_size);
+        max_packet_size
+    }
+
+    pub fn set_client_multi_statements(&mut self, multi_statements: bool) {
+        *self.session_context.multi_statements.lock().unwrap() = multi_statements;
+    }
+
+    /// The SELECT of the client returns the stream of its rows instead of the collected rows
+    pub fn set_client_stream_results(&mut self, stream_results: bool) {
+        *self.session_context.stream_results.lock().unwrap() = stream_results;
+    }
+
+    /// The warnings of the last statement, with the ones raised while the rows of its stream were produced
+    pub fn warning_count(&self) -> u64 {
+        self.session_context.warning_count()
+    }
+
+    /// The error which ended the rows of the stream, the statement was done before its rows were sent
+    pub fn push_stream_error(&self, mysql_error: &MysqlError) {
+        self.session_context.push_error(mysql_error);
+    }
+
+    pub fn set_client_found_rows(&mut self, found_rows: bool) {
+        *self.session_context.found_rows.lock().unwrap() = found_rows;
+    }
+
+    pub fn set_client_interactive(&mut self, interactive: bool) {
+        *self.session_context.interactive.lock().unwrap() = interactive;
+    }
+
+    /// The time the connection waits for the next command before it is closed, the session wait_timeout
+    pub fn wait_timeout(&self) -> Duration {
+        let wait_timeout = self
+            .session_context
+            .get_session_variable(meta_const::SYSTEM_VARIABLE_WAIT_TIMEOUT)
+            .or_else(|| self.global_context.lock().unwrap().variable.get_variable_value(meta_const::SYSTEM_VARIABLE_WAIT_TIMEOUT))
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(28800);
+        Duration::from_secs(wait_timeout)
+    }
+
+    /// The connection is closed by COM_QUIT, KILL CONNECTION or the wait_timeout,
+    /// the prepared statements with their cursors are released and the connection leaves the process list
+    pub fn close(&mut self) {
+        self.stmt_context = StmtContext::new();
+        self.session_context.reset();
+        self.global_context.lock().unwrap().process_list.unregister(self.connection_id);
+    }
+
+    pub fn connection_id(&self) -> u32 {
+        self.connection_id
+    }
+
+    /// The token cancelled when the connection is killed, the handle closes the connection then
+    pub fn connection_token(&self) -> CancellationToken {
+        self.connection_token.clone()
+    }
+
+    /// The user of the connection in information_schema.PROCESSLIST after the auth,
+    /// the privileges of the user are checked for the next statements and the session is bound to its tenant
+    pub fn set_process_user(&self, user: &str, host: &str) {
+        *self.session_context.current_user.lock().unwrap() = Some(Account::new(user, host));
+        let tenant_id = self.global_context.lock().unwrap().my_config.server.tenants.get(user).cloned();
+        *self.session_context.tenant_id.lock().unwrap() = tenant_id;
+        self.global_context.lock().unwrap().process_list.set_user(self.connection_id, user, host);
+    }
+
+    /// The connection attributes sent by the client, shown by information_schema.SESSION_CONNECT_ATTRS
+    pub fn set_connect_attrs(&self, connect_attrs: Vec<(String, String)>) {
+        self.global_context.lock().unwrap().process_list.set_connect_attrs(self.connection_id, connect_attrs);
+    }
+
+    /// The autocommit of the session, with it on every statement outside BEGIN and COMMIT is committed by itself
+    pub fn autocommit(&self) -> bool {
+        let value = match self.session_context.get_session_variable(meta_const::SYSTEM_VARIABLE_AUTOCOMMIT) {
+            Some(value) => Some(value),
+            None => self
+                .global_context
+                .lock()
+                .unwrap()
+                .variable
+                .get_variable_value(meta_const::SYSTEM_VARIABLE_AUTOCOMMIT),
+        };
+        value.map(|value| value.eq_ignore_ascii_case("ON")).unwrap_or(true)
+    }
+
+    /// The session has a transaction opened by BEGIN or by a statement with autocommit off
+    pub fn in_transaction(&self) -> bool {
+        self.session_context.transaction.lock().unwrap().is_some()
+    }
+
+    /// The transaction reads the snapshot of the last commit until it ends
+    fn open_transaction(&mut self) {
+        let snapshot = transaction::read_snapshot(self.global_context.clone(), None);
+        let lock_owner = self.global_context.lock().unwrap().lock_manager.owner();
+        *self.session_context.transaction.lock().unwrap() = Some(Transaction::new(snapshot, lock_owner));
+    }
+
+    /// BEGIN and START TRANSACTION, the transaction already open is committed first
+    pub fn begin_transaction(&mut self) -> MysqlResult<CoreOutput> {
+        self.commit_transaction()?;
+        self.open_transaction();
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
+    /// The writes staged by the transaction are written in one batch of their engine, COMMIT without a transaction
+    /// does nothing. It fails if a row written by the transaction was committed by another one after its snapshot.
+    /// The row locks are released after the writes
+    pub fn commit_transaction(&mut self) -> MysqlResult<CoreOutput> {
+        let transaction = self.session_context.transaction.lock().unwrap().take();
+        if let Some(transaction) = transaction {
+            let snapshot = transaction.snapshot().clone();
+            // into_writes consumes the transaction, the owner keeps the row locks held until the writes are applied
+            let lock_owner = transaction.lock_owner();
+            for (engine, writes) in transaction.into_writes() {
+                let store_engine = StoreEngineFactory::try_new_with_engine(self.global_context.clone(), engine.as_str())?;
+                store_engine.commit_keys(writes, &snapshot)?;
+            }
+            drop(lock_owner);
+        }
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
+    /// The writes staged by the transaction are discarded and its row locks are released
+    pub fn rollback_transaction(&mut self) -> MysqlResult<CoreOutput> {
+        *self.session_context.transaction.lock().unwrap() = None;
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
+    /// KILL [CONNECTION | QUERY], the connection closes itself when it sees its token cancelled
+    pub fn kill(&self, statement: KillStatement) -> MysqlResult<CoreOutput> {
+        let process_list = self.global_context.lock().unwrap().process_list.clone();
+        let unknown_thread_error = || {
+            MysqlError::new_server_error(
+                1094,
+                "HY000",
+                format!("Unknown thread id: {}", statement.connection_id).as_str(),
+            )
+        };
+        let process = match process_list.get(statement.connection_id) {
+            Some(process) => process,
+            None => return Err(unknown_thread_error()),
+        };
+
+        // the user kills only its own connections without SUPER
+        let current_user = self.session_context.current_user.lock().unwrap().clone();
+        if let Some(account) = current_user {
+            if process.user != account.user && !privilege::has_privilege(self.global_context.clone(), &account, Privilege::Super, None)? {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_KILL_DENIED_ERROR as u16,
+                    "HY000",
+                    format!("You are not owner of thread {}", statement.connection_id).as_str(),
+                ));
+            }
+        }
+
+        if !process_list.kill(statement.connection_id, statement.query_only) {
+            return Err(unknown_thread_error());
+        }
+
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
+    /// COM_PROCESS_KILL, the deprecated command of KILL CONNECTION
+    pub fn com_process_kill(&self, bytes: &[u8]) -> MysqlResult<CoreOutput> {
+        if bytes.len() < 4 {
+            return Err(malformed_packet_error());
+        }
+        let connection_id = LittleEndian::read_u32(bytes);
+        self.kill(KillStatement {
+            connection_id,
+            query_only: false,
+        })
+    }
+
+    /// Check the auth response of the client with the mysql_native_password scramble,
+    /// the anonymous user logs in with an empty user name if it is in mysql.user.
+    /// The row of the user is the one with the most specific host matching the host of the client
+    pub fn authenticate(&self, user: &str, host: &str, auth_response: &[u8]) -> MysqlResult<()> {
+        let passed = match self.authentication_string(user, host)? {
+            Some(authentication_string) => auth::check_native_password(&self.scramble, auth_response, authentication_string.as_str()),
+            None => false,
+        };
+        if passed {
+            return Ok(());
+        }
+
+        Err(auth::access_denied_error(user, host, !auth_response.is_empty()))
+    }
+
+    /// The fast auth of caching_sha2_password, false if the user has no digest cached by a full auth
+    /// with the current password, the client sends the password for the full auth then
+    pub fn fast_authenticate_sha2_password(&self, user: &str, host: &str, auth_response: &[u8]) -> MysqlResult<bool> {
+        let authentication_string = match self.authentication_string(user, host)? {
+            Some(authentication_string) => authentication_string,
+            None => return Ok(false),
+        };
+
+        let global_context = self.global_context.lock().unwrap();
+        match global_context.sha2_password_cache.get(user) {
+            Some((cached_authentication_string, digest)) if *cached_authentication_string == authentication_string => {
+                Ok(auth::check_sha2_password(&self.scramble, auth_response, digest))
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// The full auth of caching_sha2_password with the password, the digest of the password
+    /// is cached for the fast auth of the next connections of the user
+    pub fn authenticate_password(&self, user: &str, host: &str, password: &[u8]) -> MysqlResult<()> {
+        if let Some(authentication_string) = self.authentication_string(user, host)? {
+            let passed = match std::str::from_utf8(password) {
+                Ok(password) => auth::native_password_hash(password) == authentication_string,
+                Err(_) => false,
+            };
+            if passed {
+                let digest = auth::sha2_password_digest(password);
+                self.global_context
+                    .lock()
+                    .unwrap()
+                    .sha2_password_cache
+                    .insert(user.to_string(), (authentication_string.clone(), digest));
+                return Ok(());
+            }
+        }
+
+        Err(auth::access_denied_error(user, host, !password.is_empty()))
+    }
+
+    /// The authentication_string of the row of mysql.user the client logs in with
+    fn authentication_string(&self, user: &str, host: &str) -> MysqlResult<Option<String>> {
+        let user = privilege::find_user(self.global_context.clone(), user, host)?;
+        Ok(user.map(|user| privilege::row_value(&user, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_AUTHENTICATION_STRING).to_string()))
+    }
+
+    /// The rows of the tables with the tenant column are isolated by the tenant id,
+    /// for the sessions opened by the server itself, the clients are bound to the tenant of their user
+    pub fn set_tenant_id(&mut self, tenant_id: &str) {
+        *self.session_context.tenant_id.lock().unwrap() = Some(tenant_id.to_string());
+    }
+
+    /// The max execution time of SELECT in milliseconds for the session, 0 is no limit
+    pub fn set_max_execution_time(&mut self, max_execution_time: u64) {
+        *self.session_context.max_execution_time.lock().unwrap() = Some(max_execution_time);
+    }
+
+    pub fn set_sql_mode(&mut self, sql_mode: &str) {
+        *self.session_context.sql_mode.lock().unwrap() = sql_mode.to_uppercase();
+    }
+
+    /// SET of the system variables, all the values are checked before any of them is set.
+    /// SET GLOBAL needs SUPER
+    pub fn set_system_variables(&mut self, statement: SetVariableStatement) -> MysqlResult<CoreOutput> {
+        if statement.assignments.iter().any(|assignment| assignment.global) {
+            privilege::check_global_privilege(self.global_context.clone(), &self.session_context, Privilege::Super)?;
+        }
+
+        let mut values = vec![];
+        for assignment in statement.assignments {
+            let variable_def = system_variable::get_settable_variable_def(assignment.name.as_str(), assignment.global)?;
+            let value = match assignment.value {
+                Some(value) => variable_def.parse_value(value.as_str())?,
+                // DEFAULT, the global value gets the default of the variable and the session value the global one
+                None if assignment.global => variable_def.default_value.to_string(),
+                None => self
+                    .global_context
+                    .lock()
+                    .unwrap()
+                    .variable
+                    .get_variable_value(variable_def.name)
+                    .unwrap_or_else(|| variable_def.default_value.to_string()),
+            };
+            values.push((variable_def, assignment.global, value));
+        }
+
+        let mut final_count = FinalCount::new(0, 0);
+        for (variable_def, global, value) in values {
+            if global {
+                self.global_context.lock().unwrap().variable.set_variable(variable_def.name, value.as_str());
+                continue;
+            }
+            self.set_session_variable(variable_def.name, value.as_str())?;
+            if meta_const::TRACKED_SYSTEM_VARIABLES.contains(&variable_def.name) {
+                let value = self.session_context.get_session_variable(variable_def.name).unwrap_or(value);
+                final_count.with_session_state_change(SessionStateChange::SystemVariable(variable_def.name.to_string(), value));
+            }
+        }
+        Ok(CoreOutput::FinalCount(final_count))
+    }
+
+    /// Set the session value of the system variable, by SET or by the session variables of the config
+    pub fn set_session_variable(&mut self, variable_name: &str, value: &str) -> MysqlResult<()> {
+        let variable_name = variable_name.to_lowercase();
+        if variable_name == meta_const::SYSTEM_VARIABLE_CHARACTER_SET {
+            for variable_name in &[
+                meta_const::SYSTEM_VARIABLE_CHARACTER_SET_CLIENT,
+                meta_const::SYSTEM_VARIABLE_CHARACTER_SET_CONNECTION,
+                meta_const::SYSTEM_VARIABLE_CHARACTER_SET_RESULTS,
+            ] {
+                self.set_session_variable(variable_name, value)?;
+            }
+            return Ok(());
+        }
+
+        let variable_def = system_variable::get_settable_variable_def(variable_name.as_str(), false)?;
+        let value = variable_def.parse_value(value)?;
+        match variable_def.name {
+            meta_const::SYSTEM_VARIABLE_SQL_MODE => self.set_sql_mode(value.as_str()),
+            meta_const::SYSTEM_VARIABLE_MAX_EXECUTION_TIME => {
+                self.set_max_execution_time(value.parse::<u64>().unwrap_or(0))
+            }
+            meta_const::SYSTEM_VARIABLE_AUTOCOMMIT => {
+                // turning autocommit on commits the open transaction
+                let autocommit = value == "ON";
+                self.session_context.system_variables.lock().unwrap().insert(variable_def.name.to_string(), value);
+                if autocommit {
+                    self.commit_transaction()?;
+                }
+            }
+            _ => {
+                self.session_context.system_variables.lock().unwrap().insert(variable_def.name.to_string(), value);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn show_variables(&mut self, statement: ShowVariablesStatement) -> MysqlResult<CoreOutput> {
+        let mut show_variables = ShowVariables::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.datafusion_context.clone(),
+        );
+        let result_set = show_variables.execute(statement).await?;
+        Ok(CoreOutput::ResultSet(result_set))
+    }
+
+    /// COM_PING only answers OK, it must stay cheap and must not touch the session state
+    pub fn com_ping(&self) -> MysqlResult<CoreOutput> {
+        Ok(CoreOutput::FinalCount(FinalCount::new(0, 0)))
+    }
+
+    /// COM_FIELD_LIST, the columns of the table whose names match the wildcard, all the columns without a wildcard
+    pub async fn com_field_list(&mut self, table_name: &str, wildcard: &str) -> MysqlResult<CoreOutput> {
         let table_name = table_name.to_object_name();
-        log::debug!("com field list table name: {}", table_name);
+        log::debug!("com field list table name: {}, wildcard: {}", table_name, wildcard);
 
         let mut com_field_list = ComFieldList::new(
             self.global_context.clone(),
@@ -1309,7 +3514,21 @@ impl Execution {
         let result = com_field_list.execute(table_name.clone());
         match result {
             Ok((schema_name, table_name, table_def)) => {
-                Ok(CoreOutput::ComFieldList(schema_name, table_name, table_def))
+                let wildcard = wildcard.to_lowercase();
+                let columns = table_def
+                    .get_columns()
+                    .iter()
+                    .filter(|column_def| {
+                        wildcard.is_empty()
+                            || like::like_match(
+                                column_def.sql_column.name.value.to_lowercase().as_str(),
+                                wildcard.as_str(),
+                                like::DEFAULT_ESCAPE_CHAR,
+                            )
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                Ok(CoreOutput::ComFieldList(schema_name, table_name, columns))
             }
             Err(mysql_error) => Err(mysql_error),
         }
@@ -1333,3 +3552,64 @@ impl Execution {
         }
     }
 }
+
+/// The unit of the interval of the date functions, the other units are not supported yet
+fn parse_interval_unit(unit: &str) -> datafusion::error::Result<date_function::IntervalUnit> {
+    match date_function::IntervalUnit::parse(unit) {
+        Some(unit) => Ok(unit),
+        None => {
+            let mysql_error = MysqlError::new_server_error(
+                1235,
+                "42000",
+                format!("This version of MySQL doesn't yet support 'INTERVAL unit {}'", unit).as_str(),
+            );
+            Err(DataFusionError::External(Box::new(mysql_error)))
+        }
+    }
+}
+
+/// The collation of the COLLATE clause, None for the other expressions and the unknown collations
+fn collate_collation(sql_expr: &SQLExpr) -> Option<&'static Collation> {
+    match sql_expr {
+        SQLExpr::Collate { collation, .. } => collation::find_collation(collation.to_string().as_str()),
+        _ => None,
+    }
+}
+
+/// COLLATION_KEY(expr, 'collation'), the strings compared and sorted by the collation
+fn collation_key_expr(sql_expr: SQLExpr, collation: &Collation) -> SQLExpr {
+    SQLExpr::Function(Function {
+        name: meta_const::FUNCTION_NAME_COLLATION_KEY.to_object_name(),
+        args: vec![
+            FunctionArg::Unnamed(sql_expr),
+            FunctionArg::Unnamed(SQLExpr::Value(Value::SingleQuotedString(collation.name.to_string()))),
+        ],
+        over: None,
+        distinct: false,
+    })
+}
+
+/// The OK of LOAD DATA with the counts of the rows
+fn load_data_output(load_data_count: LoadDataCount) -> CoreOutput {
+    let message = format!(
+        "Records: {}  Deleted: {}  Skipped: {}  Warnings: {}",
+        load_data_count.records, load_data_count.deleted, load_data_count.skipped, load_data_count.warnings,
+    );
+    CoreOutput::FinalCount(FinalCount::new_with_message(load_data_count.loaded, 0, message.as_str()))
+}
+
+/// The statement id at the start of the packet of the statement commands
+fn read_stmt_id(bytes: &[u8]) -> MysqlResult<u32> {
+    if bytes.len() < 4 {
+        return Err(malformed_packet_error());
+    }
+    Ok(LittleEndian::read_u32(bytes))
+}
+
+fn unknown_stmt_error(stmt_id: u32, command: &str) -> MysqlError {
+    MysqlError::new_server_error(
+        1243,
+        "HY000",
+        format!("Unknown prepared statement handler ({}) given to {}", stmt_id, command).as_str(),
+    )
+}
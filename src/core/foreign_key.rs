@@ -0,0 +1,606 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use datafusion::scalar::ScalarValue;
+use sqlparser::ast::{Ident, ObjectName};
+
+use crate::core::core_util;
+use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
+use crate::meta::meta_const;
+use crate::meta::meta_def::{ForeignKeyDef, ReferentialAction, TableDef, TableIndexDef};
+use crate::meta::meta_util;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::store::engine::engine_util::{StoreEngine, StoreEngineFactory};
+use crate::store::engine::row_mutation::RowMutation;
+use crate::util::dbkey;
+
+/// The number of keys of an index read from the store at a time
+const SCAN_BATCH_SIZE: usize = 1024;
+
+/// A row by its rowid with the new values of its columns by their store ids, NULL is `[0x00]` as it is stored
+pub type RowChange = (String, Vec<(i64, Vec<u8>)>);
+
+/// The changes of the rows of the referencing tables made by ON DELETE and ON UPDATE. They are all
+/// planned before any of them is written, a RESTRICT found deep in the cascade changes no row
+#[derive(Default)]
+struct CascadePlan {
+    /// The rows deleted by the statement and by the cascade, a row is deleted once
+    deleting: HashSet<(ObjectName, String)>,
+    updates: Vec<(TableDef, Vec<RowChange>)>,
+    deletes: Vec<(TableDef, Vec<String>)>,
+}
+
+impl CascadePlan {
+    fn is_deleting(&self, table_def: &TableDef, rowid: &str) -> bool {
+        self.deleting.contains(&(table_def.option.full_table_name.clone(), rowid.to_string()))
+    }
+
+    fn mark_deleting(&mut self, table_def: &TableDef, rowids: &[String]) {
+        for rowid in rowids {
+            self.deleting.insert((table_def.option.full_table_name.clone(), rowid.clone()));
+        }
+    }
+
+    /// The rows are updated before the ones of the cascade are deleted, a row set to NULL may be deleted after
+    fn write(self, global_context: Arc<Mutex<GlobalContext>>, session_context: &SessionContext) -> MysqlResult<()> {
+        for (table_def, row_changes) in self.updates {
+            let store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, table_def.clone())?;
+            let table_index_list = meta_util::get_table_index_list(global_context.clone(), table_def.option.full_table_name.clone())?;
+            let mut row_mutation = RowMutation::new(&table_def, table_index_list, store_engine.as_ref());
+            for (rowid, column_values) in row_changes {
+                row_mutation.update_row(rowid.as_str(), column_values)?;
+            }
+            row_mutation.write()?;
+        }
+        for (table_def, rowids) in self.deletes {
+            let store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, table_def.clone())?;
+            let table_index_list = meta_util::get_table_index_list(global_context.clone(), table_def.option.full_table_name.clone())?;
+            let mut row_mutation = RowMutation::new(&table_def, table_index_list, store_engine.as_ref());
+            for rowid in rowids {
+                row_mutation.delete_row(rowid.as_str())?;
+            }
+            row_mutation.write()?;
+        }
+        Ok(())
+    }
+}
+
+/// Check the foreign keys of the rows to insert, each refers to a row of its referenced table found by the index
+/// of the referenced columns. The rows of a table referring to itself may refer to the rows inserted with them
+pub fn check_insert_rows(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    table_def: &TableDef,
+    column_value_map_list: &[HashMap<Ident, ScalarValue>],
+) -> MysqlResult<()> {
+    for foreign_key in table_def.get_foreign_keys() {
+        let parent_table = get_referenced_table(global_context.clone(), table_def, foreign_key)?;
+        let parent_store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, parent_table.clone())?;
+        let parent_index_list = meta_util::get_table_index_list(global_context.clone(), parent_table.option.full_table_name.clone())?;
+
+        let mut inserted_values = vec![];
+        if parent_table.option.full_table_name == table_def.option.full_table_name {
+            for column_value_map in column_value_map_list {
+                inserted_values.push(scalar_values(column_value_map, &foreign_key.referenced_column_name_list)?);
+            }
+        }
+
+        for column_value_map in column_value_map_list {
+            let values = scalar_values(column_value_map, &foreign_key.column_name_list)?;
+            if values.iter().any(Option::is_none) || inserted_values.contains(&values) {
+                continue;
+            }
+            let parent_rowids = find_rowids(
+                parent_store_engine.as_ref(),
+                &parent_table,
+                &parent_index_list,
+                &foreign_key.referenced_column_name_list,
+                &values,
+            )?;
+            if parent_rowids.is_empty() {
+                return Err(no_referenced_row_error(table_def, foreign_key));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check the foreign keys of the rows to update, then cascade the changed referenced columns to the rows
+/// referring to them by the ON UPDATE of their foreign keys. The rows themselves are written by the caller
+pub fn update_rows(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    table_def: &TableDef,
+    row_changes: &[RowChange],
+) -> MysqlResult<()> {
+    check_update_rows(global_context.clone(), session_context, table_def, row_changes)?;
+
+    let mut plan = CascadePlan::default();
+    plan_update(global_context.clone(), session_context, table_def, row_changes, 0, &mut plan)?;
+    plan.write(global_context, session_context)
+}
+
+/// Cascade the rows to delete to the rows referring to them by the ON DELETE of their foreign keys.
+/// The rows themselves are deleted by the caller
+pub fn delete_rows(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    table_def: &TableDef,
+    rowids: &[String],
+) -> MysqlResult<()> {
+    let mut plan = CascadePlan::default();
+    plan.mark_deleting(table_def, rowids);
+    plan_delete(global_context.clone(), session_context, table_def, rowids, 0, &mut plan)?;
+    plan.write(global_context, session_context)
+}
+
+/// Whether the rows of the table are referred to by the rows of another table
+pub fn is_referenced(global_context: Arc<Mutex<GlobalContext>>, full_table_name: &ObjectName) -> bool {
+    referencing_foreign_keys(global_context, full_table_name)
+        .iter()
+        .any(|(child_table, _)| child_table.option.full_table_name != *full_table_name)
+}
+
+/// A table referred to by another table can not be dropped, a table referring to itself can
+pub fn check_drop_table(global_context: Arc<Mutex<GlobalContext>>, full_table_name: &ObjectName) -> MysqlResult<()> {
+    let referencing_foreign_keys = referencing_foreign_keys(global_context, full_table_name);
+    let referencing_foreign_key = referencing_foreign_keys
+        .iter()
+        .find(|(child_table, _)| child_table.option.full_table_name != *full_table_name);
+    match referencing_foreign_key {
+        Some((child_table, foreign_key)) => Err(MysqlError::new_server_error(
+            mysql_error_code::ER_FK_CANNOT_DROP_PARENT as u16,
+            "HY000",
+            format!(
+                "Cannot drop table '{}' referenced by a foreign key constraint '{}' on table '{}'.",
+                meta_util::cut_out_table_name(full_table_name.clone()),
+                foreign_key.constraint_name,
+                child_table.option.table_name,
+            ).as_str(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// A table referred to by another table can not be truncated, its rows are not deleted one by one
+pub fn check_truncate_table(global_context: Arc<Mutex<GlobalContext>>, full_table_name: &ObjectName) -> MysqlResult<()> {
+    let referencing_foreign_keys = referencing_foreign_keys(global_context, full_table_name);
+    let referencing_foreign_key = referencing_foreign_keys
+        .iter()
+        .find(|(child_table, _)| child_table.option.full_table_name != *full_table_name);
+    match referencing_foreign_key {
+        Some((child_table, foreign_key)) => Err(MysqlError::new_server_error(
+            mysql_error_code::ER_TRUNCATE_ILLEGAL_FK as u16,
+            "42000",
+            format!(
+                "Cannot truncate a table referenced in a foreign key constraint ({})",
+                foreign_key_description(child_table, foreign_key),
+            ).as_str(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The index of the referenced columns of a foreign key can not be dropped, unless another index holds them
+pub fn check_drop_index(global_context: Arc<Mutex<GlobalContext>>, table_def: &TableDef, index_name: &str) -> MysqlResult<()> {
+    let table_index_list = meta_util::get_table_index_list(global_context.clone(), table_def.option.full_table_name.clone())?;
+    let holds_columns = |table_index: &TableIndexDef, column_name_list: &[Ident]| {
+        table_index.level != meta_const::INDEX_LEVEL_OF_FOREIGN_KEY
+            && table_index.column_name_list.len() >= column_name_list.len()
+            && column_name_list.iter().zip(table_index.column_name_list.iter()).all(|(column_name, index_column_name)| {
+            column_name.value.to_lowercase() == index_column_name.value.to_lowercase()
+        })
+    };
+    for (_, foreign_key) in referencing_foreign_keys(global_context, &table_def.option.full_table_name) {
+        let column_name_list = &foreign_key.referenced_column_name_list;
+        let dropped_index_holds = table_index_list
+            .iter()
+            .any(|table_index| table_index.index_name.to_lowercase() == index_name.to_lowercase() && holds_columns(table_index, column_name_list));
+        let other_index_holds = table_index_list
+            .iter()
+            .any(|table_index| table_index.index_name.to_lowercase() != index_name.to_lowercase() && holds_columns(table_index, column_name_list));
+        if dropped_index_holds && !other_index_holds {
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_DROP_INDEX_FK as u16,
+                "HY000",
+                format!("Cannot drop index '{}': needed in a foreign key constraint", index_name).as_str(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The new values of the foreign keys of the updated rows refer to rows of their referenced tables,
+/// a foreign key whose values did not change is not checked
+fn check_update_rows(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    table_def: &TableDef,
+    row_changes: &[RowChange],
+) -> MysqlResult<()> {
+    if table_def.get_foreign_keys().is_empty() {
+        return Ok(());
+    }
+    let store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, table_def.clone())?;
+
+    for foreign_key in table_def.get_foreign_keys() {
+        let store_ids = store_ids(table_def, &foreign_key.column_name_list)?;
+        let changed_rows: Vec<&RowChange> = row_changes
+            .iter()
+            .filter(|(_, column_values)| column_values.iter().any(|(store_id, _)| store_ids.contains(store_id)))
+            .collect();
+        if changed_rows.is_empty() {
+            continue;
+        }
+
+        let parent_table = get_referenced_table(global_context.clone(), table_def, foreign_key)?;
+        let parent_store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, parent_table.clone())?;
+        let parent_index_list = meta_util::get_table_index_list(global_context.clone(), parent_table.option.full_table_name.clone())?;
+
+        // the rows of the table referring to itself refer to the new values of the updated rows
+        let mut updated_values: HashMap<String, Vec<Option<String>>> = HashMap::new();
+        if parent_table.option.full_table_name == table_def.option.full_table_name {
+            for (rowid, column_values) in row_changes {
+                let values = changed_values(store_engine.as_ref(), table_def, &foreign_key.referenced_column_name_list, rowid, column_values)?;
+                updated_values.insert(rowid.clone(), values);
+            }
+        }
+
+        for (rowid, column_values) in changed_rows {
+            let old_values = read_values(store_engine.as_ref(), table_def, &foreign_key.column_name_list, rowid)?;
+            let new_values = changed_values(store_engine.as_ref(), table_def, &foreign_key.column_name_list, rowid, column_values)?;
+            if new_values.iter().any(Option::is_none) || old_values == new_values || updated_values.values().any(|values| *values == new_values) {
+                continue;
+            }
+            let parent_rowids = find_rowids(
+                parent_store_engine.as_ref(),
+                &parent_table,
+                &parent_index_list,
+                &foreign_key.referenced_column_name_list,
+                &new_values,
+            )?;
+            if !parent_rowids.iter().any(|parent_rowid| !updated_values.contains_key(parent_rowid)) {
+                return Err(no_referenced_row_error(table_def, foreign_key));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The changes of the rows referring to the updated rows whose referenced columns changed
+fn plan_update(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    table_def: &TableDef,
+    row_changes: &[RowChange],
+    depth: usize,
+    plan: &mut CascadePlan,
+) -> MysqlResult<()> {
+    let referencing_foreign_keys = referencing_foreign_keys(global_context.clone(), &table_def.option.full_table_name);
+    if referencing_foreign_keys.is_empty() {
+        return Ok(());
+    }
+    let store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, table_def.clone())?;
+
+    for (child_table, foreign_key) in referencing_foreign_keys {
+        let referenced_store_ids = store_ids(table_def, &foreign_key.referenced_column_name_list)?;
+        let child_store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, child_table.clone())?;
+        let child_index_list = meta_util::get_table_index_list(global_context.clone(), child_table.option.full_table_name.clone())?;
+
+        // the rows referring to the old values of the referenced columns with their new values
+        let mut child_rows: Vec<(String, Vec<Option<String>>)> = vec![];
+        for (rowid, column_values) in row_changes {
+            if !column_values.iter().any(|(store_id, _)| referenced_store_ids.contains(store_id)) {
+                continue;
+            }
+            let old_values = read_values(store_engine.as_ref(), table_def, &foreign_key.referenced_column_name_list, rowid)?;
+            let new_values = changed_values(store_engine.as_ref(), table_def, &foreign_key.referenced_column_name_list, rowid, column_values)?;
+            if old_values.iter().any(Option::is_none) || old_values == new_values {
+                continue;
+            }
+            let child_rowids = find_rowids(child_store_engine.as_ref(), &child_table, &child_index_list, &foreign_key.column_name_list, &old_values)?;
+            for child_rowid in child_rowids {
+                if !plan.is_deleting(&child_table, child_rowid.as_str()) {
+                    child_rows.push((child_rowid, new_values.clone()));
+                }
+            }
+        }
+        if child_rows.is_empty() {
+            continue;
+        }
+        if depth >= meta_const::MAX_DEPTH_OF_FOREIGN_KEY_CASCADE {
+            return Err(depth_exceeded_error());
+        }
+
+        let child_store_ids = store_ids(&child_table, &foreign_key.column_name_list)?;
+        let child_changes: Vec<RowChange> = match foreign_key.on_update {
+            ReferentialAction::Restrict | ReferentialAction::NoAction => {
+                return Err(row_is_referenced_error(&child_table, &foreign_key));
+            }
+            ReferentialAction::Cascade => child_rows
+                .into_iter()
+                .map(|(rowid, new_values)| {
+                    let column_values = child_store_ids.iter().cloned().zip(new_values.iter().map(stored_value)).collect();
+                    (rowid, column_values)
+                })
+                .collect(),
+            ReferentialAction::SetNull => child_rows
+                .into_iter()
+                .map(|(rowid, _)| (rowid, child_store_ids.iter().map(|store_id| (*store_id, vec![0x00])).collect()))
+                .collect(),
+        };
+        plan_update(global_context.clone(), session_context, &child_table, &child_changes, depth + 1, plan)?;
+        plan.updates.push((child_table, child_changes));
+    }
+    Ok(())
+}
+
+/// The changes of the rows referring to the deleted rows, the rows of the cascade are deleted in their turn
+fn plan_delete(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    table_def: &TableDef,
+    rowids: &[String],
+    depth: usize,
+    plan: &mut CascadePlan,
+) -> MysqlResult<()> {
+    let referencing_foreign_keys = referencing_foreign_keys(global_context.clone(), &table_def.option.full_table_name);
+    if referencing_foreign_keys.is_empty() {
+        return Ok(());
+    }
+    let store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, table_def.clone())?;
+
+    for (child_table, foreign_key) in referencing_foreign_keys {
+        let child_store_engine = StoreEngineFactory::try_new_with_session(global_context.clone(), session_context, child_table.clone())?;
+        let child_index_list = meta_util::get_table_index_list(global_context.clone(), child_table.option.full_table_name.clone())?;
+
+        // the rows referring to the deleted rows, found by the index of the foreign key
+        let mut found_rowids = HashSet::new();
+        let mut child_rowids: Vec<String> = vec![];
+        for rowid in rowids {
+            let values = read_values(store_engine.as_ref(), table_def, &foreign_key.referenced_column_name_list, rowid)?;
+            if values.iter().any(Option::is_none) {
+                continue;
+            }
+            for child_rowid in find_rowids(child_store_engine.as_ref(), &child_table, &child_index_list, &foreign_key.column_name_list, &values)? {
+                if !plan.is_deleting(&child_table, child_rowid.as_str()) && found_rowids.insert(child_rowid.clone()) {
+                    child_rowids.push(child_rowid);
+                }
+            }
+        }
+        if child_rowids.is_empty() {
+            continue;
+        }
+        if depth >= meta_const::MAX_DEPTH_OF_FOREIGN_KEY_CASCADE {
+            return Err(depth_exceeded_error());
+        }
+
+        match foreign_key.on_delete {
+            ReferentialAction::Restrict | ReferentialAction::NoAction => {
+                return Err(row_is_referenced_error(&child_table, &foreign_key));
+            }
+            ReferentialAction::Cascade => {
+                plan.mark_deleting(&child_table, &child_rowids);
+                plan_delete(global_context.clone(), session_context, &child_table, &child_rowids, depth + 1, plan)?;
+                plan.deletes.push((child_table, child_rowids));
+            }
+            ReferentialAction::SetNull => {
+                let child_store_ids = store_ids(&child_table, &foreign_key.column_name_list)?;
+                let child_changes: Vec<RowChange> = child_rowids
+                    .into_iter()
+                    .map(|rowid| (rowid, child_store_ids.iter().map(|store_id| (*store_id, vec![0x00])).collect()))
+                    .collect();
+                plan_update(global_context.clone(), session_context, &child_table, &child_changes, depth + 1, plan)?;
+                plan.updates.push((child_table, child_changes));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The foreign keys of the tables referring to the table, the ones of the table referring to itself too
+fn referencing_foreign_keys(global_context: Arc<Mutex<GlobalContext>>, full_table_name: &ObjectName) -> Vec<(TableDef, ForeignKeyDef)> {
+    let table_map = global_context.lock().unwrap().meta_data.get_table_map();
+    let mut referencing_foreign_keys = vec![];
+    for table_def in table_map.values() {
+        for foreign_key in table_def.get_foreign_keys() {
+            if foreign_key.referenced_table_name == *full_table_name {
+                referencing_foreign_keys.push((table_def.clone(), foreign_key.clone()));
+            }
+        }
+    }
+    // the tables are visited in the same order by every statement
+    referencing_foreign_keys.sort_by_key(|(table_def, foreign_key)| (table_def.option.full_table_name.to_string(), foreign_key.constraint_name.clone()));
+    referencing_foreign_keys
+}
+
+fn get_referenced_table(global_context: Arc<Mutex<GlobalContext>>, table_def: &TableDef, foreign_key: &ForeignKeyDef) -> MysqlResult<TableDef> {
+    match global_context.lock().unwrap().meta_data.get_table(foreign_key.referenced_table_name.clone()) {
+        Some(parent_table) => Ok(parent_table.clone()),
+        None => Err(no_referenced_row_error(table_def, foreign_key)),
+    }
+}
+
+/// The rowids of the rows whose columns have the values, found by the index led by the columns. The values
+/// compare as the keys of the index, none of them is NULL as a row with NULL in its foreign key refers to no row
+fn find_rowids(
+    store_engine: &dyn StoreEngine,
+    table_def: &TableDef,
+    table_index_list: &[TableIndexDef],
+    column_name_list: &[Ident],
+    values: &[Option<String>],
+) -> MysqlResult<Vec<String>> {
+    let table_index = table_index_list.iter().find(|table_index| {
+        table_index.column_name_list.len() >= column_name_list.len()
+            && column_name_list.iter().zip(table_index.column_name_list.iter()).all(|(column_name, index_column_name)| {
+            column_name.value.to_lowercase() == index_column_name.value.to_lowercase()
+        })
+    });
+    let table_index = match table_index {
+        Some(table_index) => table_index,
+        None => {
+            return Err(MysqlError::new_global_error(1105, format!(
+                "Unknown error. The index of the foreign key columns not found, table: {}, columns: {:?}",
+                table_def.option.full_table_name,
+                column_name_list,
+            ).as_str()));
+        }
+    };
+
+    let column_values = index_column_values(table_def, column_name_list, values)?;
+    // the primary index of the table without rowid is stored in the rowid keys, the rowid is the key after the prefix
+    let without_rowid = table_def.option.without_rowid && table_index.level == 1;
+    let prefix = if without_rowid {
+        format!("{}{}", dbkey::scan_record_rowid(&table_def.option), column_values)
+    } else {
+        format!("{}{}/{}", dbkey::scan_table_index_key(&table_def.option), table_index.index_name, column_values)
+    };
+    let rowid_start = prefix.len() - column_values.len();
+
+    let mut rowids = vec![];
+    let mut start_after = None;
+    loop {
+        let keys = store_engine.scan_prefix_keys(prefix.clone(), start_after.clone(), SCAN_BATCH_SIZE)?;
+        for key in keys.iter() {
+            let rowid = if without_rowid {
+                Some(key[rowid_start..].to_string())
+            } else {
+                store_engine.get_key(key.clone())?.map(|value| String::from_utf8_lossy(value.as_slice()).to_string())
+            };
+            if let Some(rowid) = rowid {
+                // the key of a value with a '/' starts with the one of its leading part, the values are compared again
+                let row_values = read_values(store_engine, table_def, column_name_list, rowid.as_str())?;
+                if index_column_values(table_def, column_name_list, &row_values)? == column_values {
+                    rowids.push(rowid);
+                }
+            }
+        }
+        if keys.len() < SCAN_BATCH_SIZE {
+            return Ok(rowids);
+        }
+        start_after = keys.last().cloned();
+    }
+}
+
+/// The values of the columns as the leading columns of an index key
+fn index_column_values(table_def: &TableDef, column_name_list: &[Ident], values: &[Option<String>]) -> MysqlResult<String> {
+    let mut column_values = vec![];
+    for (column_name, value) in column_name_list.iter().zip(values.iter()) {
+        let sparrow_column = table_def.get_table_column().get_sparrow_column(column_name.clone())?;
+        let scalar_value = core_util::stored_value_to_scalar_value(&sparrow_column.sql_column.data_type, value.clone());
+        column_values.push((sparrow_column, scalar_value));
+    }
+    Ok(dbkey::create_index_column_values(&column_values))
+}
+
+/// The stored values of the columns of the row
+fn read_values(store_engine: &dyn StoreEngine, table_def: &TableDef, column_name_list: &[Ident], rowid: &str) -> MysqlResult<Vec<Option<String>>> {
+    let mut values = vec![];
+    for column_name in column_name_list {
+        let sparrow_column = table_def.get_table_column().get_sparrow_column(column_name.clone())?;
+        values.push(core_util::read_column_value_with(table_def, &sparrow_column, rowid, &|key| store_engine.get_key(key))?);
+    }
+    Ok(values)
+}
+
+/// The values of the columns of the row with the new values of the update
+fn changed_values(
+    store_engine: &dyn StoreEngine,
+    table_def: &TableDef,
+    column_name_list: &[Ident],
+    rowid: &str,
+    column_values: &[(i64, Vec<u8>)],
+) -> MysqlResult<Vec<Option<String>>> {
+    let mut values = read_values(store_engine, table_def, column_name_list, rowid)?;
+    for (index, column_name) in column_name_list.iter().enumerate() {
+        let sparrow_column = table_def.get_table_column().get_sparrow_column(column_name.clone())?;
+        if let Some((_, column_value)) = column_values.iter().find(|(store_id, _)| *store_id == sparrow_column.store_id) {
+            values[index] = if column_value.as_slice() == [0x00] {
+                None
+            } else {
+                Some(String::from_utf8_lossy(column_value.as_slice()).to_string())
+            };
+        }
+    }
+    Ok(values)
+}
+
+/// The values of the columns of the row to insert as they are stored
+fn scalar_values(column_value_map: &HashMap<Ident, ScalarValue>, column_name_list: &[Ident]) -> MysqlResult<Vec<Option<String>>> {
+    let mut values = vec![];
+    for column_name in column_name_list {
+        let scalar_value = column_value_map
+            .iter()
+            .find(|(name, _)| name.value.to_lowercase() == column_name.value.to_lowercase())
+            .map(|(_, scalar_value)| scalar_value.clone());
+        let value = match scalar_value {
+            Some(scalar_value) => core_util::convert_scalar_value(scalar_value)?,
+            None => None,
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+fn store_ids(table_def: &TableDef, column_name_list: &[Ident]) -> MysqlResult<Vec<i64>> {
+    let mut store_ids = vec![];
+    for column_name in column_name_list {
+        store_ids.push(table_def.get_table_column().get_sparrow_column(column_name.clone())?.store_id);
+    }
+    Ok(store_ids)
+}
+
+fn stored_value(value: &Option<String>) -> Vec<u8> {
+    match value {
+        None => vec![0x00],
+        Some(value) => value.clone().into_bytes(),
+    }
+}
+
+/// The foreign key as MySQL shows it in its errors,
+/// `` `test`.`child`, CONSTRAINT `child_ibfk_1` FOREIGN KEY (`parent_id`) REFERENCES `parent` (`id`) ``
+fn foreign_key_description(table_def: &TableDef, foreign_key: &ForeignKeyDef) -> String {
+    let column_names: Vec<String> = foreign_key.column_name_list.iter().map(|column_name| format!("`{}`", column_name.value)).collect();
+    let referenced_column_names: Vec<String> = foreign_key
+        .referenced_column_name_list
+        .iter()
+        .map(|column_name| format!("`{}`", column_name.value))
+        .collect();
+    format!(
+        "`{}`.`{}`, CONSTRAINT `{}` FOREIGN KEY ({}) REFERENCES `{}` ({})",
+        table_def.option.schema_name,
+        table_def.option.table_name,
+        foreign_key.constraint_name,
+        column_names.join(", "),
+        meta_util::cut_out_table_name(foreign_key.referenced_table_name.clone()),
+        referenced_column_names.join(", "),
+    )
+}
+
+fn no_referenced_row_error(table_def: &TableDef, foreign_key: &ForeignKeyDef) -> MysqlError {
+    let message = format!(
+        "Cannot add or update a child row: a foreign key constraint fails ({})",
+        foreign_key_description(table_def, foreign_key)
+    );
+    log::error!("{}", message);
+    MysqlError::new_server_error(mysql_error_code::ER_NO_REFERENCED_ROW_2 as u16, "23000", message.as_str())
+}
+
+fn row_is_referenced_error(child_table: &TableDef, foreign_key: &ForeignKeyDef) -> MysqlError {
+    let message = format!(
+        "Cannot delete or update a parent row: a foreign key constraint fails ({})",
+        foreign_key_description(child_table, foreign_key)
+    );
+    log::error!("{}", message);
+    MysqlError::new_server_error(mysql_error_code::ER_ROW_IS_REFERENCED_2 as u16, "23000", message.as_str())
+}
+
+fn depth_exceeded_error() -> MysqlError {
+    MysqlError::new_server_error(
+        mysql_error_code::ER_FK_DEPTH_EXCEEDED as u16,
+        "HY000",
+        format!("Foreign key cascade delete/update exceeds max depth of {}.", meta_const::MAX_DEPTH_OF_FOREIGN_KEY_CASCADE).as_str(),
+    )
+}
@@ -0,0 +1,80 @@
+use std::future::Future;
+use std::io;
+
+use async_trait::async_trait;
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::core::execution::Execution;
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+/// The wire protocol of a connection, MySQL or PostgreSQL. The handle reads the messages of its protocol,
+/// the session, the catalog and the statements are the ones of the Execution of the connection
+#[async_trait]
+pub trait FrontEnd: Send {
+    /// The session of the connection
+    fn core_execution(&mut self) -> &mut Execution;
+
+    /// The start of the connection, until the user is authenticated and the session is started
+    async fn handshake(&mut self) -> MysqlResult<()>;
+
+    /// The commands of the client until it quits, the connection is closed after them
+    async fn exec_command(&mut self);
+
+    /// The error in the message of the protocol
+    async fn write_error(&mut self, mysql_error: MysqlError);
+
+    /// The responses written are flushed before the socket is shut down, and the session is released
+    async fn close(&mut self);
+
+    async fn run(&mut self) {
+        let result = self.handshake().await;
+        if let Err(mysql_error) = result {
+            self.write_error(mysql_error).await;
+            self.close().await;
+            return;
+        }
+        self.exec_command().await;
+    }
+
+    /// The session of the authenticated user, the same for every protocol. The database of the handshake
+    /// is the default schema, an unknown database fails the handshake
+    async fn start_session(
+        &mut self,
+        user: &str,
+        host: &str,
+        connect_attrs: Vec<(String, String)>,
+        database: Option<String>,
+    ) -> MysqlResult<()> {
+        let core_execution = self.core_execution();
+        core_execution.set_process_user(user, host);
+        core_execution.set_connect_attrs(connect_attrs);
+        core_execution.try_init()?;
+        if let Some(database) = database.filter(|database| !database.is_empty()) {
+            core_execution.set_default_schema(database.as_str()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Accept the connections of the listener, every connection runs its handle in its own task
+pub async fn serve<H, F, Fut>(listener: TcpListener, new_handle: F)
+where
+    H: FrontEnd + 'static,
+    F: Fn(TcpStream) -> Fut,
+    Fut: Future<Output = io::Result<H>>,
+{
+    loop {
+        match listener.accept().await {
+            Ok((socket, _)) => match new_handle(socket).await {
+                Ok(mut handle) => {
+                    tokio::spawn(async move {
+                        handle.run().await;
+                        log::info!("client closed");
+                    });
+                }
+                Err(e) => log::error!("error creating the handle; error = {:?}", e),
+            },
+            Err(e) => log::error!("error accepting socket; error = {:?}", e),
+        }
+    }
+}
@@ -1,12 +1,23 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
 use sled::Db as SledDb;
+use sqlparser::ast::{ObjectName, TableConstraint};
 
 use crate::meta::data::MetaData;
 use crate::meta::variable::Variable;
 use crate::config::def::MyConfig;
+use crate::core::plan_rewrite::{OrderGroupBy, PlanRewriteRegistry, TenantFilter};
+use crate::core::process_list::ProcessList;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::store::lock::LockManager;
+use crate::store::mvcc::Mvcc;
 
 #[derive(Clone, Debug)]
 pub struct Engine {
     pub sled_db: Option<SledDb>,
+    /// The commits and the snapshots of the rows of the sled db
+    pub sled_mvcc: Option<Arc<Mvcc>>,
 }
 
 #[derive(Clone, Debug)]
@@ -15,32 +26,76 @@ pub struct GlobalContext {
     pub meta_data: MetaData,
     pub variable: Variable,
     pub engine: Engine,
+    pub plan_rewrite: PlanRewriteRegistry,
+    /// The auto increment values and the table ids are read and saved by one statement at a time
+    pub auto_increment_lock: Arc<Mutex<()>>,
+    /// The indexes being built by CREATE INDEX, the writers already maintain them
+    /// but the scans do not use them until the build is done
+    pub building_index_map: HashMap<ObjectName, Vec<TableConstraint>>,
+    /// The writers hold it for reading, a batch of the index build for writing
+    pub index_build_lock: Arc<RwLock<()>>,
+    /// The SHA256(SHA256(password)) of the users by the last full auth of caching_sha2_password,
+    /// with the authentication_string it was checked against, a new password makes it stale
+    pub sha2_password_cache: HashMap<String, (String, [u8; 32])>,
+    /// The connections of the server, read by information_schema.PROCESSLIST and KILL
+    pub process_list: ProcessList,
+    /// The row locks of the transactions, taken by UPDATE, DELETE and the locking reads
+    pub lock_manager: Arc<LockManager>,
 }
 
 impl GlobalContext {
-    pub fn new_with_config(my_config: MyConfig) -> Self {
+    /// Open the engines of the config, the engine which can not be opened fails the startup
+    pub fn new_with_config(my_config: MyConfig) -> MysqlResult<Self> {
 
         let meta_cache = MetaData::new();
         let variable = Variable::new();
 
         let mut sled_db = None;
+        let mut sled_mvcc = None;
         for engine in &my_config.server.engines {
             if engine.eq("sled") {
-                let config = sled::Config::new().temporary(false).path(my_config.engine.sled.data_path.clone());
-                let db = config.open().unwrap();
+                let data_path = my_config.engine.sled.data_path.clone();
+                let config = sled::Config::new().temporary(false).path(data_path.clone());
+                let db = match config.open() {
+                    Ok(db) => db,
+                    Err(error) => {
+                        // the directory is not writable, or the db is locked by another process
+                        return Err(MysqlError::new_server_error(
+                            1016,
+                            "HY000",
+                            format!("Can't open the sled db at '{}': {}", data_path, error).as_str(),
+                        ));
+                    }
+                };
+                sled_mvcc = Some(Arc::new(Mvcc::open(db.clone())?));
                 sled_db = Some(db);
             }
         }
         let engine = Engine {
             sled_db,
+            sled_mvcc,
         };
 
-        let global_context = Self {
+        let mut plan_rewrite = PlanRewriteRegistry::new();
+        if let Some(tenant_column) = my_config.server.tenant_column.clone() {
+            plan_rewrite.register(Arc::new(TenantFilter::new(tenant_column.as_str())));
+        }
+        if my_config.server.order_group_by {
+            plan_rewrite.register(Arc::new(OrderGroupBy::new()));
+        }
+
+        Ok(Self {
             my_config,
             meta_data: meta_cache,
             variable,
             engine,
-        };
-        global_context
+            plan_rewrite,
+            auto_increment_lock: Arc::new(Mutex::new(())),
+            building_index_map: HashMap::new(),
+            index_build_lock: Arc::new(RwLock::new(())),
+            sha2_password_cache: HashMap::new(),
+            process_list: ProcessList::new(),
+            lock_manager: Arc::new(LockManager::new()),
+        })
     }
 }
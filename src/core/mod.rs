@@ -1,9 +1,15 @@
 pub mod core_def;
 pub mod core_util;
 pub mod execution;
+pub mod foreign_key;
+pub mod front_end;
 pub mod global_context;
 pub mod logical_plan;
 pub mod output;
+pub mod plan_rewrite;
+pub mod privilege;
+pub mod process_list;
+pub mod profile;
 pub mod session_context;
 pub mod stmt_context;
 pub mod udf;
@@ -1,14 +1,18 @@
 use std::error;
 use std::fmt::{Display, Formatter};
+use std::pin::Pin;
 use std::result;
 
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use datafusion::error::DataFusionError;
+use datafusion::scalar::ScalarValue;
+use futures::{stream, Stream, TryStreamExt};
 use sqlparser::ast::ObjectName;
 
-use crate::meta::meta_def::TableDef;
-use crate::mysql::error::MysqlError;
+use crate::meta::meta_def::SparrowColumnDef;
+use crate::util::load_data_statement::LoadDataStatement;
+use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::mysql::metadata::Column;
 
 pub struct StmtPrepare {
@@ -31,10 +35,21 @@ impl StmtPrepare {
     }
 }
 
+/// A change of the session state that is reported to the client in the OK packet,
+/// only sent when the client negotiated CLIENT_SESSION_TRACK.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionStateChange {
+    SystemVariable(String, String),
+    Schema(String),
+}
+
 pub struct FinalCount {
     pub affect_rows: u64,
     pub last_insert_id: u64,
     pub message: String,
+    /// The warnings of the statement, set when the statement is done
+    pub warning_count: u64,
+    pub session_state_changes: Vec<SessionStateChange>,
 }
 
 impl FinalCount {
@@ -47,13 +62,21 @@ impl FinalCount {
             affect_rows,
             last_insert_id,
             message: message.to_string(),
+            warning_count: 0,
+            session_state_changes: vec![],
         }
     }
+
+    pub fn with_session_state_change(&mut self, session_state_change: SessionStateChange) {
+        self.session_state_changes.push(session_state_change);
+    }
 }
 
 pub struct ResultSet {
     pub schema_ref: SchemaRef,
     pub record_batches: Vec<RecordBatch>,
+    /// The warnings of the statement, sent in the EOF after the rows
+    pub warning_count: u64,
 }
 
 impl ResultSet {
@@ -61,17 +84,99 @@ impl ResultSet {
         Self {
             schema_ref,
             record_batches,
+            warning_count: 0,
+        }
+    }
+}
+
+/// The batches of a query as they are produced, the stream ends after its first error
+pub type RecordBatchStream = Pin<Box<dyn Stream<Item = MysqlResult<RecordBatch>> + Send>>;
+
+/// The result set of the SELECT of the client, the rows are sent while the query produces them
+/// instead of being collected first
+pub struct ResultStream {
+    pub schema_ref: SchemaRef,
+    pub stream: RecordBatchStream,
+    /// The warnings of the statement raised before its rows
+    pub warning_count: u64,
+}
+
+impl ResultStream {
+    pub fn new(schema_ref: SchemaRef, stream: RecordBatchStream) -> Self {
+        Self {
+            schema_ref,
+            stream,
+            warning_count: 0,
+        }
+    }
+
+    /// All the rows of the stream, for the outputs which are not sent as soon as they are returned
+    pub async fn collect(self) -> MysqlResult<ResultSet> {
+        let record_batches = self.stream.try_collect::<Vec<_>>().await?;
+        Ok(ResultSet {
+            schema_ref: self.schema_ref,
+            record_batches,
+            warning_count: self.warning_count,
+        })
+    }
+}
+
+/// The collected rows are sent as the streamed ones
+impl From<ResultSet> for ResultStream {
+    fn from(result_set: ResultSet) -> Self {
+        let record_batches = result_set.record_batches.into_iter().map(Ok);
+        Self {
+            schema_ref: result_set.schema_ref,
+            stream: Box::pin(stream::iter(record_batches)),
+            warning_count: result_set.warning_count,
         }
     }
 }
 
+/// The rows of COM_STMT_FETCH, the last rows of the cursor close it.
+/// The schema of the cursor gives the types of the binary values
+pub struct StmtFetch {
+    pub schema_ref: SchemaRef,
+    pub rows: Vec<Vec<ScalarValue>>,
+    pub last_row_sent: bool,
+}
+
+impl StmtFetch {
+    pub fn new(schema_ref: SchemaRef, rows: Vec<Vec<ScalarValue>>, last_row_sent: bool) -> Self {
+        Self { schema_ref, rows, last_row_sent }
+    }
+}
+
 pub enum CoreOutput {
     FinalCount(FinalCount),
     ResultSet(ResultSet),
-    MultiResultSet(Vec<Vec<RecordBatch>>),
-    ComFieldList(ObjectName, ObjectName, TableDef),
+    ResultStream(ResultStream),
+    /// The outputs of the statements of a CLIENT_MULTI_STATEMENTS query in order,
+    /// an error is the last output, the statements after it are not executed
+    MultiStatement(Vec<MysqlResult<CoreOutput>>),
+    /// The columns of the table which match the wildcard of COM_FIELD_LIST
+    ComFieldList(ObjectName, ObjectName, Vec<SparrowColumnDef>),
     ComStmtPrepare(StmtPrepare),
+    /// COM_STMT_EXECUTE opened a read only cursor, only the columns are sent
+    ComStmtCursor(SchemaRef),
+    ComStmtFetch(StmtFetch),
     ComStmtClose,
+    ComStmtSendLongData,
+    /// LOAD DATA LOCAL, the handle requests the file from the client
+    LoadDataLocalInfile(LoadDataStatement),
+}
+
+impl CoreOutput {
+    /// The warnings of the statement for the OK packet or the EOF of the rows
+    pub fn with_warning_count(mut self, warning_count: u64) -> Self {
+        match &mut self {
+            CoreOutput::FinalCount(final_count) => final_count.warning_count = warning_count,
+            CoreOutput::ResultSet(result_set) => result_set.warning_count = warning_count,
+            CoreOutput::ResultStream(result_stream) => result_stream.warning_count = warning_count,
+            _ => {}
+        }
+        self
+    }
 }
 
 pub type Result<T> = result::Result<T, OutputError>;
@@ -0,0 +1,690 @@
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::logical_plan::{col, lit, Column, Expr, LogicalPlan, LogicalPlanBuilder};
+use datafusion::optimizer::utils;
+use datafusion::scalar::ScalarValue;
+use sqlparser::ast::{DataType as SQLDataType, Query, SetExpr};
+
+use crate::core::core_util;
+use crate::core::session_context::SessionContext;
+use crate::datafusion_impl::datasource::sled::SledTable;
+use crate::meta::{meta_const, meta_util};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::util::{collation, date_function};
+
+/// What a plan rewrite knows about the statement being planned
+pub struct PlanRewriteContext<'a> {
+    pub session_context: &'a SessionContext,
+    pub query: &'a Query,
+}
+
+/// A logical plan rewrite, run after the DataFusion optimization and before execution
+pub trait PlanRewrite: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn rewrite(&self, logical_plan: &LogicalPlan, context: &PlanRewriteContext) -> MysqlResult<LogicalPlan>;
+}
+
+/// The plan rewrites registered on the global context, run in the order of registration
+#[derive(Clone)]
+pub struct PlanRewriteRegistry {
+    plan_rewrite_list: Vec<Arc<dyn PlanRewrite>>,
+}
+
+impl PlanRewriteRegistry {
+    pub fn new() -> Self {
+        Self {
+            plan_rewrite_list: vec![
+                Arc::new(RemoveRowidFromProjection::new()),
+                Arc::new(TimestampToSessionTimeZone::new()),
+                Arc::new(DatetimeLiteral::new()),
+            ],
+        }
+    }
+
+    pub fn register(&mut self, plan_rewrite: Arc<dyn PlanRewrite>) {
+        self.plan_rewrite_list.push(plan_rewrite);
+    }
+
+    pub fn rewrite(&self, logical_plan: &LogicalPlan, context: &PlanRewriteContext) -> MysqlResult<LogicalPlan> {
+        let mut logical_plan = logical_plan.clone();
+        for plan_rewrite in &self.plan_rewrite_list {
+            logical_plan = plan_rewrite.rewrite(&logical_plan, context)?;
+            log::debug!("logical plan after rewrite {}: {:?}", plan_rewrite.name(), logical_plan);
+        }
+        Ok(logical_plan)
+    }
+}
+
+impl fmt::Debug for PlanRewriteRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.plan_rewrite_list.iter().map(|plan_rewrite| plan_rewrite.name()).collect();
+        f.debug_struct("PlanRewriteRegistry")
+            .field("plan_rewrite_list", &names)
+            .finish()
+    }
+}
+
+/// The rowid is a hidden column, `select *` must not return it unless it is selected by name.
+/// The rowid selected as `_rowid` is an alias, it is kept
+pub struct RemoveRowidFromProjection {}
+
+impl RemoveRowidFromProjection {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PlanRewrite for RemoveRowidFromProjection {
+    fn name(&self) -> &str {
+        "remove_rowid_from_projection"
+    }
+
+    fn rewrite(&self, logical_plan: &LogicalPlan, context: &PlanRewriteContext) -> MysqlResult<LogicalPlan> {
+        let has_rowid = match context.query.body {
+            SetExpr::Select(ref select) => core_util::projection_has_rowid(select.projection.clone()),
+            _ => false,
+        };
+        if has_rowid {
+            return Ok(logical_plan.clone());
+        }
+
+        remove_rowid_from_projection(logical_plan)
+    }
+}
+
+fn is_rowid_column(expr: &Expr) -> bool {
+    match expr {
+        Expr::Column(column) => column.name == meta_const::COLUMN_ROWID,
+        Expr::Sort { expr, .. } => is_rowid_column(expr),
+        _ => false,
+    }
+}
+
+fn remove_rowid_from_projection(logical_plan: &LogicalPlan) -> MysqlResult<LogicalPlan> {
+    match logical_plan {
+        LogicalPlan::Projection { expr, input, .. } => {
+            let new_expr: Vec<Expr> = expr.iter().filter(|expr| !is_rowid_column(expr)).cloned().collect();
+            if new_expr.len() == expr.len() || new_expr.is_empty() {
+                return Ok(logical_plan.clone());
+            }
+
+            LogicalPlanBuilder::from(input.as_ref().clone())
+                .project(new_expr)
+                .and_then(|builder| builder.build())
+                .map_err(MysqlError::from)
+        }
+        // the output projection is below the order by and the limit
+        LogicalPlan::Sort { .. } | LogicalPlan::Limit { .. } => {
+            let expr = logical_plan.expressions();
+            // ordered by the rowid, the projection must keep it
+            if expr.iter().any(is_rowid_column) {
+                return Ok(logical_plan.clone());
+            }
+
+            let new_inputs = logical_plan
+                .inputs()
+                .iter()
+                .map(|input| remove_rowid_from_projection(input))
+                .collect::<MysqlResult<Vec<_>>>()?;
+            utils::from_plan(logical_plan, &expr, &new_inputs).map_err(MysqlError::from)
+        }
+        _ => Ok(logical_plan.clone()),
+    }
+}
+
+/// The TIMESTAMP values are stored in UTC, the TIMESTAMP columns in the output of the query
+/// are converted to the time zone of the session. The filters and the order compare the values in UTC
+pub struct TimestampToSessionTimeZone {}
+
+impl TimestampToSessionTimeZone {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PlanRewrite for TimestampToSessionTimeZone {
+    fn name(&self) -> &str {
+        "timestamp_to_session_time_zone"
+    }
+
+    fn rewrite(&self, logical_plan: &LogicalPlan, context: &PlanRewriteContext) -> MysqlResult<LogicalPlan> {
+        let mut timestamp_column_list = vec![];
+        collect_timestamp_columns(logical_plan, &mut timestamp_column_list);
+        if timestamp_column_list.is_empty() {
+            return Ok(logical_plan.clone());
+        }
+
+        let output_index_list = match output_projection(logical_plan) {
+            Some(LogicalPlan::Projection { expr, .. }) => expr
+                .iter()
+                .enumerate()
+                .filter(|(_, expr)| is_timestamp_column(expr, &timestamp_column_list))
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        if output_index_list.is_empty() {
+            return Ok(logical_plan.clone());
+        }
+
+        let time_zone = context
+            .session_context
+            .system_variables
+            .lock()
+            .unwrap()
+            .get(meta_const::SYSTEM_VARIABLE_TIME_ZONE)
+            .cloned()
+            .unwrap_or_else(|| "SYSTEM".to_string());
+        let convert_tz = Arc::new(core_util::create_convert_tz_udf());
+        let expr = logical_plan
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let column = Expr::Column(field.qualified_column());
+                if !output_index_list.contains(&index) {
+                    return column;
+                }
+                Expr::ScalarUDF {
+                    fun: convert_tz.clone(),
+                    args: vec![column, lit("+00:00"), lit(time_zone.as_str())],
+                }
+                .alias(field.name())
+            })
+            .collect::<Vec<_>>();
+
+        LogicalPlanBuilder::from(logical_plan.clone())
+            .project(expr)
+            .and_then(|builder| builder.build())
+            .map_err(MysqlError::from)
+    }
+}
+
+/// The TIMESTAMP columns of the tables scanned by the plan
+fn collect_timestamp_columns(logical_plan: &LogicalPlan, timestamp_column_list: &mut Vec<Column>) {
+    if let LogicalPlan::TableScan { table_name, source, .. } = logical_plan {
+        if let Some(sled_table) = source.as_any().downcast_ref::<SledTable>() {
+            for sparrow_column in sled_table.table_def().get_columns() {
+                if sparrow_column.sql_column.data_type == SQLDataType::Timestamp {
+                    timestamp_column_list.push(Column {
+                        relation: Some(table_name.clone()),
+                        name: sparrow_column.sql_column.name.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    for input in logical_plan.inputs() {
+        collect_timestamp_columns(input, timestamp_column_list);
+    }
+}
+
+/// The projection of the output, it is below the order by and the limit
+fn output_projection(logical_plan: &LogicalPlan) -> Option<&LogicalPlan> {
+    match logical_plan {
+        LogicalPlan::Projection { .. } => Some(logical_plan),
+        LogicalPlan::Sort { input, .. } | LogicalPlan::Limit { input, .. } => output_projection(input),
+        _ => None,
+    }
+}
+
+fn is_timestamp_column(expr: &Expr, timestamp_column_list: &[Column]) -> bool {
+    match expr {
+        Expr::Column(column) => timestamp_column_list.contains(column),
+        Expr::Alias(expr, _) => is_timestamp_column(expr, timestamp_column_list),
+        _ => false,
+    }
+}
+
+/// The text compared with a DATETIME or a TIMESTAMP column is the timestamp of the text as mysql reads it,
+/// the timestamps of arrow are not compared with the text. The TIMESTAMP is compared in UTC as it is stored
+pub struct DatetimeLiteral {}
+
+impl DatetimeLiteral {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PlanRewrite for DatetimeLiteral {
+    fn name(&self) -> &str {
+        "datetime_literal"
+    }
+
+    fn rewrite(&self, logical_plan: &LogicalPlan, _context: &PlanRewriteContext) -> MysqlResult<LogicalPlan> {
+        let mut datetime_column_list = vec![];
+        collect_datetime_columns(logical_plan, &mut datetime_column_list);
+        if datetime_column_list.is_empty() {
+            return Ok(logical_plan.clone());
+        }
+
+        rewrite_datetime_literal(logical_plan, &datetime_column_list)
+    }
+}
+
+/// The DATETIME and TIMESTAMP columns of the tables scanned by the plan
+fn collect_datetime_columns(logical_plan: &LogicalPlan, datetime_column_list: &mut Vec<Column>) {
+    if let LogicalPlan::TableScan { table_name, source, .. } = logical_plan {
+        if let Some(sled_table) = source.as_any().downcast_ref::<SledTable>() {
+            for sparrow_column in sled_table.table_def().get_columns() {
+                let data_type = &sparrow_column.sql_column.data_type;
+                if *data_type == SQLDataType::Timestamp || meta_util::is_datetime_data_type(data_type) {
+                    datetime_column_list.push(Column {
+                        relation: Some(table_name.clone()),
+                        name: sparrow_column.sql_column.name.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    for input in logical_plan.inputs() {
+        collect_datetime_columns(input, datetime_column_list);
+    }
+}
+
+/// The filters of the plan and the ones pushed down to the scans compare the DATETIME and TIMESTAMP columns with the timestamps
+fn rewrite_datetime_literal(logical_plan: &LogicalPlan, datetime_column_list: &[Column]) -> MysqlResult<LogicalPlan> {
+    match logical_plan {
+        LogicalPlan::TableScan { table_name, source, projection, projected_schema, filters, limit } => Ok(LogicalPlan::TableScan {
+            table_name: table_name.clone(),
+            source: source.clone(),
+            projection: projection.clone(),
+            projected_schema: projected_schema.clone(),
+            filters: filters
+                .iter()
+                .map(|expr| datetime_literal_expr(expr.clone(), datetime_column_list))
+                .collect(),
+            limit: *limit,
+        }),
+        _ => {
+            let expr = match logical_plan {
+                LogicalPlan::Filter { predicate, .. } => vec![datetime_literal_expr(predicate.clone(), datetime_column_list)],
+                _ => logical_plan.expressions(),
+            };
+            let new_inputs = logical_plan
+                .inputs()
+                .iter()
+                .map(|input| rewrite_datetime_literal(input, datetime_column_list))
+                .collect::<MysqlResult<Vec<_>>>()?;
+            utils::from_plan(logical_plan, &expr, &new_inputs).map_err(MysqlError::from)
+        }
+    }
+}
+
+fn is_datetime_column(expr: &Expr, datetime_column_list: &[Column]) -> bool {
+    match expr {
+        Expr::Column(column) => datetime_column_list
+            .iter()
+            .any(|datetime_column| datetime_column.name == column.name && (column.relation.is_none() || column.relation == datetime_column.relation)),
+        _ => false,
+    }
+}
+
+/// The text literals compared with the DATETIME and TIMESTAMP columns in the expression are the timestamps
+fn datetime_literal_expr(expr: Expr, datetime_column_list: &[Column]) -> Expr {
+    match expr {
+        Expr::BinaryExpr { left, op, right } => {
+            let mut left = datetime_literal_expr(*left, datetime_column_list);
+            let mut right = datetime_literal_expr(*right, datetime_column_list);
+            if is_datetime_column(&left, datetime_column_list) {
+                right = timestamp_literal(right);
+            } else if is_datetime_column(&right, datetime_column_list) {
+                left = timestamp_literal(left);
+            }
+            Expr::BinaryExpr {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            }
+        }
+        Expr::Between { expr, negated, low, high } if is_datetime_column(&expr, datetime_column_list) => Expr::Between {
+            expr,
+            negated,
+            low: Box::new(timestamp_literal(*low)),
+            high: Box::new(timestamp_literal(*high)),
+        },
+        Expr::InList { expr, list, negated } if is_datetime_column(&expr, datetime_column_list) => Expr::InList {
+            expr,
+            list: list.into_iter().map(timestamp_literal).collect(),
+            negated,
+        },
+        Expr::Not(expr) => Expr::Not(Box::new(datetime_literal_expr(*expr, datetime_column_list))),
+        expr => expr,
+    }
+}
+
+/// The text of a date or a datetime is the timestamp, the other expressions are kept
+fn timestamp_literal(expr: Expr) -> Expr {
+    let datetime = match &expr {
+        Expr::Literal(ScalarValue::Utf8(Some(text))) => date_function::parse_datetime(text),
+        _ => None,
+    };
+    match datetime {
+        Some((datetime, _)) => Expr::Literal(date_function::timestamp_scalar_value(datetime)),
+        None => expr,
+    }
+}
+
+/// The display width of each output column of the plan which is a ZEROFILL column of a table,
+/// selected as it is or with an alias. None for the other output columns
+pub fn zerofill_output_widths(logical_plan: &LogicalPlan) -> Vec<Option<u64>> {
+    let mut zerofill_column_list = vec![];
+    collect_zerofill_columns(logical_plan, &mut zerofill_column_list);
+    if zerofill_column_list.is_empty() {
+        return vec![None; logical_plan.schema().fields().len()];
+    }
+
+    let output_expr = match output_projection(logical_plan) {
+        Some(LogicalPlan::Projection { expr, .. }) => expr.clone(),
+        _ => vec![],
+    };
+    logical_plan
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let column = match output_expr.get(index) {
+                Some(Expr::Alias(expr, _)) => match expr.as_ref() {
+                    Expr::Column(column) => column.clone(),
+                    _ => field.qualified_column(),
+                },
+                _ => field.qualified_column(),
+            };
+            zerofill_column_list
+                .iter()
+                .find(|(zerofill_column, _)| *zerofill_column == column)
+                .map(|(_, width)| *width)
+        })
+        .collect()
+}
+
+/// The ZEROFILL columns of the tables scanned by the plan, with their display width
+fn collect_zerofill_columns(logical_plan: &LogicalPlan, zerofill_column_list: &mut Vec<(Column, u64)>) {
+    if let LogicalPlan::TableScan { table_name, source, .. } = logical_plan {
+        if let Some(sled_table) = source.as_any().downcast_ref::<SledTable>() {
+            for sparrow_column in sled_table.table_def().get_columns() {
+                if let Some(width) = meta_util::get_zerofill_width(&sparrow_column.sql_column) {
+                    let column = Column {
+                        relation: Some(table_name.clone()),
+                        name: sparrow_column.sql_column.name.to_string(),
+                    };
+                    zerofill_column_list.push((column, width));
+                }
+            }
+        }
+    }
+    for input in logical_plan.inputs() {
+        collect_zerofill_columns(input, zerofill_column_list);
+    }
+}
+
+/// The data type of each output column of the plan which is a DATE, TIME, DATETIME or TIMESTAMP column of a table,
+/// selected as it is, with an alias or converted to the session time zone. None for the other output columns
+pub fn temporal_output_types(logical_plan: &LogicalPlan) -> Vec<Option<String>> {
+    let mut temporal_column_list = vec![];
+    collect_temporal_columns(logical_plan, &mut temporal_column_list);
+    if temporal_column_list.is_empty() {
+        return vec![None; logical_plan.schema().fields().len()];
+    }
+
+    logical_plan
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let column = output_source_column(logical_plan, index).unwrap_or_else(|| field.qualified_column());
+            temporal_column_list
+                .iter()
+                .find(|(temporal_column, _)| *temporal_column == column)
+                .map(|(_, data_type)| data_type.clone())
+        })
+        .collect()
+}
+
+/// The column selected by the output column of the plan, through the aliases and the projection
+/// which converts the TIMESTAMP columns to the session time zone
+fn output_source_column(logical_plan: &LogicalPlan, index: usize) -> Option<Column> {
+    let (expr, input) = match output_projection(logical_plan) {
+        Some(LogicalPlan::Projection { expr, input, .. }) => (expr.get(index)?, input),
+        _ => return None,
+    };
+    let expr = match expr {
+        Expr::Alias(expr, _) => expr.as_ref(),
+        expr => expr,
+    };
+    match expr {
+        Expr::Column(column) => Some(column.clone()),
+        Expr::ScalarUDF { args, .. } => match args.first() {
+            Some(Expr::Column(column)) => {
+                let input_index = input.schema().fields().iter().position(|field| field.qualified_column() == *column)?;
+                output_source_column(input, input_index).or_else(|| Some(column.clone()))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The DATE, TIME, DATETIME and TIMESTAMP columns of the tables scanned by the plan, with their data type
+fn collect_temporal_columns(logical_plan: &LogicalPlan, temporal_column_list: &mut Vec<(Column, String)>) {
+    if let LogicalPlan::TableScan { table_name, source, .. } = logical_plan {
+        if let Some(sled_table) = source.as_any().downcast_ref::<SledTable>() {
+            for sparrow_column in sled_table.table_def().get_columns() {
+                let data_type = &sparrow_column.sql_column.data_type;
+                if !meta_util::is_temporal_data_type(data_type) {
+                    continue;
+                }
+                if let Ok(data_type) = meta_util::convert_sql_data_type(data_type) {
+                    let column = Column {
+                        relation: Some(table_name.clone()),
+                        name: sparrow_column.sql_column.name.to_string(),
+                    };
+                    temporal_column_list.push((column, data_type));
+                }
+            }
+        }
+    }
+    for input in logical_plan.inputs() {
+        collect_temporal_columns(input, temporal_column_list);
+    }
+}
+
+/// The collation of each output column of the plan which is a text column of a table with a collation
+/// other than the default one, selected as it is or with an alias. None for the other output columns
+pub fn collation_output_names(logical_plan: &LogicalPlan) -> Vec<Option<String>> {
+    let mut collation_column_list = vec![];
+    collect_collation_columns(logical_plan, &mut collation_column_list);
+    if collation_column_list.is_empty() {
+        return vec![None; logical_plan.schema().fields().len()];
+    }
+
+    logical_plan
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let column = output_source_column(logical_plan, index).unwrap_or_else(|| field.qualified_column());
+            collation_column_list
+                .iter()
+                .find(|(collation_column, _)| *collation_column == column)
+                .map(|(_, collation_name)| collation_name.clone())
+        })
+        .collect()
+}
+
+/// The text columns of the tables scanned by the plan which do not have the default collation, with their collation
+fn collect_collation_columns(logical_plan: &LogicalPlan, collation_column_list: &mut Vec<(Column, String)>) {
+    if let LogicalPlan::TableScan { table_name, source, .. } = logical_plan {
+        if let Some(sled_table) = source.as_any().downcast_ref::<SledTable>() {
+            for sparrow_column in sled_table.table_def().get_columns() {
+                let collation = match meta_util::get_column_collation(&sparrow_column.sql_column) {
+                    Some(collation) if collation.name != collation::DEFAULT_COLLATION => collation,
+                    _ => continue,
+                };
+                let column = Column {
+                    relation: Some(table_name.clone()),
+                    name: sparrow_column.sql_column.name.to_string(),
+                };
+                collation_column_list.push((column, collation.name.to_string()));
+            }
+        }
+    }
+    for input in logical_plan.inputs() {
+        collect_collation_columns(input, collation_column_list);
+    }
+}
+
+/// Every scan of a table with the tenant column only reads the rows of the session tenant,
+/// the client session without a tenant can not scan these tables
+pub struct TenantFilter {
+    column_name: String,
+}
+
+impl TenantFilter {
+    pub fn new(column_name: &str) -> Self {
+        Self {
+            column_name: column_name.to_string(),
+        }
+    }
+
+    fn rewrite_table_scan(&self, logical_plan: &LogicalPlan, tenant_id: Option<&str>) -> MysqlResult<LogicalPlan> {
+        match logical_plan {
+            LogicalPlan::TableScan { table_name, source, projection, projected_schema, filters, .. } => {
+                let schema = source.schema();
+                let column_index = match schema.index_of(self.column_name.as_str()) {
+                    Ok(column_index) => column_index,
+                    Err(_) => return Ok(logical_plan.clone()),
+                };
+                let tenant_id = match tenant_id {
+                    Some(tenant_id) => tenant_id,
+                    None => {
+                        let table_name = table_name.rsplit('.').next().unwrap_or(table_name.as_str());
+                        return Err(core_util::error_of_no_tenant(table_name));
+                    }
+                };
+                let tenant_id = core_util::convert_tenant_id_to_scalar_value(
+                    schema.field(column_index).data_type(),
+                    self.column_name.as_str(),
+                    tenant_id,
+                )?;
+                let predicate = col(self.column_name.as_str()).eq(Expr::Literal(tenant_id));
+
+                // the tenant column may be pruned from the scan, it is read for the filter
+                let mut new_projection = projection.clone().unwrap_or_else(|| (0..schema.fields().len()).collect());
+                if !new_projection.contains(&column_index) {
+                    new_projection.push(column_index);
+                }
+                let scan = LogicalPlanBuilder::scan(table_name.as_str(), source.clone(), Some(new_projection))
+                    .and_then(|builder| builder.build())
+                    .map_err(MysqlError::from)?;
+                // the limit of the scan is dropped, it is applied before the tenant filter
+                let scan = match scan {
+                    LogicalPlan::TableScan { table_name, source, projection, projected_schema, .. } => {
+                        let mut filters = filters.clone();
+                        filters.push(predicate.clone());
+                        LogicalPlan::TableScan {
+                            table_name,
+                            source,
+                            projection,
+                            projected_schema,
+                            filters,
+                            limit: None,
+                        }
+                    }
+                    scan => scan,
+                };
+
+                let mut builder = LogicalPlanBuilder::from(scan).filter(predicate).map_err(MysqlError::from)?;
+                if builder.schema().fields().len() != projected_schema.fields().len() {
+                    let expr = projected_schema
+                        .fields()
+                        .iter()
+                        .map(|field| Expr::Column(field.qualified_column()))
+                        .collect::<Vec<_>>();
+                    builder = builder.project(expr).map_err(MysqlError::from)?;
+                }
+                builder.build().map_err(MysqlError::from)
+            }
+            _ => {
+                let expr = logical_plan.expressions();
+                let new_inputs = logical_plan
+                    .inputs()
+                    .iter()
+                    .map(|input| self.rewrite_table_scan(input, tenant_id))
+                    .collect::<MysqlResult<Vec<_>>>()?;
+                utils::from_plan(logical_plan, &expr, &new_inputs).map_err(MysqlError::from)
+            }
+        }
+    }
+}
+
+impl PlanRewrite for TenantFilter {
+    fn name(&self) -> &str {
+        "tenant_filter"
+    }
+
+    fn rewrite(&self, logical_plan: &LogicalPlan, context: &PlanRewriteContext) -> MysqlResult<LogicalPlan> {
+        let tenant_id = context.session_context.tenant_id.lock().unwrap().clone();
+        match tenant_id {
+            // the sessions opened by the server itself are not isolated
+            None if context.session_context.current_user.lock().unwrap().is_none() => Ok(logical_plan.clone()),
+            tenant_id => self.rewrite_table_scan(logical_plan, tenant_id.as_deref()),
+        }
+    }
+}
+
+/// The GROUP BY results are ordered by the grouping columns unless the query has an ORDER BY
+pub struct OrderGroupBy {}
+
+impl OrderGroupBy {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PlanRewrite for OrderGroupBy {
+    fn name(&self) -> &str {
+        "order_group_by"
+    }
+
+    fn rewrite(&self, logical_plan: &LogicalPlan, _context: &PlanRewriteContext) -> MysqlResult<LogicalPlan> {
+        order_group_by(logical_plan)
+    }
+}
+
+fn order_group_by(logical_plan: &LogicalPlan) -> MysqlResult<LogicalPlan> {
+    match logical_plan {
+        LogicalPlan::Aggregate { group_expr, schema, .. } => {
+            if group_expr.is_empty() {
+                return Ok(logical_plan.clone());
+            }
+
+            // the grouping columns are the first columns of the aggregate
+            let sort_expr = (0..group_expr.len())
+                .map(|i| Expr::Column(schema.field(i).qualified_column()).sort(true, true))
+                .collect::<Vec<_>>();
+            LogicalPlanBuilder::from(logical_plan.clone())
+                .sort(sort_expr)
+                .and_then(|builder| builder.build())
+                .map_err(MysqlError::from)
+        }
+        // the order is kept by the plans above the aggregate
+        LogicalPlan::Projection { .. } | LogicalPlan::Filter { .. } | LogicalPlan::Limit { .. } => {
+            let expr = logical_plan.expressions();
+            let new_inputs = logical_plan
+                .inputs()
+                .iter()
+                .map(|input| order_group_by(input))
+                .collect::<MysqlResult<Vec<_>>>()?;
+            utils::from_plan(logical_plan, &expr, &new_inputs).map_err(MysqlError::from)
+        }
+        // the order by of the query is kept
+        _ => Ok(logical_plan.clone()),
+    }
+}
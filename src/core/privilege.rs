@@ -0,0 +1,393 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use datafusion::logical_plan::LogicalPlan;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
+use crate::datafusion_impl::datasource::sled::SledTable;
+use crate::datafusion_impl::datasource::view::ViewTable;
+use crate::meta::def::mysql;
+use crate::meta::{initial, meta_const};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::util::like;
+
+/// A row of mysql.user or mysql.db, the values by the column names with the rowid
+pub type GrantRow = HashMap<String, Option<String>>;
+
+/// The privileges kept by the *_priv columns of mysql.user, the schema privileges by the ones of mysql.db too
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Create,
+    Drop,
+    Reload,
+    Shutdown,
+    Process,
+    File,
+    Grant,
+    References,
+    Index,
+    Alter,
+    ShowDb,
+    Super,
+    CreateUser,
+}
+
+/// The privileges in the order of SHOW GRANTS, GRANT OPTION is shown as WITH GRANT OPTION
+pub const PRIVILEGES: &[Privilege] = &[
+    Privilege::Select,
+    Privilege::Insert,
+    Privilege::Update,
+    Privilege::Delete,
+    Privilege::Create,
+    Privilege::Drop,
+    Privilege::Reload,
+    Privilege::Shutdown,
+    Privilege::Process,
+    Privilege::File,
+    Privilege::References,
+    Privilege::Index,
+    Privilege::Alter,
+    Privilege::ShowDb,
+    Privilege::Super,
+    Privilege::CreateUser,
+    Privilege::Grant,
+];
+
+impl Privilege {
+    /// The name in GRANT, REVOKE and the errors
+    pub fn name(&self) -> &'static str {
+        match self {
+            Privilege::Select => "SELECT",
+            Privilege::Insert => "INSERT",
+            Privilege::Update => "UPDATE",
+            Privilege::Delete => "DELETE",
+            Privilege::Create => "CREATE",
+            Privilege::Drop => "DROP",
+            Privilege::Reload => "RELOAD",
+            Privilege::Shutdown => "SHUTDOWN",
+            Privilege::Process => "PROCESS",
+            Privilege::File => "FILE",
+            Privilege::Grant => "GRANT OPTION",
+            Privilege::References => "REFERENCES",
+            Privilege::Index => "INDEX",
+            Privilege::Alter => "ALTER",
+            Privilege::ShowDb => "SHOW DATABASES",
+            Privilege::Super => "SUPER",
+            Privilege::CreateUser => "CREATE USER",
+        }
+    }
+
+    pub fn column_name(&self) -> &'static str {
+        match self {
+            Privilege::Select => "Select_priv",
+            Privilege::Insert => "Insert_priv",
+            Privilege::Update => "Update_priv",
+            Privilege::Delete => "Delete_priv",
+            Privilege::Create => "Create_priv",
+            Privilege::Drop => "Drop_priv",
+            Privilege::Reload => "Reload_priv",
+            Privilege::Shutdown => "Shutdown_priv",
+            Privilege::Process => "Process_priv",
+            Privilege::File => "File_priv",
+            Privilege::Grant => "Grant_priv",
+            Privilege::References => "References_priv",
+            Privilege::Index => "Index_priv",
+            Privilege::Alter => "Alter_priv",
+            Privilege::ShowDb => "Show_db_priv",
+            Privilege::Super => "Super_priv",
+            Privilege::CreateUser => "Create_user_priv",
+        }
+    }
+
+    /// The privilege can be granted on a schema, the others are only granted ON *.*
+    pub fn is_schema_privilege(&self) -> bool {
+        matches!(
+            self,
+            Privilege::Select
+                | Privilege::Insert
+                | Privilege::Update
+                | Privilege::Delete
+                | Privilege::Create
+                | Privilege::Drop
+                | Privilege::Grant
+                | Privilege::References
+                | Privilege::Index
+                | Privilege::Alter
+        )
+    }
+}
+
+/// An account as 'user'@'host'. The host of the user of a session is the host of the client,
+/// the hosts of mysql.user are patterns with the wildcards of LIKE
+#[derive(Clone, Debug, PartialEq)]
+pub struct Account {
+    pub user: String,
+    pub host: String,
+}
+
+impl Account {
+    pub fn new(user: &str, host: &str) -> Self {
+        Self {
+            user: user.to_string(),
+            host: host.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}'@'{}'", self.user, self.host)
+    }
+}
+
+/// The level of GRANT and REVOKE, `*.*` or `db_name.*`
+#[derive(Clone, Debug, PartialEq)]
+pub enum GrantLevel {
+    Global,
+    Schema(String),
+    /// `*`, the current schema of the session
+    DefaultSchema,
+}
+
+pub fn row_value<'a>(row: &'a GrantRow, column_name: &str) -> &'a str {
+    row.get(column_name).and_then(|value| value.as_deref()).unwrap_or("")
+}
+
+pub fn row_has_privilege(row: &GrantRow, privilege: Privilege) -> bool {
+    row_value(row, privilege.column_name()).eq_ignore_ascii_case("Y")
+}
+
+/// The host of the client matches the host pattern of the row
+fn host_matches(host_pattern: &str, host: &str) -> bool {
+    like::like_match(host.to_lowercase().as_str(), host_pattern.to_lowercase().as_str(), '\\')
+}
+
+/// The row of mysql.user or mysql.db is a row of the account, the host of the account is the host pattern of the row
+pub fn is_row_of_account(row: &GrantRow, account: &Account) -> bool {
+    row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_USER) == account.user
+        && row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_HOST).eq_ignore_ascii_case(account.host.as_str())
+}
+
+pub fn read_users(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<Vec<GrantRow>> {
+    initial::read_def_mysql_rows(global_context.clone(), mysql::users(global_context))
+}
+
+pub fn read_schema_grants(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<Vec<GrantRow>> {
+    initial::read_def_mysql_rows(global_context.clone(), mysql::db(global_context))
+}
+
+/// The row of mysql.user the client logs in with. As mysql the rows of the user matching the host of the client
+/// are sorted with the most specific host first: the hosts without wildcard, then the longer patterns
+pub fn find_user(global_context: Arc<Mutex<GlobalContext>>, user: &str, host: &str) -> MysqlResult<Option<GrantRow>> {
+    let mut rows = read_users(global_context)?
+        .into_iter()
+        .filter(|row| {
+            row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_USER) == user
+                && host_matches(row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_HOST), host)
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by_key(|row| {
+        let host_pattern = row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_HOST);
+        (host_pattern.contains('%') || host_pattern.contains('_'), Reverse(host_pattern.len()))
+    });
+    Ok(rows.into_iter().next())
+}
+
+/// The row of mysql.user of the account, the host of the account is the host pattern of the row
+pub fn find_account(users: &[GrantRow], account: &Account) -> Option<GrantRow> {
+    users.iter().find(|row| is_row_of_account(row, account)).cloned()
+}
+
+/// The row of mysql.db of the account for the schema
+pub fn find_schema_grant(schema_grants: &[GrantRow], account: &Account, schema_name: &str) -> Option<GrantRow> {
+    schema_grants
+        .iter()
+        .find(|row| is_row_of_account(row, account) && row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_DB) == schema_name)
+        .cloned()
+}
+
+/// The row of mysql.user of a new account, without any privilege
+pub fn new_user_row(account: &Account, authentication_string: &str) -> GrantRow {
+    let mut row = GrantRow::new();
+    for privilege in PRIVILEGES {
+        row.insert(privilege.column_name().to_string(), Some("N".to_string()));
+    }
+    row.insert(meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_HOST.to_string(), Some(account.host.clone()));
+    row.insert(meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_USER.to_string(), Some(account.user.clone()));
+    row.insert("plugin".to_string(), Some("mysql_native_password".to_string()));
+    row.insert(
+        meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_AUTHENTICATION_STRING.to_string(),
+        Some(authentication_string.to_string()),
+    );
+    row.insert("password_expired".to_string(), Some("N".to_string()));
+    row.insert("account_locked".to_string(), Some("N".to_string()));
+    row
+}
+
+/// The row of mysql.db of the account for the schema, without any privilege
+pub fn new_schema_grant_row(account: &Account, schema_name: &str) -> GrantRow {
+    let mut row = GrantRow::new();
+    for privilege in PRIVILEGES.iter().filter(|privilege| privilege.is_schema_privilege()) {
+        row.insert(privilege.column_name().to_string(), Some("N".to_string()));
+    }
+    row.insert(meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_HOST.to_string(), Some(account.host.clone()));
+    row.insert(meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_DB.to_string(), Some(schema_name.to_string()));
+    row.insert(meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_USER.to_string(), Some(account.user.clone()));
+    row
+}
+
+/// The user of the session has the privilege globally, or on the schema by a row of mysql.db
+pub fn has_privilege(
+    global_context: Arc<Mutex<GlobalContext>>,
+    account: &Account,
+    privilege: Privilege,
+    schema_name: Option<&str>,
+) -> MysqlResult<bool> {
+    let user = match find_user(global_context.clone(), account.user.as_str(), account.host.as_str())? {
+        Some(user) => user,
+        None => return Ok(false),
+    };
+    if row_has_privilege(&user, privilege) {
+        return Ok(true);
+    }
+
+    let schema_name = match schema_name {
+        Some(schema_name) if privilege.is_schema_privilege() => schema_name,
+        _ => return Ok(false),
+    };
+    let granted = read_schema_grants(global_context)?.iter().any(|row| {
+        row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_USER) == account.user
+            && host_matches(row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_HOST), account.host.as_str())
+            && row_value(row, meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_DB) == schema_name
+            && row_has_privilege(row, privilege)
+    });
+    Ok(granted)
+}
+
+/// The privilege on the table for the user of the session. The tables of information_schema
+/// and performance_schema are readable by every user
+pub fn check_table_privilege(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    privilege: Privilege,
+    schema_name: &str,
+    table_name: &str,
+) -> MysqlResult<()> {
+    let account = match session_context.current_user.lock().unwrap().clone() {
+        Some(account) => account,
+        None => return Ok(()),
+    };
+    let readable_schema = schema_name.eq_ignore_ascii_case(meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA)
+        || schema_name.eq_ignore_ascii_case(meta_const::SCHEMA_NAME_OF_DEF_PERFORMANCE_SCHEMA);
+    if privilege == Privilege::Select && readable_schema {
+        return Ok(());
+    }
+    if has_privilege(global_context, &account, privilege, Some(schema_name))? {
+        return Ok(());
+    }
+
+    let message = format!("{} command denied to user {} for table '{}'", privilege.name(), account, table_name);
+    log::error!("{}", message);
+    Err(MysqlError::new_server_error(
+        mysql_error_code::ER_TABLEACCESS_DENIED_ERROR as u16,
+        "42000",
+        message.as_str(),
+    ))
+}
+
+/// SELECT on every table scanned by the plan, checked before the plan is optimized and executed
+pub fn check_plan_privileges(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    logical_plan: &LogicalPlan,
+) -> MysqlResult<()> {
+    if let LogicalPlan::TableScan { source, .. } = logical_plan {
+        if let Some(sled_table) = source.as_any().downcast_ref::<SledTable>() {
+            let table_option = &sled_table.table_def().option;
+            check_table_privilege(
+                global_context.clone(),
+                session_context,
+                Privilege::Select,
+                table_option.schema_name.as_str(),
+                table_option.table_name.as_str(),
+            )?;
+        }
+        // the view reads its tables with the privileges of its definer, the user needs the SELECT on the view
+        if let Some(view_table) = source.as_any().downcast_ref::<ViewTable>() {
+            let view_def = view_table.view_def();
+            check_table_privilege(
+                global_context.clone(),
+                session_context,
+                Privilege::Select,
+                view_def.schema_name.as_str(),
+                view_def.view_name.as_str(),
+            )?;
+        }
+    }
+    for input in logical_plan.inputs() {
+        check_plan_privileges(global_context.clone(), session_context, input)?;
+    }
+    Ok(())
+}
+
+/// The global privilege of the administration statements, as CREATE USER for CREATE USER and DROP USER
+pub fn check_global_privilege(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    privilege: Privilege,
+) -> MysqlResult<()> {
+    let account = match session_context.current_user.lock().unwrap().clone() {
+        Some(account) => account,
+        None => return Ok(()),
+    };
+    if has_privilege(global_context, &account, privilege, None)? {
+        return Ok(());
+    }
+
+    Err(MysqlError::new_server_error(
+        mysql_error_code::ER_SPECIFIC_ACCESS_DENIED_ERROR as u16,
+        "42000",
+        format!("Access denied; you need (at least one of) the {} privilege(s) for this operation", privilege.name()).as_str(),
+    ))
+}
+
+/// The privilege on the schema, globally or by a row of mysql.db, as GRANT OPTION for the GRANT on the schema
+pub fn check_schema_privilege(
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: &SessionContext,
+    privilege: Privilege,
+    schema_name: &str,
+) -> MysqlResult<()> {
+    let account = match session_context.current_user.lock().unwrap().clone() {
+        Some(account) => account,
+        None => return Ok(()),
+    };
+    if has_privilege(global_context, &account, privilege, Some(schema_name))? {
+        return Ok(());
+    }
+
+    Err(MysqlError::new_server_error(
+        mysql_error_code::ER_DBACCESS_DENIED_ERROR as u16,
+        "42000",
+        format!("Access denied for user {} to database '{}'", account, schema_name).as_str(),
+    ))
+}
+
+/// CREATE USER of an existing account or DROP USER of an unknown one, the accounts are all the failed ones
+pub fn cannot_user_error(operation: &str, accounts: &[Account]) -> MysqlError {
+    let accounts = accounts.iter().map(|account| account.to_string()).collect::<Vec<_>>().join(",");
+    MysqlError::new_server_error(
+        mysql_error_code::ER_CANNOT_USER as u16,
+        "HY000",
+        format!("Operation {} failed for {}", operation, accounts).as_str(),
+    )
+}
+
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use tokio_util::sync::CancellationToken;
+
+pub const COMMAND_SLEEP: &str = "Sleep";
+pub const COMMAND_QUERY: &str = "Query";
+
+/// A connection of the server, as shown by information_schema.PROCESSLIST
+#[derive(Clone, Debug)]
+pub struct Process {
+    pub id: u32,
+    pub user: String,
+    pub host: String,
+    pub db: Option<String>,
+    pub command: String,
+    /// The start of the current command
+    pub started: Instant,
+    /// The sql of the running statement
+    pub info: Option<String>,
+    /// The connection attributes sent by the client at the handshake, in the order it sent them
+    pub connect_attrs: Vec<(String, String)>,
+    /// Cancelled by KILL QUERY and KILL CONNECTION, a new token for every statement
+    query_token: CancellationToken,
+    /// Cancelled by KILL CONNECTION, the handle closes the connection
+    connection_token: CancellationToken,
+}
+
+/// The connections of the server by their connection id, the ids increase from 1 and are never reused
+#[derive(Clone, Debug)]
+pub struct ProcessList {
+    next_id: Arc<AtomicU32>,
+    processes: Arc<Mutex<HashMap<u32, Process>>>,
+}
+
+impl ProcessList {
+    pub fn new() -> Self {
+        Self {
+            next_id: Arc::new(AtomicU32::new(1)),
+            processes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// A new connection, its id and the token cancelled when it is killed
+    pub fn register(&self) -> (u32, CancellationToken) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let connection_token = CancellationToken::new();
+        let process = Process {
+            id,
+            user: "".to_string(),
+            host: "".to_string(),
+            db: None,
+            command: COMMAND_SLEEP.to_string(),
+            started: Instant::now(),
+            info: None,
+            connect_attrs: vec![],
+            query_token: connection_token.child_token(),
+            connection_token: connection_token.clone(),
+        };
+        self.processes.lock().unwrap().insert(id, process);
+        (id, connection_token)
+    }
+
+    pub fn unregister(&self, id: u32) {
+        self.processes.lock().unwrap().remove(&id);
+    }
+
+    /// The user of the connection after the auth
+    pub fn set_user(&self, id: u32, user: &str, host: &str) {
+        if let Some(process) = self.processes.lock().unwrap().get_mut(&id) {
+            process.user = user.to_string();
+            process.host = host.to_string();
+        }
+    }
+
+    pub fn set_connect_attrs(&self, id: u32, connect_attrs: Vec<(String, String)>) {
+        if let Some(process) = self.processes.lock().unwrap().get_mut(&id) {
+            process.connect_attrs = connect_attrs;
+        }
+    }
+
+    /// The statement starts running, the token is cancelled when the statement is killed
+    pub fn start_query(&self, id: u32, db: Option<String>, sql: &str) -> CancellationToken {
+        let mut processes = self.processes.lock().unwrap();
+        match processes.get_mut(&id) {
+            Some(process) => {
+                process.db = db;
+                process.command = COMMAND_QUERY.to_string();
+                process.started = Instant::now();
+                process.info = Some(sql.to_string());
+                process.query_token = process.connection_token.child_token();
+                process.query_token.clone()
+            }
+            None => CancellationToken::new(),
+        }
+    }
+
+    pub fn end_query(&self, id: u32) {
+        if let Some(process) = self.processes.lock().unwrap().get_mut(&id) {
+            process.command = COMMAND_SLEEP.to_string();
+            process.started = Instant::now();
+            process.info = None;
+        }
+    }
+
+    /// KILL QUERY interrupts the running statement, KILL CONNECTION the statement and the connection.
+    /// False if there is no connection with the id
+    pub fn kill(&self, id: u32, query_only: bool) -> bool {
+        match self.processes.lock().unwrap().get(&id) {
+            Some(process) => {
+                if query_only {
+                    process.query_token.cancel();
+                } else {
+                    process.connection_token.cancel();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<Process> {
+        self.processes.lock().unwrap().get(&id).cloned()
+    }
+
+    /// The connections ordered by id
+    pub fn processes(&self) -> Vec<Process> {
+        let mut processes = self.processes.lock().unwrap().values().cloned().collect::<Vec<_>>();
+        processes.sort_by_key(|process| process.id);
+        processes
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::physical_plan::{DisplayFormatType, ExecutionPlan};
+
+/// The metric of the scans for the bytes read from the store
+pub const METRIC_NAME_BYTES_SCANNED: &str = "bytes_scanned";
+/// The metric of the scans for the batches read ahead of the consumer
+pub const METRIC_NAME_BATCHES_READ_AHEAD: &str = "batches_read_ahead";
+
+/// The metrics of an operator of the physical plan, None if the operator does not collect it
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperatorProfile {
+    /// The depth of the operator in the plan, the root is 0
+    pub depth: usize,
+    pub name: String,
+    pub output_rows: Option<usize>,
+    pub bytes_scanned: Option<usize>,
+    pub batches_read_ahead: Option<usize>,
+    pub elapsed_compute: Option<Duration>,
+}
+
+/// The metrics of a query collected from the physical plan after the execution, read by SHOW PROFILE
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryProfile {
+    /// From the planning to the last batch
+    pub elapsed: Duration,
+    /// The operators of the plan from the root, the children follow their parent
+    pub operators: Vec<OperatorProfile>,
+}
+
+impl QueryProfile {
+    pub fn from_plan(execution_plan: &Arc<dyn ExecutionPlan>, elapsed: Duration) -> Self {
+        let mut operators = vec![];
+        collect_operator_profile(execution_plan, 0, &mut operators);
+
+        Self {
+            elapsed,
+            operators,
+        }
+    }
+}
+
+fn collect_operator_profile(execution_plan: &Arc<dyn ExecutionPlan>, depth: usize, operators: &mut Vec<OperatorProfile>) {
+    let metrics = execution_plan.metrics();
+    let operator_profile = OperatorProfile {
+        depth,
+        name: operator_name(execution_plan.as_ref()),
+        output_rows: metrics.as_ref().and_then(|metrics| metrics.output_rows()),
+        bytes_scanned: metrics
+            .as_ref()
+            .and_then(|metrics| metrics.sum_by_name(METRIC_NAME_BYTES_SCANNED))
+            .map(|value| value.as_usize()),
+        batches_read_ahead: metrics
+            .as_ref()
+            .and_then(|metrics| metrics.sum_by_name(METRIC_NAME_BATCHES_READ_AHEAD))
+            .map(|value| value.as_usize()),
+        elapsed_compute: metrics
+            .as_ref()
+            .and_then(|metrics| metrics.elapsed_compute())
+            .map(|nanos| Duration::from_nanos(nanos as u64)),
+    };
+    operators.push(operator_profile);
+
+    for child in execution_plan.children() {
+        collect_operator_profile(&child, depth + 1, operators);
+    }
+}
+
+/// The name of the operator is the part of its display before the details, `SledExec: table=...` is `SledExec`
+fn operator_name(execution_plan: &dyn ExecutionPlan) -> String {
+    struct DefaultDisplay<'a>(&'a dyn ExecutionPlan);
+
+    impl fmt::Display for DefaultDisplay<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.fmt_as(DisplayFormatType::Default, f)
+        }
+    }
+
+    let display = DefaultDisplay(execution_plan).to_string();
+    match display.split_once(':') {
+        Some((name, _)) => name.trim().to_string(),
+        None => display.trim().to_string(),
+    }
+}
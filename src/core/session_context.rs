@@ -1,12 +1,89 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use datafusion::scalar::ScalarValue;
+use sqlparser::ast::ObjectName;
+use tokio_util::sync::CancellationToken;
+
+use crate::core::privilege::Account;
+use crate::core::profile::QueryProfile;
+use crate::datafusion_impl::datasource::temporary::TemporaryTable;
+use crate::meta::meta_const;
 use crate::meta::variable::Variable;
+use crate::mysql::error::MysqlError;
+use crate::store::engine::transaction::Transaction;
+
+/// The level of a condition shown by SHOW WARNINGS
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WarningLevel {
+    Note,
+    Warning,
+    Error,
+}
+
+impl WarningLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningLevel::Note => "Note",
+            WarningLevel::Warning => "Warning",
+            WarningLevel::Error => "Error",
+        }
+    }
+}
+
+/// A condition raised by the last statement
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    pub level: WarningLevel,
+    pub code: u16,
+    pub message: String,
+}
 
 #[derive(Clone, Debug)]
 pub struct SessionContext {
     pub current_catalog: Arc<Mutex<Option<String>>>,
     pub current_schema: Arc<Mutex<Option<String>>>,
+    /// The max packet size negotiated with the client in the handshake
+    pub max_packet_size: Arc<Mutex<Option<u32>>>,
+    /// The client sets CLIENT_MULTI_STATEMENTS in the handshake
+    pub multi_statements: Arc<Mutex<bool>>,
+    /// The handle sends the rows of the SELECT of the client as they are produced, the SELECT returns their stream
+    pub stream_results: Arc<Mutex<bool>>,
+    /// The user logged in and the host of the client, its privileges are checked for the statements of the session.
+    /// None for the sessions opened by the server itself, they are not checked
+    pub current_user: Arc<Mutex<Option<Account>>>,
+    /// The tenant of the user of the session, bound at auth by the tenants of the config, the client can not change it
+    pub tenant_id: Arc<Mutex<Option<String>>>,
+    /// The sql mode of the session, set by SET sql_mode
+    pub sql_mode: Arc<Mutex<String>>,
+    /// The max execution time of SELECT in milliseconds set by SET max_execution_time, None is the config one
+    pub max_execution_time: Arc<Mutex<Option<u64>>>,
+    /// The warnings and the errors of the last statement, read by @@warning_count and @@error_count
+    pub warning_count: Arc<Mutex<u64>>,
+    pub error_count: Arc<Mutex<u64>>,
+    /// The conditions of the last statement in the order they were raised, shown by SHOW WARNINGS and SHOW ERRORS
+    pub warnings: Arc<Mutex<Vec<Warning>>>,
+    /// The client sets CLIENT_FOUND_ROWS in the handshake, UPDATE reports the matched rows instead of the changed ones
+    pub found_rows: Arc<Mutex<bool>>,
+    /// The client sets CLIENT_INTERACTIVE in the handshake, the session wait_timeout starts with interactive_timeout
+    pub interactive: Arc<Mutex<bool>>,
+    /// The first auto increment value generated by the last INSERT which generated one, read by LAST_INSERT_ID()
+    pub last_insert_id: Arc<Mutex<u64>>,
+    /// The metrics of the last SELECT of the session, read by SHOW PROFILE
+    pub last_profile: Arc<Mutex<Option<QueryProfile>>>,
+    /// The session values of the system variables set by SET or the config, the others read the global values
+    pub system_variables: Arc<Mutex<HashMap<String, String>>>,
+    /// The user variables set by SET @var and SELECT ... INTO @var, by their lower case names
+    pub user_variables: Arc<Mutex<HashMap<String, ScalarValue>>>,
+    /// The token of the running statement, cancelled by KILL QUERY
+    pub query_token: Arc<Mutex<CancellationToken>>,
+    /// The writes of the transaction opened by BEGIN or by a statement with autocommit off, None without a transaction.
+    /// They are written by COMMIT and discarded by ROLLBACK, the reads see the committed rows only
+    pub transaction: Arc<Mutex<Option<Transaction>>>,
+    /// The TEMPORARY tables of the session by their full names, they hide the tables of the same names.
+    /// They are dropped with the session
+    pub temporary_tables: Arc<Mutex<HashMap<ObjectName, TemporaryTable>>>,
     pub variable: Variable,
 }
 
@@ -17,16 +94,122 @@ impl SessionContext {
         Self {
             current_catalog: Arc::new(Mutex::new(None)),
             current_schema: Arc::new(Mutex::new(None)),
+            max_packet_size: Arc::new(Mutex::new(None)),
+            multi_statements: Arc::new(Mutex::new(false)),
+            stream_results: Arc::new(Mutex::new(false)),
+            found_rows: Arc::new(Mutex::new(false)),
+            interactive: Arc::new(Mutex::new(false)),
+            current_user: Arc::new(Mutex::new(None)),
+            tenant_id: Arc::new(Mutex::new(None)),
+            sql_mode: Arc::new(Mutex::new(meta_const::DEFAULT_SQL_MODE.to_string())),
+            max_execution_time: Arc::new(Mutex::new(None)),
+            warning_count: Arc::new(Mutex::new(0)),
+            error_count: Arc::new(Mutex::new(0)),
+            warnings: Arc::new(Mutex::new(vec![])),
+            last_insert_id: Arc::new(Mutex::new(0)),
+            last_profile: Arc::new(Mutex::new(None)),
+            system_variables: Arc::new(Mutex::new(HashMap::new())),
+            user_variables: Arc::new(Mutex::new(HashMap::new())),
+            query_token: Arc::new(Mutex::new(CancellationToken::new())),
+            transaction: Arc::new(Mutex::new(None)),
+            temporary_tables: Arc::new(Mutex::new(HashMap::new())),
             variable,
         }
     }
 
+    /// Clear the state set by the statements of the session, as for a new connection.
+    /// The max packet size, the capabilities of the handshake and the tenant of the user are kept
+    pub fn reset(&self) {
+        *self.current_schema.lock().unwrap() = None;
+        *self.sql_mode.lock().unwrap() = meta_const::DEFAULT_SQL_MODE.to_string();
+        *self.max_execution_time.lock().unwrap() = None;
+        self.clear_warnings();
+        *self.last_insert_id.lock().unwrap() = 0;
+        *self.last_profile.lock().unwrap() = None;
+        self.system_variables.lock().unwrap().clear();
+        self.user_variables.lock().unwrap().clear();
+        // the transaction of the session is rolled back
+        *self.transaction.lock().unwrap() = None;
+        self.temporary_tables.lock().unwrap().clear();
+    }
+
+    /// The next statement clears the conditions of the last one
+    pub fn clear_warnings(&self) {
+        self.warnings.lock().unwrap().clear();
+        *self.warning_count.lock().unwrap() = 0;
+        *self.error_count.lock().unwrap() = 0;
+    }
+
+    pub fn push_warning(&self, code: u16, message: &str) {
+        self.warnings.lock().unwrap().push(Warning {
+            level: WarningLevel::Warning,
+            code,
+            message: message.to_string(),
+        });
+        *self.warning_count.lock().unwrap() += 1;
+    }
+
+    /// The error which ended the statement, it is one of the conditions counted by @@warning_count too
+    pub fn push_error(&self, mysql_error: &MysqlError) {
+        self.warnings.lock().unwrap().push(Warning {
+            level: WarningLevel::Error,
+            code: mysql_error.error_number(),
+            message: mysql_error.message(),
+        });
+        *self.error_count.lock().unwrap() += 1;
+        *self.warning_count.lock().unwrap() += 1;
+    }
+
+    pub fn warning_count(&self) -> u64 {
+        *self.warning_count.lock().unwrap()
+    }
+
+    /// The temporary table of the full table name without quotes in lower case
+    pub fn get_temporary_table(&self, full_table_name: &ObjectName) -> Option<TemporaryTable> {
+        self.temporary_tables.lock().unwrap().get(full_table_name).cloned()
+    }
+
+    pub fn get_temporary_table_list(&self) -> Vec<TemporaryTable> {
+        self.temporary_tables.lock().unwrap().values().cloned().collect()
+    }
+
+    /// The session value of the system variable, None if the session reads the global value
+    pub fn get_session_variable(&self, name: &str) -> Option<String> {
+        let name = name.to_lowercase();
+        match name.as_str() {
+            meta_const::SYSTEM_VARIABLE_SQL_MODE => Some(self.sql_mode.lock().unwrap().clone()),
+            meta_const::SYSTEM_VARIABLE_MAX_EXECUTION_TIME => {
+                self.max_execution_time.lock().unwrap().map(|max_execution_time| max_execution_time.to_string())
+            }
+            _ => self.system_variables.lock().unwrap().get(name.as_str()).cloned(),
+        }
+    }
+
     pub fn new_with_catalog(catalog_name: &str) -> Self {
         let variable = Variable::new();
 
         Self {
             current_catalog: Arc::new(Mutex::new(Some(catalog_name.to_string()))),
             current_schema: Arc::new(Mutex::new(None)),
+            max_packet_size: Arc::new(Mutex::new(None)),
+            multi_statements: Arc::new(Mutex::new(false)),
+            stream_results: Arc::new(Mutex::new(false)),
+            found_rows: Arc::new(Mutex::new(false)),
+            interactive: Arc::new(Mutex::new(false)),
+            current_user: Arc::new(Mutex::new(None)),
+            tenant_id: Arc::new(Mutex::new(None)),
+            sql_mode: Arc::new(Mutex::new(meta_const::DEFAULT_SQL_MODE.to_string())),
+            max_execution_time: Arc::new(Mutex::new(None)),
+            warning_count: Arc::new(Mutex::new(0)),
+            error_count: Arc::new(Mutex::new(0)),
+            warnings: Arc::new(Mutex::new(vec![])),
+            last_insert_id: Arc::new(Mutex::new(0)),
+            last_profile: Arc::new(Mutex::new(None)),
+            system_variables: Arc::new(Mutex::new(HashMap::new())),
+            user_variables: Arc::new(Mutex::new(HashMap::new())),
+            query_token: Arc::new(Mutex::new(CancellationToken::new())),
+            transaction: Arc::new(Mutex::new(None)),
+            temporary_tables: Arc::new(Mutex::new(HashMap::new())),
             variable,
         }
     }
@@ -37,6 +220,25 @@ impl SessionContext {
         Self {
             current_catalog: Arc::new(Mutex::new(Some(catalog_name.to_string()))),
             current_schema: Arc::new(Mutex::new(Some(schema_name.to_string()))),
+            max_packet_size: Arc::new(Mutex::new(None)),
+            multi_statements: Arc::new(Mutex::new(false)),
+            stream_results: Arc::new(Mutex::new(false)),
+            found_rows: Arc::new(Mutex::new(false)),
+            interactive: Arc::new(Mutex::new(false)),
+            current_user: Arc::new(Mutex::new(None)),
+            tenant_id: Arc::new(Mutex::new(None)),
+            sql_mode: Arc::new(Mutex::new(meta_const::DEFAULT_SQL_MODE.to_string())),
+            max_execution_time: Arc::new(Mutex::new(None)),
+            warning_count: Arc::new(Mutex::new(0)),
+            error_count: Arc::new(Mutex::new(0)),
+            warnings: Arc::new(Mutex::new(vec![])),
+            last_insert_id: Arc::new(Mutex::new(0)),
+            last_profile: Arc::new(Mutex::new(None)),
+            system_variables: Arc::new(Mutex::new(HashMap::new())),
+            user_variables: Arc::new(Mutex::new(HashMap::new())),
+            query_token: Arc::new(Mutex::new(CancellationToken::new())),
+            transaction: Arc::new(Mutex::new(None)),
+            temporary_tables: Arc::new(Mutex::new(HashMap::new())),
             variable,
         }
     }
@@ -26,8 +26,12 @@ use arrow::{
     record_batch::RecordBatch,
 };
 use arrow::array::{
+    as_string_array,
+    ArrayRef,
     Int32Array,
+    Int64Array,
     StringArray,
+    UInt64Array,
 };
 use datafusion::catalog::{
     catalog::{CatalogList, CatalogProvider},
@@ -36,15 +40,21 @@ use datafusion::catalog::{
 use datafusion::datasource::{MemTable, TableProvider};
 use datafusion::error::{Result};
 
-use crate::meta::{meta_const};
+use crate::meta::{meta_const, meta_util};
+use crate::meta::def::information_schema;
 use datafusion::catalog::catalog::MemoryCatalogProvider;
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::mysql::error::MysqlResult;
+use crate::store::engine::engine_util::TableEngineFactory;
 use std::sync::Mutex;
 
 /// Wraps another [`CatalogProvider`] and adds a "information_schema"
 /// schema that can introspect on tables in the catalog_list
 pub struct CatalogWithInformationSchemaProvider {
     global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
     catalog_list: Arc<dyn CatalogList>,
     /// wrapped provider
     inner: Arc<dyn CatalogProvider>,
@@ -53,11 +63,13 @@ pub struct CatalogWithInformationSchemaProvider {
 impl CatalogWithInformationSchemaProvider {
     pub fn new(
         global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
         catalog_list: Arc<dyn CatalogList>,
         inner: Arc<dyn CatalogProvider>,
     ) -> Self {
         Self {
             global_context,
+            session_context,
             catalog_list,
             inner,
         }
@@ -92,6 +104,7 @@ impl CatalogProvider for CatalogWithInformationSchemaProvider {
         if name.eq_ignore_ascii_case(meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA) {
             Some(Arc::new(InformationSchemaProvider {
                 global_context: self.global_context.clone(),
+                session_context: self.session_context.clone(),
                 catalog_list: self.catalog_list.clone(),
                 inner: self.inner.schema(name).unwrap(),
             }))
@@ -109,6 +122,8 @@ impl CatalogProvider for CatalogWithInformationSchemaProvider {
 /// table is queried.
 struct InformationSchemaProvider {
     global_context: Arc<Mutex<GlobalContext>>,
+    /// The session reading the tables, the user without PROCESS sees only its own connections
+    session_context: SessionContext,
     catalog_list: Arc<dyn CatalogList>,
     /// wrapped provider
     inner: Arc<dyn SchemaProvider>,
@@ -145,25 +160,137 @@ impl InformationSchemaProvider {
         Arc::new(mem_table)
     }
 
-    fn make_referential_constraints(&self) -> Arc<dyn TableProvider> {
+    /// The connections of the server when the table is read, only the ones of the user of the session
+    /// unless the user has PROCESS
+    fn make_processlist(&self) -> Arc<dyn TableProvider> {
         let schema = Arc::new(Schema::new(vec![
-            Field::new("constraint_catalog", DataType::Utf8, false),
-            Field::new("constraint_schema", DataType::Utf8, false),
-            Field::new("constraint_name", DataType::Utf8, false),
-            Field::new("unique_constraint_catalog", DataType::Utf8, false),
-            Field::new("unique_constraint_schema", DataType::Utf8, false),
-            Field::new("unique_constraint_name", DataType::Utf8, false),
-            Field::new("match_option", DataType::Utf8, false),
-            Field::new("update_rule", DataType::Utf8, false),
-            Field::new("delete_rule", DataType::Utf8, false),
-            Field::new("table_name", DataType::Utf8, false),
-            Field::new("referenced_table_name", DataType::Utf8, false),
+            Field::new("id", DataType::UInt64, false),
+            Field::new("user", DataType::Utf8, false),
+            Field::new("host", DataType::Utf8, false),
+            Field::new("db", DataType::Utf8, true),
+            Field::new("command", DataType::Utf8, false),
+            Field::new("time", DataType::Int64, false),
+            Field::new("state", DataType::Utf8, true),
+            Field::new("info", DataType::Utf8, true),
         ]));
-        let batch = RecordBatch::new_empty(schema.clone());
 
-        let mem_table = MemTable::try_new(schema.clone(), vec![vec![batch.clone()]]).unwrap();
+        let process_list = self.global_context.lock().unwrap().process_list.clone();
+        let mut processes = process_list.processes();
+        let current_user = self.session_context.current_user.lock().unwrap().clone();
+        if let Some(account) = current_user {
+            let has_process = privilege::has_privilege(self.global_context.clone(), &account, Privilege::Process, None).unwrap_or(false);
+            if !has_process {
+                processes.retain(|process| process.user == account.user);
+            }
+        }
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt64Array::from(processes.iter().map(|process| process.id as u64).collect::<Vec<_>>())),
+                Arc::new(StringArray::from(processes.iter().map(|process| process.user.as_str()).collect::<Vec<_>>())),
+                Arc::new(StringArray::from(processes.iter().map(|process| process.host.as_str()).collect::<Vec<_>>())),
+                Arc::new(StringArray::from(processes.iter().map(|process| process.db.as_deref()).collect::<Vec<_>>())),
+                Arc::new(StringArray::from(processes.iter().map(|process| process.command.as_str()).collect::<Vec<_>>())),
+                Arc::new(Int64Array::from(processes.iter().map(|process| process.started.elapsed().as_secs() as i64).collect::<Vec<_>>())),
+                Arc::new(StringArray::from(processes.iter().map(|process| process.info.as_ref().map(|_| "executing")).collect::<Vec<_>>())),
+                Arc::new(StringArray::from(processes.iter().map(|process| process.info.as_deref()).collect::<Vec<_>>())),
+            ],
+        ).unwrap();
+
+        let mem_table = MemTable::try_new(schema.clone(), vec![vec![batch]]).unwrap();
+        Arc::new(mem_table)
+    }
+
+    /// The connection attributes of the connections, in the order the clients sent them
+    fn make_session_connect_attrs(&self) -> Arc<dyn TableProvider> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("processlist_id", DataType::UInt64, false),
+            Field::new("attr_name", DataType::Utf8, false),
+            Field::new("attr_value", DataType::Utf8, true),
+            Field::new("ordinal_position", DataType::Int64, false),
+        ]));
+
+        let process_list = self.global_context.lock().unwrap().process_list.clone();
+        let mut processlist_ids = vec![];
+        let mut attr_names = vec![];
+        let mut attr_values = vec![];
+        let mut ordinal_positions = vec![];
+        for process in process_list.processes() {
+            for (ordinal_position, (attr_name, attr_value)) in process.connect_attrs.into_iter().enumerate() {
+                processlist_ids.push(process.id as u64);
+                attr_names.push(attr_name);
+                attr_values.push(attr_value);
+                ordinal_positions.push(ordinal_position as i64);
+            }
+        }
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt64Array::from(processlist_ids)),
+                Arc::new(StringArray::from(attr_names)),
+                Arc::new(StringArray::from(attr_values)),
+                Arc::new(Int64Array::from(ordinal_positions)),
+            ],
+        ).unwrap();
+
+        let mem_table = MemTable::try_new(schema.clone(), vec![vec![batch]]).unwrap();
         Arc::new(mem_table)
     }
+
+    /// The saved rows of the tables with the next auto increment value of every table when the table is read,
+    /// NULL for the table without an auto increment column
+    fn make_tables(&self) -> MysqlResult<Arc<dyn TableProvider>> {
+        let table_def = information_schema::tables(self.global_context.clone());
+        let table_schema = table_def.to_schema();
+        let column_index_of_schema_name = table_schema.index_of(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA)?;
+        let column_index_of_table_name = table_schema.index_of(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME)?;
+        let column_index_of_auto_increment = table_schema.index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_AUTO_INCREMENT)?;
+        let fields = table_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(column_index, field)| {
+                if column_index == column_index_of_auto_increment {
+                    Field::new(field.name(), DataType::Int64, true)
+                } else {
+                    field.clone()
+                }
+            })
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+
+        let table_map = self.global_context.lock().unwrap().meta_data.get_table_map();
+        let engine = TableEngineFactory::try_new_with_table(self.global_context.clone(), table_def.clone())?;
+        let mut batches = vec![];
+        for item in engine.table_iterator(None, &[]) {
+            let record_batch = item?;
+            let schema_name_row = as_string_array(record_batch.column(column_index_of_schema_name));
+            let table_name_row = as_string_array(record_batch.column(column_index_of_table_name));
+            let mut auto_increment_values = vec![];
+            for row_index in 0..record_batch.num_rows() {
+                let full_table_name = meta_util::create_full_table_name(
+                    meta_const::CATALOG_NAME,
+                    schema_name_row.value(row_index),
+                    table_name_row.value(row_index),
+                );
+                let auto_increment_value = match table_map.get(&full_table_name) {
+                    Some(table_def) if table_def.get_auto_increment_column().is_some() => {
+                        Some(meta_util::read_auto_increment(self.global_context.clone(), table_def)?)
+                    }
+                    _ => None,
+                };
+                auto_increment_values.push(auto_increment_value);
+            }
+
+            let mut columns: Vec<ArrayRef> = record_batch.columns().to_vec();
+            columns[column_index_of_auto_increment] = Arc::new(Int64Array::from(auto_increment_values));
+            batches.push(RecordBatch::try_new(schema.clone(), columns)?);
+        }
+
+        let mem_table = MemTable::try_new(schema.clone(), vec![batches])?;
+        Ok(Arc::new(mem_table))
+    }
 }
 
 impl SchemaProvider for InformationSchemaProvider {
@@ -177,7 +304,8 @@ impl SchemaProvider for InformationSchemaProvider {
             .into_iter()
             .chain(std::iter::once(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_DUAL.to_string()))
             .chain(std::iter::once(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_CHECK_CONSTRAINTS.to_string()))
-            .chain(std::iter::once(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS.to_string()))
+            .chain(std::iter::once(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_PROCESSLIST.to_string()))
+            .chain(std::iter::once(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_SESSION_CONNECT_ATTRS.to_string()))
             .collect::<Vec<String>>()
     }
 
@@ -186,8 +314,18 @@ impl SchemaProvider for InformationSchemaProvider {
             Some(self.make_dual())
         } else if name.eq_ignore_ascii_case(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_CHECK_CONSTRAINTS) {
             Some(self.make_check_constraints())
-        } else if name.eq_ignore_ascii_case(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS) {
-            Some(self.make_referential_constraints())
+        } else if name.eq_ignore_ascii_case(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_PROCESSLIST) {
+            Some(self.make_processlist())
+        } else if name.eq_ignore_ascii_case(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_SESSION_CONNECT_ATTRS) {
+            Some(self.make_session_connect_attrs())
+        } else if name.eq_ignore_ascii_case(meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES) {
+            match self.make_tables() {
+                Ok(table_provider) => Some(table_provider),
+                Err(mysql_error) => {
+                    log::error!("Read the tables of information_schema failed: {:?}", mysql_error);
+                    self.inner.table(name)
+                }
+            }
         } else {
             self.inner.table(name)
         }
@@ -1 +1,3 @@
 pub mod sled;
+pub mod temporary;
+pub mod view;
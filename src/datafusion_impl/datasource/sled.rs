@@ -7,17 +7,26 @@ use async_trait::async_trait;
 use datafusion::datasource::datasource::TableProviderFilterPushDown;
 use datafusion::datasource::TableProvider;
 use datafusion::error::Result;
-use datafusion::logical_plan::Expr;
+use datafusion::logical_plan::{Column, Expr, Operator};
+use datafusion::scalar::ScalarValue;
 use datafusion::physical_plan::ExecutionPlan;
+use sqlparser::ast::Ident;
 
 use crate::core::global_context::GlobalContext;
 use crate::datafusion_impl::physical_plan::sled::SledExec;
+use crate::meta::meta_const;
 use crate::meta::meta_def::TableDef;
+use crate::store::engine::transaction;
+use crate::store::engine::transaction::Transaction;
+use crate::store::reader::reader_util;
+use crate::util::dbkey;
 
 #[derive(Clone)]
 pub struct SledTable {
     global_context: Arc<Mutex<GlobalContext>>,
     table: TableDef,
+    /// The transaction of the session of the provider, the scans read its snapshot while it is open
+    transaction: Option<Arc<Mutex<Option<Transaction>>>>,
 }
 
 impl SledTable {
@@ -26,8 +35,26 @@ impl SledTable {
         Self {
             global_context,
             table,
+            transaction: None,
         }
     }
+
+    pub fn with_transaction(&mut self, transaction: Arc<Mutex<Option<Transaction>>>) {
+        self.transaction = Some(transaction)
+    }
+
+    pub fn table_def(&self) -> &TableDef {
+        &self.table
+    }
+
+    /// The LIKE of the text column whose pattern starts with literal chars seeks the keys of the prefix
+    fn is_like_prefix(&self, column: &Column, pattern: &ScalarValue) -> bool {
+        let is_text_column = match self.table.get_table_column().get_sparrow_column(Ident::new(column.name.clone())) {
+            Ok(sparrow_column) => dbkey::is_text_index_key(&sparrow_column.sql_column.data_type),
+            Err(_) => false,
+        };
+        is_text_column && reader_util::create_like_range(pattern).is_some()
+    }
 }
 
 #[async_trait]
@@ -44,16 +71,79 @@ impl TableProvider for SledTable {
         &self,
         projection: &Option<Vec<usize>>,
         batch_size: usize,
-        _: &[Expr],
+        filters: &[Expr],
         _: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        let snapshot = transaction::read_snapshot(self.global_context.clone(), self.transaction.as_ref());
         let exec = SledExec::try_new(
             self.global_context.clone(),
             self.table.clone(),
             projection.clone(),
             batch_size,
-            &[],
+            filters,
+            snapshot,
         )?;
         Ok(Arc::new(exec))
     }
+
+    fn supports_filter_pushdown(&self, filter: &Expr) -> Result<TableProviderFilterPushDown> {
+        // the filter is only used to seek the index,
+        // it is still applied to the rows read from the index
+        match filter {
+            // the NULL values are indexed only with the index_nulls option
+            Expr::IsNull(expr) if self.table.option.index_nulls => match expr.as_ref() {
+                Expr::Column(_) => Ok(TableProviderFilterPushDown::Inexact),
+                _ => Ok(TableProviderFilterPushDown::Unsupported),
+            },
+            Expr::BinaryExpr { left, op, right } => match (left.as_ref(), op, right.as_ref()) {
+                (Expr::Column(_), Operator::Eq, Expr::Literal(ScalarValue::Utf8(Some(_)))) => Ok(TableProviderFilterPushDown::Inexact),
+                (Expr::Column(column), Operator::Like, Expr::Literal(pattern)) if self.is_like_prefix(column, pattern) => {
+                    Ok(TableProviderFilterPushDown::Inexact)
+                }
+                // the numbers, the dates and the timestamps are kept in order in the index keys
+                (Expr::Column(_), Operator::Eq | Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq, Expr::Literal(value)) if is_ordered_value(value) => {
+                    Ok(TableProviderFilterPushDown::Inexact)
+                }
+                _ => Ok(TableProviderFilterPushDown::Unsupported),
+            },
+            Expr::Between { expr, negated: false, low, high } => match (expr.as_ref(), low.as_ref(), high.as_ref()) {
+                (Expr::Column(_), Expr::Literal(low), Expr::Literal(high)) if is_ordered_value(low) && is_ordered_value(high) => {
+                    Ok(TableProviderFilterPushDown::Inexact)
+                }
+                _ => Ok(TableProviderFilterPushDown::Unsupported),
+            },
+            // the LIKE with an escaped wildcard, the escaped wildcard is a literal char of the prefix
+            Expr::ScalarUDF { fun, args } if fun.name == meta_const::FUNCTION_NAME_LIKE_ESCAPE => match args.as_slice() {
+                [Expr::Column(column), Expr::Literal(pattern)] if self.is_like_prefix(column, pattern) => {
+                    Ok(TableProviderFilterPushDown::Inexact)
+                }
+                _ => Ok(TableProviderFilterPushDown::Unsupported),
+            },
+            _ => Ok(TableProviderFilterPushDown::Unsupported),
+        }
+    }
+}
+
+/// The literal whose index keys are in the order of the values, the text is compared as bytes only for equality and LIKE prefixes
+fn is_ordered_value(value: &ScalarValue) -> bool {
+    matches!(
+        value,
+        ScalarValue::Int8(Some(_))
+            | ScalarValue::Int16(Some(_))
+            | ScalarValue::Int32(Some(_))
+            | ScalarValue::Int64(Some(_))
+            | ScalarValue::UInt8(Some(_))
+            | ScalarValue::UInt16(Some(_))
+            | ScalarValue::UInt32(Some(_))
+            | ScalarValue::UInt64(Some(_))
+            | ScalarValue::Float32(Some(_))
+            | ScalarValue::Float64(Some(_))
+            | ScalarValue::Date32(Some(_))
+            | ScalarValue::Date64(Some(_))
+            | ScalarValue::TimestampSecond(Some(_), ..)
+            | ScalarValue::TimestampMillisecond(Some(_), ..)
+            | ScalarValue::TimestampMicrosecond(Some(_), ..)
+            | ScalarValue::TimestampNanosecond(Some(_), ..)
+            | ScalarValue::Decimal128(Some(_), _, _)
+    )
 }
@@ -0,0 +1,104 @@
+use std::sync::{Arc, Mutex};
+
+use bitflags::_core::any::Any;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use datafusion::datasource::datasource::TableType;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::Result;
+use datafusion::logical_plan::Expr;
+use datafusion::physical_plan::ExecutionPlan;
+use sqlparser::ast::{ColumnDef, ObjectName};
+
+/// The PRIMARY KEY or a UNIQUE key of a temporary table, the rows with NULL in its columns are not checked
+#[derive(Clone, Debug)]
+pub struct UniqueKey {
+    pub index_name: String,
+    pub column_name_list: Vec<String>,
+}
+
+/// A TEMPORARY table, its rows are kept in the memory of the session which created it.
+/// The clones share the rows, the rows are gone with the last clone
+#[derive(Clone)]
+pub struct TemporaryTable {
+    full_table_name: ObjectName,
+    sql_column_list: Vec<ColumnDef>,
+    unique_key_list: Vec<UniqueKey>,
+    schema: SchemaRef,
+    record_batches: Arc<Mutex<Vec<RecordBatch>>>,
+}
+
+impl TemporaryTable {
+    pub fn new(
+        full_table_name: ObjectName,
+        sql_column_list: Vec<ColumnDef>,
+        unique_key_list: Vec<UniqueKey>,
+        schema: SchemaRef,
+    ) -> Self {
+        Self {
+            full_table_name,
+            sql_column_list,
+            unique_key_list,
+            schema,
+            record_batches: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    pub fn full_table_name(&self) -> ObjectName {
+        self.full_table_name.clone()
+    }
+
+    pub fn get_columns(&self) -> &Vec<ColumnDef> {
+        &self.sql_column_list
+    }
+
+    pub fn get_unique_keys(&self) -> &Vec<UniqueKey> {
+        &self.unique_key_list
+    }
+
+    pub fn get_record_batches(&self) -> Vec<RecordBatch> {
+        self.record_batches.lock().unwrap().clone()
+    }
+
+    pub fn append(&self, record_batch: RecordBatch) {
+        self.record_batches.lock().unwrap().push(record_batch);
+    }
+
+    /// Delete all the rows, returns the count of them
+    pub fn truncate(&self) -> u64 {
+        let mut record_batches = self.record_batches.lock().unwrap();
+        let count = record_batches.iter().map(|record_batch| record_batch.num_rows() as u64).sum();
+        record_batches.clear();
+        count
+    }
+}
+
+#[async_trait]
+impl TableProvider for TemporaryTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    /// The scan reads the rows inserted before it starts
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let record_batches = self.record_batches.lock().unwrap().clone();
+        let mem_table = MemTable::try_new(self.schema.clone(), vec![record_batches])?;
+        mem_table.scan(projection, batch_size, filters, limit).await
+    }
+}
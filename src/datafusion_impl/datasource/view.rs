@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use bitflags::_core::any::Any;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use async_trait::async_trait;
+use datafusion::datasource::datasource::TableType;
+use datafusion::datasource::TableProvider;
+use datafusion::error::Result;
+use datafusion::execution::context::ExecutionContext;
+use datafusion::logical_plan::{col, Expr, LogicalPlan, LogicalPlanBuilder};
+use datafusion::physical_plan::ExecutionPlan;
+
+use crate::meta::meta_def::ViewDef;
+
+/// A VIEW, its scan runs the plan of its definition. The tables of the definition are in the plan,
+/// they are read with the snapshot of the session which planned the view
+#[derive(Clone)]
+pub struct ViewTable {
+    view: ViewDef,
+    /// The plan of the definition with its columns renamed to the columns of the view
+    logical_plan: LogicalPlan,
+    schema: SchemaRef,
+}
+
+impl ViewTable {
+    pub fn try_new(view: ViewDef, logical_plan: LogicalPlan) -> Result<Self> {
+        let expr = logical_plan
+            .schema()
+            .fields()
+            .iter()
+            .zip(view.column_name_list.iter())
+            .map(|(field, column_name)| Expr::Column(field.qualified_column()).alias(column_name))
+            .collect::<Vec<_>>();
+        let logical_plan = LogicalPlanBuilder::from(logical_plan).project(expr)?.build()?;
+        let schema: Schema = logical_plan.schema().as_ref().clone().into();
+
+        Ok(Self {
+            view,
+            logical_plan,
+            schema: Arc::new(schema),
+        })
+    }
+
+    pub fn view_def(&self) -> &ViewDef {
+        &self.view
+    }
+}
+
+#[async_trait]
+impl TableProvider for ViewTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _: usize,
+        _: &[Expr],
+        _: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let logical_plan = match projection {
+            Some(projection) => {
+                let expr = projection
+                    .iter()
+                    .map(|column_index| col(self.schema.field(*column_index).name()))
+                    .collect::<Vec<_>>();
+                LogicalPlanBuilder::from(self.logical_plan.clone()).project(expr)?.build()?
+            }
+            None => self.logical_plan.clone(),
+        };
+
+        // the plan is optimized and rewritten when the view is registered, the scans of its tables are in it
+        let execution_context = ExecutionContext::new();
+        execution_context.create_physical_plan(&logical_plan).await
+    }
+}
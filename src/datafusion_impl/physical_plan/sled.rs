@@ -17,6 +17,7 @@
 
 //! Execution plan for reading CSV files
 use std::any::Any;
+use std::fmt;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
@@ -27,12 +28,17 @@ use arrow::record_batch::{RecordBatch};
 use async_trait::async_trait;
 use datafusion::error::{DataFusionError, Result};
 use datafusion::logical_plan::Expr;
-use datafusion::physical_plan::{Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics};
+use datafusion::physical_plan::metrics::{BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
+use datafusion::physical_plan::{ColumnStatistics, DisplayFormatType, Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics};
+use datafusion::scalar::ScalarValue;
 use datafusion::physical_plan::ExecutionPlan;
 use futures::Stream;
 
 use crate::core::global_context::GlobalContext;
-use crate::meta::{meta_def};
+use crate::core::profile::{METRIC_NAME_BATCHES_READ_AHEAD, METRIC_NAME_BYTES_SCANNED};
+use crate::meta::{meta_def, meta_util};
+use crate::store::reader::reader_util;
+use crate::store::mvcc::Snapshot;
 use crate::store::reader::sled::SledReader;
 
 #[derive(Debug, Clone)]
@@ -44,6 +50,12 @@ pub struct SledExec {
     projected_schema: SchemaRef,
     batch_size: usize,
     filters: Vec<Expr>,
+    /// The statistics of the last ANALYZE TABLE, they are estimates
+    statistics: Statistics,
+    /// The rows, the time and the bytes of the scan, read by SHOW PROFILE
+    metrics: ExecutionPlanMetricsSet,
+    /// The commit the rows are read at
+    snapshot: Snapshot,
 }
 
 impl SledExec {
@@ -54,12 +66,14 @@ impl SledExec {
         projection: Option<Vec<usize>>,
         batch_size: usize,
         filters: &[Expr],
+        snapshot: Snapshot,
     ) -> Result<Self> {
         let schema_ref = table_def.to_schema_ref();
         let projected_schema = match &projection {
             None => schema_ref,
             Some(p) => SchemaRef::new(Schema::new(p.iter().map(|i| schema_ref.field(*i).clone()).collect())),
         };
+        let statistics = projected_statistics(global_context.clone(), &table_def, &projected_schema);
 
         Ok(Self {
             global_context,
@@ -68,10 +82,48 @@ impl SledExec {
             projected_schema,
             batch_size,
             filters: filters.to_vec(),
+            statistics,
+            metrics: ExecutionPlanMetricsSet::new(),
+            snapshot,
         })
     }
 }
 
+fn projected_statistics(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_def: &meta_def::TableDef,
+    projected_schema: &SchemaRef,
+) -> Statistics {
+    let table_statistics = match meta_util::read_table_statistics(global_context, table_def) {
+        Ok(Some(table_statistics)) => table_statistics,
+        _ => return Statistics::default(),
+    };
+
+    let column_statistics = projected_schema
+        .fields()
+        .iter()
+        .map(|field| match table_statistics.column_statistics.get(field.name()) {
+            Some(column_statistics) => ColumnStatistics {
+                null_count: Some(column_statistics.null_count),
+                min_value: column_statistics.min_value.clone()
+                    .and_then(|value| ScalarValue::try_from_string(value, field.data_type()).ok()),
+                max_value: column_statistics.max_value.clone()
+                    .and_then(|value| ScalarValue::try_from_string(value, field.data_type()).ok()),
+                distinct_count: None,
+            },
+            None => ColumnStatistics::default(),
+        })
+        .collect();
+
+    // the rows may be changed after the analyze, so the statistics are never exact
+    Statistics {
+        num_rows: Some(table_statistics.num_rows),
+        total_byte_size: None,
+        column_statistics: Some(column_statistics),
+        is_exact: false,
+    }
+}
+
 #[async_trait]
 impl ExecutionPlan for SledExec {
     fn as_any(&self) -> &dyn Any {
@@ -107,25 +159,56 @@ impl ExecutionPlan for SledExec {
         }
     }
 
-    async fn execute(&self, _: usize) -> Result<SendableRecordBatchStream> {
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
         let reader = SledReader::new(
             self.global_context.clone(),
             self.table_def.clone(),
             self.batch_size,
             self.projection.clone(),
             self.filters.as_slice(),
+            self.snapshot.clone(),
         );
 
-        Ok(Box::pin(RocksdbStream { reader}))
+        Ok(Box::pin(RocksdbStream {
+            reader,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+            bytes_scanned: MetricBuilder::new(&self.metrics).counter(METRIC_NAME_BYTES_SCANNED, partition),
+            batches_read_ahead: MetricBuilder::new(&self.metrics).counter(METRIC_NAME_BATCHES_READ_AHEAD, partition),
+        }))
     }
 
     fn statistics(&self) -> Statistics {
-        Statistics::default()
+        self.statistics.clone()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                // the same seek as the reader chooses for the filters
+                let seek = reader_util::get_seek_prefix(
+                    self.global_context.clone(),
+                    self.table_def.option.full_table_name.clone(),
+                    self.table_def.clone(),
+                    self.filters.as_slice(),
+                );
+                match seek {
+                    Ok(seek_type) => write!(f, "SledExec: table={}, seek={}", self.table_def.option.full_table_name, seek_type),
+                    Err(_) => write!(f, "SledExec: table={}", self.table_def.option.full_table_name),
+                }
+            }
+        }
     }
 }
 
 struct RocksdbStream {
     reader: SledReader,
+    baseline_metrics: BaselineMetrics,
+    bytes_scanned: Count,
+    batches_read_ahead: Count,
 }
 
 impl Stream for RocksdbStream {
@@ -135,7 +218,21 @@ impl Stream for RocksdbStream {
         mut self: Pin<&mut Self>,
         _: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        Poll::Ready(self.reader.next())
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        let timer = elapsed_compute.timer();
+        let batch = self.reader.next();
+        timer.done();
+
+        // the counter only adds, the reader counts from the start of the scan
+        let bytes_scanned = self.reader.bytes_scanned();
+        self.bytes_scanned.add(bytes_scanned - self.bytes_scanned.value());
+        let batches_read_ahead = self.reader.batches_read_ahead();
+        self.batches_read_ahead.add(batches_read_ahead - self.batches_read_ahead.value());
+        match &batch {
+            Some(Ok(batch)) => self.baseline_metrics.record_output(batch.num_rows()),
+            _ => self.baseline_metrics.done(),
+        }
+        Poll::Ready(batch)
     }
 }
 
@@ -3,14 +3,22 @@ use std::sync::{Arc, Mutex};
 use datafusion::execution::context::ExecutionContext;
 use sqlparser::ast::{AlterTableOperation, ColumnDef, ObjectName};
 
+use crate::core::core_util;
 use crate::core::core_util::register_all_table;
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::session_context::SessionContext;
-use crate::meta::initial;
+use crate::execute_impl::insert::Insert;
+use crate::execute_impl::update::Update;
+use crate::meta::{initial, meta_const};
 use crate::meta::meta_def::{SparrowColumnDef, TableDef};
 use crate::meta::meta_util;
 use crate::meta::meta_util::load_all_table;
-use crate::mysql::error::{MysqlResult};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::store::compression;
+use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::util::alter_column_statement::ColumnPosition;
+use crate::util::dbkey;
 
 pub struct AddColumn {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -31,9 +39,11 @@ impl AddColumn {
         }
     }
 
-    pub fn execute(&mut self, table_name: ObjectName, column_def: ColumnDef) -> MysqlResult<u64> {
+    /// The columns after the position of the new column move one position further. The rows written before
+    /// the column are given its default
+    pub async fn execute(&mut self, table_name: ObjectName, column_def: ColumnDef, position: Option<ColumnPosition>) -> MysqlResult<u64> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
 
         let table_map = self
             .global_context
@@ -49,17 +59,48 @@ impl AddColumn {
             }
             Some(table_def) => table_def.clone(),
         };
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Alter,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
 
         let mut sparrow_column_list = vec![];
 
-        let before_sparrow_column = table_def.column.get_last_sparrow_column().unwrap();
-        let mut ordinal_position = before_sparrow_column.ordinal_position;
+        let before_ordinal_position = match position {
+            None => table_def.column.get_last_sparrow_column().unwrap().ordinal_position,
+            Some(ColumnPosition::First) => 0,
+            Some(ColumnPosition::After(column_name)) => {
+                let before_sparrow_column = table_def
+                    .get_columns()
+                    .iter()
+                    .find(|sparrow_column| sparrow_column.sql_column.name.value.to_lowercase() == column_name.to_lowercase());
+                match before_sparrow_column {
+                    Some(before_sparrow_column) => before_sparrow_column.ordinal_position,
+                    None => {
+                        let message = format!("Unknown column '{}' in '{}'", column_name, table_def.option.table_name);
+                        log::error!("{}", message);
+                        return Err(MysqlError::new_server_error(1054, "42S22", message.as_str()));
+                    }
+                }
+            }
+        };
+        let ordinal_position = before_ordinal_position + 1;
         let mut store_id = table_def.column.get_max_store_id();
-        ordinal_position += 1;
         store_id += 1;
+        let mut column_def = column_def;
+        column_def.data_type = meta_util::normalize_sql_data_type(&column_def.data_type)?;
+        let default_collation = meta_util::get_table_default_collation(self.global_context.clone(), &table_def.option)?;
+        meta_util::resolve_column_collation(&mut column_def, default_collation)?;
         let sparrow_column = SparrowColumnDef::new(store_id, ordinal_position, column_def.clone());
         sparrow_column_list.push(sparrow_column.clone());
 
+        if table_def.column.get_last_sparrow_column().unwrap().ordinal_position > before_ordinal_position {
+            self.move_columns(&table_def, before_ordinal_position).await?;
+        }
+
         let result = initial::add_information_schema_columns(self.global_context.clone(), table_def.option.clone(), sparrow_column_list);
         if let Err(mysql_error) = result {
             return Err(mysql_error);
@@ -70,11 +111,71 @@ impl AddColumn {
             return Err(mysql_error);
         }
 
-        let result = register_all_table(self.global_context.clone(), &mut self.execution_context.clone());
+        let result = register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context.clone());
         if let Err(mysql_error) = result {
             return Err(mysql_error);
         }
 
+        let table_def = meta_util::get_table(self.global_context.clone(), full_table_name)?;
+        self.backfill_default(&table_def, &sparrow_column)?;
+
         Ok(1)
     }
+
+    /// The columns after the ordinal position move one position further in information_schema.columns
+    async fn move_columns(&mut self, table_def: &TableDef, ordinal_position: i64) -> MysqlResult<u64> {
+        let metadata_table_name = meta_util::create_full_table_name(
+            meta_const::CATALOG_NAME,
+            meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS,
+        );
+        let assignments = core_util::build_update_column_assignments();
+        let selection = core_util::build_find_column_ordinal_position_sqlwhere(
+            table_def.option.catalog_name.as_ref(),
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+            ordinal_position,
+        );
+        let mut update_set = Update::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.execution_context.clone(),
+        );
+        let (count, _) = update_set.execute(metadata_table_name, assignments, Some(selection)).await?;
+        Ok(count)
+    }
+
+    /// Write the default of the new column into the rows written before the column, a select of the old rows
+    /// gives the default instead of NULL. The rows written since the column was added have a value already
+    fn backfill_default(&self, table_def: &TableDef, sparrow_column: &SparrowColumnDef) -> MysqlResult<u64> {
+        let insert = Insert::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.execution_context.clone(),
+        );
+        let default_value = match insert.column_default_value(table_def, sparrow_column)? {
+            None => return Ok(0),
+            Some(default_value) => default_value,
+        };
+        let default_value = compression::compress_column_value(&table_def.option, default_value.as_bytes());
+
+        // the writers wait for the backfill
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.write().unwrap();
+
+        let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table_def.clone())?;
+        let mut key_values = vec![];
+        for rowid in core_util::scan_rowids(store_engine.as_ref(), table_def)? {
+            let column_key = dbkey::create_column_key(&table_def.option, sparrow_column.store_id, rowid.as_str());
+            if store_engine.get_key(column_key.clone())?.is_none() {
+                key_values.push((column_key, default_value.clone()));
+            }
+        }
+        if key_values.is_empty() {
+            return Ok(0);
+        }
+        let count = key_values.len() as u64;
+        store_engine.put_keys(key_values)?;
+        Ok(count)
+    }
 }
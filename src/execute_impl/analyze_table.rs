@@ -0,0 +1,57 @@
+use std::sync::{Arc, Mutex};
+
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::ObjectName;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::output::ResultSet;
+use crate::core::session_context::SessionContext;
+use crate::meta::meta_util;
+use crate::mysql::error::MysqlResult;
+
+pub struct AnalyzeTable {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl AnalyzeTable {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    pub fn execute(&mut self, table_name: ObjectName) -> MysqlResult<ResultSet> {
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        let table_def = meta_util::get_table(self.global_context.clone(), full_table_name.clone())?;
+
+        let table_statistics = meta_util::analyze_table(self.global_context.clone(), &table_def)?;
+        meta_util::save_table_statistics(self.global_context.clone(), &table_def, &table_statistics)?;
+
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new("Table", DataType::Utf8, false),
+            Field::new("Op", DataType::Utf8, false),
+            Field::new("Msg_type", DataType::Utf8, false),
+            Field::new("Msg_text", DataType::Utf8, false),
+        ]));
+        let table = format!("{}.{}", table_def.option.schema_name, table_def.option.table_name);
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(vec![table.as_str()])),
+            Arc::new(StringArray::from(vec!["analyze"])),
+            Arc::new(StringArray::from(vec!["status"])),
+            Arc::new(StringArray::from(vec!["OK"])),
+        ]).unwrap();
+
+        Ok(ResultSet::new(schema, vec![record_batch]))
+    }
+}
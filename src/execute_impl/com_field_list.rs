@@ -39,7 +39,7 @@ impl ComFieldList {
         table_name: ObjectName,
     ) -> MysqlResult<(ObjectName, ObjectName, TableDef)> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
 
         let schema_name = meta_util::cut_out_schema_name(full_table_name.clone());
         let table_name = meta_util::cut_out_table_name(full_table_name.clone());
@@ -6,8 +6,7 @@ use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use datafusion::execution::context::ExecutionContext;
 use datafusion::sql::parser::Statement as DFStatement;
-use sqlparser::ast::{ColumnDef, DataType as SQLDataType, Ident};
-use sqlparser::ast::{ObjectName, SetExpr, Statement as SQLStatement, Value, Expr as SQLExpr};
+use sqlparser::ast::{ColumnDef, DataType as SQLDataType};
 
 use crate::core::core_def::StmtCacheDef;
 use crate::core::global_context::GlobalContext;
@@ -19,6 +18,7 @@ use crate::meta::meta_util;
 use crate::mysql::error::MysqlResult;
 use crate::mysql::metadata::Column;
 use crate::util::convert::ToObjectName;
+use crate::util::placeholder;
 
 pub struct ComStmtPrepare {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -39,50 +39,26 @@ impl ComStmtPrepare {
         }
     }
 
-    pub async fn execute(&mut self, stmt_context: &mut StmtContext, df_statements: Vec<DFStatement>) -> MysqlResult<StmtPrepare> {
-        let mut stmt_cache = StmtCacheDef::new(df_statements.clone());
+    /// Every `?` placeholder of the sql is a parameter, the values are bound to the sql at the execution
+    pub async fn execute(&mut self, stmt_context: &mut StmtContext, sql: &str, df_statements: Vec<DFStatement>) -> MysqlResult<StmtPrepare> {
+        let mut stmt_cache = StmtCacheDef::new(sql, df_statements);
 
         let schema_name = "schema".to_object_name();
         let table_name = "table".to_object_name();
 
-        let mut params = vec![];
-
-        for sql_statement in df_statements.clone() {
-            match sql_statement.clone() {
-                DFStatement::Statement(statement) => match statement {
-                    SQLStatement::Insert { source, .. } => match &source.body {
-                        SetExpr::Values(values) => {
-                            for row in values.0.iter() {
-                                for v in row {
-                                    match v {
-                                        SQLExpr::Value(Value::OnlyString(_)) => {
-                                            let column_def = ColumnDef {
-                                                name: "name".into(),
-                                                data_type: SQLDataType::Char(Some(100)),
-                                                collation: None,
-                                                options: vec![],
-                                            };
-                                            let sparrow_column_def =
-                                                SparrowColumnDef::new(1, 1, column_def);
-                                            let column = Column::new(
-                                                schema_name.clone(),
-                                                table_name.clone(),
-                                                &sparrow_column_def,
-                                            );
-                                            params.push(column);
-                                        }
-                                        _ => {},
-                                    }
-                                }
-                            }
-                        }
-                        _ => {}
-                    },
-                    _ => {}
-                },
-                _ => {}
-            };
-        }
+        let num_params = placeholder::placeholder_offsets(sql).len();
+        let params = (0..num_params)
+            .map(|_| {
+                let column_def = ColumnDef {
+                    name: "?".into(),
+                    data_type: SQLDataType::Char(None),
+                    collation: None,
+                    options: vec![],
+                };
+                let sparrow_column_def = SparrowColumnDef::new(1, 1, column_def);
+                Column::new(schema_name.clone(), table_name.clone(), &sparrow_column_def)
+            })
+            .collect::<Vec<_>>();
 
         stmt_cache.set_num_params(params.len());
         stmt_context.add_stmt(stmt_cache);
@@ -8,10 +8,12 @@ use datafusion::execution::context::ExecutionContext;
 use sqlparser::ast::{Ident, ObjectName};
 
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::output::ResultSet;
 use crate::core::session_context::SessionContext;
-use crate::mysql::error::MysqlResult;
+use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::meta::{meta_util, initial, meta_const};
+use crate::meta::meta_def::{SchemaDef, SchemaOptionDef};
 use crate::core::core_util;
 
 pub struct CreateDb {
@@ -33,16 +35,53 @@ impl CreateDb {
         }
     }
 
-    pub fn execute(&mut self, db_name: ObjectName) -> MysqlResult<u64> {
+    pub fn execute(
+        &mut self,
+        db_name: ObjectName,
+        if_not_exists: bool,
+        character_set_name: Option<String>,
+        collation_name: Option<String>,
+    ) -> MysqlResult<u64> {
         let full_schema_name = meta_util::fill_up_schema_name(&mut self.session_context, db_name.clone()).unwrap();
 
         let db_name = meta_util::cut_out_schema_name(full_schema_name.clone());
+        privilege::check_schema_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Create,
+            db_name.to_string().as_str(),
+        )?;
 
-        let result = initial::create_schema(self.global_context.clone(), full_schema_name);
+        if meta_util::get_schema(self.global_context.clone(), full_schema_name.clone()).is_ok() {
+            if if_not_exists {
+                return Ok(0);
+            }
+            return Err(MysqlError::new_server_error(
+                1007,
+                "HY000",
+                format!("Can't create database '{}'; database exists", db_name).as_str(),
+            ));
+        }
+
+        let (character_set_name, collation_name) = meta_util::resolve_schema_charset(character_set_name, collation_name)?;
+
+        let result = initial::create_schema(
+            self.global_context.clone(),
+            full_schema_name.clone(),
+            character_set_name.as_str(),
+            collation_name.as_str(),
+        );
         if let Err(e) = result {
             return Err(e);
         }
 
+        let mut schema_option = SchemaOptionDef::new(meta_const::CATALOG_NAME, db_name.to_string().as_str());
+        schema_option.with_default_character_set_name(character_set_name.as_str());
+        schema_option.with_default_collation_name(collation_name.as_str());
+        let mut schema = SchemaDef::new(full_schema_name.clone());
+        schema.with_schema_option(schema_option);
+        self.global_context.lock().unwrap().meta_data.add_schema(full_schema_name.clone(), schema);
+
         core_util::register_schema(&mut self.execution_context, meta_const::CATALOG_NAME, db_name.to_string().as_str());
 
         Ok(1)
@@ -0,0 +1,288 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::{Expr as SQLExpr, Ident, ObjectName, OrderByExpr, TableConstraint};
+
+use crate::core::core_util;
+use crate::core::core_util::register_all_table;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::meta::meta_const;
+use crate::meta::meta_def::TableIndexDef;
+use crate::meta::meta_util;
+use crate::meta::meta_util::load_all_table;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::util::dbkey;
+
+/// The rows of the table indexed by a batch of the index build, the writers run between the batches
+const INDEX_BUILD_BATCH_SIZE: usize = 1024;
+
+pub struct CreateIndex {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl CreateIndex {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// Build the index online, the rows of the table are indexed in batches
+    /// while the inserts and updates go on and write the index entries of their rows
+    pub async fn execute(
+        &mut self,
+        index_name: ObjectName,
+        table_name: ObjectName,
+        columns: Vec<OrderByExpr>,
+        unique: bool,
+        if_not_exists: bool,
+    ) -> MysqlResult<u64> {
+        if !unique {
+            // the index keys are unique, the rows with the same values can not be told apart yet
+            return Err(MysqlError::new_server_error(
+                1235,
+                "42000",
+                "This version of MySQL doesn't yet support 'CREATE INDEX without UNIQUE'",
+            ));
+        }
+
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Index,
+            meta_util::cut_out_schema_name(full_table_name.clone()).to_string().as_str(),
+            meta_util::cut_out_table_name(full_table_name.clone()).to_string().as_str(),
+        )?;
+        let index_name = match index_name.0.last() {
+            Some(ident) => ident.value.clone(),
+            None => {
+                return Err(MysqlError::new_server_error(
+                    1280,
+                    "42000",
+                    "Incorrect index name ''",
+                ));
+            }
+        };
+
+        let mut column_name_list = vec![];
+        for column in columns {
+            match column.expr {
+                SQLExpr::Identifier(ident) => column_name_list.push(ident),
+                expr => {
+                    let message = format!("Key column '{}' doesn't exist in table", expr);
+                    log::error!("{}", message);
+                    return Err(MysqlError::new_server_error(1072, "42000", message.as_str()));
+                }
+            }
+        }
+
+        let result = IndexBuild::start(self.global_context.clone(), full_table_name, index_name.as_str(), column_name_list);
+        let mut index_build = match result {
+            Err(mysql_error) if if_not_exists && mysql_error.error_number() == 1061 => return Ok(0),
+            Err(mysql_error) => return Err(mysql_error),
+            Ok(index_build) => index_build,
+        };
+
+        loop {
+            match index_build.backfill_batch(INDEX_BUILD_BATCH_SIZE) {
+                Ok(true) => break,
+                Ok(false) => tokio::task::yield_now().await,
+                Err(mysql_error) => {
+                    index_build.abort()?;
+                    return Err(mysql_error);
+                }
+            }
+        }
+        index_build.finish()?;
+
+        register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context)?;
+
+        Ok(0)
+    }
+}
+
+/// An index being built, the writers maintain it from the start
+/// and the rows written before the start are indexed batch by batch
+pub struct IndexBuild {
+    global_context: Arc<Mutex<GlobalContext>>,
+    full_table_name: ObjectName,
+    table_constraint: TableConstraint,
+    table_index: TableIndexDef,
+    /// The rowid key of the last row indexed
+    last_rowid_key: Option<String>,
+}
+
+impl IndexBuild {
+    /// Register the index being built, the rows written from now on have their index entries
+    pub fn start(
+        global_context: Arc<Mutex<GlobalContext>>,
+        full_table_name: ObjectName,
+        index_name: &str,
+        column_name_list: Vec<Ident>,
+    ) -> MysqlResult<Self> {
+        let index_build_lock = global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.write().unwrap();
+
+        let table = meta_util::get_table(global_context.clone(), full_table_name.clone())?;
+
+        // the indexes of the table and the ones being built by the other sessions
+        let table_index_list = meta_util::get_table_index_list(global_context.clone(), full_table_name.clone())?;
+        let foreign_key_index_names: Vec<String> = table_index_list
+            .iter()
+            .filter(|table_index| table_index.level == meta_const::INDEX_LEVEL_OF_FOREIGN_KEY)
+            .map(|table_index| table_index.index_name.to_lowercase())
+            .collect();
+        let mut existing_index_list: Vec<(String, Vec<Ident>)> = table_index_list
+            .into_iter()
+            .map(|table_index| (table_index.index_name, table_index.column_name_list))
+            .collect();
+        if let Some(building_index_list) = global_context.lock().unwrap().building_index_map.get(&full_table_name) {
+            for table_constraint in building_index_list {
+                if let TableConstraint::Unique { name: Some(name), columns, .. } = table_constraint {
+                    existing_index_list.push((name.value.clone(), columns.clone()));
+                }
+            }
+        }
+        if existing_index_list.iter().any(|(existing_index_name, _)| existing_index_name.to_lowercase() == index_name.to_lowercase()) {
+            let message = format!("Duplicate key name '{}'", index_name);
+            log::error!("{}", message);
+            return Err(MysqlError::new_server_error(1061, "42000", message.as_str()));
+        }
+
+        let mut index_column_list = vec![];
+        for column_name in column_name_list {
+            match table.get_table_column().get_sparrow_column(column_name.clone()) {
+                Ok(sparrow_column) => index_column_list.push(sparrow_column.sql_column.name),
+                Err(_) => {
+                    let message = format!("Key column '{}' doesn't exist in table", column_name);
+                    log::error!("{}", message);
+                    return Err(MysqlError::new_server_error(1072, "42000", message.as_str()));
+                }
+            }
+        }
+
+        // the index over the same columns in the same order only doubles the writes,
+        // the index of a foreign key is not unique
+        let duplicate_index = existing_index_list.iter().find(|(existing_index_name, existing_column_list)| {
+            !foreign_key_index_names.contains(&existing_index_name.to_lowercase())
+                && existing_column_list.len() == index_column_list.len()
+                && existing_column_list.iter().zip(index_column_list.iter()).all(|(a, b)| a.value.to_lowercase() == b.value.to_lowercase())
+        });
+        if let Some((existing_index_name, _)) = duplicate_index {
+            let message = format!("Duplicate key name '{}', the index '{}' has the same columns", index_name, existing_index_name);
+            log::error!("{}", message);
+            return Err(MysqlError::new_server_error(1061, "42000", message.as_str()));
+        }
+
+        let table_constraint = TableConstraint::Unique {
+            name: Some(Ident::new(index_name)),
+            columns: index_column_list.clone(),
+            is_primary: false,
+        };
+        let table_index = TableIndexDef {
+            index_name: index_name.to_string(),
+            level: 2,
+            column_name_list: index_column_list,
+        };
+
+        global_context
+            .lock()
+            .unwrap()
+            .building_index_map
+            .entry(full_table_name.clone())
+            .or_insert(vec![])
+            .push(table_constraint.clone());
+
+        Ok(Self {
+            global_context,
+            full_table_name,
+            table_constraint,
+            table_index,
+            last_rowid_key: None,
+        })
+    }
+
+    /// Index the next rows written before the start, returns true when all the rows are indexed.
+    /// The writers wait for the batch, and a row indexed by a writer already is indexed again with the same key
+    pub fn backfill_batch(&mut self, batch_size: usize) -> MysqlResult<bool> {
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.write().unwrap();
+
+        let table = meta_util::get_table(self.global_context.clone(), self.full_table_name.clone())?;
+        let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table.clone())?;
+
+        let prefix = dbkey::scan_record_rowid(&table.option);
+        let rowid_key_list = store_engine.scan_prefix_keys(prefix.clone(), self.last_rowid_key.clone(), batch_size)?;
+        for rowid_key in rowid_key_list.iter() {
+            let rowid = &rowid_key[prefix.len()..];
+            let index_keys = core_util::read_index_keys(&table, &[self.table_index.clone()], store_engine.as_ref(), rowid)?;
+            for index_key in index_keys.into_iter().flatten() {
+                match store_engine.get_key(index_key.clone())? {
+                    Some(value) if value != rowid.as_bytes() => {
+                        let values = core_util::read_index_values_with(&table, &self.table_index, rowid, &|key| store_engine.get_key(key))?;
+                        return Err(core_util::duplicate_entry_error(
+                            table.option.table_name.as_str(),
+                            self.table_index.index_name.as_str(),
+                            values,
+                        ));
+                    }
+                    _ => store_engine.put_key(index_key, rowid.as_bytes())?,
+                }
+            }
+        }
+
+        if let Some(rowid_key) = rowid_key_list.last() {
+            self.last_rowid_key = Some(rowid_key.clone());
+        }
+        Ok(rowid_key_list.len() < batch_size)
+    }
+
+    /// Save the index to the table, the scans use it from now on
+    pub fn finish(self) -> MysqlResult<()> {
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.write().unwrap();
+
+        let table = meta_util::get_table(self.global_context.clone(), self.full_table_name.clone())?;
+        meta_util::save_table_constraint(self.global_context.clone(), table.option.clone(), vec![self.table_constraint.clone()])?;
+        load_all_table(self.global_context.clone())?;
+
+        self.remove_building_index();
+        Ok(())
+    }
+
+    /// Give up the index, the entries written for it are removed
+    pub fn abort(self) -> MysqlResult<()> {
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.write().unwrap();
+
+        self.remove_building_index();
+
+        let table = meta_util::get_table(self.global_context.clone(), self.full_table_name.clone())?;
+        let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table.clone())?;
+        let index_prefix = format!("{}{}/", dbkey::scan_table_index_key(&table.option), self.table_index.index_name);
+        store_engine.delete_prefix(index_prefix)?;
+        Ok(())
+    }
+
+    fn remove_building_index(&self) {
+        let mut gc = self.global_context.lock().unwrap();
+        if let Some(building_index_list) = gc.building_index_map.get_mut(&self.full_table_name) {
+            building_index_list.retain(|table_constraint| *table_constraint != self.table_constraint);
+            if building_index_list.is_empty() {
+                gc.building_index_map.remove(&self.full_table_name);
+            }
+        }
+    }
+}
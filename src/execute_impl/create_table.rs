@@ -5,22 +5,28 @@ use arrow::datatypes::SchemaRef;
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use datafusion::execution::context::ExecutionContext;
-use sqlparser::ast::{Ident, ObjectName, TableConstraint, SqlOption, ColumnDef};
+use sqlparser::ast::{Ident, ObjectName, TableConstraint, SqlOption, ColumnDef, ColumnOption, DataType as SQLDataType};
 
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::output::ResultSet;
 use crate::core::session_context::SessionContext;
-use crate::mysql::error::MysqlResult;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
 use crate::meta::{meta_util, initial, meta_const};
 use crate::core::core_util;
-use crate::meta::meta_def::{SparrowColumnDef, TableOptionDef};
+use crate::meta::meta_def::{ForeignKeyDef, ReferentialAction, SparrowColumnDef, TableIndexDef, TableOptionDef};
 use crate::meta::meta_util::load_all_table;
 use crate::core::core_util::register_all_table;
+use crate::store::compression;
+use crate::util::decimal;
+use crate::util::create_table_statement::ForeignKeyClause;
 
 pub struct CreateTable {
     global_context: Arc<Mutex<GlobalContext>>,
     session_context: SessionContext,
     execution_context: ExecutionContext,
+    foreign_keys: Vec<ForeignKeyClause>,
 }
 
 impl CreateTable {
@@ -33,23 +39,85 @@ impl CreateTable {
             global_context,
             session_context,
             execution_context,
+            foreign_keys: vec![],
         }
     }
 
+    pub fn with_foreign_keys(&mut self, foreign_keys: Vec<ForeignKeyClause>) {
+        self.foreign_keys = foreign_keys
+    }
+
     pub fn execute(&mut self, table_name: ObjectName,
                    sql_column_list: Vec<ColumnDef>,
                    constraints: Vec<TableConstraint>,
                    table_options: Vec<SqlOption>) -> MysqlResult<u64> {
-        let full_table_name = meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
 
         let catalog_name = meta_util::cut_out_catalog_name(full_table_name.clone());
         let schema_name = meta_util::cut_out_schema_name(full_table_name.clone());
         let table_name = meta_util::cut_out_table_name(full_table_name.clone());
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Create,
+            schema_name.to_string().as_str(),
+            table_name.to_string().as_str(),
+        )?;
+
+        // the tables and the views share their names
+        if self.global_context.lock().unwrap().meta_data.get_view(full_table_name.clone()).is_some() {
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_TABLE_EXISTS_ERROR as u16,
+                "42S01",
+                format!("Table '{}' already exists", table_name).as_str(),
+            ));
+        }
+
+        let mut table_option = TableOptionDef::new(catalog_name.to_string().as_str(), schema_name.to_string().as_str(), table_name.to_string().as_str());
+        table_option.load_table_options(table_options.clone());
+
+        // the text columns without a collation have the one of the table, else the one of the schema
+        let default_collation = meta_util::get_table_default_collation(self.global_context.clone(), &table_option)?;
 
         let mut sparrow_column_list = vec![];
         let mut ordinal_position = 0;
         let mut store_id = 0;
-        for sql_column in sql_column_list.clone() {
+        for mut sql_column in sql_column_list.clone() {
+            sql_column.data_type = meta_util::normalize_sql_data_type(&sql_column.data_type)?;
+            if meta_util::column_is_zerofill(&sql_column) {
+                match sql_column.data_type {
+                    SQLDataType::Int(width) => {
+                        sql_column.data_type = SQLDataType::Int(Some(width.unwrap_or(meta_const::DISPLAY_WIDTH_OF_ZEROFILL_INT)));
+                    }
+                    _ => {
+                        return Err(MysqlError::new_server_error(
+                            1235,
+                            "42000",
+                            "This version of MySQL doesn't yet support 'ZEROFILL on columns other than INT'",
+                        ));
+                    }
+                }
+            }
+            if let SQLDataType::Decimal(precision, scale) = &sql_column.data_type {
+                let (precision, scale) = decimal::precision_scale(precision, scale);
+                if precision > decimal::MAX_PRECISION {
+                    return Err(MysqlError::new_server_error(
+                        1426,
+                        "42000",
+                        format!("Too-big precision {} specified for '{}'. Maximum is {}.", precision, sql_column.name, decimal::MAX_PRECISION).as_str(),
+                    ));
+                }
+                if scale > precision {
+                    return Err(MysqlError::new_server_error(
+                        1427,
+                        "42000",
+                        format!("For float(M,D), double(M,D) or decimal(M,D), M must be >= D (column '{}').", sql_column.name).as_str(),
+                    ));
+                }
+            }
+
+            meta_util::resolve_column_collation(&mut sql_column, default_collation)?;
+
             ordinal_position += 1;
             store_id += 1;
 
@@ -58,14 +126,69 @@ impl CreateTable {
         }
         let column_store_id = store_id;
 
-        let mut table_option = TableOptionDef::new(catalog_name.to_string().as_str(), schema_name.to_string().as_str(), table_name.to_string().as_str());
-        table_option.load_table_options(table_options.clone());
+        // the primary key is one, either on a column or over the columns of the table
+        let column_primary_key_count = sql_column_list.iter().filter(|sql_column| {
+            sql_column.options.iter().any(|option| option.option == ColumnOption::Unique { is_primary: true })
+        }).count();
+        let table_primary_key_count = constraints.iter().filter(|constraint| match constraint {
+            TableConstraint::Unique { is_primary, .. } => *is_primary,
+            _ => false,
+        }).count();
+        if column_primary_key_count + table_primary_key_count > 1 {
+            return Err(MysqlError::new_server_error(
+                1068,
+                "42000",
+                "Multiple primary key defined",
+            ));
+        }
+        for constraint in constraints.iter() {
+            if let TableConstraint::Unique { columns, .. } = constraint {
+                for column_name in columns {
+                    let column_exists = sql_column_list.iter().any(|sql_column| {
+                        sql_column.name.value.to_lowercase() == column_name.value.to_lowercase()
+                    });
+                    if !column_exists {
+                        let message = format!("Key column '{}' doesn't exist in table", column_name);
+                        log::error!("{}", message);
+                        return Err(MysqlError::new_server_error(1072, "42000", message.as_str()));
+                    }
+                }
+            }
+        }
+
         table_option.with_table_type(meta_const::VALUE_OF_TABLE_OPTION_TABLE_TYPE_BASE_TABLE);
         table_option.with_column_max_store_id(column_store_id);
+        if table_option.without_rowid {
+            let has_primary_key = constraints.iter().any(|constraint| match constraint {
+                TableConstraint::Unique { is_primary, .. } => *is_primary,
+                _ => false,
+            });
+            if !has_primary_key {
+                return Err(MysqlError::new_server_error(
+                    1173,
+                    "42000",
+                    "This table type requires a primary key",
+                ));
+            }
+        }
         if table_option.engine.is_empty() {
             let mutex_guard_global_context = self.global_context.lock().unwrap();
             table_option.with_engine(mutex_guard_global_context.my_config.server.engines.first().unwrap())
         }
+        if let Some(compression) = &table_option.compression {
+            if !compression::is_supported_compression(compression.as_str()) {
+                return Err(MysqlError::new_server_error(
+                    1478,
+                    "HY000",
+                    format!("Table storage engine '{}' does not support the create option 'compression={}'", table_option.engine, compression).as_str(),
+                ));
+            }
+        }
+
+        let foreign_keys = self.resolve_foreign_keys(&full_table_name, &sparrow_column_list, &constraints)?;
+
+        // the keys of the rows name the table by its id, the table is renamed without moving them
+        table_option.with_table_id(meta_util::next_table_id(self.global_context.clone())?);
 
         let result = initial::add_information_schema_columns(self.global_context.clone(), table_option.clone(), sparrow_column_list);
         if let Err(e) = result {
@@ -77,6 +200,10 @@ impl CreateTable {
             return Err(mysql_error);
         }
 
+        if !foreign_keys.is_empty() {
+            meta_util::save_table_foreign_keys(self.global_context.clone(), table_option.clone(), foreign_keys)?;
+        }
+
         let result = initial::add_information_schema_tables(self.global_context.clone(), table_option.clone());
         if let Err(e) = result {
             return Err(e);
@@ -87,11 +214,237 @@ impl CreateTable {
             return Err(mysql_error);
         }
 
-        let result = register_all_table(self.global_context.clone(), &mut self.execution_context);
+        let result = register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context);
         if let Err(mysql_error) = result {
             return Err(mysql_error);
         }
 
         Ok(1)
     }
+
+    /// The FOREIGN KEY constraints checked against the columns and the indexes of the referenced tables,
+    /// each with the name of the index of the referenced table which holds the referenced columns
+    fn resolve_foreign_keys(
+        &mut self,
+        full_table_name: &ObjectName,
+        sparrow_column_list: &Vec<SparrowColumnDef>,
+        constraints: &Vec<TableConstraint>,
+    ) -> MysqlResult<Vec<(ForeignKeyDef, String)>> {
+        let schema_name = meta_util::cut_out_schema_name(full_table_name.clone());
+        let table_name = meta_util::cut_out_table_name(full_table_name.clone()).to_string();
+
+        // the names of the foreign keys are unique in the schema
+        let mut constraint_name_list = vec![];
+        let table_map = self.global_context.lock().unwrap().meta_data.get_table_map();
+        for (other_table_name, table_def) in table_map.iter() {
+            if meta_util::cut_out_schema_name(other_table_name.clone()) == schema_name {
+                for foreign_key in table_def.get_foreign_keys() {
+                    constraint_name_list.push(foreign_key.constraint_name.to_lowercase());
+                }
+            }
+        }
+
+        let mut foreign_keys = vec![];
+        let mut generated_count = 0;
+        for foreign_key_clause in self.foreign_keys.clone() {
+            let constraint_name = match foreign_key_clause.constraint_name.clone() {
+                Some(constraint_name) => constraint_name,
+                None => {
+                    generated_count += 1;
+                    format!("{}{}{}", table_name, meta_const::NAME_INFIX_OF_FOREIGN_KEY, generated_count)
+                }
+            };
+            if constraint_name_list.contains(&constraint_name.to_lowercase()) {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_FK_DUP_NAME as u16,
+                    "HY000",
+                    format!("Duplicate foreign key constraint name '{}'", constraint_name).as_str(),
+                ));
+            }
+            constraint_name_list.push(constraint_name.to_lowercase());
+
+            if foreign_key_clause.column_names.len() != foreign_key_clause.referenced_column_names.len() {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_WRONG_FK_DEF as u16,
+                    "42000",
+                    format!("Incorrect foreign key definition for '{}': Key reference and table reference don't match", constraint_name).as_str(),
+                ));
+            }
+
+            let referenced_table_name = ObjectName(foreign_key_clause.referenced_table_name.iter().map(|name| Ident::new(name.as_str())).collect());
+            let referenced_table_name = meta_util::resolve_table_name(&mut self.session_context, &referenced_table_name)?;
+
+            // a table may refer to itself, its columns and its indexes are not saved yet
+            let (referenced_sql_column_list, referenced_index_list) = if referenced_table_name == *full_table_name {
+                let sql_column_list = sparrow_column_list.iter().map(|sparrow_column| sparrow_column.sql_column.clone()).collect::<Vec<_>>();
+                (sql_column_list.clone(), table_index_list(&sql_column_list, constraints))
+            } else {
+                let referenced_table = match meta_util::get_table(self.global_context.clone(), referenced_table_name.clone()) {
+                    Ok(referenced_table) => referenced_table,
+                    Err(_) => {
+                        return Err(MysqlError::new_server_error(
+                            mysql_error_code::ER_FK_CANNOT_OPEN_PARENT as u16,
+                            "HY000",
+                            format!("Failed to open the referenced table '{}'", meta_util::cut_out_table_name(referenced_table_name.clone())).as_str(),
+                        ));
+                    }
+                };
+                let index_list = meta_util::get_table_index_list(self.global_context.clone(), referenced_table_name.clone())?;
+                (referenced_table.get_table_column().sql_column_list, index_list)
+            };
+
+            let on_delete = referential_action(foreign_key_clause.on_delete.as_ref())?;
+            let on_update = referential_action(foreign_key_clause.on_update.as_ref())?;
+
+            let mut column_name_list = vec![];
+            let mut referenced_column_name_list = vec![];
+            let column_pairs = foreign_key_clause.column_names.iter().zip(foreign_key_clause.referenced_column_names.iter());
+            for (column_name, referenced_column_name) in column_pairs {
+                let sql_column = match sparrow_column_list.iter().find(|sparrow_column| {
+                    sparrow_column.sql_column.name.value.to_lowercase() == column_name.to_lowercase()
+                }) {
+                    Some(sparrow_column) => sparrow_column.sql_column.clone(),
+                    None => {
+                        let message = format!("Key column '{}' doesn't exist in table", column_name);
+                        log::error!("{}", message);
+                        return Err(MysqlError::new_server_error(1072, "42000", message.as_str()));
+                    }
+                };
+                let referenced_sql_column = match referenced_sql_column_list.iter().find(|sql_column| {
+                    sql_column.name.value.to_lowercase() == referenced_column_name.to_lowercase()
+                }) {
+                    Some(sql_column) => sql_column.clone(),
+                    None => {
+                        return Err(MysqlError::new_server_error(
+                            mysql_error_code::ER_FK_NO_COLUMN_PARENT as u16,
+                            "HY000",
+                            format!(
+                                "Failed to add the foreign key constraint. Missing column '{}' for constraint '{}' in the referenced table '{}'",
+                                referenced_column_name,
+                                constraint_name,
+                                meta_util::cut_out_table_name(referenced_table_name.clone()),
+                            ).as_str(),
+                        ));
+                    }
+                };
+
+                let data_type = meta_util::convert_sql_data_type_to_arrow_data_type(&sql_column.data_type)?;
+                let referenced_data_type = meta_util::convert_sql_data_type_to_arrow_data_type(&referenced_sql_column.data_type)?;
+                if data_type != referenced_data_type {
+                    return Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_FK_INCOMPATIBLE_COLUMNS as u16,
+                        "HY000",
+                        format!(
+                            "Referencing column '{}' and referenced column '{}' in foreign key constraint '{}' are incompatible.",
+                            sql_column.name, referenced_sql_column.name, constraint_name,
+                        ).as_str(),
+                    ));
+                }
+
+                let not_null = sql_column.options.iter().any(|option| {
+                    option.option == ColumnOption::NotNull || option.option == ColumnOption::Unique { is_primary: true }
+                });
+                if not_null && (on_delete == ReferentialAction::SetNull || on_update == ReferentialAction::SetNull) {
+                    return Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_FK_COLUMN_NOT_NULL as u16,
+                        "HY000",
+                        format!("Column '{}' cannot be NOT NULL: needed in a foreign key constraint '{}' SET NULL", sql_column.name, constraint_name).as_str(),
+                    ));
+                }
+
+                column_name_list.push(sql_column.name.clone());
+                referenced_column_name_list.push(referenced_sql_column.name.clone());
+            }
+
+            // the referenced columns are the leading columns of a unique index, the parent rows are found by it.
+            // The rows of the table are found by the index of the foreign key over its columns
+            let referenced_index = referenced_index_list.iter().find(|table_index| {
+                table_index.level != meta_const::INDEX_LEVEL_OF_FOREIGN_KEY
+                    && table_index.column_name_list.len() >= referenced_column_name_list.len()
+                    && referenced_column_name_list.iter().zip(table_index.column_name_list.iter()).all(|(referenced_column_name, index_column_name)| {
+                    referenced_column_name.value.to_lowercase() == index_column_name.value.to_lowercase()
+                })
+            });
+            let unique_constraint_name = match referenced_index {
+                Some(table_index) => table_index.index_name.clone(),
+                None => {
+                    return Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_FK_NO_INDEX_PARENT as u16,
+                        "HY000",
+                        format!(
+                            "Failed to add the foreign key constraint. Missing index for constraint '{}' in the referenced table '{}'",
+                            constraint_name,
+                            meta_util::cut_out_table_name(referenced_table_name.clone()),
+                        ).as_str(),
+                    ));
+                }
+            };
+
+            let foreign_key = ForeignKeyDef {
+                constraint_name,
+                column_name_list,
+                referenced_table_name,
+                referenced_column_name_list,
+                on_delete,
+                on_update,
+            };
+            foreign_keys.push((foreign_key, unique_constraint_name));
+        }
+
+        Ok(foreign_keys)
+    }
+}
+
+/// The action of the rule of ON DELETE or ON UPDATE, NO ACTION if the rule is not given
+fn referential_action(rule: Option<&String>) -> MysqlResult<ReferentialAction> {
+    match rule {
+        None => Ok(ReferentialAction::NoAction),
+        Some(rule) => match ReferentialAction::from_rule(rule.as_str()) {
+            Some(referential_action) => Ok(referential_action),
+            None => Err(MysqlError::new_server_error(
+                mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                "42000",
+                format!("This version of MySQL doesn't yet support '{} in foreign key'", rule).as_str(),
+            )),
+        },
+    }
+}
+
+/// The indexes of the table which is being created, the ones of its constraints and of its column options
+fn table_index_list(sql_column_list: &Vec<ColumnDef>, constraints: &Vec<TableConstraint>) -> Vec<TableIndexDef> {
+    let mut table_index_list = vec![];
+    for constraint in constraints {
+        if let TableConstraint::Unique { name, columns, is_primary } = constraint {
+            let index_name = if *is_primary {
+                meta_const::NAME_OF_PRIMARY.to_string()
+            } else {
+                match name {
+                    Some(name) => name.to_string(),
+                    None => meta_util::create_unique_index_name(columns.clone()),
+                }
+            };
+            table_index_list.push(TableIndexDef {
+                index_name,
+                level: if *is_primary { 1 } else { 2 },
+                column_name_list: columns.clone(),
+            });
+        }
+    }
+    for sql_column in sql_column_list {
+        for option in sql_column.options.iter() {
+            if let ColumnOption::Unique { is_primary } = option.option {
+                let index_name = if is_primary {
+                    meta_const::NAME_OF_PRIMARY.to_string()
+                } else {
+                    sql_column.name.to_string()
+                };
+                table_index_list.push(TableIndexDef {
+                    index_name,
+                    level: if is_primary { 1 } else { 2 },
+                    column_name_list: vec![sql_column.name.clone()],
+                });
+            }
+        }
+    }
+    table_index_list
 }
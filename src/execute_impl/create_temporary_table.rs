@@ -0,0 +1,173 @@
+use std::sync::{Arc, Mutex};
+
+use arrow::datatypes::{Field, Schema};
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::{ColumnDef, ColumnOption, ObjectName, TableConstraint};
+
+use crate::core::core_util;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::datafusion_impl::datasource::temporary::{TemporaryTable, UniqueKey};
+use crate::meta::{meta_const, meta_util};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::util::convert::ToLowercase;
+
+pub struct CreateTemporaryTable {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl CreateTemporaryTable {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The table is kept by the session, nothing is saved in information_schema.
+    /// It hides the table of the same name of the schema until it is dropped
+    pub fn execute(
+        &mut self,
+        table_name: ObjectName,
+        sql_column_list: Vec<ColumnDef>,
+        constraints: Vec<TableConstraint>,
+        if_not_exists: bool,
+    ) -> MysqlResult<u64> {
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        let full_table_name = meta_util::object_name_remove_quote(full_table_name).to_lowercase();
+        let catalog_name = meta_util::cut_out_catalog_name(full_table_name.clone()).to_string();
+        let schema_name = meta_util::cut_out_schema_name(full_table_name.clone()).to_string();
+        let table_name = meta_util::cut_out_table_name(full_table_name.clone()).to_string();
+
+        let full_schema_name = meta_util::create_full_schema_name(catalog_name.as_str(), schema_name.as_str());
+        meta_util::get_schema(self.global_context.clone(), full_schema_name)?;
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Create,
+            schema_name.as_str(),
+            table_name.as_str(),
+        )?;
+
+        if self.session_context.get_temporary_table(&full_table_name).is_some() {
+            let message = format!("Table '{}' already exists", table_name);
+            if if_not_exists {
+                self.session_context.push_warning(mysql_error_code::ER_TABLE_EXISTS_ERROR as u16, message.as_str());
+                return Ok(0);
+            }
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_TABLE_EXISTS_ERROR as u16,
+                "42S01",
+                message.as_str(),
+            ));
+        }
+
+        let mut unique_key_list = vec![];
+        for sql_column in sql_column_list.iter() {
+            for column_option in sql_column.options.iter() {
+                if let ColumnOption::Unique { is_primary } = column_option.option {
+                    let index_name = if is_primary { meta_const::NAME_OF_PRIMARY.to_string() } else { sql_column.name.value.clone() };
+                    unique_key_list.push(UniqueKey {
+                        index_name,
+                        column_name_list: vec![sql_column.name.value.clone()],
+                    });
+                }
+            }
+        }
+        for constraint in constraints.iter() {
+            if let TableConstraint::Unique { name, columns, is_primary } = constraint {
+                for column_name in columns {
+                    if !sql_column_list.iter().any(|sql_column| sql_column.name.value.eq_ignore_ascii_case(column_name.value.as_str())) {
+                        let message = format!("Key column '{}' doesn't exist in table", column_name);
+                        return Err(MysqlError::new_server_error(1072, "42000", message.as_str()));
+                    }
+                }
+                let index_name = if *is_primary {
+                    meta_const::NAME_OF_PRIMARY.to_string()
+                } else {
+                    name.as_ref().unwrap_or(&columns[0]).value.clone()
+                };
+                let column_name_list = columns
+                    .iter()
+                    .map(|column_name| {
+                        sql_column_list
+                            .iter()
+                            .find(|sql_column| sql_column.name.value.eq_ignore_ascii_case(column_name.value.as_str()))
+                            .map(|sql_column| sql_column.name.value.clone())
+                            .unwrap()
+                    })
+                    .collect();
+                unique_key_list.push(UniqueKey { index_name, column_name_list });
+            }
+        }
+        let primary_key_count = unique_key_list
+            .iter()
+            .filter(|unique_key| unique_key.index_name == meta_const::NAME_OF_PRIMARY)
+            .count();
+        if primary_key_count > 1 {
+            return Err(MysqlError::new_server_error(1068, "42000", "Multiple primary key defined"));
+        }
+        let primary_column_name_list = unique_key_list
+            .iter()
+            .filter(|unique_key| unique_key.index_name == meta_const::NAME_OF_PRIMARY)
+            .flat_map(|unique_key| unique_key.column_name_list.clone())
+            .collect::<Vec<_>>();
+
+        let mut field_list: Vec<Field> = vec![];
+        for sql_column in sql_column_list.iter() {
+            let column_name = sql_column.name.value.clone();
+            if field_list.iter().any(|field| field.name().eq_ignore_ascii_case(column_name.as_str())) {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_DUP_FIELDNAME as u16,
+                    "42S21",
+                    format!("Duplicate column name '{}'", column_name).as_str(),
+                ));
+            }
+            if meta_util::column_is_auto_increment(sql_column) {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                    "42000",
+                    "This version of MySQL doesn't yet support 'AUTO_INCREMENT columns of TEMPORARY tables'",
+                ));
+            }
+
+            let sql_data_type = meta_util::normalize_sql_data_type(&sql_column.data_type)?;
+            let data_type = meta_util::convert_sql_data_type_to_arrow_data_type(&sql_data_type)?;
+            // the columns of the primary key are always NOT NULL
+            let nullable = !primary_column_name_list.contains(&column_name)
+                && !sql_column.options.iter().any(|column_option| column_option.option == ColumnOption::NotNull);
+            field_list.push(Field::new(column_name.as_str(), data_type, nullable));
+        }
+
+        let temporary_table = TemporaryTable::new(
+            full_table_name.clone(),
+            sql_column_list,
+            unique_key_list,
+            Arc::new(Schema::new(field_list)),
+        );
+        self.session_context
+            .temporary_tables
+            .lock()
+            .unwrap()
+            .insert(full_table_name, temporary_table.clone());
+
+        core_util::register_table(
+            &mut self.execution_context,
+            catalog_name.as_str(),
+            schema_name.as_str(),
+            table_name.as_str(),
+            Arc::new(temporary_table),
+        )?;
+
+        Ok(0)
+    }
+}
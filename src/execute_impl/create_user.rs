@@ -0,0 +1,64 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege;
+use crate::core::privilege::Privilege;
+use crate::core::session_context::SessionContext;
+use crate::meta::def::mysql;
+use crate::meta::initial;
+use crate::mysql::auth;
+use crate::mysql::error::MysqlResult;
+use crate::util::user_statement::CreateUserStatement;
+
+pub struct CreateUser {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl CreateUser {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The users are added to mysql.user without any privilege. If one of them exists none is added,
+    /// with IF NOT EXISTS the existing ones are skipped with a warning
+    pub fn execute(&self, statement: CreateUserStatement) -> MysqlResult<u64> {
+        privilege::check_global_privilege(self.global_context.clone(), &self.session_context, Privilege::CreateUser)?;
+
+        let users = privilege::read_users(self.global_context.clone())?;
+        let mut new_users = vec![];
+        let mut existing_accounts = vec![];
+        for user_specification in statement.users {
+            if privilege::find_account(&users, &user_specification.account).is_none() {
+                new_users.push(user_specification);
+            } else if statement.if_not_exists {
+                let message = format!("Authorization ID {} already exists.", user_specification.account);
+                self.session_context.push_warning(3163, message.as_str());
+            } else {
+                existing_accounts.push(user_specification.account);
+            }
+        }
+        if !existing_accounts.is_empty() {
+            return Err(privilege::cannot_user_error("CREATE USER", &existing_accounts));
+        }
+
+        for user_specification in new_users {
+            let password = user_specification.password.unwrap_or_default();
+            let row = privilege::new_user_row(&user_specification.account, auth::native_password_hash(password.as_str()).as_str());
+            initial::add_def_mysql_row(self.global_context.clone(), mysql::users(self.global_context.clone()), &row)?;
+        }
+
+        Ok(0)
+    }
+}
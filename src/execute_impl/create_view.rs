@@ -0,0 +1,160 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use datafusion::logical_plan::LogicalPlan;
+use sqlparser::ast::{Ident, ObjectName, Query};
+
+use crate::core::core_util;
+use crate::core::core_util::register_all_table;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege;
+use crate::core::session_context::SessionContext;
+use crate::datafusion_impl::datasource::temporary::TemporaryTable;
+use crate::execute_impl::drop_view::{self, DropView};
+use crate::meta::meta_def::{TableOptionDef, ViewDef};
+use crate::meta::{initial, meta_const, meta_util};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::util::convert::ToLowercase;
+
+pub struct CreateView {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl CreateView {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The query has the full table names, it is saved as the definition of the view.
+    /// The columns of the view are the column list, else the columns of the query
+    pub async fn execute(
+        &mut self,
+        view_name: ObjectName,
+        columns: Vec<Ident>,
+        query: Query,
+        or_replace: bool,
+    ) -> MysqlResult<u64> {
+        let full_view_name = meta_util::resolve_table_name(&mut self.session_context, &view_name)?;
+        let full_view_name = meta_util::object_name_remove_quote(full_view_name).to_lowercase();
+        let catalog_name = meta_util::cut_out_catalog_name(full_view_name.clone()).to_string();
+        let schema_name = meta_util::cut_out_schema_name(full_view_name.clone()).to_string();
+        let view_name = meta_util::cut_out_table_name(full_view_name.clone()).to_string();
+
+        let full_schema_name = meta_util::create_full_schema_name(catalog_name.as_str(), schema_name.as_str());
+        meta_util::get_schema(self.global_context.clone(), full_schema_name)?;
+
+        if meta_util::get_table(self.global_context.clone(), full_view_name.clone()).is_ok() {
+            if or_replace {
+                return Err(drop_view::error_of_not_view(schema_name.as_str(), view_name.as_str()));
+            }
+            return Err(error_of_view_exists(view_name.as_str()));
+        }
+        let old_view_def = self.global_context.lock().unwrap().meta_data.get_view(full_view_name.clone()).cloned();
+        if old_view_def.is_some() && !or_replace {
+            return Err(error_of_view_exists(view_name.as_str()));
+        }
+
+        core_util::check_table_exists(self.global_context.clone(), &mut self.session_context, &mut self.execution_context, &query)?;
+        let definition = query.to_string();
+        let logical_plan = core_util::plan_view_definition(
+            self.global_context.clone(),
+            &self.session_context,
+            &mut self.execution_context,
+            definition.as_str(),
+        )?;
+        if let Some(temporary_table) = find_temporary_table(&logical_plan) {
+            let table_name = meta_util::cut_out_table_name(temporary_table.full_table_name());
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_VIEW_SELECT_TMPTABLE as u16,
+                "HY000",
+                format!("View's SELECT refers to a temporary table '{}'", table_name).as_str(),
+            ));
+        }
+        // the view reads the tables with the privileges of its definer
+        privilege::check_plan_privileges(self.global_context.clone(), &self.session_context, &logical_plan)?;
+
+        let output_field_list = logical_plan.schema().fields().clone();
+        let column_name_list = if columns.is_empty() {
+            output_field_list.iter().map(|field| field.name().clone()).collect::<Vec<_>>()
+        } else {
+            if columns.len() != output_field_list.len() {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_VIEW_WRONG_LIST as u16,
+                    "HY000",
+                    "In definition of view, derived table or common table expression, SELECT list and column names list have different column counts",
+                ));
+            }
+            columns.iter().map(|column| column.value.to_lowercase()).collect::<Vec<_>>()
+        };
+        for (column_index, column_name) in column_name_list.iter().enumerate() {
+            if column_name_list[..column_index].iter().any(|other| other.eq_ignore_ascii_case(column_name)) {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_DUP_FIELDNAME as u16,
+                    "42S21",
+                    format!("Duplicate column name '{}'", column_name).as_str(),
+                ));
+            }
+        }
+
+        let view_def = ViewDef::new(
+            catalog_name.as_str(),
+            schema_name.as_str(),
+            view_name.as_str(),
+            definition.as_str(),
+            column_name_list,
+        );
+        if let Some(old_view_def) = old_view_def {
+            let mut drop_view = DropView::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.execution_context.clone(),
+            );
+            drop_view.delete_metadata_view(&old_view_def).await?;
+        }
+
+        let mut table_option = TableOptionDef::new(catalog_name.as_str(), schema_name.as_str(), view_name.as_str());
+        table_option.with_table_type(meta_const::VALUE_OF_TABLE_OPTION_TABLE_TYPE_VIEW);
+        initial::add_information_schema_tables(self.global_context.clone(), table_option)?;
+
+        let definer = match self.session_context.current_user.lock().unwrap().clone() {
+            Some(account) => format!("{}@{}", account.user, account.host),
+            None => "root@%".to_string(),
+        };
+        initial::add_information_schema_views(self.global_context.clone(), &view_def, definer.as_str())?;
+
+        self.global_context.lock().unwrap().meta_data.add_view(full_view_name, view_def);
+
+        register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context)?;
+
+        Ok(1)
+    }
+}
+
+/// The temporary table read by the plan, the views do not read the tables of one session
+fn find_temporary_table(logical_plan: &LogicalPlan) -> Option<TemporaryTable> {
+    if let LogicalPlan::TableScan { source, .. } = logical_plan {
+        if let Some(temporary_table) = source.as_any().downcast_ref::<TemporaryTable>() {
+            return Some(temporary_table.clone());
+        }
+    }
+    logical_plan.inputs().into_iter().find_map(find_temporary_table)
+}
+
+fn error_of_view_exists(view_name: &str) -> MysqlError {
+    MysqlError::new_server_error(
+        mysql_error_code::ER_TABLE_EXISTS_ERROR as u16,
+        "42S01",
+        format!("Table '{}' already exists", view_name).as_str(),
+    )
+}
@@ -4,16 +4,20 @@ use arrow::array::{Array, StringArray};
 use arrow::record_batch::RecordBatch;
 use datafusion::execution::context::ExecutionContext;
 use datafusion::physical_plan::{collect, ExecutionPlan};
-use sqlparser::ast::{Expr as SQLExpr, ObjectName, Query, SetExpr};
+use sqlparser::ast::{Expr as SQLExpr, ObjectName, OrderByExpr, Query, SetExpr};
 
 use crate::core::core_util;
+use crate::core::foreign_key;
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::session_context::SessionContext;
 use crate::execute_impl::select::SelectFrom;
 use crate::meta::meta_def::TableDef;
-use crate::meta::{meta_const, meta_util};
+use crate::meta::meta_util;
 use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::store::engine::row_mutation::RowMutation;
+use crate::store::lock::LockMode;
 use crate::util;
 
 pub struct DeleteFrom {
@@ -39,9 +43,20 @@ impl DeleteFrom {
         &mut self,
         table_name: ObjectName,
         selection: Option<SQLExpr>,
+    ) -> MysqlResult<u64> {
+        self.execute_with_limit(table_name, selection, vec![], None).await
+    }
+
+    /// DELETE ... ORDER BY ... LIMIT, only the first rows in the order are deleted
+    pub async fn execute_with_limit(
+        &mut self,
+        table_name: ObjectName,
+        selection: Option<SQLExpr>,
+        order_by: Vec<OrderByExpr>,
+        limit: Option<SQLExpr>,
     ) -> MysqlResult<u64> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
 
         let table_map = self
             .global_context
@@ -61,13 +76,35 @@ impl DeleteFrom {
             }
             Some(table) => table.clone(),
         };
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Delete,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
+
+        let tenant = core_util::get_table_tenant(self.global_context.clone(), &self.session_context, &table_def)?;
+        // the rows of a table referred to by another table are deleted one by one with the rows referring to them
+        let referenced = foreign_key::is_referenced(self.global_context.clone(), &full_table_name);
+        if selection.is_none() && tenant.is_none() && limit.is_none() && !referenced {
+            let rowids = self.read_all_rowids(&table_def)?;
+            core_util::lock_rows(self.global_context.clone(), &self.session_context, &full_table_name, rowids, LockMode::Exclusive).await?;
+            return self.delete_all(table_def);
+        }
 
-        let select = core_util::build_select_rowid_sqlselect(full_table_name.clone(), selection);
+        // the rows are ordered by their columns
+        let select = if order_by.is_empty() {
+            core_util::build_select_rowid_sqlselect(full_table_name.clone(), selection)
+        } else {
+            let column_names = table_def.get_columns().iter().map(|sparrow_column| sparrow_column.sql_column.name.clone()).collect();
+            core_util::build_update_sqlselect(full_table_name.clone(), column_names, selection)
+        };
         let query = Box::new(Query {
             with: None,
             body: SetExpr::Select(Box::new(select)),
-            order_by: vec![],
-            limit: None,
+            order_by,
+            limit,
             offset: None,
             fetch: None,
         });
@@ -79,6 +116,9 @@ impl DeleteFrom {
         let result = select_from.execute(&query).await;
         return match result {
             Ok(result_set) => {
+                // the rows stay locked until the transaction ends, the other writers of them wait
+                let rowids = core_util::get_rowids(&result_set.record_batches);
+                core_util::lock_rows(self.global_context.clone(), &self.session_context, &full_table_name, rowids, LockMode::Exclusive).await?;
                 let result = self.delete_record_batches(table_def, result_set.record_batches);
                 result
             }
@@ -86,6 +126,39 @@ impl DeleteFrom {
         };
     }
 
+    /// The rowids of all the rows of the table as the transaction of the session sees them
+    fn read_all_rowids(&self, table_def: &TableDef) -> MysqlResult<Vec<String>> {
+        let store_engine =
+            StoreEngineFactory::try_new_with_session(self.global_context.clone(), &self.session_context, table_def.clone())?;
+
+        let prefix = util::dbkey::scan_record_rowid(&table_def.option);
+        let mut rowids = vec![];
+        let mut start_after = None;
+        loop {
+            let keys = store_engine.scan_prefix_keys(prefix.clone(), start_after, 1024)?;
+            let count = keys.len();
+            start_after = keys.last().cloned();
+            rowids.extend(keys.into_iter().map(|key| key[prefix.len()..].to_string()));
+            if count < 1024 {
+                break;
+            }
+        }
+        Ok(rowids)
+    }
+
+    /// Without a WHERE clause all the rows are deleted by their key prefix,
+    /// the number of deleted rowid keys is the number of affected rows
+    fn delete_all(&self, table_def: TableDef) -> MysqlResult<u64> {
+        let store_engine =
+            StoreEngineFactory::try_new_with_session(self.global_context.clone(), &self.session_context, table_def.clone())?;
+
+        let count = store_engine.delete_prefix(util::dbkey::scan_record_rowid(&table_def.option))?;
+        store_engine.delete_prefix(util::dbkey::scan_column_key(&table_def.option))?;
+        store_engine.delete_prefix(util::dbkey::scan_table_index_key(&table_def.option))?;
+
+        Ok(count)
+    }
+
     fn delete_record_batches(
         &self,
         table_def: TableDef,
@@ -107,9 +180,13 @@ impl DeleteFrom {
         table_def: TableDef,
         record_batch: RecordBatch,
     ) -> MysqlResult<u64> {
+        // the index build does not run a batch in the middle of the rows being deleted
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.read().unwrap();
+
         let store_engine =
-            StoreEngineFactory::try_new_with_table(self.global_context.clone(), table_def.clone())
-                .unwrap();
+            StoreEngineFactory::try_new_with_session(self.global_context.clone(), &self.session_context, table_def.clone())?;
+        let table_index_list = meta_util::get_table_index_list(self.global_context.clone(), table_def.option.full_table_name.clone())?;
 
         let rowid_array = record_batch
             .column(0)
@@ -117,49 +194,18 @@ impl DeleteFrom {
             .downcast_ref::<StringArray>()
             .unwrap();
 
+        // the rows are deleted with their index entries in one batch
+        let mut row_mutation = RowMutation::new(&table_def, table_index_list, store_engine.as_ref());
+        let mut rowids = vec![];
         for row_index in 0..rowid_array.len() {
             let rowid = rowid_array.value(row_index);
-
-            let record_rowid_key = util::dbkey::create_record_rowid(
-                table_def.option.full_table_name.clone(),
-                rowid.as_ref(),
-            );
-            log::debug!("record_rowid_key: {:?}", record_rowid_key);
-            let result = store_engine.delete_key(record_rowid_key);
-            if let Err(e) = result {
-                return Err(e);
-            }
-
-            for sql_column in table_def.get_table_column().sql_column_list {
-                let column_name = sql_column.name;
-                if column_name.to_string().contains(meta_const::COLUMN_ROWID) {
-                    continue;
-                }
-
-                let sparrow_column = table_def
-                    .get_table_column()
-                    .get_sparrow_column(column_name)
-                    .unwrap();
-                let store_id = sparrow_column.store_id;
-
-                let record_column_key = util::dbkey::create_column_key(
-                    table_def.option.full_table_name.clone(),
-                    store_id,
-                    rowid.as_ref(),
-                );
-                let result = store_engine.delete_key(record_column_key.clone());
-                match result {
-                    Err(error) => {
-                        return Err(MysqlError::new_global_error(1105, format!(
-                            "Unknown error. An error occurred while deleting the key, key: {:?}, error: {:?}",
-                            record_column_key.clone(),
-                            error,
-                        ).as_str()));
-                    }
-                    _ => {}
-                }
-            }
+            log::debug!("delete rowid: {:?}", rowid);
+            row_mutation.delete_row(rowid)?;
+            rowids.push(rowid.to_string());
         }
+        // the rows referring to the deleted rows are deleted or updated by the ON DELETE of their foreign keys
+        foreign_key::delete_rows(self.global_context.clone(), &self.session_context, &table_def, &rowids)?;
+        row_mutation.write()?;
 
         Ok(rowid_array.len() as u64)
     }
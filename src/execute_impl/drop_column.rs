@@ -5,6 +5,7 @@ use sqlparser::ast::{AlterTableOperation, ObjectName, Ident};
 
 use crate::core::core_util::register_all_table;
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::session_context::SessionContext;
 use crate::meta::{initial, meta_const};
 use crate::meta::meta_def::{SparrowColumnDef, TableDef};
@@ -36,7 +37,7 @@ impl DropColumn {
 
     pub async fn execute(&mut self, table_name: ObjectName, column_name: Ident) -> MysqlResult<u64> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
 
         let table_map = self
             .global_context
@@ -52,6 +53,13 @@ impl DropColumn {
             }
             Some(table_def) => table_def.clone(),
         };
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Alter,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
 
         // delete column from information_schema.columns
         let result = self.update_metadata(table_def.clone(), column_name.clone()).await;
@@ -72,7 +80,7 @@ impl DropColumn {
             return Err(error);
         }
 
-        let result = register_all_table(self.global_context.clone(), &mut self.execution_context.clone());
+        let result = register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context.clone());
         if let Err(mysql_error) = result {
             return Err(mysql_error);
         }
@@ -105,7 +113,7 @@ impl DropColumn {
             self.execution_context.clone(),
         );
         let result = update_set.execute(metadata_table_name, assignments, Some(selection)).await;
-        result
+        result.map(|(count, _)| count)
     }
 
     async fn delete_metadata(&mut self, table_def: TableDef, column_name: Ident) -> MysqlResult<u64> {
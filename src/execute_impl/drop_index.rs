@@ -0,0 +1,141 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::{BinaryOperator, Expr as SQLExpr, Ident, ObjectName, TableConstraint, Value};
+
+use crate::core::core_util::register_all_table;
+use crate::core::foreign_key;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::execute_impl::delete::DeleteFrom;
+use crate::meta::meta_def::TableDef;
+use crate::meta::meta_util::load_all_table;
+use crate::meta::{meta_const, meta_util};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::util::dbkey;
+
+pub struct DropIndex {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl DropIndex {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The index is removed from the meta data first, then its entries are deleted
+    /// while the writers wait, so no writer adds an entry of the dropped index
+    pub async fn execute(&mut self, index_name: &str, table_name: ObjectName) -> MysqlResult<u64> {
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        let table_def = meta_util::get_table(self.global_context.clone(), full_table_name.clone())?;
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Index,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
+
+        let table_constraint = table_def
+            .get_constraints()
+            .iter()
+            .find(|table_constraint| meta_util::constraint_name(table_constraint) == Some(index_name.to_lowercase()))
+            .cloned();
+        let index_name = match table_constraint {
+            Some(TableConstraint::Unique { is_primary: true, .. }) => {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                    "42000",
+                    "This version of MySQL doesn't yet support 'dropping the primary key'",
+                ));
+            }
+            // the name as it is saved in the meta data
+            Some(TableConstraint::Unique { name: Some(name), .. }) => name.value,
+            _ => {
+                let message = format!("Can't DROP '{}'; check that column/key exists", index_name);
+                log::error!("{}", message);
+                return Err(MysqlError::new_server_error(1091, "42000", message.as_str()));
+            }
+        };
+
+        foreign_key::check_drop_index(self.global_context.clone(), &table_def, index_name.as_str())?;
+
+        // the rows of the index in information_schema.statistics, key_column_usage and table_constraints
+        self.delete_metadata(
+            &table_def,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_STATISTICS,
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_STATISTICS_INDEX_NAME,
+            index_name.as_str(),
+        ).await?;
+        self.delete_metadata(
+            &table_def,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE,
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_CONSTRAINT_NAME,
+            index_name.as_str(),
+        ).await?;
+        self.delete_metadata(
+            &table_def,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLE_CONSTRAINTS,
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_CONSTRAINT_NAME,
+            index_name.as_str(),
+        ).await?;
+
+        {
+            let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+            let _index_build_guard = index_build_lock.write().unwrap();
+
+            load_all_table(self.global_context.clone())?;
+
+            let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table_def.clone())?;
+            let index_prefix = format!("{}{}/", dbkey::scan_table_index_key(&table_def.option), index_name);
+            store_engine.delete_prefix(index_prefix)?;
+        }
+
+        register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context)?;
+
+        Ok(0)
+    }
+
+    async fn delete_metadata(&mut self, table_def: &TableDef, metadata_table_name: &str, column_name: &str, index_name: &str) -> MysqlResult<u64> {
+        let metadata_table_name = meta_util::create_full_table_name(
+            meta_const::CATALOG_NAME,
+            meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+            metadata_table_name,
+        );
+        let selection = vec![
+            (meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA, table_def.option.schema_name.as_str()),
+            (meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME, table_def.option.table_name.as_str()),
+            (column_name, index_name),
+        ]
+            .into_iter()
+            .map(|(column_name, value)| SQLExpr::BinaryOp {
+                left: Box::new(SQLExpr::Identifier(Ident::new(column_name))),
+                op: BinaryOperator::Eq,
+                right: Box::new(SQLExpr::Value(Value::SingleQuotedString(value.to_string()))),
+            })
+            .reduce(|left, right| SQLExpr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::And,
+                right: Box::new(right),
+            });
+        let mut delete_from = DeleteFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.execution_context.clone(),
+        );
+        delete_from.execute(metadata_table_name, selection).await
+    }
+}
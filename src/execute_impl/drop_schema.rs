@@ -6,6 +6,7 @@ use sqlparser::ast::{AlterTableOperation, Ident, ObjectName};
 use crate::core::core_util;
 use crate::core::core_util::register_all_table;
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::session_context::SessionContext;
 use crate::execute_impl::delete::DeleteFrom;
 use crate::execute_impl::update::Update;
@@ -37,6 +38,12 @@ impl DropSchema {
     pub async fn execute(&mut self, origin_schema_name: ObjectName) -> MysqlResult<u64> {
         let full_schema_name =
             meta_util::fill_up_table_name(&mut self.session_context, origin_schema_name.clone()).unwrap();
+        privilege::check_schema_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Drop,
+            meta_util::cut_out_schema_name(full_schema_name.clone()).to_string().as_str(),
+        )?;
 
         let schema_map = meta_util::read_all_schema(self.global_context.clone()).unwrap();
         if !schema_map.contains_key(&full_schema_name) {
@@ -5,7 +5,9 @@ use sqlparser::ast::{AlterTableOperation, Ident, ObjectName};
 
 use crate::core::core_util;
 use crate::core::core_util::register_all_table;
+use crate::core::foreign_key;
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::session_context::SessionContext;
 use crate::execute_impl::delete::DeleteFrom;
 use crate::execute_impl::update::Update;
@@ -36,13 +38,21 @@ impl DropTable {
 
     pub async fn execute(&mut self, table_name: ObjectName) -> MysqlResult<u64> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
 
         let result = meta_util::get_table(self.global_context.clone(), full_table_name.clone());
         let table_def = match result {
             Ok(table) => table.clone(),
             Err(mysql_error) => return Err(mysql_error),
         };
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Drop,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
+        foreign_key::check_drop_table(self.global_context.clone(), &full_table_name)?;
 
         // delete from information_schema.columns
         let result = self.delete_metadata_column(table_def.clone()).await;
@@ -56,6 +66,12 @@ impl DropTable {
             return Err(error);
         }
 
+        // delete from information_schema.key_column_usage, table_constraints and referential_constraints
+        let result = self.delete_metadata_constraint(table_def.clone()).await;
+        if let Err(error) = result {
+            return Err(error);
+        }
+
         // delete from information_schema.tables
         let result = self.delete_metadata_table(table_def.clone()).await;
         if let Err(error) = result {
@@ -119,6 +135,47 @@ impl DropTable {
         result
     }
 
+    /// The constraints of the table, the foreign keys are found by the schema of their constraints
+    async fn delete_metadata_constraint(&mut self, table_def: TableDef) -> MysqlResult<u64> {
+        let metadata_selections = vec![
+            (
+                meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE,
+                meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA,
+            ),
+            (
+                meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLE_CONSTRAINTS,
+                meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA,
+            ),
+            (
+                meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS,
+                meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_CONSTRAINT_SCHEMA,
+            ),
+        ];
+        let mut total = 0;
+        for (metadata_table_name, schema_column_name) in metadata_selections {
+            let metadata_table_name = meta_util::create_full_table_name(
+                meta_const::CATALOG_NAME,
+                meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+                metadata_table_name,
+            );
+            let selection = core_util::build_find_pair_sqlwhere(
+                schema_column_name,
+                table_def.option.schema_name.as_str(),
+                meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME,
+                table_def.option.table_name.as_str(),
+            );
+            let mut delete_from = DeleteFrom::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.execution_context.clone(),
+            );
+            total += delete_from
+                .execute(metadata_table_name, Some(selection))
+                .await?;
+        }
+        Ok(total)
+    }
+
     async fn delete_metadata_table(&mut self, table_def: TableDef) -> MysqlResult<u64> {
         let metadata_table_name = meta_util::create_full_table_name(
             meta_const::CATALOG_NAME,
@@ -0,0 +1,75 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::ObjectName;
+
+use crate::core::core_util::register_all_table;
+use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
+use crate::meta::meta_util;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::util::convert::ToLowercase;
+
+pub struct DropTemporaryTable {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl DropTemporaryTable {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The temporary tables are dropped only if all of them exist, the unknown ones are notes with IF EXISTS.
+    /// The tables they hid are seen again
+    pub fn execute(&mut self, table_names: Vec<ObjectName>, if_exists: bool) -> MysqlResult<u64> {
+        let mut full_table_name_list = vec![];
+        let mut unknown_table_list = vec![];
+        for table_name in table_names {
+            let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+            let full_table_name = meta_util::object_name_remove_quote(full_table_name).to_lowercase();
+            if self.session_context.get_temporary_table(&full_table_name).is_some() {
+                full_table_name_list.push(full_table_name);
+            } else {
+                let schema_name = meta_util::cut_out_schema_name(full_table_name.clone());
+                let table_name = meta_util::cut_out_table_name(full_table_name.clone());
+                unknown_table_list.push(format!("{}.{}", schema_name, table_name));
+            }
+        }
+
+        if !unknown_table_list.is_empty() {
+            let message = format!("Unknown table '{}'", unknown_table_list.join(","));
+            if !if_exists {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_BAD_TABLE_ERROR as u16,
+                    "42S02",
+                    message.as_str(),
+                ));
+            }
+            self.session_context.push_warning(mysql_error_code::ER_BAD_TABLE_ERROR as u16, message.as_str());
+        }
+
+        for full_table_name in full_table_name_list {
+            self.session_context.temporary_tables.lock().unwrap().remove(&full_table_name);
+
+            let result = self.execution_context.deregister_table(full_table_name.to_string().as_str());
+            if let Err(error) = result {
+                return Err(MysqlError::from(error));
+            }
+        }
+
+        register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context)?;
+
+        Ok(0)
+    }
+}
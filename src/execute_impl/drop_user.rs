@@ -0,0 +1,71 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege;
+use crate::core::privilege::Privilege;
+use crate::core::session_context::SessionContext;
+use crate::meta::def::mysql;
+use crate::meta::{initial, meta_const};
+use crate::mysql::error::MysqlResult;
+use crate::util::user_statement::DropUserStatement;
+
+pub struct DropUser {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl DropUser {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The users are deleted from mysql.user with their grants of mysql.db. If one of them does not exist
+    /// none is deleted, with IF EXISTS the unknown ones are skipped with a warning
+    pub fn execute(&self, statement: DropUserStatement) -> MysqlResult<u64> {
+        privilege::check_global_privilege(self.global_context.clone(), &self.session_context, Privilege::CreateUser)?;
+
+        let users = privilege::read_users(self.global_context.clone())?;
+        let mut dropped_users = vec![];
+        let mut unknown_accounts = vec![];
+        for account in statement.accounts {
+            match privilege::find_account(&users, &account) {
+                Some(row) => dropped_users.push((account, row)),
+                None if statement.if_exists => {
+                    let message = format!("Authorization ID {} does not exist.", account);
+                    self.session_context.push_warning(3162, message.as_str());
+                }
+                None => unknown_accounts.push(account),
+            }
+        }
+        if !unknown_accounts.is_empty() {
+            return Err(privilege::cannot_user_error("DROP USER", &unknown_accounts));
+        }
+
+        let schema_grants = privilege::read_schema_grants(self.global_context.clone())?;
+        for (account, row) in dropped_users {
+            let rowid = privilege::row_value(&row, meta_const::COLUMN_ROWID);
+            initial::delete_def_mysql_row(self.global_context.clone(), mysql::users(self.global_context.clone()), rowid)?;
+
+            let account_schema_grants = schema_grants
+                .iter()
+                .filter(|schema_grant| privilege::is_row_of_account(schema_grant, &account));
+            for schema_grant in account_schema_grants {
+                let rowid = privilege::row_value(schema_grant, meta_const::COLUMN_ROWID);
+                initial::delete_def_mysql_row(self.global_context.clone(), mysql::db(self.global_context.clone()), rowid)?;
+            }
+        }
+
+        Ok(0)
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::ObjectName;
+
+use crate::core::core_util;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::execute_impl::delete::DeleteFrom;
+use crate::meta::meta_def::ViewDef;
+use crate::meta::{meta_const, meta_util};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::util::convert::{ToLowercase, ToObjectName};
+
+pub struct DropView {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl DropView {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The views are dropped only if all of them exist, the unknown ones are notes with IF EXISTS
+    pub async fn execute(&mut self, view_names: Vec<ObjectName>, if_exists: bool) -> MysqlResult<u64> {
+        let mut view_def_list = vec![];
+        let mut unknown_view_list = vec![];
+        for view_name in view_names {
+            let full_view_name = meta_util::resolve_table_name(&mut self.session_context, &view_name)?;
+            let full_view_name = meta_util::object_name_remove_quote(full_view_name).to_lowercase();
+            let schema_name = meta_util::cut_out_schema_name(full_view_name.clone());
+            let view_name = meta_util::cut_out_table_name(full_view_name.clone());
+
+            let view_def = self.global_context.lock().unwrap().meta_data.get_view(full_view_name.clone()).cloned();
+            match view_def {
+                Some(view_def) => {
+                    privilege::check_table_privilege(
+                        self.global_context.clone(),
+                        &self.session_context,
+                        Privilege::Drop,
+                        view_def.schema_name.as_str(),
+                        view_def.view_name.as_str(),
+                    )?;
+                    view_def_list.push(view_def)
+                }
+                None => {
+                    if meta_util::get_table(self.global_context.clone(), full_view_name.clone()).is_ok() {
+                        return Err(error_of_not_view(schema_name.to_string().as_str(), view_name.to_string().as_str()));
+                    }
+                    unknown_view_list.push(format!("{}.{}", schema_name, view_name));
+                }
+            }
+        }
+
+        if !unknown_view_list.is_empty() {
+            let message = format!("Unknown table '{}'", unknown_view_list.join(","));
+            if !if_exists {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_BAD_TABLE_ERROR as u16,
+                    "42S02",
+                    message.as_str(),
+                ));
+            }
+            self.session_context.push_warning(mysql_error_code::ER_BAD_TABLE_ERROR as u16, message.as_str());
+        }
+
+        for view_def in view_def_list {
+            self.delete_metadata_view(&view_def).await?;
+
+            self.global_context.lock().unwrap().meta_data.delete_view(view_def.full_view_name.clone());
+
+            let result = self
+                .execution_context
+                .deregister_table(view_def.full_view_name.to_string().as_str());
+            if let Err(error) = result {
+                return Err(MysqlError::from(error));
+            }
+        }
+
+        Ok(1)
+    }
+
+    /// The rows of the view in information_schema.views and information_schema.tables
+    pub async fn delete_metadata_view(&mut self, view_def: &ViewDef) -> MysqlResult<u64> {
+        let metadata_table_name_list = vec![
+            meta_const::FULL_TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS,
+            meta_const::FULL_TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES,
+        ];
+        let mut total = 0;
+        for metadata_table_name in metadata_table_name_list {
+            let selection = core_util::build_find_table_sqlwhere(
+                view_def.catalog_name.as_str(),
+                view_def.schema_name.as_str(),
+                view_def.view_name.as_str(),
+            );
+            let mut delete_from = DeleteFrom::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.execution_context.clone(),
+            );
+            total += delete_from
+                .execute(metadata_table_name.to_object_name(), Some(selection))
+                .await?;
+        }
+        Ok(total)
+    }
+}
+
+/// DROP VIEW of a table and CREATE OR REPLACE VIEW over a table
+pub fn error_of_not_view(schema_name: &str, table_name: &str) -> MysqlError {
+    MysqlError::new_server_error(
+        mysql_error_code::ER_WRONG_OBJECT as u16,
+        "HY000",
+        format!("'{}.{}' is not VIEW", schema_name, table_name).as_str(),
+    )
+}
@@ -1,10 +1,12 @@
+use std::fmt;
 use std::sync::{Arc, Mutex};
 
-use arrow::datatypes::SchemaRef;
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::execution::context::ExecutionContext;
 use datafusion::logical_plan::LogicalPlan;
-use datafusion::physical_plan::{collect, ExecutionPlan};
+use datafusion::physical_plan::{collect, DisplayFormatType, ExecutionPlan};
 use datafusion::sql::planner::{ContextProvider, SqlToRel};
 use sqlparser::ast::{AlterTableOperation, Query, Statement};
 
@@ -12,6 +14,7 @@ use crate::core::core_util;
 use crate::core::core_util::{check_table_exists, register_all_table};
 use crate::core::global_context::GlobalContext;
 use crate::core::output::ResultSet;
+use crate::core::plan_rewrite::PlanRewriteContext;
 use crate::core::session_context::SessionContext;
 use crate::meta::initial;
 use crate::meta::meta_def::{SparrowColumnDef, TableDef};
@@ -79,6 +82,39 @@ impl Explain {
         }
     }
 
+    /// EXPLAIN FORMAT=TREE, the physical plan as the indented operator tree in one row
+    pub async fn execute_tree(&mut self, statement: &Statement) -> MysqlResult<ResultSet> {
+        let state = self.execution_context.state.lock().unwrap().clone();
+        let query_planner = SqlToRel::new(&state);
+        let logical_plan = query_planner.sql_statement_to_plan(statement).map_err(MysqlError::from)?;
+
+        let logical_plan = self.execution_context.optimize(&logical_plan).map_err(MysqlError::from)?;
+        // the tree is the plan the query runs with
+        let logical_plan = match statement {
+            Statement::Query(query) => {
+                let plan_rewrite = self.global_context.lock().unwrap().plan_rewrite.clone();
+                let plan_rewrite_context = PlanRewriteContext {
+                    session_context: &self.session_context,
+                    query,
+                };
+                plan_rewrite.rewrite(&logical_plan, &plan_rewrite_context)?
+            }
+            _ => logical_plan,
+        };
+
+        let execution_plan = self.execution_context.create_physical_plan(&logical_plan).await.map_err(MysqlError::from)?;
+
+        let mut tree = String::new();
+        render_tree(execution_plan.as_ref(), 0, &mut tree);
+
+        let schema = Schema::new(vec![Field::new("EXPLAIN", DataType::Utf8, false)]);
+        let schema_ref = SchemaRef::new(schema);
+        let column = StringArray::from(vec![tree.trim_end()]);
+        let record_batch = RecordBatch::try_new(schema_ref.clone(), vec![Arc::new(column)]).map_err(MysqlError::from)?;
+
+        Ok(ResultSet::new(schema_ref, vec![record_batch]))
+    }
+
     fn explain_statement_to_plan(
         &mut self,
         verbose: bool,
@@ -99,3 +135,20 @@ impl Explain {
         return Ok(logical_plan);
     }
 }
+
+/// The one line description of the plan node, as the indent display of DataFusion shows it
+struct PlanNode<'a>(&'a dyn ExecutionPlan);
+
+impl<'a> fmt::Display for PlanNode<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_as(DisplayFormatType::Default, f)
+    }
+}
+
+/// Every node is a `-> ` line, and the children are indented by 4 spaces under it like mysql
+fn render_tree(execution_plan: &dyn ExecutionPlan, level: usize, tree: &mut String) {
+    tree.push_str(format!("{}-> {}\n", " ".repeat(level * 4), PlanNode(execution_plan)).as_str());
+    for child in execution_plan.children() {
+        render_tree(child.as_ref(), level + 1, tree);
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege;
+use crate::core::privilege::{Account, GrantLevel, GrantRow, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::meta::def::mysql;
+use crate::meta::{initial, meta_const};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::util::user_statement::GrantStatement;
+
+pub struct Grant {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl Grant {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// GRANT and REVOKE need the GRANT OPTION of the level. The global privileges are kept by mysql.user,
+    /// the privileges of a schema by a row of mysql.db which is deleted when its last privilege is revoked
+    pub fn execute(&self, statement: GrantStatement) -> MysqlResult<u64> {
+        let level = match statement.level {
+            GrantLevel::DefaultSchema => match self.session_context.current_schema.lock().unwrap().clone() {
+                Some(schema_name) => GrantLevel::Schema(schema_name),
+                None => return Err(MysqlError::new_server_error(1046, "3D000", "No database selected")),
+            },
+            level => level,
+        };
+
+        match &level {
+            GrantLevel::Schema(schema_name) => {
+                privilege::check_schema_privilege(self.global_context.clone(), &self.session_context, Privilege::Grant, schema_name)?;
+                if statement.privileges.iter().any(|privilege| !privilege.is_schema_privilege()) {
+                    return Err(MysqlError::new_server_error(
+                        1221,
+                        "HY000",
+                        "Incorrect usage of DB GRANT and GLOBAL PRIVILEGES",
+                    ));
+                }
+            }
+            _ => privilege::check_global_privilege(self.global_context.clone(), &self.session_context, Privilege::Grant)?,
+        }
+
+        let users = privilege::read_users(self.global_context.clone())?;
+        let mut granted_users = vec![];
+        for account in statement.accounts {
+            match privilege::find_account(&users, &account) {
+                Some(user) => granted_users.push((account, user)),
+                None if statement.revoke => return Err(no_such_grant_error(&account)),
+                None => {
+                    return Err(MysqlError::new_server_error(
+                        1410,
+                        "42000",
+                        "You are not allowed to create a user with GRANT",
+                    ));
+                }
+            }
+        }
+
+        let value = if statement.revoke { "N" } else { "Y" };
+        for (account, user) in granted_users {
+            match &level {
+                GrantLevel::Schema(schema_name) => {
+                    self.grant_schema(&account, schema_name, &statement.privileges, value, statement.revoke)?;
+                }
+                _ => {
+                    let table_def = mysql::users(self.global_context.clone());
+                    let rowid = privilege::row_value(&user, meta_const::COLUMN_ROWID).to_string();
+                    initial::delete_def_mysql_row(self.global_context.clone(), table_def.clone(), rowid.as_str())?;
+                    let user = with_privileges(user, &statement.privileges, value);
+                    initial::add_def_mysql_row(self.global_context.clone(), table_def, &user)?;
+                }
+            }
+        }
+
+        Ok(0)
+    }
+
+    fn grant_schema(&self, account: &Account, schema_name: &str, privileges: &[Privilege], value: &str, revoke: bool) -> MysqlResult<()> {
+        let table_def = mysql::db(self.global_context.clone());
+        let schema_grants = privilege::read_schema_grants(self.global_context.clone())?;
+        let schema_grant = match privilege::find_schema_grant(&schema_grants, account, schema_name) {
+            Some(schema_grant) => {
+                let rowid = privilege::row_value(&schema_grant, meta_const::COLUMN_ROWID).to_string();
+                initial::delete_def_mysql_row(self.global_context.clone(), table_def.clone(), rowid.as_str())?;
+                schema_grant
+            }
+            None if revoke => return Err(no_such_grant_error(account)),
+            None => privilege::new_schema_grant_row(account, schema_name),
+        };
+
+        let schema_grant = with_privileges(schema_grant, privileges, value);
+        let has_privileges = privilege::PRIVILEGES
+            .iter()
+            .any(|privilege| privilege.is_schema_privilege() && privilege::row_has_privilege(&schema_grant, *privilege));
+        if has_privileges {
+            initial::add_def_mysql_row(self.global_context.clone(), table_def, &schema_grant)?;
+        }
+        Ok(())
+    }
+}
+
+fn with_privileges(mut row: GrantRow, privileges: &[Privilege], value: &str) -> GrantRow {
+    for privilege in privileges {
+        row.insert(privilege.column_name().to_string(), Some(value.to_string()));
+    }
+    row
+}
+
+fn no_such_grant_error(account: &Account) -> MysqlError {
+    MysqlError::new_server_error(
+        mysql_error_code::ER_NONEXISTING_GRANT as u16,
+        "42000",
+        format!("There is no such grant defined for user '{}' on host '{}'", account.user, account.host).as_str(),
+    )
+}
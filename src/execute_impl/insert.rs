@@ -1,28 +1,38 @@
 use bytes::{Buf, Bytes};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
-use arrow::datatypes::Schema;
+use arrow::compute::{cast, concat};
+use arrow::datatypes::{DataType, Schema};
 use arrow::record_batch::RecordBatch;
 use datafusion::execution::context::ExecutionContext;
-use datafusion::logical_plan::{Expr, ToDFSchema};
+use datafusion::datasource::TableProvider;
+use datafusion::logical_plan::{DFSchema, Expr, ToDFSchema};
 use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
 use datafusion::physical_plan::ColumnarValue;
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::planner::{ContextProvider, SqlToRel};
-use sqlparser::ast::{Ident, ObjectName};
-use sqlparser::ast::{Query, SetExpr};
+use sqlparser::ast::{DataType as SQLDataType, Expr as SQLExpr, Ident, ObjectName};
+use sqlparser::ast::{Query, SetExpr, Values};
 use uuid::Uuid;
 
 use crate::core::core_util;
+use crate::core::foreign_key;
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 use crate::core::session_context::SessionContext;
-use crate::meta::meta_def::{IndexDef, TableDef};
+use crate::datafusion_impl::datasource::temporary::TemporaryTable;
+use crate::execute_impl::select::SelectFrom;
+use crate::meta::meta_def::{IndexDef, SparrowColumnDef, TableDef, TableIndexDef};
 use crate::meta::meta_util;
 use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::store::engine::row_mutation::RowMutation;
+use crate::store::lock::LockMode;
 use crate::util::convert::ToIdent;
-use crate::util::dbkey::{create_table_index_key, create_column_key, create_column_rowid_key};
+use crate::util::date_function;
+use crate::util::upsert_statement::{self, OnDuplicate};
+use crate::util::dbkey::{create_table_index_key, create_column_key, create_column_rowid_key, create_rowid_with_primary_key};
 use datafusion::prelude::col;
 use crate::physical_plan::insert::PhysicalPlanInsert;
 
@@ -30,6 +40,12 @@ pub struct Insert {
     global_context: Arc<Mutex<GlobalContext>>,
     session_context: SessionContext,
     execution_context: ExecutionContext,
+    /// The first auto increment value generated by the insert
+    generated_insert_id: Option<u64>,
+    /// The rows given by VALUES or selected by the query
+    records: u64,
+    /// The rows which duplicated a unique key of the table, replaced or updated
+    duplicates: u64,
 }
 
 impl Insert {
@@ -42,9 +58,24 @@ impl Insert {
             global_context,
             session_context,
             execution_context,
+            generated_insert_id: None,
+            records: 0,
+            duplicates: 0,
         }
     }
 
+    pub fn generated_insert_id(&self) -> Option<u64> {
+        self.generated_insert_id
+    }
+
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
     pub fn execute(
         &mut self,
         origin_table_name: ObjectName,
@@ -52,45 +83,575 @@ impl Insert {
         overwrite: bool,
         source: Box<Query>,
     ) -> MysqlResult<u64> {
+        let (table, column_name_list, column_value_map_list) = self.create_rows(&origin_table_name, columns, source)?;
+        let full_table_name = table.option.full_table_name.clone();
+        let table_name = table.option.table_name.to_string();
+
+        let store_engine = StoreEngineFactory::try_new_with_session(
+            self.global_context.clone(),
+            &self.session_context,
+            table.clone(),
+        )?;
+
+        // the indexes being built are written too, the index build does not run a batch until the rows are written
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.read().unwrap();
+
+        let table_index_list =
+            meta_util::get_table_index_list(self.global_context.clone(), full_table_name.clone())
+                .unwrap();
+
+        let mut index_keys_list = vec![];
+        for column_value_map in column_value_map_list.iter() {
+            index_keys_list.push(self.create_index_keys(&table, &table_index_list, column_value_map)?);
+        }
+
+        // the unique keys of the rows before, a row may duplicate a row of the same statement
+        let mut batch_unique_keys = HashSet::new();
+        for (row_number, index_keys) in index_keys_list.iter().enumerate() {
+            for row_index in index_keys {
+                if (row_index.level == 1 || row_index.level == 2) && !row_index.contains_null {
+                    let mut check_key = row_index.index_key.clone();
+                    if table.option.without_rowid && row_index.level == 1 {
+                        // the primary index of the table without rowid is the rowid key
+                        let rowid = create_rowid_with_primary_key(&table.option, row_index);
+                        check_key = create_column_rowid_key(&table.option, rowid.as_str());
+                    }
+                    let duplicate = !batch_unique_keys.insert(check_key.clone())
+                        || store_engine.get_key(check_key)?.is_some();
+                    if duplicate && !overwrite {
+                        let mut values = vec![];
+                        let table_index = table_index_list.iter().find(|table_index| table_index.index_name == row_index.index_name).unwrap();
+                        for column_name in table_index.column_name_list.iter() {
+                            let value = match column_value_map_list[row_number].get(column_name) {
+                                Some(scalar_value) => core_util::convert_scalar_value(scalar_value.clone())?,
+                                None => None,
+                            };
+                            values.push(value);
+                        }
+                        return Err(core_util::duplicate_entry_error(table_name.as_str(), row_index.index_name.as_str(), values));
+                    }
+                }
+            }
+        }
+
+        foreign_key::check_insert_rows(self.global_context.clone(), &self.session_context, &table, &column_value_map_list)?;
+
+        let mut insert = PhysicalPlanInsert::new(self.global_context.clone());
+        insert.with_session_context(self.session_context.clone());
+        let count = insert.execute(table.clone(), column_name_list.clone(), index_keys_list.clone(), column_value_map_list.clone())?;
+        if let Some(generated_insert_id) = self.generated_insert_id {
+            *self.session_context.last_insert_id.lock().unwrap() = generated_insert_id;
+        }
+        Ok(count)
+    }
+
+    /// INSERT ... SELECT, the rows of the query are inserted as the rows of VALUES
+    pub async fn execute_select(
+        &mut self,
+        origin_table_name: ObjectName,
+        columns: Vec<Ident>,
+        overwrite: bool,
+        source: Box<Query>,
+    ) -> MysqlResult<u64> {
+        let source = self.select_source(source).await?;
+        self.execute(origin_table_name, columns, overwrite, source)
+    }
+
+    /// INSERT into the TEMPORARY table, the rows are kept with the rows of the session. The values are cast
+    /// to the types of the columns, the columns which are not given take their default
+    pub async fn execute_temporary_table(
+        &mut self,
+        temporary_table: TemporaryTable,
+        columns: Vec<Ident>,
+        source: Box<Query>,
+    ) -> MysqlResult<u64> {
+        let source = self.select_source(source).await?;
+        let row_values = match &source.body {
+            SetExpr::Values(values) => values.0.clone(),
+            _ => vec![],
+        };
+        if row_values.is_empty() {
+            return Ok(0);
+        }
+
+        let table_name = meta_util::cut_out_table_name(temporary_table.full_table_name()).to_string();
+        let sql_column_list = temporary_table.get_columns();
+        let column_name_list: Vec<String> = if columns.is_empty() {
+            sql_column_list.iter().map(|sql_column| sql_column.name.value.clone()).collect()
+        } else {
+            columns.iter().map(|column| column.value.clone()).collect()
+        };
+
+        let mut column_index_list = vec![];
+        for column_name in column_name_list.iter() {
+            let column_index = sql_column_list
+                .iter()
+                .position(|sql_column| sql_column.name.value.eq_ignore_ascii_case(column_name));
+            match column_index {
+                Some(column_index) => column_index_list.push(column_index),
+                None => {
+                    return Err(MysqlError::new_server_error(
+                        1054,
+                        "42S22",
+                        format!("Unknown column '{}' in 'field list'", column_name).as_str(),
+                    ));
+                }
+            }
+        }
+        for (row_index, row_value) in row_values.iter().enumerate() {
+            if row_value.len() != column_index_list.len() {
+                return Err(MysqlError::new_server_error(
+                    1136,
+                    "21S01",
+                    format!("Column count doesn't match value count at row {}", row_index + 1).as_str(),
+                ));
+            }
+        }
+
+        let dfschema = DFSchema::empty();
+        let schema = temporary_table.schema();
+        let mut column_array_list = vec![];
+        for (column_index, sql_column) in sql_column_list.iter().enumerate() {
+            let field = schema.field(column_index);
+            let value_index = column_index_list.iter().position(|index| *index == column_index);
+            let default_value = match (value_index, meta_util::get_column_default(sql_column)) {
+                (Some(_), _) => None,
+                (None, Some(default_expr)) => Some(self.evaluate_constant_with_schema(&default_expr, &dfschema)?),
+                (None, None) if field.is_nullable() => Some(ScalarValue::Utf8(None)),
+                (None, None) => {
+                    return Err(MysqlError::new_server_error(
+                        1364,
+                        "HY000",
+                        format!("Field '{}' doesn't have a default value", field.name()).as_str(),
+                    ));
+                }
+            };
+
+            let mut value_array_list = vec![];
+            for (row_index, row_value) in row_values.iter().enumerate() {
+                let scalar_value = match value_index {
+                    Some(value_index) => self.evaluate_constant_with_schema(&row_value[value_index], &dfschema)?,
+                    None => default_value.clone().unwrap(),
+                };
+                // the text of the DATE and the DATETIME is read as mysql writes it, not by the cast of arrow
+                let scalar_value = match (&scalar_value, field.data_type()) {
+                    (ScalarValue::Utf8(Some(text)), DataType::Date32 | DataType::Timestamp(_, _)) => {
+                        match date_function::text_to_temporal_value(text, field.data_type()) {
+                            Some(scalar_value) => scalar_value,
+                            None => {
+                                let data_type = meta_util::convert_sql_data_type(&sql_column.data_type)?;
+                                return Err(MysqlError::new_server_error(
+                                    1292,
+                                    "22007",
+                                    format!("Incorrect {} value: '{}' for column '{}' at row {}", data_type, text, field.name(), row_index + 1).as_str(),
+                                ));
+                            }
+                        }
+                    }
+                    _ => scalar_value,
+                };
+                let value_array = cast(&scalar_value.to_array_of_size(1), field.data_type())?;
+                if value_array.is_null(0) && !field.is_nullable() {
+                    return Err(MysqlError::new_server_error(
+                        1048,
+                        "23000",
+                        format!("Column '{}' cannot be null", field.name()).as_str(),
+                    ));
+                }
+                value_array_list.push(value_array);
+            }
+            let value_array_list = value_array_list.iter().map(|value_array| value_array.as_ref()).collect::<Vec<_>>();
+            column_array_list.push(concat(value_array_list.as_slice())?);
+        }
+        let record_batch = RecordBatch::try_new(schema.clone(), column_array_list)?;
+
+        // the keys of the rows before are unique, a row may duplicate a row before or a row of the same statement
+        let mut record_batch_list = temporary_table.get_record_batches();
+        record_batch_list.push(record_batch.clone());
+        for unique_key in temporary_table.get_unique_keys() {
+            let key_column_index_list = unique_key
+                .column_name_list
+                .iter()
+                .map(|column_name| schema.index_of(column_name.as_str()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut unique_keys = HashSet::new();
+            for row in record_batch_list.iter().flat_map(|record_batch| core_util::convert_record_to_scalar_value(record_batch.clone())) {
+                let mut values = vec![];
+                for key_column_index in key_column_index_list.iter() {
+                    values.push(core_util::convert_scalar_value(row[*key_column_index].clone())?);
+                }
+                if values.iter().any(|value| value.is_none()) {
+                    continue;
+                }
+                if !unique_keys.insert(values.clone()) {
+                    return Err(core_util::duplicate_entry_error(table_name.as_str(), unique_key.index_name.as_str(), values));
+                }
+            }
+        }
+
+        temporary_table.append(record_batch);
+        Ok(row_values.len() as u64)
+    }
+
+    /// The VALUES of the rows of the query, VALUES is kept as it is
+    async fn select_source(&self, source: Box<Query>) -> MysqlResult<Box<Query>> {
+        if let SetExpr::Values(_) = &source.body {
+            return Ok(source);
+        }
+
+        let mut select_from = SelectFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.execution_context.clone(),
+        );
+        let result_set = select_from.execute(&source).await?;
+        let row_values = core_util::record_batches_to_values(&result_set.record_batches)?;
+        Ok(Box::new(Query {
+            with: None,
+            body: SetExpr::Values(Values(row_values)),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+        }))
+    }
+
+    /// REPLACE and INSERT ... ON DUPLICATE KEY UPDATE. The rows are written one by one, a row sees the rows
+    /// written before it by the statement. Returns the affected rows as MySQL counts them: one for an inserted
+    /// row, one more for every row it replaces, and two for the row changed by the assignments
+    pub async fn execute_on_duplicate(
+        &mut self,
+        origin_table_name: ObjectName,
+        columns: Vec<Ident>,
+        source: Box<Query>,
+        on_duplicate: OnDuplicate,
+    ) -> MysqlResult<u64> {
+        let source = self.select_source(source).await?;
+        let (table, column_name_list, column_value_map_list) = self.create_rows(&origin_table_name, columns, source)?;
+        let privilege = match on_duplicate {
+            OnDuplicate::Replace => Privilege::Delete,
+            OnDuplicate::Update(_) => Privilege::Update,
+        };
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            privilege,
+            table.option.schema_name.as_str(),
+            table.option.table_name.as_str(),
+        )?;
+
+        let mut affected_rows = 0;
+        for column_value_map in column_value_map_list {
+            // the rows holding the keys stay locked until the transaction ends, as the rows of UPDATE and DELETE
+            let duplicate_rowids = self.find_duplicate_rowids(&table, &column_value_map)?;
+            core_util::lock_rows(
+                self.global_context.clone(),
+                &self.session_context,
+                &table.option.full_table_name,
+                duplicate_rowids.clone(),
+                LockMode::Exclusive,
+            ).await?;
+
+            match (&on_duplicate, duplicate_rowids.first()) {
+                (OnDuplicate::Update(assignments_sql), Some(rowid)) => {
+                    // only the row holding the first duplicated key is updated
+                    affected_rows += self.update_duplicate_row(&table, assignments_sql, &column_value_map, rowid)?;
+                }
+                _ => {
+                    affected_rows += self.replace_row(&table, &column_name_list, column_value_map, &duplicate_rowids)?;
+                }
+            }
+        }
+
+        if let Some(generated_insert_id) = self.generated_insert_id {
+            *self.session_context.last_insert_id.lock().unwrap() = generated_insert_id;
+        }
+        Ok(affected_rows)
+    }
+
+    /// The rows holding the unique keys of the row, in the order of the indexes.
+    /// The key held by a row of another tenant is a duplicate entry, that row is never replaced nor updated
+    fn find_duplicate_rowids(&self, table: &TableDef, column_value_map: &HashMap<Ident, ScalarValue>) -> MysqlResult<Vec<String>> {
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.read().unwrap();
+
+        let full_table_name = table.option.full_table_name.clone();
+        let store_engine = StoreEngineFactory::try_new_with_session(self.global_context.clone(), &self.session_context, table.clone())?;
+        let table_index_list = meta_util::get_table_index_list(self.global_context.clone(), full_table_name.clone())?;
+        let index_keys = self.create_index_keys(table, &table_index_list, column_value_map)?;
+        let tenant = match core_util::get_table_tenant(self.global_context.clone(), &self.session_context, table)? {
+            Some((sparrow_column, tenant_id)) => {
+                let data_type = meta_util::convert_sql_data_type_to_arrow_data_type(&sparrow_column.sql_column.data_type)?;
+                let tenant_id = core_util::convert_tenant_id_to_scalar_value(&data_type, sparrow_column.sql_column.name.value.as_str(), tenant_id.as_str())?;
+                Some((sparrow_column, core_util::convert_scalar_value(tenant_id)?))
+            }
+            None => None,
+        };
+
+        let mut duplicate_rowids = vec![];
+        for index in index_keys.iter() {
+            if !(index.level == 1 || index.level == 2) || index.contains_null {
+                continue;
+            }
+            let duplicate_rowid = if table.option.without_rowid && index.level == 1 {
+                // the primary index of the table without rowid is the rowid key
+                let rowid = create_rowid_with_primary_key(&table.option, index);
+                let rowid_key = create_column_rowid_key(&table.option, rowid.as_str());
+                store_engine.get_key(rowid_key)?.map(|_| rowid)
+            } else {
+                store_engine.get_key(index.index_key.clone())?.map(|value| String::from_utf8_lossy(value.as_slice()).to_string())
+            };
+            if let Some(rowid) = duplicate_rowid {
+                if let Some((sparrow_column, tenant_value)) = &tenant {
+                    let row_tenant_value = core_util::read_column_value_with(table, sparrow_column, rowid.as_str(), &|key| store_engine.get_key(key))?;
+                    if row_tenant_value != *tenant_value {
+                        let table_index = table_index_list.iter().find(|table_index| table_index.index_name == index.index_name).unwrap();
+                        let values = core_util::read_index_values_with(table, table_index, rowid.as_str(), &|key| store_engine.get_key(key))?;
+                        return Err(core_util::duplicate_entry_error(table.option.table_name.as_str(), index.index_name.as_str(), values));
+                    }
+                }
+                if !duplicate_rowids.contains(&rowid) {
+                    duplicate_rowids.push(rowid);
+                }
+            }
+        }
+        Ok(duplicate_rowids)
+    }
+
+    /// Apply the assignments of ON DUPLICATE KEY UPDATE to the row holding the key, the values of the row are
+    /// read with the writes of the statement before. Returns two if the row changed, zero otherwise
+    fn update_duplicate_row(
+        &mut self,
+        table: &TableDef,
+        assignments_sql: &str,
+        column_value_map: &HashMap<Ident, ScalarValue>,
+        rowid: &str,
+    ) -> MysqlResult<u64> {
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.read().unwrap();
+
+        let store_engine = StoreEngineFactory::try_new_with_session(self.global_context.clone(), &self.session_context, table.clone())?;
+        let table_index_list = meta_util::get_table_index_list(self.global_context.clone(), table.option.full_table_name.clone())?;
+
+        let mut row_values = HashMap::new();
+        for sparrow_column in table.get_columns() {
+            let value = core_util::read_column_value_with(table, sparrow_column, rowid, &|key| store_engine.get_key(key))?;
+            row_values.insert(
+                sparrow_column.sql_column.name.value.to_lowercase(),
+                core_util::column_value_literal(&sparrow_column.sql_column, value),
+            );
+        }
+        let mut insert_values = HashMap::new();
+        for (column_name, scalar_value) in column_value_map.iter() {
+            insert_values.insert(column_name.value.to_lowercase(), core_util::user_variable_literal(scalar_value));
+        }
+        let assignments = upsert_statement::parse_duplicate_assignments(assignments_sql, &row_values, &insert_values)?;
+
+        let tenant = core_util::get_table_tenant(self.global_context.clone(), &self.session_context, table)?;
+        let mut assigned_value_map = HashMap::new();
+        for assignment in assignments.iter() {
+            let column_name = assignment.id.value.to_lowercase();
+            let sparrow_column = match table.get_columns().iter().find(|sparrow_column| sparrow_column.sql_column.name.value.to_lowercase() == column_name) {
+                Some(sparrow_column) => sparrow_column,
+                None => {
+                    return Err(MysqlError::new_server_error(
+                        1054,
+                        "42S22",
+                        format!("Unknown column '{}' in 'field list'", assignment.id.value).as_str(),
+                    ));
+                }
+            };
+            if table.option.without_rowid {
+                // the rowid of the table without rowid is the primary key, it can not be changed
+                let primary_key_column = table_index_list
+                    .iter()
+                    .filter(|table_index| table_index.level == 1)
+                    .any(|table_index| table_index.column_name_list.iter().any(|name| name.value.to_lowercase() == column_name));
+                if primary_key_column {
+                    let message = format!("Updating the primary key column '{}' of a table without rowid is not supported", assignment.id.value);
+                    log::error!("{}", message);
+                    return Err(MysqlError::new_global_error(1105, message.as_str()));
+                }
+            }
+            if let Some((tenant_column, _)) = &tenant {
+                if tenant_column.sql_column.name.value.to_lowercase() == column_name {
+                    let message = format!("Updating the tenant column '{}' is not allowed", tenant_column.sql_column.name);
+                    log::error!("{}", message);
+                    return Err(MysqlError::new_global_error(1105, message.as_str()));
+                }
+            }
+
+            let mut value = assignment.value.clone();
+            if matches!(sparrow_column.sql_column.data_type, SQLDataType::Decimal(_, _)) {
+                value = core_util::quote_decimal_literal(&value);
+            }
+            let scalar_value = self.evaluate_constant(table, &value)?;
+            assigned_value_map.insert(sparrow_column.sql_column.name.clone(), scalar_value);
+        }
+
+        let mut assigned_value_map_list = vec![assigned_value_map];
+        core_util::cast_decimal_column_values(table, &mut assigned_value_map_list)?;
+        core_util::cast_tinyint_column_values(table, &mut assigned_value_map_list)?;
+        core_util::cast_binary_column_values(table, &mut assigned_value_map_list)?;
+        let session_time_zone = core_util::get_session_time_zone(&self.session_context);
+        core_util::cast_datetime_column_values(table, &mut assigned_value_map_list, &session_time_zone)?;
+
+        let mut column_values = vec![];
+        for (column_name, scalar_value) in assigned_value_map_list.remove(0) {
+            let sparrow_column = table.get_table_column().get_sparrow_column(column_name).unwrap();
+            let column_value = match core_util::convert_scalar_value(scalar_value)? {
+                None => vec![0x00],
+                Some(value) => value.into_bytes(),
+            };
+            column_values.push((sparrow_column.store_id, column_value));
+        }
+
+        let mut row_mutation = RowMutation::new(table, table_index_list, store_engine.as_ref());
+        let changed = row_mutation.update_row(rowid, column_values.clone())?;
+        foreign_key::update_rows(self.global_context.clone(), &self.session_context, table, &[(rowid.to_string(), column_values)])?;
+        row_mutation.write()?;
+        self.duplicates += 1;
+        Ok(if changed { 2 } else { 0 })
+    }
+
+    /// The value of the default of the column as it is stored, None if the column has no default or it is NULL
+    pub fn column_default_value(&self, table: &TableDef, sparrow_column: &SparrowColumnDef) -> MysqlResult<Option<String>> {
+        let mut default_expr = match meta_util::get_column_default(&sparrow_column.sql_column) {
+            None => return Ok(None),
+            Some(default_expr) => default_expr,
+        };
+        if matches!(sparrow_column.sql_column.data_type, SQLDataType::Decimal(_, _)) {
+            default_expr = core_util::quote_decimal_literal(&default_expr);
+        }
+        let scalar_value = self.evaluate_constant(table, &default_expr)?;
+
+        let mut column_value_map = HashMap::new();
+        column_value_map.insert(sparrow_column.sql_column.name.clone(), scalar_value);
+        let mut column_value_map_list = vec![column_value_map];
+        core_util::cast_decimal_column_values(table, &mut column_value_map_list)?;
+        core_util::cast_tinyint_column_values(table, &mut column_value_map_list)?;
+        core_util::cast_binary_column_values(table, &mut column_value_map_list)?;
+        let session_time_zone = core_util::get_session_time_zone(&self.session_context);
+        core_util::cast_datetime_column_values(table, &mut column_value_map_list, &session_time_zone)?;
+        let scalar_value = column_value_map_list.remove(0).remove(&sparrow_column.sql_column.name).unwrap();
+        core_util::convert_scalar_value(scalar_value)
+    }
+
+    /// The value of the expression without columns
+    fn evaluate_constant(&self, table: &TableDef, value: &SQLExpr) -> MysqlResult<ScalarValue> {
+        let datafusion_dfschema = table.to_datafusion_dfschema().unwrap();
+        self.evaluate_constant_with_schema(value, &datafusion_dfschema)
+    }
+
+    fn evaluate_constant_with_schema(&self, value: &SQLExpr, datafusion_dfschema: &DFSchema) -> MysqlResult<ScalarValue> {
+        let state = self.execution_context.state.lock().unwrap().clone();
+        let query_planner = SqlToRel::new(&state);
+        let expr = core_util::column_value_to_logical_expr(&query_planner, value, datafusion_dfschema)?;
+
+        let schema = Schema::empty();
+        let batch = RecordBatch::new_empty(Arc::new(schema.clone()));
+        let dfschema = schema.clone().to_dfschema().unwrap();
+        let planner = DefaultPhysicalPlanner::default();
+        let physical_expr = planner.create_physical_expr(&expr, &dfschema, &schema, &state)?;
+        match physical_expr.evaluate(&batch)? {
+            ColumnarValue::Scalar(scalar_value) => Ok(scalar_value),
+            _ => Err(MysqlError::new_global_error(1105, "Value is not a scalar value. An error occurred while evaluate the value")),
+        }
+    }
+
+    /// Delete the rows holding the unique keys of the row, then insert the row
+    fn replace_row(
+        &mut self,
+        table: &TableDef,
+        column_name_list: &[String],
+        column_value_map: HashMap<Ident, ScalarValue>,
+        duplicate_rowids: &[String],
+    ) -> MysqlResult<u64> {
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.read().unwrap();
+
+        let store_engine = StoreEngineFactory::try_new_with_session(self.global_context.clone(), &self.session_context, table.clone())?;
+        let table_index_list = meta_util::get_table_index_list(self.global_context.clone(), table.option.full_table_name.clone())?;
+        let index_keys = self.create_index_keys(table, &table_index_list, &column_value_map)?;
+
+        // the rows referring to the replaced rows are deleted or updated as by DELETE
+        foreign_key::check_insert_rows(self.global_context.clone(), &self.session_context, table, std::slice::from_ref(&column_value_map))?;
+        if !duplicate_rowids.is_empty() {
+            foreign_key::delete_rows(self.global_context.clone(), &self.session_context, table, duplicate_rowids)?;
+            let mut row_mutation = RowMutation::new(table, table_index_list, store_engine.as_ref());
+            for rowid in duplicate_rowids {
+                row_mutation.delete_row(rowid)?;
+            }
+            row_mutation.write()?;
+            self.duplicates += 1;
+        }
+
+        let mut insert = PhysicalPlanInsert::new(self.global_context.clone());
+        insert.with_session_context(self.session_context.clone());
+        let count = insert.execute(table.clone(), column_name_list.to_vec(), vec![index_keys], vec![column_value_map])?;
+        Ok(count + duplicate_rowids.len() as u64)
+    }
+
+    /// The values of the columns of the rows, with the defaults and the auto increment values filled up
+    fn create_rows(
+        &mut self,
+        origin_table_name: &ObjectName,
+        columns: Vec<Ident>,
+        source: Box<Query>,
+    ) -> MysqlResult<(TableDef, Vec<String>, Vec<HashMap<Ident, ScalarValue>>)> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, origin_table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, origin_table_name)?;
 
         let table =
             meta_util::get_table(self.global_context.clone(), full_table_name.clone()).unwrap();
 
-        let catalog_name = table.option.catalog_name.to_string();
         let schema_name = table.option.schema_name.to_string();
         let table_name = table.option.table_name.to_string();
-
-        let store_engine = StoreEngineFactory::try_new_with_table_name(
+        privilege::check_table_privilege(
             self.global_context.clone(),
-            full_table_name.clone(),
-        ).unwrap();
+            &self.session_context,
+            Privilege::Insert,
+            schema_name.as_str(),
+            table_name.as_str(),
+        )?;
 
         let state = self.execution_context.state.lock().unwrap().clone();
         let query_planner = SqlToRel::new(&state);
 
+        let mut column_name_list: Vec<String> = vec![];
+        if columns.len() < 1 {
+            for column_def in table.get_columns() {
+                column_name_list.push(column_def.sql_column.name.to_string())
+            }
+        } else {
+            for column in &columns {
+                column_name_list.push(column.to_string())
+            }
+        }
+
+        let decimal_column_name_list: Vec<String> = table
+            .get_columns()
+            .iter()
+            .filter(|column_def| matches!(column_def.sql_column.data_type, SQLDataType::Decimal(_, _)))
+            .map(|column_def| column_def.sql_column.name.to_string())
+            .collect();
+
         let mut column_values_list = vec![];
         match &source.body {
             SetExpr::Values(values) => {
                 for row_value_ast in &values.0 {
                     let mut row_value: Vec<Expr> = vec![];
-                    for column_value_ast in row_value_ast {
-                        let datafusion_dfschema = table.to_datafusion_dfschema().unwrap();
-                        let result = query_planner
-                            .sql_expr_to_logical_expr(&column_value_ast, &datafusion_dfschema);
-                        let expr = match result {
-                            Ok(v) => v,
-                            Err(e) => {
-                                let message = e.to_string();
-                                log::error!("{}", message);
-                                return Err(MysqlError::new_server_error(
-                                    1305,
-                                    "42000",
-                                    message.as_str(),
-                                ));
+                    for (column_index, column_value_ast) in row_value_ast.iter().enumerate() {
+                        let mut column_value_ast = column_value_ast.clone();
+                        if let Some(column_name) = column_name_list.get(column_index) {
+                            if decimal_column_name_list.contains(column_name) {
+                                column_value_ast = core_util::quote_decimal_literal(&column_value_ast);
                             }
-                        };
+                        }
+
+                        let datafusion_dfschema = table.to_datafusion_dfschema().unwrap();
+                        let expr = core_util::column_value_to_logical_expr(&query_planner, &column_value_ast, &datafusion_dfschema)?;
                         row_value.push(expr)
                     }
                     column_values_list.push(row_value);
@@ -99,14 +660,37 @@ impl Insert {
             _ => {}
         }
 
-        let mut column_name_list: Vec<String> = vec![];
-        if columns.len() < 1 {
-            for column_def in table.get_columns() {
-                column_name_list.push(column_def.sql_column.name.to_string())
+        for (row_index, row_value) in column_values_list.iter().enumerate() {
+            if row_value.len() != column_name_list.len() {
+                return Err(MysqlError::new_server_error(
+                    1136,
+                    "21S01",
+                    format!("Column count doesn't match value count at row {}", row_index + 1).as_str(),
+                ));
             }
-        } else {
-            for column in &columns {
-                column_name_list.push(column.to_string())
+        }
+
+        // the columns which are not given take their default
+        for sparrow_column in table.get_columns() {
+            let column_name = sparrow_column.sql_column.name.to_string();
+            if column_name_list.contains(&column_name) {
+                continue;
+            }
+            let default_expr = match meta_util::get_column_default(&sparrow_column.sql_column) {
+                None => continue,
+                Some(default_expr) => default_expr,
+            };
+            let datafusion_dfschema = table.to_datafusion_dfschema().unwrap();
+            let expr = query_planner
+                .sql_expr_to_logical_expr(&default_expr, &datafusion_dfschema)
+                .map_err(|e| {
+                    let message = e.to_string();
+                    log::error!("{}", message);
+                    MysqlError::new_server_error(1305, "42000", message.as_str())
+                })?;
+            column_name_list.push(column_name);
+            for row_value in column_values_list.iter_mut() {
+                row_value.push(expr.clone());
             }
         }
 
@@ -166,60 +750,101 @@ impl Insert {
 
             column_value_map_list.push(column_value_map);
         }
+        self.records = column_value_map_list.len() as u64;
 
-        let table_index_list =
-            meta_util::get_table_index_list(self.global_context.clone(), full_table_name.clone())
-                .unwrap();
-
-        let mut index_keys_list = vec![];
-        for row_number in 0..column_value_map_list.len() {
-            let column_value_map = column_value_map_list[row_number].clone();
-
-            let mut index_keys = vec![];
-            for table_index in table_index_list.clone() {
-                let index_key = create_table_index_key(
-                    table.clone(),
-                    table_index.clone(),
-                    column_value_map.clone(),
-                )
-                    .unwrap();
-                let index = IndexDef::new(
-                    table_index.index_name.as_str(),
-                    table_index.level,
-                    index_key.as_str(),
-                );
-                index_keys.push(index);
+        let tenant = core_util::get_table_tenant(self.global_context.clone(), &self.session_context, &table)?;
+        if let Some((sparrow_column, tenant_id)) = tenant {
+            // the rows are always written to the tenant of the session
+            let column_name = sparrow_column.sql_column.name.clone();
+            let data_type = meta_util::convert_sql_data_type_to_arrow_data_type(&sparrow_column.sql_column.data_type)?;
+            let tenant_id = core_util::convert_tenant_id_to_scalar_value(&data_type, column_name.value.as_str(), tenant_id.as_str())?;
+            for column_value_map in column_value_map_list.iter_mut() {
+                match column_value_map.get(&column_name) {
+                    Some(column_value) if *column_value != tenant_id => {
+                        let message = format!("Column '{}' must be the tenant of the session", column_name);
+                        log::error!("{}", message);
+                        return Err(MysqlError::new_global_error(1105, message.as_str()));
+                    }
+                    _ => {
+                        column_value_map.insert(column_name.clone(), tenant_id.clone());
+                    }
+                }
             }
+            if !column_name_list.contains(&column_name.to_string()) {
+                column_name_list.push(column_name.to_string());
+            }
+        }
 
-            index_keys_list.push(index_keys);
+        core_util::cast_decimal_column_values(&table, &mut column_value_map_list)?;
+        core_util::cast_tinyint_column_values(&table, &mut column_value_map_list)?;
+        core_util::cast_binary_column_values(&table, &mut column_value_map_list)?;
+        let session_time_zone = core_util::get_session_time_zone(&self.session_context);
+        core_util::cast_datetime_column_values(&table, &mut column_value_map_list, &session_time_zone)?;
+        self.generated_insert_id = core_util::fill_auto_increment(self.global_context.clone(), &table, &mut column_value_map_list)?;
+        if let Some(sparrow_column) = table.get_auto_increment_column() {
+            let column_name = sparrow_column.sql_column.name.to_string();
+            if !column_name_list.contains(&column_name) {
+                column_name_list.push(column_name);
+            }
         }
 
-        for index_keys in index_keys_list.clone() {
-            for row_index in index_keys {
-                if row_index.level == 1 || row_index.level == 2 {
-                    match store_engine.get_key(row_index.index_key.clone()).unwrap() {
-                        None => {}
-                        Some(_) => {
-                            if !overwrite {
-                                return Err(MysqlError::new_server_error(
-                                    1062,
-                                    "23000",
-                                    format!(
-                                        "Duplicate entry '{:?}' for key '{:?}.{:?}'",
-                                        row_index.index_key,
-                                        table_name.clone(),
-                                        row_index.index_name,
-                                    )
-                                        .as_str(),
-                                ));
-                            }
-                        }
-                    }
+        Ok((table, column_name_list, column_value_map_list))
+    }
+
+    /// The keys of the indexes of the row, the row with a NULL value is not in the index without the index_nulls option
+    fn create_index_keys(
+        &self,
+        table: &TableDef,
+        table_index_list: &[TableIndexDef],
+        column_value_map: &HashMap<Ident, ScalarValue>,
+    ) -> MysqlResult<Vec<IndexDef>> {
+        let mut index_keys = vec![];
+        for table_index in table_index_list.iter() {
+            let contains_null = table_index.column_name_list.iter().any(|column_name| {
+                match column_value_map.get(column_name) {
+                    Some(scalar_value) => scalar_value.is_null(),
+                    None => true,
                 }
+            });
+            if contains_null && table_index.level == 1 {
+                // the columns of the primary key are always NOT NULL, each one of the composite key too
+                let column_name = table_index.column_name_list.iter().find(|column_name| {
+                    match column_value_map.get(column_name) {
+                        Some(scalar_value) => scalar_value.is_null(),
+                        None => true,
+                    }
+                }).unwrap();
+                return Err(MysqlError::new_server_error(
+                    1048,
+                    "23000",
+                    format!("Column '{}' cannot be null", column_name).as_str(),
+                ));
+            }
+            if contains_null && !table.option.index_nulls {
+                // without the index_nulls option the NULL values are not indexed
+                continue;
             }
+
+            let mut index_column_value_map = column_value_map.clone();
+            for column_name in table_index.column_name_list.iter() {
+                // the column which is not given is NULL
+                index_column_value_map.entry(column_name.clone()).or_insert(ScalarValue::Utf8(None));
+            }
+            let index_key = create_table_index_key(
+                table.clone(),
+                table_index.clone(),
+                index_column_value_map,
+            )
+                .unwrap();
+            let mut index = IndexDef::new(
+                table_index.index_name.as_str(),
+                table_index.level,
+                index_key.as_str(),
+            );
+            index.with_contains_null(contains_null);
+            index_keys.push(index);
         }
 
-        let insert = PhysicalPlanInsert::new(self.global_context.clone());
-        insert.execute(table.clone(), column_name_list.clone(), index_keys_list.clone(), column_value_map_list.clone())
+        Ok(index_keys)
     }
 }
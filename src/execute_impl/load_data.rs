@@ -0,0 +1,338 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::{ColumnDef, DataType as SQLDataType, Expr as SQLExpr, Ident, Query, SetExpr, Value, Values};
+use tokio::sync::mpsc::Receiver;
+
+use crate::core::core_util;
+use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
+use crate::execute_impl::insert::Insert;
+use crate::meta::meta_util;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::util::load_data_statement::{LoadDataDuplicate, LoadDataReader, LoadDataStatement};
+
+/// The counts of LOAD DATA, reported as `Records: 3  Deleted: 0  Skipped: 1  Warnings: 1`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadDataCount {
+    /// The rows read from the file
+    pub records: u64,
+    /// The rows written to the table
+    pub loaded: u64,
+    pub deleted: u64,
+    /// The rows which failed and are not written
+    pub skipped: u64,
+    pub warnings: u64,
+}
+
+/// The rows written to the store in one batch, the batch which fails is loaded row by row
+/// to find the rows to skip
+const LOAD_DATA_BATCH_ROWS: usize = 1000;
+
+/// The file of LOAD DATA LOCAL read as its packets are received from the client,
+/// the error of the connection ends the file with the error
+pub struct LocalInfileReader {
+    packets: Receiver<io::Result<Vec<u8>>>,
+    packet: Vec<u8>,
+    position: usize,
+}
+
+impl LocalInfileReader {
+    pub fn new(packets: Receiver<io::Result<Vec<u8>>>) -> Self {
+        Self {
+            packets,
+            packet: vec![],
+            position: 0,
+        }
+    }
+}
+
+impl Read for LocalInfileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position >= self.packet.len() {
+            match self.packets.blocking_recv() {
+                Some(packet) => {
+                    self.packet = packet?;
+                    self.position = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let length = buf.len().min(self.packet.len() - self.position);
+        buf[..length].copy_from_slice(&self.packet[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+/// The rows of a batch, the rows of a batch have the same columns
+struct LoadDataBatch {
+    columns: Vec<Ident>,
+    rows: Vec<(u64, Vec<SQLExpr>)>,
+}
+
+pub struct LoadData {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl LoadData {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// Load the file of the server into the table, the file is read as the rows are written
+    pub fn execute(&mut self, statement: LoadDataStatement) -> MysqlResult<LoadDataCount> {
+        if statement.local {
+            return Err(MysqlError::new_server_error(
+                1148,
+                "42000",
+                "The used command is not allowed with this MySQL version",
+            ));
+        }
+        let file = self.open_file(statement.file_name.as_str())?;
+        self.load(&statement, BufReader::new(file))
+    }
+
+    /// Load the file of LOAD DATA LOCAL sent by the client, the file is read as its packets are received
+    pub fn execute_local(&mut self, statement: LoadDataStatement, reader: LocalInfileReader) -> MysqlResult<LoadDataCount> {
+        self.load(&statement, BufReader::new(reader))
+    }
+
+    /// Load the rows into the table in batches. The row which fails the conversion or the constraints
+    /// stops the load in the strict sql mode, without it or with IGNORE the row is skipped with a warning, as in mysql
+    fn load<R: BufRead>(&mut self, statement: &LoadDataStatement, reader: R) -> MysqlResult<LoadDataCount> {
+        if statement.duplicate == LoadDataDuplicate::Replace {
+            return Err(MysqlError::new_server_error(
+                1235,
+                "42000",
+                "This version of MySQL doesn't yet support 'LOAD DATA ... REPLACE'",
+            ));
+        }
+
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &statement.table_name)?;
+        let table = meta_util::get_table(self.global_context.clone(), full_table_name.clone())?;
+
+        let mut column_list = vec![];
+        if statement.columns.is_empty() {
+            for sparrow_column in table.get_columns() {
+                column_list.push(sparrow_column.sql_column.clone());
+            }
+        } else {
+            for column_name in statement.columns.iter() {
+                match table.get_table_column().get_sparrow_column(column_name.clone()) {
+                    Ok(sparrow_column) => column_list.push(sparrow_column.sql_column),
+                    Err(_) => {
+                        let message = format!("Unknown column '{}' in 'field list'", column_name);
+                        log::error!("{}", message);
+                        return Err(MysqlError::new_server_error(1054, "42S22", message.as_str()));
+                    }
+                }
+            }
+        }
+
+        let mut reader = LoadDataReader::new(reader, statement.format.clone());
+
+        let strict = statement.duplicate != LoadDataDuplicate::Ignore
+            && core_util::is_strict_sql_mode(self.session_context.sql_mode.lock().unwrap().as_str());
+
+        let mut load_data_count = LoadDataCount::default();
+        let mut batch = LoadDataBatch { columns: vec![], rows: vec![] };
+        loop {
+            let (line_number, fields) = match reader.next_row() {
+                Ok(Some(row)) => row,
+                Ok(None) => break,
+                Err(error) => {
+                    let message = format!("Error reading file '{}' (Errcode: {})", statement.file_name, error);
+                    log::error!("{}", message);
+                    return Err(MysqlError::new_server_error(1024, "HY000", message.as_str()));
+                }
+            };
+            if line_number <= statement.ignore_lines {
+                continue;
+            }
+            load_data_count.records += 1;
+
+            let result = self.convert_row(&column_list, line_number, fields, strict);
+            match result {
+                Ok((columns, row_value, warnings)) => {
+                    load_data_count.warnings += warnings;
+                    if columns != batch.columns || batch.rows.len() >= LOAD_DATA_BATCH_ROWS {
+                        self.flush_batch(statement, &mut batch, strict, &mut load_data_count)?;
+                        batch.columns = columns;
+                    }
+                    batch.rows.push((line_number, row_value));
+                }
+                Err(mysql_error) if !strict => {
+                    log::warn!("Skipped the row {} of '{}': {}", line_number, statement.file_name, mysql_error.message());
+                    self.session_context.push_warning(mysql_error.error_number(), mysql_error.message().as_str());
+                    load_data_count.skipped += 1;
+                    load_data_count.warnings += 1;
+                }
+                Err(mysql_error) => return Err(mysql_error),
+            }
+        }
+        self.flush_batch(statement, &mut batch, strict, &mut load_data_count)?;
+        Ok(load_data_count)
+    }
+
+    /// Write the rows of the batch at once, a failed batch is written row by row without the strict sql mode
+    fn flush_batch(
+        &self,
+        statement: &LoadDataStatement,
+        batch: &mut LoadDataBatch,
+        strict: bool,
+        load_data_count: &mut LoadDataCount,
+    ) -> MysqlResult<()> {
+        if batch.rows.is_empty() {
+            return Ok(());
+        }
+        let rows = std::mem::take(&mut batch.rows);
+
+        let row_values = rows.iter().map(|(_, row_value)| row_value.clone()).collect::<Vec<_>>();
+        let result = self.insert_rows(statement, batch.columns.clone(), row_values);
+        match result {
+            Ok(_) => {
+                load_data_count.loaded += rows.len() as u64;
+                return Ok(());
+            }
+            Err(mysql_error) if strict => return Err(mysql_error),
+            Err(_) => {}
+        }
+
+        for (line_number, row_value) in rows {
+            let result = self.insert_rows(statement, batch.columns.clone(), vec![row_value]);
+            match result {
+                Ok(_) => load_data_count.loaded += 1,
+                Err(mysql_error) => {
+                    log::warn!("Skipped the row {} of '{}': {}", line_number, statement.file_name, mysql_error.message());
+                    self.session_context.push_warning(mysql_error.error_number(), mysql_error.message().as_str());
+                    load_data_count.skipped += 1;
+                    load_data_count.warnings += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_rows(&self, statement: &LoadDataStatement, columns: Vec<Ident>, row_values: Vec<Vec<SQLExpr>>) -> MysqlResult<u64> {
+        let source = Box::new(Query {
+            with: None,
+            body: SetExpr::Values(Values(row_values)),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+        });
+        let mut insert = Insert::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.execution_context.clone(),
+        );
+        insert.execute(statement.table_name.clone(), columns, false, source)
+    }
+
+    /// The file under the secure_file_priv directory, the relative name is relative to the directory
+    fn open_file(&self, file_name: &str) -> MysqlResult<File> {
+        let secure_file_priv = self.global_context.lock().unwrap().my_config.server.secure_file_priv.clone();
+        let secure_file_priv_error = MysqlError::new_server_error(
+            1290,
+            "HY000",
+            "The MySQL server is running with the --secure-file-priv option so it cannot execute this statement",
+        );
+        let secure_directory = match secure_file_priv.map(|directory| Path::new(directory.as_str()).canonicalize()) {
+            Some(Ok(secure_directory)) => secure_directory,
+            _ => return Err(secure_file_priv_error),
+        };
+
+        let file_not_found_error = MysqlError::new_server_error(
+            29,
+            "HY000",
+            format!("File '{}' not found (Errcode: 2 - No such file or directory)", file_name).as_str(),
+        );
+        let file_path = match secure_directory.join(file_name).canonicalize() {
+            Ok(file_path) => file_path,
+            Err(_) => return Err(file_not_found_error),
+        };
+        if !file_path.starts_with(&secure_directory) {
+            return Err(secure_file_priv_error);
+        }
+        File::open(file_path).map_err(|_| file_not_found_error)
+    }
+
+    /// The columns and the values of the row of the line, with the warnings of the row
+    fn convert_row(
+        &self,
+        column_list: &[ColumnDef],
+        line_number: u64,
+        fields: Vec<Option<String>>,
+        strict: bool,
+    ) -> MysqlResult<(Vec<Ident>, Vec<SQLExpr>, u64)> {
+        let mut warnings = 0;
+        if fields.len() != column_list.len() {
+            let mysql_error = if fields.len() < column_list.len() {
+                MysqlError::new_server_error(
+                    1261,
+                    "01000",
+                    format!("Row {} doesn't contain data for all columns", line_number).as_str(),
+                )
+            } else {
+                MysqlError::new_server_error(
+                    1262,
+                    "01000",
+                    format!("Row {} was truncated; it contained more data than there were input columns", line_number).as_str(),
+                )
+            };
+            if strict {
+                return Err(mysql_error);
+            }
+            // the missing columns are not written, and the extra fields are dropped
+            log::warn!("{}", mysql_error.message());
+            self.session_context.push_warning(mysql_error.error_number(), mysql_error.message().as_str());
+            warnings += 1;
+        }
+
+        let mut columns: Vec<Ident> = vec![];
+        let mut row_value = vec![];
+        for (column, field) in column_list.iter().zip(fields.into_iter()) {
+            columns.push(column.name.clone());
+            row_value.push(convert_field_to_expr(column, field, line_number)?);
+        }
+
+        Ok((columns, row_value, warnings))
+    }
+}
+
+/// The value of the field for the column, the numbers are checked before they are written
+fn convert_field_to_expr(column: &ColumnDef, field: Option<String>, line_number: u64) -> MysqlResult<SQLExpr> {
+    let text = match field {
+        None => return Ok(SQLExpr::Value(Value::Null)),
+        Some(text) => text,
+    };
+
+    let (type_name, valid) = match column.data_type {
+        SQLDataType::TinyInt(_) | SQLDataType::Int(_) => ("integer", text.trim().parse::<i64>().is_ok()),
+        SQLDataType::Float(_) | SQLDataType::Double => ("double", text.trim().parse::<f64>().is_ok()),
+        _ => return Ok(SQLExpr::Value(Value::SingleQuotedString(text))),
+    };
+    if !valid {
+        return Err(MysqlError::new_server_error(
+            1366,
+            "HY000",
+            format!("Incorrect {} value: '{}' for column '{}' at row {}", type_name, text, column.name, line_number).as_str(),
+        ));
+    }
+    Ok(SQLExpr::Value(Value::Number(text.trim().to_string(), false)))
+}
@@ -1,26 +1,46 @@
 pub mod add_column;
+pub mod analyze_table;
 pub mod com_field_list;
 pub mod com_stmt_prepare;
 pub mod create_db;
+pub mod create_index;
 pub mod create_table;
+pub mod create_temporary_table;
+pub mod create_user;
+pub mod create_view;
 pub mod delete;
 pub mod drop_column;
+pub mod drop_index;
 pub mod drop_schema;
 pub mod drop_table;
+pub mod drop_temporary_table;
+pub mod drop_user;
+pub mod drop_view;
 pub mod explain;
+pub mod grant;
 pub mod insert;
+pub mod load_data;
+pub mod modify_column;
+pub mod rename_table;
 pub mod select;
 pub mod set_default_schema;
+pub mod set_password;
 pub mod set_variable;
 pub mod show_charset;
 pub mod show_collation;
 pub mod show_columns_from_table;
+pub mod show_config;
+pub mod show_create_db;
 pub mod show_create_table;
 pub mod show_databases;
 pub mod show_engines;
 pub mod show_grants;
+pub mod show_prepared_statements;
 pub mod show_privileges;
+pub mod show_profile;
 pub mod show_table_status;
 pub mod show_tables;
 pub mod show_variables;
+pub mod show_warnings;
+pub mod truncate_table;
 pub mod update;
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use datafusion::scalar::ScalarValue;
+use sqlparser::ast::{Assignment, ColumnDef, ColumnOption, DataType as SQLDataType, Expr as SQLExpr, Ident, ObjectName, Value};
+
+use crate::core::core_util;
+use crate::core::core_util::register_all_table;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::execute_impl::delete::DeleteFrom;
+use crate::execute_impl::update::Update;
+use crate::meta::initial;
+use crate::meta::meta_const;
+use crate::meta::meta_def::{SparrowColumnDef, TableColumnDef, TableDef, TableIndexDef};
+use crate::meta::meta_util;
+use crate::meta::meta_util::load_all_table;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::store::engine::row_mutation::RowMutation;
+
+pub struct ModifyColumn {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl ModifyColumn {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// Change the definition of the column, its store id and its position are kept. The stored values are
+    /// cast to the new type, they are checked before the definition changes and written after it, so the
+    /// rows written in between are cast too. Returns the number of rows rewritten
+    pub async fn execute(&mut self, table_name: ObjectName, column_name: Ident, column_def: ColumnDef) -> MysqlResult<u64> {
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        let table_def = meta_util::get_table(self.global_context.clone(), full_table_name.clone())?;
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Alter,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
+
+        let sparrow_column = match find_column(&table_def, &column_name) {
+            Some(sparrow_column) => sparrow_column,
+            None => {
+                let message = format!("Unknown column '{}' in '{}'", column_name, table_def.option.table_name);
+                log::error!("{}", message);
+                return Err(MysqlError::new_server_error(1054, "42S22", message.as_str()));
+            }
+        };
+        let column_name = sparrow_column.sql_column.name.clone();
+
+        let mut column_def = column_def;
+        column_def.data_type = meta_util::normalize_sql_data_type(&column_def.data_type)?;
+        let default_collation = meta_util::get_table_default_collation(self.global_context.clone(), &table_def.option)?;
+        meta_util::resolve_column_collation(&mut column_def, default_collation)?;
+        if column_def.options.iter().any(|option| matches!(option.option, ColumnOption::Unique { .. })) {
+            return Err(MysqlError::new_server_error(
+                1235,
+                "42000",
+                "This version of MySQL doesn't yet support 'MODIFY COLUMN with PRIMARY KEY or UNIQUE'",
+            ));
+        }
+        let renamed = column_def.name.value.to_lowercase() != column_name.value.to_lowercase();
+        if renamed && find_column(&table_def, &column_def.name).is_some() {
+            let message = format!("Duplicate column name '{}'", column_def.name);
+            log::error!("{}", message);
+            return Err(MysqlError::new_server_error(1060, "42S21", message.as_str()));
+        }
+
+        // the indexes with the column, their entries move with the values
+        let table_index_list: Vec<TableIndexDef> = meta_util::get_table_index_list(self.global_context.clone(), full_table_name.clone())?
+            .into_iter()
+            .filter(|table_index| table_index.column_name_list.iter().any(|name| name.value.to_lowercase() == column_name.value.to_lowercase()))
+            .collect();
+        if table_def.option.without_rowid && table_index_list.iter().any(|table_index| table_index.level == 1) {
+            // the rowid of the table without rowid is the primary key, it can not be changed
+            let message = format!("Modifying the primary key column '{}' of a table without rowid is not supported", column_name);
+            log::error!("{}", message);
+            return Err(MysqlError::new_global_error(1105, message.as_str()));
+        }
+
+        let new_sparrow_column = SparrowColumnDef::new(sparrow_column.store_id, sparrow_column.ordinal_position, column_def.clone());
+        let mut new_table_def = table_def.clone();
+        let sparrow_column_list = table_def
+            .get_columns()
+            .iter()
+            .map(|column| if column.store_id == sparrow_column.store_id { new_sparrow_column.clone() } else { column.clone() })
+            .collect();
+        let mut table_column = TableColumnDef::default();
+        table_column.load_sparrow_column_list(sparrow_column_list);
+        new_table_def.with_column(table_column);
+        let new_table_index_list: Vec<TableIndexDef> = table_index_list
+            .iter()
+            .map(|table_index| {
+                let mut table_index = table_index.clone();
+                for index_column_name in table_index.column_name_list.iter_mut() {
+                    if index_column_name.value.to_lowercase() == column_name.value.to_lowercase() {
+                        *index_column_name = column_def.name.clone();
+                    }
+                }
+                table_index
+            })
+            .collect();
+
+        // the values are stored as text, only the change of the type casts them
+        let rewrite = sparrow_column.sql_column.data_type != column_def.data_type;
+        if rewrite {
+            self.rewrite_rows(&table_def, &new_table_def, &sparrow_column, &table_index_list, &new_table_index_list, false)?;
+        }
+
+        self.delete_metadata(&table_def, &column_name).await?;
+        initial::add_information_schema_columns(self.global_context.clone(), table_def.option.clone(), vec![new_sparrow_column])?;
+        if renamed {
+            self.rename_index_column(&table_def, &column_name, &column_def.name).await?;
+        }
+
+        load_all_table(self.global_context.clone())?;
+        register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context.clone())?;
+
+        if !rewrite {
+            return Ok(0);
+        }
+        self.rewrite_rows(&table_def, &new_table_def, &sparrow_column, &table_index_list, &new_table_index_list, true)
+    }
+
+    /// Cast the values of the column of all the rows to the new type in one batch, the entries of the indexes
+    /// with the column move with the values. Without write the values are only cast, a value which can not be
+    /// cast fails the statement before anything changes
+    fn rewrite_rows(
+        &self,
+        table_def: &TableDef,
+        new_table_def: &TableDef,
+        sparrow_column: &SparrowColumnDef,
+        table_index_list: &[TableIndexDef],
+        new_table_index_list: &[TableIndexDef],
+        write: bool,
+    ) -> MysqlResult<u64> {
+        // the writers wait for the rewrite, the values they wrote with the new definition are cast to themselves
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = if write { Some(index_build_lock.write().unwrap()) } else { None };
+
+        let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), new_table_def.clone())?;
+        let new_sparrow_column = new_table_def
+            .get_columns()
+            .iter()
+            .find(|column| column.store_id == sparrow_column.store_id)
+            .unwrap()
+            .clone();
+        let new_column_name = new_sparrow_column.sql_column.name.clone();
+
+        let rowid_list = core_util::scan_rowids(store_engine.as_ref(), table_def)?;
+        let mut value_list = vec![];
+        let mut column_value_map_list = vec![];
+        for (row_index, rowid) in rowid_list.iter().enumerate() {
+            let value = core_util::read_column_value_with(table_def, sparrow_column, rowid, &|key| store_engine.get_key(key))?;
+            let scalar_value = stored_scalar_value(&new_sparrow_column, value.clone(), row_index + 1)?;
+            let mut column_value_map = HashMap::new();
+            column_value_map.insert(new_column_name.clone(), scalar_value);
+            column_value_map_list.push(column_value_map);
+            value_list.push(value);
+        }
+        core_util::cast_decimal_column_values(new_table_def, &mut column_value_map_list)?;
+        core_util::cast_tinyint_column_values(new_table_def, &mut column_value_map_list)?;
+        core_util::cast_binary_column_values(new_table_def, &mut column_value_map_list)?;
+        let session_time_zone = core_util::get_session_time_zone(&self.session_context);
+        core_util::cast_datetime_column_values(new_table_def, &mut column_value_map_list, &session_time_zone)?;
+
+        let mut row_mutation = RowMutation::new(new_table_def, new_table_index_list.to_vec(), store_engine.as_ref());
+        for ((rowid, value), mut column_value_map) in rowid_list.iter().zip(value_list).zip(column_value_map_list) {
+            let new_value = core_util::convert_scalar_value(column_value_map.remove(&new_column_name).unwrap())?;
+            if new_value == value {
+                continue;
+            }
+            let old_index_keys = core_util::read_index_keys(table_def, table_index_list, store_engine.as_ref(), rowid)?;
+            let column_value = match new_value {
+                None => vec![0x00],
+                Some(value) => value.into_bytes(),
+            };
+            row_mutation.rewrite_row(rowid, vec![(sparrow_column.store_id, column_value)], old_index_keys)?;
+        }
+        if write {
+            row_mutation.write()?;
+        }
+
+        Ok(rowid_list.len() as u64)
+    }
+
+    async fn delete_metadata(&mut self, table_def: &TableDef, column_name: &Ident) -> MysqlResult<u64> {
+        let metadata_table_name = meta_util::create_full_table_name(
+            meta_const::CATALOG_NAME,
+            meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS,
+        );
+        let selection = core_util::build_find_column_sqlwhere(
+            table_def.option.catalog_name.as_ref(),
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+            column_name.to_string().as_str(),
+        );
+        let mut delete_from = DeleteFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.execution_context.clone(),
+        );
+        delete_from.execute(metadata_table_name, Some(selection)).await
+    }
+
+    /// The indexes refer to their columns by name in information_schema.statistics and key_column_usage
+    async fn rename_index_column(&mut self, table_def: &TableDef, column_name: &Ident, new_column_name: &Ident) -> MysqlResult<()> {
+        let metadata_table_names = vec![
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_STATISTICS,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE,
+        ];
+        for metadata_table_name in metadata_table_names {
+            let metadata_table_name = meta_util::create_full_table_name(
+                meta_const::CATALOG_NAME,
+                meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+                metadata_table_name,
+            );
+            let assignments = vec![Assignment {
+                id: Ident::new(meta_const::COLUMN_INFORMATION_SCHEMA_COLUMN_NAME),
+                value: SQLExpr::Value(Value::SingleQuotedString(new_column_name.to_string())),
+            }];
+            let selection = core_util::build_find_column_sqlwhere(
+                table_def.option.catalog_name.as_ref(),
+                table_def.option.schema_name.as_str(),
+                table_def.option.table_name.as_str(),
+                column_name.to_string().as_str(),
+            );
+            let mut update_set = Update::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.execution_context.clone(),
+            );
+            update_set.execute(metadata_table_name, assignments, Some(selection)).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The column of the table by its name, the names of the columns are case insensitive
+fn find_column(table_def: &TableDef, column_name: &Ident) -> Option<SparrowColumnDef> {
+    table_def
+        .get_columns()
+        .iter()
+        .find(|sparrow_column| sparrow_column.sql_column.name.value.to_lowercase() == column_name.value.to_lowercase())
+        .cloned()
+}
+
+/// The text stored for the old type as the value given to the column of the new type, the numbers are
+/// parsed here and the other values are cast as the inserted ones
+fn stored_scalar_value(sparrow_column: &SparrowColumnDef, value: Option<String>, row_number: usize) -> MysqlResult<ScalarValue> {
+    let column_name = &sparrow_column.sql_column.name;
+    match sparrow_column.sql_column.data_type {
+        SQLDataType::TinyInt(_) | SQLDataType::Int(_) => {
+            let value = match value {
+                None => return Ok(ScalarValue::Int64(None)),
+                Some(value) => value,
+            };
+            if let Ok(number) = value.trim().parse::<i64>() {
+                return Ok(ScalarValue::Int64(Some(number)));
+            }
+            // the fractions are rounded as mysql does
+            match value.trim().parse::<f64>() {
+                Ok(number) if number.round() >= i64::MIN as f64 && number.round() <= i64::MAX as f64 => {
+                    Ok(ScalarValue::Int64(Some(number.round() as i64)))
+                }
+                Ok(_) => Err(MysqlError::new_server_error(
+                    1264,
+                    "22003",
+                    format!("Out of range value for column '{}' at row {}", column_name, row_number).as_str(),
+                )),
+                Err(_) => Err(MysqlError::new_server_error(
+                    1366,
+                    "HY000",
+                    format!("Incorrect integer value: '{}' for column '{}' at row {}", value, column_name, row_number).as_str(),
+                )),
+            }
+        }
+        SQLDataType::Float(_) | SQLDataType::Double => {
+            let value = match value {
+                None => return Ok(ScalarValue::Float64(None)),
+                Some(value) => value,
+            };
+            match value.trim().parse::<f64>() {
+                Ok(number) => Ok(ScalarValue::Float64(Some(number))),
+                Err(_) => Err(MysqlError::new_server_error(
+                    1366,
+                    "HY000",
+                    format!("Incorrect double value: '{}' for column '{}' at row {}", value, column_name, row_number).as_str(),
+                )),
+            }
+        }
+        _ => Ok(ScalarValue::Utf8(value)),
+    }
+}
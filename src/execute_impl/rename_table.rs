@@ -0,0 +1,239 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::{Assignment, BinaryOperator, Expr as SQLExpr, Ident, ObjectName, Value};
+
+use crate::core::core_util;
+use crate::core::core_util::register_all_table;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::execute_impl::update::Update;
+use crate::meta::meta_const;
+use crate::meta::meta_def::TableDef;
+use crate::meta::meta_util;
+use crate::meta::meta_util::load_all_table;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::store::engine::engine_util::StoreEngineFactory;
+
+pub struct RenameTable {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl RenameTable {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// Rename the tables one after the other, so the tables can swap their names through a temporary one.
+    /// No row is affected as in mysql
+    pub async fn execute(&mut self, table_names: Vec<(ObjectName, ObjectName)>) -> MysqlResult<u64> {
+        for (table_name, new_table_name) in table_names {
+            self.rename_table(table_name, new_table_name).await?;
+        }
+        Ok(0)
+    }
+
+    /// The metadata of the table is renamed in place, the keys of the table with a table id stay. The table
+    /// keyed by its full table name gets a table id, its keys move to the table id once
+    async fn rename_table(&mut self, table_name: ObjectName, new_table_name: ObjectName) -> MysqlResult<()> {
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        let table_def = meta_util::get_table(self.global_context.clone(), full_table_name.clone())?;
+        for table_privilege in [Privilege::Alter, Privilege::Drop] {
+            privilege::check_table_privilege(
+                self.global_context.clone(),
+                &self.session_context,
+                table_privilege,
+                table_def.option.schema_name.as_str(),
+                table_def.option.table_name.as_str(),
+            )?;
+        }
+
+        let new_full_table_name = meta_util::resolve_table_name(&mut self.session_context, &new_table_name)?;
+        let new_schema_name = new_full_table_name.0[1].to_string();
+        let new_table_name = new_full_table_name.0[2].to_string();
+        for table_privilege in [Privilege::Create, Privilege::Insert] {
+            privilege::check_table_privilege(
+                self.global_context.clone(),
+                &self.session_context,
+                table_privilege,
+                new_schema_name.as_str(),
+                new_table_name.as_str(),
+            )?;
+        }
+
+        let full_schema_name = meta_util::create_full_schema_name(meta_const::CATALOG_NAME, new_schema_name.as_str());
+        if self.global_context.lock().unwrap().meta_data.get_schema(full_schema_name).is_none() {
+            let message = format!("Unknown database '{}'", new_schema_name);
+            log::error!("{}", message);
+            return Err(MysqlError::new_server_error(1049, "42000", message.as_str()));
+        }
+        let view_exists = self.global_context.lock().unwrap().meta_data.get_view(new_full_table_name.clone()).is_some();
+        if view_exists || meta_util::get_table(self.global_context.clone(), new_full_table_name.clone()).is_ok() {
+            let message = format!("Table '{}' already exists", new_table_name);
+            log::error!("{}", message);
+            return Err(MysqlError::new_server_error(1050, "42S01", message.as_str()));
+        }
+
+        let new_table_id = match table_def.option.table_id {
+            0 => Some(meta_util::next_table_id(self.global_context.clone())?),
+            _ => None,
+        };
+        self.rename_metadata(&table_def, new_schema_name.as_str(), new_table_name.as_str(), new_table_id).await?;
+
+        {
+            // no row is written in the middle of its keys being moved
+            let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+            let _index_build_guard = index_build_lock.write().unwrap();
+
+            if let Some(new_table_id) = new_table_id {
+                let mut new_table_option = table_def.option.clone();
+                new_table_option.with_table_id(new_table_id);
+                let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table_def.clone())?;
+                meta_util::move_table_keys(store_engine.as_ref(), &table_def.option, &new_table_option)?;
+            }
+
+            self.global_context.lock().unwrap().meta_data.delete_table(full_table_name.clone());
+            load_all_table(self.global_context.clone())?;
+        }
+
+        let result = self
+            .execution_context
+            .deregister_table(full_table_name.to_string().as_str());
+        if let Err(error) = result {
+            return Err(MysqlError::from(error));
+        }
+        register_all_table(self.global_context.clone(), &self.session_context, &mut self.execution_context)?;
+
+        Ok(())
+    }
+
+    /// The rows of the table in information_schema.tables, columns, statistics, key_column_usage,
+    /// table_constraints and referential_constraints take the new schema name and the new table name, the row
+    /// of the table takes the new table id. The foreign keys referring to the table refer to the new name
+    async fn rename_metadata(&mut self, table_def: &TableDef, new_schema_name: &str, new_table_name: &str, new_table_id: Option<u64>) -> MysqlResult<()> {
+        let metadata_table_names = vec![
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_STATISTICS,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE,
+            meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLE_CONSTRAINTS,
+        ];
+        for metadata_table_name in metadata_table_names {
+            let mut assignments = vec![
+                Assignment {
+                    id: Ident::new(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA),
+                    value: SQLExpr::Value(Value::SingleQuotedString(new_schema_name.to_string())),
+                },
+                Assignment {
+                    id: Ident::new(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME),
+                    value: SQLExpr::Value(Value::SingleQuotedString(new_table_name.to_string())),
+                },
+            ];
+            // the constraints of the table belong to the schema of the table
+            if metadata_table_name == meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE
+                || metadata_table_name == meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLE_CONSTRAINTS
+            {
+                assignments.push(Assignment {
+                    id: Ident::new(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_CONSTRAINT_SCHEMA),
+                    value: SQLExpr::Value(Value::SingleQuotedString(new_schema_name.to_string())),
+                });
+            }
+            if metadata_table_name == meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES {
+                if let Some(new_table_id) = new_table_id {
+                    assignments.push(Assignment {
+                        id: Ident::new(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_ID),
+                        value: SQLExpr::Value(Value::Number(new_table_id.to_string(), false)),
+                    });
+                }
+            }
+
+            // table_constraints has no table_catalog, the table is found by its schema and its name
+            let selection = SQLExpr::BinaryOp {
+                left: Box::new(SQLExpr::BinaryOp {
+                    left: Box::new(SQLExpr::Identifier(Ident::new(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA))),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(SQLExpr::Value(Value::SingleQuotedString(table_def.option.schema_name.clone()))),
+                }),
+                op: BinaryOperator::And,
+                right: Box::new(SQLExpr::BinaryOp {
+                    left: Box::new(SQLExpr::Identifier(Ident::new(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME))),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(SQLExpr::Value(Value::SingleQuotedString(table_def.option.table_name.clone()))),
+                }),
+            };
+
+            let metadata_table_name = meta_util::create_full_table_name(
+                meta_const::CATALOG_NAME,
+                meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+                metadata_table_name,
+            );
+            let mut update_set = Update::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.execution_context.clone(),
+            );
+            update_set.execute(metadata_table_name, assignments, Some(selection)).await?;
+        }
+
+        // referential_constraints has no table_schema, the foreign keys are found by the schema of their constraints
+        let metadata_updates = vec![
+            (
+                meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS,
+                meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_CONSTRAINT_SCHEMA,
+                meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_TABLE_NAME,
+            ),
+            (
+                meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS,
+                meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_UNIQUE_CONSTRAINT_SCHEMA,
+                meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_REFERENCED_TABLE_NAME,
+            ),
+            (
+                meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE,
+                meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_REFERENCED_TABLE_SCHEMA,
+                meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_REFERENCED_TABLE_NAME,
+            ),
+        ];
+        for (metadata_table_name, schema_column_name, table_column_name) in metadata_updates {
+            let assignments = vec![
+                Assignment {
+                    id: Ident::new(schema_column_name),
+                    value: SQLExpr::Value(Value::SingleQuotedString(new_schema_name.to_string())),
+                },
+                Assignment {
+                    id: Ident::new(table_column_name),
+                    value: SQLExpr::Value(Value::SingleQuotedString(new_table_name.to_string())),
+                },
+            ];
+            let selection = core_util::build_find_pair_sqlwhere(
+                schema_column_name,
+                table_def.option.schema_name.as_str(),
+                table_column_name,
+                table_def.option.table_name.as_str(),
+            );
+
+            let metadata_table_name = meta_util::create_full_table_name(
+                meta_const::CATALOG_NAME,
+                meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+                metadata_table_name,
+            );
+            let mut update_set = Update::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.execution_context.clone(),
+            );
+            update_set.execute(metadata_table_name, assignments, Some(selection)).await?;
+        }
+        Ok(())
+    }
+}
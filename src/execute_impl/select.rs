@@ -1,28 +1,46 @@
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use arrow::datatypes::SchemaRef;
-use datafusion::arrow::record_batch::RecordBatch;
+use arrow::array::BooleanBuilder;
+use arrow::compute::{cast, filter_record_batch};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
 use datafusion::execution::context::ExecutionContext;
-use datafusion::logical_plan::LogicalPlan;
-use datafusion::physical_plan::{collect, ExecutionPlan};
+use datafusion::logical_plan::{lit, Column, DFSchema, Expr, JoinType, LogicalPlan, LogicalPlanBuilder};
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::scalar::ScalarValue;
+use futures::future::BoxFuture;
+use futures::{stream, FutureExt, StreamExt, TryStreamExt};
 use datafusion::sql::planner::{ContextProvider, SqlToRel};
-use sqlparser::ast::{AlterTableOperation, Query};
+use sqlparser::ast::{AlterTableOperation, Cte, Expr as SQLExpr, Ident, ObjectName, OrderByExpr, Query, SetExpr, SetOperator, Value};
+use tokio_util::sync::CancellationToken;
 
 use crate::core::core_util;
 use crate::core::core_util::{register_all_table, check_table_exists};
 use crate::core::global_context::GlobalContext;
+use crate::core::plan_rewrite;
+use crate::core::privilege;
+use crate::core::plan_rewrite::PlanRewriteContext;
+use crate::core::profile::QueryProfile;
 use crate::core::session_context::SessionContext;
 use crate::meta::initial;
 use crate::meta::meta_def::{SparrowColumnDef, TableDef};
-use crate::meta::meta_util;
+use crate::meta::{meta_const, meta_util};
 use crate::meta::meta_util::load_all_table;
 use crate::mysql::error::{MysqlError, MysqlResult};
-use crate::core::output::ResultSet;
+use crate::mysql::mysql_error_code;
+use crate::core::output::{RecordBatchStream, ResultSet, ResultStream};
+use crate::util::common_table_expression;
 
 pub struct SelectFrom {
     global_context: Arc<Mutex<GlobalContext>>,
     session_context: SessionContext,
     execution_context: ExecutionContext,
+    /// The metrics of the last query executed
+    profile: Option<QueryProfile>,
 }
 
 impl SelectFrom {
@@ -35,22 +53,233 @@ impl SelectFrom {
             global_context,
             session_context,
             execution_context,
+            profile: None,
         }
     }
 
+    /// The metrics of the last query executed, None if it failed
+    pub fn take_profile(&mut self) -> Option<QueryProfile> {
+        self.profile.take()
+    }
+
     pub async fn execute(&mut self, query: &Query) -> MysqlResult<ResultSet> {
+        let started = Instant::now();
+        self.profile = None;
+
+        let (execution_plan, schema_ref) = self.create_execution_plan(query).await?;
+        let (deadline, query_token) = self.interruption(started);
+        let stream = interruptible_stream(execution_plan.clone(), deadline, query_token).await?;
+        let batches = stream.try_collect::<Vec<_>>().await?;
+        self.profile = Some(QueryProfile::from_plan(&execution_plan, started.elapsed()));
+        Ok(ResultSet::new(schema_ref, batches))
+    }
+
+    /// The rows of the query as they are produced, the profile of the session is set when the last row is read.
+    /// The rows are interrupted by max_execution_time and KILL QUERY as the collected ones
+    pub async fn execute_stream(&mut self, query: &Query) -> MysqlResult<ResultStream> {
+        let started = Instant::now();
+
+        let (execution_plan, schema_ref) = self.create_execution_plan(query).await?;
+        let (deadline, query_token) = self.interruption(started);
+        let stream = interruptible_stream(execution_plan.clone(), deadline, query_token).await?;
+
+        // the profile of a failed query is not kept
+        let last_profile = self.session_context.last_profile.clone();
+        let stream = stream::unfold(Some(stream), move |stream| {
+            let execution_plan = execution_plan.clone();
+            let last_profile = last_profile.clone();
+            async move {
+                let mut stream = stream?;
+                match stream.next().await {
+                    Some(Ok(batch)) => Some((Ok(batch), Some(stream))),
+                    Some(Err(mysql_error)) => Some((Err(mysql_error), None)),
+                    None => {
+                        *last_profile.lock().unwrap() = Some(QueryProfile::from_plan(&execution_plan, started.elapsed()));
+                        None
+                    }
+                }
+            }
+        });
+        Ok(ResultStream::new(schema_ref, Box::pin(stream)))
+    }
+
+    /// The deadline of max_execution_time for the query started at the instant, and the token of KILL QUERY
+    fn interruption(&self, started: Instant) -> (Option<Instant>, CancellationToken) {
+        let max_execution_time = core_util::get_max_execution_time(self.global_context.clone(), &self.session_context);
+        let deadline = max_execution_time.map(|max_execution_time| started + max_execution_time);
+        let query_token = self.session_context.query_token.lock().unwrap().clone();
+        (deadline, query_token)
+    }
+
+    /// The physical plan of the query and the columns of its result.
+    /// The rows of the common table expressions are computed first, the plan keeps the ones it reads
+    async fn create_execution_plan(&mut self, query: &Query) -> MysqlResult<(Arc<dyn ExecutionPlan>, SchemaRef)> {
+        let with = match &query.with {
+            None => return self.create_query_plan(query).await,
+            Some(with) => with,
+        };
+
+        let catalog_provider = core_util::get_catalog_provider(&mut self.execution_context, meta_const::CATALOG_NAME);
+        if catalog_provider.schema(meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION).is_none() {
+            core_util::register_schema(
+                &mut self.execution_context,
+                meta_const::CATALOG_NAME,
+                meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION,
+            );
+        }
+
+        let mut cte_table_name_list = vec![];
+        let mut result = Ok(());
+        for cte in with.cte_tables.iter() {
+            cte_table_name_list.push(cte.alias.name.value.clone());
+            result = self.register_common_table_expression(cte, with.recursive).await;
+            if result.is_err() {
+                break;
+            }
+        }
+        let result = match result {
+            Ok(()) => self.create_query_plan(&Query { with: None, ..query.clone() }).await,
+            Err(mysql_error) => Err(mysql_error),
+        };
+
+        for cte_table_name in cte_table_name_list {
+            self.deregister_cte_table(cte_table_name.as_str())?;
+        }
+        result
+    }
+
+    /// The rows of the common table expression are kept in a table of the hidden schema until the plan is created.
+    /// A recursive expression is a UNION, its recursive part reads the rows of the last iteration
+    /// and is repeated until it finds no new row, at most cte_max_recursion_depth times
+    async fn register_common_table_expression(&mut self, cte: &Cte, recursive: bool) -> MysqlResult<()> {
+        let cte_table_name = cte.alias.name.value.clone();
+        let cte_name = common_table_expression::cut_out_cte_name(cte_table_name.as_str()).to_string();
+        let full_cte_table_name = ObjectName(vec![
+            Ident::new(meta_const::CATALOG_NAME),
+            Ident::new(meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION),
+            cte.alias.name.clone(),
+        ]);
+
+        if !recursive || !common_table_expression::query_reads_table(&cte.query, &full_cte_table_name) {
+            let result_set = self.execute_cte_query(&cte.query).await?;
+            let schema_ref = cte_schema(cte, &result_set.schema_ref)?;
+            let record_batches = cast_record_batches(&result_set.record_batches, &schema_ref)?;
+            return self.register_cte_table(cte_table_name.as_str(), schema_ref, record_batches);
+        }
+
+        let (anchor, recursive_part, all) = match &cte.query.body {
+            SetExpr::SetOperation { op: SetOperator::Union, all, left, right } => (left, right, *all),
+            _ => {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_CTE_RECURSIVE_REQUIRES_UNION as u16,
+                    "HY000",
+                    format!("Recursive Common Table Expression '{}' should contain a UNION", cte_name).as_str(),
+                ));
+            }
+        };
+        let anchor_query = build_query(anchor);
+        let recursive_query = build_query(recursive_part);
+
+        let result_set = self.execute_cte_query(&anchor_query).await?;
+        let schema_ref = cte_schema(cte, &result_set.schema_ref)?;
+        // UNION DISTINCT keeps the rows not seen before, the ones of the anchor included
+        let mut seen_rows = HashSet::new();
+        let mut working_record_batches = cast_record_batches(&result_set.record_batches, &schema_ref)?;
+        if !all {
+            working_record_batches = distinct_record_batches(working_record_batches, &mut seen_rows)?;
+        }
+        let mut record_batches = working_record_batches.clone();
+
+        let max_recursion_depth = core_util::get_cte_max_recursion_depth(self.global_context.clone(), &self.session_context);
+        let mut recursion_depth: u64 = 0;
+        while working_record_batches.iter().any(|record_batch| record_batch.num_rows() > 0) {
+            self.register_cte_table(cte_table_name.as_str(), schema_ref.clone(), working_record_batches)?;
+            let result = self.execute_cte_query(&recursive_query).await;
+            self.deregister_cte_table(cte_table_name.as_str())?;
+            let result_set = result?;
+
+            working_record_batches = cast_record_batches(&result_set.record_batches, &schema_ref)?;
+            if !all {
+                working_record_batches = distinct_record_batches(working_record_batches, &mut seen_rows)?;
+            }
+            if working_record_batches.iter().all(|record_batch| record_batch.num_rows() == 0) {
+                break;
+            }
+
+            recursion_depth += 1;
+            if recursion_depth > max_recursion_depth {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_CTE_MAX_RECURSION_DEPTH as u16,
+                    "HY000",
+                    format!(
+                        "Recursive query aborted after {} iterations. Try increasing @@cte_max_recursion_depth to a larger value.",
+                        recursion_depth
+                    )
+                    .as_str(),
+                ));
+            }
+            record_batches.extend(working_record_batches.clone());
+        }
+
+        self.register_cte_table(cte_table_name.as_str(), schema_ref, record_batches)
+    }
+
+    /// The rows of the query of a common table expression, boxed as the expressions may nest
+    fn execute_cte_query<'a>(&'a self, query: &'a Query) -> BoxFuture<'a, MysqlResult<ResultSet>> {
+        let mut select_from = SelectFrom::new(
+            self.global_context.clone(),
+            self.session_context.clone(),
+            self.execution_context.clone(),
+        );
+        async move { select_from.execute(query).await }.boxed()
+    }
+
+    fn register_cte_table(&mut self, cte_table_name: &str, schema_ref: SchemaRef, record_batches: Vec<RecordBatch>) -> MysqlResult<()> {
+        let mem_table = MemTable::try_new(schema_ref, vec![record_batches])?;
+        core_util::register_table(
+            &mut self.execution_context,
+            meta_const::CATALOG_NAME,
+            meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION,
+            cte_table_name,
+            Arc::new(mem_table),
+        )
+    }
+
+    fn deregister_cte_table(&mut self, cte_table_name: &str) -> MysqlResult<()> {
+        let schema_provider = core_util::get_schema_provider(
+            &mut self.execution_context,
+            meta_const::CATALOG_NAME,
+            meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION,
+        );
+        schema_provider.deregister_table(cte_table_name)?;
+        Ok(())
+    }
+
+    /// The physical plan of the query without WITH, the privileges of the user are checked first
+    async fn create_query_plan(&mut self, query: &Query) -> MysqlResult<(Arc<dyn ExecutionPlan>, SchemaRef)> {
+        let result = core_util::check_query_limit(query);
+        if let Err(mysql_error) = result {
+            return Err(mysql_error);
+        }
+
         let result = check_table_exists(self.global_context.clone(), &mut self.session_context, &mut self.execution_context, query);
         if let Err(mysql_error) = result {
             return Err(mysql_error);
         }
 
-        let result = self.query_to_plan(query);
+        // DataFusion plans the UNION of the SELECTs of the same column types only
+        let mut select_plan_list = vec![];
+        let result = match &query.body {
+            SetExpr::SetOperation { .. } => self.set_operation_query_to_plan(query, &mut select_plan_list),
+            _ => self.query_to_plan(query),
+        };
         let logical_plan = match result {
             Ok(logical_plan) => logical_plan,
             Err(error) => {
                 return Err(MysqlError::from(error));
             }
         };
+        privilege::check_plan_privileges(self.global_context.clone(), &self.session_context, &logical_plan)?;
 
         let result = self.execution_context.optimize(&logical_plan);
         let logical_plan = match result {
@@ -60,6 +289,26 @@ impl SelectFrom {
             }
         };
 
+        let plan_rewrite = self.global_context.lock().unwrap().plan_rewrite.clone();
+        let plan_rewrite_context = PlanRewriteContext {
+            session_context: &self.session_context,
+            query,
+        };
+        let logical_plan = plan_rewrite.rewrite(&logical_plan, &plan_rewrite_context)?;
+        let (zerofill_widths, temporal_types, collation_names) = if select_plan_list.is_empty() {
+            (
+                plan_rewrite::zerofill_output_widths(&logical_plan),
+                plan_rewrite::temporal_output_types(&logical_plan),
+                plan_rewrite::collation_output_names(&logical_plan),
+            )
+        } else {
+            (
+                merge_output_metadata(select_plan_list.iter().map(plan_rewrite::zerofill_output_widths).collect()),
+                merge_output_metadata(select_plan_list.iter().map(plan_rewrite::temporal_output_types).collect()),
+                merge_output_metadata(select_plan_list.iter().map(plan_rewrite::collation_output_names).collect()),
+            )
+        };
+
         let result = self.execution_context.create_physical_plan(&logical_plan).await;
         let execution_plan = match result {
             Ok(execution_plan) => execution_plan,
@@ -68,16 +317,8 @@ impl SelectFrom {
             }
         };
 
-        let schema_ref = execution_plan.schema();
-        let result = collect(execution_plan).await;
-        match result {
-            Ok(batches) => {
-                Ok(ResultSet::new(schema_ref, batches))
-            }
-            Err(error) => {
-                Err(MysqlError::from(error))
-            }
-        }
+        let schema_ref = with_column_metadata(execution_plan.schema(), &zerofill_widths, &temporal_types, &collation_names);
+        Ok((execution_plan, schema_ref))
     }
 
     fn query_to_plan(&mut self, query: &Query) -> MysqlResult<LogicalPlan> {
@@ -94,4 +335,453 @@ impl SelectFrom {
 
         return Ok(logical_plan);
     }
+
+    /// The plan of UNION, INTERSECT or EXCEPT, the plans of its SELECTs are kept in the list.
+    /// The ORDER BY and the LIMIT of the query sort and limit the rows of the set operation
+    fn set_operation_query_to_plan(&mut self, query: &Query, select_plan_list: &mut Vec<LogicalPlan>) -> MysqlResult<LogicalPlan> {
+        if query.offset.is_some() {
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                "42000",
+                "This version of MySQL doesn't yet support 'OFFSET of UNION, INTERSECT and EXCEPT'",
+            ));
+        }
+
+        let logical_plan = self.set_expr_to_plan(&query.body, select_plan_list)?;
+        let mut builder = LogicalPlanBuilder::from(logical_plan.clone());
+        if !query.order_by.is_empty() {
+            let sort_expr = query
+                .order_by
+                .iter()
+                .map(|order_by_expr| set_operation_sort_expr(logical_plan.schema(), order_by_expr))
+                .collect::<MysqlResult<Vec<_>>>()?;
+            builder = builder.sort(sort_expr)?;
+        }
+        if let Some(limit) = &query.limit {
+            let limit = match limit {
+                SQLExpr::Value(Value::Number(limit, _)) => limit.parse::<usize>().ok(),
+                _ => None,
+            };
+            match limit {
+                Some(limit) => builder = builder.limit(limit)?,
+                None => {
+                    return Err(MysqlError::new_server_error(
+                        1064,
+                        "42000",
+                        "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use near 'LIMIT'",
+                    ));
+                }
+            }
+        }
+        Ok(builder.build()?)
+    }
+
+    /// The columns of the sides are cast to their common types and named by the left side,
+    /// the rows of UNION, INTERSECT and EXCEPT are distinct unless ALL
+    fn set_expr_to_plan(&mut self, set_expr: &SetExpr, select_plan_list: &mut Vec<LogicalPlan>) -> MysqlResult<LogicalPlan> {
+        let (op, all, left, right) = match set_expr {
+            SetExpr::SetOperation { op, all, left, right } => (op, *all, left, right),
+            SetExpr::Query(query) if matches!(query.body, SetExpr::SetOperation { .. }) => {
+                return self.set_operation_query_to_plan(query, select_plan_list);
+            }
+            SetExpr::Query(query) => {
+                let logical_plan = self.query_to_plan(query)?;
+                select_plan_list.push(logical_plan.clone());
+                return Ok(logical_plan);
+            }
+            _ => {
+                let logical_plan = self.query_to_plan(&build_query(set_expr))?;
+                select_plan_list.push(logical_plan.clone());
+                return Ok(logical_plan);
+            }
+        };
+
+        let left_plan = self.set_expr_to_plan(left, select_plan_list)?;
+        let right_plan = self.set_expr_to_plan(right, select_plan_list)?;
+        let left_fields = left_plan.schema().fields().clone();
+        let right_fields = right_plan.schema().fields().clone();
+        if left_fields.len() != right_fields.len() {
+            return Err(different_number_of_columns_error());
+        }
+        let data_types = left_fields
+            .iter()
+            .zip(right_fields.iter())
+            .map(|(left_field, right_field)| set_operation_data_type(left_field.data_type(), right_field.data_type()))
+            .collect::<Vec<_>>();
+        let left_column_names = left_fields.iter().map(|field| field.name().clone()).collect::<Vec<_>>();
+        let left_plan = cast_set_operation_side(left_plan, &data_types, &left_column_names)?;
+
+        let builder = match op {
+            SetOperator::Union => {
+                let right_plan = cast_set_operation_side(right_plan, &data_types, &left_column_names)?;
+                LogicalPlanBuilder::from(left_plan).union(right_plan)?
+            }
+            SetOperator::Intersect | SetOperator::Except => {
+                if all {
+                    return Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_NOT_SUPPORTED_YET as u16,
+                        "42000",
+                        format!("This version of MySQL doesn't yet support '{} ALL'", op).as_str(),
+                    ));
+                }
+                // the columns of the right side are renamed so the join keys of the two sides do not meet,
+                // NULL is not distinct from NULL so the sides are joined on keys without NULL
+                let right_column_names = (0..data_types.len()).map(|index| format!("#right_{}", index)).collect::<Vec<_>>();
+                let right_plan = cast_set_operation_side(right_plan, &data_types, &right_column_names)?;
+                let left_columns = left_plan.schema().fields().iter().map(|field| Expr::Column(field.qualified_column())).collect::<Vec<_>>();
+                let (left_plan, left_keys) = null_safe_join_keys(left_plan, &data_types, "#left", true)?;
+                let (right_plan, right_keys) = null_safe_join_keys(right_plan, &data_types, "#right", false)?;
+                let join_type = if *op == SetOperator::Intersect { JoinType::Semi } else { JoinType::Anti };
+                LogicalPlanBuilder::from(left_plan)
+                    .join(&right_plan, join_type, (left_keys, right_keys))?
+                    .project(left_columns)?
+            }
+        };
+        let builder = if all { builder } else { builder.distinct()? };
+        Ok(builder.build()?)
+    }
+}
+
+/// A query of a side of the UNION of a recursive common table expression
+fn build_query(set_expr: &SetExpr) -> Query {
+    Query {
+        with: None,
+        body: set_expr.clone(),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        fetch: None,
+    }
+}
+
+/// The type of a column of a set operation whose sides have the types, the integers are widened,
+/// the other numbers are DOUBLE and the other types are text
+fn set_operation_data_type(left: &DataType, right: &DataType) -> DataType {
+    if left == right {
+        return left.clone();
+    }
+    match (left, right) {
+        (DataType::Null, data_type) | (data_type, DataType::Null) => data_type.clone(),
+        _ if is_integer_data_type(left) && is_integer_data_type(right) => {
+            if is_unsigned_data_type(left) && is_unsigned_data_type(right) {
+                DataType::UInt64
+            } else {
+                DataType::Int64
+            }
+        }
+        _ if is_number_data_type(left) && is_number_data_type(right) => DataType::Float64,
+        _ => DataType::Utf8,
+    }
+}
+
+fn is_unsigned_data_type(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::Boolean | DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64)
+}
+
+fn is_integer_data_type(data_type: &DataType) -> bool {
+    is_unsigned_data_type(data_type) || matches!(data_type, DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64)
+}
+
+fn is_number_data_type(data_type: &DataType) -> bool {
+    is_integer_data_type(data_type) || matches!(data_type, DataType::Float32 | DataType::Float64 | DataType::Decimal(_, _))
+}
+
+/// The side of a set operation with its columns cast to the types and renamed, as it is if nothing changes
+fn cast_set_operation_side(logical_plan: LogicalPlan, data_types: &[DataType], column_names: &[String]) -> MysqlResult<LogicalPlan> {
+    let fields = logical_plan.schema().fields().clone();
+    let unchanged = fields
+        .iter()
+        .zip(data_types.iter().zip(column_names.iter()))
+        .all(|(field, (data_type, column_name))| field.data_type() == data_type && field.name() == column_name);
+    if unchanged {
+        return Ok(logical_plan);
+    }
+
+    let expr = fields
+        .iter()
+        .zip(data_types.iter().zip(column_names.iter()))
+        .map(|(field, (data_type, column_name))| {
+            let column = Expr::Column(field.qualified_column());
+            if field.data_type() == data_type {
+                column.alias(column_name)
+            } else {
+                Expr::Cast {
+                    expr: Box::new(column),
+                    data_type: data_type.clone(),
+                }
+                .alias(column_name)
+            }
+        })
+        .collect::<Vec<_>>();
+    Ok(LogicalPlanBuilder::from(logical_plan).project(expr)?.build()?)
+}
+
+/// The side of INTERSECT or EXCEPT with its join keys, for each column whether it is NULL and its value with NULL
+/// replaced by a value of its type, the join matches NULL with NULL as MySQL does. The columns are kept if asked
+fn null_safe_join_keys(logical_plan: LogicalPlan, data_types: &[DataType], prefix: &str, keep_columns: bool) -> MysqlResult<(LogicalPlan, Vec<Column>)> {
+    let fields = logical_plan.schema().fields().clone();
+    let mut expr = vec![];
+    if keep_columns {
+        expr.extend(fields.iter().map(|field| Expr::Column(field.qualified_column())));
+    }
+
+    let mut keys = vec![];
+    for (index, (field, data_type)) in fields.iter().zip(data_types.iter()).enumerate() {
+        let column = Expr::Column(field.qualified_column());
+        let null_key = format!("{}_null_{}", prefix, index);
+        if *data_type == DataType::Null {
+            // the column of the NULL literals is always NULL
+            expr.push(lit(true).alias(null_key.as_str()));
+            keys.push(Column::from_name(null_key));
+            continue;
+        }
+        expr.push(Expr::IsNull(Box::new(column.clone())).alias(null_key.as_str()));
+        keys.push(Column::from_name(null_key));
+
+        let value_key = format!("{}_value_{}", prefix, index);
+        let value = Expr::Case {
+            expr: None,
+            when_then_expr: vec![(Box::new(Expr::IsNull(Box::new(column.clone()))), Box::new(null_replacement(data_type)))],
+            else_expr: Some(Box::new(column)),
+        };
+        expr.push(value.alias(value_key.as_str()));
+        keys.push(Column::from_name(value_key));
+    }
+    Ok((LogicalPlanBuilder::from(logical_plan).project(expr)?.build()?, keys))
+}
+
+/// The value of the type replacing NULL in the join keys, the NULL is told apart by its own key
+fn null_replacement(data_type: &DataType) -> Expr {
+    match data_type {
+        DataType::Utf8 => lit(""),
+        DataType::Binary => Expr::Literal(ScalarValue::Binary(Some(vec![]))),
+        DataType::LargeBinary => Expr::Literal(ScalarValue::LargeBinary(Some(vec![]))),
+        _ => Expr::Cast {
+            expr: Box::new(lit(0_i64)),
+            data_type: data_type.clone(),
+        },
+    }
+}
+
+/// The column of the set operation sorted by the ORDER BY, by its name or by its position
+fn set_operation_sort_expr(schema: &DFSchema, order_by_expr: &OrderByExpr) -> MysqlResult<Expr> {
+    let field = match &order_by_expr.expr {
+        SQLExpr::Identifier(ident) => schema.fields().iter().find(|field| field.name().eq_ignore_ascii_case(ident.value.as_str())),
+        SQLExpr::CompoundIdentifier(idents) => match idents.last() {
+            Some(ident) => schema.fields().iter().find(|field| field.name().eq_ignore_ascii_case(ident.value.as_str())),
+            None => None,
+        },
+        SQLExpr::Value(Value::Number(position, _)) => match position.parse::<usize>() {
+            Ok(position) if position >= 1 => schema.fields().get(position - 1),
+            _ => None,
+        },
+        _ => None,
+    };
+    match field {
+        Some(field) => {
+            // NULL is the smallest value
+            let asc = order_by_expr.asc.unwrap_or(true);
+            let nulls_first = order_by_expr.nulls_first.unwrap_or(asc);
+            Ok(Expr::Column(field.qualified_column()).sort(asc, nulls_first))
+        }
+        None => Err(MysqlError::new_server_error(
+            1054,
+            "42S22",
+            format!("Unknown column '{}' in 'order clause'", order_by_expr.expr).as_str(),
+        )),
+    }
+}
+
+/// The metadata of a column of a set operation is kept if all its SELECTs have the same
+fn merge_output_metadata<T: Clone + PartialEq>(metadata_list: Vec<Vec<Option<T>>>) -> Vec<Option<T>> {
+    let mut metadata_list = metadata_list.into_iter();
+    let first = metadata_list.next().unwrap_or_default();
+    metadata_list.fold(first, |merged, metadata| {
+        merged
+            .into_iter()
+            .zip(metadata.into_iter())
+            .map(|(merged_value, value)| if merged_value == value { merged_value } else { None })
+            .collect()
+    })
+}
+
+fn different_number_of_columns_error() -> MysqlError {
+    MysqlError::new_server_error(
+        1222,
+        "21000",
+        "The used SELECT statements have a different number of columns",
+    )
+}
+
+/// The columns of the common table expression are named by its column list or by its query,
+/// all of them are nullable as the recursive part may produce NULL
+fn cte_schema(cte: &Cte, schema_ref: &SchemaRef) -> MysqlResult<SchemaRef> {
+    if !cte.alias.columns.is_empty() && cte.alias.columns.len() != schema_ref.fields().len() {
+        return Err(MysqlError::new_server_error(
+            mysql_error_code::ER_VIEW_WRONG_LIST as u16,
+            "HY000",
+            "In definition of view, derived table or common table expression, SELECT list and column names list have different column counts",
+        ));
+    }
+
+    let fields = schema_ref
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let column_name = match cte.alias.columns.get(index) {
+                Some(column_name) => column_name.value.clone(),
+                None => field.name().clone(),
+            };
+            Field::new(column_name.as_str(), field.data_type().clone(), true)
+        })
+        .collect::<Vec<_>>();
+    let field_names = fields.iter().map(|field| field.name().to_lowercase()).collect::<Vec<_>>();
+    for (index, field_name) in field_names.iter().enumerate() {
+        if field_names[..index].contains(field_name) {
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_DUP_FIELDNAME as u16,
+                "42S21",
+                format!("Duplicate column name '{}'", fields[index].name()).as_str(),
+            ));
+        }
+    }
+    Ok(SchemaRef::new(Schema::new(fields)))
+}
+
+/// The rows cast to the columns of the common table expression, the recursive part may produce other types
+fn cast_record_batches(record_batches: &[RecordBatch], schema_ref: &SchemaRef) -> MysqlResult<Vec<RecordBatch>> {
+    let mut new_record_batches = vec![];
+    for record_batch in record_batches {
+        if record_batch.num_columns() != schema_ref.fields().len() {
+            return Err(different_number_of_columns_error());
+        }
+        let mut columns = vec![];
+        for (column, field) in record_batch.columns().iter().zip(schema_ref.fields()) {
+            columns.push(cast(column, field.data_type())?);
+        }
+        new_record_batches.push(RecordBatch::try_new(schema_ref.clone(), columns)?);
+    }
+    Ok(new_record_batches)
+}
+
+/// The rows not seen before, the rows are compared by their values as text
+fn distinct_record_batches(
+    record_batches: Vec<RecordBatch>,
+    seen_rows: &mut HashSet<Vec<Option<String>>>,
+) -> MysqlResult<Vec<RecordBatch>> {
+    let mut new_record_batches = vec![];
+    for record_batch in record_batches {
+        let mut filter = BooleanBuilder::new(record_batch.num_rows());
+        for row in core_util::convert_record_to_scalar_value(record_batch.clone()) {
+            let mut values = vec![];
+            for scalar_value in row {
+                values.push(core_util::convert_scalar_value(scalar_value)?);
+            }
+            filter.append_value(seen_rows.insert(values))?;
+        }
+        new_record_batches.push(filter_record_batch(&record_batch, &filter.finish())?);
+    }
+    Ok(new_record_batches)
+}
+
+/// The display width of the ZEROFILL columns is kept in the metadata of their fields,
+/// the values are padded with it when they are sent to the client.
+/// The data type of the temporal columns is kept too, they are sent with their mysql type,
+/// and the collation of the text columns, they are sent with its id
+fn with_column_metadata(
+    schema_ref: SchemaRef,
+    zerofill_widths: &[Option<u64>],
+    temporal_types: &[Option<String>],
+    collation_names: &[Option<String>],
+) -> SchemaRef {
+    if zerofill_widths.iter().all(|width| width.is_none())
+        && temporal_types.iter().all(|data_type| data_type.is_none())
+        && collation_names.iter().all(|collation_name| collation_name.is_none())
+    {
+        return schema_ref;
+    }
+
+    let fields = schema_ref
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let mut field = field.clone();
+            let mut metadata = BTreeMap::new();
+            if let Some(Some(width)) = zerofill_widths.get(index) {
+                metadata.insert(meta_const::FIELD_METADATA_ZEROFILL_WIDTH.to_string(), width.to_string());
+            }
+            if let Some(Some(data_type)) = temporal_types.get(index) {
+                metadata.insert(meta_const::FIELD_METADATA_DATA_TYPE.to_string(), data_type.clone());
+            }
+            if let Some(Some(collation_name)) = collation_names.get(index) {
+                metadata.insert(meta_const::FIELD_METADATA_COLLATION.to_string(), collation_name.clone());
+            }
+            if !metadata.is_empty() {
+                field.set_metadata(Some(metadata));
+            }
+            field
+        })
+        .collect::<Vec<_>>();
+    SchemaRef::new(Schema::new_with_metadata(fields, schema_ref.metadata().clone()))
+}
+
+/// The batches of the plan as they are produced until the deadline, then the query is interrupted with the error 3024,
+/// or until the query is killed with the error 1317.
+/// The stream of the plan is dropped at the interruption, so the scans stop and no more rows are sent
+async fn interruptible_stream(
+    execution_plan: Arc<dyn ExecutionPlan>,
+    deadline: Option<Instant>,
+    query_token: CancellationToken,
+) -> MysqlResult<RecordBatchStream> {
+    let execution_plan: Arc<dyn ExecutionPlan> = match execution_plan.output_partitioning().partition_count() {
+        0 => return Ok(Box::pin(stream::empty())),
+        1 => execution_plan,
+        _ => Arc::new(CoalescePartitionsExec::new(execution_plan)),
+    };
+
+    if query_token.is_cancelled() {
+        return Err(core_util::query_interrupted_error());
+    }
+    if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+        return Err(core_util::execution_timeout_error());
+    }
+    let plan_stream = execution_plan.execute(0).await.map_err(MysqlError::from)?;
+
+    let stream = stream::unfold(Some(plan_stream), move |plan_stream| {
+        let query_token = query_token.clone();
+        async move {
+            let mut plan_stream = plan_stream?;
+            let timeout = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await,
+                    None => futures::future::pending::<()>().await,
+                }
+            };
+            let result = tokio::select! {
+                batch = plan_stream.next() => match batch {
+                    Some(batch) => batch.map_err(MysqlError::from),
+                    None => return None,
+                },
+                _ = query_token.cancelled() => Err(core_util::query_interrupted_error()),
+                _ = timeout => Err(core_util::execution_timeout_error()),
+            };
+            // the batch may be computed without yielding, so the deadline and the kill are checked between the batches too
+            let result = result.and_then(|batch| {
+                if query_token.is_cancelled() {
+                    return Err(core_util::query_interrupted_error());
+                }
+                if deadline.map_or(false, |deadline| Instant::now() > deadline) {
+                    return Err(core_util::execution_timeout_error());
+                }
+                Ok(batch)
+            });
+            match result {
+                Ok(batch) => Some((Ok(batch), Some(plan_stream))),
+                Err(mysql_error) => Some((Err(mysql_error), None)),
+            }
+        }
+    });
+    Ok(Box::pin(stream))
 }
@@ -0,0 +1,77 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege;
+use crate::core::privilege::Privilege;
+use crate::core::session_context::SessionContext;
+use crate::meta::def::mysql;
+use crate::meta::{initial, meta_const};
+use crate::mysql::auth;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::util::user_statement::SetPasswordStatement;
+
+pub struct SetPassword {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl SetPassword {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The users change their own password, the password of another user needs the CREATE USER privilege.
+    /// The new authentication_string is read by the next auth of the user
+    pub fn execute(&self, statement: SetPasswordStatement) -> MysqlResult<u64> {
+        let current_user = self.session_context.current_user.lock().unwrap().clone();
+        let session_user = match &current_user {
+            Some(account) => privilege::find_user(self.global_context.clone(), account.user.as_str(), account.host.as_str())?,
+            None => None,
+        };
+
+        let user = match statement.account {
+            Some(account) => {
+                let own_password = session_user
+                    .as_ref()
+                    .map_or(false, |session_user| privilege::is_row_of_account(session_user, &account));
+                if !own_password {
+                    privilege::check_global_privilege(self.global_context.clone(), &self.session_context, Privilege::CreateUser)?;
+                }
+                let users = privilege::read_users(self.global_context.clone())?;
+                privilege::find_account(&users, &account)
+            }
+            None => session_user,
+        };
+        let mut user = match user {
+            Some(user) => user,
+            None => {
+                return Err(MysqlError::new_server_error(
+                    1133,
+                    "28000",
+                    "Can't find any matching row in the user table",
+                ));
+            }
+        };
+
+        let rowid = privilege::row_value(&user, meta_const::COLUMN_ROWID).to_string();
+        initial::delete_def_mysql_row(self.global_context.clone(), mysql::users(self.global_context.clone()), rowid.as_str())?;
+        user.insert(
+            meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_AUTHENTICATION_STRING.to_string(),
+            Some(auth::native_password_hash(statement.password.as_str())),
+        );
+        initial::add_def_mysql_row(self.global_context.clone(), mysql::users(self.global_context.clone()), &user)?;
+
+        Ok(0)
+    }
+}
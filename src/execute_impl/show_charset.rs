@@ -11,6 +11,8 @@ use crate::core::global_context::GlobalContext;
 use crate::core::output::ResultSet;
 use crate::core::session_context::SessionContext;
 use crate::mysql::error::MysqlResult;
+use crate::util::collation;
+use crate::util::like;
 
 pub struct ShowCharset {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -31,7 +33,8 @@ impl ShowCharset {
         }
     }
 
-    pub fn execute(&self) -> MysqlResult<ResultSet> {
+    /// The character sets whose names match the pattern of LIKE
+    pub fn execute(&self, pattern: Option<&str>) -> MysqlResult<ResultSet> {
         let schema = SchemaRef::new(Schema::new(vec![
             Field::new("Charset", DataType::Utf8, false),
             Field::new("Description", DataType::Utf8, false),
@@ -39,18 +42,26 @@ impl ShowCharset {
             Field::new("Maxlen", DataType::Utf8, false),
         ]));
 
-        let column_values_of_charset = StringArray::from(vec![
-            "utf8", "utf8mb4",
-        ]);
-        let column_values_of_description = StringArray::from(vec![
-            "UTF-8 Unicode", "UTF-8 Unicode",
-        ]);
-        let column_values_of_default_collation = StringArray::from(vec![
-            "utf8_general_ci", "utf8mb4_0900_ai_ci",
-        ]);
-        let column_values_of_maxlen = StringArray::from(vec![
-            "3", "4",
-        ]);
+        let character_sets = collation::CHARACTER_SETS
+            .iter()
+            .filter(|character_set| match pattern {
+                Some(pattern) => like::like_match(character_set.name, pattern, like::DEFAULT_ESCAPE_CHAR),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        let column_values_of_charset = StringArray::from(
+            character_sets.iter().map(|character_set| character_set.name).collect::<Vec<_>>(),
+        );
+        let column_values_of_description = StringArray::from(
+            character_sets.iter().map(|character_set| character_set.description).collect::<Vec<_>>(),
+        );
+        let column_values_of_default_collation = StringArray::from(
+            character_sets.iter().map(|character_set| character_set.default_collation).collect::<Vec<_>>(),
+        );
+        let column_values_of_maxlen = StringArray::from(
+            character_sets.iter().map(|character_set| character_set.max_length.to_string()).collect::<Vec<_>>(),
+        );
         let record_batch = RecordBatch::try_new(schema.clone(), vec![
             Arc::new(column_values_of_charset),
             Arc::new(column_values_of_description),
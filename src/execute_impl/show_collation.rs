@@ -11,6 +11,8 @@ use crate::core::global_context::GlobalContext;
 use crate::core::output::ResultSet;
 use crate::core::session_context::SessionContext;
 use crate::mysql::error::MysqlResult;
+use crate::util::collation;
+use crate::util::like;
 
 pub struct ShowCollation {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -31,7 +33,8 @@ impl ShowCollation {
         }
     }
 
-    pub fn execute(&self) -> MysqlResult<ResultSet> {
+    /// The collations whose names match the pattern of LIKE
+    pub fn execute(&self, pattern: Option<&str>) -> MysqlResult<ResultSet> {
         let schema = SchemaRef::new(Schema::new(vec![
             Field::new("Collation", DataType::Utf8, false),
             Field::new("Charset", DataType::Utf8, false),
@@ -42,14 +45,28 @@ impl ShowCollation {
             Field::new("Pad_attribute", DataType::Utf8, false),
         ]));
 
+        let collations = collation::COLLATIONS
+            .iter()
+            .filter(|collation| match pattern {
+                Some(pattern) => like::like_match(collation.name, pattern, like::DEFAULT_ESCAPE_CHAR),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
         let column_values_of_collation =
-            StringArray::from(vec!["utf8mb4_general_ci", "utf8_general_ci"]);
-        let column_values_of_charset = StringArray::from(vec!["utf8mb4", "utf8"]);
-        let column_values_of_id: Int64Array = [Some(45), Some(33)].into_iter().collect();
-        let column_values_of_default = StringArray::from(vec!["", "Yes"]);
-        let column_values_of_compiled = StringArray::from(vec!["Yes", "Yes"]);
-        let column_values_of_sortlen: Int64Array = [Some(1), Some(1)].into_iter().collect();
-        let column_values_of_pad_attribute = StringArray::from(vec!["PAD SPACE", "PAD SPACE"]);
+            StringArray::from(collations.iter().map(|collation| collation.name).collect::<Vec<_>>());
+        let column_values_of_charset =
+            StringArray::from(collations.iter().map(|collation| collation.character_set).collect::<Vec<_>>());
+        let column_values_of_id: Int64Array = collations.iter().map(|collation| Some(collation.id as i64)).collect();
+        let column_values_of_default = StringArray::from(
+            collations.iter().map(|collation| if collation.is_default { "Yes" } else { "" }).collect::<Vec<_>>(),
+        );
+        let column_values_of_compiled = StringArray::from(collations.iter().map(|_| "Yes").collect::<Vec<_>>());
+        let column_values_of_sortlen: Int64Array =
+            collations.iter().map(|collation| Some(collation.sort_length as i64)).collect();
+        let column_values_of_pad_attribute = StringArray::from(
+            collations.iter().map(|collation| if collation.pad_space { "PAD SPACE" } else { "NO PAD" }).collect::<Vec<_>>(),
+        );
         let record_batch = RecordBatch::try_new(
             schema.clone(),
             vec![
@@ -169,6 +169,8 @@ impl ShowColumns {
         let column_index_of_column_name = schema.index_of("COLUMN_NAME").unwrap();
         let column_index_of_data_type = schema.index_of("DATA_TYPE").unwrap();
         let column_index_of_is_nullable = schema.index_of("IS_NULLABLE").unwrap();
+        let column_index_of_numeric_precision = schema.index_of("NUMERIC_PRECISION").ok();
+        let column_index_of_numeric_scale = schema.index_of("NUMERIC_SCALE").ok();
         let columns_rows = core_util::convert_record_to_scalar_value(record_batch.clone());
         let mut column_fields = vec![];
         let mut column_types = vec![];
@@ -184,7 +186,15 @@ impl ShowColumns {
             // data type
             let value = row.get(column_index_of_data_type).unwrap();
             let data_type = scalar_value::to_utf8(value.clone()).unwrap();
-            let sql_data_type = meta_util::create_sql_data_type(data_type.as_str()).unwrap();
+            let numeric_precision = match column_index_of_numeric_precision.and_then(|index| row.get(index)) {
+                Some(ScalarValue::Int64(value)) => *value,
+                _ => None,
+            };
+            let numeric_scale = match column_index_of_numeric_scale.and_then(|index| row.get(index)) {
+                Some(ScalarValue::Int64(value)) => *value,
+                _ => None,
+            };
+            let sql_data_type = meta_util::create_sql_data_type(data_type.as_str(), numeric_precision, numeric_scale).unwrap();
             column_types.push(sql_data_type.to_string());
             // nullable
             let value = row.get(column_index_of_is_nullable).unwrap();
@@ -0,0 +1,98 @@
+use std::sync::{Mutex, Arc};
+
+use arrow::array::StringArray;
+use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion::execution::context::ExecutionContext;
+use serde_json::Value;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::output::ResultSet;
+use crate::core::session_context::SessionContext;
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+/// The value shown for the secrets of the config
+const REDACTED_VALUE: &str = "******";
+/// The config items with these words in the name are secrets
+const SECRET_WORDS: [&str; 3] = ["password", "secret", "token"];
+
+pub struct ShowConfig {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl ShowConfig {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// List the effective config, the config file merged with the defaults, one row for each item
+    pub fn execute(&self) -> MysqlResult<ResultSet> {
+        let my_config = self.global_context.lock().unwrap().my_config.clone();
+        let value = match serde_json::to_value(&my_config) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(MysqlError::new_global_error(1105, format!("Can't read the config: {}", error).as_str()));
+            }
+        };
+
+        let mut config_items = vec![];
+        flatten_config_value("", &value, &mut config_items);
+        config_items.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new("Name", DataType::Utf8, false),
+            Field::new("Value", DataType::Utf8, true),
+        ]));
+
+        let column_values_of_name = config_items.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        let column_values_of_value = config_items.iter().map(|(_, value)| value.as_deref()).collect::<Vec<_>>();
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(column_values_of_name)),
+            Arc::new(StringArray::from(column_values_of_value)),
+        ]).unwrap();
+
+        Ok(ResultSet::new(schema, vec![record_batch]))
+    }
+}
+
+/// The items of the config named by their path, `engine.sled.data_path`,
+/// the lists are joined by commas and the secrets are redacted
+fn flatten_config_value(name: &str, value: &Value, config_items: &mut Vec<(String, Option<String>)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let name = if name.is_empty() { key.clone() } else { format!("{}.{}", name, key) };
+                flatten_config_value(name.as_str(), value, config_items);
+            }
+        }
+        _ => {
+            let lowercase_name = name.to_lowercase();
+            let config_value = if SECRET_WORDS.iter().any(|word| lowercase_name.contains(word)) {
+                Some(REDACTED_VALUE.to_string())
+            } else {
+                match value {
+                    Value::Null => None,
+                    Value::String(value) => Some(value.clone()),
+                    Value::Array(values) => Some(values.iter().map(|value| match value {
+                        Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    }).collect::<Vec<_>>().join(",")),
+                    value => Some(value.to_string()),
+                }
+            };
+            config_items.push((name.to_string(), config_value));
+        }
+    }
+}
+
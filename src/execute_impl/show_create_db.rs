@@ -0,0 +1,71 @@
+use std::sync::{Arc, Mutex};
+
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::ObjectName;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::output::ResultSet;
+use crate::core::session_context::SessionContext;
+use crate::meta::meta_util;
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+pub struct ShowCreateDb {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl ShowCreateDb {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    pub fn execute(&mut self, db_name: ObjectName, if_not_exists: bool) -> MysqlResult<ResultSet> {
+        let full_schema_name = meta_util::fill_up_schema_name(&mut self.session_context, db_name.clone()).unwrap();
+
+        let schema_def = match meta_util::get_schema(self.global_context.clone(), full_schema_name.clone()) {
+            Ok(schema_def) => schema_def,
+            Err(_) => {
+                return Err(MysqlError::new_server_error(
+                    1049,
+                    "42000",
+                    format!("Unknown database '{}'", db_name).as_str(),
+                ));
+            }
+        };
+
+        // the statement is kept without the version comments, so it can be parsed again
+        let mut create_database = "CREATE DATABASE ".to_string();
+        if if_not_exists {
+            create_database.push_str("IF NOT EXISTS ");
+        }
+        create_database.push_str(format!(
+            "`{}` DEFAULT CHARACTER SET {} COLLATE {}",
+            schema_def.option.schema_name,
+            schema_def.option.default_character_set_name,
+            schema_def.option.default_collation_name,
+        ).as_str());
+
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new("Database", DataType::Utf8, false),
+            Field::new("Create Database", DataType::Utf8, false),
+        ]));
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(vec![schema_def.option.schema_name.as_str()])),
+            Arc::new(StringArray::from(vec![create_database.as_str()])),
+        ]).unwrap();
+
+        Ok(ResultSet::new(schema, vec![record_batch]))
+    }
+}
@@ -23,6 +23,7 @@ use crate::meta::meta_util::load_all_table;
 use crate::meta::{def, meta_util};
 use crate::meta::{initial, meta_const, scalar_value};
 use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::util::collation;
 use crate::util::convert::ToObjectName;
 use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use std::collections::HashMap;
@@ -48,7 +49,7 @@ impl ShowCreateTable {
 
     pub async fn execute(&mut self, table_name: &ObjectName) -> MysqlResult<ResultSet> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, table_name)?;
 
         let result = meta_util::get_table(self.global_context.clone(), full_table_name.clone());
         let table_def = match result {
@@ -217,6 +218,18 @@ impl ShowCreateTable {
             .schema_ref
             .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_IS_NULLABLE)
             .unwrap();
+        let column_index_of_numeric_precision = result_set_columns
+            .schema_ref
+            .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_NUMERIC_PRECISION)
+            .unwrap();
+        let column_index_of_numeric_scale = result_set_columns
+            .schema_ref
+            .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_NUMERIC_SCALE)
+            .unwrap();
+        let column_index_of_collation_name = result_set_columns
+            .schema_ref
+            .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLLATION_NAME)
+            .unwrap();
         let columns_rows = core_util::convert_record_to_scalar_value(record_batch.clone());
         let mut columns = vec![];
         for row_index in 0..record_batch.num_rows() {
@@ -228,14 +241,28 @@ impl ShowCreateTable {
             // data type
             let value = row.get(column_index_of_data_type).unwrap();
             let data_type = scalar_value::to_utf8(value.clone()).unwrap();
-            let sql_data_type = meta_util::create_sql_data_type(data_type.as_str()).unwrap();
+            let numeric_precision = match row.get(column_index_of_numeric_precision) {
+                Some(ScalarValue::Int64(value)) => *value,
+                _ => None,
+            };
+            let numeric_scale = match row.get(column_index_of_numeric_scale) {
+                Some(ScalarValue::Int64(value)) => *value,
+                _ => None,
+            };
+            let sql_data_type = meta_util::create_sql_data_type(data_type.as_str(), numeric_precision, numeric_scale).unwrap();
             // nullable
             let value = row.get(column_index_of_is_nullable).unwrap();
             let text_is_nullable = scalar_value::to_utf8(value.clone()).unwrap();
             let nullable = meta_util::text_to_null(text_is_nullable.as_str()).unwrap();
             // create sql column
-            let sql_column =
+            let mut sql_column =
                 meta_util::create_sql_column(column_name.as_str(), sql_data_type, nullable);
+            // the text columns show their collation if it is not the default one
+            if let Some(ScalarValue::Utf8(Some(collation_name))) = row.get(column_index_of_collation_name) {
+                if collation_name != collation::DEFAULT_COLLATION {
+                    sql_column.collation = Some(meta_util::convert_to_object_name(collation_name.as_str()));
+                }
+            }
 
             columns.push(sql_column);
         }
@@ -256,7 +283,7 @@ impl ShowCreateTable {
             file_format: None,
             location: None,
             query: None,
-            without_rowid: false,
+            without_rowid: table_def.option.without_rowid,
             like: None,
             table_options: vec![],
         };
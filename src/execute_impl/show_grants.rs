@@ -5,12 +5,15 @@ use arrow::datatypes::SchemaRef;
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use datafusion::execution::context::ExecutionContext;
-use sqlparser::ast::Ident;
 
 use crate::core::global_context::GlobalContext;
 use crate::core::output::ResultSet;
+use crate::core::privilege;
+use crate::core::privilege::{Account, GrantRow, Privilege};
 use crate::core::session_context::SessionContext;
-use crate::mysql::error::MysqlResult;
+use crate::meta::meta_const;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::util::user_statement::ShowGrantsStatement;
 
 pub struct ShowGrants {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -31,19 +34,76 @@ impl ShowGrants {
         }
     }
 
-    pub fn execute(&self) -> MysqlResult<ResultSet> {
+    /// The GRANT statements of the privileges of the account, the global ones then the ones of each schema.
+    /// Without FOR the account is the user of the session, root for the sessions without a user
+    pub fn execute(&self, statement: &ShowGrantsStatement) -> MysqlResult<ResultSet> {
+        let current_user = self.session_context.current_user.lock().unwrap().clone();
+        let user = match (&statement.account, &current_user) {
+            (Some(account), _) => {
+                privilege::check_schema_privilege(
+                    self.global_context.clone(),
+                    &self.session_context,
+                    Privilege::Select,
+                    meta_const::SCHEMA_NAME_OF_DEF_MYSQL,
+                )?;
+                let users = privilege::read_users(self.global_context.clone())?;
+                privilege::find_account(&users, account)
+            }
+            (None, Some(account)) => privilege::find_user(self.global_context.clone(), account.user.as_str(), account.host.as_str())?,
+            (None, None) => privilege::find_user(self.global_context.clone(), "root", "%")?,
+        };
+        let user = match user {
+            Some(user) => user,
+            None => {
+                let account = statement.account.clone().or(current_user).unwrap_or_else(|| Account::new("root", "%"));
+                return Err(MysqlError::new_server_error(
+                    1141,
+                    "42000",
+                    format!("There is no such grant defined for user '{}' on host '{}'", account.user, account.host).as_str(),
+                ));
+            }
+        };
+        let account = Account::new(
+            privilege::row_value(&user, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_USER),
+            privilege::row_value(&user, meta_const::COLUMN_NAME_OF_DEF_MYSQL_USERS_HOST),
+        );
+
+        let mut grants = vec![grant_statement(&user, "*.*", &account, false)];
+        let mut schema_grants = privilege::read_schema_grants(self.global_context.clone())?
+            .into_iter()
+            .filter(|schema_grant| privilege::is_row_of_account(schema_grant, &account))
+            .collect::<Vec<_>>();
+        schema_grants.sort_by(|a, b| {
+            privilege::row_value(a, meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_DB).cmp(privilege::row_value(b, meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_DB))
+        });
+        for schema_grant in schema_grants {
+            let level = format!("`{}`.*", privilege::row_value(&schema_grant, meta_const::COLUMN_NAME_OF_DEF_MYSQL_DB_DB));
+            grants.push(grant_statement(&schema_grant, level.as_str(), &account, true));
+        }
+
         let schema = SchemaRef::new(Schema::new(vec![Field::new(
-            "Grants for root@%",
+            format!("Grants for {}@{}", account.user, account.host).as_str(),
             DataType::Utf8,
             false,
         )]));
-        let column_values = StringArray::from(vec![
-            "GRANT SELECT, INSERT, UPDATE, DELETE, CREATE, DROP, RELOAD, SHUTDOWN, PROCESS, FILE, REFERENCES, INDEX, ALTER, SHOW DATABASES, SUPER, CREATE TEMPORARY TABLES, LOCK TABLES, EXECUTE, REPLICATION SLAVE, REPLICATION CLIENT, CREATE VIEW, SHOW VIEW, CREATE ROUTINE, ALTER ROUTINE, CREATE USER, EVENT, TRIGGER, CREATE TABLESPACE, CREATE ROLE, DROP ROLE ON *.* TO `root`@`%`",
-            "GRANT APPLICATION_PASSWORD_ADMIN,AUDIT_ADMIN,BACKUP_ADMIN,BINLOG_ADMIN,BINLOG_ENCRYPTION_ADMIN,CLONE_ADMIN,CONNECTION_ADMIN,ENCRYPTION_KEY_ADMIN,FLUSH_OPTIMIZER_COSTS,FLUSH_STATUS,FLUSH_TABLES,FLUSH_USER_RESOURCES,GROUP_REPLICATION_ADMIN,INNODB_REDO_LOG_ARCHIVE,INNODB_REDO_LOG_ENABLE,PERSIST_RO_VARIABLES_ADMIN,REPLICATION_APPLIER,REPLICATION_SLAVE_ADMIN,RESOURCE_GROUP_ADMIN,RESOURCE_GROUP_USER,ROLE_ADMIN,SERVICE_CONNECTION_ADMIN,SESSION_VARIABLES_ADMIN,SET_USER_ID,SHOW_ROUTINE,SYSTEM_USER,SYSTEM_VARIABLES_ADMIN,TABLE_ENCRYPTION_ADMIN,XA_RECOVER_ADMIN ON *.* TO `root`@`%`"
-        ]);
+        let column_values = StringArray::from(grants.iter().map(|grant| grant.as_str()).collect::<Vec<_>>());
         let record_batch =
             RecordBatch::try_new(schema.clone(), vec![Arc::new(column_values)]).unwrap();
 
         Ok(ResultSet::new(schema, vec![record_batch]))
     }
 }
+
+/// The GRANT of the privileges of the row, USAGE without any privilege
+fn grant_statement(row: &GrantRow, level: &str, account: &Account, schema_level: bool) -> String {
+    let privileges = privilege::PRIVILEGES
+        .iter()
+        .filter(|privilege| **privilege != Privilege::Grant)
+        .filter(|privilege| !schema_level || privilege.is_schema_privilege())
+        .filter(|privilege| privilege::row_has_privilege(row, **privilege))
+        .map(|privilege| privilege.name())
+        .collect::<Vec<_>>();
+    let privileges = if privileges.is_empty() { "USAGE".to_string() } else { privileges.join(", ") };
+    let grant_option = if privilege::row_has_privilege(row, Privilege::Grant) { " WITH GRANT OPTION" } else { "" };
+    format!("GRANT {} ON {} TO `{}`@`{}`{}", privileges, level, account.user, account.host, grant_option)
+}
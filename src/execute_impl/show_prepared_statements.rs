@@ -0,0 +1,63 @@
+use std::sync::{Mutex, Arc};
+
+use arrow::array::{Int64Array, StringArray};
+use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion::execution::context::ExecutionContext;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::output::ResultSet;
+use crate::core::session_context::SessionContext;
+use crate::core::stmt_context::StmtContext;
+use crate::mysql::error::MysqlResult;
+
+pub struct ShowPreparedStatements {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl ShowPreparedStatements {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// List the prepared statements of the session which are not closed yet
+    pub fn execute(&self, stmt_context: &StmtContext) -> MysqlResult<ResultSet> {
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new("Stmt_id", DataType::Int64, false),
+            Field::new("Sql", DataType::Utf8, false),
+            Field::new("Param_count", DataType::Int64, false),
+        ]));
+
+        let mut stmt_ids: Vec<&u32> = stmt_context.stmts.keys().collect();
+        stmt_ids.sort();
+
+        let mut column_values_of_stmt_id = vec![];
+        let mut column_values_of_sql = vec![];
+        let mut column_values_of_param_count = vec![];
+        for stmt_id in stmt_ids {
+            let stmt_cache = stmt_context.stmts.get(stmt_id).unwrap();
+            column_values_of_stmt_id.push(*stmt_id as i64);
+            column_values_of_sql.push(stmt_cache.get_sql());
+            column_values_of_param_count.push(stmt_cache.get_num_params() as i64);
+        }
+
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(Int64Array::from(column_values_of_stmt_id)),
+            Arc::new(StringArray::from(column_values_of_sql)),
+            Arc::new(Int64Array::from(column_values_of_param_count)),
+        ]).unwrap();
+
+        Ok(ResultSet::new(schema, vec![record_batch]))
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::{Mutex, Arc};
+
+use arrow::array::{StringArray, UInt64Array};
+use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion::execution::context::ExecutionContext;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::output::ResultSet;
+use crate::core::session_context::SessionContext;
+use crate::mysql::error::MysqlResult;
+
+/// The operator of the row with the elapsed time of the whole query
+const OPERATOR_TOTAL: &str = "total";
+
+pub struct ShowProfile {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl ShowProfile {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The metrics of the last SELECT of the session, one row for each operator of the plan
+    /// indented by its depth, then the total. Empty if no SELECT has succeeded yet
+    pub fn execute(&self) -> MysqlResult<ResultSet> {
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new("Operator", DataType::Utf8, false),
+            Field::new("Rows", DataType::UInt64, true),
+            Field::new("Bytes_scanned", DataType::UInt64, true),
+            Field::new("Batches_read_ahead", DataType::UInt64, true),
+            Field::new("Duration", DataType::Utf8, true),
+        ]));
+
+        let query_profile = match self.session_context.last_profile.lock().unwrap().clone() {
+            Some(query_profile) => query_profile,
+            None => return Ok(ResultSet::new(schema, vec![])),
+        };
+
+        let mut column_values_of_operator = vec![];
+        let mut column_values_of_rows = vec![];
+        let mut column_values_of_bytes_scanned = vec![];
+        let mut column_values_of_batches_read_ahead = vec![];
+        let mut column_values_of_duration = vec![];
+        for operator_profile in query_profile.operators.iter() {
+            column_values_of_operator.push(format!("{}{}", "  ".repeat(operator_profile.depth), operator_profile.name));
+            column_values_of_rows.push(operator_profile.output_rows.map(|rows| rows as u64));
+            column_values_of_bytes_scanned.push(operator_profile.bytes_scanned.map(|bytes| bytes as u64));
+            column_values_of_batches_read_ahead.push(operator_profile.batches_read_ahead.map(|batches| batches as u64));
+            // the seconds with the microseconds, as the Duration of mysql
+            column_values_of_duration.push(operator_profile.elapsed_compute.map(|elapsed| format!("{:.6}", elapsed.as_secs_f64())));
+        }
+        column_values_of_operator.push(OPERATOR_TOTAL.to_string());
+        column_values_of_rows.push(None);
+        column_values_of_bytes_scanned.push(None);
+        column_values_of_batches_read_ahead.push(None);
+        column_values_of_duration.push(Some(format!("{:.6}", query_profile.elapsed.as_secs_f64())));
+
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(column_values_of_operator)),
+            Arc::new(UInt64Array::from(column_values_of_rows)),
+            Arc::new(UInt64Array::from(column_values_of_bytes_scanned)),
+            Arc::new(UInt64Array::from(column_values_of_batches_read_ahead)),
+            Arc::new(StringArray::from(column_values_of_duration)),
+        ]).unwrap();
+
+        Ok(ResultSet::new(schema, vec![record_batch]))
+    }
+}
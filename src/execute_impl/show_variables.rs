@@ -1,31 +1,29 @@
-use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use arrow::datatypes::SchemaRef;
-use datafusion::arrow::datatypes::{DataType, Field, Schema};
-use datafusion::arrow::record_batch::RecordBatch;
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
 use datafusion::execution::context::ExecutionContext;
-use datafusion::logical_plan::LogicalPlan;
-use datafusion::physical_plan::{collect, ExecutionPlan};
-use datafusion::sql::planner::{ContextProvider, SqlToRel};
-use sqlparser::ast::{
-    AlterTableOperation, Assignment, BinaryOperator, ColumnDef, Expr as SQLExpr, Ident,
-    JoinConstraint, JoinOperator, ObjectName, ObjectType, Query, Select, SelectItem, SetExpr,
-    ShowStatementFilter, Statement as SQLStatement, TableFactor, Value,
-};
+use datafusion::physical_plan::collect;
+use datafusion::sql::planner::SqlToRel;
+use sqlparser::ast::Statement as SQLStatement;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
 
-use crate::core::core_util;
-use crate::core::core_util::{check_table_exists, register_all_table};
 use crate::core::global_context::GlobalContext;
 use crate::core::output::ResultSet;
 use crate::core::session_context::SessionContext;
-use crate::execute_impl::select::SelectFrom;
-use crate::meta::meta_def::{SparrowColumnDef, TableDef};
-use crate::meta::meta_util;
-use crate::meta::meta_util::load_all_table;
-use crate::meta::{initial, meta_const, scalar_value};
 use crate::mysql::error::{MysqlError, MysqlResult};
-use crate::util::convert::ToObjectName;
+use crate::util::like;
+use crate::util::show_statement::{ShowVariablesFilter, ShowVariablesStatement};
+use crate::variable::system_variable;
+
+const COLUMN_NAME_VARIABLE_NAME: &str = "Variable_name";
+const COLUMN_NAME_VALUE: &str = "Value";
+/// The table of the variables the WHERE condition is run on
+const TABLE_NAME_VARIABLES: &str = "variables";
 
 pub struct ShowVariables {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -46,73 +44,97 @@ impl ShowVariables {
         }
     }
 
-    pub async fn execute(&mut self, filter: Option<ShowStatementFilter>) -> MysqlResult<ResultSet> {
-        let full_table_name = meta_util::convert_to_object_name(
-            meta_const::FULL_TABLE_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES,
-        );
-        let table_with_joins = core_util::build_table_with_joins(full_table_name.clone());
+    pub async fn execute(&mut self, statement: ShowVariablesStatement) -> MysqlResult<ResultSet> {
+        let mut variables = self.global_context.lock().unwrap().variable.variables();
+        if !statement.global {
+            for (variable_name, value) in variables.iter_mut() {
+                let global_only = system_variable::get_system_variable_def(variable_name)
+                    .map_or(false, |variable_def| variable_def.global_only);
+                if global_only {
+                    continue;
+                }
+                if let Some(session_value) = self.session_context.get_session_variable(variable_name) {
+                    *value = session_value;
+                }
+            }
+        }
+        if let Some(ShowVariablesFilter::Like(pattern)) = &statement.filter {
+            let pattern = pattern.to_lowercase();
+            variables.retain(|variable_name, _| like::like_match(variable_name, pattern.as_str(), like::DEFAULT_ESCAPE_CHAR));
+        }
 
-        let mut projection = vec![];
-        let sql_expr = SQLExpr::Identifier(Ident {
-            value: meta_const::COLUMN_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES_VARIABLE_NAME
-                .to_string(),
-            quote_style: None,
-        });
-        let select_item = SelectItem::UnnamedExpr(sql_expr);
-        projection.push(select_item);
-        let sql_expr = SQLExpr::Identifier(Ident {
-            value:
-                meta_const::COLUMN_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES_VARIABLE_VALUE
-                    .to_string(),
-            quote_style: None,
-        });
-        let select_item = SelectItem::UnnamedExpr(sql_expr);
-        projection.push(select_item);
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new(COLUMN_NAME_VARIABLE_NAME, DataType::Utf8, false),
+            Field::new(COLUMN_NAME_VALUE, DataType::Utf8, false),
+        ]));
+        let column_values_of_name = StringArray::from(variables.keys().map(|name| name.as_str()).collect::<Vec<_>>());
+        let column_values_of_value = StringArray::from(variables.values().map(|value| value.as_str()).collect::<Vec<_>>());
+        let record_batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(column_values_of_name), Arc::new(column_values_of_value)],
+        )?;
 
-        let selection = match filter {
-            None => None,
-            Some(filter) => match filter {
-                ShowStatementFilter::Like(value) => {
-                    let sql_expr = SQLExpr::BinaryOp {
-                        left: Box::new(SQLExpr::Identifier(Ident::new(meta_const::COLUMN_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES_VARIABLE_VALUE))),
-                        op: BinaryOperator::Like,
-                        right: Box::new(SQLExpr::Value(Value::SingleQuotedString(value.clone()))),
-                    };
-                    Some(sql_expr)
-                }
-                ShowStatementFilter::ILike(_) => None,
-                ShowStatementFilter::Where(sql_expr) => Some(sql_expr),
-            },
-        };
+        match statement.filter {
+            Some(ShowVariablesFilter::Where(condition)) => self.filter(schema, record_batch, condition.as_str()).await,
+            _ => Ok(ResultSet::new(schema, vec![record_batch])),
+        }
+    }
 
-        // select
-        let select = Select {
-            distinct: false,
-            top: None,
-            projection,
-            from: vec![table_with_joins],
-            lateral_views: vec![],
-            selection,
-            group_by: vec![],
-            cluster_by: vec![],
-            distribute_by: vec![],
-            sort_by: vec![],
-            having: None,
+    /// The variables which meet the WHERE condition, the condition is run by a query on a table of the variables
+    async fn filter(&self, schema: SchemaRef, record_batch: RecordBatch, condition: &str) -> MysqlResult<ResultSet> {
+        let sql = format!("SELECT * FROM {} WHERE {}", TABLE_NAME_VARIABLES, quote_column_names(condition));
+        let dialect = GenericDialect {};
+        let statements = Parser::parse_sql(&dialect, sql.as_str()).map_err(|parser_error| {
+            let message = format!(
+                "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use, error: {:?}",
+                parser_error
+            );
+            MysqlError::new_server_error(1064, "42000", message.as_str())
+        })?;
+        let query = match statements.first() {
+            Some(SQLStatement::Query(query)) => query.clone(),
+            _ => {
+                return Err(MysqlError::new_server_error(
+                    1064,
+                    "42000",
+                    "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use",
+                ));
+            }
         };
 
-        let query = Box::new(Query {
-            with: None,
-            body: SetExpr::Select(Box::new(select)),
-            order_by: vec![],
-            limit: None,
-            offset: None,
-            fetch: None,
-        });
-        let mut select_from = SelectFrom::new(
-            self.global_context.clone(),
-            self.session_context.clone(),
-            self.execution_context.clone(),
-        );
-        select_from.execute(&query).await
+        let mut execution_context = ExecutionContext::new();
+        let mem_table = MemTable::try_new(schema.clone(), vec![vec![record_batch]])?;
+        execution_context.register_table(TABLE_NAME_VARIABLES, Arc::new(mem_table))?;
+        let logical_plan = {
+            let state = execution_context.state.lock().unwrap().clone();
+            SqlToRel::new(&state).query_to_plan(&query)?
+        };
+        let logical_plan = execution_context.optimize(&logical_plan)?;
+        let execution_plan = execution_context.create_physical_plan(&logical_plan).await?;
+        let record_batches = collect(execution_plan).await?;
+        Ok(ResultSet::new(schema, record_batches))
     }
 }
+
+/// The column names in the condition are case insensitive, they are quoted with the case of the fields
+fn quote_column_names(condition: &str) -> String {
+    let dialect = GenericDialect {};
+    let tokens = match Tokenizer::new(&dialect, condition).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return condition.to_string(),
+    };
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case(COLUMN_NAME_VARIABLE_NAME) => {
+                Token::make_word(COLUMN_NAME_VARIABLE_NAME, Some('"')).to_string()
+            }
+            Token::Word(word) if word.quote_style.is_none() && word.value.eq_ignore_ascii_case(COLUMN_NAME_VALUE) => {
+                Token::make_word(COLUMN_NAME_VALUE, Some('"')).to_string()
+            }
+            Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+            _ => token.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("")
+}
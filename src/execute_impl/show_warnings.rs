@@ -0,0 +1,86 @@
+use std::sync::{Mutex, Arc};
+
+use arrow::array::{Int64Array, StringArray, UInt64Array};
+use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion::execution::context::ExecutionContext;
+
+use crate::core::global_context::GlobalContext;
+use crate::core::output::ResultSet;
+use crate::core::session_context::{SessionContext, WarningLevel};
+use crate::meta::meta_const;
+use crate::mysql::error::MysqlResult;
+use crate::util::show_statement::ShowWarningsStatement;
+
+pub struct ShowWarnings {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl ShowWarnings {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The conditions of the last statement in the order they were raised, SHOW ERRORS only shows the errors
+    pub fn execute(&self, statement: &ShowWarningsStatement) -> MysqlResult<ResultSet> {
+        if statement.count {
+            return Ok(self.execute_count(statement.errors_only));
+        }
+
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new("Level", DataType::Utf8, false),
+            Field::new("Code", DataType::UInt64, false),
+            Field::new("Message", DataType::Utf8, false),
+        ]));
+
+        let warnings = self
+            .session_context
+            .warnings
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|warning| !statement.errors_only || warning.level == WarningLevel::Error)
+            .cloned()
+            .collect::<Vec<_>>();
+        if warnings.is_empty() {
+            return Ok(ResultSet::new(schema, vec![]));
+        }
+
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(warnings.iter().map(|warning| warning.level.as_str()).collect::<Vec<_>>())),
+            Arc::new(UInt64Array::from(warnings.iter().map(|warning| warning.code as u64).collect::<Vec<_>>())),
+            Arc::new(StringArray::from(warnings.iter().map(|warning| warning.message.as_str()).collect::<Vec<_>>())),
+        ]).unwrap();
+
+        Ok(ResultSet::new(schema, vec![record_batch]))
+    }
+
+    /// SHOW COUNT(*) WARNINGS is SELECT @@session.warning_count
+    fn execute_count(&self, errors_only: bool) -> ResultSet {
+        let (variable_name, count) = if errors_only {
+            (meta_const::SYSTEM_VARIABLE_ERROR_COUNT, *self.session_context.error_count.lock().unwrap())
+        } else {
+            (meta_const::SYSTEM_VARIABLE_WARNING_COUNT, self.session_context.warning_count())
+        };
+
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new(format!("@@session.{}", variable_name).as_str(), DataType::Int64, false),
+        ]));
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(Int64Array::from(vec![count as i64])),
+        ]).unwrap();
+
+        ResultSet::new(schema, vec![record_batch])
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+use datafusion::execution::context::ExecutionContext;
+use sqlparser::ast::ObjectName;
+
+use crate::core::core_util;
+use crate::core::foreign_key;
+use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
+use crate::core::session_context::SessionContext;
+use crate::execute_impl::delete::DeleteFrom;
+use crate::meta::meta_util;
+use crate::mysql::error::MysqlResult;
+use crate::store::engine::engine_util::StoreEngineFactory;
+use crate::util::dbkey;
+
+pub struct TruncateTable {
+    global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
+    execution_context: ExecutionContext,
+}
+
+impl TruncateTable {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        session_context: SessionContext,
+        execution_context: ExecutionContext,
+    ) -> Self {
+        Self {
+            global_context,
+            session_context,
+            execution_context,
+        }
+    }
+
+    /// The rows and their index entries are deleted by the key prefixes of the table, not row by row,
+    /// and the auto increment value starts again. No row is affected as in mysql
+    pub async fn execute(&mut self, table_name: ObjectName) -> MysqlResult<u64> {
+        // the rows of the temporary table are kept by the session
+        if let Some(temporary_table) = core_util::get_temporary_table(&mut self.session_context, &table_name) {
+            temporary_table.truncate();
+            return Ok(0);
+        }
+
+        let full_table_name = meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
+        let table_def = meta_util::get_table(self.global_context.clone(), full_table_name.clone())?;
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Drop,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
+        foreign_key::check_truncate_table(self.global_context.clone(), &full_table_name)?;
+
+        // the rows of the other tenants are kept, only the rows of the tenant of the session are deleted
+        let tenant = core_util::get_table_tenant(self.global_context.clone(), &self.session_context, &table_def)?;
+        if tenant.is_some() {
+            let mut delete_from = DeleteFrom::new(
+                self.global_context.clone(),
+                self.session_context.clone(),
+                self.execution_context.clone(),
+            );
+            delete_from.execute(table_name, None).await?;
+            return Ok(0);
+        }
+
+        {
+            // the index build does not run a batch in the middle of the entries being deleted
+            let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+            let _index_build_guard = index_build_lock.read().unwrap();
+
+            let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table_def.clone())?;
+            store_engine.delete_prefix(dbkey::scan_record_rowid(&table_def.option))?;
+            store_engine.delete_prefix(dbkey::scan_column_key(&table_def.option))?;
+            store_engine.delete_prefix(dbkey::scan_table_index_key(&table_def.option))?;
+        }
+        if table_def.get_auto_increment_column().is_some() {
+            meta_util::save_auto_increment(self.global_context.clone(), &table_def, 1)?;
+        }
+
+        Ok(0)
+    }
+}
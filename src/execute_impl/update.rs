@@ -1,36 +1,28 @@
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use arrow::array::{Array};
-use arrow::array::{
-    Int8Array,
-    Int16Array,
-    Int32Array,
-    Int64Array,
-    UInt8Array,
-    UInt16Array,
-    UInt32Array,
-    UInt64Array,
-    Float32Array,
-    Float64Array,
-    StringArray,
-};
-use arrow::datatypes::{DataType};
+use arrow::array::{Array, StringArray};
 use arrow::record_batch::RecordBatch;
-use datafusion::physical_plan::{collect, ExecutionPlan};
-use sqlparser::ast::{Assignment, ObjectName, SetExpr, Query, Expr as SQLExpr};
+use datafusion::logical_plan::ToDFSchema;
+use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
+use datafusion::scalar::ScalarValue;
+use datafusion::sql::planner::SqlToRel;
+use sqlparser::ast::{Assignment, DataType as SQLDataType, Ident, ObjectName, SetExpr, Query, Expr as SQLExpr};
 
-use crate::mysql::{metadata};
 use crate::core::global_context::GlobalContext;
+use crate::core::privilege::{self, Privilege};
 
 use crate::mysql::error::{MysqlError, MysqlResult};
 
 use crate::core::session_context::SessionContext;
 use crate::store::engine::engine_util::StoreEngineFactory;
-use crate::util::dbkey::create_column_key;
-use crate::meta::meta_def::TableDef;
+use crate::store::engine::row_mutation::RowMutation;
+use crate::store::lock::LockMode;
+use crate::meta::meta_def::{TableDef, TableIndexDef};
 use datafusion::execution::context::ExecutionContext;
 use crate::meta::meta_util;
 use crate::core::core_util;
+use crate::core::foreign_key;
 use crate::execute_impl::select::SelectFrom;
 
 pub struct Update {
@@ -57,9 +49,9 @@ impl Update {
         table_name: ObjectName,
         assignments: Vec<Assignment>,
         selection: Option<SQLExpr>,
-    ) -> MysqlResult<u64> {
+    ) -> MysqlResult<(u64, u64)> {
         let full_table_name =
-            meta_util::fill_up_table_name(&mut self.session_context, table_name.clone()).unwrap();
+            meta_util::resolve_table_name(&mut self.session_context, &table_name)?;
 
         let table_map = self
             .global_context
@@ -79,9 +71,41 @@ impl Update {
             }
             Some(table) => table.clone(),
         };
+        privilege::check_table_privilege(
+            self.global_context.clone(),
+            &self.session_context,
+            Privilege::Update,
+            table_def.option.schema_name.as_str(),
+            table_def.option.table_name.as_str(),
+        )?;
+
+        if table_def.option.without_rowid {
+            // the rowid of the table without rowid is the primary key, it can not be changed
+            let table_index_list = meta_util::get_table_index_list(self.global_context.clone(), full_table_name.clone())?;
+            for table_index in table_index_list.iter().filter(|table_index| table_index.level == 1) {
+                for assignment in assignments.iter() {
+                    if table_index.column_name_list.iter().any(|column_name| column_name.value.to_lowercase() == assignment.id.value.to_lowercase()) {
+                        let message = format!("Updating the primary key column '{}' of a table without rowid is not supported", assignment.id.value);
+                        log::error!("{}", message);
+                        return Err(MysqlError::new_global_error(1105, message.as_str()));
+                    }
+                }
+            }
+        }
+
+        let tenant = core_util::get_table_tenant(self.global_context.clone(), &self.session_context, &table_def)?;
+        if let Some((sparrow_column, _)) = tenant {
+            // the row can not be moved to another tenant
+            let column_name = sparrow_column.sql_column.name.value.to_lowercase();
+            if assignments.iter().any(|assignment| assignment.id.value.to_lowercase() == column_name) {
+                let message = format!("Updating the tenant column '{}' is not allowed", sparrow_column.sql_column.name);
+                log::error!("{}", message);
+                return Err(MysqlError::new_global_error(1105, message.as_str()));
+            }
+        }
 
-        let select =
-            core_util::build_update_sqlselect(table_name.clone(), assignments.clone(), selection);
+        let column_names = table_def.get_columns().iter().map(|sparrow_column| sparrow_column.sql_column.name.clone()).collect();
+        let select = core_util::build_update_sqlselect(table_name.clone(), column_names, selection);
         let query = Box::new(Query {
             with: None,
             body: SetExpr::Select(Box::new(select)),
@@ -98,6 +122,9 @@ impl Update {
         let result = select_from.execute(&query).await;
         match result {
             Ok(result_set) => {
+                // the rows stay locked until the transaction ends, the other writers of them wait
+                let rowids = core_util::get_rowids(&result_set.record_batches);
+                core_util::lock_rows(self.global_context.clone(), &self.session_context, &table_def.option.full_table_name, rowids, LockMode::Exclusive).await?;
                 let result = self.update_record_batches(table_def, assignments, result_set.record_batches);
                 result
             }
@@ -105,184 +132,117 @@ impl Update {
         }
     }
 
-    fn update_record_batches(&self, table_def: TableDef, assignments: Vec<Assignment>, record_batches: Vec<RecordBatch>) -> MysqlResult<u64> {
-        let mut total = 0;
+    /// Returns the number of rows matched and the number of rows actually changed
+    fn update_record_batches(&self, table_def: TableDef, assignments: Vec<Assignment>, record_batches: Vec<RecordBatch>) -> MysqlResult<(u64, u64)> {
+        let mut total_matched = 0;
+        let mut total_changed = 0;
         for record_batch in record_batches {
             let result = self.update_record_batch(table_def.clone(), assignments.clone(), record_batch);
             match result {
-                Ok(count) => total += count,
+                Ok((matched, changed)) => {
+                    total_matched += matched;
+                    total_changed += changed;
+                }
                 Err(mysql_error) => return Err(mysql_error),
             }
         }
-        Ok(total)
+        Ok((total_matched, total_changed))
     }
 
-    pub fn update_record_batch(&self, table_def: TableDef, assignments: Vec<Assignment>, batch: RecordBatch) -> MysqlResult<u64> {
-        let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table_def.clone()).unwrap();
+    /// The assignments are evaluated against the columns of every row, as `a = a + 1`, before the rows are written
+    pub fn update_record_batch(&self, table_def: TableDef, assignments: Vec<Assignment>, batch: RecordBatch) -> MysqlResult<(u64, u64)> {
+        let column_value_map_list = self.evaluate_assignments(&table_def, &assignments, &batch)?;
 
-        let mut assignment_column_value: Vec<metadata::ArrayCell> = Vec::new();
-        for column_id in 1..batch.num_columns() {
-            let schema = &batch.schema();
-            let field = schema.field(column_id);
-            match field.data_type() {
-                DataType::Utf8 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<StringArray>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::StringArray(c));
-                }
-                DataType::Int8 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<Int8Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::Int8Array(c));
-                }
-                DataType::Int16 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<Int16Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::Int16Array(c));
-                }
-                DataType::Int32 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<Int32Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::Int32Array(c));
-                }
-                DataType::Int64 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<Int64Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::Int64Array(c));
-                }
-                DataType::UInt8 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<UInt8Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::UInt8Array(c));
-                }
-                DataType::UInt16 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<UInt16Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::UInt16Array(c));
-                }
-                DataType::UInt32 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<UInt32Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::UInt32Array(c));
-                }
-                DataType::UInt64 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<UInt64Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::UInt64Array(c));
-                }
-                DataType::Float32 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<Float32Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::Float32Array(c));
-                }
-                DataType::Float64 => {
-                    let c = batch
-                        .column(column_id)
-                        .as_any()
-                        .downcast_ref::<Float64Array>()
-                        .unwrap();
-                    assignment_column_value.push(metadata::ArrayCell::Float64Array(c));
-                }
-                _ => {}
-            }
-        }
+        // the index build does not run a batch in the middle of the rows being changed
+        let index_build_lock = self.global_context.lock().unwrap().index_build_lock.clone();
+        let _index_build_guard = index_build_lock.read().unwrap();
+
+        let store_engine = StoreEngineFactory::try_new_with_session(self.global_context.clone(), &self.session_context, table_def.clone())?;
+
+        // the indexes with an assigned column, the primary key of the table without rowid can not be assigned
+        let table_index_list: Vec<TableIndexDef> = meta_util::get_table_index_list(self.global_context.clone(), table_def.option.full_table_name.clone())?
+            .into_iter()
+            .filter(|table_index| !(table_def.option.without_rowid && table_index.level == 1))
+            .filter(|table_index| table_index.column_name_list.iter().any(|column_name| {
+                assignments.iter().any(|assignment| assignment.id.value.to_lowercase() == column_name.value.to_lowercase())
+            }))
+            .collect();
 
         let rowid_array = batch
             .column(0)
             .as_any()
             .downcast_ref::<StringArray>()
             .unwrap();
-        for row_index in 0..rowid_array.len() {
+        // the changed columns and the index entries they move are written together
+        let mut row_mutation = RowMutation::new(&table_def, table_index_list, store_engine.as_ref());
+        let mut row_changes = vec![];
+        let mut changed = 0;
+        for (row_index, column_value_map) in column_value_map_list.into_iter().enumerate() {
             let rowid = rowid_array.value(row_index);
-            for assignment_index in 0..assignments.len() {
-                let assignment = &assignments[assignment_index];
-                let column_value;
-                match assignment_column_value[assignment_index] {
-                    metadata::ArrayCell::StringArray(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::Int8Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::Int16Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::Int32Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::Int64Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::UInt8Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::UInt16Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::UInt32Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::UInt64Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::Float32Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                    metadata::ArrayCell::Float64Array(s) => {
-                        column_value = s.value(row_index).to_string()
-                    }
-                }
-
-                let column_name = &assignment.id;
+            let mut column_values = vec![];
+            for (column_name, scalar_value) in column_value_map {
+                let sparrow_column = table_def.get_table_column().get_sparrow_column(column_name).unwrap();
+                let column_value = match core_util::convert_scalar_value(scalar_value)? {
+                    None => vec![0x00],
+                    Some(value) => value.into_bytes(),
+                };
+                column_values.push((sparrow_column.store_id, column_value));
+            }
+            if row_mutation.update_row(rowid, column_values.clone())? {
+                changed += 1;
+                row_changes.push((rowid.to_string(), column_values));
+            }
+        }
+        // the foreign keys of the changed rows are checked and the rows referring to them follow the change
+        foreign_key::update_rows(self.global_context.clone(), &self.session_context, &table_def, &row_changes)?;
+        row_mutation.write()?;
 
-                let sparrow_column = table_def.get_table_column().get_sparrow_column(column_name.clone()).unwrap();
-                let store_id = sparrow_column.store_id;
+        Ok((rowid_array.len() as u64, changed))
+    }
 
-                let record_column_key = create_column_key(table_def.option.full_table_name.clone(), store_id, rowid.as_ref());
-                let result = store_engine.put_key(record_column_key.clone(), column_value.as_bytes());
-                match result {
-                    Err(error) => {
-                        return Err(MysqlError::new_global_error(1105, format!(
-                            "Unknown error. An error occurred while updating the key, key: {:?}, error: {:?}",
-                            record_column_key.clone(),
-                            error,
-                        ).as_str()));
-                    }
-                    _ => {}
+    /// The assigned values of every row by their columns, checked and cast as the values of the insert
+    fn evaluate_assignments(&self, table_def: &TableDef, assignments: &[Assignment], batch: &RecordBatch) -> MysqlResult<Vec<HashMap<Ident, ScalarValue>>> {
+        let state = self.execution_context.state.lock().unwrap().clone();
+        let query_planner = SqlToRel::new(&state);
+        let planner = DefaultPhysicalPlanner::default();
+        let schema = batch.schema();
+        let dfschema = schema.as_ref().clone().to_dfschema()?;
+
+        let mut column_value_map_list = vec![HashMap::new(); batch.num_rows()];
+        for assignment in assignments {
+            let sparrow_column = table_def
+                .get_columns()
+                .iter()
+                .find(|sparrow_column| sparrow_column.sql_column.name.value.to_lowercase() == assignment.id.value.to_lowercase());
+            let sparrow_column = match sparrow_column {
+                Some(sparrow_column) => sparrow_column,
+                None => {
+                    return Err(MysqlError::new_server_error(
+                        1054,
+                        "42S22",
+                        format!("Unknown column '{}' in 'field list'", assignment.id.value).as_str(),
+                    ));
                 }
+            };
+
+            let mut value = assignment.value.clone();
+            if matches!(sparrow_column.sql_column.data_type, SQLDataType::Decimal(_, _)) {
+                value = core_util::quote_decimal_literal(&value);
+            }
+            let expr = core_util::column_value_to_logical_expr(&query_planner, &value, &dfschema)?;
+            let physical_expr = planner.create_physical_expr(&expr, &dfschema, &schema, &state)?;
+            let array = physical_expr.evaluate(batch)?.into_array(batch.num_rows());
+            for (row_index, column_value_map) in column_value_map_list.iter_mut().enumerate() {
+                let scalar_value = core_util::user_variable_value(&array, row_index)?;
+                column_value_map.insert(sparrow_column.sql_column.name.clone(), scalar_value);
             }
         }
 
-        Ok(rowid_array.len() as u64)
+        core_util::cast_decimal_column_values(table_def, &mut column_value_map_list)?;
+        core_util::cast_tinyint_column_values(table_def, &mut column_value_map_list)?;
+        core_util::cast_binary_column_values(table_def, &mut column_value_map_list)?;
+        let session_time_zone = core_util::get_session_time_zone(&self.session_context);
+        core_util::cast_datetime_column_values(table_def, &mut column_value_map_list, &session_time_zone)?;
+        Ok(column_value_map_list)
     }
 }
@@ -5,6 +5,7 @@ extern crate clap;
 
 use std::io;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use arrow::datatypes::DataType as ArrowDataType;
 use log4rs;
@@ -15,9 +16,12 @@ use meta::initial;
 
 use crate::config::util::get_config_path;
 use crate::config::util::read_config;
+use crate::core::front_end;
 use crate::core::global_context::GlobalContext;
 use crate::meta::meta_util;
+use crate::mysql::auth;
 use crate::mysql::handle;
+use crate::mysql::tls;
 use crate::mysql::metadata::MysqlType;
 
 pub mod core;
@@ -25,6 +29,7 @@ pub mod config;
 pub mod datafusion_impl;
 pub mod meta;
 pub mod mysql;
+pub mod postgres;
 pub mod store;
 pub mod util;
 pub mod test;
@@ -39,7 +44,14 @@ async fn main() {
 
     let my_config = read_config(config_path.as_str());
 
-    let global_context = Arc::new(Mutex::new(GlobalContext::new_with_config(my_config)));
+    let global_context = match GlobalContext::new_with_config(my_config) {
+        Ok(global_context) => Arc::new(Mutex::new(global_context)),
+        Err(e) => {
+            // the log is not set up yet
+            eprintln!("open engine error: {}", e.message());
+            std::process::exit(1);
+        }
+    };
 
     log4rs::init_file(global_context.lock().unwrap().my_config.server.log_file.to_string(), Default::default()).unwrap();
 
@@ -47,6 +59,16 @@ async fn main() {
     let listener = TcpListener::bind(&addr).await.unwrap();
     log::info!("Listening on: {}", addr.clone());
 
+    let postgres_addr = global_context.lock().unwrap().my_config.server.postgres_bind_host.clone();
+    let postgres_listener = match postgres_addr {
+        Some(postgres_addr) => {
+            let postgres_listener = TcpListener::bind(&postgres_addr).await.unwrap();
+            log::info!("Listening on: {} for the PostgreSQL protocol", postgres_addr);
+            Some(postgres_listener)
+        }
+        None => None,
+    };
+
     let result = meta_util::init_meta(global_context.clone()).await;
     if let Err(e) = result {
         log::error!("init meta error: {}", e);
@@ -82,23 +104,81 @@ async fn main() {
         }
     }
 
+    // view def
+    let result = meta_util::load_all_view(global_context.clone());
+    if let Err(e) = result {
+        log::error!("init meta view error: {}", e);
+        return;
+    }
+
+    let repair_auto_increment = global_context.lock().unwrap().my_config.server.repair_auto_increment;
+    if repair_auto_increment {
+        let result = meta_util::repair_auto_increment(global_context.clone());
+        if let Err(e) = result {
+            log::error!("repair auto increment error: {}", e);
+            return;
+        }
+    }
+
+    let migrate_table_id = global_context.lock().unwrap().my_config.server.migrate_table_id;
+    if migrate_table_id {
+        let result = meta_util::migrate_table_id(global_context.clone());
+        if let Err(e) = result {
+            log::error!("migrate table id error: {}", e);
+            return;
+        }
+    }
+
+    let check_meta = global_context.lock().unwrap().my_config.server.check_meta;
+    if check_meta {
+        let result = meta_util::verify_meta(global_context.clone());
+        if let Err(e) = result {
+            log::error!("check meta error: {}", e);
+            return;
+        }
+    }
+
+    let tls_acceptor = match tls::load_tls_acceptor(&global_context.lock().unwrap().my_config.server) {
+        Ok(tls_acceptor) => tls_acceptor,
+        Err(e) => {
+            log::error!("load tls error: {}", e);
+            return;
+        }
+    };
+
+    let rsa_private_key = match auth::load_rsa_private_key(&global_context.lock().unwrap().my_config.server) {
+        Ok(rsa_private_key) => rsa_private_key.map(Arc::new),
+        Err(e) => {
+            log::error!("load rsa key error: {}", e);
+            return;
+        }
+    };
+
+    let gc_interval = global_context.lock().unwrap().my_config.engine.sled.gc_interval;
+    let sled_mvcc = global_context.lock().unwrap().engine.sled_mvcc.clone();
+    if let Some(sled_mvcc) = sled_mvcc {
+        if gc_interval > 0 {
+            tokio::spawn(store::mvcc::run_garbage_collection(sled_mvcc, Duration::from_secs(gc_interval)));
+        }
+    }
+
     let mut stream = signal(SignalKind::interrupt()).unwrap();
 
+    if let Some(postgres_listener) = postgres_listener {
+        let global_context = global_context.clone();
+        let tls_acceptor = tls_acceptor.clone();
+        tokio::spawn(async move {
+            front_end::serve(postgres_listener, |socket| {
+                postgres::handle::Handle::new(socket, global_context.clone(), tls_acceptor.clone())
+            })
+            .await;
+        });
+    }
+
     tokio::select! {
-        _ = async {
-            loop {
-                match listener.accept().await {
-                    Ok((socket, _)) => {
-                        let mut handler = handle::Handle::new(socket, global_context.clone()).await.unwrap();
-                        tokio::spawn(async move {
-                            handler.run().await;
-                            log::info!("client closed");
-                        });
-                    }
-                    Err(e) => log::error!("error accepting socket; error = {:?}", e),
-                }
-            }
-        } => {}
+        _ = front_end::serve(listener, |socket| {
+            handle::Handle::new(socket, global_context.clone(), tls_acceptor.clone(), rsa_private_key.clone())
+        }) => {}
         _ = async {
             stream.recv().await;
             log::info!("got signal interrupt");
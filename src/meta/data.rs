@@ -6,11 +6,13 @@ use crate::meta::{meta_def};
 use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::meta::meta_def::TableDef;
 use crate::meta::meta_def::SchemaDef;
+use crate::meta::meta_def::ViewDef;
 
 #[derive(Debug, Clone)]
 pub struct MetaData {
     schema_map: HashMap<ObjectName, meta_def::SchemaDef>,
     table_map: HashMap<ObjectName, meta_def::TableDef>,
+    view_map: HashMap<ObjectName, ViewDef>,
     /// Map the column name to an serial number
     serial_number_map: HashMap<ObjectName, HashMap<Ident, usize>>,
 }
@@ -19,11 +21,13 @@ impl MetaData {
     pub fn new() -> Self {
         let schema_map: HashMap<ObjectName, meta_def::SchemaDef> = HashMap::new();
         let table_map: HashMap<ObjectName, meta_def::TableDef> = HashMap::new();
+        let view_map: HashMap<ObjectName, ViewDef> = HashMap::new();
         let serial_number_map: HashMap<ObjectName, HashMap<Ident, usize>> = HashMap::new();
 
         Self {
             schema_map,
             table_map,
+            view_map,
             serial_number_map,
         }
     }
@@ -34,6 +38,10 @@ impl MetaData {
         }
     }
 
+    pub fn add_all_view(&mut self, view_map: HashMap<ObjectName, ViewDef>) {
+        self.view_map = view_map;
+    }
+
     pub fn add_all_schema(&mut self, schema_map: HashMap<ObjectName, SchemaDef>) {
         for (full_schema_name, schema_def) in schema_map.iter() {
             self.add_schema(full_schema_name.clone(), schema_def.clone());
@@ -41,6 +49,14 @@ impl MetaData {
     }
 
     pub fn add_table(&mut self, full_table_name: ObjectName, table: meta_def::TableDef) {
+        // the serial number of a column is its store id, it is kept when the column is renamed or modified
+        let serial_number_map = table
+            .get_columns()
+            .iter()
+            .map(|sparrow_column| (sparrow_column.sql_column.name.clone(), sparrow_column.store_id as usize))
+            .collect();
+        self.serial_number_map.insert(full_table_name.clone(), serial_number_map);
+
         let t = self.table_map.entry(full_table_name.clone()).or_insert(table.clone());
         *t = table;
     }
@@ -70,7 +86,28 @@ impl MetaData {
         self.table_map.get(&full_table_name)
     }
 
+    pub fn add_view(&mut self, full_view_name: ObjectName, view_def: ViewDef) {
+        self.view_map.insert(full_view_name, view_def);
+    }
+
+    pub fn get_view_map(&self) -> HashMap<ObjectName, ViewDef> {
+        self.view_map.clone()
+    }
+
+    pub fn get_view(&self, full_view_name: ObjectName) -> Option<&ViewDef> {
+        self.view_map.get(&full_view_name)
+    }
+
+    pub fn delete_view(&mut self, full_view_name: ObjectName) {
+        self.view_map.remove(&full_view_name);
+    }
+
+    pub fn get_serial_number_map(&self, full_table_name: ObjectName) -> Option<&HashMap<Ident, usize>> {
+        self.serial_number_map.get(&full_table_name)
+    }
+
     pub fn delete_table(&mut self, full_table_name: ObjectName) {
         self.table_map.remove(&full_table_name).unwrap();
+        self.serial_number_map.remove(&full_table_name);
     }
 }
@@ -16,7 +16,7 @@ pub fn columns(global_context: Arc<Mutex<GlobalContext>>) -> TableDef {
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_NAME, SQLDataType::Char(None), ColumnOption::NotNull),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_STORE_ID, SQLDataType::Int(None), ColumnOption::NotNull),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_ORDINAL_POSITION, SQLDataType::Int(None), ColumnOption::NotNull),
-        meta_util::create_sql_column("COLUMN_DEFAULT", SQLDataType::Char(None), ColumnOption::Null),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_DEFAULT, SQLDataType::Char(None), ColumnOption::Null),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_IS_NULLABLE, SQLDataType::Char(None), ColumnOption::NotNull),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_DATA_TYPE, SQLDataType::Char(None), ColumnOption::NotNull),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_CHARACTER_MAXIMUM_LENGTH, SQLDataType::Int(None), ColumnOption::Null),
@@ -24,11 +24,11 @@ pub fn columns(global_context: Arc<Mutex<GlobalContext>>) -> TableDef {
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_NUMERIC_PRECISION, SQLDataType::Int(None), ColumnOption::Null),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_NUMERIC_SCALE, SQLDataType::Int(None), ColumnOption::Null),
         meta_util::create_sql_column("DATETIME_PRECISION", SQLDataType::Int(None), ColumnOption::Null),
-        meta_util::create_sql_column("CHARACTER_SET_NAME", SQLDataType::Char(None), ColumnOption::Null),
-        meta_util::create_sql_column("COLLATION_NAME", SQLDataType::Char(None), ColumnOption::Null),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_CHARACTER_SET_NAME, SQLDataType::Char(None), ColumnOption::Null),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLLATION_NAME, SQLDataType::Char(None), ColumnOption::Null),
         meta_util::create_sql_column("COLUMN_TYPE", SQLDataType::Char(None), ColumnOption::NotNull),
         meta_util::create_sql_column("COLUMN_KEY", SQLDataType::Char(None), ColumnOption::NotNull),
-        meta_util::create_sql_column("EXTRA", SQLDataType::Char(None), ColumnOption::Null),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_EXTRA, SQLDataType::Char(None), ColumnOption::Null),
         meta_util::create_sql_column("PRIVILEGES", SQLDataType::Char(None), ColumnOption::Null),
         meta_util::create_sql_column("COLUMN_COMMENT", SQLDataType::Char(None), ColumnOption::NotNull),
         meta_util::create_sql_column("GENERATION_EXPRESSION", SQLDataType::Char(None), ColumnOption::NotNull),
@@ -57,6 +57,8 @@ pub fn tables(global_context: Arc<Mutex<GlobalContext>>) -> TableDef {
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_INDEX_LENGTH, SQLDataType::Int(None), ColumnOption::NotNull),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_AUTO_INCREMENT, SQLDataType::Int(None), ColumnOption::NotNull),
         meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_COLUMN_MAX_STORE_ID, SQLDataType::Int(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_CREATE_OPTIONS, SQLDataType::Char(None), ColumnOption::Null),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_ID, SQLDataType::Int(None), ColumnOption::Null),
     ];
     let constraints = vec![];
 
@@ -136,6 +138,57 @@ pub fn key_column_usage(global_context: Arc<Mutex<GlobalContext>>) -> TableDef {
     )
 }
 
+pub fn referential_constraints(global_context: Arc<Mutex<GlobalContext>>) -> TableDef {
+    let sql_column_list = vec![
+        meta_util::create_sql_column("constraint_catalog", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("constraint_schema", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("constraint_name", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("unique_constraint_catalog", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("unique_constraint_schema", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("unique_constraint_name", SQLDataType::Char(None), ColumnOption::Null),
+        meta_util::create_sql_column("match_option", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("update_rule", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("delete_rule", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("table_name", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("referenced_table_name", SQLDataType::Char(None), ColumnOption::NotNull),
+    ];
+    let constraints = vec![];
+
+    create_table(
+        global_context.clone(),
+        meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+        meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS,
+        sql_column_list.clone(),
+        constraints.clone(),
+    )
+}
+
+/// The view_columns are the names of the columns of the view as a json array, the definition is renamed to them
+pub fn views(global_context: Arc<Mutex<GlobalContext>>) -> TableDef {
+    let sql_column_list = vec![
+        meta_util::create_sql_column(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_CATALOG, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_DEFINITION, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_CHECK_OPTION, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_IS_UPDATABLE, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_DEFINER, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_SECURITY_TYPE, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_CHARACTER_SET_CLIENT, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_COLLATION_CONNECTION, SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_COLUMNS, SQLDataType::Char(None), ColumnOption::NotNull),
+    ];
+    let constraints = vec![];
+
+    create_table(
+        global_context.clone(),
+        meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+        meta_const::TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS,
+        sql_column_list.clone(),
+        constraints.clone(),
+    )
+}
+
 pub fn table_constraints(global_context: Arc<Mutex<GlobalContext>>) -> TableDef {
     let sql_column_list = vec![
         meta_util::create_sql_column("constraint_catalog", SQLDataType::Char(None), ColumnOption::NotNull),
@@ -79,3 +79,50 @@ pub fn users(global_context: Arc<Mutex<GlobalContext>>) -> meta_def::TableDef {
         constraints.clone(),
     )
 }
+
+/// The privileges of the users on a schema, granted by GRANT ... ON db_name.*
+pub fn db(global_context: Arc<Mutex<GlobalContext>>) -> meta_def::TableDef {
+    let sql_column_list = vec![
+        meta_util::create_sql_column("Host", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Db", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("User", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Select_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Insert_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Update_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Delete_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Create_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Drop_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Grant_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("References_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Index_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Alter_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Create_tmp_table_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Lock_tables_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Create_view_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Show_view_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Create_routine_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Alter_routine_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Execute_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Event_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+        meta_util::create_sql_column("Trigger_priv", SQLDataType::Char(None), ColumnOption::NotNull),
+    ];
+
+    let mut columns = vec![];
+    columns.push(Ident::new("Host"));
+    columns.push(Ident::new("Db"));
+    columns.push(Ident::new("User"));
+    let table_constraint = TableConstraint::Unique {
+        name: Some(Ident { value: meta_const::NAME_OF_PRIMARY.to_string(), quote_style: None }),
+        columns,
+        is_primary: true,
+    };
+    let constraints = vec![table_constraint];
+
+    create_table(
+        global_context.clone(),
+        meta_const::SCHEMA_NAME_OF_DEF_MYSQL,
+        meta_const::TABLE_NAME_OF_DEF_MYSQL_DB,
+        sql_column_list.clone(),
+        constraints.clone(),
+    )
+}
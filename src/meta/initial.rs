@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use arrow::array::{as_primitive_array, as_string_array, Int64Array, StringArray};
+use arrow::array::{as_primitive_array, as_string_array, Array, Int64Array, StringArray};
 use datafusion::scalar::ScalarValue;
 use sqlparser::ast::{ColumnDef as SQLColumnDef, ColumnOption, Ident, ObjectName, TableConstraint};
 
@@ -10,15 +10,18 @@ use crate::execute_impl::insert::Insert;
 use crate::meta::def::performance_schema;
 use crate::meta::def::{information_schema, mysql};
 use crate::meta::meta_def::{
-    SchemaDef, SchemaOptionDef, SparrowColumnDef, StatisticsColumn, TableColumnDef, TableDef,
-    TableOptionDef,
+    ForeignKeyDef, ReferentialAction, SchemaDef, SchemaOptionDef, SparrowColumnDef, StatisticsColumn,
+    TableColumnDef, TableDef, TableOptionDef, ViewDef,
 };
 use crate::meta::{def, meta_const, meta_util};
+use crate::mysql::auth;
 use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::physical_plan::insert::PhysicalPlanInsert;
+use crate::store::compression;
 use crate::store::engine::engine_util;
 use crate::store::engine::engine_util::TableEngineFactory;
 use crate::util::convert::{ToIdent, ToObjectName};
+use crate::util::dbkey;
 
 pub fn create_table(
     global_context: Arc<Mutex<GlobalContext>>,
@@ -50,6 +53,8 @@ pub fn create_table(
 pub fn create_schema(
     global_context: Arc<Mutex<GlobalContext>>,
     full_schema_name: ObjectName,
+    default_character_set_name: &str,
+    default_collation_name: &str,
 ) -> MysqlResult<u64> {
     let mut column_name_list = vec![];
     for sql_column in def::information_schema::schemata(global_context.clone())
@@ -74,11 +79,11 @@ pub fn create_schema(
     );
     column_value_map.insert(
         "default_character_set_name".to_ident(),
-        ScalarValue::Utf8(Some("utf8mb4".to_string())),
+        ScalarValue::Utf8(Some(default_character_set_name.to_string())),
     );
     column_value_map.insert(
         "default_collation_name".to_ident(),
-        ScalarValue::Utf8(Some("utf8mb4_0900_ai_ci".to_string())),
+        ScalarValue::Utf8(Some(default_collation_name.to_string())),
     );
     column_value_map.insert(
         "SQL_PATH".to_ident(),
@@ -156,9 +161,10 @@ impl SaveTableConstraints {
             "constraint_type".to_ident(),
             ScalarValue::Utf8(Some(constraint_type.to_string())),
         );
+        // the primary key and the unique constraints are always enforced
         column_value_map.insert(
             "enforced".to_ident(),
-            ScalarValue::Utf8(Some("YES".to_string())),
+            ScalarValue::Utf8(Some(meta_const::CONSTRAINT_ENFORCED_YES.to_string())),
         );
         self.column_value_map_list.push(column_value_map);
     }
@@ -255,6 +261,36 @@ impl SaveKeyColumnUsage {
         self.column_value_map_list.push(column_value_map);
     }
 
+    /// The column of a foreign key with the column of the referenced table it refers to
+    pub fn add_foreign_key_row(
+        &mut self,
+        constraint_name: &str,
+        seq_in_index: i64,
+        column_name: &str,
+        referenced_table_schema: &str,
+        referenced_table_name: &str,
+        referenced_column_name: &str,
+    ) {
+        self.add_row(constraint_name, seq_in_index, column_name);
+        let column_value_map = self.column_value_map_list.last_mut().unwrap();
+        column_value_map.insert(
+            "position_in_unique_constraint".to_ident(),
+            ScalarValue::Int64(Some(seq_in_index)),
+        );
+        column_value_map.insert(
+            "referenced_table_schema".to_ident(),
+            ScalarValue::Utf8(Some(referenced_table_schema.to_string())),
+        );
+        column_value_map.insert(
+            "referenced_table_name".to_ident(),
+            ScalarValue::Utf8(Some(referenced_table_name.to_string())),
+        );
+        column_value_map.insert(
+            "referenced_column_name".to_ident(),
+            ScalarValue::Utf8(Some(referenced_column_name.to_string())),
+        );
+    }
+
     pub fn save(&mut self) -> MysqlResult<u64> {
         let table_def = def::information_schema::key_column_usage(self.global_context.clone());
 
@@ -274,6 +310,108 @@ impl SaveKeyColumnUsage {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct SaveReferentialConstraints {
+    global_context: Arc<Mutex<GlobalContext>>,
+    catalog_name: String,
+    schema_name: String,
+    table_name: String,
+    column_value_map_list: Vec<HashMap<Ident, ScalarValue>>,
+}
+
+impl SaveReferentialConstraints {
+    pub fn new(
+        global_context: Arc<Mutex<GlobalContext>>,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Self {
+        Self {
+            global_context,
+            catalog_name: catalog_name.to_string(),
+            schema_name: schema_name.to_string(),
+            table_name: table_name.to_string(),
+            column_value_map_list: vec![],
+        }
+    }
+
+    /// The unique constraint is the key of the referenced table over the referenced columns
+    pub fn add_row(
+        &mut self,
+        constraint_name: &str,
+        referenced_table_schema: &str,
+        referenced_table_name: &str,
+        unique_constraint_name: &str,
+        update_rule: &str,
+        delete_rule: &str,
+    ) {
+        let mut column_value_map = HashMap::new();
+        column_value_map.insert(
+            "constraint_catalog".to_ident(),
+            ScalarValue::Utf8(Some(self.catalog_name.clone())),
+        );
+        column_value_map.insert(
+            "constraint_schema".to_ident(),
+            ScalarValue::Utf8(Some(self.schema_name.clone())),
+        );
+        column_value_map.insert(
+            "constraint_name".to_ident(),
+            ScalarValue::Utf8(Some(constraint_name.to_string())),
+        );
+        column_value_map.insert(
+            "unique_constraint_catalog".to_ident(),
+            ScalarValue::Utf8(Some(self.catalog_name.clone())),
+        );
+        column_value_map.insert(
+            "unique_constraint_schema".to_ident(),
+            ScalarValue::Utf8(Some(referenced_table_schema.to_string())),
+        );
+        column_value_map.insert(
+            "unique_constraint_name".to_ident(),
+            ScalarValue::Utf8(Some(unique_constraint_name.to_string())),
+        );
+        column_value_map.insert(
+            "match_option".to_ident(),
+            ScalarValue::Utf8(Some(meta_const::MATCH_OPTION_OF_FOREIGN_KEY.to_string())),
+        );
+        column_value_map.insert(
+            "update_rule".to_ident(),
+            ScalarValue::Utf8(Some(update_rule.to_string())),
+        );
+        column_value_map.insert(
+            "delete_rule".to_ident(),
+            ScalarValue::Utf8(Some(delete_rule.to_string())),
+        );
+        column_value_map.insert(
+            "table_name".to_ident(),
+            ScalarValue::Utf8(Some(self.table_name.clone())),
+        );
+        column_value_map.insert(
+            "referenced_table_name".to_ident(),
+            ScalarValue::Utf8(Some(referenced_table_name.to_string())),
+        );
+        self.column_value_map_list.push(column_value_map);
+    }
+
+    pub fn save(&mut self) -> MysqlResult<u64> {
+        let table_def = def::information_schema::referential_constraints(self.global_context.clone());
+
+        let mut column_name_list = vec![];
+        for column_def in table_def.get_columns() {
+            column_name_list.push(column_def.sql_column.name.to_string());
+        }
+
+        let insert = PhysicalPlanInsert::new(self.global_context.clone());
+        let total = insert.execute(
+            table_def,
+            column_name_list.clone(),
+            vec![],
+            self.column_value_map_list.clone(),
+        );
+        total
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SaveStatistics {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -437,6 +575,12 @@ pub fn add_information_schema_tables(
     let table_type = table_option.table_type;
     let engine = table_option.engine;
     let column_max_store_id = table_option.column_max_store_id;
+    let create_options = table_option.to_create_options();
+    // the tables keyed by their full table names have no table id
+    let table_id = match table_option.table_id {
+        0 => None,
+        table_id => Some(table_id as i64),
+    };
 
     let mut column_value_map_list: Vec<HashMap<Ident, ScalarValue>> = vec![];
     let mut column_value_map = HashMap::new();
@@ -480,6 +624,14 @@ pub fn add_information_schema_tables(
         meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_COLUMN_MAX_STORE_ID.to_ident(),
         ScalarValue::Int64(Some(column_max_store_id)),
     );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_CREATE_OPTIONS.to_ident(),
+        ScalarValue::Utf8(Some(create_options)),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_ID.to_ident(),
+        ScalarValue::Int64(table_id),
+    );
     column_value_map_list.push(column_value_map);
 
     let insert = PhysicalPlanInsert::new(global_context.clone());
@@ -492,6 +644,49 @@ pub fn add_information_schema_tables(
     total
 }
 
+/// Save the table id of the table keyed by its full table name before in its row of information_schema.tables,
+/// the row had no table id so only the key of the column is written
+pub fn save_information_schema_table_id(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_option: &TableOptionDef,
+    table_id: u64,
+) -> MysqlResult<()> {
+    let table_def = information_schema::tables(global_context.clone());
+    let projection_schema = table_def.to_schema();
+    let column_index_of_rowid = projection_schema.index_of(meta_const::COLUMN_ROWID).unwrap();
+    let column_index_of_db_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_SCHEMA)
+        .unwrap();
+    let column_index_of_table_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_NAME)
+        .unwrap();
+    let sparrow_column = table_def
+        .get_table_column()
+        .get_sparrow_column(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_ID.to_ident())
+        .unwrap();
+
+    let table_engine = TableEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+    let store_engine = engine_util::StoreEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+    for item in table_engine.table_iterator(None, &[]) {
+        let record_batch = match item {
+            Ok(record_batch) => record_batch,
+            Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+        };
+        let rowid_row: &StringArray = as_string_array(record_batch.column(column_index_of_rowid));
+        let db_name_row: &StringArray = as_string_array(record_batch.column(column_index_of_db_name));
+        let table_name_row: &StringArray = as_string_array(record_batch.column(column_index_of_table_name));
+        for row_index in 0..record_batch.num_rows() {
+            if db_name_row.value(row_index) != table_option.schema_name || table_name_row.value(row_index) != table_option.table_name {
+                continue;
+            }
+            let column_key = dbkey::create_column_key(&table_def.option, sparrow_column.store_id, rowid_row.value(row_index));
+            let column_value = compression::compress_column_value(&table_def.option, table_id.to_string().as_bytes());
+            store_engine.put_key(column_key, column_value.as_slice())?;
+        }
+    }
+    Ok(())
+}
+
 pub fn add_information_schema_columns(
     global_context: Arc<Mutex<GlobalContext>>,
     table_option: TableOptionDef,
@@ -552,6 +747,7 @@ pub fn add_information_schema_columns(
 
         let data_type =
             meta_util::convert_sql_data_type(&sparrow_column.sql_column.data_type).unwrap();
+        let column_type = meta_util::get_column_type(&sparrow_column.sql_column).unwrap();
         let numeric_precision =
             meta_util::get_numeric_precision(&sparrow_column.sql_column.data_type);
         let numeric_scale = meta_util::get_numeric_scale(&sparrow_column.sql_column.data_type);
@@ -570,6 +766,11 @@ pub fn add_information_schema_columns(
             is_nullable = "YES"
         }
 
+        let mut extra = "";
+        if meta_util::column_is_auto_increment(&sparrow_column.sql_column) {
+            extra = meta_const::EXTRA_OF_AUTO_INCREMENT;
+        }
+
         let mut column_value_map = HashMap::new();
         // TABLE_CATALOG
         column_value_map.insert(
@@ -602,7 +803,11 @@ pub fn add_information_schema_columns(
             ScalarValue::Int64(Some(sparrow_column.ordinal_position)),
         );
         // COLUMN_DEFAULT
-        column_value_map.insert("COLUMN_DEFAULT".to_ident(), ScalarValue::Utf8(None));
+        let column_default = meta_util::get_column_default(&sparrow_column.sql_column).map(|expr| expr.to_string());
+        column_value_map.insert(
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_DEFAULT.to_ident(),
+            ScalarValue::Utf8(column_default),
+        );
         // IS_NULLABLE
         column_value_map.insert(
             "is_nullable".to_ident(),
@@ -634,18 +839,27 @@ pub fn add_information_schema_columns(
         );
         // DATETIME_PRECISION
         column_value_map.insert("DATETIME_PRECISION".to_ident(), ScalarValue::Int64(None));
-        // CHARACTER_SET_NAME
-        column_value_map.insert("CHARACTER_SET_NAME".to_ident(), ScalarValue::Utf8(None));
-        // COLLATION_NAME
-        column_value_map.insert("COLLATION_NAME".to_ident(), ScalarValue::Utf8(None));
+        // CHARACTER_SET_NAME and COLLATION_NAME, only the text columns have them
+        let collation = meta_util::get_column_collation(&sparrow_column.sql_column);
+        column_value_map.insert(
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_CHARACTER_SET_NAME.to_ident(),
+            ScalarValue::Utf8(collation.map(|collation| collation.character_set.to_string())),
+        );
+        column_value_map.insert(
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLLATION_NAME.to_ident(),
+            ScalarValue::Utf8(collation.map(|collation| collation.name.to_string())),
+        );
         // COLUMN_TYPE
-        column_value_map.insert("COLUMN_TYPE".to_ident(), ScalarValue::Utf8(None));
+        column_value_map.insert(
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_TYPE.to_ident(),
+            ScalarValue::Utf8(Some(column_type)),
+        );
         // COLUMN_KEY
         column_value_map.insert("COLUMN_KEY".to_ident(), ScalarValue::Utf8(Some(column_key)));
         // EXTRA
         column_value_map.insert(
-            "EXTRA".to_ident(),
-            ScalarValue::Utf8(Some("aaa".to_string())),
+            meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_EXTRA.to_ident(),
+            ScalarValue::Utf8(Some(extra.to_string())),
         );
         // PRIVILEGES
         column_value_map.insert("PRIVILEGES".to_ident(), ScalarValue::Utf8(None));
@@ -686,6 +900,7 @@ pub fn read_all_table(
     let schema_table_columns = read_information_schema_columns(global_context.clone()).unwrap();
     let schema_table_constraints =
         read_information_schema_statistics(global_context.clone()).unwrap();
+    let schema_table_foreign_keys = read_information_schema_foreign_keys(global_context.clone())?;
 
     let mut all_schema: HashMap<ObjectName, TableDef> = HashMap::new();
 
@@ -706,9 +921,15 @@ pub fn read_all_table(
             }
         }
 
+        let table_foreign_keys = schema_table_foreign_keys
+            .get(&full_table_name)
+            .cloned()
+            .unwrap_or_default();
+
         let mut table_def = TableDef::new();
         table_def.with_column(table_column);
         table_def.with_constraints(table_constraints);
+        table_def.with_foreign_keys(table_foreign_keys);
         table_def.with_option(table_option);
 
         all_schema.insert(full_table_name.clone(), table_def);
@@ -746,6 +967,12 @@ pub fn read_information_schema_tables(
     let projection_index_of_column_store_id = projection_schema
         .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_COLUMN_MAX_STORE_ID)
         .unwrap();
+    let column_index_of_create_options = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_CREATE_OPTIONS)
+        .unwrap();
+    let column_index_of_table_id = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_ID)
+        .unwrap();
 
     let mut table_sql_options: HashMap<ObjectName, TableOptionDef> = HashMap::new();
     loop {
@@ -763,11 +990,19 @@ pub fn read_information_schema_tables(
                     let column_store_id_row: &Int64Array = as_primitive_array(
                         record_batch.column(projection_index_of_column_store_id),
                     );
+                    let create_options_row: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_create_options));
+                    let table_id_row: &Int64Array =
+                        as_primitive_array(record_batch.column(column_index_of_table_id));
 
                     for row_index in 0..record_batch.num_rows() {
                         let schema_name = db_name_row.value(row_index).to_string();
                         let table_name = table_name_row.value(row_index).to_string();
                         let table_type = table_type_row.value(row_index).to_string();
+                        // the views are read from information_schema.views, they have no columns to store
+                        if table_type == meta_const::VALUE_OF_TABLE_OPTION_TABLE_TYPE_VIEW {
+                            continue;
+                        }
                         let engine = engine_row.value(row_index).to_string();
                         let column_store_id = column_store_id_row.value(row_index);
 
@@ -785,6 +1020,14 @@ pub fn read_information_schema_tables(
                         table_option.with_table_type(table_type.as_str());
                         table_option.with_column_max_store_id(column_store_id);
                         table_option.with_engine(engine.as_str());
+                        // tables created before the create_options column was added have no value
+                        if !create_options_row.is_null(row_index) {
+                            table_option.load_create_options(create_options_row.value(row_index));
+                        }
+                        // tables created before the table ids are keyed by their full table names
+                        if !table_id_row.is_null(row_index) {
+                            table_option.with_table_id(table_id_row.value(row_index) as u64);
+                        }
 
                         table_sql_options
                             .entry(full_table_name.clone())
@@ -800,6 +1043,150 @@ pub fn read_information_schema_tables(
     Ok(table_sql_options.clone())
 }
 
+/// The row of the view in information_schema.views, its row in information_schema.tables is saved on its own
+pub fn add_information_schema_views(
+    global_context: Arc<Mutex<GlobalContext>>,
+    view_def: &ViewDef,
+    definer: &str,
+) -> MysqlResult<u64> {
+    let table_def = information_schema::views(global_context.clone());
+
+    let mut column_name_list = vec![];
+    for column_def in table_def.get_columns() {
+        column_name_list.push(column_def.sql_column.name.to_string());
+    }
+
+    let view_columns = match serde_json::to_string(&view_def.column_name_list) {
+        Ok(view_columns) => view_columns,
+        Err(error) => {
+            return Err(MysqlError::new_global_error(
+                meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR,
+                format!("Unknown error. The columns of the view are broken, view_name: {}, error: {}", view_def.full_view_name, error).as_str(),
+            ));
+        }
+    };
+
+    let mut column_value_map = HashMap::new();
+    column_value_map.insert(
+        meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_CATALOG.to_ident(),
+        ScalarValue::Utf8(Some(view_def.catalog_name.clone())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA.to_ident(),
+        ScalarValue::Utf8(Some(view_def.schema_name.clone())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME.to_ident(),
+        ScalarValue::Utf8(Some(view_def.view_name.clone())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_DEFINITION.to_ident(),
+        ScalarValue::Utf8(Some(view_def.definition.clone())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_CHECK_OPTION.to_ident(),
+        ScalarValue::Utf8(Some(meta_const::CHECK_OPTION_OF_VIEW.to_string())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_IS_UPDATABLE.to_ident(),
+        ScalarValue::Utf8(Some(meta_const::IS_UPDATABLE_OF_VIEW.to_string())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_DEFINER.to_ident(),
+        ScalarValue::Utf8(Some(definer.to_string())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_SECURITY_TYPE.to_ident(),
+        ScalarValue::Utf8(Some(meta_const::SECURITY_TYPE_OF_VIEW.to_string())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_CHARACTER_SET_CLIENT.to_ident(),
+        ScalarValue::Utf8(Some(meta_const::VALUE_OF_SCHEMA_OPTION_DEFAULT_CHARACTER_SET_NAME.to_string())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_COLLATION_CONNECTION.to_ident(),
+        ScalarValue::Utf8(Some(meta_const::VALUE_OF_SCHEMA_OPTION_DEFAULT_COLLATION_NAME.to_string())),
+    );
+    column_value_map.insert(
+        meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_COLUMNS.to_ident(),
+        ScalarValue::Utf8(Some(view_columns)),
+    );
+
+    let insert = PhysicalPlanInsert::new(global_context.clone());
+    insert.execute(table_def, column_name_list, vec![], vec![column_value_map])
+}
+
+pub fn read_information_schema_views(
+    global_context: Arc<Mutex<GlobalContext>>,
+) -> MysqlResult<HashMap<ObjectName, ViewDef>> {
+    let table_def = information_schema::views(global_context.clone());
+    let engine = engine_util::TableEngineFactory::try_new_with_table(
+        global_context.clone(),
+        table_def.clone(),
+    )?;
+    let mut table_iterator = engine.table_iterator(None, &[]);
+
+    let projection_schema = table_def.to_schema();
+    let column_index_of_table_schema = projection_schema
+        .index_of(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA)
+        .unwrap();
+    let column_index_of_table_name = projection_schema
+        .index_of(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_NAME)
+        .unwrap();
+    let column_index_of_view_definition = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_DEFINITION)
+        .unwrap();
+    let column_index_of_view_columns = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_COLUMNS)
+        .unwrap();
+
+    let mut view_map: HashMap<ObjectName, ViewDef> = HashMap::new();
+    loop {
+        match table_iterator.next() {
+            Some(item) => match item {
+                Ok(record_batch) => {
+                    let column_of_table_schema: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_table_schema));
+                    let column_of_table_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_table_name));
+                    let column_of_view_definition: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_view_definition));
+                    let column_of_view_columns: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_view_columns));
+
+                    for row_index in 0..record_batch.num_rows() {
+                        let column_name_list: Vec<String> = match serde_json::from_str(column_of_view_columns.value(row_index)) {
+                            Ok(column_name_list) => column_name_list,
+                            Err(error) => {
+                                return Err(MysqlError::new_global_error(
+                                    meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR,
+                                    format!(
+                                        "Unknown error. The columns of the view are broken, view_name: {}, error: {}",
+                                        column_of_table_name.value(row_index),
+                                        error,
+                                    ).as_str(),
+                                ));
+                            }
+                        };
+                        let view_def = ViewDef::new(
+                            meta_const::CATALOG_NAME,
+                            column_of_table_schema.value(row_index),
+                            column_of_table_name.value(row_index),
+                            column_of_view_definition.value(row_index),
+                            column_name_list,
+                        );
+                        view_map.insert(view_def.full_view_name.clone(), view_def);
+                    }
+                }
+                Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+            },
+            None => break,
+        }
+    }
+
+    Ok(view_map)
+}
+
 pub fn read_information_schema_schemata(
     global_context: Arc<Mutex<GlobalContext>>,
 ) -> MysqlResult<HashMap<ObjectName, SchemaDef>> {
@@ -974,7 +1361,7 @@ pub fn read_information_schema_statistics(
                     quote_style: None,
                 }),
                 columns,
-                is_primary: true,
+                is_primary: index_name == meta_const::NAME_OF_PRIMARY,
             };
             table_constraints.push(table_constraint);
         }
@@ -987,6 +1374,179 @@ pub fn read_information_schema_statistics(
     Ok(schema_table_constraint.clone())
 }
 
+/// The foreign keys of the tables, the rules in information_schema.referential_constraints
+/// and the columns with the referenced columns in information_schema.key_column_usage
+pub fn read_information_schema_foreign_keys(
+    global_context: Arc<Mutex<GlobalContext>>,
+) -> MysqlResult<HashMap<ObjectName, Vec<ForeignKeyDef>>> {
+    // the columns of the foreign keys by their schemas, tables and names, with their ordinal positions
+    let mut foreign_key_columns: HashMap<(String, String, String), Vec<(i64, String, String)>> = HashMap::new();
+
+    let table_def = information_schema::key_column_usage(global_context.clone());
+    let engine = engine_util::TableEngineFactory::try_new_with_table(
+        global_context.clone(),
+        table_def.clone(),
+    )?;
+    let mut table_iterator = engine.table_iterator(None, &[]);
+
+    let projection_schema = table_def.to_schema();
+    let column_index_of_table_schema = projection_schema
+        .index_of(meta_const::COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA)
+        .unwrap();
+    let column_index_of_table_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_TABLE_NAME)
+        .unwrap();
+    let column_index_of_constraint_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_CONSTRAINT_NAME)
+        .unwrap();
+    let column_index_of_column_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_COLUMN_NAME)
+        .unwrap();
+    let column_index_of_ordinal_position = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_ORDINAL_POSITION)
+        .unwrap();
+    let column_index_of_referenced_column_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_REFERENCED_COLUMN_NAME)
+        .unwrap();
+
+    loop {
+        match table_iterator.next() {
+            Some(item) => match item {
+                Ok(record_batch) => {
+                    let column_of_table_schema: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_table_schema));
+                    let column_of_table_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_table_name));
+                    let column_of_constraint_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_constraint_name));
+                    let column_of_column_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_column_name));
+                    let column_of_ordinal_position: &Int64Array =
+                        as_primitive_array(record_batch.column(column_index_of_ordinal_position));
+                    let column_of_referenced_column_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_referenced_column_name));
+
+                    for row_index in 0..record_batch.num_rows() {
+                        // the columns of the primary keys and the unique keys refer to no column
+                        if column_of_referenced_column_name.is_null(row_index) {
+                            continue;
+                        }
+                        foreign_key_columns
+                            .entry((
+                                column_of_table_schema.value(row_index).to_string(),
+                                column_of_table_name.value(row_index).to_string(),
+                                column_of_constraint_name.value(row_index).to_string(),
+                            ))
+                            .or_insert(Vec::new())
+                            .push((
+                                column_of_ordinal_position.value(row_index),
+                                column_of_column_name.value(row_index).to_string(),
+                                column_of_referenced_column_name.value(row_index).to_string(),
+                            ));
+                    }
+                }
+                Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+            },
+            None => break,
+        }
+    }
+
+    let table_def = information_schema::referential_constraints(global_context.clone());
+    let engine = engine_util::TableEngineFactory::try_new_with_table(
+        global_context.clone(),
+        table_def.clone(),
+    )?;
+    let mut table_iterator = engine.table_iterator(None, &[]);
+
+    let projection_schema = table_def.to_schema();
+    let column_index_of_constraint_schema = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_CONSTRAINT_SCHEMA)
+        .unwrap();
+    let column_index_of_constraint_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_CONSTRAINT_NAME)
+        .unwrap();
+    let column_index_of_unique_constraint_schema = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_UNIQUE_CONSTRAINT_SCHEMA)
+        .unwrap();
+    let column_index_of_update_rule = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_UPDATE_RULE)
+        .unwrap();
+    let column_index_of_delete_rule = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_DELETE_RULE)
+        .unwrap();
+    let column_index_of_table_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_TABLE_NAME)
+        .unwrap();
+    let column_index_of_referenced_table_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_REFERENCED_TABLE_NAME)
+        .unwrap();
+
+    let mut schema_table_foreign_keys: HashMap<ObjectName, Vec<ForeignKeyDef>> = HashMap::new();
+    loop {
+        match table_iterator.next() {
+            Some(item) => match item {
+                Ok(record_batch) => {
+                    let column_of_constraint_schema: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_constraint_schema));
+                    let column_of_constraint_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_constraint_name));
+                    let column_of_unique_constraint_schema: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_unique_constraint_schema));
+                    let column_of_update_rule: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_update_rule));
+                    let column_of_delete_rule: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_delete_rule));
+                    let column_of_table_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_table_name));
+                    let column_of_referenced_table_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_referenced_table_name));
+
+                    for row_index in 0..record_batch.num_rows() {
+                        let schema_name = column_of_constraint_schema.value(row_index).to_string();
+                        let table_name = column_of_table_name.value(row_index).to_string();
+                        let constraint_name = column_of_constraint_name.value(row_index).to_string();
+
+                        let mut columns = foreign_key_columns
+                            .remove(&(schema_name.clone(), table_name.clone(), constraint_name.clone()))
+                            .unwrap_or_default();
+                        columns.sort_by(|a, b| a.0.cmp(&b.0));
+
+                        let foreign_key = ForeignKeyDef {
+                            constraint_name,
+                            column_name_list: columns.iter().map(|(_, column_name, _)| Ident::new(column_name)).collect(),
+                            referenced_table_name: meta_util::create_full_table_name(
+                                meta_const::CATALOG_NAME,
+                                column_of_unique_constraint_schema.value(row_index),
+                                column_of_referenced_table_name.value(row_index),
+                            ),
+                            referenced_column_name_list: columns.iter().map(|(_, _, column_name)| Ident::new(column_name)).collect(),
+                            on_delete: ReferentialAction::from_rule(column_of_delete_rule.value(row_index)).unwrap_or(ReferentialAction::NoAction),
+                            on_update: ReferentialAction::from_rule(column_of_update_rule.value(row_index)).unwrap_or(ReferentialAction::NoAction),
+                        };
+
+                        let full_table_name = meta_util::create_full_table_name(
+                            meta_const::CATALOG_NAME,
+                            schema_name.as_str(),
+                            table_name.as_str(),
+                        );
+                        schema_table_foreign_keys
+                            .entry(full_table_name)
+                            .or_insert(Vec::new())
+                            .push(foreign_key);
+                    }
+                }
+                Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+            },
+            None => break,
+        }
+    }
+
+    for foreign_keys in schema_table_foreign_keys.values_mut() {
+        foreign_keys.sort_by(|a, b| a.constraint_name.cmp(&b.constraint_name));
+    }
+    Ok(schema_table_foreign_keys)
+}
+
 pub fn read_information_schema_columns(
     global_context: Arc<Mutex<GlobalContext>>,
 ) -> MysqlResult<HashMap<ObjectName, Vec<SparrowColumnDef>>> {
@@ -1033,6 +1593,18 @@ pub fn read_information_schema_columns(
     let column_index_of_numeric_scale = projection_schema
         .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_NUMERIC_SCALE)
         .unwrap();
+    let column_index_of_extra = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_EXTRA)
+        .unwrap();
+    let column_index_of_column_default = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_DEFAULT)
+        .unwrap();
+    let column_index_of_column_type = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_TYPE)
+        .unwrap();
+    let column_index_of_collation_name = projection_schema
+        .index_of(meta_const::COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLLATION_NAME)
+        .unwrap();
 
     let mut schema_column: HashMap<ObjectName, Vec<SparrowColumnDef>> = HashMap::new();
     loop {
@@ -1060,6 +1632,14 @@ pub fn read_information_schema_columns(
                         as_primitive_array(record_batch.column(column_index_of_numeric_precision));
                     let column_of_numeric_scale: &Int64Array =
                         as_primitive_array(record_batch.column(column_index_of_numeric_scale));
+                    let column_of_extra: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_extra));
+                    let column_of_column_default: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_column_default));
+                    let column_of_column_type: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_column_type));
+                    let column_of_collation_name: &StringArray =
+                        as_string_array(record_batch.column(column_index_of_collation_name));
 
                     for row_index in 0..record_batch.num_rows() {
                         let db_name = column_of_db_name.value(row_index).to_string();
@@ -1073,6 +1653,7 @@ pub fn read_information_schema_columns(
                             column_of_character_maximum_length.value(row_index);
                         let numeric_precision = column_of_numeric_precision.value(row_index);
                         let numeric_scale = column_of_numeric_scale.value(row_index);
+                        let extra = column_of_extra.value(row_index).to_string();
 
                         let full_table_name = meta_util::create_full_table_name(
                             meta_const::CATALOG_NAME,
@@ -1081,14 +1662,29 @@ pub fn read_information_schema_columns(
                         );
 
                         let sql_data_type =
-                            meta_util::create_sql_data_type(data_type.as_str()).unwrap();
+                            meta_util::create_sql_data_type(data_type.as_str(), Some(numeric_precision), Some(numeric_scale)).unwrap();
                         let nullable = meta_util::text_to_null(is_nullable.as_str()).unwrap();
 
-                        let sql_column = meta_util::create_sql_column(
+                        let mut sql_column = meta_util::create_sql_column(
                             column_name.as_str(),
                             sql_data_type,
                             nullable,
                         );
+                        if extra == meta_const::EXTRA_OF_AUTO_INCREMENT {
+                            sql_column.options.push(meta_util::create_auto_increment_option());
+                        }
+                        if !column_of_column_default.is_null(row_index) {
+                            let column_default = column_of_column_default.value(row_index);
+                            sql_column.options.push(meta_util::create_default_option(column_default)?);
+                        }
+                        // the columns saved before the COLUMN_TYPE have no display width
+                        if !column_of_column_type.is_null(row_index) {
+                            meta_util::load_column_type(&mut sql_column, column_of_column_type.value(row_index));
+                        }
+                        if !column_of_collation_name.is_null(row_index) {
+                            let collation_name = column_of_collation_name.value(row_index);
+                            sql_column.collation = Some(ObjectName(vec![Ident::new(collation_name)]));
+                        }
                         let sparrow_column = meta_util::create_sparrow_column(
                             store_id,
                             ordinal_position,
@@ -1110,6 +1706,89 @@ pub fn read_information_schema_columns(
     Ok(schema_column.clone())
 }
 
+/// The rows of a table of the mysql schema, the values by the column names with the rowid
+pub fn read_def_mysql_rows(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_def: TableDef,
+) -> MysqlResult<Vec<HashMap<String, Option<String>>>> {
+    let engine = engine_util::TableEngineFactory::try_new_with_table(
+        global_context.clone(),
+        table_def.clone(),
+    )?;
+    let mut table_iterator = engine.table_iterator(None, &[]);
+
+    let projection_schema = table_def.to_schema();
+
+    let mut rows = vec![];
+    loop {
+        match table_iterator.next() {
+            Some(item) => match item {
+                Ok(record_batch) => {
+                    for row_index in 0..record_batch.num_rows() {
+                        let mut row = HashMap::new();
+                        for (column_index, field) in projection_schema.fields().iter().enumerate() {
+                            let column: &StringArray = as_string_array(record_batch.column(column_index));
+                            let value = if column.is_null(row_index) {
+                                None
+                            } else {
+                                Some(column.value(row_index).to_string())
+                            };
+                            row.insert(field.name().to_string(), value);
+                        }
+                        rows.push(row);
+                    }
+                }
+                Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+            },
+            None => break,
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Add a row to a table of the mysql schema, the columns without a value are null
+pub fn add_def_mysql_row(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_def: TableDef,
+    row: &HashMap<String, Option<String>>,
+) -> MysqlResult<u64> {
+    let mut column_name_list = vec![];
+    let mut column_value_map = HashMap::new();
+    for sql_column in table_def.column.sql_column_list.clone() {
+        let column_name = sql_column.name.to_string();
+        let value = row.get(&column_name).cloned().flatten();
+        column_value_map.insert(column_name.to_ident(), ScalarValue::Utf8(value));
+        column_name_list.push(column_name);
+    }
+
+    let insert = PhysicalPlanInsert::new(global_context.clone());
+    insert.execute(table_def, column_name_list, vec![], vec![column_value_map])
+}
+
+/// Delete the row of a table of the mysql schema by its rowid, the keys of all its columns are deleted
+pub fn delete_def_mysql_row(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_def: TableDef,
+    rowid: &str,
+) -> MysqlResult<()> {
+    let store_engine = engine_util::StoreEngineFactory::try_new_with_table(
+        global_context.clone(),
+        table_def.clone(),
+    )?;
+
+    store_engine.delete_key(dbkey::create_record_rowid(&table_def.option, rowid))?;
+    for sql_column in table_def.get_table_column().sql_column_list {
+        let sparrow_column = table_def
+            .get_table_column()
+            .get_sparrow_column(sql_column.name)
+            .unwrap();
+        store_engine.delete_key(dbkey::create_column_key(&table_def.option, sparrow_column.store_id, rowid))?;
+    }
+
+    Ok(())
+}
+
 pub fn read_performance_schema_global_variables(
     global_context: Arc<Mutex<GlobalContext>>,
 ) -> MysqlResult<HashMap<String, String>> {
@@ -1158,7 +1837,22 @@ pub fn read_performance_schema_global_variables(
     Ok(variable_map.clone())
 }
 
+/// Add root with the initial password of the config, or a random one written to the log once
 pub fn add_def_mysql_users(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<u64> {
+    let initial_root_password = global_context.lock().unwrap().my_config.server.initial_root_password.clone();
+    let password = match initial_root_password {
+        Some(password) => password,
+        None => {
+            let password = auth::create_password();
+            log::warn!("A temporary password is generated for root@%: {}", password);
+            password
+        }
+    };
+    add_def_mysql_user(global_context, "root", auth::native_password_hash(password.as_str()).as_str())
+}
+
+/// Add a user of all the privileges, the user with an empty name is the anonymous user
+pub fn add_def_mysql_user(global_context: Arc<Mutex<GlobalContext>>, user: &str, authentication_string: &str) -> MysqlResult<u64> {
     let table_of_def_mysql_users = mysql::users(global_context.clone());
 
     let mut column_name_list = vec![];
@@ -1173,7 +1867,7 @@ pub fn add_def_mysql_users(global_context: Arc<Mutex<GlobalContext>>) -> MysqlRe
     // User
     column_value_map.insert(
         "User".to_ident(),
-        ScalarValue::Utf8(Some("root".to_string())),
+        ScalarValue::Utf8(Some(user.to_string())),
     );
     // Select_priv
     column_value_map.insert(
@@ -1347,9 +2041,7 @@ pub fn add_def_mysql_users(global_context: Arc<Mutex<GlobalContext>>) -> MysqlRe
     // authentication_string
     column_value_map.insert(
         "authentication_string".to_ident(),
-        ScalarValue::Utf8(Some(
-            "*6BB4837EB74329105EE4568DDA7DC67ED2CA2AD9".to_string(),
-        )),
+        ScalarValue::Utf8(Some(authentication_string.to_string())),
     );
     // password_expired
     column_value_map.insert(
@@ -8,6 +8,8 @@ pub const SCHEMA_NAME_OF_DEF_MYSQL: &str = "mysql";
 pub const FULL_SCHEMA_NAME_OF_DEF_MYSQL: &str = "def.mysql";
 pub const SCHEMA_NAME_OF_DEF_PERFORMANCE_SCHEMA: &str = "performance_schema";
 pub const FULL_SCHEMA_NAME_OF_DEF_PERFORMANCE_SCHEMA: &str = "def.performance_schema";
+/// The schema of the rows of the common table expressions while their query runs, it is not saved in information_schema
+pub const SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION: &str = "#cte";
 
 // table name of information_schema
 pub const TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES: &str = "tables";
@@ -28,10 +30,18 @@ pub const TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLE_CONSTRAINTS: &str = "table_
 pub const FULL_TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_TABLE_CONSTRAINTS: &str = "def.information_schema.table_constraints";
 pub const TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS: &str = "referential_constraints";
 pub const FULL_TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS: &str = "def.information_schema.referential_constraints";
+pub const TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS: &str = "views";
+pub const FULL_TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS: &str = "def.information_schema.views";
+pub const TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_PROCESSLIST: &str = "processlist";
+pub const FULL_TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_PROCESSLIST: &str = "def.information_schema.processlist";
+pub const TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_SESSION_CONNECT_ATTRS: &str = "session_connect_attrs";
+pub const FULL_TABLE_NAME_OF_DEF_INFORMATION_SCHEMA_SESSION_CONNECT_ATTRS: &str = "def.information_schema.session_connect_attrs";
 
 // table name of mysql
 pub const TABLE_NAME_OF_DEF_MYSQL_USERS: &str = "user";
 pub const FULL_TABLE_NAME_OF_DEF_MYSQL_USERS: &str = "def.mysql.user";
+pub const TABLE_NAME_OF_DEF_MYSQL_DB: &str = "db";
+pub const FULL_TABLE_NAME_OF_DEF_MYSQL_DB: &str = "def.mysql.db";
 // table name of performance_schema
 pub const TABLE_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES: &str = "global_variables";
 pub const FULL_TABLE_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES: &str = "def.performance_schema.global_variables";
@@ -39,12 +49,38 @@ pub const FULL_TABLE_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES: &str = "de
 pub const NAME_OF_PRIMARY: &str = "PRIMARY";
 pub const CONSTRAINT_TYPE_PRIMARY: &str = "PRIMARY KEY";
 pub const CONSTRAINT_TYPE_UNIQUE: &str = "UNIQUE";
+pub const CONSTRAINT_TYPE_FOREIGN_KEY: &str = "FOREIGN KEY";
+/// The foreign key without a name is named as `<table>_ibfk_<n>` of mysql
+pub const NAME_INFIX_OF_FOREIGN_KEY: &str = "_ibfk_";
+/// The MATCH of the foreign keys in information_schema.referential_constraints, the only one of mysql
+pub const MATCH_OPTION_OF_FOREIGN_KEY: &str = "NONE";
+/// The depth of the cascaded deletes and updates of the foreign keys, as the one of mysql
+pub const MAX_DEPTH_OF_FOREIGN_KEY_CASCADE: usize = 15;
+/// The level of the index of the referencing columns of a foreign key, named as the foreign key. The rows may
+/// have the same values, its keys end with the rowid
+pub const INDEX_LEVEL_OF_FOREIGN_KEY: i32 = 3;
+/// The views have no WITH CHECK OPTION, are read only and read their tables with the privileges of their definers
+pub const CHECK_OPTION_OF_VIEW: &str = "NONE";
+pub const IS_UPDATABLE_OF_VIEW: &str = "NO";
+pub const SECURITY_TYPE_OF_VIEW: &str = "DEFINER";
+pub const CONSTRAINT_ENFORCED_YES: &str = "YES";
 pub const COLUMN_KEY_OF_PRIMARY: &str = "PRI";
 pub const COLUMN_KEY_OF_UNIQUE: &str = "UNI";
+pub const EXTRA_OF_AUTO_INCREMENT: &str = "auto_increment";
+pub const COLUMN_TYPE_OF_ZEROFILL: &str = "zerofill";
+/// The display width of INT ZEROFILL without a width, as INT(10) ZEROFILL of mysql
+pub const DISPLAY_WIDTH_OF_ZEROFILL_INT: u64 = 10;
+/// The metadata of the result field with the display width of a ZEROFILL column
+pub const FIELD_METADATA_ZEROFILL_WIDTH: &str = "zerofill_width";
+/// The metadata of the result field with the data type of a DATE, TIME, DATETIME or TIMESTAMP column
+pub const FIELD_METADATA_DATA_TYPE: &str = "data_type";
+/// The metadata of the result field with the collation of a text column which is not the default collation
+pub const FIELD_METADATA_COLLATION: &str = "collation";
 
 pub const MYSQL_DATA_TYPE_CHAR: &str = "char";
 pub const MYSQL_DATA_TYPE_VARCHAR: &str = "varchar";
 pub const MYSQL_DATA_TYPE_TEXT: &str = "text";
+pub const MYSQL_DATA_TYPE_TINYINT: &str = "tinyint";
 pub const MYSQL_DATA_TYPE_MEDIUMINT: &str = "mediumint";
 pub const MYSQL_DATA_TYPE_SMALLINT: &str = "smallint";
 pub const MYSQL_DATA_TYPE_INT: &str = "int";
@@ -53,10 +89,22 @@ pub const MYSQL_DATA_TYPE_ENUM: &str = "enum";
 pub const MYSQL_DATA_TYPE_FLOAT: &str = "float";
 pub const MYSQL_DATA_TYPE_DOUBLE: &str = "double";
 pub const MYSQL_DATA_TYPE_DECIMAL: &str = "decimal";
+pub const MYSQL_DATA_TYPE_DATE: &str = "date";
+pub const MYSQL_DATA_TYPE_TIME: &str = "time";
+pub const MYSQL_DATA_TYPE_DATETIME: &str = "datetime";
+pub const MYSQL_DATA_TYPE_TIMESTAMP: &str = "timestamp";
+pub const MYSQL_DATA_TYPE_BINARY: &str = "binary";
+pub const MYSQL_DATA_TYPE_VARBINARY: &str = "varbinary";
+pub const MYSQL_DATA_TYPE_BLOB: &str = "blob";
+pub const MYSQL_DATA_TYPE_LONGBLOB: &str = "longblob";
+/// The length of the BLOB, the BLOB of the length is a TINYBLOB, a BLOB or a MEDIUMBLOB in mysql
+pub const LENGTH_OF_BLOB: u64 = 65535;
 
 pub const MYSQL_ERROR_CODE_UNKNOWN_ERROR: u16 = 1105;
 
 pub const COLUMN_ROWID: &str = "rowid";
+/// The name the clients select the rowid with, it does not clash with the columns of the table
+pub const COLUMN_ROWID_ALIAS: &str = "_rowid";
 pub const COLUMN_INFORMATION_SCHEMA_TABLE_CATALOG: &str = "table_catalog";
 pub const COLUMN_INFORMATION_SCHEMA_TABLE_SCHEMA: &str = "table_schema";
 pub const COLUMN_INFORMATION_SCHEMA_TABLE_NAME: &str = "table_name";
@@ -83,6 +131,10 @@ pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_AUTO_INCREMENT: &str = "a
 pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_AUTO_INCREMENT: &str = "def.information_schema.tables.auto_increment";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_COLUMN_MAX_STORE_ID: &str = "column_max_store_id";
 pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_COLUMN_MAX_STORE_ID: &str = "def.information_schema.tables.column_max_store_id";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_CREATE_OPTIONS: &str = "create_options";
+pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_CREATE_OPTIONS: &str = "def.information_schema.tables.create_options";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_ID: &str = "table_id";
+pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_TABLES_TABLE_ID: &str = "def.information_schema.tables.table_id";
 // column of def.information_schema.statistics
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_STATISTICS_TABLE_CATALOG: &str = "table_catalog";
 pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_STATISTICS_TABLE_CATALOG: &str = "def.information_schema.statistics.table_catalog";
@@ -102,6 +154,29 @@ pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_STATISTICS_SEQ_IN_INDEX: &s
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_CONSTRAINT_CATALOG: &str = "constraint_catalog";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_CONSTRAINT_SCHEMA: &str = "constraint_schema";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_CONSTRAINT_NAME: &str = "constraint_name";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_TABLE_NAME: &str = "table_name";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_COLUMN_NAME: &str = "column_name";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_ORDINAL_POSITION: &str = "ordinal_position";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_REFERENCED_TABLE_SCHEMA: &str = "referenced_table_schema";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_REFERENCED_TABLE_NAME: &str = "referenced_table_name";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_KEY_COLUMN_USAGE_REFERENCED_COLUMN_NAME: &str = "referenced_column_name";
+// column of def.information_schema.referential_constraints
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_CONSTRAINT_SCHEMA: &str = "constraint_schema";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_CONSTRAINT_NAME: &str = "constraint_name";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_UNIQUE_CONSTRAINT_SCHEMA: &str = "unique_constraint_schema";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_UPDATE_RULE: &str = "update_rule";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_DELETE_RULE: &str = "delete_rule";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_TABLE_NAME: &str = "table_name";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_REFERENTIAL_CONSTRAINTS_REFERENCED_TABLE_NAME: &str = "referenced_table_name";
+// column of def.information_schema.views
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_DEFINITION: &str = "view_definition";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_CHECK_OPTION: &str = "check_option";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_IS_UPDATABLE: &str = "is_updatable";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_DEFINER: &str = "definer";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_SECURITY_TYPE: &str = "security_type";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_CHARACTER_SET_CLIENT: &str = "character_set_client";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_COLLATION_CONNECTION: &str = "collation_connection";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_VIEWS_VIEW_COLUMNS: &str = "view_columns";
 // column of def.information_schema.schemata
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_SCHEMATA_CATALOG_NAME: &str = "catalog_name";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_SCHEMATA_SCHEMA_NAME: &str = "schema_name";
@@ -122,6 +197,7 @@ pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_STORE_ID: &str = "store_
 pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_STORE_ID: &str = "def.information_schema.columns.store_id";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_ORDINAL_POSITION: &str = "ordinal_position";
 pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_ORDINAL_POSITION: &str = "def.information_schema.columns.ordinal_position";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_DEFAULT: &str = "COLUMN_DEFAULT";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_IS_NULLABLE: &str = "is_nullable";
 pub const FULL_COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_IS_NULLABLE: &str = "def.information_schema.columns.is_nullable";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_DATA_TYPE: &str = "data_type";
@@ -130,6 +206,18 @@ pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_CHARACTER_MAXIMUM_LENGTH
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_CHARACTER_OCTET_LENGTH: &str = "character_octed_length";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_NUMERIC_PRECISION: &str = "numeric_precision";
 pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_NUMERIC_SCALE: &str = "numeric_scale";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_EXTRA: &str = "EXTRA";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLUMN_TYPE: &str = "COLUMN_TYPE";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_CHARACTER_SET_NAME: &str = "CHARACTER_SET_NAME";
+pub const COLUMN_NAME_OF_DEF_INFORMATION_SCHEMA_COLUMNS_COLLATION_NAME: &str = "COLLATION_NAME";
+// column of def.mysql.user
+pub const COLUMN_NAME_OF_DEF_MYSQL_USERS_HOST: &str = "Host";
+pub const COLUMN_NAME_OF_DEF_MYSQL_USERS_USER: &str = "User";
+pub const COLUMN_NAME_OF_DEF_MYSQL_USERS_AUTHENTICATION_STRING: &str = "authentication_string";
+// column of def.mysql.db
+pub const COLUMN_NAME_OF_DEF_MYSQL_DB_HOST: &str = "Host";
+pub const COLUMN_NAME_OF_DEF_MYSQL_DB_DB: &str = "Db";
+pub const COLUMN_NAME_OF_DEF_MYSQL_DB_USER: &str = "User";
 // column of def.performance_schema.global_variables
 pub const COLUMN_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES_VARIABLE_NAME: &str = "variable_name";
 pub const FULL_COLUMN_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES_VARIABLE_NAME: &str = "def.performance_schema.global_variables.variable_name";
@@ -139,6 +227,8 @@ pub const FULL_COLUMN_NAME_OF_DEF_PERFORMANCE_SCHEMA_GLOBAL_VARIABLES_VARIABLE_V
 // schema option
 pub const NAME_OF_SCHEMA_OPTION_DEFAULT_CHARACTER_SET_NAME: &str = "default_character_set_name";
 pub const NAME_OF_SCHEMA_OPTION_DEFAULT_COLLATION_NAME: &str = "default_collation_name";
+pub const VALUE_OF_SCHEMA_OPTION_DEFAULT_CHARACTER_SET_NAME: &str = "utf8mb4";
+pub const VALUE_OF_SCHEMA_OPTION_DEFAULT_COLLATION_NAME: &str = "utf8mb4_0900_ai_ci";
 
 // table option
 pub const NAME_OF_TABLE_OPTION_TABLE_TYPE: &str = "TABLE_TYPE";
@@ -147,6 +237,20 @@ pub const VALUE_OF_TABLE_OPTION_TABLE_TYPE_SYSTEM_VIEW: &str = "SYSTEM VIEW";
 pub const VALUE_OF_TABLE_OPTION_TABLE_TYPE_VIEW: &str = "VIEW";
 pub const NAME_OF_TABLE_OPTION_COLUMN_MAX_STORE_ID: &str = "column_max_store_id";
 pub const NAME_OF_TABLE_OPTION_ENGINE: &str = "engine";
+/// The primary key is the identity of the row, no hidden rowid is generated
+pub const NAME_OF_TABLE_OPTION_WITHOUT_ROWID: &str = "without_rowid";
+/// The NULL values are written to the unique indexes, so IS NULL can seek the index
+pub const NAME_OF_TABLE_OPTION_INDEX_NULLS: &str = "index_nulls";
+/// The scan without an index returns the rows in the reverse rowid order
+pub const NAME_OF_TABLE_OPTION_REVERSE_SCAN: &str = "reverse_scan";
+/// The column values are compressed in the store with this algorithm
+pub const NAME_OF_TABLE_OPTION_COMPRESSION: &str = "compression";
+/// The column values shorter than this number of bytes are stored without compression
+pub const NAME_OF_TABLE_OPTION_COMPRESSION_THRESHOLD: &str = "compression_threshold";
+/// The default collation of the text columns of the table
+pub const NAME_OF_TABLE_OPTION_COLLATION: &str = "collation";
+pub const VALUE_OF_TABLE_OPTION_COMPRESSION_LZ4: &str = "lz4";
+pub const VALUE_OF_TABLE_OPTION_COMPRESSION_THRESHOLD: usize = 1024;
 pub const VALUE_OF_TABLE_OPTION_ENGINE_ROCKSDB: &str = "rocksdb";
 pub const VALUE_OF_TABLE_OPTION_ENGINE_SLED: &str = "sled";
 
@@ -158,5 +262,74 @@ pub const SHOW_VARIABLE_PRIVILEGES: &str = "PRIVILEGES";
 pub const SHOW_VARIABLE_ENGINES: &str = "ENGINES";
 pub const SHOW_VARIABLE_CHARSET: &str = "CHARSET";
 pub const SHOW_VARIABLE_COLLATION: &str = "COLLATION";
+/// SHOW PREPARED STATEMENTS, the prepared statements of the current session
+pub const SHOW_VARIABLE_PREPARED: &str = "PREPARED";
+/// SHOW CONFIG, the effective config of the server with the secrets redacted
+pub const SHOW_VARIABLE_CONFIG: &str = "CONFIG";
+/// SHOW PROFILE, the metrics of the last SELECT of the current session
+pub const SHOW_VARIABLE_PROFILE: &str = "PROFILE";
+
+// FUNCTION
+pub const FUNCTION_NAME_LIKE_ESCAPE: &str = "like_escape";
+/// The key by which a collation compares and sorts the strings, the COLLATE clauses are rewritten to it
+pub const FUNCTION_NAME_COLLATION_KEY: &str = "collation_key";
+/// REPLACE() of mysql, the calls are renamed before parsing, see `rewrite_replace_function`
+pub const FUNCTION_NAME_MYSQL_REPLACE: &str = "mysql_replace";
+pub const FUNCTION_NAME_LOCATE: &str = "locate";
+pub const FUNCTION_NAME_INSTR: &str = "instr";
+/// CAST(... AS DECIMAL(p,s)), one udf of each precision and scale named with the prefix
+pub const FUNCTION_NAME_CAST_DECIMAL: &str = "mysql_cast_decimal";
+/// DATE_ADD(), DATE_SUB() and TIMESTAMPDIFF() of mysql, the calls are rewritten before parsing, see `rewrite_date_functions`
+pub const FUNCTION_NAME_MYSQL_DATE_ADD: &str = "mysql_date_add";
+pub const FUNCTION_NAME_MYSQL_DATE_SUB: &str = "mysql_date_sub";
+pub const FUNCTION_NAME_MYSQL_TIMESTAMPDIFF: &str = "mysql_timestampdiff";
+pub const FUNCTION_NAME_DATEDIFF: &str = "datediff";
+pub const FUNCTION_NAME_MYSQL_NOW: &str = "mysql_now";
+pub const FUNCTION_NAME_MYSQL_CURRENT_DATE: &str = "mysql_current_date";
+pub const FUNCTION_NAME_MYSQL_CURRENT_TIME: &str = "mysql_current_time";
+pub const FUNCTION_NAME_LAST_INSERT_ID: &str = "last_insert_id";
+/// CONVERT_TZ() of mysql, the TIMESTAMP columns are converted with it to the time zone of the session
+pub const FUNCTION_NAME_CONVERT_TZ: &str = "convert_tz";
+
+// SYSTEM VARIABLE
+/// The state of the session read as the system variables
+pub const SYSTEM_VARIABLE_WARNING_COUNT: &str = "warning_count";
+pub const SYSTEM_VARIABLE_ERROR_COUNT: &str = "error_count";
+pub const SYSTEM_VARIABLE_LAST_INSERT_ID: &str = "last_insert_id";
+pub const SYSTEM_VARIABLE_IDENTITY: &str = "identity";
+pub const SYSTEM_VARIABLE_AUTOCOMMIT: &str = "autocommit";
+pub const SYSTEM_VARIABLE_MAX_ALLOWED_PACKET: &str = "max_allowed_packet";
+pub const SYSTEM_VARIABLE_SQL_MODE: &str = "sql_mode";
+pub const SYSTEM_VARIABLE_MAX_EXECUTION_TIME: &str = "max_execution_time";
+pub const SYSTEM_VARIABLE_TIME_ZONE: &str = "time_zone";
+pub const SYSTEM_VARIABLE_CHARACTER_SET_CLIENT: &str = "character_set_client";
+pub const SYSTEM_VARIABLE_CHARACTER_SET_CONNECTION: &str = "character_set_connection";
+pub const SYSTEM_VARIABLE_CHARACTER_SET_RESULTS: &str = "character_set_results";
+pub const SYSTEM_VARIABLE_WAIT_TIMEOUT: &str = "wait_timeout";
+pub const SYSTEM_VARIABLE_INTERACTIVE_TIMEOUT: &str = "interactive_timeout";
+/// The seconds a statement waits for the row locks of the other transactions
+pub const SYSTEM_VARIABLE_INNODB_LOCK_WAIT_TIMEOUT: &str = "innodb_lock_wait_timeout";
+/// The iterations a recursive common table expression may run before it is aborted
+pub const SYSTEM_VARIABLE_CTE_MAX_RECURSION_DEPTH: &str = "cte_max_recursion_depth";
+/// The character set of the client, the connection and the results together, as SET NAMES
+pub const SYSTEM_VARIABLE_CHARACTER_SET: &str = "character_set";
+/// The session variables whose changes are reported to the client in the OK packet of SET
+pub const TRACKED_SYSTEM_VARIABLES: &[&str] = &[
+    SYSTEM_VARIABLE_AUTOCOMMIT,
+    SYSTEM_VARIABLE_TIME_ZONE,
+    SYSTEM_VARIABLE_CHARACTER_SET_CLIENT,
+    SYSTEM_VARIABLE_CHARACTER_SET_CONNECTION,
+    SYSTEM_VARIABLE_CHARACTER_SET_RESULTS,
+];
+
+// SQL MODE
+pub const DEFAULT_SQL_MODE: &str = "ONLY_FULL_GROUP_BY,STRICT_TRANS_TABLES,NO_ZERO_IN_DATE,NO_ZERO_DATE,ERROR_FOR_DIVISION_BY_ZERO,NO_ENGINE_SUBSTITUTION";
+pub const SQL_MODE_STRICT_TRANS_TABLES: &str = "STRICT_TRANS_TABLES";
+pub const SQL_MODE_STRICT_ALL_TABLES: &str = "STRICT_ALL_TABLES";
+
+// SESSION VARIABLE
+/// The tenant of the session when the tenant column is configured, it is read only for the clients
+pub const SESSION_VARIABLE_TENANT_ID: &str = "tenant_id";
 
 pub const SIGN_MASK: u64 = 0x8000000000000000;
+pub const DECIMAL_SIGN_MASK: u128 = 1 << 127;
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use arrow::datatypes::{DataType, Schema, SchemaRef};
 use datafusion::error;
 use datafusion::logical_plan::{DFField, DFSchema};
+use serde_derive::{Deserialize, Serialize};
 use sqlparser::ast::{ColumnDef as SQLColumnDef, ColumnOption, SqlOption, TableConstraint, Value, ObjectName, Ident};
 
 use crate::meta::{meta_const, meta_util};
@@ -239,7 +240,16 @@ pub struct TableOptionDef {
     pub full_table_name: ObjectName,
     pub engine: String,
     pub column_max_store_id: i64,
+    /// The id of the table in the keys of its rows, 0 for the tables keyed by their full table names
+    pub table_id: u64,
     pub table_type: String,
+    pub without_rowid: bool,
+    pub index_nulls: bool,
+    pub reverse_scan: bool,
+    pub compression: Option<String>,
+    pub compression_threshold: usize,
+    /// The default collation of the text columns, None for the collation of the schema
+    pub collation: Option<String>,
 }
 
 impl Default for TableOptionDef {
@@ -251,7 +261,14 @@ impl Default for TableOptionDef {
             full_table_name: ObjectName(vec![]),
             engine: "".to_string(),
             column_max_store_id: 0,
+            table_id: 0,
             table_type: "".to_string(),
+            without_rowid: false,
+            index_nulls: false,
+            reverse_scan: false,
+            compression: None,
+            compression_threshold: meta_const::VALUE_OF_TABLE_OPTION_COMPRESSION_THRESHOLD,
+            collation: None,
         }
     }
 }
@@ -267,7 +284,14 @@ impl TableOptionDef {
             full_table_name,
             engine: "".to_string(),
             column_max_store_id: 0,
+            table_id: 0,
             table_type: "".to_string(),
+            without_rowid: false,
+            index_nulls: false,
+            reverse_scan: false,
+            compression: None,
+            compression_threshold: meta_const::VALUE_OF_TABLE_OPTION_COMPRESSION_THRESHOLD,
+            collation: None,
         }
     }
 }
@@ -280,10 +304,76 @@ impl TableOptionDef {
         table_options.push(sql_option);
         let sql_option = SqlOption { name: Ident { value: meta_const::NAME_OF_TABLE_OPTION_ENGINE.to_string(), quote_style: None }, value: Value::SingleQuotedString(self.engine.clone()) };
         table_options.push(sql_option);
+        if self.without_rowid {
+            let sql_option = SqlOption { name: Ident { value: meta_const::NAME_OF_TABLE_OPTION_WITHOUT_ROWID.to_string(), quote_style: None }, value: Value::Boolean(true) };
+            table_options.push(sql_option);
+        }
+        if self.index_nulls {
+            let sql_option = SqlOption { name: Ident { value: meta_const::NAME_OF_TABLE_OPTION_INDEX_NULLS.to_string(), quote_style: None }, value: Value::Boolean(true) };
+            table_options.push(sql_option);
+        }
+        if self.reverse_scan {
+            let sql_option = SqlOption { name: Ident { value: meta_const::NAME_OF_TABLE_OPTION_REVERSE_SCAN.to_string(), quote_style: None }, value: Value::Boolean(true) };
+            table_options.push(sql_option);
+        }
+        if let Some(compression) = &self.compression {
+            let sql_option = SqlOption { name: Ident { value: meta_const::NAME_OF_TABLE_OPTION_COMPRESSION.to_string(), quote_style: None }, value: Value::SingleQuotedString(compression.clone()) };
+            table_options.push(sql_option);
+            let sql_option = SqlOption { name: Ident { value: meta_const::NAME_OF_TABLE_OPTION_COMPRESSION_THRESHOLD.to_string(), quote_style: None }, value: Value::Number(self.compression_threshold.to_string(), false) };
+            table_options.push(sql_option);
+        }
+        if let Some(collation) = &self.collation {
+            let sql_option = SqlOption { name: Ident { value: meta_const::NAME_OF_TABLE_OPTION_COLLATION.to_string(), quote_style: None }, value: Value::SingleQuotedString(collation.clone()) };
+            table_options.push(sql_option);
+        }
 
         table_options
     }
 
+    /// The options which are saved in the create_options of information_schema.tables
+    pub fn to_create_options(&self) -> String {
+        let mut create_options = vec![];
+        if self.without_rowid {
+            create_options.push(meta_const::NAME_OF_TABLE_OPTION_WITHOUT_ROWID.to_string());
+        }
+        if self.index_nulls {
+            create_options.push(meta_const::NAME_OF_TABLE_OPTION_INDEX_NULLS.to_string());
+        }
+        if self.reverse_scan {
+            create_options.push(meta_const::NAME_OF_TABLE_OPTION_REVERSE_SCAN.to_string());
+        }
+        if let Some(compression) = &self.compression {
+            create_options.push(format!("{}={}", meta_const::NAME_OF_TABLE_OPTION_COMPRESSION, compression));
+            create_options.push(format!("{}={}", meta_const::NAME_OF_TABLE_OPTION_COMPRESSION_THRESHOLD, self.compression_threshold));
+        }
+        if let Some(collation) = &self.collation {
+            create_options.push(format!("{}={}", meta_const::NAME_OF_TABLE_OPTION_COLLATION, collation));
+        }
+        create_options.join(" ")
+    }
+
+    pub fn load_create_options(&mut self, create_options: &str) {
+        for create_option in create_options.split_whitespace() {
+            if create_option == meta_const::NAME_OF_TABLE_OPTION_WITHOUT_ROWID {
+                self.with_without_rowid(true);
+            } else if create_option == meta_const::NAME_OF_TABLE_OPTION_INDEX_NULLS {
+                self.with_index_nulls(true);
+            } else if create_option == meta_const::NAME_OF_TABLE_OPTION_REVERSE_SCAN {
+                self.with_reverse_scan(true);
+            } else if let Some((name, value)) = create_option.split_once('=') {
+                if name == meta_const::NAME_OF_TABLE_OPTION_COMPRESSION {
+                    self.with_compression(Some(value.to_string()));
+                } else if name == meta_const::NAME_OF_TABLE_OPTION_COMPRESSION_THRESHOLD {
+                    if let Ok(compression_threshold) = value.parse::<usize>() {
+                        self.with_compression_threshold(compression_threshold);
+                    }
+                } else if name == meta_const::NAME_OF_TABLE_OPTION_COLLATION {
+                    self.with_collation(Some(value.to_string()));
+                }
+            }
+        }
+    }
+
     pub fn load_table_options(&mut self, table_options: Vec<SqlOption>) {
         for sql_option in table_options {
             if sql_option.name.to_string().to_uppercase() == meta_const::NAME_OF_TABLE_OPTION_ENGINE.to_uppercase() {
@@ -293,11 +383,45 @@ impl TableOptionDef {
                     }
                     _ => {}
                 };
+            } else if sql_option.name.to_string().to_uppercase() == meta_const::NAME_OF_TABLE_OPTION_WITHOUT_ROWID.to_uppercase() {
+                self.with_without_rowid(table_option_is_true(&sql_option.value));
+            } else if sql_option.name.to_string().to_uppercase() == meta_const::NAME_OF_TABLE_OPTION_INDEX_NULLS.to_uppercase() {
+                self.with_index_nulls(table_option_is_true(&sql_option.value));
+            } else if sql_option.name.to_string().to_uppercase() == meta_const::NAME_OF_TABLE_OPTION_REVERSE_SCAN.to_uppercase() {
+                self.with_reverse_scan(table_option_is_true(&sql_option.value));
+            } else if sql_option.name.to_string().to_uppercase() == meta_const::NAME_OF_TABLE_OPTION_COMPRESSION.to_uppercase() {
+                match sql_option.value {
+                    Value::SingleQuotedString(value) => self.with_compression(Some(value.to_lowercase())),
+                    _ => {}
+                };
+            } else if sql_option.name.to_string().to_uppercase() == meta_const::NAME_OF_TABLE_OPTION_COMPRESSION_THRESHOLD.to_uppercase() {
+                match sql_option.value {
+                    Value::Number(value, _) => {
+                        if let Ok(compression_threshold) = value.parse::<usize>() {
+                            self.with_compression_threshold(compression_threshold);
+                        }
+                    }
+                    _ => {}
+                };
+            } else if sql_option.name.to_string().to_uppercase() == meta_const::NAME_OF_TABLE_OPTION_COLLATION.to_uppercase() {
+                match sql_option.value {
+                    Value::SingleQuotedString(value) => self.with_collation(Some(value.to_lowercase())),
+                    _ => {}
+                };
             }
         }
     }
 }
 
+fn table_option_is_true(value: &Value) -> bool {
+    match value {
+        Value::Boolean(value) => *value,
+        Value::Number(value, _) => value == "1",
+        Value::SingleQuotedString(value) => value.to_uppercase() == "TRUE" || value == "1",
+        _ => false,
+    }
+}
+
 impl TableOptionDef {
     pub fn with_engine(&mut self, engine: &str) {
         self.engine = engine.to_string()
@@ -307,9 +431,45 @@ impl TableOptionDef {
         self.column_max_store_id = column_max_store_id
     }
 
+    pub fn with_table_id(&mut self, table_id: u64) {
+        self.table_id = table_id
+    }
+
+    /// The table in the keys of its rows, the table id stays when the table is renamed
+    pub fn key_name(&self) -> String {
+        if self.table_id == 0 {
+            return self.full_table_name.to_string();
+        }
+        self.table_id.to_string()
+    }
+
     pub fn with_table_type(&mut self, table_type: &str) {
         self.table_type = table_type.to_string()
     }
+
+    pub fn with_without_rowid(&mut self, without_rowid: bool) {
+        self.without_rowid = without_rowid
+    }
+
+    pub fn with_index_nulls(&mut self, index_nulls: bool) {
+        self.index_nulls = index_nulls
+    }
+
+    pub fn with_reverse_scan(&mut self, reverse_scan: bool) {
+        self.reverse_scan = reverse_scan
+    }
+
+    pub fn with_compression(&mut self, compression: Option<String>) {
+        self.compression = compression
+    }
+
+    pub fn with_compression_threshold(&mut self, compression_threshold: usize) {
+        self.compression_threshold = compression_threshold
+    }
+
+    pub fn with_collation(&mut self, collation: Option<String>) {
+        self.collation = collation
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -319,10 +479,77 @@ pub struct TableIndexDef {
     pub column_name_list: Vec<Ident>,
 }
 
+/// What a foreign key does to the child rows when their parent row is deleted or its referenced columns are updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferentialAction {
+    Restrict,
+    Cascade,
+    SetNull,
+    NoAction,
+}
+
+impl ReferentialAction {
+    /// The action by its rule in information_schema.referential_constraints, as `SET NULL`
+    pub fn from_rule(rule: &str) -> Option<Self> {
+        match rule.to_uppercase().as_str() {
+            "RESTRICT" => Some(ReferentialAction::Restrict),
+            "CASCADE" => Some(ReferentialAction::Cascade),
+            "SET NULL" => Some(ReferentialAction::SetNull),
+            "NO ACTION" => Some(ReferentialAction::NoAction),
+            _ => None,
+        }
+    }
+
+    pub fn to_rule(&self) -> &'static str {
+        match self {
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+            ReferentialAction::NoAction => "NO ACTION",
+        }
+    }
+}
+
+/// A FOREIGN KEY of a table, the columns of its rows refer to the columns of a row of the referenced table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKeyDef {
+    pub constraint_name: String,
+    pub column_name_list: Vec<Ident>,
+    pub referenced_table_name: ObjectName,
+    pub referenced_column_name_list: Vec<Ident>,
+    pub on_delete: ReferentialAction,
+    pub on_update: ReferentialAction,
+}
+
+/// A VIEW, the definition is its SELECT with the full table names, the columns of the SELECT are renamed to the columns of the view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewDef {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub view_name: String,
+    pub full_view_name: ObjectName,
+    pub definition: String,
+    pub column_name_list: Vec<String>,
+}
+
+impl ViewDef {
+    pub fn new(catalog_name: &str, schema_name: &str, view_name: &str, definition: &str, column_name_list: Vec<String>) -> Self {
+        Self {
+            catalog_name: catalog_name.to_string(),
+            schema_name: schema_name.to_string(),
+            view_name: view_name.to_string(),
+            full_view_name: meta_util::create_full_table_name(catalog_name, schema_name, view_name),
+            definition: definition.to_string(),
+            column_name_list,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TableDef {
     pub column: TableColumnDef,
     pub constraints: Vec<TableConstraint>,
+    pub foreign_keys: Vec<ForeignKeyDef>,
     pub option: TableOptionDef,
 }
 
@@ -335,6 +562,7 @@ impl TableDef {
         Self {
             column: table_column,
             constraints: table_constraints,
+            foreign_keys: vec![],
             option: table_option,
         }
     }
@@ -360,6 +588,10 @@ impl TableDef {
         self.constraints = constraints
     }
 
+    pub fn with_foreign_keys(&mut self, foreign_keys: Vec<ForeignKeyDef>) {
+        self.foreign_keys = foreign_keys
+    }
+
     pub fn with_option(&mut self, table_option: TableOptionDef) {
         self.option = table_option
     }
@@ -374,6 +606,13 @@ impl TableDef {
         &self.column.sparrow_column_list
     }
 
+    pub fn get_auto_increment_column(&self) -> Option<SparrowColumnDef> {
+        self.column.sparrow_column_list
+            .iter()
+            .find(|sparrow_column| meta_util::column_is_auto_increment(&sparrow_column.sql_column))
+            .cloned()
+    }
+
     pub fn get_table_column(&self) -> TableColumnDef {
         self.column.clone()
     }
@@ -382,6 +621,10 @@ impl TableDef {
         &self.constraints
     }
 
+    pub fn get_foreign_keys(&self) -> &Vec<ForeignKeyDef> {
+        &self.foreign_keys
+    }
+
     pub fn to_datafusion_dfschema(&self) -> error::Result<DFSchema> {
         let mut dffields = vec![];
         dffields.push(DFField::new(Some(self.option.full_table_name.to_string().as_str()), meta_const::COLUMN_ROWID, DataType::Utf8, false));
@@ -411,6 +654,21 @@ impl TableDef {
     }
 }
 
+/// The statistics of a column collected by ANALYZE TABLE, the min and max values are kept as text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColumnStatisticsDef {
+    pub null_count: usize,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+}
+
+/// The statistics of a table collected by ANALYZE TABLE
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableStatisticsDef {
+    pub num_rows: usize,
+    pub column_statistics: HashMap<String, ColumnStatisticsDef>,
+}
+
 #[derive(Debug, Clone)]
 pub struct StatisticsColumn {
     pub column_name: String,
@@ -422,6 +680,9 @@ pub struct IndexDef {
     pub index_name: String,
     pub level: i32,
     pub index_key: String,
+    /// The key contains a NULL value, NULL values never conflict with each other,
+    /// so the rowid is appended to keep the key unique
+    pub contains_null: bool,
 }
 
 impl IndexDef {
@@ -430,6 +691,18 @@ impl IndexDef {
             index_name: index_name.to_string(),
             level,
             index_key: index_key.to_string(),
+            contains_null: false,
         }
     }
+}
+
+impl IndexDef {
+    pub fn with_contains_null(&mut self, contains_null: bool) {
+        self.contains_null = contains_null
+    }
+
+    /// The rowid is appended to the key of a NULL value and to the keys of the index of a foreign key
+    pub fn ends_with_rowid(&self) -> bool {
+        self.contains_null || self.level == meta_const::INDEX_LEVEL_OF_FOREIGN_KEY
+    }
 }
\ No newline at end of file
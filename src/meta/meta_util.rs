@@ -1,25 +1,39 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use arrow::datatypes::DataType;
+use arrow::array::{as_primitive_array, as_string_array, Array, Float64Array, Int64Array, StringArray};
+use arrow::compute;
+use arrow::datatypes::{DataType, TimeUnit};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::scalar::ScalarValue;
 use sqlparser::ast::{
-    ColumnDef as SQLColumnDef, ColumnOption, ColumnOptionDef, DataType as SQLDataType, Ident,
+    ColumnDef as SQLColumnDef, ColumnOption, ColumnOptionDef, DataType as SQLDataType, Expr as SQLExpr, Ident,
     ObjectName, TableConstraint,
 };
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
 
 use crate::core::global_context::GlobalContext;
 use crate::core::session_context::SessionContext;
 use crate::meta;
-use crate::meta::def::information_schema::{key_column_usage, table_constraints};
+use crate::meta::def::information_schema::{key_column_usage, referential_constraints, table_constraints, views};
 use crate::meta::initial::{
-    get_full_table_name_list, SaveKeyColumnUsage, SaveStatistics, SaveTableConstraints,
+    get_full_table_name_list, SaveKeyColumnUsage, SaveReferentialConstraints, SaveStatistics,
+    SaveTableConstraints,
+};
+use crate::meta::meta_def::{
+    ColumnStatisticsDef, ForeignKeyDef, SparrowColumnDef, SchemaDef, TableDef, TableIndexDef,
+    TableOptionDef, TableStatisticsDef,
 };
-use crate::meta::meta_def::{SparrowColumnDef, SchemaDef, TableDef, TableIndexDef, TableOptionDef};
 use crate::meta::{def, initial, meta_const, meta_util};
 use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::store::engine::engine_util::{StoreEngine, StoreEngineFactory, TableEngineFactory};
+use crate::util::collation;
+use crate::util::collation::Collation;
 use crate::util::convert::ToObjectName;
+use crate::util::dbkey;
+use crate::util::decimal;
 
 pub fn get_schema(
     global_context: Arc<Mutex<GlobalContext>>,
@@ -55,8 +69,17 @@ pub fn get_table_index_list(
 ) -> MysqlResult<Vec<TableIndexDef>> {
     let table = meta_util::get_table(global_context.clone(), full_table_name.clone()).unwrap();
 
+    let mut table_constraint_list = table.get_constraints().to_vec();
+    let building_index_list = global_context.lock().unwrap().building_index_map.get(&full_table_name).cloned();
+    for building_index in building_index_list.unwrap_or_default() {
+        // the index which is done is in the table already
+        if !table_constraint_list.iter().any(|table_constraint| constraint_name(table_constraint) == constraint_name(&building_index)) {
+            table_constraint_list.push(building_index);
+        }
+    }
+
     let mut all_index = vec![];
-    for table_constraint in table.get_constraints() {
+    for table_constraint in table_constraint_list.iter() {
         match table_constraint {
             TableConstraint::Unique {
                 name,
@@ -89,9 +112,26 @@ pub fn get_table_index_list(
         }
     }
 
+    // the rows referring to a row are found by the index of the foreign key, as mysql creates it
+    for foreign_key in table.get_foreign_keys() {
+        all_index.push(TableIndexDef {
+            index_name: foreign_key.constraint_name.clone(),
+            level: meta_const::INDEX_LEVEL_OF_FOREIGN_KEY,
+            column_name_list: foreign_key.column_name_list.clone(),
+        });
+    }
+
     Ok(all_index)
 }
 
+/// The name of the unique or primary key constraint
+pub fn constraint_name(table_constraint: &TableConstraint) -> Option<String> {
+    match table_constraint {
+        TableConstraint::Unique { name, .. } => name.as_ref().map(|name| name.value.to_lowercase()),
+        _ => None,
+    }
+}
+
 pub fn create_sparrow_column(
     store_id: i64,
     ordinal_position: i64,
@@ -126,6 +166,118 @@ pub fn create_sql_column(
     }
 }
 
+/// The AUTO_INCREMENT column option, it is kept as a dialect specific option by the parser
+pub fn create_auto_increment_option() -> ColumnOptionDef {
+    ColumnOptionDef {
+        name: None,
+        option: ColumnOption::DialectSpecific(vec![Token::make_keyword("AUTO_INCREMENT")]),
+    }
+}
+
+pub fn column_is_auto_increment(sql_column: &SQLColumnDef) -> bool {
+    sql_column.options.iter().any(|column_option| match &column_option.option {
+        ColumnOption::DialectSpecific(tokens) => tokens.iter().any(|token| {
+            let token = token.to_string().to_uppercase();
+            token == "AUTO_INCREMENT" || token == "AUTOINCREMENT"
+        }),
+        _ => false,
+    })
+}
+
+/// The ZEROFILL column option, it is removed from the sql before the parser and added as a dialect specific option
+pub fn create_zerofill_option() -> ColumnOptionDef {
+    ColumnOptionDef {
+        name: None,
+        option: ColumnOption::DialectSpecific(vec![Token::make_keyword("ZEROFILL")]),
+    }
+}
+
+pub fn column_is_zerofill(sql_column: &SQLColumnDef) -> bool {
+    sql_column.options.iter().any(|column_option| match &column_option.option {
+        ColumnOption::DialectSpecific(tokens) => tokens.iter().any(|token| token.to_string().to_uppercase() == "ZEROFILL"),
+        _ => false,
+    })
+}
+
+/// The display width of the ZEROFILL column, the values are padded with the leading zeros to it
+pub fn get_zerofill_width(sql_column: &SQLColumnDef) -> Option<u64> {
+    if !column_is_zerofill(sql_column) {
+        return None;
+    }
+    match sql_column.data_type {
+        SQLDataType::Int(width) => Some(width.unwrap_or(meta_const::DISPLAY_WIDTH_OF_ZEROFILL_INT)),
+        _ => None,
+    }
+}
+
+/// The COLUMN_TYPE of information_schema.columns, the data type with the display width and the ZEROFILL,
+/// as `int(5) zerofill`
+pub fn get_column_type(sql_column: &SQLColumnDef) -> MysqlResult<String> {
+    let data_type = convert_sql_data_type(&sql_column.data_type)?;
+    let mut column_type = match &sql_column.data_type {
+        SQLDataType::TinyInt(Some(width)) | SQLDataType::Int(Some(width)) => format!("{}({})", data_type, width),
+        SQLDataType::Binary(length) | SQLDataType::Varbinary(length) => format!("{}({})", data_type, length),
+        SQLDataType::Decimal(precision, scale) => {
+            let (precision, scale) = decimal::precision_scale(precision, scale);
+            format!("{}({},{})", data_type, precision, scale)
+        }
+        _ => data_type,
+    };
+    if column_is_zerofill(sql_column) {
+        column_type = format!("{} {}", column_type, meta_const::COLUMN_TYPE_OF_ZEROFILL);
+    }
+    Ok(column_type)
+}
+
+/// The display width and the ZEROFILL of the COLUMN_TYPE are added to the column read from DATA_TYPE,
+/// and the length of the BINARY and the VARBINARY
+pub fn load_column_type(sql_column: &mut SQLColumnDef, column_type: &str) {
+    let width = column_type
+        .split_once('(')
+        .and_then(|(_, rest)| rest.split_once(')'))
+        .and_then(|(width, _)| width.trim().parse::<u64>().ok());
+    match sql_column.data_type {
+        SQLDataType::Int(None) => sql_column.data_type = SQLDataType::Int(width),
+        SQLDataType::Binary(_) => sql_column.data_type = SQLDataType::Binary(width.unwrap_or(1)),
+        SQLDataType::Varbinary(_) => sql_column.data_type = SQLDataType::Varbinary(width.unwrap_or(meta_const::LENGTH_OF_BLOB)),
+        _ => {}
+    }
+    let is_zerofill = column_type
+        .split_whitespace()
+        .any(|word| word.eq_ignore_ascii_case(meta_const::COLUMN_TYPE_OF_ZEROFILL));
+    if is_zerofill && !column_is_zerofill(sql_column) {
+        sql_column.options.push(create_zerofill_option());
+    }
+}
+
+/// The DEFAULT column option of the expression saved in COLUMN_DEFAULT
+pub fn create_default_option(column_default: &str) -> MysqlResult<ColumnOptionDef> {
+    let dialect = GenericDialect {};
+    let expr = Tokenizer::new(&dialect, column_default)
+        .tokenize()
+        .map_err(|error| format!("{:?}", error))
+        .and_then(|tokens| Parser::new(tokens, &dialect).parse_expr().map_err(|error| format!("{:?}", error)));
+    match expr {
+        Ok(expr) => Ok(ColumnOptionDef {
+            name: None,
+            option: ColumnOption::Default(expr),
+        }),
+        Err(error) => {
+            let message = format!("Invalid default value '{}', error: {}", column_default, error);
+            log::error!("{}", message);
+            Err(MysqlError::new_global_error(meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR, message.as_str()))
+        }
+    }
+}
+
+/// The expression of the DEFAULT column option, None if the column has no default
+pub fn get_column_default(sql_column: &SQLColumnDef) -> Option<SQLExpr> {
+    sql_column.options.iter().find_map(|column_option| match &column_option.option {
+        ColumnOption::Default(expr) => Some(expr.clone()),
+        _ => None,
+    })
+}
+
 pub fn create_schema_name(catalog: &str, db_name: &str, table_name: &str) -> String {
     let data: String = vec![catalog, db_name, table_name].join(".");
     data
@@ -317,6 +469,360 @@ pub fn load_global_variable(global_context: Arc<Mutex<GlobalContext>>) -> MysqlR
     Ok(())
 }
 
+/// The next value of the auto increment column of the table
+pub fn read_auto_increment(global_context: Arc<Mutex<GlobalContext>>, table_def: &TableDef) -> MysqlResult<i64> {
+    let store_engine = StoreEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+    let key = dbkey::create_auto_increment_key(&table_def.option);
+    match store_engine.get_key(key)? {
+        None => Ok(1),
+        Some(value) => match String::from_utf8_lossy(value.as_slice()).parse::<i64>() {
+            Ok(next_value) => Ok(next_value),
+            Err(_) => Err(MysqlError::new_global_error(1105, format!(
+                "Unknown error. The auto increment value is broken, table_name: {}",
+                table_def.option.full_table_name,
+            ).as_str())),
+        },
+    }
+}
+
+pub fn save_auto_increment(global_context: Arc<Mutex<GlobalContext>>, table_def: &TableDef, next_value: i64) -> MysqlResult<()> {
+    let store_engine = StoreEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+    let key = dbkey::create_auto_increment_key(&table_def.option);
+    store_engine.put_key(key, next_value.to_string().as_bytes())
+}
+
+/// The id of a new table, the ids are handed out one after the other and never reused
+pub fn next_table_id(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<u64> {
+    let auto_increment_lock = global_context.lock().unwrap().auto_increment_lock.clone();
+    let _guard = auto_increment_lock.lock().unwrap();
+
+    let store_engine = StoreEngineFactory::try_new_schema_engine(global_context.clone())?;
+    let key = dbkey::create_current_table_id();
+    let current_table_id = read_table_id(store_engine.as_ref(), key.clone())?.unwrap_or(0);
+    let table_id = current_table_id + 1;
+    store_engine.put_key(key, table_id.to_string().as_bytes())?;
+    Ok(table_id)
+}
+
+fn read_table_id(store_engine: &dyn StoreEngine, key: String) -> MysqlResult<Option<u64>> {
+    match store_engine.get_key(key)? {
+        None => Ok(None),
+        Some(value) => match String::from_utf8_lossy(value.as_slice()).parse::<u64>() {
+            Ok(table_id) => Ok(Some(table_id)),
+            Err(_) => Err(MysqlError::new_global_error(1105, "Unknown error. The table id is broken")),
+        },
+    }
+}
+
+/// The number of keys moved in one batch
+const MOVE_KEY_BATCH_SIZE: usize = 1024;
+
+/// Move the rows, the column values, the index entries, the auto increment value and the statistics of the
+/// table from the keys of the table option to the keys of the new one. Every batch writes the moved keys
+/// and deletes the old ones at once
+pub fn move_table_keys(store_engine: &dyn StoreEngine, table_option: &TableOptionDef, new_table_option: &TableOptionDef) -> MysqlResult<()> {
+    let prefixes = vec![
+        (dbkey::scan_record_rowid(table_option), dbkey::scan_record_rowid(new_table_option)),
+        (dbkey::scan_column_key(table_option), dbkey::scan_column_key(new_table_option)),
+        (dbkey::scan_table_index_key(table_option), dbkey::scan_table_index_key(new_table_option)),
+    ];
+    for (prefix, new_prefix) in prefixes {
+        loop {
+            // the moved keys are deleted, the scan starts again from the prefix
+            let keys = store_engine.scan_prefix_keys(prefix.clone(), None, MOVE_KEY_BATCH_SIZE)?;
+            if keys.is_empty() {
+                break;
+            }
+            let mut writes = vec![];
+            for key in keys {
+                if let Some(value) = store_engine.get_key(key.clone())? {
+                    writes.push((format!("{}{}", new_prefix, &key[prefix.len()..]), Some(value)));
+                }
+                writes.push((key, None));
+            }
+            store_engine.write_keys(writes)?;
+        }
+    }
+
+    let keys = vec![
+        (dbkey::create_auto_increment_key(table_option), dbkey::create_auto_increment_key(new_table_option)),
+        (dbkey::create_table_statistics_key(table_option), dbkey::create_table_statistics_key(new_table_option)),
+    ];
+    for (key, new_key) in keys {
+        if let Some(value) = store_engine.get_key(key.clone())? {
+            store_engine.write_keys(vec![(new_key, Some(value)), (key, None)])?;
+        }
+    }
+    Ok(())
+}
+
+/// Give the tables created before the table ids a table id, their keys move from their full table names
+/// to the table id. The tables of the def schemas are read by their definitions in the code, they keep
+/// their keys. The id is saved before the keys move, the migration stopped by a crash resumes with the same id
+pub fn migrate_table_id(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<()> {
+    let schema_engine = StoreEngineFactory::try_new_schema_engine(global_context.clone())?;
+    let table_map = global_context.lock().unwrap().meta_data.get_table_map();
+    for (full_table_name, table_def) in table_map {
+        if table_def.option.table_id != 0 {
+            continue;
+        }
+        let def_schema_names = [
+            meta_const::SCHEMA_NAME_OF_DEF_INFORMATION_SCHEMA,
+            meta_const::SCHEMA_NAME_OF_DEF_MYSQL,
+            meta_const::SCHEMA_NAME_OF_DEF_PERFORMANCE_SCHEMA,
+        ];
+        if def_schema_names.contains(&table_def.option.schema_name.as_str()) {
+            continue;
+        }
+
+        let migrating_key = dbkey::create_migrating_table_id(&table_def.option);
+        let table_id = match read_table_id(schema_engine.as_ref(), migrating_key.clone())? {
+            Some(table_id) => table_id,
+            None => {
+                let table_id = next_table_id(global_context.clone())?;
+                schema_engine.put_key(migrating_key.clone(), table_id.to_string().as_bytes())?;
+                table_id
+            }
+        };
+        let mut new_table_option = table_def.option.clone();
+        new_table_option.with_table_id(table_id);
+        log::warn!("migrate the keys of table {} to the table id {}", full_table_name, table_id);
+
+        let store_engine = StoreEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+        move_table_keys(store_engine.as_ref(), &table_def.option, &new_table_option)?;
+        initial::save_information_schema_table_id(global_context.clone(), &table_def.option, table_id)?;
+        schema_engine.delete_key(migrating_key)?;
+    }
+    load_all_table(global_context)
+}
+
+/// Move the auto increment value of the tables past the largest value of their rows,
+/// a value saved before the last rows were written would be handed out again
+pub fn repair_auto_increment(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<()> {
+    let table_map = global_context.lock().unwrap().meta_data.get_table_map();
+    for (full_table_name, table_def) in table_map {
+        let sparrow_column = match table_def.get_auto_increment_column() {
+            None => continue,
+            Some(sparrow_column) => sparrow_column,
+        };
+        let column_index = match table_def.to_schema().index_of(sparrow_column.sql_column.name.value.as_str()) {
+            Ok(column_index) => column_index,
+            Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+        };
+
+        let engine = TableEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+        let mut max_value = 0;
+        for item in engine.table_iterator(Some(vec![column_index]), &[]) {
+            let record_batch = match item {
+                Ok(record_batch) => record_batch,
+                Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+            };
+            let column: &Int64Array = as_primitive_array(record_batch.column(0));
+            if let Some(value) = compute::max(column) {
+                max_value = max_value.max(value);
+            }
+        }
+
+        let next_value = read_auto_increment(global_context.clone(), &table_def)?;
+        if next_value <= max_value {
+            log::warn!(
+                "repair the auto increment value of table {} from {} to {}",
+                full_table_name,
+                next_value,
+                max_value + 1,
+            );
+            save_auto_increment(global_context.clone(), &table_def, max_value + 1)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check the metadata of the tables against the store, returns the problems found,
+/// the metadata written partly by an unclean shutdown is reported here instead of failing a query
+pub fn check_meta(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<Vec<String>> {
+    let table_map = global_context.lock().unwrap().meta_data.get_table_map();
+    let mut problems = vec![];
+
+    let mut full_table_name_list: Vec<ObjectName> = table_map.keys().cloned().collect();
+    full_table_name_list.sort_by_key(|full_table_name| full_table_name.to_string());
+    for full_table_name in full_table_name_list {
+        let table_def = table_map.get(&full_table_name).unwrap();
+
+        if let Err(mysql_error) = TableEngineFactory::try_new_with_table(global_context.clone(), table_def.clone()) {
+            problems.push(format!("table {}: the engine '{}' can not be resolved, {}", full_table_name, table_def.option.engine, mysql_error.message()));
+        }
+
+        let sparrow_column_list = &table_def.column.sparrow_column_list;
+        if sparrow_column_list.is_empty() {
+            problems.push(format!("table {}: no columns found", full_table_name));
+        }
+        let mut store_id_list = vec![];
+        for (i, sparrow_column) in sparrow_column_list.iter().enumerate() {
+            let column_name = sparrow_column.sql_column.name.to_string();
+            if sparrow_column.ordinal_position != i as i64 + 1 {
+                problems.push(format!(
+                    "table {}: the ordinal position of column {} is {}, expected {}",
+                    full_table_name, column_name, sparrow_column.ordinal_position, i + 1,
+                ));
+            }
+            if sparrow_column.store_id < 1 || sparrow_column.store_id > table_def.option.column_max_store_id {
+                problems.push(format!(
+                    "table {}: the store id {} of column {} is out of the column max store id {}",
+                    full_table_name, sparrow_column.store_id, column_name, table_def.option.column_max_store_id,
+                ));
+            }
+            if store_id_list.contains(&sparrow_column.store_id) {
+                problems.push(format!(
+                    "table {}: the store id {} of column {} is used by another column",
+                    full_table_name, sparrow_column.store_id, column_name,
+                ));
+            }
+            store_id_list.push(sparrow_column.store_id);
+        }
+
+        for constraint in &table_def.constraints {
+            if let TableConstraint::Unique { name, columns, .. } = constraint {
+                for column_name in columns {
+                    if !table_def.column.sparrow_column_map.contains_key(column_name) {
+                        problems.push(format!(
+                            "table {}: the index {:?} uses the unknown column {}",
+                            full_table_name, name, column_name,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    // the columns of a table whose table row was not written
+    let schema_table_columns = initial::read_information_schema_columns(global_context.clone())?;
+    let mut orphan_table_name_list: Vec<String> = schema_table_columns
+        .keys()
+        .filter(|full_table_name| !table_map.contains_key(full_table_name))
+        .map(|full_table_name| full_table_name.to_string())
+        .collect();
+    orphan_table_name_list.sort();
+    for full_table_name in orphan_table_name_list {
+        problems.push(format!("table {}: columns found without the table", full_table_name));
+    }
+
+    Ok(problems)
+}
+
+/// Fail fast when the metadata does not pass the check
+pub fn verify_meta(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<()> {
+    let problems = check_meta(global_context)?;
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    for problem in problems.iter() {
+        log::error!("check meta: {}", problem);
+    }
+    Err(MysqlError::new_global_error(1105, format!(
+        "Unknown error. The metadata is inconsistent with the store, problems: {}",
+        problems.join("; "),
+    ).as_str()))
+}
+
+/// Scan the table for the row count and the min, max and null count of the columns
+pub fn analyze_table(global_context: Arc<Mutex<GlobalContext>>, table_def: &TableDef) -> MysqlResult<TableStatisticsDef> {
+    let schema = table_def.to_schema();
+    let engine = TableEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+
+    let mut table_statistics = TableStatisticsDef::default();
+    for item in engine.table_iterator(None, &[]) {
+        let record_batch = match item {
+            Ok(record_batch) => record_batch,
+            Err(arrow_error) => return Err(MysqlError::from(arrow_error)),
+        };
+        table_statistics.num_rows += record_batch.num_rows();
+
+        for (column_index, field) in schema.fields().iter().enumerate() {
+            if field.name() == meta_const::COLUMN_ROWID {
+                continue;
+            }
+
+            let column = record_batch.column(column_index);
+            let (min_value, max_value) = match field.data_type() {
+                DataType::Int64 => {
+                    let column: &Int64Array = as_primitive_array(column);
+                    (compute::min(column).map(|v| v.to_string()), compute::max(column).map(|v| v.to_string()))
+                }
+                DataType::Float64 => {
+                    let column: &Float64Array = as_primitive_array(column);
+                    (compute::min(column).map(|v| v.to_string()), compute::max(column).map(|v| v.to_string()))
+                }
+                DataType::Utf8 => {
+                    let column: &StringArray = as_string_array(column);
+                    (compute::min_string(column).map(|v| v.to_string()), compute::max_string(column).map(|v| v.to_string()))
+                }
+                _ => (None, None),
+            };
+
+            let column_statistics = table_statistics
+                .column_statistics
+                .entry(field.name().to_string())
+                .or_insert(ColumnStatisticsDef::default());
+            column_statistics.null_count += column.null_count();
+            column_statistics.min_value = match (column_statistics.min_value.take(), min_value) {
+                (Some(a), Some(b)) => Some(pick_scalar_text(field.data_type(), a, b, true)),
+                (a, b) => a.or(b),
+            };
+            column_statistics.max_value = match (column_statistics.max_value.take(), max_value) {
+                (Some(a), Some(b)) => Some(pick_scalar_text(field.data_type(), a, b, false)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+
+    Ok(table_statistics)
+}
+
+/// Pick the smaller, or the larger, of two values of the data type in text
+fn pick_scalar_text(data_type: &DataType, a: String, b: String, smaller: bool) -> String {
+    let a_is_smaller = match data_type {
+        DataType::Int64 => a.parse::<i64>().unwrap_or_default() < b.parse::<i64>().unwrap_or_default(),
+        DataType::Float64 => a.parse::<f64>().unwrap_or_default() < b.parse::<f64>().unwrap_or_default(),
+        _ => a < b,
+    };
+    if a_is_smaller == smaller {
+        a
+    } else {
+        b
+    }
+}
+
+pub fn save_table_statistics(global_context: Arc<Mutex<GlobalContext>>, table_def: &TableDef, table_statistics: &TableStatisticsDef) -> MysqlResult<()> {
+    let store_engine = StoreEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+    let key = dbkey::create_table_statistics_key(&table_def.option);
+    let value = match serde_json::to_vec(table_statistics) {
+        Ok(value) => value,
+        Err(error) => return Err(MysqlError::new_global_error(1105, format!(
+            "Unknown error. Serialize the table statistics error: {}",
+            error,
+        ).as_str())),
+    };
+    store_engine.put_key(key, value.as_slice())
+}
+
+/// The statistics of the last ANALYZE TABLE, None if the table is never analyzed
+pub fn read_table_statistics(global_context: Arc<Mutex<GlobalContext>>, table_def: &TableDef) -> MysqlResult<Option<TableStatisticsDef>> {
+    let store_engine = StoreEngineFactory::try_new_with_table(global_context.clone(), table_def.clone())?;
+    let key = dbkey::create_table_statistics_key(&table_def.option);
+    match store_engine.get_key(key)? {
+        None => Ok(None),
+        Some(value) => match serde_json::from_slice(value.as_slice()) {
+            Ok(table_statistics) => Ok(Some(table_statistics)),
+            Err(error) => Err(MysqlError::new_global_error(1105, format!(
+                "Unknown error. The table statistics are broken, table_name: {}, error: {}",
+                table_def.option.full_table_name,
+                error,
+            ).as_str())),
+        },
+    }
+}
+
 pub fn load_all_table(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<()> {
     let result = initial::read_all_table(global_context.clone());
     match result {
@@ -335,6 +841,17 @@ pub fn load_all_table(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<
     }
 }
 
+/// The views are kept apart from the tables, they have no rows and no columns to store
+pub fn load_all_view(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<()> {
+    let view_map = initial::read_information_schema_views(global_context.clone())?;
+    global_context
+        .lock()
+        .unwrap()
+        .meta_data
+        .add_all_view(view_map);
+    Ok(())
+}
+
 pub async fn init_meta(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<()> {
     let mut init_tables = vec![];
     init_tables.push(def::information_schema::tables(global_context.clone()));
@@ -343,7 +860,10 @@ pub async fn init_meta(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult
     init_tables.push(def::information_schema::statistics(global_context.clone()));
     init_tables.push(key_column_usage(global_context.clone()));
     init_tables.push(table_constraints(global_context.clone()));
+    init_tables.push(referential_constraints(global_context.clone()));
+    init_tables.push(views(global_context.clone()));
     init_tables.push(def::mysql::users(global_context.clone()));
+    init_tables.push(def::mysql::db(global_context.clone()));
     init_tables.push(def::performance_schema::global_variables(
         global_context.clone(),
     ));
@@ -381,6 +901,8 @@ pub async fn init_meta(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult
         let result = initial::create_schema(
             global_context.clone(),
             meta_const::FULL_SCHEMA_NAME_OF_DEF_MYSQL.to_object_name(),
+            meta_const::VALUE_OF_SCHEMA_OPTION_DEFAULT_CHARACTER_SET_NAME,
+            meta_const::VALUE_OF_SCHEMA_OPTION_DEFAULT_COLLATION_NAME,
         );
         if let Err(e) = result {
             return Err(e);
@@ -389,6 +911,8 @@ pub async fn init_meta(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult
         let result = initial::create_schema(
             global_context.clone(),
             meta_const::FULL_SCHEMA_NAME_OF_DEF_PERFORMANCE_SCHEMA.to_object_name(),
+            meta_const::VALUE_OF_SCHEMA_OPTION_DEFAULT_CHARACTER_SET_NAME,
+            meta_const::VALUE_OF_SCHEMA_OPTION_DEFAULT_COLLATION_NAME,
         );
         if let Err(e) = result {
             return Err(e);
@@ -457,11 +981,27 @@ pub fn text_to_null(is_nullable: &str) -> Result<ColumnOption> {
     }
 }
 
-pub fn create_sql_data_type(data_type: &str) -> Result<SQLDataType> {
+/// The sql data type of the data type text, the numeric precision and scale are of the DECIMAL
+pub fn create_sql_data_type(data_type: &str, numeric_precision: Option<i64>, numeric_scale: Option<i64>) -> Result<SQLDataType> {
     match data_type {
+        meta_const::MYSQL_DATA_TYPE_TINYINT => Ok(SQLDataType::TinyInt(Some(1))),
         meta_const::MYSQL_DATA_TYPE_INT => Ok(SQLDataType::Int(None)),
         meta_const::MYSQL_DATA_TYPE_FLOAT => Ok(SQLDataType::Float(None)),
+        meta_const::MYSQL_DATA_TYPE_DOUBLE => Ok(SQLDataType::Double),
         meta_const::MYSQL_DATA_TYPE_CHAR => Ok(SQLDataType::Char(None)),
+        meta_const::MYSQL_DATA_TYPE_DATE => Ok(SQLDataType::Date),
+        meta_const::MYSQL_DATA_TYPE_TIME => Ok(SQLDataType::Time),
+        meta_const::MYSQL_DATA_TYPE_DATETIME => Ok(datetime_data_type()),
+        meta_const::MYSQL_DATA_TYPE_TIMESTAMP => Ok(SQLDataType::Timestamp),
+        // the length of the BINARY and the VARBINARY is read from the COLUMN_TYPE, see `load_column_type`
+        meta_const::MYSQL_DATA_TYPE_BINARY => Ok(SQLDataType::Binary(1)),
+        meta_const::MYSQL_DATA_TYPE_VARBINARY => Ok(SQLDataType::Varbinary(meta_const::LENGTH_OF_BLOB)),
+        meta_const::MYSQL_DATA_TYPE_BLOB => Ok(SQLDataType::Blob(meta_const::LENGTH_OF_BLOB)),
+        meta_const::MYSQL_DATA_TYPE_LONGBLOB => Ok(longblob_data_type()),
+        meta_const::MYSQL_DATA_TYPE_DECIMAL => Ok(SQLDataType::Decimal(
+            numeric_precision.map(|precision| precision as u64),
+            numeric_scale.map(|scale| scale as u64),
+        )),
         _ => Err(DataFusionError::Execution(format!(
             "Unsupported text data type: {:?}.",
             data_type
@@ -469,13 +1009,22 @@ pub fn create_sql_data_type(data_type: &str) -> Result<SQLDataType> {
     }
 }
 
-/// if the scalar value is number, add 0 before the number, until the number lenth is 19
+/// if the scalar value is number, add 0 before the number, until the number lenth is 20,
+/// so the index keys are in the order of the numbers
 pub fn convert_scalar_value_to_string(scalar_value: ScalarValue) -> MysqlResult<Option<String>> {
     match scalar_value {
+        ScalarValue::Int8(limit) => {
+            if let Some(value) = limit {
+                let new_value = (value as u64) ^ meta_const::SIGN_MASK;
+                Ok(Some(format!("{:020}", new_value)))
+            } else {
+                Ok(None)
+            }
+        }
         ScalarValue::Int32(limit) => {
             if let Some(value) = limit {
                 let new_value = (value as u64) ^ meta_const::SIGN_MASK;
-                Ok(Some(new_value.to_string()))
+                Ok(Some(format!("{:020}", new_value)))
             } else {
                 Ok(None)
             }
@@ -483,7 +1032,7 @@ pub fn convert_scalar_value_to_string(scalar_value: ScalarValue) -> MysqlResult<
         ScalarValue::Int64(limit) => {
             if let Some(value) = limit {
                 let new_value = (value as u64) ^ meta_const::SIGN_MASK;
-                Ok(Some(new_value.to_string()))
+                Ok(Some(format!("{:020}", new_value)))
             } else {
                 Ok(None)
             }
@@ -508,9 +1057,20 @@ pub fn convert_scalar_value_to_string(scalar_value: ScalarValue) -> MysqlResult<
 
 pub fn convert_sql_data_type(sql_type: &SQLDataType) -> MysqlResult<String> {
     match sql_type {
+        SQLDataType::TinyInt(_) => Ok(meta_const::MYSQL_DATA_TYPE_TINYINT.to_string()),
         SQLDataType::Int(_) => Ok(meta_const::MYSQL_DATA_TYPE_INT.to_string()),
         SQLDataType::Float(_) => Ok(meta_const::MYSQL_DATA_TYPE_FLOAT.to_string()),
+        SQLDataType::Double => Ok(meta_const::MYSQL_DATA_TYPE_DOUBLE.to_string()),
         SQLDataType::Char(_) => Ok(meta_const::MYSQL_DATA_TYPE_CHAR.to_string()),
+        SQLDataType::Decimal(_, _) => Ok(meta_const::MYSQL_DATA_TYPE_DECIMAL.to_string()),
+        SQLDataType::Date => Ok(meta_const::MYSQL_DATA_TYPE_DATE.to_string()),
+        SQLDataType::Time => Ok(meta_const::MYSQL_DATA_TYPE_TIME.to_string()),
+        SQLDataType::Timestamp => Ok(meta_const::MYSQL_DATA_TYPE_TIMESTAMP.to_string()),
+        SQLDataType::Custom(_) if is_datetime_data_type(sql_type) => Ok(meta_const::MYSQL_DATA_TYPE_DATETIME.to_string()),
+        SQLDataType::Binary(_) => Ok(meta_const::MYSQL_DATA_TYPE_BINARY.to_string()),
+        SQLDataType::Varbinary(_) => Ok(meta_const::MYSQL_DATA_TYPE_VARBINARY.to_string()),
+        SQLDataType::Blob(_) => Ok(meta_const::MYSQL_DATA_TYPE_BLOB.to_string()),
+        SQLDataType::Custom(_) if is_longblob_data_type(sql_type) => Ok(meta_const::MYSQL_DATA_TYPE_LONGBLOB.to_string()),
         _ => Err(MysqlError::new_global_error(
             meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR,
             format!("Unsupported convert sql data type: {:?} to text.", sql_type).as_str(),
@@ -520,16 +1080,26 @@ pub fn convert_sql_data_type(sql_type: &SQLDataType) -> MysqlResult<String> {
 
 pub fn get_numeric_precision(sql_type: &SQLDataType) -> ScalarValue {
     match sql_type {
+        SQLDataType::TinyInt(_) => ScalarValue::Int64(Some(3)),
         SQLDataType::Int(_) => ScalarValue::Int64(Some(19)),
         SQLDataType::Float(_) => ScalarValue::Int64(Some(12)),
+        SQLDataType::Double => ScalarValue::Int64(Some(22)),
+        SQLDataType::Decimal(precision, scale) => {
+            let (precision, _) = decimal::precision_scale(precision, scale);
+            ScalarValue::Int64(Some(precision as i64))
+        }
         _ => ScalarValue::Utf8(None),
     }
 }
 
 pub fn get_numeric_scale(sql_type: &SQLDataType) -> ScalarValue {
     match sql_type {
-        SQLDataType::Int(_) => ScalarValue::Int64(Some(0)),
-        SQLDataType::Float(_) => ScalarValue::Utf8(None),
+        SQLDataType::TinyInt(_) | SQLDataType::Int(_) => ScalarValue::Int64(Some(0)),
+        SQLDataType::Float(_) | SQLDataType::Double => ScalarValue::Utf8(None),
+        SQLDataType::Decimal(precision, scale) => {
+            let (_, scale) = decimal::precision_scale(precision, scale);
+            ScalarValue::Int64(Some(scale as i64))
+        }
         _ => ScalarValue::Utf8(None),
     }
 }
@@ -537,6 +1107,8 @@ pub fn get_numeric_scale(sql_type: &SQLDataType) -> ScalarValue {
 pub fn get_character_maximum_length(sql_type: &SQLDataType) -> ScalarValue {
     match sql_type {
         SQLDataType::Char(_) => ScalarValue::Int64(Some(4294967295)),
+        SQLDataType::Binary(length) | SQLDataType::Varbinary(length) | SQLDataType::Blob(length) => ScalarValue::Int64(Some(*length as i64)),
+        SQLDataType::Custom(_) if is_longblob_data_type(sql_type) => ScalarValue::Int64(Some(4294967295)),
         _ => ScalarValue::Utf8(None),
     }
 }
@@ -545,11 +1117,100 @@ pub fn get_character_octed_length() -> ScalarValue {
     ScalarValue::Utf8(None)
 }
 
+/// BOOLEAN and BOOL are the aliases of TINYINT(1) as in mysql,
+/// the TINYINT is only supported as TINYINT(1) for now
+pub fn normalize_sql_data_type(sql_type: &SQLDataType) -> MysqlResult<SQLDataType> {
+    match sql_type {
+        SQLDataType::Boolean | SQLDataType::TinyInt(Some(1)) => Ok(SQLDataType::TinyInt(Some(1))),
+        SQLDataType::Real => Ok(SQLDataType::Double),
+        SQLDataType::Custom(name) if name.to_string().to_uppercase() == "BOOL" => Ok(SQLDataType::TinyInt(Some(1))),
+        SQLDataType::Custom(_) if is_datetime_data_type(sql_type) => Ok(datetime_data_type()),
+        SQLDataType::Custom(_) if is_longblob_data_type(sql_type) => Ok(longblob_data_type()),
+        SQLDataType::Custom(name) if ["TINYBLOB", "BLOB", "MEDIUMBLOB"].contains(&name.to_string().to_uppercase().as_str()) => {
+            Ok(SQLDataType::Blob(meta_const::LENGTH_OF_BLOB))
+        }
+        SQLDataType::TinyInt(_) => Err(MysqlError::new_server_error(
+            1235,
+            "42000",
+            "This version of MySQL doesn't yet support 'TINYINT other than TINYINT(1)'",
+        )),
+        _ => Ok(sql_type.clone()),
+    }
+}
+
+/// The sql parser has no DATETIME, it is a custom data type
+pub fn datetime_data_type() -> SQLDataType {
+    SQLDataType::Custom(ObjectName(vec![Ident::new(meta_const::MYSQL_DATA_TYPE_DATETIME.to_uppercase())]))
+}
+
+pub fn is_datetime_data_type(sql_type: &SQLDataType) -> bool {
+    match sql_type {
+        SQLDataType::Custom(name) => name.to_string().to_lowercase() == meta_const::MYSQL_DATA_TYPE_DATETIME,
+        _ => false,
+    }
+}
+
+/// The sql parser has no LONGBLOB, it is a custom data type
+pub fn longblob_data_type() -> SQLDataType {
+    SQLDataType::Custom(ObjectName(vec![Ident::new(meta_const::MYSQL_DATA_TYPE_LONGBLOB.to_uppercase())]))
+}
+
+pub fn is_longblob_data_type(sql_type: &SQLDataType) -> bool {
+    match sql_type {
+        SQLDataType::Custom(name) => name.to_string().to_lowercase() == meta_const::MYSQL_DATA_TYPE_LONGBLOB,
+        _ => false,
+    }
+}
+
+/// The BINARY, VARBINARY, BLOB and LONGBLOB, their values are bytes stored as the hex text
+pub fn is_binary_data_type(sql_type: &SQLDataType) -> bool {
+    matches!(sql_type, SQLDataType::Binary(_) | SQLDataType::Varbinary(_) | SQLDataType::Blob(_)) || is_longblob_data_type(sql_type)
+}
+
+/// The DATE, TIME, DATETIME and TIMESTAMP, their values are stored as text
+pub fn is_temporal_data_type(sql_type: &SQLDataType) -> bool {
+    matches!(sql_type, SQLDataType::Date | SQLDataType::Time | SQLDataType::Timestamp) || is_datetime_data_type(sql_type)
+}
+
+/// The column type of the query output, as for CREATE TABLE ... AS SELECT. The dates are DATE columns and
+/// the timestamps DATETIME columns, the TIME values are text in the query output, they are CHAR columns
+pub fn convert_arrow_data_type_to_sql_data_type(data_type: &DataType) -> SQLDataType {
+    match data_type {
+        DataType::Boolean | DataType::Int8 => SQLDataType::TinyInt(Some(1)),
+        DataType::Int16 | DataType::Int32 | DataType::Int64 | DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
+            SQLDataType::Int(None)
+        }
+        DataType::Float32 => SQLDataType::Float(None),
+        DataType::Float64 => SQLDataType::Double,
+        DataType::Decimal(precision, scale) => SQLDataType::Decimal(Some(*precision as u64), Some(*scale as u64)),
+        DataType::Binary => SQLDataType::Blob(meta_const::LENGTH_OF_BLOB),
+        DataType::LargeBinary => longblob_data_type(),
+        DataType::Date32 | DataType::Date64 => SQLDataType::Date,
+        DataType::Timestamp(_, _) => datetime_data_type(),
+        _ => SQLDataType::Char(None),
+    }
+}
+
+/// The DATE is the date and the DATETIME and the TIMESTAMP are the timestamp of microseconds without a time zone,
+/// the TIMESTAMP in UTC and the DATETIME as given. The TIME is the text `10:20:30[.fraction]`, the scalar values
+/// of DataFusion have no time of the day. They are all stored as their text, see `core_util::convert_datetime_text`.
+/// The LONGBLOB is the large binary, the other binary columns are the binary
 pub fn convert_sql_data_type_to_arrow_data_type(sql_type: &SQLDataType) -> MysqlResult<DataType> {
     match sql_type {
+        SQLDataType::Binary(_) | SQLDataType::Varbinary(_) | SQLDataType::Blob(_) => Ok(DataType::Binary),
+        SQLDataType::Custom(_) if is_longblob_data_type(sql_type) => Ok(DataType::LargeBinary),
+        SQLDataType::Date => Ok(DataType::Date32),
+        SQLDataType::Time => Ok(DataType::Utf8),
+        SQLDataType::Timestamp => Ok(DataType::Timestamp(TimeUnit::Microsecond, None)),
+        SQLDataType::Custom(_) if is_datetime_data_type(sql_type) => Ok(DataType::Timestamp(TimeUnit::Microsecond, None)),
+        SQLDataType::TinyInt(_) => Ok(DataType::Int8),
         SQLDataType::Int(_) => Ok(DataType::Int64),
-        SQLDataType::Float(_) => Ok(DataType::Float64),
+        SQLDataType::Float(_) | SQLDataType::Double => Ok(DataType::Float64),
         SQLDataType::Char(_) => Ok(DataType::Utf8),
+        SQLDataType::Decimal(precision, scale) => {
+            let (precision, scale) = decimal::precision_scale(precision, scale);
+            Ok(DataType::Decimal(precision, scale))
+        }
         _ => Err(MysqlError::new_global_error(
             meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR,
             format!(
@@ -651,8 +1312,9 @@ pub fn save_table_constraint(
                         seq_in_index,
                         column_name.to_string().as_str(),
                     );
-                    save_table_constraints.add_row(constraint_name.as_str(), constraint_type);
                 }
+                // one row for each constraint, whatever the number of its columns
+                save_table_constraints.add_row(constraint_name.as_str(), constraint_type);
             }
             _ => continue,
         }
@@ -676,6 +1338,68 @@ pub fn save_table_constraint(
     Ok(())
 }
 
+/// The foreign keys of the table with the names of the indexes of the referenced tables over the referenced columns,
+/// saved in information_schema.key_column_usage, table_constraints and referential_constraints
+pub fn save_table_foreign_keys(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_option: TableOptionDef,
+    foreign_keys: Vec<(ForeignKeyDef, String)>,
+) -> MysqlResult<()> {
+    let catalog_name = table_option.catalog_name;
+    let schema_name = table_option.schema_name;
+    let table_name = table_option.table_name;
+
+    let mut save_key_column_usage = SaveKeyColumnUsage::new(
+        global_context.clone(),
+        catalog_name.as_str(),
+        schema_name.as_str(),
+        table_name.as_str(),
+    );
+    let mut save_table_constraints = SaveTableConstraints::new(
+        global_context.clone(),
+        catalog_name.as_str(),
+        schema_name.as_str(),
+        table_name.as_str(),
+    );
+    let mut save_referential_constraints = SaveReferentialConstraints::new(
+        global_context.clone(),
+        catalog_name.as_str(),
+        schema_name.as_str(),
+        table_name.as_str(),
+    );
+
+    for (foreign_key, unique_constraint_name) in foreign_keys {
+        let referenced_schema_name = cut_out_schema_name(foreign_key.referenced_table_name.clone()).to_string();
+        let referenced_table_name = cut_out_table_name(foreign_key.referenced_table_name.clone()).to_string();
+
+        let column_pairs = foreign_key.column_name_list.iter().zip(foreign_key.referenced_column_name_list.iter());
+        for (seq_in_index, (column_name, referenced_column_name)) in column_pairs.enumerate() {
+            save_key_column_usage.add_foreign_key_row(
+                foreign_key.constraint_name.as_str(),
+                seq_in_index as i64 + 1,
+                column_name.value.as_str(),
+                referenced_schema_name.as_str(),
+                referenced_table_name.as_str(),
+                referenced_column_name.value.as_str(),
+            );
+        }
+        save_table_constraints.add_row(foreign_key.constraint_name.as_str(), meta_const::CONSTRAINT_TYPE_FOREIGN_KEY);
+        save_referential_constraints.add_row(
+            foreign_key.constraint_name.as_str(),
+            referenced_schema_name.as_str(),
+            referenced_table_name.as_str(),
+            unique_constraint_name.as_str(),
+            foreign_key.on_update.to_rule(),
+            foreign_key.on_delete.to_rule(),
+        );
+    }
+
+    save_key_column_usage.save()?;
+    save_table_constraints.save()?;
+    save_referential_constraints.save()?;
+    Ok(())
+}
+
 pub fn create_unique_index_name(columns: Vec<Ident>) -> String {
     format!(
         "Unique_{}",
@@ -722,6 +1446,66 @@ pub fn error_of_table_doesnt_exists(full_table_name: ObjectName) -> MysqlError {
     MysqlError::new_server_error(1146, "42S02", message.as_str())
 }
 
+/// The charset and the collation of a new schema, the default collation of the charset is used if it is not given,
+/// only the charsets of SHOW CHARACTER SET are known
+pub fn resolve_schema_charset(
+    character_set_name: Option<String>,
+    collation_name: Option<String>,
+) -> MysqlResult<(String, String)> {
+    let collation = collation::resolve_collation(character_set_name.as_deref(), collation_name.as_deref())?
+        .unwrap_or_else(collation::default_collation);
+    Ok((collation.character_set.to_string(), collation.name.to_string()))
+}
+
+/// The CHAR columns have a collation, the other columns have none
+pub fn is_text_data_type(sql_type: &SQLDataType) -> bool {
+    matches!(sql_type, SQLDataType::Char(_))
+}
+
+/// The collation of the text column, the default one if the column was created without it
+pub fn get_column_collation(sql_column: &SQLColumnDef) -> Option<&'static Collation> {
+    if !is_text_data_type(&sql_column.data_type) {
+        return None;
+    }
+    let collation = sql_column
+        .collation
+        .as_ref()
+        .and_then(|collation_name| collation::find_collation(collation_name.to_string().as_str()));
+    Some(collation.unwrap_or_else(collation::default_collation))
+}
+
+/// The default collation of the text columns of the table, the one of the table, else the one of its schema
+pub fn get_table_default_collation(
+    global_context: Arc<Mutex<GlobalContext>>,
+    table_option: &TableOptionDef,
+) -> MysqlResult<&'static Collation> {
+    let collation = match &table_option.collation {
+        Some(collation_name) => collation::resolve_collation(None, Some(collation_name.as_str()))?,
+        None => {
+            let full_schema_name = create_full_schema_name(table_option.catalog_name.as_str(), table_option.schema_name.as_str());
+            get_schema(global_context, full_schema_name)
+                .ok()
+                .and_then(|schema_def| collation::find_collation(schema_def.option.default_collation_name.as_str()))
+        }
+    };
+    Ok(collation.unwrap_or_else(collation::default_collation))
+}
+
+/// The text column is saved with its collation, the default one if it is created without it,
+/// the other columns have none
+pub fn resolve_column_collation(sql_column: &mut SQLColumnDef, default_collation: &'static Collation) -> MysqlResult<()> {
+    if !is_text_data_type(&sql_column.data_type) {
+        sql_column.collation = None;
+        return Ok(());
+    }
+    let collation = match &sql_column.collation {
+        Some(collation_name) => collation::resolve_collation(None, Some(collation_name.to_string().as_str()))?.unwrap(),
+        None => default_collation,
+    };
+    sql_column.collation = Some(ObjectName(vec![Ident::new(collation.name)]));
+    Ok(())
+}
+
 pub fn error_of_schema_doesnt_exists(full_schema_name: ObjectName) -> MysqlError {
     let message = format!("Schema '{}' doesn't exist", full_schema_name.to_string());
     log::error!("{}", message);
@@ -1,38 +1,64 @@
-use std::collections::{HashMap};
+use std::collections::{BTreeMap, HashMap};
 use datafusion::scalar::ScalarValue;
 
+use crate::variable::system_variable;
+
+/// The global values of the system variables, the ones not set read the default of their definitions
 #[derive(Debug, Clone)]
 pub struct Variable {
-    variable_map: HashMap<String, ScalarValue>,
+    variable_map: HashMap<String, String>,
 }
 
 impl Variable {
     pub fn new() -> Self {
-        let variable_map: HashMap<String, ScalarValue> = HashMap::new();
+        let variable_map: HashMap<String, String> = HashMap::new();
         Self {
             variable_map
         }
     }
 
-    pub fn get_variable(&self, name: &str) -> Option<&ScalarValue> {
-        return self.variable_map.get(name)
+    /// The value read by SELECT @@global.name, typed by the definition of the variable
+    pub fn get_variable(&self, name: &str) -> Option<ScalarValue> {
+        let value = self.get_variable_value(name)?;
+        match system_variable::get_system_variable_def(name) {
+            Some(variable_def) => Some(variable_def.to_scalar_value(value.as_str())),
+            None => Some(ScalarValue::Utf8(Some(value))),
+        }
+    }
+
+    pub fn get_variable_value(&self, name: &str) -> Option<String> {
+        let name = name.to_lowercase();
+        if let Some(value) = self.variable_map.get(name.as_str()) {
+            return Some(value.clone());
+        }
+        system_variable::get_system_variable_def(name.as_str()).map(|variable_def| variable_def.default_value.to_string())
+    }
+
+    /// Set by SET GLOBAL, the value is checked by the caller
+    pub fn set_variable(&mut self, name: &str, value: &str) {
+        self.variable_map.insert(name.to_lowercase(), value.to_string());
+    }
+
+    /// All the variables ordered by name, the defined ones not set have their default value
+    pub fn variables(&self) -> BTreeMap<String, String> {
+        let mut variables: BTreeMap<String, String> = system_variable::SYSTEM_VARIABLES
+            .iter()
+            .map(|variable_def| (variable_def.name.to_string(), variable_def.default_value.to_string()))
+            .collect();
+        for (variable_name, variable_value) in &self.variable_map {
+            variables.insert(variable_name.clone(), variable_value.clone());
+        }
+        variables
     }
 
     pub fn add_variable_map(&mut self, variable_map: HashMap<String, String>) {
         for (variable_name, variable_value) in variable_map {
-            let value = match variable_name.as_str() {
-                "transaction_read_only" => {
-                    let mut value = 0;
-                    if variable_value.to_lowercase().eq("on") {
-                        value = 1;
-                    }
-                    ScalarValue::Int64(Some(value))
-                }
-                _ => {
-                    ScalarValue::Utf8(Some(variable_value))
-                }
+            // the stored values are normalized as the values set by SET GLOBAL
+            let variable_value = match system_variable::get_system_variable_def(variable_name.as_str()) {
+                Some(variable_def) => variable_def.parse_value(variable_value.as_str()).unwrap_or(variable_value),
+                None => variable_value,
             };
-            self.variable_map.insert(variable_name, value);
+            self.set_variable(variable_name.as_str(), variable_value.as_str());
         }
     }
 }
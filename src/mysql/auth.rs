@@ -0,0 +1,183 @@
+use std::fs;
+
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use rsa::pkcs1::FromRsaPrivateKey;
+use rsa::pkcs8::{FromPrivateKey, ToPublicKey};
+use rsa::{PaddingScheme, RsaPrivateKey};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::config::def::ConfigServer;
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+/// The length of the scramble sent in the handshake and the auth switch request
+pub const SCRAMBLE_LENGTH: usize = 20;
+
+/// The auth more data of caching_sha2_password after the fast auth succeeded
+pub const SHA2_FAST_AUTH_SUCCESS: u8 = 0x03;
+/// The auth more data of caching_sha2_password asking the client for the password
+pub const SHA2_PERFORM_FULL_AUTHENTICATION: u8 = 0x04;
+/// The client without TLS asks for the RSA public key of the server
+pub const SHA2_REQUEST_PUBLIC_KEY: u8 = 0x02;
+
+/// A new random scramble for each connection, so a captured auth response can not be replayed.
+/// The bytes are printable and never 0 or '$', as in mysql
+pub fn create_scramble() -> [u8; SCRAMBLE_LENGTH] {
+    let mut rng = rand::thread_rng();
+    let mut scramble = [0; SCRAMBLE_LENGTH];
+    for byte in scramble.iter_mut() {
+        *byte = rng.gen_range(0x21u8, 0x7fu8);
+        if *byte == b'$' {
+            *byte += 1;
+        }
+    }
+    scramble
+}
+
+/// A random password of letters and digits, the initial password of root when the config has none
+pub fn create_password() -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(16).collect()
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    Sha1::from(data).digest().bytes()
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(Sha256::digest(data).as_slice());
+    bytes
+}
+
+pub fn access_denied_error(user: &str, host: &str, using_password: bool) -> MysqlError {
+    let using_password = if using_password { "YES" } else { "NO" };
+    let message = format!("Access denied for user '{}'@'{}' (using password: {})", user, host, using_password);
+    log::error!("{}", message);
+    MysqlError::new_server_error(1045, "28000", message.as_str())
+}
+
+/// The authentication_string of mysql_native_password, `*` and the hex of SHA1(SHA1(password)),
+/// the empty password has an empty authentication_string
+pub fn native_password_hash(password: &str) -> String {
+    if password.is_empty() {
+        return "".to_string();
+    }
+    format!("*{}", hex::encode_upper(sha1(&sha1(password.as_bytes()))))
+}
+
+/// The auth response of the client, SHA1(password) XOR SHA1(scramble + SHA1(SHA1(password)))
+pub fn scramble_native_password(scramble: &[u8], password: &str) -> Vec<u8> {
+    if password.is_empty() {
+        return vec![];
+    }
+    let stage1 = sha1(password.as_bytes());
+    let stage2 = sha1(&stage1);
+    let mut data = scramble.to_vec();
+    data.extend_from_slice(&stage2);
+    let mask = sha1(data.as_slice());
+    stage1.iter().zip(mask.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+/// Check the auth response against the authentication_string of the user,
+/// https://dev.mysql.com/doc/internals/en/secure-password-authentication.html
+pub fn check_native_password(scramble: &[u8], auth_response: &[u8], authentication_string: &str) -> bool {
+    if authentication_string.is_empty() {
+        // the user without password answers with an empty auth response
+        return auth_response.is_empty();
+    }
+    if auth_response.len() != 20 {
+        return false;
+    }
+
+    let stage2 = match hex::decode(authentication_string.trim_start_matches('*')) {
+        Ok(stage2) if stage2.len() == 20 => stage2,
+        _ => return false,
+    };
+    let mut data = scramble.to_vec();
+    data.extend_from_slice(stage2.as_slice());
+    let mask = sha1(data.as_slice());
+    let stage1: Vec<u8> = auth_response.iter().zip(mask.iter()).map(|(a, b)| a ^ b).collect();
+    sha1(stage1.as_slice()).to_vec() == stage2
+}
+
+/// The digest the fast auth of caching_sha2_password is checked against, SHA256(SHA256(password))
+pub fn sha2_password_digest(password: &[u8]) -> [u8; 32] {
+    sha256(&sha256(password))
+}
+
+/// The auth response of caching_sha2_password, SHA256(password) XOR SHA256(SHA256(SHA256(password)) + scramble)
+pub fn scramble_sha2_password(scramble: &[u8], password: &str) -> Vec<u8> {
+    if password.is_empty() {
+        return vec![];
+    }
+    let stage1 = sha256(password.as_bytes());
+    let mut data = sha256(&stage1).to_vec();
+    data.extend_from_slice(scramble);
+    let mask = sha256(data.as_slice());
+    stage1.iter().zip(mask.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+/// The fast auth of caching_sha2_password, the auth response against the cached digest of the user
+pub fn check_sha2_password(scramble: &[u8], auth_response: &[u8], digest: &[u8; 32]) -> bool {
+    if auth_response.len() != 32 {
+        return false;
+    }
+    let mut data = digest.to_vec();
+    data.extend_from_slice(scramble);
+    let mask = sha256(data.as_slice());
+    let stage1: Vec<u8> = auth_response.iter().zip(mask.iter()).map(|(a, b)| a ^ b).collect();
+    sha256(stage1.as_slice()) == *digest
+}
+
+/// The password of the full auth, sent with its terminating 0
+pub fn read_clear_password(data: &[u8]) -> Vec<u8> {
+    match data.split_last() {
+        Some((&0, password)) => password.to_vec(),
+        _ => data.to_vec(),
+    }
+}
+
+/// The password of the full auth without TLS, XOR the scramble then encrypted by RSA with the OAEP padding,
+/// None if it can not be decrypted by the key of the server
+pub fn decrypt_sha2_password(private_key: &RsaPrivateKey, scramble: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+    let padding = PaddingScheme::new_oaep::<sha1_digest::Sha1>();
+    let decrypted = private_key.decrypt(padding, data).ok()?;
+    let password = decrypted
+        .iter()
+        .enumerate()
+        .map(|(index, byte)| byte ^ scramble[index % scramble.len()])
+        .collect::<Vec<u8>>();
+    Some(read_clear_password(password.as_slice()))
+}
+
+/// The RSA key of caching_sha2_password in the config, PKCS#1 or PKCS#8. None if it is not set,
+/// the clients without TLS can only use the fast auth then
+pub fn load_rsa_private_key(config_server: &ConfigServer) -> MysqlResult<Option<RsaPrivateKey>> {
+    let key_path = match &config_server.caching_sha2_password_private_key_path {
+        Some(key_path) => key_path,
+        None => return Ok(None),
+    };
+
+    let rsa_key_error = |message: String| {
+        MysqlError::new_global_error(1105, format!("Unknown error. Failed to load the RSA key {}, {}", key_path, message).as_str())
+    };
+    let pem = fs::read_to_string(key_path).map_err(|error| rsa_key_error(error.to_string()))?;
+    let private_key = match RsaPrivateKey::from_pkcs1_pem(pem.as_str()) {
+        Ok(private_key) => private_key,
+        Err(_) => RsaPrivateKey::from_pkcs8_pem(pem.as_str()).map_err(|error| rsa_key_error(error.to_string()))?,
+    };
+    Ok(Some(private_key))
+}
+
+/// The public key the client encrypts the password with, PEM of the SubjectPublicKeyInfo
+pub fn rsa_public_key_pem(private_key: &RsaPrivateKey) -> String {
+    match private_key.to_public_key().to_public_key_pem() {
+        Ok(pem) => pem,
+        Err(error) => {
+            log::error!("Unknown error. Failed to encode the RSA public key, error: {:?}", error);
+            "".to_string()
+        }
+    }
+}
+
@@ -26,7 +26,7 @@ use datafusion::error::DataFusionError;
 
 pub type MysqlResult<T> = result::Result<T, MysqlError>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(missing_docs)]
 pub struct MysqlError {
     error_number: u16,
@@ -64,14 +64,41 @@ impl MysqlError {
     }
 }
 
+/// The mysql error raised in a udf, wrapped by DataFusion and arrow while the plan is executed
+fn find_mysql_error(datafusion_error: &DataFusionError) -> Option<MysqlError> {
+    match datafusion_error {
+        DataFusionError::External(error) => downcast_mysql_error(error.as_ref()),
+        DataFusionError::ArrowError(ArrowError::ExternalError(error)) => downcast_mysql_error(error.as_ref()),
+        _ => None,
+    }
+}
+
+fn downcast_mysql_error(error: &(dyn error::Error + Send + Sync + 'static)) -> Option<MysqlError> {
+    if let Some(mysql_error) = error.downcast_ref::<MysqlError>() {
+        return Some(mysql_error.clone());
+    }
+    if let Some(datafusion_error) = error.downcast_ref::<DataFusionError>() {
+        return find_mysql_error(datafusion_error);
+    }
+    None
+}
+
 impl From<DataFusionError> for MysqlError {
     fn from(datafusion_error: DataFusionError) -> Self {
+        if let Some(mysql_error) = find_mysql_error(&datafusion_error) {
+            return mysql_error;
+        }
         MysqlError::new_global_error(1105, format!("Unknown error. Datafusion error: {:?}", datafusion_error).as_str())
     }
 }
 
 impl From<ArrowError> for MysqlError {
     fn from(arrow_error: ArrowError) -> Self {
+        if let ArrowError::ExternalError(error) = &arrow_error {
+            if let Some(mysql_error) = downcast_mysql_error(error.as_ref()) {
+                return mysql_error;
+            }
+        }
         MysqlError::new_global_error(1105, format!("Unknown error. Arrow error: {:?}", arrow_error).as_str())
     }
 }
@@ -3,26 +3,48 @@ use bytes::Buf;
 use std::io;
 use std::sync::{Arc, Mutex};
 
+use async_trait::async_trait;
+use rsa::RsaPrivateKey;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
 
 use crate::core::core_util;
 use crate::core::execution::Execution;
+use crate::core::front_end::FrontEnd;
 use crate::core::global_context::GlobalContext;
 use crate::core::output::CoreOutput;
 use crate::core::output::FinalCount;
+use crate::core::output::ResultStream;
+use crate::core::output::StmtFetch;
 use crate::core::output::StmtPrepare;
 use crate::mysql::error::MysqlResult;
 use crate::mysql::metadata::Column;
-use crate::mysql::{error::MysqlError, message, metadata, packet, request, response};
+use crate::mysql::mysql_error_code;
+use crate::mysql::tls::ClientStream;
+use crate::util::load_data_statement::LoadDataStatement;
+use crate::mysql::{auth, error::MysqlError, message, metadata, packet, request, response};
 use bstr::ByteSlice;
+use futures::StreamExt;
+
+/// The packets of the file of LOAD DATA LOCAL received ahead of the load, the client waits while they are full
+const LOCAL_INFILE_QUEUED_PACKETS: usize = 16;
 
 /// The state for each connected client.
 pub struct Handle {
     core_context: Arc<Mutex<GlobalContext>>,
-    socket: TcpStream,
+    socket: ClientStream,
+    /// The TLS of the connections, None if the server has no certificate
+    tls_acceptor: Option<TlsAcceptor>,
+    /// The RSA key of caching_sha2_password, None if the clients without TLS can not do the full auth
+    rsa_private_key: Option<Arc<RsaPrivateKey>>,
     packet_message: packet::PacketMessage,
+    /// The payloads of the client, reassembled from their packets
+    packet_reader: packet::PacketReader,
+    /// The compressed protocol after the handshake, if the client asked for it with CLIENT_COMPRESS
+    compressed_packet: Option<packet::CompressedPacket>,
     core_execution: Execution,
+    client_capability: metadata::CapabilityFlags,
 }
 
 impl Handle {
@@ -30,27 +52,61 @@ impl Handle {
     pub async fn new(
         socket: TcpStream,
         core_context: Arc<Mutex<GlobalContext>>,
+        tls_acceptor: Option<TlsAcceptor>,
+        rsa_private_key: Option<Arc<RsaPrivateKey>>,
     ) -> io::Result<Handle> {
-        let core_execution = Execution::new(core_context.clone());
+        let mut core_execution = Execution::new(core_context.clone());
+        // the rows of the SELECT are written as they are produced, they are not collected first
+        core_execution.set_client_stream_results(true);
         let packet_message = packet::PacketMessage::new();
+        // the max_allowed_packet set by SET GLOBAL is used by the new connections
+        let mut packet_reader = packet::PacketReader::new();
+        packet_reader.with_max_allowed_packet(core_execution.max_allowed_packet());
         Ok(Handle {
-            socket,
+            socket: ClientStream::Plain(socket),
+            tls_acceptor,
+            rsa_private_key,
+            compressed_packet: None,
             packet_message,
+            packet_reader,
             core_context,
             core_execution,
+            client_capability: metadata::CapabilityFlags::empty(),
         })
     }
 
     pub fn payload_packet(&mut self, buf: &[u8]) -> request::RequestPayload {
         log::debug!("buf: {:?}", buf);
         let rp = request::RequestPayload::new(buf.to_vec());
-        self.packet_message.sequence_increase();
+        if buf.len() >= 4 {
+            self.packet_message.sequence_receive(rp.get_sequence_id());
+        } else {
+            self.packet_message.sequence_increase();
+        }
 
         rp
     }
 
     pub async fn write_packet(&mut self, response_payload: response::ResponsePayload) {
-        let mem = self.packet_message.create(response_payload).unwrap();
+        let mut mem = match self.packet_message.create(response_payload) {
+            Ok(mem) => mem,
+            Err(mysql_error) => {
+                // the client gets the error instead of the payload longer than its max packet size
+                log::error!("error on creating the packet: {:?}", mysql_error);
+                let payload = message::error_message(
+                    mysql_error.error_number(),
+                    mysql_error.sql_state().as_str(),
+                    mysql_error.message().as_str(),
+                );
+                match self.packet_message.create(payload) {
+                    Ok(mem) => mem,
+                    Err(_) => return,
+                }
+            }
+        };
+        if let Some(compressed_packet) = self.compressed_packet.as_mut() {
+            mem = compressed_packet.compress(mem.as_ref());
+        }
         log::debug!("write packet bytes: {:?}", mem);
 
         if let Err(e) = self.socket.write_all(mem.bytes()).await {
@@ -60,15 +116,6 @@ impl Handle {
         self.packet_message.sequence_increase();
     }
 
-    pub async fn run(&mut self) {
-        let result = self.handshake().await;
-        if let Err(mysql_error) = result {
-            self.write_packet_error(mysql_error).await;
-            return;
-        }
-        self.exec_command().await;
-    }
-
     // pub async fn read_packet(&mut self) -> MysqlResult<Option<&[u8]>> {
     //     let mut buf = [0; 1024];
     //     let n = match self.socket.read(&mut buf).await {
@@ -83,97 +130,533 @@ impl Handle {
     //     Ok(Some(bytes))
     // }
 
-    pub async fn handshake(&mut self) -> MysqlResult<()> {
-        self.write_packet(message::handshark_message()).await;
-
-        let mut buf = [0; 1024];
-        let n = match self.socket.read(&mut buf).await {
-            Ok(n) if n == 0 => return Ok(()),
-            Ok(n) => n,
-            Err(error) => {
-                return Err(MysqlError::new_global_error(
-                    1105,
-                    format!(
-                        "Unknown error. Failed to read from socket, error: {:?}",
-                        error
-                    )
-                    .as_str(),
-                ));
+    /// The next payload of the client which may be compressed, reassembled from its packets,
+    /// None if the client closed the connection
+    async fn read_packet(&mut self) -> MysqlResult<Option<request::RequestPayload>> {
+        let mut buf = vec![0; 65536];
+        loop {
+            if let Some(bytes) = self.packet_reader.next_payload(self.compressed_packet.as_mut())? {
+                return Ok(Some(self.payload_packet(bytes.as_slice())));
             }
+
+            let n = match self.socket.read(&mut buf).await {
+                Ok(n) if n == 0 => return Ok(None),
+                Ok(n) => n,
+                Err(error) => {
+                    return Err(MysqlError::new_global_error(
+                        1105,
+                        format!(
+                            "Unknown error. Failed to read from socket, error: {:?}",
+                            error
+                        )
+                        .as_str(),
+                    ));
+                }
+            };
+            self.packet_reader.push(&buf[0..n]);
+        }
+    }
+
+    /// COM_CHANGE_USER, the new user is authenticated as in the handshake, then the session is reset.
+    /// None if the client closed the connection
+    async fn change_user(&mut self, request_payload: &request::RequestPayload) -> MysqlResult<Option<CoreOutput>> {
+        let change_user = request_payload.get_change_user(self.client_capability)?;
+        let host = match self.socket.peer_addr() {
+            Ok(peer_addr) => peer_addr.ip().to_string(),
+            Err(_) => "".to_string(),
+        };
+
+        let is_sha2_password = self.client_capability.contains(metadata::CapabilityFlags::CLIENT_PLUGIN_AUTH)
+            && change_user.auth_plugin_name.as_deref() == Some(message::AUTH_PLUGIN_NAME_CACHING_SHA2_PASSWORD);
+        if is_sha2_password {
+            let authenticated = self
+                .authenticate_sha2_password(change_user.username.as_str(), host.as_str(), change_user.auth_response.clone())
+                .await?;
+            if !authenticated {
+                return Ok(None);
+            }
+        } else {
+            let mut auth_response = change_user.auth_response.clone();
+            if let Some(auth_switch_message) = message::auth_switch_message(&change_user, self.core_execution.scramble()) {
+                self.write_packet(auth_switch_message).await;
+                let rp = match self.read_packet().await? {
+                    Some(rp) => rp,
+                    None => return Ok(None),
+                };
+                auth_response = rp.get_auth_switch_response();
+            }
+            self.core_execution.authenticate(change_user.username.as_str(), host.as_str(), auth_response.as_slice())?;
+        }
+        log::debug!("change the user of the connection to {}", change_user.username);
+
+        self.core_execution.set_process_user(change_user.username.as_str(), host.as_str());
+        self.core_execution.set_connect_attrs(change_user.connect_attrs.clone());
+        let core_output = self.core_execution.reset_connection()?;
+        if let Some(database) = change_user.database {
+            self.core_execution.set_default_schema(database.as_str()).await?;
+        }
+        Ok(Some(core_output))
+    }
+
+    /// caching_sha2_password, the fast auth with the digest cached by the last full auth of the user,
+    /// else the full auth with the password in clear text on TLS or encrypted by the RSA key of the server.
+    /// False if the client closed the connection
+    async fn authenticate_sha2_password(&mut self, user: &str, host: &str, auth_response: Vec<u8>) -> MysqlResult<bool> {
+        // the user without password answers with an empty auth response
+        if auth_response.is_empty() {
+            self.core_execution.authenticate_password(user, host, &[])?;
+            return Ok(true);
+        }
+        if self.core_execution.fast_authenticate_sha2_password(user, host, auth_response.as_slice())? {
+            self.write_packet(message::auth_more_data_message(&[auth::SHA2_FAST_AUTH_SUCCESS])).await;
+            return Ok(true);
+        }
+
+        self.write_packet(message::auth_more_data_message(&[auth::SHA2_PERFORM_FULL_AUTHENTICATION])).await;
+        let mut data = match self.read_packet().await? {
+            Some(rp) => rp.get_auth_switch_response(),
+            None => return Ok(false),
         };
-        let bytes = &buf[0..n];
-        let _rp = self.payload_packet(bytes);
-
-        self.write_packet(message::handshark_auth_switch_request())
-            .await;
-
-        let mut buf = [0; 1024];
-        let n = match self.socket.read(&mut buf).await {
-            Ok(n) if n == 0 => return Ok(()),
-            Ok(n) => n,
-            Err(error) => {
-                return Err(MysqlError::new_global_error(
-                    1105,
-                    format!(
-                        "Unknown error. Failed to read from socket, error: {:?}",
-                        error
-                    )
-                    .as_str(),
-                ));
+
+        let password = if self.socket.is_tls() {
+            auth::read_clear_password(data.as_slice())
+        } else {
+            let private_key = match &self.rsa_private_key {
+                Some(private_key) => private_key.clone(),
+                None => {
+                    // the password can not be sent in clear text without TLS
+                    log::error!("caching_sha2_password needs TLS or the RSA key for the full auth");
+                    return Err(auth::access_denied_error(user, host, true));
+                }
+            };
+            if data.as_slice() == [auth::SHA2_REQUEST_PUBLIC_KEY] {
+                let public_key_pem = auth::rsa_public_key_pem(&private_key);
+                self.write_packet(message::auth_more_data_message(public_key_pem.as_bytes())).await;
+                data = match self.read_packet().await? {
+                    Some(rp) => rp.get_auth_switch_response(),
+                    None => return Ok(false),
+                };
+            }
+            match auth::decrypt_sha2_password(&private_key, self.core_execution.scramble(), data.as_slice()) {
+                Some(password) => password,
+                None => return Err(auth::access_denied_error(user, host, true)),
             }
         };
-        let bytes = &buf[0..n];
-        let _rp = self.payload_packet(bytes);
 
-        let result = self.core_execution.try_init();
-        if let Err(mysql_error) = result {
-            return Err(mysql_error);
+        self.core_execution.authenticate_password(user, host, password.as_slice())?;
+        Ok(true)
+    }
+
+    pub async fn write_packet_error(&mut self, mysql_error: MysqlError) {
+        let payload = message::error_message(
+            mysql_error.error_number(),
+            mysql_error.sql_state().as_str(),
+            mysql_error.message().as_str(),
+        );
+        self.write_packet(payload).await;
+    }
+
+    /// LOAD DATA LOCAL, the client is asked for the file and sends it in packets ended by an empty packet
+    async fn load_data_local_infile(&mut self, statement: LoadDataStatement) -> MysqlResult<CoreOutput> {
+        if !self.client_capability.contains(metadata::CapabilityFlags::CLIENT_LOCAL_FILES) {
+            return Err(MysqlError::new_server_error(
+                3948,
+                "42000",
+                "Loading local data is disabled; this must be enabled on both the client and server sides",
+            ));
         }
 
+        self.write_packet(message::local_infile_request_message(statement.file_name.as_str())).await;
+        let file_name = statement.file_name.clone();
+        let (sender, receiver) = tokio::sync::mpsc::channel(LOCAL_INFILE_QUEUED_PACKETS);
+        let load = self.core_execution.start_load_data_local(statement, receiver);
+        let result = self.read_local_infile(sender).await;
+        let output = self.core_execution.finish_load_data_local(load).await;
+        let length = result?;
+        log::debug!("read {} bytes of the local infile: {}", length, file_name);
+        output
+    }
+
+    /// Send the packets of the file of LOAD DATA LOCAL to the load as they are received, the empty packet ends the file.
+    /// The packets are read to the end even if the load stopped, the error of the connection is sent to the load too
+    async fn read_local_infile(&mut self, sender: tokio::sync::mpsc::Sender<io::Result<Vec<u8>>>) -> MysqlResult<usize> {
+        let mut length = 0;
+        loop {
+            let request_payload = match self.read_packet().await {
+                Ok(Some(request_payload)) => request_payload,
+                result => {
+                    let mysql_error = match result {
+                        Err(mysql_error) => mysql_error,
+                        _ => MysqlError::new_global_error(
+                            mysql_error_code::CR_SERVER_LOST as u16,
+                            "Lost connection to MySQL server during query",
+                        ),
+                    };
+                    let _ = sender.send(Err(io::Error::new(io::ErrorKind::UnexpectedEof, mysql_error.message()))).await;
+                    return Err(mysql_error);
+                }
+            };
+            // the empty packet ends the file
+            if request_payload.get_payload().is_empty() {
+                return Ok(length);
+            }
+            length += request_payload.get_payload().len();
+            // the load which stopped at an error drops the receiver, the rest of the file is read and dropped
+            let _ = sender.send(Ok(request_payload.get_payload().to_vec())).await;
+        }
+    }
+
+    /// The outputs of a multi-statement query are sent one after another, the error ends them
+    async fn send_message(&mut self, core_output: CoreOutput) {
+        match core_output {
+            CoreOutput::MultiStatement(outputs) => {
+                let output_count = outputs.len();
+                for (index, result) in outputs.into_iter().enumerate() {
+                    match result {
+                        Ok(core_output) => self.send_output(core_output, index + 1 < output_count).await,
+                        Err(mysql_error) => self.write_packet_error(mysql_error).await,
+                    }
+                }
+            }
+            core_output => self.send_output(core_output, false).await,
+        }
+    }
+
+    /// The autocommit and the transaction of the session in the status of the OK and the EOF packets
+    fn server_status(&self) -> metadata::StatusFlags {
+        let mut status_flags = metadata::StatusFlags::empty();
+        if self.core_execution.autocommit() {
+            status_flags |= metadata::StatusFlags::SERVER_STATUS_AUTOCOMMIT;
+        }
+        if self.core_execution.in_transaction() {
+            status_flags |= metadata::StatusFlags::SERVER_STATUS_IN_TRANS;
+        }
+        status_flags
+    }
+
+    /// The output of a statement, with SERVER_MORE_RESULTS_EXISTS if the output of another statement follows
+    async fn send_output(&mut self, core_output: CoreOutput, more_results: bool) {
+        let mut status_flags = self.server_status();
+        if more_results {
+            status_flags |= metadata::StatusFlags::SERVER_MORE_RESULTS_EXISTS;
+        }
+        match core_output {
+            CoreOutput::FinalCount(FinalCount {
+                affect_rows,
+                last_insert_id,
+                message,
+                warning_count,
+                session_state_changes,
+            }) => {
+                let mut session_state_changes = session_state_changes;
+                if !self.client_capability.contains(metadata::CapabilityFlags::CLIENT_SESSION_TRACK) {
+                    session_state_changes = vec![];
+                }
+                let ok_message = message::ok_message_with_session_state(
+                    affect_rows,
+                    last_insert_id,
+                    status_flags,
+                    warning_count.min(u16::MAX as u64) as u16,
+                    message,
+                    session_state_changes,
+                );
+                self.write_packet(ok_message).await;
+            }
+            CoreOutput::ResultSet(result_set) => {
+                self.send_result_stream(ResultStream::from(result_set), false, more_results).await;
+            }
+            CoreOutput::ResultStream(result_stream) => {
+                self.send_result_stream(result_stream, false, more_results).await;
+            }
+            CoreOutput::ComFieldList(schema_name, table_name, columns) => {
+                for column_def in columns.iter() {
+                    let column = Column::new(schema_name.clone(), table_name.clone(), column_def);
+                    let payload = column.to_response_payload(true);
+                    self.write_packet(payload).await;
+                }
+                self.write_packet(message::eof_message(0, 0)).await;
+            }
+            CoreOutput::ComStmtCursor(schema_ref) => {
+                // the rows are not sent, the client fetches them from the cursor
+                self.write_packet(message::column_count_message(schema_ref.fields().len())).await;
+                for field in schema_ref.fields() {
+                    let payload = Column::from(field).to_response_payload(true);
+                    self.write_packet(payload).await;
+                }
+                let status = metadata::StatusFlags::SERVER_STATUS_CURSOR_EXISTS.bits();
+                self.write_packet(message::eof_message(0, status)).await;
+            }
+            CoreOutput::ComStmtFetch(StmtFetch { schema_ref, rows, last_row_sent }) => {
+                let column_types = schema_ref.fields().iter().map(|field| Column::from(field).column_type()).collect::<Vec<_>>();
+                for row in rows {
+                    self.write_packet(message::binary_row_message(row, &column_types)).await;
+                }
+                let mut status = metadata::StatusFlags::SERVER_STATUS_CURSOR_EXISTS;
+                if last_row_sent {
+                    status |= metadata::StatusFlags::SERVER_STATUS_LAST_ROW_SENT;
+                }
+                self.write_packet(message::eof_message(0, status.bits())).await;
+            }
+            CoreOutput::ComStmtPrepare(StmtPrepare {
+                statement_id,
+                columns,
+                params,
+            }) => {
+                let payload = message::com_stmt_prepare_first_message(
+                    statement_id as u64,
+                    columns.len() as u64,
+                    params.len() as u64,
+                    0,
+                );
+                self.write_packet(payload).await;
+
+                // the definitions are followed by an EOF only if there are any
+                if !params.is_empty() {
+                    for column_def in params {
+                        let payload = column_def.to_response_payload(true);
+                        self.write_packet(payload).await;
+                    }
+                    self.write_packet(message::eof_message(0, 0)).await;
+                }
+                if !columns.is_empty() {
+                    for column_def in columns {
+                        let payload = column_def.to_response_payload(true);
+                        self.write_packet(payload).await;
+                    }
+                    self.write_packet(message::eof_message(0, 0)).await;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The rows of the text protocol for COM_QUERY, the rows of the binary protocol for COM_STMT_EXECUTE.
+    /// The rows are written as the batches are produced, the first batch is read before the columns so the query
+    /// which fails at once only sends its error. The error of a later batch ends the rows instead of the EOF
+    async fn send_result_stream(&mut self, result_stream: ResultStream, binary_protocol: bool, more_results: bool) {
+        let ResultStream { schema_ref, mut stream, warning_count } = result_stream;
+        let session_warning_count = self.core_execution.warning_count();
+        let mut next_batch = stream.next().await;
+        if let Some(Err(mysql_error)) = next_batch {
+            self.core_execution.push_stream_error(&mysql_error);
+            self.write_packet_error(mysql_error).await;
+            return;
+        }
+
+        let payload = message::column_count_message(schema_ref.fields().len());
+        self.write_packet(payload).await;
+        let columns = schema_ref.fields().iter().map(Column::from).collect::<Vec<_>>();
+        for column in columns.iter() {
+            let payload = column.to_response_payload(true);
+            self.write_packet(payload).await;
+        }
+        self.write_packet(message::eof_message(0, 0)).await;
+        let column_types = columns.iter().map(Column::column_type).collect::<Vec<_>>();
+
+        while let Some(batch) = next_batch {
+            let record_batch = match batch {
+                Ok(record_batch) => record_batch,
+                Err(mysql_error) => {
+                    self.core_execution.push_stream_error(&mysql_error);
+                    self.write_packet_error(mysql_error).await;
+                    return;
+                }
+            };
+            let rows = core_util::convert_record_to_scalar_value(record_batch.clone());
+            for row_index in 0..record_batch.num_rows() {
+                let row = rows.get(row_index).unwrap();
+                let payload = if binary_protocol {
+                    // the client pads the zerofill integers of the binary rows
+                    message::binary_row_message(row.clone(), &column_types)
+                } else {
+                    let row = row
+                        .iter()
+                        .zip(columns.iter())
+                        .map(|(scalar_value, column)| column.format_text_value(scalar_value.clone()))
+                        .collect::<Vec<_>>();
+                    message::row_message(row)
+                };
+                self.write_packet(payload).await;
+            }
+            next_batch = stream.next().await;
+        }
+        // the warnings raised while the rows were produced are counted too
+        let warning_count = warning_count + self.core_execution.warning_count().saturating_sub(session_warning_count);
+        let warning_count = warning_count.min(u16::MAX as u64) as u16;
+        // the EOF of the rows tells the client whether another result follows
+        let mut status = self.server_status();
+        if more_results {
+            status |= metadata::StatusFlags::SERVER_MORE_RESULTS_EXISTS;
+        }
+        self.write_packet(message::eof_message(warning_count, status.bits())).await;
+    }
+}
+
+#[async_trait]
+impl FrontEnd for Handle {
+    fn core_execution(&mut self) -> &mut Execution {
+        &mut self.core_execution
+    }
+
+    async fn handshake(&mut self) -> MysqlResult<()> {
+        let connection_id = self.core_execution.connection_id();
+        let handshark_message =
+            message::handshark_message(self.tls_acceptor.is_some(), connection_id, self.core_execution.scramble());
+        self.write_packet(handshark_message).await;
+
+        let mut rp = match self.read_packet().await? {
+            Some(rp) => rp,
+            None => return Ok(()),
+        };
+
+        // the SSL request is the start of the handshake response, the whole response follows on the TLS connection
+        let capability_flags = metadata::CapabilityFlags::from_bits_truncate(rp.get_client_capability_flags()?);
+        if capability_flags.contains(metadata::CapabilityFlags::CLIENT_SSL) && !self.socket.is_tls() {
+            let tls_acceptor = match &self.tls_acceptor {
+                Some(tls_acceptor) => tls_acceptor.clone(),
+                None => {
+                    return Err(MysqlError::new_global_error(2026, "SSL connection error: SSL is not enabled on the server"));
+                }
+            };
+            if let Err(error) = self.socket.upgrade(&tls_acceptor).await {
+                // the connection is closed, there is no way to send the error
+                log::error!("SSL connection error: {:?}", error);
+                return Ok(());
+            }
+            log::debug!("the connection is upgraded to TLS");
+
+            rp = match self.read_packet().await? {
+                Some(rp) => rp,
+                None => return Ok(()),
+            };
+        }
+
+        let handshake_response = rp.get_handshake_response()?;
+        self.client_capability = handshake_response.capability_flags;
+        self.core_execution.set_client_multi_statements(
+            self.client_capability.contains(metadata::CapabilityFlags::CLIENT_MULTI_STATEMENTS),
+        );
+        self.core_execution.set_client_found_rows(
+            self.client_capability.contains(metadata::CapabilityFlags::CLIENT_FOUND_ROWS),
+        );
+        self.core_execution.set_client_interactive(
+            self.client_capability.contains(metadata::CapabilityFlags::CLIENT_INTERACTIVE),
+        );
+        let max_packet_size = self.core_execution.set_client_max_packet_size(handshake_response.max_packet_size);
+        self.packet_message.with_max_packet_size(max_packet_size as usize);
+
+        let host = match self.socket.peer_addr() {
+            Ok(peer_addr) => peer_addr.ip().to_string(),
+            Err(_) => "".to_string(),
+        };
+
+        let mut auth_response = handshake_response.auth_response.clone();
+        let is_sha2_password = self.client_capability.contains(metadata::CapabilityFlags::CLIENT_PLUGIN_AUTH)
+            && handshake_response.auth_plugin_name.as_deref() == Some(message::AUTH_PLUGIN_NAME_CACHING_SHA2_PASSWORD);
+        if is_sha2_password {
+            let authenticated = self
+                .authenticate_sha2_password(handshake_response.username.as_str(), host.as_str(), auth_response)
+                .await?;
+            if !authenticated {
+                return Ok(());
+            }
+        } else {
+            if let Some(auth_switch_message) = message::auth_switch_message(&handshake_response, self.core_execution.scramble()) {
+                log::debug!("switch the auth plugin of the client: {:?}", handshake_response.auth_plugin_name);
+                self.write_packet(auth_switch_message).await;
+
+                let rp = match self.read_packet().await? {
+                    Some(rp) => rp,
+                    None => return Ok(()),
+                };
+                auth_response = rp.get_auth_switch_response();
+            }
+            self.core_execution.authenticate(handshake_response.username.as_str(), host.as_str(), auth_response.as_slice())?;
+        }
+
+        self.start_session(
+            handshake_response.username.as_str(),
+            host.as_str(),
+            handshake_response.connect_attrs.clone(),
+            handshake_response.database.clone(),
+        )
+        .await?;
+
         let ok_message = message::ok_message(
             0,
             0,
-            metadata::StatusFlags::SERVER_STATUS_AUTOCOMMIT,
+            self.server_status(),
             0,
             "success".to_string(),
         );
         self.write_packet(ok_message).await;
 
+        // the packets after the OK of the handshake are compressed
+        if self.client_capability.contains(metadata::CapabilityFlags::CLIENT_COMPRESS) {
+            self.compressed_packet = Some(packet::CompressedPacket::new());
+        }
+
         self.packet_message.sequence_init();
 
         Ok(())
     }
 
-    pub async fn write_packet_error(&mut self, mysql_error: MysqlError) {
-        let payload = message::error_message(
-            mysql_error.error_number(),
-            mysql_error.sql_state().as_str(),
-            mysql_error.message().as_str(),
-        );
-        self.write_packet(payload).await;
+    async fn write_error(&mut self, mysql_error: MysqlError) {
+        self.write_packet_error(mysql_error).await;
     }
 
-    pub async fn exec_command(&mut self) {
-        let mut buf = [0; 10240];
+    async fn exec_command(&mut self) {
+        let connection_id = self.core_execution.connection_id();
+        let connection_token = self.core_execution.connection_token();
 
         loop {
-            let n = match self.socket.read(&mut buf).await {
-                Ok(n) if n == 0 => break,
-                Ok(n) => n,
-                Err(e) => {
-                    log::error!("failed to read from socket; err = {:?}", e);
+            // every command starts a new sequence
+            self.packet_message.sequence_init();
+            if let Some(compressed_packet) = self.compressed_packet.as_mut() {
+                compressed_packet.sequence_init();
+            }
+
+            // the connection killed by KILL CONNECTION is closed, while it waits for the next command too,
+            // and the connection idle for wait_timeout is closed with an error
+            let wait_timeout = self.core_execution.wait_timeout();
+            let result = tokio::select! {
+                biased;
+                _ = connection_token.cancelled() => {
+                    log::debug!("the connection {} is killed", connection_id);
                     break;
                 }
+                _ = tokio::time::sleep(wait_timeout) => {
+                    log::debug!("the connection {} is idle for {:?}", connection_id, wait_timeout);
+                    Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_CLIENT_INTERACTION_TIMEOUT as u16,
+                        "HY000",
+                        "The client was disconnected by the server because of inactivity. See wait_timeout and interactive_timeout for configuring this behavior.",
+                    ))
+                }
+                result = self.read_packet() => result,
+            };
+            let request_payload = match result {
+                Ok(Some(request_payload)) => request_payload,
+                Ok(None) => break,
+                Err(mysql_error) => {
+                    // the packet too large and the idle connection are closed after the error, as in mysql
+                    log::error!("failed to read the packet; err = {}", mysql_error);
+                    self.write_packet_error(mysql_error).await;
+                    break;
+                }
+            };
+            let command_id = match request_payload.get_command_id() {
+                Ok(command_id) => command_id,
+                Err(mysql_error) => {
+                    self.write_packet_error(mysql_error).await;
+                    continue;
+                }
             };
-            let bytes = &buf[0..n];
-
-            let request_payload = self.payload_packet(bytes);
-            let command_id = request_payload.get_command_id();
             log::debug!("command id: {}", command_id);
 
             let result = match command_id {
                 0x01 => {
-                    // quit
+                    // quit, the connection is closed after the loop
                     break;
                 }
                 0x02 => {
@@ -213,11 +696,41 @@ impl Handle {
                     };
                     log::debug!("set db name: {}", table_name);
 
-                    let table_name = table_name.trim_end_matches("\x00").to_string();
+                    // the table name is ended by NUL, the wildcard of the column names follows it
+                    let (table_name, wildcard) = match table_name.split_once('\x00') {
+                        Some((table_name, wildcard)) => (table_name, wildcard.trim_end_matches('\x00')),
+                        None => (table_name.as_str(), ""),
+                    };
                     self.core_execution
-                        .com_field_list(table_name.as_str())
+                        .com_field_list(table_name, wildcard)
                         .await
                 }
+                0x0c => {
+                    // ComProcessKill
+                    match request_payload.get_process_kill() {
+                        Ok(bytes) => self.core_execution.com_process_kill(bytes),
+                        Err(mysql_error) => Err(mysql_error),
+                    }
+                }
+                0x11 => {
+                    // ComChangeUser, the connection is closed if the auth of the new user fails
+                    match self.change_user(&request_payload).await {
+                        Ok(Some(core_output)) => Ok(core_output),
+                        Ok(None) => break,
+                        Err(mysql_error) => {
+                            self.write_packet_error(mysql_error).await;
+                            break;
+                        }
+                    }
+                }
+                0x1f => {
+                    // ComResetConnection
+                    self.core_execution.reset_connection()
+                }
+                0x0e => {
+                    // ComPing
+                    self.core_execution.com_ping()
+                }
                 0x16 => {
                     // StmpPrepare
                     let sql = match request_payload.get_query_sql().to_str() {
@@ -232,10 +745,63 @@ impl Handle {
                     self.core_execution.com_stmt_prepare(sql.as_str()).await
                 }
                 0x17 => {
-                    self.core_execution.com_stmt_execute(request_payload.get_stmt_execute().unwrap()).await
+                    // StmtExecute, the result set is sent with the binary rows
+                    let result = match request_payload.get_stmt_execute() {
+                        Ok(bytes) => self.core_execution.com_stmt_execute(bytes).await,
+                        Err(mysql_error) => Err(mysql_error),
+                    };
+                    match result {
+                        Ok(CoreOutput::ResultSet(result_set)) => {
+                            self.send_result_stream(ResultStream::from(result_set), true, false).await;
+                            continue;
+                        }
+                        Ok(CoreOutput::ResultStream(result_stream)) => {
+                            self.send_result_stream(result_stream, true, false).await;
+                            continue;
+                        }
+                        _ => result,
+                    }
+                }
+                0x18 => {
+                    // StmtSendLongData has no response
+                    if let Ok(bytes) = request_payload.get_stmt_send_long_data() {
+                        let _ = self.core_execution.com_stmt_send_long_data(bytes).await;
+                    }
+                    continue;
                 }
                 0x19 => {
-                    self.core_execution.com_stmt_close(request_payload.get_stmt_close().unwrap()).await
+                    // StmtClose has no response
+                    if let Ok(bytes) = request_payload.get_stmt_close() {
+                        let _ = self.core_execution.com_stmt_close(bytes).await;
+                    }
+                    continue;
+                }
+                0x1c => {
+                    // StmtFetch, the next rows of the cursor opened by StmtExecute
+                    match request_payload.get_stmt_fetch() {
+                        Ok(bytes) => self.core_execution.com_stmt_fetch(bytes).await,
+                        Err(mysql_error) => Err(mysql_error),
+                    }
+                }
+                0x1b => {
+                    // ComSetOption, CLIENT_MULTI_STATEMENTS on or off for the session, answered by EOF
+                    match request_payload.get_set_option() {
+                        Ok(option @ 0x00) | Ok(option @ 0x01) => {
+                            let multi_statements = option == 0x00;
+                            self.core_execution.set_client_multi_statements(multi_statements);
+                            self.write_packet(message::eof_message(0, 0)).await;
+                            continue;
+                        }
+                        Ok(_) => Err(MysqlError::new_server_error(1047, "08S01", "Unknown command")),
+                        Err(mysql_error) => Err(mysql_error),
+                    }
+                }
+                0x1a => {
+                    // StmtReset
+                    match request_payload.get_stmt_reset() {
+                        Ok(bytes) => self.core_execution.com_stmt_reset(bytes).await,
+                        Err(mysql_error) => Err(mysql_error),
+                    }
                 }
                 _ => {
                     log::error!(
@@ -246,6 +812,12 @@ impl Handle {
                 }
             };
 
+            // LOAD DATA LOCAL reads the file from the client before its result
+            let result = match result {
+                Ok(CoreOutput::LoadDataLocalInfile(statement)) => self.load_data_local_infile(statement).await,
+                result => result,
+            };
+
             match result {
                 Ok(core_output) => {
                     self.send_message(core_output).await;
@@ -254,75 +826,19 @@ impl Handle {
                     self.write_packet_error(mysql_error).await;
                 }
             }
-
-            self.packet_message.sequence_init();
         }
 
         log::debug!("loop break");
+        self.close().await;
     }
 
-    async fn send_message(&mut self, core_output: CoreOutput) {
-        match core_output {
-            CoreOutput::FinalCount(FinalCount {
-                affect_rows,
-                last_insert_id,
-                message,
-            }) => {
-                let ok_message = message::ok_message(
-                    affect_rows,
-                    last_insert_id,
-                    metadata::StatusFlags::SERVER_STATUS_AUTOCOMMIT,
-                    0,
-                    message,
-                );
-                self.write_packet(ok_message).await;
-            }
-            CoreOutput::ResultSet(result_set) => {
-                let schema_ref = result_set.schema_ref;
-                let batches = result_set.record_batches;
-
-                let payload = message::column_count_message(schema_ref.fields().len());
-                self.write_packet(payload).await;
-                for field in schema_ref.fields() {
-                    let column = Column::from(field);
-                    let payload = column.to_response_payload(true);
-                    self.write_packet(payload).await;
-                }
-                self.write_packet(message::eof_message(0, 0)).await;
-
-                for record_batch in batches {
-                    let rows = core_util::convert_record_to_scalar_value(record_batch.clone());
-                    for row_index in 0..record_batch.num_rows() {
-                        let payload = message::row_message(rows.get(row_index).unwrap().clone());
-                        self.write_packet(payload).await;
-                    }
-                }
-                self.write_packet(message::eof_message(0, 0)).await;
-            }
-            CoreOutput::ComFieldList(schema_name, table_name, table_def) => {
-                let columns = table_def.get_columns();
-                for column_def in columns {
-                    let column = Column::new(schema_name.clone(), table_name.clone(), column_def);
-                    let payload = column.to_response_payload(true);
-                    self.write_packet(payload).await;
-                }
-                self.write_packet(message::eof_message(0, 0)).await;
-            }
-            CoreOutput::ComStmtPrepare(StmtPrepare {
-                statement_id,
-                columns,
-                params,
-            }) => {
-                let payload = message::com_stmt_prepare_first_message(1, 0, 2, 0);
-                self.write_packet(payload).await;
-
-                for column_def in params {
-                    let payload = column_def.to_response_payload(true);
-                    self.write_packet(payload).await;
-                }
-                self.write_packet(message::eof_message(0, 0)).await;
-            }
-            _ => {}
+    async fn close(&mut self) {
+        self.core_execution.close();
+        if let Err(e) = self.socket.flush().await {
+            log::debug!("error on flushing the connection, error: {:?}", e);
+        }
+        if let Err(e) = self.socket.shutdown().await {
+            log::debug!("error on shutting down the connection, error: {:?}", e);
         }
     }
 }
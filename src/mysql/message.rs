@@ -5,6 +5,8 @@ use arrow::record_batch::RecordBatch;
 
 use super::response::ResponsePayload;
 use super::{mysql_util, metadata};
+use super::request::HandshakeResponse;
+use crate::core::output::SessionStateChange;
 use datafusion::scalar::ScalarValue;
 
 ///
@@ -28,6 +30,12 @@ pub fn row_message(columns: Vec<ScalarValue>) -> ResponsePayload {
     return payload;
 }
 
+pub fn binary_row_message(columns: Vec<ScalarValue>, column_types: &[metadata::MysqlType]) -> ResponsePayload {
+    let mut payload = ResponsePayload::new(1024);
+    payload.dump_binary_row(columns, column_types);
+    return payload;
+}
+
 pub fn column_count_message(count: usize) -> ResponsePayload {
     let mut payload = ResponsePayload::new(1024);
     payload.dump_length_encoded_int(count as u64);
@@ -44,6 +52,17 @@ pub fn column_count_message(count: usize) -> ResponsePayload {
 /// https://dev.mysql.com/doc/internals/en/packet-OK_Packet.html
 ///
 pub fn ok_message(affect_rows: u64, last_insert_id: u64, status_flags: metadata::StatusFlags, warning_count: u16, msg: String) -> ResponsePayload {
+    ok_message_with_session_state(affect_rows, last_insert_id, status_flags, warning_count, msg, vec![])
+}
+
+///
+/// OK packet carrying the session state info, the client must have negotiated CLIENT_SESSION_TRACK.
+/// https://dev.mysql.com/doc/internals/en/packet-OK_Packet.html
+///
+pub fn ok_message_with_session_state(affect_rows: u64, last_insert_id: u64, mut status_flags: metadata::StatusFlags, warning_count: u16, msg: String, session_state_changes: Vec<SessionStateChange>) -> ResponsePayload {
+    if !session_state_changes.is_empty() {
+        status_flags.insert(metadata::StatusFlags::SERVER_SESSION_STATE_CHANGED);
+    }
     let status = u16::from(status_flags);
     let enclen = mysql_util::length_encoded_int_size(msg.len() as u64) + msg.len() as i32;
 
@@ -56,22 +75,34 @@ pub fn ok_message(affect_rows: u64, last_insert_id: u64, status_flags: metadata:
     if enclen > 0 {
         payload.dump_length_encoded_string(msg.as_bytes());
     }
-
-    // let mut body: Vec<u8> = Vec::with_capacity(32);
-    // body.push(0x00); //
-    // body.extend_from_slice(&[0, 0]); //
-    // // status
-    // // autocommit
-    // let e: u16 = 2;
-    // let f: u16 = 0;
-    // body.extend_from_slice(&[e as u8, f as u8]);
-    // body.extend_from_slice(&[0, 0]); //
-    //
-    // payload.bytes = body;
+    if !session_state_changes.is_empty() {
+        payload.dump_length_encoded_string(session_state_info(session_state_changes).as_slice());
+    }
 
     return payload;
 }
 
+pub fn session_state_info(session_state_changes: Vec<SessionStateChange>) -> Vec<u8> {
+    let mut payload = ResponsePayload::new(64);
+    for session_state_change in session_state_changes {
+        let mut data = ResponsePayload::new(32);
+        let state_type = match session_state_change {
+            SessionStateChange::SystemVariable(name, value) => {
+                data.dump_length_encoded_string(name.as_bytes());
+                data.dump_length_encoded_string(value.as_bytes());
+                metadata::SESSION_TRACK_SYSTEM_VARIABLES
+            }
+            SessionStateChange::Schema(schema_name) => {
+                data.dump_length_encoded_string(schema_name.as_bytes());
+                metadata::SESSION_TRACK_SCHEMA
+            }
+        };
+        payload.bytes.push(state_type);
+        payload.dump_length_encoded_string(data.bytes.as_slice());
+    }
+    payload.bytes
+}
+
 pub fn error_message(code: u16, state: &str, msg: &str) -> ResponsePayload {
     let mut payload = ResponsePayload::new(9 + msg.len());
     payload.bytes.push(0xff); // packet type
@@ -85,6 +116,15 @@ pub fn error_message(code: u16, state: &str, msg: &str) -> ResponsePayload {
 ///
 /// https://dev.mysql.com/doc/internals/en/packet-EOF_Packet.html
 ///
+/// The request of the file of LOAD DATA LOCAL INFILE,
+/// https://dev.mysql.com/doc/internals/en/com-query-response.html#packet-Protocol::LOCAL_INFILE_Request
+pub fn local_infile_request_message(file_name: &str) -> ResponsePayload {
+    let mut payload = ResponsePayload::new(file_name.len() + 1);
+    payload.bytes.push(0xfb);
+    payload.bytes.extend_from_slice(file_name.as_bytes());
+    payload
+}
+
 pub fn eof_message(warning_count: u16, status: u16) -> ResponsePayload {
     // start building payload
     let mut payload = ResponsePayload::new(5);
@@ -94,16 +134,48 @@ pub fn eof_message(warning_count: u16, status: u16) -> ResponsePayload {
     return payload;
 }
 
-pub fn handshark_auth_switch_request() -> ResponsePayload {
+/// The auth plugin of the handshake
+pub const AUTH_PLUGIN_NAME_NATIVE_PASSWORD: &str = "mysql_native_password";
+/// The default auth plugin of the MySQL 8 clients
+pub const AUTH_PLUGIN_NAME_CACHING_SHA2_PASSWORD: &str = "caching_sha2_password";
+
+/// The client which answered the handshake with an unknown auth plugin is switched to mysql_native_password,
+/// https://dev.mysql.com/doc/internals/en/authentication-method-mismatch.html
+pub fn auth_switch_message(handshake_response: &HandshakeResponse, scramble: &[u8]) -> Option<ResponsePayload> {
+    if !handshake_response.capability_flags.contains(metadata::CapabilityFlags::CLIENT_PLUGIN_AUTH) {
+        // the client without plugin auth always answers with mysql_native_password
+        return None;
+    }
+    match handshake_response.auth_plugin_name {
+        Some(ref auth_plugin_name)
+            if auth_plugin_name == AUTH_PLUGIN_NAME_NATIVE_PASSWORD
+                || auth_plugin_name == AUTH_PLUGIN_NAME_CACHING_SHA2_PASSWORD =>
+        {
+            None
+        }
+        _ => Some(handshark_auth_switch_request(scramble)),
+    }
+}
+
+/// The data of the auth plugin during the authentication,
+/// https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_connection_phase_packets_protocol_auth_more_data.html
+pub fn auth_more_data_message(data: &[u8]) -> ResponsePayload {
+    let mut payload = ResponsePayload::new(data.len() + 1);
+    payload.bytes.push(0x01);
+    payload.bytes.extend_from_slice(data);
+    payload
+}
+
+pub fn handshark_auth_switch_request(scramble: &[u8]) -> ResponsePayload {
     let mut payload = ResponsePayload::new(128);
     // fe
     payload.bytes.push(0xfe);
     // plugin name
-    payload.bytes.extend_from_slice("mysql_native_password".as_bytes());
+    payload.bytes.extend_from_slice(AUTH_PLUGIN_NAME_NATIVE_PASSWORD.as_bytes());
     // auth plugin data
     payload.bytes.push(0x00);
     // salt
-    payload.bytes.extend_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14]);
+    payload.bytes.extend_from_slice(scramble);
     // filler [00]
     payload.bytes.push(0);
 
@@ -111,10 +183,17 @@ pub fn handshark_auth_switch_request() -> ResponsePayload {
 
 }
 
-pub fn handshark_message() -> ResponsePayload {
-    let a = 181211;
-    let b = 7078;
-    let c = 27;
+/// The initial handshake, with CLIENT_SSL when the server has the certificate of the TLS connections
+/// and the scramble of the connection as the auth plugin data
+pub fn handshark_message(ssl: bool, connection_id: u32, scramble: &[u8]) -> ResponsePayload {
+    // lower capability flags with CLIENT_COMPRESS
+    let a = 181211 | metadata::CapabilityFlags::CLIENT_COMPRESS.bits() as i32;
+    let mut b = 7078;
+    if ssl {
+        b |= (metadata::CapabilityFlags::CLIENT_SSL.bits() >> 8) as i32;
+    }
+    // upper capability flags with CLIENT_SESSION_TRACK
+    let c = 27 | 0x80;
     let d = 0;
     // start building payload
     let mut payload = ResponsePayload::new(128);
@@ -123,15 +202,15 @@ pub fn handshark_message() -> ResponsePayload {
     // filler [00]
     payload.bytes.push(0); //
     // connection id
-    payload.bytes.extend_from_slice(&[0x0b, 0x00, 0x00, 0x00]);
+    payload.bytes.extend_from_slice(&connection_id.to_le_bytes());
     // auth-plugin-data-part-1
-    payload.bytes.extend_from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+    payload.bytes.extend_from_slice(&scramble[..8]);
     // filler [00]
     payload.bytes.push(0);
     // capability flag lower 2 bytes, using default capability here
     payload.bytes.extend_from_slice(&[a as u8, b as u8]);
     // charset
-    payload.bytes.push(46);
+    payload.bytes.push(metadata::CHARACTER_SET_UTF8MB4_0900_AI_CI);
     // status
     // autocommit
     let e: u16 = 2;
@@ -145,11 +224,11 @@ pub fn handshark_message() -> ResponsePayload {
     // reserved 10 [00]
     payload.bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
     // auth-plugin-data-part-2
-    payload.bytes.extend_from_slice(&[0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14]);
+    payload.bytes.extend_from_slice(&scramble[8..]);
     // filler [00]
     payload.bytes.push(0);
     // auth-plugin name
-    payload.bytes.extend_from_slice("mysql_native_password".as_bytes());
+    payload.bytes.extend_from_slice(AUTH_PLUGIN_NAME_NATIVE_PASSWORD.as_bytes());
     // filler [00]
     payload.bytes.push(0);
 
@@ -1,7 +1,8 @@
 use bitflags;
 use serde_derive::{Deserialize, Serialize};
 
-use arrow::datatypes::{Field};
+use arrow::datatypes::{DataType, Field};
+use datafusion::scalar::ScalarValue;
 use arrow::array::{
     Int8Array,
     Int16Array,
@@ -20,6 +21,8 @@ use super::response::ResponsePayload;
 use crate::mysql::mysql_util;
 use crate::meta::meta_def::SparrowColumnDef;
 use crate::meta::{meta_util, meta_const};
+use crate::util::collation;
+use crate::util::collation::Collation;
 use sqlparser::ast::{ColumnOption, ObjectName};
 
 pub enum ArrayCell<'a> {
@@ -58,18 +61,36 @@ impl Column {
         let column_name = column_def.sql_column.name.value.clone();
 
         let arrow_data_type = meta_util::convert_sql_data_type_to_arrow_data_type(&column_def.sql_column.data_type).unwrap();
-        let mysql_type = mysql_util::convert_arrow_data_type_to_mysql_type(&arrow_data_type).unwrap();
+        let mut mysql_type = mysql_util::convert_arrow_data_type_to_mysql_type(&arrow_data_type).unwrap();
 
         let nullable = column_def.sql_column.options
             .iter()
             .any(|x| x.option == ColumnOption::Null);
-        let flags = if nullable {
+        let mut flags = if nullable {
             ColumnFlags::NO_DEFAULT_VALUE_FLAG
         } else {
             ColumnFlags::NOT_NULL_FLAG
         };
 
-        let column_length = 100000;
+        let (mut column_length, mut decimals) = column_length_and_decimals(&arrow_data_type, 100000);
+        let collation = meta_util::get_column_collation(&column_def.sql_column);
+        let mut character_set = column_character_set(&arrow_data_type, collation, &mut flags);
+        if is_unsigned_data_type(&arrow_data_type) {
+            flags |= ColumnFlags::UNSIGNED_FLAG;
+        }
+        if let Some(width) = meta_util::get_zerofill_width(&column_def.sql_column) {
+            flags |= ColumnFlags::ZEROFILL_FLAG;
+            column_length = width;
+        }
+        let temporal_type = meta_util::convert_sql_data_type(&column_def.sql_column.data_type)
+            .ok()
+            .and_then(|data_type| mysql_util::convert_temporal_data_type_to_mysql_type(data_type.as_str()));
+        if let Some(temporal_type) = temporal_type {
+            mysql_type = temporal_type;
+            column_length = temporal_column_length(temporal_type);
+            decimals = 0;
+            character_set = CHARACTER_SET_BINARY;
+        }
 
         Column {
             schema: schema_name.to_string(),
@@ -77,17 +98,90 @@ impl Column {
             org_table: table_name.to_string(),
             name: column_name.to_string(),
             org_name: column_name.to_string(),
-            character_set: 46,
+            character_set,
             column_length,
             column_type: mysql_type,
             flags,
-            decimals: 8,
+            decimals,
             default_value: None,
         }
     }
 }
 
+/// The text columns are sent with the id of their collation, the default one without it.
+/// The binary columns are sent with the binary character set and the BINARY and BLOB flags,
+/// so the clients read their values as the bytes, the numbers have the binary character set too
+fn column_character_set(data_type: &DataType, collation: Option<&Collation>, flags: &mut ColumnFlags) -> u8 {
+    match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 => match collation {
+            Some(collation) => collation.id,
+            None => CHARACTER_SET_UTF8MB4_0900_AI_CI,
+        },
+        DataType::Binary | DataType::LargeBinary => {
+            *flags |= ColumnFlags::BINARY_FLAG | ColumnFlags::BLOB_FLAG;
+            CHARACTER_SET_BINARY
+        }
+        _ => CHARACTER_SET_BINARY,
+    }
+}
+
+fn is_unsigned_data_type(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64)
+}
+
+/// The DECIMAL column is sent with its scale as the decimals, and the length of the digits,
+/// the sign and the point, so the clients format it as the exact string.
+/// The TINYINT is always TINYINT(1), the clients read it as a boolean.
+/// The FLOAT and the DOUBLE have no fixed decimals, as 31 in mysql.
+/// The dates and the timestamps have the length of their text without the fraction
+fn column_length_and_decimals(data_type: &DataType, column_length: u64) -> (u64, u8) {
+    match data_type {
+        DataType::Decimal(precision, scale) => ((*precision + 2) as u64, *scale as u8),
+        DataType::Boolean | DataType::Int8 => (1, 0),
+        DataType::Float32 => (12, 31),
+        DataType::Float64 => (22, 31),
+        DataType::Binary | DataType::LargeBinary => (column_length, 0),
+        DataType::Date32 | DataType::Date64 => (temporal_column_length(MysqlType::MYSQL_TYPE_DATE), 0),
+        DataType::Timestamp(_, _) => (temporal_column_length(MysqlType::MYSQL_TYPE_DATETIME), 0),
+        _ => (column_length, 8),
+    }
+}
+
+/// The length of the text of the temporal value without the fraction
+fn temporal_column_length(mysql_type: MysqlType) -> u64 {
+    match mysql_type {
+        MysqlType::MYSQL_TYPE_DATE | MysqlType::MYSQL_TYPE_TIME => 10,
+        _ => 19,
+    }
+}
+
 impl Column {
+    pub fn character_set(&self) -> u8 {
+        self.character_set
+    }
+
+    pub fn flags(&self) -> ColumnFlags {
+        self.flags
+    }
+
+    pub fn column_type(&self) -> MysqlType {
+        self.column_type
+    }
+
+    /// The value of the ZEROFILL column is padded with the leading zeros to the column length in the text protocol
+    pub fn format_text_value(&self, scalar_value: ScalarValue) -> ScalarValue {
+        if !self.flags.contains(ColumnFlags::ZEROFILL_FLAG) {
+            return scalar_value;
+        }
+
+        let width = self.column_length as usize;
+        match scalar_value {
+            ScalarValue::Int8(Some(value)) => ScalarValue::Utf8(Some(format!("{:0width$}", value, width = width))),
+            ScalarValue::Int64(Some(value)) => ScalarValue::Utf8(Some(format!("{:0width$}", value, width = width))),
+            _ => scalar_value,
+        }
+    }
+
     pub fn to_response_payload(&self, com_field_list: bool) -> ResponsePayload {
         let mut payload = ResponsePayload::new(1024);
         payload.dump_length_encoded_string(meta_const::CATALOG_NAME.as_bytes());
@@ -127,25 +221,56 @@ impl Column {
 impl From<&Field> for Column {
     fn from(field: &Field) -> Self {
         let column_name = field.name();
-        let mysql_type = mysql_util::convert_arrow_data_type_to_mysql_type(field.data_type()).unwrap();
+        let mut mysql_type = mysql_util::convert_arrow_data_type_to_mysql_type(field.data_type()).unwrap();
 
-        let flags = if field.is_nullable() {
+        let mut flags = if field.is_nullable() {
             ColumnFlags::NO_DEFAULT_VALUE_FLAG
         } else {
             ColumnFlags::NOT_NULL_FLAG
         };
 
+        let (mut column_length, mut decimals) = column_length_and_decimals(field.data_type(), 15);
+        let collation = field
+            .metadata()
+            .as_ref()
+            .and_then(|metadata| metadata.get(meta_const::FIELD_METADATA_COLLATION))
+            .and_then(|collation_name| collation::find_collation(collation_name.as_str()));
+        let mut character_set = column_character_set(field.data_type(), collation, &mut flags);
+        if is_unsigned_data_type(field.data_type()) {
+            flags |= ColumnFlags::UNSIGNED_FLAG;
+        }
+        let zerofill_width = field
+            .metadata()
+            .as_ref()
+            .and_then(|metadata| metadata.get(meta_const::FIELD_METADATA_ZEROFILL_WIDTH))
+            .and_then(|width| width.parse::<u64>().ok());
+        if let Some(width) = zerofill_width {
+            flags |= ColumnFlags::ZEROFILL_FLAG;
+            column_length = width;
+        }
+        let temporal_type = field
+            .metadata()
+            .as_ref()
+            .and_then(|metadata| metadata.get(meta_const::FIELD_METADATA_DATA_TYPE))
+            .and_then(|data_type| mysql_util::convert_temporal_data_type_to_mysql_type(data_type.as_str()));
+        if let Some(temporal_type) = temporal_type {
+            mysql_type = temporal_type;
+            column_length = temporal_column_length(temporal_type);
+            decimals = 0;
+            character_set = CHARACTER_SET_BINARY;
+        }
+
         Self {
             schema: "".to_string(),
             table: "SCHEMATA".to_string(),
             org_table: "schemata".to_string(),
             name: column_name.to_string(),
             org_name: column_name.to_string(),
-            character_set: 33,
-            column_length: 15,
+            character_set,
+            column_length,
             column_type: mysql_type,
             flags,
-            decimals: 8,
+            decimals,
             default_value: None,
         }
     }
@@ -379,3 +504,50 @@ impl From<StatusFlags> for u16 {
         cf.bits as u16
     }
 }
+
+bitflags! {
+    /// MySql client/server capability flags
+    pub struct CapabilityFlags: u32 {
+        const CLIENT_LONG_PASSWORD                  = 0x0000_0001;
+        const CLIENT_FOUND_ROWS                     = 0x0000_0002;
+        const CLIENT_LONG_FLAG                      = 0x0000_0004;
+        const CLIENT_CONNECT_WITH_DB                = 0x0000_0008;
+        const CLIENT_NO_SCHEMA                      = 0x0000_0010;
+        const CLIENT_COMPRESS                       = 0x0000_0020;
+        const CLIENT_ODBC                           = 0x0000_0040;
+        const CLIENT_LOCAL_FILES                    = 0x0000_0080;
+        const CLIENT_IGNORE_SPACE                   = 0x0000_0100;
+        const CLIENT_PROTOCOL_41                    = 0x0000_0200;
+        const CLIENT_INTERACTIVE                    = 0x0000_0400;
+        const CLIENT_SSL                            = 0x0000_0800;
+        const CLIENT_IGNORE_SIGPIPE                 = 0x0000_1000;
+        const CLIENT_TRANSACTIONS                   = 0x0000_2000;
+        const CLIENT_RESERVED                       = 0x0000_4000;
+        const CLIENT_SECURE_CONNECTION              = 0x0000_8000;
+        const CLIENT_MULTI_STATEMENTS               = 0x0001_0000;
+        const CLIENT_MULTI_RESULTS                  = 0x0002_0000;
+        const CLIENT_PS_MULTI_RESULTS               = 0x0004_0000;
+        const CLIENT_PLUGIN_AUTH                    = 0x0008_0000;
+        const CLIENT_CONNECT_ATTRS                  = 0x0010_0000;
+        const CLIENT_PLUGIN_AUTH_LENENC_CLIENT_DATA = 0x0020_0000;
+        const CLIENT_CAN_HANDLE_EXPIRED_PASSWORDS   = 0x0040_0000;
+
+        /// Expects the server to send session-state changes after a OK packet.
+        const CLIENT_SESSION_TRACK                  = 0x0080_0000;
+
+        const CLIENT_DEPRECATE_EOF                  = 0x0100_0000;
+    }
+}
+
+/// Session state tracker types, https://dev.mysql.com/doc/internals/en/packet-OK_Packet.html
+pub const SESSION_TRACK_SYSTEM_VARIABLES: u8 = 0x00;
+pub const SESSION_TRACK_SCHEMA: u8 = 0x01;
+
+/// The cursor type flags of COM_STMT_EXECUTE, https://dev.mysql.com/doc/internals/en/com-stmt-execute.html
+pub const CURSOR_TYPE_NO_CURSOR: u8 = 0x00;
+pub const CURSOR_TYPE_READ_ONLY: u8 = 0x01;
+
+/// Collation id of utf8mb4_0900_ai_ci, the default utf8mb4 collation, https://dev.mysql.com/doc/internals/en/character-set.html
+pub const CHARACTER_SET_UTF8MB4_0900_AI_CI: u8 = 255;
+/// Collation id of binary, the character set of the BINARY, VARBINARY and BLOB columns
+pub const CHARACTER_SET_BINARY: u8 = 63;
@@ -1,3 +1,4 @@
+pub mod auth;
 pub mod command;
 pub mod dump;
 pub mod error;
@@ -10,4 +11,5 @@ pub mod packet;
 pub mod request;
 pub mod response;
 pub mod statement;
+pub mod tls;
 pub mod mysql_type_code;
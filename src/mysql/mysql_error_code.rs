@@ -1,3 +1,42 @@
 
 // https://dev.mysql.com/doc/refman/5.7/en/error-messages-client.html
-pub const CR_MALFORMED_PACKET:i64 = 2027;
\ No newline at end of file
+pub const CR_MALFORMED_PACKET:i64 = 2027;
+pub const CR_SERVER_LOST:i64 = 2013;
+
+// https://dev.mysql.com/doc/mysql-errors/8.0/en/server-error-reference.html
+pub const ER_DBACCESS_DENIED_ERROR:i64 = 1044;
+pub const ER_TABLE_EXISTS_ERROR:i64 = 1050;
+pub const ER_BAD_TABLE_ERROR:i64 = 1051;
+pub const ER_DUP_FIELDNAME:i64 = 1060;
+pub const ER_DUP_ENTRY:i64 = 1062;
+pub const ER_KILL_DENIED_ERROR:i64 = 1095;
+pub const ER_NONEXISTING_GRANT:i64 = 1141;
+pub const ER_TABLEACCESS_DENIED_ERROR:i64 = 1142;
+pub const ER_NET_PACKET_TOO_LARGE:i64 = 1153;
+pub const ER_LOCK_WAIT_TIMEOUT:i64 = 1205;
+pub const ER_LOCK_DEADLOCK:i64 = 1213;
+pub const ER_SPECIFIC_ACCESS_DENIED_ERROR:i64 = 1227;
+pub const ER_NOT_SUPPORTED_YET:i64 = 1235;
+pub const ER_VARIABLE_IS_READONLY:i64 = 1238;
+pub const ER_WRONG_FK_DEF:i64 = 1239;
+pub const ER_WRONG_OBJECT:i64 = 1347;
+pub const ER_VIEW_SELECT_TMPTABLE:i64 = 1352;
+pub const ER_VIEW_WRONG_LIST:i64 = 1353;
+pub const ER_VIEW_INVALID:i64 = 1356;
+pub const ER_CANNOT_USER:i64 = 1396;
+pub const ER_ROW_IS_REFERENCED_2:i64 = 1451;
+pub const ER_NO_REFERENCED_ROW_2:i64 = 1452;
+pub const ER_DROP_INDEX_FK:i64 = 1553;
+pub const ER_TRUNCATE_ILLEGAL_FK:i64 = 1701;
+pub const ER_FK_NO_INDEX_PARENT:i64 = 1822;
+pub const ER_FK_CANNOT_OPEN_PARENT:i64 = 1824;
+pub const ER_FK_DUP_NAME:i64 = 1826;
+pub const ER_FK_COLUMN_NOT_NULL:i64 = 1830;
+pub const ER_MALFORMED_PACKET:i64 = 1835;
+pub const ER_FK_DEPTH_EXCEEDED:i64 = 3008;
+pub const ER_CTE_RECURSIVE_REQUIRES_UNION:i64 = 3573;
+pub const ER_CTE_MAX_RECURSION_DEPTH:i64 = 3636;
+pub const ER_FK_CANNOT_DROP_PARENT:i64 = 3730;
+pub const ER_FK_NO_COLUMN_PARENT:i64 = 3734;
+pub const ER_FK_INCOMPATIBLE_COLUMNS:i64 = 3780;
+pub const ER_CLIENT_INTERACTION_TIMEOUT:i64 = 4031;
\ No newline at end of file
@@ -9,4 +9,9 @@ pub const TYPE_NULL:u64 = 6;
 pub const TYPE_INT64:u64 = 8;
 pub const TYPE_VARCHAR:u64 = 15;
 pub const TYPE_FLOAT64_2:u64 = 246;
-pub const TYPE_VARCHAR_2:u64 = 253;
\ No newline at end of file
+pub const TYPE_TINY_BLOB:u64 = 249;
+pub const TYPE_MEDIUM_BLOB:u64 = 250;
+pub const TYPE_LONG_BLOB:u64 = 251;
+pub const TYPE_BLOB:u64 = 252;
+pub const TYPE_VARCHAR_2:u64 = 253;
+pub const TYPE_STRING:u64 = 254;
\ No newline at end of file
@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
 use bstr::ByteSlice;
 use byteorder::{ByteOrder, LittleEndian};
 
 use datafusion::scalar::ScalarValue;
 use sqlparser::ast::{Expr as SQLExpr, Value};
 
+use crate::meta::meta_const;
 use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::mysql::mysql_error_code;
 use crate::mysql::mysql_type_code;
@@ -29,23 +32,47 @@ pub fn length_encoded_int_size(n: u64) -> i32 {
 ///
 pub fn convert_arrow_data_type_to_mysql_type(data_type: &ArrowDataType) -> MysqlResult<MysqlType> {
     match data_type {
-        ArrowDataType::Int8 => Ok(MysqlType::MYSQL_TYPE_TINY),
-        ArrowDataType::Int16 => Ok(MysqlType::MYSQL_TYPE_SHORT),
-        ArrowDataType::Int32 => Ok(MysqlType::MYSQL_TYPE_LONG),
-        ArrowDataType::Int64 => Ok(MysqlType::MYSQL_TYPE_LONGLONG),
+        ArrowDataType::Boolean | ArrowDataType::Int8 | ArrowDataType::UInt8 => Ok(MysqlType::MYSQL_TYPE_TINY),
+        ArrowDataType::Int16 | ArrowDataType::UInt16 => Ok(MysqlType::MYSQL_TYPE_SHORT),
+        ArrowDataType::Int32 | ArrowDataType::UInt32 => Ok(MysqlType::MYSQL_TYPE_LONG),
+        ArrowDataType::Int64 | ArrowDataType::UInt64 => Ok(MysqlType::MYSQL_TYPE_LONGLONG),
+        ArrowDataType::Float32 => Ok(MysqlType::MYSQL_TYPE_FLOAT),
+        ArrowDataType::Float64 => Ok(MysqlType::MYSQL_TYPE_DOUBLE),
         ArrowDataType::Utf8 => Ok(MysqlType::MYSQL_TYPE_STRING),
+        ArrowDataType::Decimal(_, _) => Ok(MysqlType::MYSQL_TYPE_NEWDECIMAL),
+        ArrowDataType::Binary | ArrowDataType::LargeBinary => Ok(MysqlType::MYSQL_TYPE_BLOB),
+        ArrowDataType::Date32 | ArrowDataType::Date64 => Ok(MysqlType::MYSQL_TYPE_DATE),
+        ArrowDataType::Timestamp(_, _) => Ok(MysqlType::MYSQL_TYPE_DATETIME),
         _ => Ok(MysqlType::MYSQL_TYPE_STRING),
     }
 }
 
+/// The mysql type of the DATE, TIME, DATETIME and TIMESTAMP columns by the text of their data type,
+/// the TIME and the TIMESTAMP can not be told from the arrow type of their values
+pub fn convert_temporal_data_type_to_mysql_type(data_type: &str) -> Option<MysqlType> {
+    match data_type {
+        meta_const::MYSQL_DATA_TYPE_DATE => Some(MysqlType::MYSQL_TYPE_DATE),
+        meta_const::MYSQL_DATA_TYPE_TIME => Some(MysqlType::MYSQL_TYPE_TIME),
+        meta_const::MYSQL_DATA_TYPE_DATETIME => Some(MysqlType::MYSQL_TYPE_DATETIME),
+        meta_const::MYSQL_DATA_TYPE_TIMESTAMP => Some(MysqlType::MYSQL_TYPE_TIMESTAMP),
+        _ => None,
+    }
+}
+
 pub fn parse_stmt_execute_args(
+    num_params: usize,
     null_bitmap: Vec<u8>,
     param_types: Vec<u8>,
     param_values: Vec<u8>,
+    long_data: &HashMap<usize, Vec<u8>>,
 ) -> MysqlResult<Vec<SQLExpr>> {
-    let num_params = 2;
-
     let mut values = vec![];
+    if null_bitmap.len() < (num_params + 7) / 8 || param_types.len() < num_params * 2 {
+        return Err(MysqlError::new_global_error(
+            mysql_error_code::CR_MALFORMED_PACKET as u16,
+            format!("malformed packet error").as_str(),
+        ));
+    }
 
     let mut param_type_pos = 0;
     let mut param_value_pos = 0;
@@ -56,8 +83,15 @@ pub fn parse_stmt_execute_args(
         let type_flag = param_types[param_type_pos];
         param_type_pos += 1;
 
-        if (null_bitmap[i / 8] & (1 << (i % 8) as u64)) > 0 {
-            SQLExpr::Value(Value::Null);
+        // the parameter sent by COM_STMT_SEND_LONG_DATA has no value in the packet
+        if let Some(data) = long_data.get(&i) {
+            values.push(SQLExpr::Value(Value::SingleQuotedString(String::from_utf8_lossy(data.as_slice()).to_string())));
+            continue;
+        }
+
+        // the NULL parameter has its type but no value
+        if (null_bitmap[i / 8] & (1 << (i % 8))) > 0 {
+            values.push(SQLExpr::Value(Value::Null));
             continue;
         }
 
@@ -65,8 +99,38 @@ pub fn parse_stmt_execute_args(
 
         let value = match mysql_type {
             mysql_type_code::TYPE_NULL => SQLExpr::Value(Value::Null),
+            mysql_type_code::TYPE_INT8 => {
+                let bytes = read_fixed_bytes(&param_values, &mut param_value_pos, 1)?;
+                if is_unsigned {
+                    SQLExpr::Value(Value::Number(bytes[0].to_string(), false))
+                } else {
+                    SQLExpr::Value(Value::Number((bytes[0] as i8).to_string(), false))
+                }
+            }
+            mysql_type_code::TYPE_INT16 => {
+                let val = LittleEndian::read_u16(read_fixed_bytes(&param_values, &mut param_value_pos, 2)?);
+                if is_unsigned {
+                    SQLExpr::Value(Value::Number(val.to_string(), false))
+                } else {
+                    SQLExpr::Value(Value::Number((val as i16).to_string(), false))
+                }
+            }
+            mysql_type_code::TYPE_FLOAT32 => {
+                let val = LittleEndian::read_f32(read_fixed_bytes(&param_values, &mut param_value_pos, 4)?);
+                SQLExpr::Value(Value::Number(val.to_string(), false))
+            }
+            mysql_type_code::TYPE_FLOAT64 => {
+                let val = LittleEndian::read_f64(read_fixed_bytes(&param_values, &mut param_value_pos, 8)?);
+                SQLExpr::Value(Value::Number(val.to_string(), false))
+            }
             mysql_type_code::TYPE_INT32 => {
                 let end = param_value_pos+4;
+                if end > param_values.len() {
+                    return Err(MysqlError::new_global_error(
+                        mysql_error_code::CR_MALFORMED_PACKET as u16,
+                        format!("malformed packet error").as_str(),
+                    ));
+                }
                 let val = LittleEndian::read_u32(param_values[param_value_pos..end].as_bytes());
                 param_value_pos = end;
 
@@ -78,6 +142,12 @@ pub fn parse_stmt_execute_args(
             }
             mysql_type_code::TYPE_INT64 => {
                 let end = param_value_pos+8;
+                if end > param_values.len() {
+                    return Err(MysqlError::new_global_error(
+                        mysql_error_code::CR_MALFORMED_PACKET as u16,
+                        format!("malformed packet error").as_str(),
+                    ));
+                }
                 let val = LittleEndian::read_u64(param_values[param_value_pos..end].as_bytes());
                 param_value_pos = end;
 
@@ -87,7 +157,16 @@ pub fn parse_stmt_execute_args(
                     SQLExpr::Value(Value::Number((val as i64).to_string(), false))
                 }
             }
-            mysql_type_code::TYPE_VARCHAR_2 | mysql_type_code::TYPE_FLOAT64_2 => {
+            // the strings, the blobs and the decimals are sent as length encoded strings
+            mysql_type_code::TYPE_DECIMAL
+            | mysql_type_code::TYPE_VARCHAR
+            | mysql_type_code::TYPE_FLOAT64_2
+            | mysql_type_code::TYPE_TINY_BLOB
+            | mysql_type_code::TYPE_MEDIUM_BLOB
+            | mysql_type_code::TYPE_LONG_BLOB
+            | mysql_type_code::TYPE_BLOB
+            | mysql_type_code::TYPE_VARCHAR_2
+            | mysql_type_code::TYPE_STRING => {
                 let last_bytes = param_values[param_value_pos..].to_vec();
                 let result = parse_length_encoded_bytes(last_bytes);
                 match result {
@@ -129,6 +208,20 @@ pub fn parse_stmt_execute_args(
     Ok(values)
 }
 
+/// The bytes of the fixed length value at the position, the position is moved after the value
+fn read_fixed_bytes<'a>(param_values: &'a [u8], param_value_pos: &mut usize, len: usize) -> MysqlResult<&'a [u8]> {
+    let end = *param_value_pos + len;
+    if end > param_values.len() {
+        return Err(MysqlError::new_global_error(
+            mysql_error_code::CR_MALFORMED_PACKET as u16,
+            format!("malformed packet error").as_str(),
+        ));
+    }
+    let bytes = &param_values[*param_value_pos..end];
+    *param_value_pos = end;
+    Ok(bytes)
+}
+
 pub fn parse_length_encoded_bytes(bytes: Vec<u8>) -> Option<(usize, Vec<u8>)> {
     let result = parse_length_encoded_int(bytes.clone());
     match result {
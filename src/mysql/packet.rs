@@ -1,22 +1,63 @@
+use std::io::{Read, Write};
+
 use byteorder::{LittleEndian};
-use byteorder::WriteBytesExt;
+use byteorder::{ByteOrder, WriteBytesExt};
 use bytes::Bytes;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 
-use datafusion::error::{Result};
-
+use super::error::{MysqlError, MysqlResult};
+use super::mysql_error_code;
 use super::response::ResponsePayload;
 
+/// The max payload length of a single packet, a longer payload is split into several packets
+pub const MAX_PAYLOAD_LENGTH: usize = 0xff_ffff;
+/// The payload length and the sequence id
+pub const HEADER_LENGTH: usize = 4;
+/// The compressed payload length, the sequence id and the payload length before the compression
+pub const COMPRESSED_HEADER_LENGTH: usize = 7;
+/// The default of max_allowed_packet, the payloads of the client are at most this long
+pub const DEFAULT_MAX_ALLOWED_PACKET: usize = 67108864;
+
+/// The length of the first packet with its header if the bytes hold the whole packet,
+/// the payload length is the first 3 bytes of the header
+pub fn complete_packet_length(bytes: &[u8], header_length: usize) -> Option<usize> {
+    if bytes.len() < header_length {
+        return None;
+    }
+    let packet_length = header_length + LittleEndian::read_u24(&bytes[0..3]) as usize;
+    if bytes.len() < packet_length {
+        return None;
+    }
+    Some(packet_length)
+}
+
 pub struct PacketMessage {
     sequence_id: u8,
+    /// The longest payload the client accepts, 0 is no limit
+    max_packet_size: usize,
 }
 
 impl PacketMessage {
     pub fn new() -> PacketMessage {
-        PacketMessage { sequence_id: 0 }
+        PacketMessage { sequence_id: 0, max_packet_size: 0 }
+    }
+
+    /// The max packet size negotiated with the client, a longer payload is not sent
+    pub fn with_max_packet_size(&mut self, max_packet_size: usize) {
+        self.max_packet_size = max_packet_size;
     }
 
+    /// The sequence id wraps around after 255, a large result set has more packets
     pub fn sequence_increase(&mut self) {
-        self.sequence_id = self.sequence_id + 1;
+        self.sequence_id = self.sequence_id.wrapping_add(1);
+        log::debug!("sequence_id: {:?}", self.sequence_id);
+    }
+
+    /// The response continues the sequence of the received packet
+    pub fn sequence_receive(&mut self, sequence_id: u8) {
+        self.sequence_id = sequence_id.wrapping_add(1);
         log::debug!("sequence_id: {:?}", self.sequence_id);
     }
 
@@ -24,20 +65,46 @@ impl PacketMessage {
         self.sequence_id = 0;
     }
 
-    pub fn create(&mut self, response_payload: ResponsePayload) -> Result<Bytes> {
-        // create header with length and sequence id
-        let mut header: Vec<u8> = Vec::with_capacity(4);
-        header
-            .write_u32::<LittleEndian>(response_payload.bytes.len() as u32)
-            .unwrap();
-        header.pop(); // we need 3 byte length, so discard last byte
-        header.push(self.sequence_id); // sequence_id
+    pub fn sequence_id(&self) -> u8 {
+        self.sequence_id
+    }
+
+    /// The packets of the payload, split at the max payload length. The error 1153 if the payload
+    /// is longer than the max packet size of the client
+    pub fn create(&mut self, response_payload: ResponsePayload) -> MysqlResult<Bytes> {
+        let bytes = response_payload.bytes;
+        if self.max_packet_size > 0 && bytes.len() > self.max_packet_size {
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_NET_PACKET_TOO_LARGE as u16,
+                "08S01",
+                "Got a packet bigger than 'max_allowed_packet' bytes",
+            ));
+        }
+        let mut payload: Vec<u8> = Vec::with_capacity(bytes.len() + 4);
+
+        // a full packet is followed by the next part, an empty packet ends a payload
+        // which is a multiple of the max payload length
+        let mut start = 0;
+        loop {
+            let end = bytes.len().min(start + MAX_PAYLOAD_LENGTH);
 
-        //self.sequence_increase();
+            // create header with length and sequence id
+            let mut header: Vec<u8> = Vec::with_capacity(4);
+            header
+                .write_u32::<LittleEndian>((end - start) as u32)
+                .unwrap();
+            header.pop(); // we need 3 byte length, so discard last byte
+            header.push(self.sequence_id); // sequence_id
 
-        let mut payload: Vec<u8> = Vec::with_capacity(header.len() + response_payload.bytes.len());
-        payload.extend_from_slice(&header);
-        payload.extend_from_slice(&response_payload.bytes);
+            payload.extend_from_slice(&header);
+            payload.extend_from_slice(&bytes[start..end]);
+
+            if end - start < MAX_PAYLOAD_LENGTH {
+                break;
+            }
+            start = end;
+            self.sequence_increase();
+        }
 
         let mem = Bytes::from(payload);
 
@@ -45,6 +112,176 @@ impl PacketMessage {
     }
 }
 
+/// The payloads of the client from the bytes of the socket, which may hold a part of a packet
+/// or several packets. A payload of the max payload length is continued by the next packet of the sequence.
+pub struct PacketReader {
+    /// The bytes of the socket, the compressed packets with the compressed protocol
+    received: Vec<u8>,
+    /// The packets not read yet, after the decompression
+    packets: Vec<u8>,
+    max_allowed_packet: usize,
+}
+
+impl PacketReader {
+    pub fn new() -> PacketReader {
+        PacketReader {
+            received: vec![],
+            packets: vec![],
+            max_allowed_packet: DEFAULT_MAX_ALLOWED_PACKET,
+        }
+    }
+
+    pub fn with_max_allowed_packet(&mut self, max_allowed_packet: usize) {
+        self.max_allowed_packet = max_allowed_packet;
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.received.extend_from_slice(bytes);
+    }
+
+    /// The next payload as a single packet, its header has the sequence id of the last packet of the payload.
+    /// None if the payload is not received yet, an error as soon as the payload is known to be
+    /// longer than max_allowed_packet, the connection is closed then
+    pub fn next_payload(&mut self, compressed_packet: Option<&mut CompressedPacket>) -> MysqlResult<Option<Vec<u8>>> {
+        match compressed_packet {
+            Some(compressed_packet) => {
+                while let Some(length) = complete_packet_length(&self.received, COMPRESSED_HEADER_LENGTH) {
+                    let compressed_packets = self.received.drain(..length).collect::<Vec<u8>>();
+                    self.packets.extend(compressed_packet.decompress(compressed_packets.as_slice(), self.max_allowed_packet)?);
+                }
+            }
+            None => self.packets.append(&mut self.received),
+        }
+
+        let mut position = 0;
+        let mut payload_length = 0;
+        loop {
+            if self.packets.len() < position + HEADER_LENGTH {
+                return Ok(None);
+            }
+            let packet_length = LittleEndian::read_u24(&self.packets[position..position + 3]) as usize;
+            payload_length += packet_length;
+            if payload_length > self.max_allowed_packet {
+                return Err(MysqlError::new_server_error(
+                    mysql_error_code::ER_NET_PACKET_TOO_LARGE as u16,
+                    "08S01",
+                    "Got a packet bigger than 'max_allowed_packet' bytes",
+                ));
+            }
+            if self.packets.len() < position + HEADER_LENGTH + packet_length {
+                return Ok(None);
+            }
+            position += HEADER_LENGTH + packet_length;
+            if packet_length < MAX_PAYLOAD_LENGTH {
+                break;
+            }
+        }
+
+        let packets = self.packets.drain(..position).collect::<Vec<u8>>();
+        let mut payload = Vec::with_capacity(HEADER_LENGTH + payload_length);
+        payload.write_u24::<LittleEndian>(payload_length.min(MAX_PAYLOAD_LENGTH) as u32).unwrap();
+        payload.push(0);
+        let mut start = 0;
+        while start < packets.len() {
+            let packet_length = LittleEndian::read_u24(&packets[start..start + 3]) as usize;
+            payload[3] = packets[start + 3];
+            payload.extend_from_slice(&packets[start + HEADER_LENGTH..start + HEADER_LENGTH + packet_length]);
+            start += HEADER_LENGTH + packet_length;
+        }
+        Ok(Some(payload))
+    }
+}
+
+/// The payloads shorter than this are sent without compression, as the server of mysql
+pub const MIN_COMPRESS_LENGTH: usize = 50;
+
+/// The compressed protocol of CLIENT_COMPRESS, the packets are carried by the compressed packets
+/// which have their own sequence id, https://dev.mysql.com/doc/internals/en/compressed-packet-header.html
+pub struct CompressedPacket {
+    sequence_id: u8,
+}
+
+impl CompressedPacket {
+    pub fn new() -> CompressedPacket {
+        CompressedPacket { sequence_id: 0 }
+    }
+
+    pub fn sequence_init(&mut self) {
+        self.sequence_id = 0;
+    }
+
+    /// The compressed packets of the packets, the compressed length, the sequence id
+    /// and the length before the compression, which is 0 if the payload is not compressed
+    pub fn compress(&mut self, packets: &[u8]) -> Bytes {
+        let mut compressed_packets = Vec::with_capacity(packets.len() + 7);
+        for chunk in packets.chunks(MAX_PAYLOAD_LENGTH) {
+            let (payload, uncompressed_length) = if chunk.len() < MIN_COMPRESS_LENGTH {
+                (chunk.to_vec(), 0)
+            } else {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                let compressed = encoder.write_all(chunk).and_then(|_| encoder.finish());
+                match compressed {
+                    // the payload which does not get shorter is sent as it is
+                    Ok(compressed) if compressed.len() < chunk.len() => (compressed, chunk.len()),
+                    _ => (chunk.to_vec(), 0),
+                }
+            };
+
+            compressed_packets.write_u24::<LittleEndian>(payload.len() as u32).unwrap();
+            compressed_packets.push(self.sequence_id);
+            compressed_packets.write_u24::<LittleEndian>(uncompressed_length as u32).unwrap();
+            compressed_packets.extend_from_slice(payload.as_slice());
+            self.sequence_id = self.sequence_id.wrapping_add(1);
+        }
+        Bytes::from(compressed_packets)
+    }
+
+    /// The packets in the compressed packets of the client, the response continues their sequence.
+    /// The payload is inflated up to its length before the compression, which is at most a packet of max_allowed_packet
+    pub fn decompress(&mut self, bytes: &[u8], max_allowed_packet: usize) -> MysqlResult<Vec<u8>> {
+        let malformed = || MysqlError::new_global_error(
+            mysql_error_code::CR_MALFORMED_PACKET as u16,
+            "reading compressed packet failed",
+        );
+
+        let mut packets = vec![];
+        let mut start = 0;
+        while start < bytes.len() {
+            let header = bytes.get(start..start + COMPRESSED_HEADER_LENGTH).ok_or_else(malformed)?;
+            let compressed_length = LittleEndian::read_u24(&header[0..3]) as usize;
+            let sequence_id = header[3];
+            let uncompressed_length = LittleEndian::read_u24(&header[4..7]) as usize;
+            let payload_start = start + COMPRESSED_HEADER_LENGTH;
+            let payload = bytes.get(payload_start..payload_start + compressed_length).ok_or_else(malformed)?;
+
+            if uncompressed_length == 0 {
+                packets.extend_from_slice(payload);
+            } else {
+                if uncompressed_length > max_allowed_packet + HEADER_LENGTH {
+                    return Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_NET_PACKET_TOO_LARGE as u16,
+                        "08S01",
+                        "Got a packet bigger than 'max_allowed_packet' bytes",
+                    ));
+                }
+                // one byte more than the length shows the payload inflating beyond it
+                let mut uncompressed = Vec::with_capacity(uncompressed_length);
+                ZlibDecoder::new(payload)
+                    .take(uncompressed_length as u64 + 1)
+                    .read_to_end(&mut uncompressed)
+                    .map_err(|_| malformed())?;
+                if uncompressed.len() != uncompressed_length {
+                    return Err(malformed());
+                }
+                packets.extend_from_slice(uncompressed.as_slice());
+            }
+
+            self.sequence_id = sequence_id.wrapping_add(1);
+            start = payload_start + compressed_length;
+        }
+        Ok(packets)
+    }
+}
 
 #[derive(Copy, Clone, Debug)]
 pub enum PacketType {
@@ -75,6 +312,8 @@ pub enum PacketType {
     ComStmtSendLongData = 0x18,
     ComStmtClose = 0x19,
     ComStmtReset = 0x1a,
+    ComSetOption = 0x1b,
+    ComStmtFetch = 0x1c,
     ComDaemon = 0x1d,
     ComBinlogDumpGtid = 0x1e,
     ComResetConnection = 0x1f,
@@ -7,7 +7,8 @@ use crate::mysql::error::{MysqlError, MysqlResult};
 use crate::mysql::mysql_error_code;
 use crate::mysql::mysql_type_code;
 use crate::mysql::packet::PacketType;
-use crate::mysql::mysql_util::parse_length_encoded_bytes;
+use crate::mysql::mysql_util::{parse_length_encoded_bytes, parse_length_encoded_int};
+use crate::mysql::metadata::CapabilityFlags;
 use crate::core::output::CoreOutput;
 
 /// A payload is just a wrapper for a Vec<u8>
@@ -25,34 +26,213 @@ impl RequestPayload {
         self.bytes[3]
     }
 
+    /// The payload after the command, the payload sent in several packets is longer than the length of the header
     pub fn get_query_sql(&self) -> &[u8] {
-        let a = self.bytes[0] as u32;
-        let b = self.bytes[1] as u32;
-        let c = self.bytes[2] as u32;
+        self.bytes.get(1 + 4..).unwrap_or(&[])
+    }
 
-        let len = a | b << 8 | c << 16;
-        let start = 1 as usize + 4;
-        let end = len as usize + 4;
-        let slice = &self.bytes[start..end];
-        slice
+    /// The payload after the command, the error 1835 if it is shorter than the fixed fields of the command
+    fn get_command_body(&self, min_length: usize) -> MysqlResult<&[u8]> {
+        match self.bytes.get(1 + 4..) {
+            Some(body) if body.len() >= min_length => Ok(body),
+            _ => Err(malformed_packet_error()),
+        }
     }
 
+    /// COM_STMT_EXECUTE, the statement id, the flags and the iteration count before the parameters
     pub fn get_stmt_execute(&self) -> MysqlResult<Vec<u8>> {
-        let value = self.bytes[5..].to_vec();
-        Ok(value)
+        Ok(self.get_command_body(9)?.to_vec())
     }
 
+    /// COM_STMT_CLOSE, the statement id
     pub fn get_stmt_close(&self) -> MysqlResult<&[u8]> {
-        let a = &self.bytes[5..];
-        Ok(a)
+        self.get_command_body(4)
+    }
+
+    /// COM_STMT_RESET, the statement id
+    pub fn get_stmt_reset(&self) -> MysqlResult<&[u8]> {
+        self.get_command_body(4)
+    }
+
+    /// COM_STMT_SEND_LONG_DATA, the statement id and the parameter id before the data
+    pub fn get_stmt_send_long_data(&self) -> MysqlResult<&[u8]> {
+        self.get_command_body(6)
+    }
+
+    /// COM_STMT_FETCH, the statement id and the number of rows
+    pub fn get_stmt_fetch(&self) -> MysqlResult<&[u8]> {
+        self.get_command_body(8)
+    }
+
+    /// COM_PROCESS_KILL, the connection id
+    pub fn get_process_kill(&self) -> MysqlResult<&[u8]> {
+        self.get_command_body(4)
+    }
+
+    /// COM_SET_OPTION, the option as 2 bytes
+    pub fn get_set_option(&self) -> MysqlResult<u16> {
+        Ok(LittleEndian::read_u16(self.get_command_body(2)?))
+    }
+
+    /// The capability flags of the handshake response
+    pub fn get_client_capability_flags(&self) -> MysqlResult<u32> {
+        if self.bytes.len() < 8 {
+            return Err(MysqlError::new_global_error(
+                mysql_error_code::CR_MALFORMED_PACKET as u16,
+                "reading client capability flags failed",
+            ));
+        }
+        Ok(LittleEndian::read_u32(&self.bytes[4..8]))
     }
 
-    pub fn get_command_id(&self) -> u8 {
-        self.bytes[4]
+    /// The max packet size the client can receive, from the handshake response
+    pub fn get_client_max_packet_size(&self) -> MysqlResult<u32> {
+        if self.bytes.len() < 12 {
+            return Err(MysqlError::new_global_error(
+                mysql_error_code::CR_MALFORMED_PACKET as u16,
+                "reading client max packet size failed",
+            ));
+        }
+        Ok(LittleEndian::read_u32(&self.bytes[8..12]))
+    }
+
+    /// The Protocol::HandshakeResponse41 sent by the client,
+    /// https://dev.mysql.com/doc/internals/en/connection-phase-packets.html#packet-Protocol::HandshakeResponse
+    pub fn get_handshake_response(&self) -> MysqlResult<HandshakeResponse> {
+        let malformed = || MysqlError::new_global_error(
+            mysql_error_code::CR_MALFORMED_PACKET as u16,
+            "reading client handshake response failed",
+        );
+
+        let capability_flags = CapabilityFlags::from_bits_truncate(self.get_client_capability_flags()?);
+        let max_packet_size = self.get_client_max_packet_size()?;
+
+        // capability flags, max packet size, character set and 23 reserved bytes
+        let mut position = 4 + 4 + 4 + 1 + 23;
+        let username = read_null_terminated(&self.bytes, &mut position).ok_or_else(malformed)?;
+
+        let auth_response;
+        if capability_flags.contains(CapabilityFlags::CLIENT_PLUGIN_AUTH_LENENC_CLIENT_DATA) {
+            let (start, len) = parse_length_encoded_int(self.bytes.get(position..).ok_or_else(malformed)?.to_vec()).ok_or_else(malformed)?;
+            let start = position + start;
+            let end = start + len as usize;
+            auth_response = self.bytes.get(start..end).ok_or_else(malformed)?.to_vec();
+            position = end;
+        } else if capability_flags.contains(CapabilityFlags::CLIENT_SECURE_CONNECTION) {
+            let len = *self.bytes.get(position).ok_or_else(malformed)? as usize;
+            let start = position + 1;
+            let end = start + len;
+            auth_response = self.bytes.get(start..end).ok_or_else(malformed)?.to_vec();
+            position = end;
+        } else {
+            auth_response = read_null_terminated(&self.bytes, &mut position).ok_or_else(malformed)?;
+        }
+
+        let mut database = None;
+        if capability_flags.contains(CapabilityFlags::CLIENT_CONNECT_WITH_DB) {
+            database = read_null_terminated(&self.bytes, &mut position)
+                .map(|database| String::from_utf8_lossy(database.as_slice()).to_string());
+        }
+
+        let mut auth_plugin_name = None;
+        if capability_flags.contains(CapabilityFlags::CLIENT_PLUGIN_AUTH) {
+            auth_plugin_name = read_null_terminated(&self.bytes, &mut position)
+                .map(|auth_plugin_name| String::from_utf8_lossy(auth_plugin_name.as_slice()).to_string());
+        }
+
+        let mut connect_attrs = vec![];
+        if capability_flags.contains(CapabilityFlags::CLIENT_CONNECT_ATTRS) && position < self.bytes.len() {
+            connect_attrs = read_connect_attrs(&self.bytes, &mut position).ok_or_else(malformed)?;
+        }
+
+        Ok(HandshakeResponse {
+            capability_flags,
+            max_packet_size,
+            username: String::from_utf8_lossy(username.as_slice()).to_string(),
+            auth_response,
+            database,
+            auth_plugin_name,
+            connect_attrs,
+        })
+    }
+
+    /// COM_CHANGE_USER, the capability flags are the ones of the connection, the max packet size is not sent
+    pub fn get_change_user(&self, capability_flags: CapabilityFlags) -> MysqlResult<HandshakeResponse> {
+        let malformed = || MysqlError::new_global_error(
+            mysql_error_code::CR_MALFORMED_PACKET as u16,
+            "reading COM_CHANGE_USER failed",
+        );
+
+        // the header and the command
+        let mut position = 4 + 1;
+        let username = read_null_terminated(&self.bytes, &mut position).ok_or_else(malformed)?;
+
+        let auth_response;
+        if capability_flags.contains(CapabilityFlags::CLIENT_SECURE_CONNECTION) {
+            let len = *self.bytes.get(position).ok_or_else(malformed)? as usize;
+            let start = position + 1;
+            let end = start + len;
+            auth_response = self.bytes.get(start..end).ok_or_else(malformed)?.to_vec();
+            position = end;
+        } else {
+            auth_response = read_null_terminated(&self.bytes, &mut position).ok_or_else(malformed)?;
+        }
+
+        // the empty database is no database
+        let database = read_null_terminated(&self.bytes, &mut position).ok_or_else(malformed)?;
+        let database = if database.is_empty() {
+            None
+        } else {
+            Some(String::from_utf8_lossy(database.as_slice()).to_string())
+        };
+
+        // the character set, then the auth plugin and the connection attributes which are optional
+        let mut auth_plugin_name = None;
+        let mut connect_attrs = vec![];
+        if self.bytes.len() >= position + 2 {
+            position += 2;
+            if capability_flags.contains(CapabilityFlags::CLIENT_PLUGIN_AUTH) {
+                auth_plugin_name = read_null_terminated(&self.bytes, &mut position)
+                    .map(|auth_plugin_name| String::from_utf8_lossy(auth_plugin_name.as_slice()).to_string());
+            }
+            if capability_flags.contains(CapabilityFlags::CLIENT_CONNECT_ATTRS) && position < self.bytes.len() {
+                connect_attrs = read_connect_attrs(&self.bytes, &mut position).ok_or_else(malformed)?;
+            }
+        }
+
+        Ok(HandshakeResponse {
+            capability_flags,
+            max_packet_size: 0,
+            username: String::from_utf8_lossy(username.as_slice()).to_string(),
+            auth_response,
+            database,
+            auth_plugin_name,
+            connect_attrs,
+        })
+    }
+
+    /// The auth response of the client after an auth switch request
+    /// The whole payload after the header
+    pub fn get_payload(&self) -> &[u8] {
+        self.bytes.get(4..).unwrap_or(&[])
+    }
+
+    pub fn get_auth_switch_response(&self) -> Vec<u8> {
+        match self.bytes.get(4..) {
+            Some(auth_response) => auth_response.to_vec(),
+            None => vec![],
+        }
+    }
+
+    pub fn get_command_id(&self) -> MysqlResult<u8> {
+        match self.bytes.get(4) {
+            Some(command_id) => Ok(*command_id),
+            None => Err(malformed_packet_error()),
+        }
     }
 
     pub fn get_packet_type(&self) -> MysqlResult<PacketType> {
-        let a = self.bytes[4];
+        let a = self.get_command_id()?;
         match a {
             0x00 => Ok(PacketType::ComSleep),
             0x01 => Ok(PacketType::ComQuit),
@@ -81,6 +261,8 @@ impl RequestPayload {
             0x18 => Ok(PacketType::ComStmtSendLongData),
             0x19 => Ok(PacketType::ComStmtClose),
             0x1a => Ok(PacketType::ComStmtReset),
+            0x1b => Ok(PacketType::ComSetOption),
+            0x1c => Ok(PacketType::ComStmtFetch),
             0x1d => Ok(PacketType::ComDaemon),
             0x1e => Ok(PacketType::ComBinlogDumpGtid),
             0x1f => Ok(PacketType::ComResetConnection),
@@ -95,3 +277,63 @@ impl RequestPayload {
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandshakeResponse {
+    pub capability_flags: CapabilityFlags,
+    pub max_packet_size: u32,
+    pub username: String,
+    pub auth_response: Vec<u8>,
+    pub database: Option<String>,
+    /// The auth plugin the client used for the auth response, only sent with CLIENT_PLUGIN_AUTH
+    pub auth_plugin_name: Option<String>,
+    /// The connection attributes of the client in the order it sent them, as program_name and _client_version,
+    /// only sent with CLIENT_CONNECT_ATTRS
+    pub connect_attrs: Vec<(String, String)>,
+}
+
+/// Read a string ending with 0x00, the position is moved after the 0x00
+fn read_null_terminated(bytes: &[u8], position: &mut usize) -> Option<Vec<u8>> {
+    let rest = bytes.get(*position..)?;
+    let len = rest.iter().position(|b| *b == 0x00)?;
+    *position += len + 1;
+    Some(rest[..len].to_vec())
+}
+
+/// Read a length encoded string, the position is moved after it
+fn read_length_encoded(bytes: &[u8], position: &mut usize) -> Option<Vec<u8>> {
+    let rest = bytes.get(*position..)?;
+    let header_len = match *rest.first()? {
+        0xfc => 3,
+        0xfd => 4,
+        0xfe => 9,
+        0xfb | 0xff => return None,
+        _ => 1,
+    };
+    let (start, len) = parse_length_encoded_int(rest.get(..header_len)?.to_vec())?;
+    let end = start.checked_add(len as usize)?;
+    let value = rest.get(start..end)?.to_vec();
+    *position += end;
+    Some(value)
+}
+
+/// The key value pairs of the connection attributes, after their total length
+fn read_connect_attrs(bytes: &[u8], position: &mut usize) -> Option<Vec<(String, String)>> {
+    let attrs = read_length_encoded(bytes, position)?;
+    let mut attrs_position = 0;
+    let mut connect_attrs = vec![];
+    while attrs_position < attrs.len() {
+        let key = read_length_encoded(&attrs, &mut attrs_position)?;
+        let value = read_length_encoded(&attrs, &mut attrs_position)?;
+        connect_attrs.push((
+            String::from_utf8_lossy(key.as_slice()).to_string(),
+            String::from_utf8_lossy(value.as_slice()).to_string(),
+        ));
+    }
+    Some(connect_attrs)
+}
+
+/// The payload is shorter than the fields of its command
+pub fn malformed_packet_error() -> MysqlError {
+    MysqlError::new_server_error(mysql_error_code::ER_MALFORMED_PACKET as u16, "HY000", "Malformed communication packet.")
+}
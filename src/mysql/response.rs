@@ -1,7 +1,11 @@
 use byteorder::{ByteOrder, LittleEndian};
+use chrono::{Datelike, NaiveDateTime, Timelike};
 
 use datafusion::scalar::ScalarValue;
 
+use crate::mysql::metadata::MysqlType;
+use crate::util::{date_function, decimal};
+
 #[derive(Debug, PartialEq)]
 pub struct ResponsePayload {
     pub bytes: Vec<u8>,
@@ -24,6 +28,13 @@ impl ResponsePayload {
                         self.dump_length_encoded_null()
                     }
                 }
+                ScalarValue::Int8(item) => {
+                    if let Some(value) = item {
+                        self.dump_length_encoded_string(value.to_string().as_ref());
+                    } else {
+                        self.dump_length_encoded_null()
+                    }
+                }
                 ScalarValue::Int64(item) => {
                     if let Some(value) = item {
                         self.dump_length_encoded_string(value.to_string().as_ref());
@@ -38,6 +49,56 @@ impl ResponsePayload {
                         self.dump_length_encoded_null()
                     }
                 }
+                // the boolean is the TINYINT 1 or 0
+                ScalarValue::Boolean(item) => {
+                    if let Some(value) = item {
+                        self.dump_length_encoded_string((value as u8).to_string().as_ref());
+                    } else {
+                        self.dump_length_encoded_null()
+                    }
+                }
+                ScalarValue::Int16(_)
+                | ScalarValue::Int32(_)
+                | ScalarValue::UInt8(_)
+                | ScalarValue::UInt16(_)
+                | ScalarValue::UInt32(_)
+                | ScalarValue::UInt64(_)
+                | ScalarValue::Float32(_) => {
+                    if scalar_value.is_null() {
+                        self.dump_length_encoded_null()
+                    } else {
+                        self.dump_length_encoded_string(scalar_value.to_string().as_ref());
+                    }
+                }
+                // the exact text of the unscaled value, never through a float
+                ScalarValue::Decimal128(item, _, scale) => {
+                    if let Some(value) = item {
+                        self.dump_length_encoded_string(decimal::format_decimal(value, scale).as_ref());
+                    } else {
+                        self.dump_length_encoded_null()
+                    }
+                }
+                // the raw bytes of the binary columns
+                ScalarValue::Binary(item) | ScalarValue::LargeBinary(item) => {
+                    if let Some(value) = item {
+                        self.dump_length_encoded_string(value.as_ref());
+                    } else {
+                        self.dump_length_encoded_null()
+                    }
+                }
+                // the dates and the timestamps are the text of mysql
+                ScalarValue::Date32(_)
+                | ScalarValue::Date64(_)
+                | ScalarValue::TimestampSecond(..)
+                | ScalarValue::TimestampMillisecond(..)
+                | ScalarValue::TimestampMicrosecond(..)
+                | ScalarValue::TimestampNanosecond(..) => {
+                    if let Some(value) = date_function::format_temporal_value(&scalar_value) {
+                        self.dump_length_encoded_string(value.as_ref());
+                    } else {
+                        self.dump_length_encoded_null()
+                    }
+                }
                 _ => {
                     let message = format!("unsupported scalar value type: {}", scalar_value.get_datatype().to_string());
                     log::error!("{}", message);
@@ -47,6 +108,105 @@ impl ResponsePayload {
         }
     }
 
+    /// The row of the binary protocol, the numbers are fixed length little endian values, the values of
+    /// the temporal columns are the structs of their parts and the other values are length encoded strings
+    /// as in the text protocol. The column types are the ones sent in the column definitions.
+    /// https://dev.mysql.com/doc/internals/en/binary-protocol-resultset-row.html
+    pub fn dump_binary_row(&mut self, columns: Vec<ScalarValue>, column_types: &[MysqlType]) {
+        self.bytes.push(0x00);
+
+        // the null bitmap starts at the bit 2
+        let mut null_bitmap = vec![0u8; (columns.len() + 7 + 2) / 8];
+        for (column_index, scalar_value) in columns.iter().enumerate() {
+            if scalar_value.is_null() {
+                let bit = column_index + 2;
+                null_bitmap[bit / 8] |= 1 << (bit % 8);
+            }
+        }
+        self.bytes.extend_from_slice(null_bitmap.as_slice());
+
+        for (column_index, scalar_value) in columns.into_iter().enumerate() {
+            let column_type = column_types.get(column_index).copied().unwrap_or(MysqlType::MYSQL_TYPE_STRING);
+            match scalar_value {
+                ScalarValue::Boolean(Some(value)) => self.bytes.push(value as u8),
+                ScalarValue::Int8(Some(value)) => self.bytes.push(value as u8),
+                ScalarValue::Int16(Some(value)) => self.dump_uint16(value as u16),
+                ScalarValue::Int32(Some(value)) => self.dump_uint32(value as u32),
+                ScalarValue::Int64(Some(value)) => self.dump_uint64(value as u64),
+                ScalarValue::UInt8(Some(value)) => self.bytes.push(value),
+                ScalarValue::UInt16(Some(value)) => self.dump_uint16(value),
+                ScalarValue::UInt32(Some(value)) => self.dump_uint32(value),
+                ScalarValue::UInt64(Some(value)) => self.dump_uint64(value),
+                ScalarValue::Utf8(Some(value)) => self.dump_binary_text(value.as_str(), column_type),
+                // the FLOAT and the DOUBLE are the little endian IEEE 754 values
+                ScalarValue::Float32(Some(value)) => self.dump_uint32(value.to_bits()),
+                ScalarValue::Float64(Some(value)) => self.dump_uint64(value.to_bits()),
+                ScalarValue::Decimal128(Some(value), _, scale) => {
+                    self.dump_length_encoded_string(decimal::format_decimal(value, scale).as_ref())
+                }
+                ScalarValue::Binary(Some(value)) | ScalarValue::LargeBinary(Some(value)) => {
+                    self.dump_length_encoded_string(value.as_ref())
+                }
+                // the null value is only in the null bitmap
+                _ if scalar_value.is_null() => {}
+                // the dates and the timestamps are the structs of their parts
+                _ => match date_function::scalar_value_to_datetime(&scalar_value) {
+                    Some((datetime, has_time)) => {
+                        self.dump_binary_datetime(datetime, !has_time || column_type == MysqlType::MYSQL_TYPE_DATE)
+                    }
+                    None => {
+                        let message = format!("unsupported scalar value type: {}", scalar_value.get_datatype().to_string());
+                        log::error!("{}", message);
+                        panic!("{}", message)
+                    }
+                },
+            }
+        }
+    }
+
+    /// The text of a temporal column is sent as the struct of its parts
+    fn dump_binary_text(&mut self, value: &str, column_type: MysqlType) {
+        match column_type {
+            MysqlType::MYSQL_TYPE_DATE | MysqlType::MYSQL_TYPE_DATETIME | MysqlType::MYSQL_TYPE_TIMESTAMP => {
+                if let Some((datetime, _)) = date_function::parse_datetime(value) {
+                    self.dump_binary_datetime(datetime, column_type == MysqlType::MYSQL_TYPE_DATE);
+                    return;
+                }
+            }
+            MysqlType::MYSQL_TYPE_TIME => {
+                if let Some(time) = date_function::parse_time(value) {
+                    self.dump_binary_time(time.num_seconds_from_midnight() as i64 * 1_000_000_000 + time.nanosecond() as i64);
+                    return;
+                }
+            }
+            _ => {}
+        }
+        self.dump_length_encoded_string(value.as_ref());
+    }
+
+    /// The DATE, DATETIME and TIMESTAMP of the binary protocol, the length is 4 with the date only,
+    /// 7 with the time and 11 with the microseconds
+    /// https://dev.mysql.com/doc/internals/en/binary-protocol-value.html#packet-ProtocolBinary::MYSQL_TYPE_DATETIME
+    pub fn dump_binary_datetime(&mut self, datetime: NaiveDateTime, date_only: bool) {
+        let microseconds = datetime.nanosecond() / 1000;
+        let length = if date_only {
+            4
+        } else if microseconds == 0 {
+            7
+        } else {
+            11
+        };
+        self.bytes.push(length);
+        self.dump_uint16(datetime.year() as u16);
+        self.bytes.extend_from_slice(&[datetime.month() as u8, datetime.day() as u8]);
+        if length > 4 {
+            self.bytes.extend_from_slice(&[datetime.hour() as u8, datetime.minute() as u8, datetime.second() as u8]);
+        }
+        if length > 7 {
+            self.dump_uint32(microseconds);
+        }
+    }
+
     pub fn dump_length_encoded_string(&mut self, msg: &[u8]) {
         self.dump_length_encoded_int(msg.len() as u64);
         self.bytes.extend_from_slice(msg);
@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+use tokio_rustls::rustls::{NoClientAuth, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::def::ConfigServer;
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+fn tls_error(message: String) -> MysqlError {
+    MysqlError::new_global_error(2026, format!("SSL connection error: {}", message).as_str())
+}
+
+/// The acceptor of the TLS connections with the certificate and the private key of the config,
+/// None if the server has no certificate and the clients connect without SSL
+pub fn load_tls_acceptor(config_server: &ConfigServer) -> MysqlResult<Option<TlsAcceptor>> {
+    let (cert_path, key_path) = match (&config_server.ssl_cert, &config_server.ssl_key) {
+        (Some(cert_path), Some(key_path)) => (cert_path, key_path),
+        (None, None) => return Ok(None),
+        _ => return Err(tls_error("ssl_cert and ssl_key must be set together".to_string())),
+    };
+
+    let cert_file = File::open(cert_path).map_err(|error| tls_error(format!("{}, {}", cert_path, error)))?;
+    let cert_chain = certs(&mut BufReader::new(cert_file))
+        .map_err(|_| tls_error(format!("{}, the certificate is not PEM", cert_path)))?;
+    if cert_chain.is_empty() {
+        return Err(tls_error(format!("{}, no certificate found", cert_path)));
+    }
+
+    // the PKCS#8 key, else the RSA key
+    let key_file = File::open(key_path).map_err(|error| tls_error(format!("{}, {}", key_path, error)))?;
+    let mut keys = pkcs8_private_keys(&mut BufReader::new(key_file))
+        .map_err(|_| tls_error(format!("{}, the private key is not PEM", key_path)))?;
+    if keys.is_empty() {
+        let key_file = File::open(key_path).map_err(|error| tls_error(format!("{}, {}", key_path, error)))?;
+        keys = rsa_private_keys(&mut BufReader::new(key_file))
+            .map_err(|_| tls_error(format!("{}, the private key is not PEM", key_path)))?;
+    }
+    let key = match keys.into_iter().next() {
+        Some(key) => key,
+        None => return Err(tls_error(format!("{}, no private key found", key_path))),
+    };
+
+    let mut server_config = ServerConfig::new(NoClientAuth::new());
+    server_config
+        .set_single_cert(cert_chain, key)
+        .map_err(|error| tls_error(format!("{:?}", error)))?;
+
+    Ok(Some(TlsAcceptor::from(Arc::new(server_config))))
+}
+
+/// The connection of the client, upgraded to TLS after the SSL request of the handshake
+pub enum ClientStream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+    /// The stream is taken by the TLS handshake, a failed handshake leaves the connection closed
+    Closed,
+}
+
+impl ClientStream {
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            ClientStream::Plain(stream) => stream.peer_addr(),
+            ClientStream::Tls(stream) => stream.get_ref().0.peer_addr(),
+            ClientStream::Closed => Err(closed_error()),
+        }
+    }
+
+    pub fn is_tls(&self) -> bool {
+        matches!(self, ClientStream::Tls(_))
+    }
+
+    /// The TLS handshake on the plain connection
+    pub async fn upgrade(&mut self, tls_acceptor: &TlsAcceptor) -> io::Result<()> {
+        match std::mem::replace(self, ClientStream::Closed) {
+            ClientStream::Plain(stream) => {
+                let stream = tls_acceptor.accept(stream).await?;
+                *self = ClientStream::Tls(Box::new(stream));
+                Ok(())
+            }
+            stream => {
+                *self = stream;
+                Err(io::Error::new(io::ErrorKind::Other, "the connection is not a plain connection"))
+            }
+        }
+    }
+}
+
+fn closed_error() -> io::Error {
+    io::Error::new(io::ErrorKind::NotConnected, "the connection is closed")
+}
+
+impl AsyncRead for ClientStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ClientStream::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+            ClientStream::Closed => Poll::Ready(Err(closed_error())),
+        }
+    }
+}
+
+impl AsyncWrite for ClientStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            ClientStream::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+            ClientStream::Closed => Poll::Ready(Err(closed_error())),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ClientStream::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+            ClientStream::Closed => Poll::Ready(Err(closed_error())),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ClientStream::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+            ClientStream::Closed => Poll::Ready(Err(closed_error())),
+        }
+    }
+}
@@ -8,41 +8,50 @@ use uuid::Uuid;
 
 use crate::core::{core_util};
 use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
 use crate::meta::meta_def::{TableDef, IndexDef};
 use crate::mysql::error::{MysqlError, MysqlResult};
 
 use crate::util;
 use crate::util::convert::ToIdent;
+use crate::store::compression;
 use crate::store::engine::engine_util::{StoreEngineFactory};
 
 pub struct PhysicalPlanInsert {
     global_context: Arc<Mutex<GlobalContext>>,
+    /// The session of the INSERT of the client, its rows are staged in the transaction of the session.
+    /// None for the rows of the meta data, they are always written at once
+    session_context: Option<SessionContext>,
 }
 
 impl PhysicalPlanInsert {
     pub fn new(global_context: Arc<Mutex<GlobalContext>>) -> Self {
         Self {
-            global_context
+            global_context,
+            session_context: None,
         }
     }
 
+    pub fn with_session_context(&mut self, session_context: SessionContext) {
+        self.session_context = Some(session_context)
+    }
+
+    /// The keys of all the rows are staged and written in one batch at the end,
+    /// so a row which fails leaves none of the rows of the statement in the table
     pub fn execute(&self, table: TableDef, column_name_list: Vec<String>, index_keys_list: Vec<Vec<IndexDef>>, column_value_map_list: Vec<HashMap<Ident, ScalarValue>>) -> MysqlResult<u64> {
-        let store_engine = StoreEngineFactory::try_new_with_table(self.global_context.clone(), table.clone()).unwrap();
+        let store_engine = match &self.session_context {
+            Some(session_context) => StoreEngineFactory::try_new_with_session(self.global_context.clone(), session_context, table.clone())?,
+            None => StoreEngineFactory::try_new_with_table(self.global_context.clone(), table.clone())?,
+        };
 
+        let mut key_values: Vec<(String, Vec<u8>)> = vec![];
         for row_number in 0..column_value_map_list.len() {
-            let rowid=  Uuid::new_v4().to_simple().encode_lower(&mut Uuid::encode_buffer()).to_string();
             let column_value_map = column_value_map_list[row_number].clone();
 
-            let column_rowid_key = util::dbkey::create_column_rowid_key(table.option.full_table_name.clone(), rowid.as_str());
-            log::debug!("rowid_key: {:?}", column_rowid_key);
-            let result = store_engine.put_key(column_rowid_key, rowid.as_bytes());
-            if let Err(e) = result {
-                return Err(e);
-            }
-
+            let mut index_keys = vec![];
             if index_keys_list.len() > 0 {
                 let result = index_keys_list.get(row_number);
-                let index_keys = match result {
+                index_keys = match result {
                     None => {
                         return Err(MysqlError::new_global_error(1105, format!(
                             "Index keys not found, row_index: {:?}",
@@ -51,15 +60,39 @@ impl PhysicalPlanInsert {
                     }
                     Some(index_keys) => index_keys.clone(),
                 };
+            }
 
-                if index_keys.len() > 0 {
-                    for index in index_keys {
-                        let result = store_engine.put_key(index.index_key, rowid.as_bytes());
-                        if let Err(e) = result {
-                            return Err(e);
-                        }
+            let rowid;
+            if table.option.without_rowid {
+                // the primary key is the rowid, the rowid key is the only copy of the primary index
+                let result = index_keys.iter().find(|index| index.level == 1);
+                let primary_index = match result {
+                    None => {
+                        return Err(MysqlError::new_global_error(1105, format!(
+                            "Primary key not found, the table without rowid requires a primary key, row_index: {:?}",
+                            row_number,
+                        ).as_str()));
                     }
+                    Some(primary_index) => primary_index.clone(),
+                };
+                rowid = util::dbkey::create_rowid_with_primary_key(&table.option, &primary_index);
+                index_keys.retain(|index| index.level != 1);
+            } else {
+                rowid = Uuid::new_v4().to_simple().encode_lower(&mut Uuid::encode_buffer()).to_string();
+            }
+
+            let column_rowid_key = util::dbkey::create_column_rowid_key(&table.option, rowid.as_str());
+            log::debug!("rowid_key: {:?}", column_rowid_key);
+            let rowid_value: &[u8] = if table.option.without_rowid { &[] } else { rowid.as_bytes() };
+            key_values.push((column_rowid_key, rowid_value.to_vec()));
+
+            for index in index_keys {
+                let mut index_key = index.index_key;
+                if index.ends_with_rowid() {
+                    index_key.push_str(rowid.as_str());
+                    index_key.push_str("/");
                 }
+                key_values.push((index_key, rowid.as_bytes().to_vec()));
             }
 
             for column_index in 0..column_name_list.to_vec().len() {
@@ -79,7 +112,7 @@ impl PhysicalPlanInsert {
                 let sparrow_column = table.get_table_column().get_sparrow_column(column_name).unwrap();
                 let store_id = sparrow_column.store_id;
 
-                let column_key = util::dbkey::create_column_key(table.option.full_table_name.clone(), store_id, rowid.as_str());
+                let column_key = util::dbkey::create_column_key(&table.option, store_id, rowid.as_str());
                 log::debug!("column_key: {:?}", column_key);
                 let result = core_util::convert_scalar_value(column_value.clone()).unwrap();
                 log::debug!("column_value: {:?}", result);
@@ -87,17 +120,15 @@ impl PhysicalPlanInsert {
                 let mut payload: Vec<u8> = Vec::new();
                 match result {
                     None => payload.push(0x00),
-                    Some(v) => payload.extend_from_slice(v.as_bytes()),
+                    Some(v) => payload.extend_from_slice(compression::compress_column_value(&table.option, v.as_bytes()).as_slice()),
                 }
                 let mem = Bytes::from(payload);
 
-                let result = store_engine.put_key(column_key, mem.bytes());
-                if let Err(e) = result {
-                    return Err(e);
-                }
+                key_values.push((column_key, mem.bytes().to_vec()));
             }
         }
 
+        store_engine.put_keys(key_values)?;
         Ok(column_value_map_list.len() as u64)
     }
 }
@@ -0,0 +1,330 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
+
+use crate::core::core_util;
+use crate::core::execution::Execution;
+use crate::core::front_end::FrontEnd;
+use crate::core::global_context::GlobalContext;
+use crate::core::output::{CoreOutput, FinalCount, ResultStream};
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::mysql::tls::ClientStream;
+use crate::postgres::message::{self, StartupMessage};
+use crate::util::multi_statement;
+
+/// The parameters of the server sent after the auth, the clients check the encoding and the date style
+const PARAMETER_STATUSES: &[(&str, &str)] = &[
+    ("server_version", "14.0 (sparrow)"),
+    ("server_encoding", "UTF8"),
+    ("client_encoding", "UTF8"),
+    ("DateStyle", "ISO, MDY"),
+    ("integer_datetimes", "on"),
+    ("standard_conforming_strings", "on"),
+];
+
+fn not_supported_error(message: &str) -> MysqlError {
+    MysqlError::new_server_error(mysql_error_code::ER_NOT_SUPPORTED_YET as u16, "0A000", message)
+}
+
+/// A connection of the PostgreSQL simple query protocol, the queries run as the queries of the MySQL protocol.
+/// https://www.postgresql.org/docs/current/protocol-flow.html
+pub struct Handle {
+    socket: ClientStream,
+    /// The TLS of the connections, None if the server has no certificate
+    tls_acceptor: Option<TlsAcceptor>,
+    core_execution: Execution,
+    /// The session is started, the errors before it are FATAL and close the connection
+    ready: bool,
+    /// The messages after an error of the extended query protocol are skipped until Sync
+    skip_until_sync: bool,
+}
+
+impl Handle {
+    pub async fn new(
+        socket: TcpStream,
+        core_context: Arc<Mutex<GlobalContext>>,
+        tls_acceptor: Option<TlsAcceptor>,
+    ) -> io::Result<Handle> {
+        // the simple query may have several statements, the handle runs them one by one
+        // and writes the rows of the SELECT as they are produced
+        let mut core_execution = Execution::new(core_context);
+        core_execution.set_client_stream_results(true);
+        Ok(Handle {
+            socket: ClientStream::Plain(socket),
+            tls_acceptor,
+            core_execution,
+            ready: false,
+            skip_until_sync: false,
+        })
+    }
+
+    async fn write_message(&mut self, bytes: Vec<u8>) {
+        log::debug!("write message bytes: {:?}", bytes);
+        if let Err(e) = self.socket.write_all(bytes.as_slice()).await {
+            log::debug!("error on sending response, error: {:?}", e);
+        }
+    }
+
+    /// The body of the message of the given length, the length counts its own 4 bytes
+    async fn read_body(&mut self, length: i32) -> MysqlResult<Option<Vec<u8>>> {
+        let max_allowed_packet = self.core_execution.max_allowed_packet();
+        if length < 4 || length as usize - 4 > max_allowed_packet {
+            return Err(MysqlError::new_server_error(
+                mysql_error_code::ER_NET_PACKET_TOO_LARGE as u16,
+                "08P01",
+                "Got a packet bigger than 'max_allowed_packet' bytes",
+            ));
+        }
+        let mut body = vec![0; length as usize - 4];
+        match self.socket.read_exact(&mut body).await {
+            Ok(_) => Ok(Some(body)),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(error) => Err(MysqlError::new_global_error(
+                1105,
+                format!("Unknown error. Failed to read from socket, error: {:?}", error).as_str(),
+            )),
+        }
+    }
+
+    /// The startup message has no type byte, None if the client closed the connection
+    async fn read_startup_message(&mut self) -> MysqlResult<Option<Vec<u8>>> {
+        let mut length = [0; 4];
+        if self.socket.read_exact(&mut length).await.is_err() {
+            return Ok(None);
+        }
+        self.read_body(i32::from_be_bytes(length)).await
+    }
+
+    /// The type and the body of the next message, None if the client closed the connection
+    async fn read_message(&mut self) -> MysqlResult<Option<(u8, Vec<u8>)>> {
+        let mut header = [0; 5];
+        if self.socket.read_exact(&mut header).await.is_err() {
+            return Ok(None);
+        }
+        let length = i32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+        let body = self.read_body(length).await?;
+        Ok(body.map(|body| (header[0], body)))
+    }
+
+    /// The password of the user in clear text, which must be sent on TLS on the untrusted networks.
+    /// The user without password is not asked for it. False if the client closed the connection
+    async fn authenticate(&mut self, user: &str, host: &str) -> MysqlResult<bool> {
+        if self.core_execution.authenticate_password(user, host, &[]).is_err() {
+            self.write_message(message::authentication_message(message::AUTHENTICATION_CLEARTEXT_PASSWORD)).await;
+            let password = match self.read_message().await? {
+                Some((b'p', body)) => message::read_cstring(body.as_slice()),
+                Some(_) => return Err(MysqlError::new_server_error(1105, "08P01", "expected a password message")),
+                None => return Ok(false),
+            };
+            self.core_execution.authenticate_password(user, host, password.as_bytes())?;
+        }
+        self.write_message(message::authentication_message(message::AUTHENTICATION_OK)).await;
+        Ok(true)
+    }
+
+    /// The statements of the simple query in order, the first error ends them
+    async fn simple_query(&mut self, sql: &str) {
+        let statements = multi_statement::split_statements(sql);
+        if statements.is_empty() {
+            self.write_message(message::empty_query_response_message()).await;
+        }
+        for statement in statements {
+            log::debug!("start sql: {}", statement);
+            let result = match self.core_execution.execute_query(statement).await {
+                Ok(CoreOutput::ResultSet(result_set)) => self.send_result_stream(ResultStream::from(result_set)).await,
+                Ok(CoreOutput::ResultStream(result_stream)) => self.send_result_stream(result_stream).await,
+                Ok(CoreOutput::FinalCount(FinalCount { affect_rows, .. })) => {
+                    let command_tag = message::command_tag(statement, affect_rows);
+                    self.write_message(message::command_complete_message(command_tag.as_str())).await;
+                    Ok(())
+                }
+                Ok(_) => Err(not_supported_error("The statement is not supported by the PostgreSQL protocol")),
+                Err(mysql_error) => Err(mysql_error),
+            };
+            if let Err(mysql_error) = result {
+                self.write_error(mysql_error).await;
+                break;
+            }
+        }
+        self.write_message(message::ready_for_query_message(self.core_execution.in_transaction())).await;
+    }
+
+    /// The rows are written as the batches are produced, the error of a batch ends them without CommandComplete
+    async fn send_result_stream(&mut self, result_stream: ResultStream) -> MysqlResult<()> {
+        let ResultStream { schema_ref, mut stream, .. } = result_stream;
+        self.write_message(message::row_description_message(schema_ref.as_ref())).await;
+
+        let mut row_count = 0;
+        while let Some(batch) = stream.next().await {
+            let record_batch = batch.map_err(|mysql_error| {
+                self.core_execution.push_stream_error(&mysql_error);
+                mysql_error
+            })?;
+            let rows = core_util::convert_record_to_scalar_value(record_batch.clone());
+            for row in rows.iter().take(record_batch.num_rows()) {
+                let values = row.iter().map(message::text_value).collect::<Vec<_>>();
+                self.write_message(message::data_row_message(values)).await;
+                row_count += 1;
+            }
+        }
+        self.write_message(message::command_complete_message(format!("SELECT {}", row_count).as_str())).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FrontEnd for Handle {
+    fn core_execution(&mut self) -> &mut Execution {
+        &mut self.core_execution
+    }
+
+    async fn handshake(&mut self) -> MysqlResult<()> {
+        let parameters = loop {
+            let bytes = match self.read_startup_message().await? {
+                Some(bytes) => bytes,
+                None => return Ok(()),
+            };
+            match message::parse_startup_message(bytes.as_slice()) {
+                Some(StartupMessage::SslRequest) => {
+                    // the startup message follows on the TLS connection, or on the plain connection after N
+                    let tls_acceptor = match &self.tls_acceptor {
+                        Some(tls_acceptor) if !self.socket.is_tls() => tls_acceptor.clone(),
+                        _ => {
+                            self.write_message(b"N".to_vec()).await;
+                            continue;
+                        }
+                    };
+                    self.write_message(b"S".to_vec()).await;
+                    if let Err(error) = self.socket.upgrade(&tls_acceptor).await {
+                        log::error!("SSL connection error: {:?}", error);
+                        return Ok(());
+                    }
+                    log::debug!("the connection is upgraded to TLS");
+                }
+                Some(StartupMessage::Startup(parameters)) => break parameters,
+                Some(StartupMessage::CancelRequest) => {
+                    // the cancel requests are not supported, KILL QUERY cancels the query of another connection
+                    return Ok(());
+                }
+                Some(StartupMessage::Unsupported(code)) => {
+                    let message = format!("unsupported frontend protocol {}.{}", code >> 16, code & 0xffff);
+                    return Err(not_supported_error(message.as_str()));
+                }
+                None => return Err(MysqlError::new_server_error(1105, "08P01", "invalid startup packet layout")),
+            }
+        };
+
+        let parameter = |name: &str| {
+            parameters
+                .iter()
+                .find(|(parameter_name, _)| parameter_name == name)
+                .map(|(_, value)| value.clone())
+        };
+        let user = parameter("user").unwrap_or_default();
+        let database = parameter("database");
+        let host = match self.socket.peer_addr() {
+            Ok(peer_addr) => peer_addr.ip().to_string(),
+            Err(_) => "".to_string(),
+        };
+
+        if !self.authenticate(user.as_str(), host.as_str()).await? {
+            return Ok(());
+        }
+        self.start_session(user.as_str(), host.as_str(), parameters.clone(), database).await?;
+
+        for (name, value) in PARAMETER_STATUSES {
+            self.write_message(message::parameter_status_message(name, value)).await;
+        }
+        self.write_message(message::ready_for_query_message(self.core_execution.in_transaction())).await;
+        self.ready = true;
+        Ok(())
+    }
+
+    async fn write_error(&mut self, mysql_error: MysqlError) {
+        let severity = if self.ready { message::SEVERITY_ERROR } else { message::SEVERITY_FATAL };
+        self.write_message(message::error_response_message(severity, &mysql_error)).await;
+    }
+
+    async fn exec_command(&mut self) {
+        let connection_id = self.core_execution.connection_id();
+        let connection_token = self.core_execution.connection_token();
+
+        loop {
+            // the connection killed by KILL CONNECTION is closed, and the connection idle for wait_timeout
+            let wait_timeout = self.core_execution.wait_timeout();
+            let result = tokio::select! {
+                biased;
+                _ = connection_token.cancelled() => {
+                    log::debug!("the connection {} is killed", connection_id);
+                    break;
+                }
+                _ = tokio::time::sleep(wait_timeout) => {
+                    log::debug!("the connection {} is idle for {:?}", connection_id, wait_timeout);
+                    Err(MysqlError::new_server_error(
+                        mysql_error_code::ER_CLIENT_INTERACTION_TIMEOUT as u16,
+                        "57P05",
+                        "terminating connection due to idle-session timeout",
+                    ))
+                }
+                result = self.read_message() => result,
+            };
+            let (message_type, body) = match result {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(mysql_error) => {
+                    log::error!("failed to read the message; err = {}", mysql_error);
+                    let payload = message::error_response_message(message::SEVERITY_FATAL, &mysql_error);
+                    self.write_message(payload).await;
+                    break;
+                }
+            };
+            log::debug!("message type: {}", message_type as char);
+
+            match message_type {
+                b'Q' => {
+                    self.skip_until_sync = false;
+                    let sql = message::read_cstring(body.as_slice());
+                    self.simple_query(sql.as_str()).await;
+                }
+                b'X' => {
+                    // Terminate, the connection is closed after the loop
+                    break;
+                }
+                b'S' => {
+                    // Sync ends the messages of the extended query protocol
+                    self.skip_until_sync = false;
+                    self.write_message(message::ready_for_query_message(self.core_execution.in_transaction())).await;
+                }
+                b'P' | b'B' | b'D' | b'E' | b'C' | b'H' | b'F' => {
+                    if !self.skip_until_sync {
+                        self.skip_until_sync = true;
+                        self.write_error(not_supported_error("The extended query protocol is not supported, use the simple query protocol")).await;
+                    }
+                }
+                _ => {
+                    log::error!("Unknown error. The message is not support, message type: {:?}", message_type);
+                    break;
+                }
+            }
+        }
+
+        log::debug!("loop break");
+        self.close().await;
+    }
+
+    async fn close(&mut self) {
+        self.core_execution.close();
+        if let Err(e) = self.socket.flush().await {
+            log::debug!("error on flushing the connection, error: {:?}", e);
+        }
+        if let Err(e) = self.socket.shutdown().await {
+            log::debug!("error on shutting down the connection, error: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use arrow::datatypes::{DataType, Schema};
+use datafusion::scalar::ScalarValue;
+
+use crate::mysql::error::MysqlError;
+use crate::util::{date_function, decimal};
+
+/// The version 3.0 of the protocol in the startup message
+pub const PROTOCOL_VERSION: i32 = 196608;
+/// The startup message asking for TLS before the real startup message
+pub const SSL_REQUEST_CODE: i32 = 80877103;
+/// The startup message of the connection opened to cancel the query of another connection
+pub const CANCEL_REQUEST_CODE: i32 = 80877102;
+
+pub const AUTHENTICATION_OK: i32 = 0;
+pub const AUTHENTICATION_CLEARTEXT_PASSWORD: i32 = 3;
+
+/// The transaction status of ReadyForQuery outside a transaction and in a transaction
+pub const TRANSACTION_STATUS_IDLE: u8 = b'I';
+pub const TRANSACTION_STATUS_IN_TRANSACTION: u8 = b'T';
+
+pub const SEVERITY_ERROR: &str = "ERROR";
+pub const SEVERITY_FATAL: &str = "FATAL";
+
+// https://www.postgresql.org/docs/current/datatype-oid.html
+const OID_BOOL: i32 = 16;
+const OID_BYTEA: i32 = 17;
+const OID_INT8: i32 = 20;
+const OID_INT2: i32 = 21;
+const OID_INT4: i32 = 23;
+const OID_TEXT: i32 = 25;
+const OID_FLOAT4: i32 = 700;
+const OID_FLOAT8: i32 = 701;
+const OID_DATE: i32 = 1082;
+const OID_TIMESTAMP: i32 = 1114;
+const OID_NUMERIC: i32 = 1700;
+
+/// The first message of the client, it has no type byte
+#[derive(Clone, Debug, PartialEq)]
+pub enum StartupMessage {
+    SslRequest,
+    CancelRequest,
+    /// The parameters of the connection in the order the client sent them, as user, database and application_name
+    Startup(Vec<(String, String)>),
+    /// The protocol version the server does not speak
+    Unsupported(i32),
+}
+
+/// The startup message without its length, None if it is malformed
+pub fn parse_startup_message(bytes: &[u8]) -> Option<StartupMessage> {
+    if bytes.len() < 4 {
+        return None;
+    }
+    let code = i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    match code {
+        SSL_REQUEST_CODE => Some(StartupMessage::SslRequest),
+        CANCEL_REQUEST_CODE => Some(StartupMessage::CancelRequest),
+        PROTOCOL_VERSION => {
+            // the names and the values end with NUL, an empty name ends the parameters
+            let mut strings = bytes[4..].split(|byte| *byte == 0x00).map(|string| String::from_utf8_lossy(string).to_string());
+            let mut parameters = vec![];
+            loop {
+                let name = strings.next()?;
+                if name.is_empty() {
+                    return Some(StartupMessage::Startup(parameters));
+                }
+                let value = strings.next()?;
+                parameters.push((name, value));
+            }
+        }
+        code => Some(StartupMessage::Unsupported(code)),
+    }
+}
+
+/// The string ending with NUL of the messages of the client, as the sql of Query and the password of PasswordMessage
+pub fn read_cstring(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|byte| *byte == 0x00).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+/// A message of the server, the length after the type byte is set by `finish`
+pub struct BackendMessage {
+    bytes: Vec<u8>,
+}
+
+impl BackendMessage {
+    pub fn new(message_type: u8) -> Self {
+        Self {
+            bytes: vec![message_type, 0, 0, 0, 0],
+        }
+    }
+
+    pub fn put_i16(&mut self, n: i16) {
+        self.bytes.extend_from_slice(&n.to_be_bytes());
+    }
+
+    pub fn put_i32(&mut self, n: i32) {
+        self.bytes.extend_from_slice(&n.to_be_bytes());
+    }
+
+    pub fn put_cstring(&mut self, value: &str) {
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.push(0x00);
+    }
+
+    pub fn put_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// The bytes of the message, the length counts itself but not the type byte
+    pub fn finish(mut self) -> Vec<u8> {
+        let length = (self.bytes.len() - 1) as i32;
+        self.bytes[1..5].copy_from_slice(&length.to_be_bytes());
+        self.bytes
+    }
+}
+
+pub fn authentication_message(code: i32) -> Vec<u8> {
+    let mut message = BackendMessage::new(b'R');
+    message.put_i32(code);
+    message.finish()
+}
+
+pub fn parameter_status_message(name: &str, value: &str) -> Vec<u8> {
+    let mut message = BackendMessage::new(b'S');
+    message.put_cstring(name);
+    message.put_cstring(value);
+    message.finish()
+}
+
+pub fn ready_for_query_message(in_transaction: bool) -> Vec<u8> {
+    let mut message = BackendMessage::new(b'Z');
+    let transaction_status = if in_transaction { TRANSACTION_STATUS_IN_TRANSACTION } else { TRANSACTION_STATUS_IDLE };
+    message.put_bytes(&[transaction_status]);
+    message.finish()
+}
+
+/// The columns of the result set, the values are sent in the text format
+pub fn row_description_message(schema: &Schema) -> Vec<u8> {
+    let mut message = BackendMessage::new(b'T');
+    message.put_i16(schema.fields().len() as i16);
+    for field in schema.fields() {
+        message.put_cstring(field.name());
+        // the oid of the table and the number of the column
+        message.put_i32(0);
+        message.put_i16(0);
+        message.put_i32(type_oid(field.data_type()));
+        // the size of the type is variable, and the type has no modifier
+        message.put_i16(-1);
+        message.put_i32(-1);
+        // the text format
+        message.put_i16(0);
+    }
+    message.finish()
+}
+
+/// A row of the result set, None is NULL
+pub fn data_row_message(values: Vec<Option<String>>) -> Vec<u8> {
+    let mut message = BackendMessage::new(b'D');
+    message.put_i16(values.len() as i16);
+    for value in values {
+        match value {
+            Some(value) => {
+                message.put_i32(value.len() as i32);
+                message.put_bytes(value.as_bytes());
+            }
+            None => message.put_i32(-1),
+        }
+    }
+    message.finish()
+}
+
+pub fn command_complete_message(command_tag: &str) -> Vec<u8> {
+    let mut message = BackendMessage::new(b'C');
+    message.put_cstring(command_tag);
+    message.finish()
+}
+
+pub fn empty_query_response_message() -> Vec<u8> {
+    BackendMessage::new(b'I').finish()
+}
+
+/// The error of the statement with its SQLSTATE, the mysql error number is kept in the detail
+pub fn error_response_message(severity: &str, mysql_error: &MysqlError) -> Vec<u8> {
+    let mut message = BackendMessage::new(b'E');
+    message.put_bytes(b"S");
+    message.put_cstring(severity);
+    message.put_bytes(b"V");
+    message.put_cstring(severity);
+    message.put_bytes(b"C");
+    message.put_cstring(mysql_error.sql_state().as_str());
+    message.put_bytes(b"M");
+    message.put_cstring(mysql_error.message().as_str());
+    message.put_bytes(b"D");
+    message.put_cstring(format!("ERROR {}", mysql_error.error_number()).as_str());
+    message.put_bytes(&[0x00]);
+    message.finish()
+}
+
+/// The oid of the type of the column, the types without a postgres type are text
+pub fn type_oid(data_type: &DataType) -> i32 {
+    match data_type {
+        DataType::Boolean => OID_BOOL,
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => OID_INT2,
+        DataType::Int32 | DataType::UInt16 => OID_INT4,
+        DataType::Int64 | DataType::UInt32 => OID_INT8,
+        DataType::UInt64 | DataType::Decimal(_, _) => OID_NUMERIC,
+        DataType::Float32 => OID_FLOAT4,
+        DataType::Float64 => OID_FLOAT8,
+        DataType::Date32 | DataType::Date64 => OID_DATE,
+        DataType::Timestamp(_, _) => OID_TIMESTAMP,
+        DataType::Binary | DataType::LargeBinary => OID_BYTEA,
+        _ => OID_TEXT,
+    }
+}
+
+/// The text format of the value, None for NULL
+pub fn text_value(scalar_value: &ScalarValue) -> Option<String> {
+    match scalar_value {
+        ScalarValue::Boolean(Some(value)) => Some(if *value { "t" } else { "f" }.to_string()),
+        ScalarValue::Utf8(Some(value)) => Some(value.clone()),
+        ScalarValue::Int8(Some(value)) => Some(value.to_string()),
+        ScalarValue::Int16(Some(value)) => Some(value.to_string()),
+        ScalarValue::Int32(Some(value)) => Some(value.to_string()),
+        ScalarValue::Int64(Some(value)) => Some(value.to_string()),
+        ScalarValue::UInt8(Some(value)) => Some(value.to_string()),
+        ScalarValue::UInt16(Some(value)) => Some(value.to_string()),
+        ScalarValue::UInt32(Some(value)) => Some(value.to_string()),
+        ScalarValue::UInt64(Some(value)) => Some(value.to_string()),
+        ScalarValue::Float32(Some(value)) => Some(value.to_string()),
+        ScalarValue::Float64(Some(value)) => Some(value.to_string()),
+        // the exact text of the unscaled value, never through a float
+        ScalarValue::Decimal128(Some(value), _, scale) => Some(decimal::format_decimal(*value, *scale)),
+        // the dates and the timestamps are in the ISO format of postgres
+        _ => date_function::format_temporal_value(scalar_value),
+    }
+}
+
+/// The tag of CommandComplete for the statement which is not a query, as `INSERT 0 2` or `CREATE TABLE`
+pub fn command_tag(sql: &str, affect_rows: u64) -> String {
+    let words = sql
+        .split_whitespace()
+        .take(2)
+        .map(|word| word.trim_start_matches('(').to_uppercase())
+        .collect::<Vec<_>>();
+    let first_word = words.first().map(|word| word.as_str()).unwrap_or("");
+    match first_word {
+        "INSERT" | "REPLACE" => format!("INSERT 0 {}", affect_rows),
+        "UPDATE" | "DELETE" => format!("{} {}", first_word, affect_rows),
+        "CREATE" | "DROP" | "ALTER" | "TRUNCATE" if words.len() > 1 => format!("{} {}", first_word, words[1]),
+        "START" => "START TRANSACTION".to_string(),
+        _ => first_word.to_string(),
+    }
+}
@@ -0,0 +1,41 @@
+use crate::meta::meta_const;
+use crate::meta::meta_def::TableOptionDef;
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+/// The first byte of a compressed column value, it never starts a utf8 value nor the null value
+const COMPRESSED_VALUE_MARK: u8 = 0xFF;
+
+/// The stored bytes of the column value, compressed by the compression of the table
+/// when the value is not shorter than the threshold and the compression saves space
+pub fn compress_column_value(table_option: &TableOptionDef, value: &[u8]) -> Vec<u8> {
+    match table_option.compression.as_deref() {
+        Some(meta_const::VALUE_OF_TABLE_OPTION_COMPRESSION_LZ4) if value.len() >= table_option.compression_threshold => {
+            let compressed = lz4_flex::compress_prepend_size(value);
+            if compressed.len() + 1 < value.len() {
+                let mut payload = Vec::with_capacity(compressed.len() + 1);
+                payload.push(COMPRESSED_VALUE_MARK);
+                payload.extend_from_slice(compressed.as_slice());
+                return payload;
+            }
+            value.to_vec()
+        }
+        _ => value.to_vec(),
+    }
+}
+
+/// The original bytes of the stored column value, the value written without compression is returned as is
+pub fn decompress_column_value(value: &[u8]) -> MysqlResult<Vec<u8>> {
+    match value.first() {
+        Some(&COMPRESSED_VALUE_MARK) => lz4_flex::decompress_size_prepended(&value[1..]).map_err(|error| {
+            MysqlError::new_global_error(1105, format!(
+                "Unknown error. An error occurred while decompressing the column value, error: {:?}",
+                error,
+            ).as_str())
+        }),
+        _ => Ok(value.to_vec()),
+    }
+}
+
+pub fn is_supported_compression(compression: &str) -> bool {
+    compression == meta_const::VALUE_OF_TABLE_OPTION_COMPRESSION_LZ4
+}
@@ -9,20 +9,38 @@ use datafusion::scalar::ScalarValue;
 use sqlparser::ast::{ObjectName, Ident};
 
 use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
 use crate::meta::{meta_const, meta_util};
 use crate::mysql::error::{MysqlError, MysqlResult};
 
 use super::sled;
+use super::transaction::StoreEngineTransaction;
+use crate::store::mvcc::Snapshot;
 use crate::meta::meta_def::TableDef;
 
 pub trait StoreEngine {
     fn delete_key(&self, key: String) -> MysqlResult<()>;
     fn get_key(&self, key: String) -> MysqlResult<Option<Vec<u8>>>;
+    /// The value of the key seen by the snapshot, the keys without versions are read at their newest value
+    fn get_key_at(&self, key: String, snapshot: &Snapshot) -> MysqlResult<Option<Vec<u8>>>;
     fn put_key(&self, key: String, value: &[u8]) -> MysqlResult<()>;
+    /// Put the keys in one batch, either all of them are written or none
+    fn put_keys(&self, key_values: Vec<(String, Vec<u8>)>) -> MysqlResult<()>;
+    /// Put the keys with a value and delete the keys without one in one batch, either all of them are written or none
+    fn write_keys(&self, writes: Vec<(String, Option<Vec<u8>>)>) -> MysqlResult<()>;
+    /// Write the keys of the transaction reading the snapshot in one batch, it fails if one of the keys
+    /// was committed by another transaction after the snapshot
+    fn commit_keys(&self, writes: Vec<(String, Option<Vec<u8>>)>, snapshot: &Snapshot) -> MysqlResult<()>;
+    /// Delete all the keys starting with the prefix, returns the number of deleted keys
+    fn delete_prefix(&self, prefix: String) -> MysqlResult<u64>;
+    /// At most limit keys starting with the prefix in order, after the key start_after if given
+    fn scan_prefix_keys(&self, prefix: String, start_after: Option<String>, limit: usize) -> MysqlResult<Vec<String>>;
 }
 
 pub trait TableEngine {
     fn table_provider(&self) -> Arc<dyn TableProvider>;
+    /// The provider of the tables of the session, its scans read the snapshot of the transaction of the session
+    fn table_provider_in_session(&self, session_context: &SessionContext) -> Arc<dyn TableProvider>;
     fn table_iterator(&self, projection: Option<Vec<usize>>, filters: &[Expr]) -> Box<dyn Iterator<Item = Result<RecordBatch>>>;
 }
 
@@ -79,6 +97,17 @@ impl StoreEngineFactory {
         StoreEngineFactory::try_new_with_engine(global_context.clone(), engine.as_str())
     }
 
+    /// The store engine of the table written by the statement of the session, the writes are staged
+    /// in the transaction of the session if one is open
+    pub fn try_new_with_session(global_context: Arc<Mutex<GlobalContext>>, session_context: &SessionContext, table: TableDef) -> MysqlResult<Box<dyn StoreEngine>> {
+        let engine = table.get_engine();
+        let store_engine = StoreEngineFactory::try_new_with_engine(global_context.clone(), engine.as_str())?;
+        if session_context.transaction.lock().unwrap().is_none() {
+            return Ok(store_engine);
+        }
+        Ok(Box::new(StoreEngineTransaction::new(engine.as_str(), store_engine, session_context.transaction.clone())))
+    }
+
     pub fn try_new_schema_engine(global_context: Arc<Mutex<GlobalContext>>) -> MysqlResult<Box<dyn StoreEngine>> {
         let engine = global_context.lock().unwrap().clone().my_config.schema.engine;
         StoreEngineFactory::try_new_with_engine(global_context.clone(), engine.as_str())
@@ -90,7 +119,8 @@ impl StoreEngineFactory {
         match engine {
             meta_const::VALUE_OF_TABLE_OPTION_ENGINE_SLED => {
                 let sled_db = gc.engine.sled_db.as_ref().unwrap();
-                Ok(Box::new(sled::StoreEngineSled::new(sled_db.clone())))
+                let sled_mvcc = gc.engine.sled_mvcc.as_ref().unwrap();
+                Ok(Box::new(sled::StoreEngineSled::new(sled_db.clone(), sled_mvcc.clone())))
             }
             _ => {
                 Err(MysqlError::new_global_error(1105, format!(
@@ -1,3 +1,5 @@
 pub mod sled;
 pub mod parquet;
-pub mod engine_util;
\ No newline at end of file
+pub mod engine_util;
+pub mod transaction;
+pub mod row_mutation;
\ No newline at end of file
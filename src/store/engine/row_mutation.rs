@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use crate::core::core_util;
+use crate::meta::meta_const;
+use crate::meta::meta_def::{TableDef, TableIndexDef};
+use crate::mysql::error::MysqlResult;
+use crate::store::compression;
+use crate::store::engine::engine_util::StoreEngine;
+use crate::util::dbkey;
+
+/// The changes of the rows of a table written with the entries of their indexes in one batch. The entries
+/// of the old values are deleted and the ones of the new values are written, so an index never points to
+/// a row without the indexed values, nor misses a row with them
+pub struct RowMutation<'a> {
+    table_def: &'a TableDef,
+    /// The indexes stored apart from the rows, the primary key of the table without rowid is its rowid key
+    table_index_list: Vec<TableIndexDef>,
+    store_engine: &'a dyn StoreEngine,
+    /// The staged writes by their keys, a key without a value is deleted
+    writes: BTreeMap<String, Option<Vec<u8>>>,
+}
+
+impl<'a> RowMutation<'a> {
+    /// The indexes are the ones the changes can move, as the indexes with a column assigned by UPDATE
+    pub fn new(table_def: &'a TableDef, table_index_list: Vec<TableIndexDef>, store_engine: &'a dyn StoreEngine) -> Self {
+        let table_index_list = table_index_list
+            .into_iter()
+            .filter(|table_index| !(table_def.option.without_rowid && table_index.level == 1))
+            .collect();
+        Self {
+            table_def,
+            table_index_list,
+            store_engine,
+            writes: BTreeMap::new(),
+        }
+    }
+
+    /// The value of the key with the staged writes
+    fn get_key(&self, key: String) -> MysqlResult<Option<Vec<u8>>> {
+        match self.writes.get(key.as_str()) {
+            Some(value) => Ok(value.clone()),
+            None => self.store_engine.get_key(key),
+        }
+    }
+
+    fn read_index_keys(&self, rowid: &str) -> MysqlResult<Vec<Option<String>>> {
+        core_util::read_index_keys_with(self.table_def, &self.table_index_list, rowid, |key| self.get_key(key))
+    }
+
+    /// Set the columns of the row by their store ids, the values already stored are kept.
+    /// Returns whether a value of the row changed
+    pub fn update_row(&mut self, rowid: &str, column_values: Vec<(i64, Vec<u8>)>) -> MysqlResult<bool> {
+        let old_index_keys = self.read_index_keys(rowid)?;
+
+        let mut changed = false;
+        for (store_id, column_value) in column_values {
+            let column_key = dbkey::create_column_key(&self.table_def.option, store_id, rowid);
+            if let Some(value) = self.get_key(column_key.clone())? {
+                if compression::decompress_column_value(value.as_slice())? == column_value {
+                    continue;
+                }
+            }
+            changed = true;
+            let column_value = compression::compress_column_value(&self.table_def.option, column_value.as_slice());
+            self.writes.insert(column_key, Some(column_value));
+        }
+
+        if changed {
+            let new_index_keys = self.read_index_keys(rowid)?;
+            self.move_index_entries(rowid, old_index_keys, new_index_keys)?;
+        }
+        Ok(changed)
+    }
+
+    /// Set the columns of the row whose index keys were read with the old definition of the table, as the
+    /// values of a column cast to its new type. The values are written even if they did not change
+    pub fn rewrite_row(&mut self, rowid: &str, column_values: Vec<(i64, Vec<u8>)>, old_index_keys: Vec<Option<String>>) -> MysqlResult<()> {
+        for (store_id, column_value) in column_values {
+            let column_key = dbkey::create_column_key(&self.table_def.option, store_id, rowid);
+            let column_value = compression::compress_column_value(&self.table_def.option, column_value.as_slice());
+            self.writes.insert(column_key, Some(column_value));
+        }
+
+        let new_index_keys = self.read_index_keys(rowid)?;
+        self.move_index_entries(rowid, old_index_keys, new_index_keys)
+    }
+
+    /// Delete the row with its columns and its index entries
+    pub fn delete_row(&mut self, rowid: &str) -> MysqlResult<()> {
+        let old_index_keys = self.read_index_keys(rowid)?;
+        let new_index_keys = vec![None; old_index_keys.len()];
+        self.move_index_entries(rowid, old_index_keys, new_index_keys)?;
+
+        self.writes.insert(dbkey::create_record_rowid(&self.table_def.option, rowid), None);
+        for sql_column in self.table_def.get_table_column().sql_column_list {
+            if sql_column.name.to_string().contains(meta_const::COLUMN_ROWID) {
+                continue;
+            }
+            let sparrow_column = self.table_def.get_table_column().get_sparrow_column(sql_column.name).unwrap();
+            let column_key = dbkey::create_column_key(&self.table_def.option, sparrow_column.store_id, rowid);
+            self.writes.insert(column_key, None);
+        }
+        Ok(())
+    }
+
+    /// The entry of the old value is only deleted while it points to the row. The new value must not be taken
+    /// by another row, the rows are checked one by one as MySQL does, so the rows swapping their values of
+    /// a unique index are duplicates too
+    fn move_index_entries(&mut self, rowid: &str, old_index_keys: Vec<Option<String>>, new_index_keys: Vec<Option<String>>) -> MysqlResult<()> {
+        for (index_number, (old_index_key, new_index_key)) in old_index_keys.into_iter().zip(new_index_keys.into_iter()).enumerate() {
+            if old_index_key == new_index_key {
+                continue;
+            }
+            if let Some(new_index_key) = &new_index_key {
+                match self.get_key(new_index_key.clone())? {
+                    Some(value) if value != rowid.as_bytes() => {
+                        let table_index = &self.table_index_list[index_number];
+                        let values = core_util::read_index_values_with(self.table_def, table_index, rowid, &|key| self.get_key(key))?;
+                        return Err(core_util::duplicate_entry_error(
+                            self.table_def.option.table_name.as_str(),
+                            table_index.index_name.as_str(),
+                            values,
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(old_index_key) = old_index_key {
+                if self.get_key(old_index_key.clone())?.as_deref() == Some(rowid.as_bytes()) {
+                    self.writes.insert(old_index_key, None);
+                }
+            }
+            if let Some(new_index_key) = new_index_key {
+                self.writes.insert(new_index_key, Some(rowid.as_bytes().to_vec()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the changes of all the rows in one batch
+    pub fn write(self) -> MysqlResult<()> {
+        if self.writes.is_empty() {
+            return Ok(());
+        }
+        self.store_engine.write_keys(self.writes.into_iter().collect())
+    }
+}
@@ -1,3 +1,4 @@
+use std::ops::Bound;
 use std::sync::{Arc, Mutex};
 
 use arrow::error::{Result};
@@ -7,13 +8,17 @@ use datafusion::logical_plan::Expr;
 use sled::Db as SledDb;
 
 use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
 use crate::datafusion_impl::datasource::sled::SledTable;
-use crate::mysql::error::{MysqlResult};
+use crate::mysql::error::{MysqlError, MysqlResult};
 
 use super::engine_util::TableEngine;
 use crate::meta::meta_def::TableDef;
 use crate::store::reader::sled::SledReader;
 use crate::store::engine::engine_util::StoreEngine;
+use crate::store::engine::transaction;
+use crate::store::mvcc::{self, Mvcc, Snapshot};
+use crate::util::dbkey;
 
 pub struct TableEngineSled {
     global_context: Arc<Mutex<GlobalContext>>,
@@ -38,43 +43,123 @@ impl TableEngine for TableEngineSled {
         Arc::new(provider)
     }
 
+    fn table_provider_in_session(&self, session_context: &SessionContext) -> Arc<dyn TableProvider> {
+        let mut provider = SledTable::new(self.global_context.clone(), self.table.clone());
+        provider.with_transaction(session_context.transaction.clone());
+        Arc::new(provider)
+    }
+
+    /// The rows of the last commit
     fn table_iterator(&self, projection: Option<Vec<usize>>, filters: &[Expr]) -> Box<dyn Iterator<Item = Result<RecordBatch>>> {
-        let reader = SledReader::new(self.global_context.clone(), self.table.clone(), 1024, projection, filters);
+        let snapshot = transaction::read_snapshot(self.global_context.clone(), None);
+        let reader = SledReader::new(self.global_context.clone(), self.table.clone(), 1024, projection, filters, snapshot);
         Box::new(reader)
     }
 }
 
 pub struct StoreEngineSled {
     sled_db: SledDb,
+    mvcc: Arc<Mvcc>,
 }
 
 impl StoreEngineSled {
-    pub fn new(sled_db: SledDb) -> Self {
+    pub fn new(sled_db: SledDb, mvcc: Arc<Mvcc>) -> Self {
         Self {
             sled_db,
+            mvcc,
         }
     }
 }
 
+/// Every write is a commit of its own, the versions of the rows are written with the newest values
 impl StoreEngine for StoreEngineSled {
     fn delete_key(&self, key: String) -> MysqlResult<()> {
-        let _result = self.sled_db.remove(key).unwrap();
-        Ok(())
+        self.mvcc.commit(vec![(key, None)], None)
     }
 
     fn get_key(&self, key: String) -> MysqlResult<Option<Vec<u8>>> {
-        let result = self.sled_db.get(key).unwrap();
-        match result {
-            None => Ok(None),
-            Some(value) => {
-                let v = value.to_vec();
-                Ok(Some(v))
-            },
+        match self.sled_db.get(key.as_bytes()) {
+            Ok(value) => Ok(value.map(|value| value.to_vec())),
+            Err(error) => {
+                Err(MysqlError::new_global_error(1105, format!(
+                    "Unknown error. An error occurred while reading the key, key: {:?}, error: {:?}",
+                    key,
+                    error,
+                ).as_str()))
+            }
+        }
+    }
+
+    fn get_key_at(&self, key: String, snapshot: &Snapshot) -> MysqlResult<Option<Vec<u8>>> {
+        if dbkey::is_versioned_key(key.as_str()) {
+            mvcc::get_visible(&self.sled_db, key.as_str(), snapshot.read_ts())
+        } else {
+            self.get_key(key)
         }
     }
 
     fn put_key(&self, key: String, value: &[u8]) -> MysqlResult<()> {
-        let _result = self.sled_db.insert(key, value).unwrap();
-        Ok(())
+        self.mvcc.commit(vec![(key, Some(value.to_vec()))], None)
+    }
+
+    fn put_keys(&self, key_values: Vec<(String, Vec<u8>)>) -> MysqlResult<()> {
+        let writes = key_values.into_iter().map(|(key, value)| (key, Some(value))).collect();
+        self.mvcc.commit(writes, None)
+    }
+
+    fn write_keys(&self, writes: Vec<(String, Option<Vec<u8>>)>) -> MysqlResult<()> {
+        self.mvcc.commit(writes, None)
+    }
+
+    fn commit_keys(&self, writes: Vec<(String, Option<Vec<u8>>)>, snapshot: &Snapshot) -> MysqlResult<()> {
+        self.mvcc.commit(writes, Some(snapshot))
+    }
+
+    fn delete_prefix(&self, prefix: String) -> MysqlResult<u64> {
+        let mut writes = vec![];
+        for result in self.sled_db.scan_prefix(prefix.clone()).keys() {
+            match result {
+                Ok(key) => writes.push((String::from_utf8_lossy(key.as_ref()).to_string(), None)),
+                Err(error) => {
+                    return Err(MysqlError::new_global_error(1105, format!(
+                        "Unknown error. An error occurred while scanning the prefix, prefix: {:?}, error: {:?}",
+                        prefix,
+                        error,
+                    ).as_str()));
+                }
+            }
+        }
+
+        let count = writes.len() as u64;
+        self.mvcc.commit(writes, None)?;
+        Ok(count)
+    }
+
+    fn scan_prefix_keys(&self, prefix: String, start_after: Option<String>, limit: usize) -> MysqlResult<Vec<String>> {
+        let start = match start_after {
+            Some(key) => Bound::Excluded(key),
+            None => Bound::Included(prefix.clone()),
+        };
+
+        let mut keys = vec![];
+        for result in self.sled_db.range::<String, _>((start, Bound::Unbounded)).keys() {
+            match result {
+                Ok(key) => {
+                    if !key.starts_with(prefix.as_bytes()) || keys.len() >= limit {
+                        break;
+                    }
+                    keys.push(String::from_utf8_lossy(key.as_ref()).to_string());
+                }
+                Err(error) => {
+                    return Err(MysqlError::new_global_error(1105, format!(
+                        "Unknown error. An error occurred while scanning the prefix, prefix: {:?}, error: {:?}",
+                        prefix,
+                        error,
+                    ).as_str()));
+                }
+            }
+        }
+
+        Ok(keys)
     }
 }
@@ -0,0 +1,192 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use crate::core::global_context::GlobalContext;
+use crate::mysql::error::MysqlResult;
+use crate::store::engine::engine_util::StoreEngine;
+use crate::store::lock::LockOwner;
+use crate::store::mvcc::Snapshot;
+
+/// The number of keys read from the store at a time by the scans of the transaction
+const SCAN_BATCH_SIZE: usize = 1024;
+
+/// The writes of the open transaction of a session by the engine of their tables. They are staged in memory
+/// until COMMIT writes them in one batch, a key without a value is deleted. The scans of the transaction
+/// read the rows of its snapshot, the rows it locks are released when it ends
+#[derive(Clone, Debug)]
+pub struct Transaction {
+    snapshot: Snapshot,
+    lock_owner: Arc<LockOwner>,
+    writes: HashMap<String, BTreeMap<String, Option<Vec<u8>>>>,
+}
+
+impl Transaction {
+    pub fn new(snapshot: Snapshot, lock_owner: LockOwner) -> Self {
+        Self {
+            snapshot,
+            lock_owner: Arc::new(lock_owner),
+            writes: HashMap::new(),
+        }
+    }
+
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    pub fn lock_owner(&self) -> Arc<LockOwner> {
+        self.lock_owner.clone()
+    }
+
+    /// The staged writes of every engine in the order of their keys, the transaction is consumed by COMMIT
+    pub fn into_writes(self) -> Vec<(String, Vec<(String, Option<Vec<u8>>)>)> {
+        self.writes
+            .into_iter()
+            .map(|(engine, writes)| (engine, writes.into_iter().collect()))
+            .collect()
+    }
+}
+
+/// The snapshot the scans of a session read, the one of its transaction or the last commit
+pub fn read_snapshot(global_context: Arc<Mutex<GlobalContext>>, transaction: Option<&Arc<Mutex<Option<Transaction>>>>) -> Snapshot {
+    let snapshot = transaction.and_then(|transaction| {
+        transaction.lock().unwrap().as_ref().map(|transaction| transaction.snapshot().clone())
+    });
+    match snapshot {
+        Some(snapshot) => snapshot,
+        None => {
+            let mvcc = global_context.lock().unwrap().engine.sled_mvcc.clone().unwrap();
+            mvcc.snapshot()
+        }
+    }
+}
+
+/// The store engine of a table written by a session in a transaction. The writes are staged in the transaction
+/// of the session, the reads of the keys see the staged writes before the ones committed at its snapshot
+pub struct StoreEngineTransaction {
+    engine: String,
+    store_engine: Box<dyn StoreEngine>,
+    transaction: Arc<Mutex<Option<Transaction>>>,
+}
+
+impl StoreEngineTransaction {
+    pub fn new(engine: &str, store_engine: Box<dyn StoreEngine>, transaction: Arc<Mutex<Option<Transaction>>>) -> Self {
+        Self {
+            engine: engine.to_string(),
+            store_engine,
+            transaction,
+        }
+    }
+
+    /// Stage the writes, they are written to the store at once if the transaction has ended in the meantime
+    fn stage(&self, writes: Vec<(String, Option<Vec<u8>>)>) -> MysqlResult<()> {
+        let mut transaction = self.transaction.lock().unwrap();
+        match transaction.as_mut() {
+            Some(transaction) => {
+                let staged = transaction.writes.entry(self.engine.clone()).or_insert_with(BTreeMap::new);
+                staged.extend(writes);
+                Ok(())
+            }
+            None => self.store_engine.write_keys(writes),
+        }
+    }
+
+    fn staged_prefix(&self, prefix: &str) -> Vec<(String, Option<Vec<u8>>)> {
+        let transaction = self.transaction.lock().unwrap();
+        let staged = transaction.as_ref().and_then(|transaction| transaction.writes.get(self.engine.as_str()));
+        match staged {
+            Some(staged) => staged
+                .range(prefix.to_string()..)
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// The keys starting with the prefix as the statements of the transaction see them
+    fn visible_prefix_keys(&self, prefix: &str) -> MysqlResult<BTreeSet<String>> {
+        let mut keys = BTreeSet::new();
+        let mut start_after = None;
+        loop {
+            let committed_keys = self.store_engine.scan_prefix_keys(prefix.to_string(), start_after, SCAN_BATCH_SIZE)?;
+            let last_key = committed_keys.last().cloned();
+            let count = committed_keys.len();
+            keys.extend(committed_keys);
+            if count < SCAN_BATCH_SIZE {
+                break;
+            }
+            start_after = last_key;
+        }
+
+        for (key, value) in self.staged_prefix(prefix) {
+            match value {
+                Some(_) => keys.insert(key),
+                None => keys.remove(key.as_str()),
+            };
+        }
+        Ok(keys)
+    }
+}
+
+impl StoreEngine for StoreEngineTransaction {
+    fn delete_key(&self, key: String) -> MysqlResult<()> {
+        self.stage(vec![(key, None)])
+    }
+
+    fn get_key(&self, key: String) -> MysqlResult<Option<Vec<u8>>> {
+        let transaction = self.transaction.lock().unwrap();
+        match transaction.as_ref() {
+            Some(transaction) => {
+                let staged = transaction
+                    .writes
+                    .get(self.engine.as_str())
+                    .and_then(|staged| staged.get(key.as_str()));
+                match staged {
+                    Some(value) => Ok(value.clone()),
+                    None => self.store_engine.get_key_at(key, transaction.snapshot()),
+                }
+            }
+            None => self.store_engine.get_key(key),
+        }
+    }
+
+    fn get_key_at(&self, key: String, snapshot: &Snapshot) -> MysqlResult<Option<Vec<u8>>> {
+        self.store_engine.get_key_at(key, snapshot)
+    }
+
+    fn put_key(&self, key: String, value: &[u8]) -> MysqlResult<()> {
+        self.stage(vec![(key, Some(value.to_vec()))])
+    }
+
+    fn put_keys(&self, key_values: Vec<(String, Vec<u8>)>) -> MysqlResult<()> {
+        self.stage(key_values.into_iter().map(|(key, value)| (key, Some(value))).collect())
+    }
+
+    fn write_keys(&self, writes: Vec<(String, Option<Vec<u8>>)>) -> MysqlResult<()> {
+        self.stage(writes)
+    }
+
+    fn commit_keys(&self, writes: Vec<(String, Option<Vec<u8>>)>, _snapshot: &Snapshot) -> MysqlResult<()> {
+        self.stage(writes)
+    }
+
+    fn delete_prefix(&self, prefix: String) -> MysqlResult<u64> {
+        let keys = self.visible_prefix_keys(prefix.as_str())?;
+        let count = keys.len() as u64;
+        self.stage(keys.into_iter().map(|key| (key, None)).collect())?;
+        Ok(count)
+    }
+
+    fn scan_prefix_keys(&self, prefix: String, start_after: Option<String>, limit: usize) -> MysqlResult<Vec<String>> {
+        let keys = self.visible_prefix_keys(prefix.as_str())?;
+        let keys = keys
+            .into_iter()
+            .filter(|key| match &start_after {
+                Some(start_after) => key > start_after,
+                None => true,
+            })
+            .take(limit)
+            .collect();
+        Ok(keys)
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+
+/// The shared lock of SELECT ... LOCK IN SHARE MODE and FOR SHARE, the exclusive lock of UPDATE, DELETE
+/// and SELECT ... FOR UPDATE
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+/// The row of a table by its full table name and its rowid
+type RowKey = (String, String);
+
+#[derive(Debug)]
+struct RowLock {
+    mode: LockMode,
+    owners: HashSet<u64>,
+}
+
+#[derive(Debug, Default)]
+struct LockState {
+    rows: HashMap<RowKey, RowLock>,
+    /// The rows locked by every transaction, released together when it ends
+    owned: HashMap<u64, HashSet<RowKey>>,
+    /// The transactions every waiting transaction waits for
+    waits_for: HashMap<u64, HashSet<u64>>,
+}
+
+impl LockState {
+    /// Lock the row for the owner, or the other owners holding a conflicting lock
+    fn try_lock(&mut self, owner: u64, row_key: &RowKey, mode: LockMode) -> Result<(), HashSet<u64>> {
+        match self.rows.get_mut(row_key) {
+            None => {
+                let mut owners = HashSet::new();
+                owners.insert(owner);
+                self.rows.insert(row_key.clone(), RowLock { mode, owners });
+            }
+            Some(row_lock) => {
+                let others: HashSet<u64> = row_lock.owners.iter().filter(|other| **other != owner).cloned().collect();
+                let compatible = others.is_empty() || (row_lock.mode == LockMode::Shared && mode == LockMode::Shared);
+                if !compatible {
+                    return Err(others);
+                }
+                row_lock.owners.insert(owner);
+                // the only owner of a shared lock upgrades it
+                if mode == LockMode::Exclusive {
+                    row_lock.mode = LockMode::Exclusive;
+                }
+            }
+        }
+        self.owned.entry(owner).or_insert_with(HashSet::new).insert(row_key.clone());
+        Ok(())
+    }
+
+    /// The owner waiting for the holders closes a cycle of waits
+    fn is_deadlock(&self, owner: u64, holders: &HashSet<u64>) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending: Vec<u64> = holders.iter().cloned().collect();
+        while let Some(waiting) = pending.pop() {
+            if waiting == owner {
+                return true;
+            }
+            if !visited.insert(waiting) {
+                continue;
+            }
+            if let Some(holders) = self.waits_for.get(&waiting) {
+                pending.extend(holders.iter().cloned());
+            }
+        }
+        false
+    }
+
+    fn release_all(&mut self, owner: u64) {
+        self.waits_for.remove(&owner);
+        let row_keys = match self.owned.remove(&owner) {
+            Some(row_keys) => row_keys,
+            None => return,
+        };
+        for row_key in row_keys {
+            let released = match self.rows.get_mut(&row_key) {
+                Some(row_lock) => {
+                    row_lock.owners.remove(&owner);
+                    row_lock.owners.is_empty()
+                }
+                None => false,
+            };
+            if released {
+                self.rows.remove(&row_key);
+            }
+        }
+    }
+}
+
+/// The row locks of the transactions. A transaction waits for the conflicting locks until they are released
+/// or its lock wait timeout, the transaction whose wait would close a cycle of waits is the deadlock victim
+#[derive(Debug, Default)]
+pub struct LockManager {
+    state: Mutex<LockState>,
+    /// Notified when the locks of a transaction are released, the waiting transactions try again
+    released: Notify,
+    next_owner: AtomicU64,
+}
+
+impl LockManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The owner of the locks of a new transaction, its locks are released when it is dropped
+    pub fn owner(self: &Arc<Self>) -> LockOwner {
+        LockOwner {
+            id: self.next_owner.fetch_add(1, Ordering::SeqCst) + 1,
+            lock_manager: self.clone(),
+        }
+    }
+
+    /// Lock the rows of the table for the owner, in the order of the rowids
+    pub async fn lock_rows(
+        &self,
+        owner: &LockOwner,
+        full_table_name: &str,
+        rowids: Vec<String>,
+        mode: LockMode,
+        lock_wait_timeout: Duration,
+    ) -> MysqlResult<()> {
+        for rowid in rowids {
+            let row_key = (full_table_name.to_string(), rowid);
+            self.lock_row(owner.id, &row_key, mode, lock_wait_timeout).await?;
+        }
+        Ok(())
+    }
+
+    async fn lock_row(&self, owner: u64, row_key: &RowKey, mode: LockMode, lock_wait_timeout: Duration) -> MysqlResult<()> {
+        let deadline = tokio::time::Instant::now() + lock_wait_timeout;
+        loop {
+            // created before the check, the release between the check and the wait is not missed
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                match state.try_lock(owner, row_key, mode) {
+                    Ok(()) => {
+                        state.waits_for.remove(&owner);
+                        return Ok(());
+                    }
+                    Err(holders) => {
+                        if state.is_deadlock(owner, &holders) {
+                            state.waits_for.remove(&owner);
+                            return Err(deadlock_error());
+                        }
+                        state.waits_for.insert(owner, holders);
+                    }
+                }
+            }
+            if tokio::time::timeout_at(deadline, released).await.is_err() {
+                self.state.lock().unwrap().waits_for.remove(&owner);
+                return Err(lock_wait_timeout_error());
+            }
+        }
+    }
+
+    fn release_all(&self, owner: u64) {
+        self.state.lock().unwrap().release_all(owner);
+        self.released.notify_waiters();
+    }
+}
+
+/// The transaction holding the row locks, they are released when the transaction ends
+pub struct LockOwner {
+    id: u64,
+    lock_manager: Arc<LockManager>,
+}
+
+impl LockOwner {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for LockOwner {
+    fn drop(&mut self) {
+        self.lock_manager.release_all(self.id);
+    }
+}
+
+impl fmt::Debug for LockOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockOwner").field("id", &self.id).finish()
+    }
+}
+
+/// The victim of the deadlock, its transaction is rolled back
+pub fn deadlock_error() -> MysqlError {
+    MysqlError::new_server_error(
+        mysql_error_code::ER_LOCK_DEADLOCK as u16,
+        "40001",
+        "Deadlock found when trying to get lock; try restarting transaction",
+    )
+}
+
+/// Only the statement waiting for the lock fails, its transaction stays open
+pub fn lock_wait_timeout_error() -> MysqlError {
+    MysqlError::new_server_error(
+        mysql_error_code::ER_LOCK_WAIT_TIMEOUT as u16,
+        "HY000",
+        "Lock wait timeout exceeded; try restarting transaction",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rowids(rowids: &[&str]) -> Vec<String> {
+        rowids.iter().map(|rowid| rowid.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn shared_and_exclusive() -> MysqlResult<()> {
+        let lock_manager = Arc::new(LockManager::new());
+        let owner_a = lock_manager.owner();
+        let owner_b = lock_manager.owner();
+        let timeout = Duration::from_millis(50);
+
+        // the shared locks do not conflict, the exclusive lock waits for them
+        lock_manager.lock_rows(&owner_a, "def.test.t1", rowids(&["a1"]), LockMode::Shared, timeout).await?;
+        lock_manager.lock_rows(&owner_b, "def.test.t1", rowids(&["a1"]), LockMode::Shared, timeout).await?;
+        let result = lock_manager.lock_rows(&owner_a, "def.test.t1", rowids(&["a1"]), LockMode::Exclusive, timeout).await;
+        assert_eq!(1205, result.unwrap_err().error_number());
+
+        // the locks of the dropped owner are released
+        drop(owner_b);
+        lock_manager.lock_rows(&owner_a, "def.test.t1", rowids(&["a1"]), LockMode::Exclusive, timeout).await?;
+        let owner_c = lock_manager.owner();
+        lock_manager.lock_rows(&owner_c, "def.test.t2", rowids(&["a1"]), LockMode::Exclusive, timeout).await?;
+        let result = lock_manager.lock_rows(&owner_c, "def.test.t1", rowids(&["a1"]), LockMode::Shared, timeout).await;
+        assert_eq!(1205, result.unwrap_err().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deadlock() -> MysqlResult<()> {
+        let lock_manager = Arc::new(LockManager::new());
+        let owner_a = lock_manager.owner();
+        let owner_b = lock_manager.owner();
+        let timeout = Duration::from_secs(5);
+
+        lock_manager.lock_rows(&owner_a, "def.test.t1", rowids(&["a1"]), LockMode::Exclusive, timeout).await?;
+        lock_manager.lock_rows(&owner_b, "def.test.t1", rowids(&["b1"]), LockMode::Exclusive, timeout).await?;
+
+        // the second wait closes the cycle, its transaction is the victim and the other one gets the lock
+        let wait_a = lock_manager.lock_rows(&owner_a, "def.test.t1", rowids(&["b1"]), LockMode::Exclusive, timeout);
+        let wait_b = async {
+            tokio::task::yield_now().await;
+            let result = lock_manager.lock_rows(&owner_b, "def.test.t1", rowids(&["a1"]), LockMode::Exclusive, timeout).await;
+            assert_eq!(1213, result.unwrap_err().error_number());
+            drop(owner_b);
+        };
+        let (result_a, _) = tokio::join!(wait_a, wait_b);
+        result_a?;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
 
 pub mod reader;
 pub mod engine;
+pub mod compression;
+pub mod mvcc;
+pub mod lock;
@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sled::Batch as SledBatch;
+use sled::Db as SledDb;
+use sled::IVec;
+
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::mysql::mysql_error_code;
+use crate::util::dbkey;
+
+/// The first byte of the value of a version, the version of a deleted key has no value after it
+const VERSION_VALUE_MARK: u8 = 0x01;
+const VERSION_DELETED_MARK: u8 = 0x00;
+
+/// The number of keys written or removed by one batch of the backfill and of the garbage collection
+const BATCH_SIZE: usize = 1024;
+
+pub fn encode_version_value(value: Option<&[u8]>) -> Vec<u8> {
+    match value {
+        Some(value) => {
+            let mut bytes = Vec::with_capacity(value.len() + 1);
+            bytes.push(VERSION_VALUE_MARK);
+            bytes.extend_from_slice(value);
+            bytes
+        }
+        None => vec![VERSION_DELETED_MARK],
+    }
+}
+
+/// The value of the version, None if the key was deleted by it
+pub fn decode_version_value(bytes: &[u8]) -> Option<Vec<u8>> {
+    match bytes.first() {
+        Some(&VERSION_VALUE_MARK) => Some(bytes[1..].to_vec()),
+        _ => None,
+    }
+}
+
+/// The value of the key seen by the snapshot, None if the key did not exist or was deleted at the snapshot
+pub fn get_visible(sled_db: &SledDb, key: &str, read_ts: u64) -> MysqlResult<Option<Vec<u8>>> {
+    let start = dbkey::scan_version_key(key);
+    let end = dbkey::create_version_key(key, read_ts);
+    match sled_db.range(start.as_bytes()..=end.as_bytes()).next_back() {
+        Some(Ok((_, value))) => Ok(decode_version_value(value.as_ref())),
+        Some(Err(error)) => Err(sled_error("reading the version of the key", key, error)),
+        None => Ok(None),
+    }
+}
+
+fn sled_error(action: &str, key: &str, error: sled::Error) -> MysqlError {
+    MysqlError::new_global_error(1105, format!(
+        "Unknown error. An error occurred while {}, key: {:?}, error: {:?}",
+        action,
+        key,
+        error,
+    ).as_str())
+}
+
+/// The write conflict of the snapshot isolation, the clients retry the transaction as for a deadlock
+fn write_conflict_error() -> MysqlError {
+    MysqlError::new_server_error(
+        mysql_error_code::ER_LOCK_DEADLOCK as u16,
+        "40001",
+        "Deadlock found when trying to get lock; try restarting transaction",
+    )
+}
+
+/// The versions of the rows of the sled engine. Every write is a commit with the next timestamp, it writes
+/// the newest values of the keys for the writers and a version of the keys of the rows for the snapshots,
+/// so the readers never wait for the writers
+#[derive(Debug)]
+pub struct Mvcc {
+    sled_db: SledDb,
+    /// The timestamp of the last commit applied, the new snapshots read it
+    last_commit_ts: AtomicU64,
+    /// The commits take their timestamps and apply their batches one at a time
+    commit_lock: Mutex<()>,
+    /// The timestamps of the snapshots being read with the number of their readers,
+    /// the versions they read are kept by the garbage collection
+    snapshots: Mutex<BTreeMap<u64, usize>>,
+}
+
+impl Mvcc {
+    /// The timestamp of the last commit is read from the db, the db written before the versions
+    /// gets a version of every row at the timestamp 0
+    pub fn open(sled_db: SledDb) -> MysqlResult<Self> {
+        let commit_ts_key = dbkey::create_commit_ts_key();
+        let last_commit_ts = match sled_db.get(commit_ts_key.as_bytes()) {
+            Ok(Some(value)) => String::from_utf8_lossy(value.as_ref()).parse::<u64>().unwrap_or(0),
+            Ok(None) => {
+                backfill_versions(&sled_db)?;
+                0
+            }
+            Err(error) => return Err(sled_error("reading the commit timestamp", commit_ts_key.as_str(), error)),
+        };
+
+        Ok(Self {
+            sled_db,
+            last_commit_ts: AtomicU64::new(last_commit_ts),
+            commit_lock: Mutex::new(()),
+            snapshots: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    pub fn last_commit_ts(&self) -> u64 {
+        self.last_commit_ts.load(Ordering::SeqCst)
+    }
+
+    /// The snapshot of the last commit, its versions are kept until it is dropped
+    pub fn snapshot(self: &Arc<Self>) -> Snapshot {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        let read_ts = self.last_commit_ts();
+        *snapshots.entry(read_ts).or_insert(0) += 1;
+        Snapshot {
+            read_ts,
+            mvcc: self.clone(),
+        }
+    }
+
+    fn release(&self, read_ts: u64) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        if let Some(count) = snapshots.get_mut(&read_ts) {
+            *count -= 1;
+            if *count == 0 {
+                snapshots.remove(&read_ts);
+            }
+        }
+    }
+
+    /// The timestamp of the oldest snapshot being read, the last commit if there is none
+    pub fn oldest_snapshot_ts(&self) -> u64 {
+        let snapshots = self.snapshots.lock().unwrap();
+        match snapshots.keys().next() {
+            Some(read_ts) => *read_ts,
+            None => self.last_commit_ts(),
+        }
+    }
+
+    /// Write the keys with a value and delete the keys without one in one batch with the next commit timestamp.
+    /// The transaction of the snapshot fails if one of its keys was committed by another one after the snapshot
+    pub fn commit(&self, writes: Vec<(String, Option<Vec<u8>>)>, snapshot: Option<&Snapshot>) -> MysqlResult<()> {
+        let _commit_guard = self.commit_lock.lock().unwrap();
+        if let Some(snapshot) = snapshot {
+            for (key, _) in writes.iter() {
+                if dbkey::is_versioned_key(key) && self.last_version_ts(key)? > snapshot.read_ts() {
+                    return Err(write_conflict_error());
+                }
+            }
+        }
+
+        let commit_ts = self.last_commit_ts() + 1;
+        let count = writes.len();
+        let mut batch = SledBatch::default();
+        for (key, value) in writes {
+            if dbkey::is_versioned_key(key.as_str()) {
+                let version_key = dbkey::create_version_key(key.as_str(), commit_ts);
+                batch.insert(version_key.as_bytes(), encode_version_value(value.as_deref()));
+            }
+            match value {
+                Some(value) => batch.insert(key.as_bytes(), value),
+                None => batch.remove(key.as_bytes()),
+            }
+        }
+        batch.insert(dbkey::create_commit_ts_key().as_bytes(), commit_ts.to_string().as_bytes());
+
+        let result = self.sled_db.apply_batch(batch);
+        if let Err(error) = result {
+            return Err(MysqlError::new_global_error(1105, format!(
+                "Unknown error. An error occurred while committing the keys, count: {:?}, error: {:?}",
+                count,
+                error,
+            ).as_str()));
+        }
+        self.last_commit_ts.store(commit_ts, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// The commit timestamp of the newest version of the key, 0 if it has none
+    fn last_version_ts(&self, key: &str) -> MysqlResult<u64> {
+        let prefix = dbkey::scan_version_key(key);
+        match self.sled_db.scan_prefix(prefix.as_bytes()).keys().next_back() {
+            Some(Ok(version_key)) => {
+                let version_key = String::from_utf8_lossy(version_key.as_ref()).to_string();
+                Ok(dbkey::parse_version_key(version_key.as_str()).map(|(_, commit_ts)| commit_ts).unwrap_or(0))
+            }
+            Some(Err(error)) => Err(sled_error("reading the version of the key", key, error)),
+            None => Ok(0),
+        }
+    }
+
+    /// Remove the versions no snapshot reads anymore, returns the number of the removed versions.
+    /// Of the versions not newer than the oldest snapshot only the newest one is kept, and none if it is a deletion
+    pub fn collect_garbage(&self) -> MysqlResult<u64> {
+        let oldest_ts = self.oldest_snapshot_ts();
+
+        let mut garbage = Garbage::new(&self.sled_db);
+        let mut current_key: Option<String> = None;
+        // the newest version of the current key seen by the oldest snapshot, and whether it is a deletion
+        let mut visible: Option<(IVec, bool)> = None;
+        // the versions of a key are next to each other in the order of their commits
+        for result in self.sled_db.scan_prefix(dbkey::VERSION_KEY_PREFIX.as_bytes()) {
+            let (version_key, value) = match result {
+                Ok(item) => item,
+                Err(error) => return Err(sled_error("scanning the versions", dbkey::VERSION_KEY_PREFIX, error)),
+            };
+            let (key, commit_ts) = match dbkey::parse_version_key(&String::from_utf8_lossy(version_key.as_ref())) {
+                Some(key_commit_ts) => key_commit_ts,
+                None => continue,
+            };
+
+            if current_key.as_deref() != Some(key.as_str()) {
+                if let Some((version_key, true)) = visible.take() {
+                    garbage.remove(version_key)?;
+                }
+                current_key = Some(key);
+            }
+            if commit_ts > oldest_ts {
+                continue;
+            }
+            let deleted = decode_version_value(value.as_ref()).is_none();
+            if let Some((older_version_key, _)) = visible.replace((version_key, deleted)) {
+                garbage.remove(older_version_key)?;
+            }
+        }
+        if let Some((version_key, true)) = visible.take() {
+            garbage.remove(version_key)?;
+        }
+
+        garbage.finish()
+    }
+}
+
+/// The versions removed by the garbage collection, applied in batches of BATCH_SIZE
+struct Garbage<'a> {
+    sled_db: &'a SledDb,
+    batch: SledBatch,
+    batch_count: usize,
+    count: u64,
+}
+
+impl<'a> Garbage<'a> {
+    fn new(sled_db: &'a SledDb) -> Self {
+        Self {
+            sled_db,
+            batch: SledBatch::default(),
+            batch_count: 0,
+            count: 0,
+        }
+    }
+
+    fn remove(&mut self, version_key: IVec) -> MysqlResult<()> {
+        self.batch.remove(version_key);
+        self.batch_count += 1;
+        self.count += 1;
+        if self.batch_count >= BATCH_SIZE {
+            self.apply()?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self) -> MysqlResult<()> {
+        let batch = std::mem::take(&mut self.batch);
+        self.batch_count = 0;
+        self.sled_db.apply_batch(batch).map_err(|error| sled_error("removing the versions", dbkey::VERSION_KEY_PREFIX, error))
+    }
+
+    fn finish(mut self) -> MysqlResult<u64> {
+        self.apply()?;
+        Ok(self.count)
+    }
+}
+
+/// The rows written before the versions are seen by every snapshot
+fn backfill_versions(sled_db: &SledDb) -> MysqlResult<()> {
+    let mut batch = SledBatch::default();
+    let mut batch_count = 0;
+    for prefix in dbkey::VERSIONED_KEY_PREFIXES.iter() {
+        for result in sled_db.scan_prefix(prefix.as_bytes()) {
+            let (key, value) = match result {
+                Ok(item) => item,
+                Err(error) => return Err(sled_error("scanning the rows", prefix, error)),
+            };
+            let key = String::from_utf8_lossy(key.as_ref()).to_string();
+            let version_key = dbkey::create_version_key(key.as_str(), 0);
+            batch.insert(version_key.as_bytes(), encode_version_value(Some(value.as_ref())));
+            batch_count += 1;
+            if batch_count >= BATCH_SIZE {
+                sled_db
+                    .apply_batch(std::mem::take(&mut batch))
+                    .map_err(|error| sled_error("writing the versions", prefix, error))?;
+                batch_count = 0;
+            }
+        }
+    }
+    batch.insert(dbkey::create_commit_ts_key().as_bytes(), "0".as_bytes());
+    sled_db
+        .apply_batch(batch)
+        .map_err(|error| sled_error("writing the versions", dbkey::VERSION_KEY_PREFIX, error))
+}
+
+/// Remove the versions no snapshot reads anymore every interval, in a blocking task
+pub async fn run_garbage_collection(mvcc: Arc<Mvcc>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        let mvcc = mvcc.clone();
+        match tokio::task::spawn_blocking(move || mvcc.collect_garbage()).await {
+            Ok(Ok(count)) if count > 0 => log::info!("removed {} versions of the rows", count),
+            Ok(Ok(_)) => {}
+            Ok(Err(mysql_error)) => log::error!("remove the versions of the rows error: {}", mysql_error),
+            Err(error) => log::error!("remove the versions of the rows error: {:?}", error),
+        }
+    }
+}
+
+/// The commit timestamp a reader sees the rows at, the versions committed after it are skipped
+pub struct Snapshot {
+    read_ts: u64,
+    mvcc: Arc<Mvcc>,
+}
+
+impl Snapshot {
+    pub fn read_ts(&self) -> u64 {
+        self.read_ts
+    }
+}
+
+impl Clone for Snapshot {
+    fn clone(&self) -> Self {
+        *self.mvcc.snapshots.lock().unwrap().entry(self.read_ts).or_insert(0) += 1;
+        Self {
+            read_ts: self.read_ts,
+            mvcc: self.mvcc.clone(),
+        }
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        self.mvcc.release(self.read_ts);
+    }
+}
+
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshot").field("read_ts", &self.read_ts).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_value() {
+        assert_eq!(Some(vec![0x00]), decode_version_value(encode_version_value(Some(&[0x00])).as_slice()));
+        assert_eq!(Some(vec![]), decode_version_value(encode_version_value(Some(&[])).as_slice()));
+        assert_eq!(None, decode_version_value(encode_version_value(None).as_slice()));
+    }
+
+    #[test]
+    fn version_key() {
+        let version_key = dbkey::create_version_key("/Table/rowid/def.test.t1/a1", 12);
+        assert_eq!("/Version/Table/rowid/def.test.t1/a1@00000000000000000012", version_key);
+        assert_eq!(
+            Some(("/Table/rowid/def.test.t1/a1".to_string(), 12)),
+            dbkey::parse_version_key(version_key.as_str())
+        );
+        assert!(version_key < dbkey::create_version_key("/Table/rowid/def.test.t1/a1", 13));
+        assert!(version_key.starts_with(dbkey::scan_version_key("/Table/rowid/def.test.t1/a1").as_str()));
+    }
+}
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::{Arc, Mutex};
 
 use datafusion::logical_plan::{Expr};
@@ -8,10 +9,12 @@ use datafusion::scalar::ScalarValue;
 use sqlparser::ast::{ObjectName, TableConstraint};
 
 use crate::core::global_context::GlobalContext;
+use crate::meta::meta_const;
 use crate::meta::meta_def::TableDef;
 use crate::mysql::error::{MysqlResult};
 use crate::util::dbkey;
 use crate::util::dbkey::CreateScanKey;
+use crate::util::like;
 
 const INDEX_LEVEL_PRIMARY: i32 = 0;
 const INDEX_LEVEL_UNIQUE: i32 = 1;
@@ -45,19 +48,118 @@ impl RangeNotNullValue {
     }
 }
 
+/// A boundary of the scan, it matches all the keys starting with the key
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScanBound {
+    pub key: Vec<u8>,
+    pub inclusive: bool,
+}
+
+impl ScanBound {
+    pub fn new(key: &[u8], inclusive: bool) -> Self {
+        Self {
+            key: key.to_vec(),
+            inclusive,
+        }
+    }
+}
+
+impl From<CreateScanKey> for ScanBound {
+    fn from(scan_key: CreateScanKey) -> Self {
+        let inclusive = match scan_key.point_type() {
+            PointType::Open => false,
+            PointType::Closed => true,
+        };
+        ScanBound::new(scan_key.key().as_bytes(), inclusive)
+    }
+}
+
+/// The keys with the prefix between the start and the end,
+/// a side without bound runs to the start or the end of the prefix
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeScan {
+    pub prefix: Vec<u8>,
+    pub start: Option<ScanBound>,
+    pub end: Option<ScanBound>,
+}
+
+impl RangeScan {
+    pub fn new(prefix: &[u8], start: Option<ScanBound>, end: Option<ScanBound>) -> Self {
+        Self {
+            prefix: prefix.to_vec(),
+            start,
+            end,
+        }
+    }
+
+    /// The same range over the versions of the keys, the commit timestamp after a key keeps
+    /// its versions on the same side of the bounds as the key
+    pub fn versioned(&self) -> RangeScan {
+        let version_bound = |bound: &ScanBound| {
+            let mut key = dbkey::VERSION_KEY_PREFIX.as_bytes().to_vec();
+            key.extend_from_slice(bound.key.as_slice());
+            ScanBound::new(key.as_slice(), bound.inclusive)
+        };
+        let mut prefix = dbkey::VERSION_KEY_PREFIX.as_bytes().to_vec();
+        prefix.extend_from_slice(self.prefix.as_slice());
+        RangeScan::new(prefix.as_slice(), self.start.as_ref().map(version_bound), self.end.as_ref().map(version_bound))
+    }
+
+    /// The first key to seek
+    pub fn seek_key(&self) -> Vec<u8> {
+        match &self.start {
+            None => self.prefix.clone(),
+            Some(start) => start.key.clone(),
+        }
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        if !key.starts_with(self.prefix.as_slice()) {
+            return false;
+        }
+        let after_start = match &self.start {
+            None => true,
+            Some(start) if start.inclusive => key >= start.key.as_slice(),
+            Some(start) => key > start.key.as_slice() && !key.starts_with(start.key.as_slice()),
+        };
+        after_start && !self.is_after_end(key)
+    }
+
+    /// The keys are iterated in order, no key after this one is in the range
+    pub fn is_after_end(&self, key: &[u8]) -> bool {
+        if !key.starts_with(self.prefix.as_slice()) {
+            return key > self.prefix.as_slice();
+        }
+        match &self.end {
+            None => false,
+            Some(end) if end.inclusive => key > end.key.as_slice() && !key.starts_with(end.key.as_slice()),
+            Some(end) => key >= end.key.as_slice(),
+        }
+    }
+}
+
 pub enum SeekType {
     FullTableScan {
-        start: CreateScanKey,
-        end: CreateScanKey,
+        range: RangeScan,
     },
     UsingTheIndex {
         index_name: String,
         order: ScanOrder,
-        start: CreateScanKey,
-        end: CreateScanKey,
+        range: RangeScan,
     },
 }
 
+/// The seek of the scan as shown by EXPLAIN FORMAT=TREE
+impl fmt::Display for SeekType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeekType::FullTableScan { .. } => write!(f, "full table scan"),
+            SeekType::UsingTheIndex { index_name, order: ScanOrder::Asc, .. } => write!(f, "index range scan on {}", index_name),
+            SeekType::UsingTheIndex { index_name, order: ScanOrder::Desc, .. } => write!(f, "index range scan on {} (reverse)", index_name),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum CompareResult {
     /// contain null and not null
@@ -99,10 +201,54 @@ pub enum PointType {
     Closed,
 }
 
+/// The range of a LIKE pattern, only if the pattern starts with literal chars,
+/// an escaped wildcard is a literal char.
+pub fn create_like_range(pattern: &ScalarValue) -> Option<Range> {
+    let pattern = match pattern {
+        ScalarValue::Utf8(Some(pattern)) => pattern,
+        _ => return None,
+    };
+
+    let prefix = like::like_pattern_prefix(pattern.as_str(), like::DEFAULT_ESCAPE_CHAR);
+    if prefix.is_empty() {
+        return None;
+    }
+
+    // the end is the prefix with the last char increased
+    let mut chars: Vec<char> = prefix.chars().collect();
+    let last_char = chars.pop().unwrap();
+    let end = match std::char::from_u32(last_char as u32 + 1) {
+        None => RangePoint::Infinity,
+        Some(next_char) => {
+            chars.push(next_char);
+            let end_prefix: String = chars.into_iter().collect();
+            RangePoint::NotNullValue(ScalarValue::Utf8(Some(end_prefix)), PointType::Open)
+        }
+    };
+
+    Some(Range {
+        start: RangePoint::NotNullValue(ScalarValue::Utf8(Some(prefix)), PointType::Closed),
+        end,
+    })
+}
+
 pub fn create_column_range(operators: Vec<Expr>) -> Range {
     let mut range_list = vec![];
     for operator in operators {
         let range = match operator {
+            Expr::ScalarUDF { fun, args } => {
+                if fun.name != meta_const::FUNCTION_NAME_LIKE_ESCAPE || args.len() != 2 {
+                    continue;
+                }
+                let range = match &args[1] {
+                    Expr::Literal(scalar_value) => create_like_range(scalar_value),
+                    _ => None,
+                };
+                match range {
+                    Some(range) => range,
+                    None => continue,
+                }
+            }
             Expr::BinaryExpr { op, right, .. } => {
                 let scalar_value = match right.as_ref() {
                     Expr::Literal(scalar_value) => {
@@ -132,16 +278,33 @@ pub fn create_column_range(operators: Vec<Expr>) -> Range {
                     }
                     Operator::Lt => {
                         Range {
-                            start: RangePoint::Infinity,
+                            start: RangePoint::NotNull,
                             end: RangePoint::NotNullValue(scalar_value.clone(), PointType::Open),
                         }
                     }
                     Operator::LtEq => {
                         Range {
-                            start: RangePoint::Infinity,
+                            start: RangePoint::NotNull,
                             end: RangePoint::NotNullValue(scalar_value.clone(), PointType::Closed),
                         }
                     }
+                    Operator::Like => {
+                        match create_like_range(&scalar_value) {
+                            Some(range) => range,
+                            None => continue,
+                        }
+                    }
+                    _ => continue,
+                }
+            }
+            Expr::Between { negated: false, low, high, .. } => {
+                match (low.as_ref(), high.as_ref()) {
+                    (Expr::Literal(low), Expr::Literal(high)) => {
+                        Range {
+                            start: RangePoint::NotNullValue(low.clone(), PointType::Closed),
+                            end: RangePoint::NotNullValue(high.clone(), PointType::Closed),
+                        }
+                    }
                     _ => continue,
                 }
             }
@@ -153,8 +316,8 @@ pub fn create_column_range(operators: Vec<Expr>) -> Range {
             }
             Expr::IsNull(_) => {
                 Range {
-                    start: RangePoint::NotNull,
-                    end: RangePoint::NotNull,
+                    start: RangePoint::Null,
+                    end: RangePoint::Null,
                 }
             }
             _ => continue,
@@ -283,9 +446,19 @@ pub fn get_seek_prefix(global_context: Arc<Mutex<GlobalContext>>, full_table_nam
     }
 }
 
+/// The scan key without any column value is the whole prefix, the side is unbounded
+fn create_scan_bound(prefix: &str, scan_key: CreateScanKey) -> Option<ScanBound> {
+    if scan_key.key() == prefix {
+        None
+    } else {
+        Some(ScanBound::from(scan_key))
+    }
+}
+
 pub fn get_seek_prefix_default(table: TableDef) -> SeekType {
     let scan_key = dbkey::create_scan_rowid(table.clone());
-    SeekType::FullTableScan { start: scan_key.clone(), end: scan_key.clone() }
+    let range = RangeScan::new(scan_key.key().as_bytes(), None, None);
+    SeekType::FullTableScan { range }
 }
 
 pub fn create_column_filter(filters: &[Expr]) -> MysqlResult<HashMap<String, Vec<Expr>>> {
@@ -317,10 +490,26 @@ pub fn create_column_filter(filters: &[Expr]) -> MysqlResult<HashMap<String, Vec
                     _ => continue
                 }
             }
+            Expr::Between { expr, .. } => {
+                match expr.as_ref() {
+                    Expr::Column(value) => {
+                        column_name = value.clone();
+                    }
+                    _ => continue
+                }
+            }
+            Expr::ScalarUDF { fun, args } if fun.name == meta_const::FUNCTION_NAME_LIKE_ESCAPE => {
+                match args.first() {
+                    Some(Expr::Column(value)) => {
+                        column_name = value.clone();
+                    }
+                    _ => continue
+                }
+            }
             _ => continue
         }
 
-        column_filter_map.entry(column_name.to_string()).or_insert(vec![]).push(expr.clone());
+        column_filter_map.entry(column_name.name.clone()).or_insert(vec![]).push(expr.clone());
     }
 
     Ok(column_filter_map)
@@ -394,7 +583,15 @@ pub fn get_seek_prefix_with_index(global_context: Arc<Mutex<GlobalContext>>, tab
         }
     });
 
-    let (start, end) = dbkey::create_scan_index(table.clone(), table_index.clone());
+    let (mut start, mut end) = dbkey::create_scan_index(table.clone(), table_index.clone())?;
+    let mut prefix = format!("{}{}/", dbkey::scan_table_index_key(&table.option), table_index.index_name);
+    if table.option.without_rowid && table_index.level == INDEX_LEVEL_PRIMARY {
+        // the primary index of the table without rowid is stored in the rowid keys
+        let rowid_prefix = dbkey::create_scan_rowid(table.clone()).key();
+        start.key = start.key.replacen(prefix.as_str(), rowid_prefix.as_str(), 1);
+        end.key = end.key.replacen(prefix.as_str(), rowid_prefix.as_str(), 1);
+        prefix = rowid_prefix;
+    }
     let order = match start.key().as_str().partial_cmp(end.key().as_str()) {
         None => ScanOrder::Asc,
         Some(order) => {
@@ -409,7 +606,182 @@ pub fn get_seek_prefix_with_index(global_context: Arc<Mutex<GlobalContext>>, tab
     Ok(SeekType::UsingTheIndex {
         index_name: table_index.index_name,
         order,
-        start,
-        end,
+        range: RangeScan::new(prefix.as_bytes(), create_scan_bound(prefix.as_str(), start), create_scan_bound(prefix.as_str(), end)),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::meta_def::{TableColumnDef, TableOptionDef};
+    use datafusion::logical_plan::{col, lit};
+    use sqlparser::ast::{ColumnDef, DataType, Ident};
+
+    #[test]
+    fn range_scan_inclusive() {
+        let range = RangeScan::new(b"/t/", Some(ScanBound::new(b"/t/1/b/", true)), Some(ScanBound::new(b"/t/1/d/", true)));
+        assert!(!range.contains(b"/t/1/a/x"));
+        assert!(range.contains(b"/t/1/b/x"));
+        assert!(range.contains(b"/t/1/c/x"));
+        assert!(range.contains(b"/t/1/d/x"));
+        assert!(!range.contains(b"/t/1/e/x"));
+        assert!(range.is_after_end(b"/t/1/e/x"));
+    }
+
+    #[test]
+    fn range_scan_exclusive() {
+        let range = RangeScan::new(b"/t/", Some(ScanBound::new(b"/t/1/b/", false)), Some(ScanBound::new(b"/t/1/d/", false)));
+        assert!(!range.contains(b"/t/1/b/x"));
+        assert!(range.contains(b"/t/1/c/x"));
+        assert!(!range.contains(b"/t/1/d/x"));
+        assert!(range.is_after_end(b"/t/1/d/x"));
+        assert!(!range.is_after_end(b"/t/1/c/x"));
+    }
+
+    #[test]
+    fn range_scan_unbounded() {
+        let range = RangeScan::new(b"/t/", None, Some(ScanBound::new(b"/t/1/d/", true)));
+        assert!(range.contains(b"/t/0/"));
+        assert!(range.contains(b"/t/1/a/x"));
+        assert!(!range.contains(b"/t/1/e/x"));
+
+        let range = RangeScan::new(b"/t/", Some(ScanBound::new(b"/t/1/b/", false)), None);
+        assert!(!range.contains(b"/t/1/a/x"));
+        assert!(!range.contains(b"/t/1/b/x"));
+        assert!(range.contains(b"/t/1/z/x"));
+        assert!(!range.is_after_end(b"/t/1/z/x"));
+
+        let range = RangeScan::new(b"/t/", None, None);
+        assert!(range.contains(b"/t/1/a/x"));
+    }
+
+    #[test]
+    fn range_scan_versioned() {
+        let range = RangeScan::new(b"/t/", Some(ScanBound::new(b"/t/1/b/", false)), Some(ScanBound::new(b"/t/1/d/", true)))
+            .versioned();
+        let version_key = |key: &str| dbkey::create_version_key(key, 7).into_bytes();
+        assert!(!range.contains(version_key("/t/1/b/").as_slice()));
+        assert!(range.contains(version_key("/t/1/c/").as_slice()));
+        assert!(range.contains(version_key("/t/1/d/").as_slice()));
+        assert!(!range.contains(version_key("/t/1/e/").as_slice()));
+        assert!(range.is_after_end(version_key("/t/1/e/").as_slice()));
+        assert!(!range.contains(b"/t/1/c/"));
+    }
+
+    #[test]
+    fn range_seek_unbounded_side() {
+        let mut table_column = TableColumnDef::default();
+        table_column.load_sql_column_list(vec![ColumnDef {
+            name: Ident::new("id"),
+            data_type: DataType::Int(None),
+            collation: None,
+            options: vec![],
+        }]);
+        let mut table = TableDef::new();
+        table.with_column(table_column);
+        table.with_option(TableOptionDef::new("def", "test", "user"));
+        table.with_constraints(vec![TableConstraint::Unique {
+            name: Some(Ident::new("id_index")),
+            columns: vec![Ident::new("id")],
+            is_primary: false,
+        }]);
+        let prefix = "/Table/index/key/def.test.user/id_index/";
+
+        let filters = vec![col("id").gt(lit(10i64))];
+        let mut column_range_map = HashMap::new();
+        for (column_name, expr_list) in create_column_filter(&filters).unwrap() {
+            column_range_map.insert(column_name, create_column_range(expr_list));
+        }
+        let table_index_list = get_table_index_list(table.clone(), column_range_map);
+        let (start, end) = dbkey::create_scan_index(table.clone(), table_index_list[0].clone()).unwrap();
+        assert_eq!(format!("{}1/1/{:020}/", prefix, 10u64 ^ meta_const::SIGN_MASK), start.key());
+        assert_eq!(PointType::Open, start.point_type());
+        assert_eq!(None, create_scan_bound(prefix, end));
+
+        let filters = vec![col("id").lt(lit(100i64))];
+        let mut column_range_map = HashMap::new();
+        for (column_name, expr_list) in create_column_filter(&filters).unwrap() {
+            column_range_map.insert(column_name, create_column_range(expr_list));
+        }
+        let table_index_list = get_table_index_list(table.clone(), column_range_map);
+        let (start, end) = dbkey::create_scan_index(table.clone(), table_index_list[0].clone()).unwrap();
+        // the NULL values are not less than any value
+        assert_eq!(format!("{}1/1/", prefix), start.key());
+        assert_eq!(format!("{}1/1/{:020}/", prefix, 100u64 ^ meta_const::SIGN_MASK), end.key());
+        assert_eq!(PointType::Open, end.point_type());
+    }
+
+    #[test]
+    fn index_key_order() {
+        let int_type = DataType::Int(None);
+        let int_keys: Vec<String> = [-100i64, -1, 0, 9, 10, 100]
+            .into_iter()
+            .map(|value| dbkey::encode_index_value(&int_type, &ScalarValue::Int64(Some(value))).unwrap())
+            .collect();
+        assert!(int_keys.windows(2).all(|keys| keys[0] < keys[1]));
+        assert_eq!(Some(int_keys[4].clone()), dbkey::encode_index_value(&int_type, &ScalarValue::Utf8(Some("10".to_string()))));
+
+        let float_type = DataType::Float(None);
+        let float_keys: Vec<String> = [f64::NEG_INFINITY, -10.5, -1.0, 0.0, 0.5, 2.0, 10.0, 1e20]
+            .into_iter()
+            .map(|value| dbkey::encode_index_value(&float_type, &ScalarValue::Float64(Some(value))).unwrap())
+            .collect();
+        assert!(float_keys.windows(2).all(|keys| keys[0] < keys[1]));
+        assert_eq!(Some(float_keys[3].clone()), dbkey::encode_index_value(&float_type, &ScalarValue::Float64(Some(-0.0))));
+        assert_eq!(Some(float_keys[5].clone()), dbkey::encode_index_value(&float_type, &ScalarValue::Int64(Some(2))));
+
+        // the fraction moves the bound of the integer inside the range
+        let start = dbkey::encode_index_bound(&int_type, &ScalarValue::Float64(Some(9.5)), PointType::Open, true);
+        assert_eq!(Some((int_keys[4].clone(), PointType::Closed)), start);
+        let end = dbkey::encode_index_bound(&int_type, &ScalarValue::Float64(Some(9.5)), PointType::Open, false);
+        assert_eq!(Some((int_keys[3].clone(), PointType::Closed)), end);
+        assert_eq!(None, dbkey::encode_index_bound(&int_type, &ScalarValue::Utf8(Some("a".to_string())), PointType::Closed, true));
+
+        let decimal_type = DataType::Decimal(Some(10), Some(2));
+        let decimal_keys: Vec<String> = [-100000i128, -1, 0, 1, 1250, 99999]
+            .into_iter()
+            .map(|value| dbkey::encode_index_value(&decimal_type, &ScalarValue::Decimal128(Some(value), 10, 2)).unwrap())
+            .collect();
+        assert!(decimal_keys.windows(2).all(|keys| keys[0] < keys[1]));
+        assert_eq!(Some(decimal_keys[4].clone()), dbkey::encode_index_value(&decimal_type, &ScalarValue::Utf8(Some("12.50".to_string()))));
+        assert_eq!(Some(decimal_keys[4].clone()), dbkey::encode_index_value(&decimal_type, &ScalarValue::Decimal128(Some(125), 10, 1)));
+
+        // the value rounded to the scale moves the bound of the DECIMAL outside
+        let start = dbkey::encode_index_bound(&decimal_type, &ScalarValue::Float64(Some(12.5)), PointType::Open, true);
+        assert_eq!(Some((decimal_keys[4].clone(), PointType::Open)), start);
+        let start = dbkey::encode_index_bound(&decimal_type, &ScalarValue::Float64(Some(0.004)), PointType::Open, true);
+        assert_eq!(Some((decimal_keys[1].clone(), PointType::Closed)), start);
+        let end = dbkey::encode_index_bound(&decimal_type, &ScalarValue::Float64(Some(-0.004)), PointType::Open, false);
+        assert_eq!(Some((decimal_keys[3].clone(), PointType::Closed)), end);
+
+        // the DATE bounds the whole day of the DATETIME
+        let date = ScalarValue::Date32(Some(1));
+        let start = dbkey::encode_index_bound(&DataType::Timestamp, &date, PointType::Open, true);
+        assert_eq!(Some(("1970-01-02 00:00:00".to_string(), PointType::Closed)), start);
+        let end = dbkey::encode_index_bound(&DataType::Timestamp, &date, PointType::Closed, false);
+        assert_eq!(Some(("1970-01-03 00:00:00".to_string(), PointType::Open)), end);
+    }
+
+    #[test]
+    fn is_null_seek_index() {
+        let mut table = TableDef::new();
+        table.with_constraints(vec![TableConstraint::Unique {
+            name: Some(Ident::new("name_index")),
+            columns: vec![Ident::new("name")],
+            is_primary: false,
+        }]);
+
+        let filters = vec![Expr::IsNull(Box::new(col("name")))];
+        let column_filter_map = create_column_filter(&filters).unwrap();
+        let mut column_range_map = HashMap::new();
+        for (column_name, expr_list) in column_filter_map {
+            column_range_map.insert(column_name, create_column_range(expr_list));
+        }
+
+        let table_index_list = get_table_index_list(table, column_range_map);
+        assert_eq!(1, table_index_list.len());
+        assert_eq!("name_index", table_index_list[0].index_name);
+        assert_eq!(RangePoint::Null, table_index_list[0].column_range_list[0].range.start);
+        assert_eq!(RangePoint::Null, table_index_list[0].column_range_list[0].range.end);
+    }
+}
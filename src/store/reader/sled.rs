@@ -1,39 +1,82 @@
 use bstr::ByteSlice;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
 use std::sync::{Arc, Mutex};
 
 use arrow::array::StructBuilder;
-use arrow::array::{Float32Builder, Float64Builder, Int32Builder, Int64Builder, StringBuilder};
+use arrow::array::{BinaryBuilder, DecimalBuilder, Float32Builder, Float64Builder, Int32Builder, Int64Builder, Int8Builder, LargeBinaryBuilder, StringBuilder};
+use arrow::array::{Date32Builder, TimestampMicrosecondBuilder};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::error::{ArrowError, Result};
 use arrow::record_batch::RecordBatch;
 use datafusion::logical_plan::Expr;
+use sled::Db as SledDb;
+use sled::IVec;
 use sled::Iter as SledIter;
 use sqlparser::ast::DataType as SQLDataType;
-use std::cmp::Ordering;
 
 use crate::core::global_context::GlobalContext;
 use crate::meta::meta_const;
 use crate::meta::meta_def::TableDef;
+use crate::meta::meta_util;
+use crate::store::compression;
+use crate::store::mvcc;
+use crate::store::mvcc::Snapshot;
 use crate::store::reader::reader_util;
-use crate::store::reader::reader_util::{PointType, SeekType};
+use crate::store::reader::reader_util::{RangeScan, SeekType};
 use crate::util;
 use crate::util::convert::ToIdent;
-use crate::util::dbkey::CreateScanKey;
+use crate::util::decimal;
 use lexical::Error;
 
+/// The versions of the keys of the range
 pub struct Seek {
     iter: SledIter,
-    start: CreateScanKey,
-    end: CreateScanKey,
+    range: RangeScan,
+    /// Iterate the keys from the end of the range
+    reverse: bool,
+    /// The first version of the next key, read past the versions of the last key
+    pending: Option<(String, u64, IVec)>,
+    done: bool,
 }
 
-pub struct SledReader {
-    global_context: Arc<Mutex<GlobalContext>>,
+impl Seek {
+    fn new(iter: SledIter, range: RangeScan, reverse: bool) -> Self {
+        Self {
+            iter,
+            range,
+            reverse,
+            pending: None,
+            done: false,
+        }
+    }
+}
+
+/// Reads the record batches of the table from the keys of the seek
+struct BatchReader {
+    sled_db: SledDb,
     table: TableDef,
-    projection: Option<Vec<usize>>,
     projected_schema: SchemaRef,
     batch_size: usize,
     seek: Seek,
+    /// The bytes of the keys and the values read from the store
+    bytes_scanned: Arc<AtomicUsize>,
+    /// The rows are read as they were at the commit of the snapshot
+    snapshot: Snapshot,
+}
+
+enum Batches {
+    Inline(BatchReader),
+    /// The batches are read by a blocking task ahead of the consumer, at most `read_ahead` batches are waiting
+    ReadAhead(Receiver<Result<RecordBatch>>),
+}
+
+pub struct SledReader {
+    projected_schema: SchemaRef,
+    batches: Batches,
+    bytes_scanned: Arc<AtomicUsize>,
+    /// The batches read by the blocking task before the consumer asked for them
+    batches_read_ahead: Arc<AtomicUsize>,
 }
 
 impl SledReader {
@@ -43,6 +86,7 @@ impl SledReader {
         batch_size: usize,
         projection: Option<Vec<usize>>,
         filters: &[Expr],
+        snapshot: Snapshot,
     ) -> Self {
         let schema_ref = table.to_schema_ref();
         let full_table_name = table.option.full_table_name.clone();
@@ -65,8 +109,22 @@ impl SledReader {
             filters.clone(),
         )
         .unwrap();
+        // the versions of the keys are scanned, the newest one seen by the snapshot is the key of the snapshot
         let seek = match table_index_prefix {
-            SeekType::FullTableScan { start, end } => {
+            SeekType::FullTableScan { range } if table.option.reverse_scan => {
+                let range = range.versioned();
+                let iter = global_context
+                    .lock()
+                    .unwrap()
+                    .engine
+                    .sled_db
+                    .as_ref()
+                    .unwrap()
+                    .scan_prefix(range.prefix.clone());
+                Seek::new(iter, range, true)
+            }
+            SeekType::FullTableScan { range } => {
+                let range = range.versioned();
                 let iter = global_context
                     .lock()
                     .unwrap()
@@ -74,10 +132,11 @@ impl SledReader {
                     .sled_db
                     .as_ref()
                     .unwrap()
-                    .scan_prefix(start.key.clone());
-                Seek { iter, start, end }
+                    .range(range.seek_key()..);
+                Seek::new(iter, range, false)
             }
-            SeekType::UsingTheIndex { start, end, .. } => {
+            SeekType::UsingTheIndex { range, .. } => {
+                let range = range.versioned();
                 let iter = global_context
                     .lock()
                     .unwrap()
@@ -85,40 +144,100 @@ impl SledReader {
                     .sled_db
                     .as_ref()
                     .unwrap()
-                    .scan_prefix(start.key.clone());
-                Seek { iter, start, end }
+                    .range(range.seek_key()..);
+                Seek::new(iter, range, false)
             }
         };
 
-        Self {
-            global_context,
+        let (sled_db, read_ahead) = {
+            let gc = global_context.lock().unwrap();
+            (gc.engine.sled_db.clone().unwrap(), gc.my_config.engine.sled.read_ahead)
+        };
+        let bytes_scanned = Arc::new(AtomicUsize::new(0));
+        let mut batch_reader = BatchReader {
+            sled_db,
             table,
-            projection,
-            projected_schema,
+            projected_schema: projected_schema.clone(),
             batch_size,
             seek,
+            bytes_scanned: bytes_scanned.clone(),
+            snapshot,
+        };
+
+        // the scan outside of the runtime, as the one of the startup, reads the batches on demand
+        let runtime = match read_ahead {
+            0 => None,
+            _ => tokio::runtime::Handle::try_current().ok(),
+        };
+        let batches_read_ahead = Arc::new(AtomicUsize::new(0));
+        let batches = match runtime {
+            Some(runtime) => {
+                let (sender, receiver) = sync_channel(read_ahead);
+                let batches_read_ahead = batches_read_ahead.clone();
+                runtime.spawn_blocking(move || {
+                    while let Some(batch) = batch_reader.next_batch() {
+                        let is_err = batch.is_err();
+                        batches_read_ahead.fetch_add(1, Ordering::Relaxed);
+                        // the reader is dropped before the end of the scan
+                        if sender.send(batch).is_err() || is_err {
+                            break;
+                        }
+                    }
+                });
+                Batches::ReadAhead(receiver)
+            }
+            None => Batches::Inline(batch_reader),
+        };
+
+        Self {
+            projected_schema,
+            batches,
+            bytes_scanned,
+            batches_read_ahead,
         }
     }
 
     pub fn projected_schema(&self) -> SchemaRef {
         self.projected_schema.clone()
     }
+
+    /// The bytes read from the store so far, the batches read ahead are counted too
+    pub fn bytes_scanned(&self) -> usize {
+        self.bytes_scanned.load(Ordering::Relaxed)
+    }
+
+    /// The batches read ahead so far, 0 if the batches are read on demand
+    pub fn batches_read_ahead(&self) -> usize {
+        self.batches_read_ahead.load(Ordering::Relaxed)
+    }
 }
 
 impl Iterator for SledReader {
     type Item = Result<RecordBatch>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let global_context = &self.global_context.lock().unwrap();
-        let sled_db = global_context.engine.sled_db.as_ref().unwrap();
-        let table_column = self.table.get_table_column();
+        match &mut self.batches {
+            Batches::Inline(batch_reader) => batch_reader.next_batch(),
+            Batches::ReadAhead(receiver) => receiver.recv().ok(),
+        }
+    }
+}
 
-        let mut rowids: Vec<String> = vec![];
+impl BatchReader {
+    /// The next version of the range with its key and its commit timestamp
+    fn next_version(&mut self) -> Option<Result<(String, u64, IVec)>> {
+        if self.seek.done {
+            return None;
+        }
         loop {
-            let result = self.seek.iter.next();
-            let (key, value) = match result {
+            let result = if self.seek.reverse {
+                self.seek.iter.next_back()
+            } else {
+                self.seek.iter.next()
+            };
+            let (version_key, value) = match result {
                 Some(item) => match item {
-                    Ok((key, value)) => (key, value),
+                    Ok((version_key, value)) => (version_key, value),
                     Err(error) => {
                         return Some(Err(ArrowError::IoError(format!(
                             "Error iter from sled: '{:?}'",
@@ -126,41 +245,99 @@ impl Iterator for SledReader {
                         ))));
                     }
                 },
-                _ => break,
+                _ => {
+                    self.seek.done = true;
+                    return None;
+                }
             };
 
-            let key = String::from_utf8(key.to_vec()).expect("Found invalid UTF-8");
+            self.bytes_scanned.fetch_add(version_key.len() + value.len(), Ordering::Relaxed);
+            if self.seek.range.is_after_end(version_key.as_ref()) {
+                self.seek.done = true;
+                return None;
+            }
+            if !self.seek.range.contains(version_key.as_ref()) {
+                continue;
+            }
 
-            match self.seek.start.point_type() {
-                PointType::Open => {
-                    if key.starts_with(self.seek.start.key().as_str()) {
-                        continue;
-                    }
-                }
-                PointType::Closed => {}
+            let version_key = String::from_utf8(version_key.to_vec()).expect("Found invalid UTF-8");
+            if let Some((key, commit_ts)) = util::dbkey::parse_version_key(version_key.as_str()) {
+                return Some(Ok((key, commit_ts, value)));
             }
-            match self.seek.end.point_type() {
-                PointType::Open => {
-                    if key.starts_with(self.seek.end.key().as_str()) {
+        }
+    }
+
+    /// The next key of the range seen by the snapshot with its value, the keys deleted at the snapshot are skipped
+    fn next_visible(&mut self) -> Option<Result<(String, Vec<u8>)>> {
+        let read_ts = self.snapshot.read_ts();
+        loop {
+            let (key, commit_ts, value) = match self.seek.pending.take() {
+                Some(version) => version,
+                None => match self.next_version()? {
+                    Ok(version) => version,
+                    Err(error) => return Some(Err(error)),
+                },
+            };
+
+            // the versions of a key are next to each other in the order of their commits,
+            // the newest one not after the snapshot is seen
+            let mut visible = if commit_ts <= read_ts { Some(value) } else { None };
+            loop {
+                match self.next_version() {
+                    Some(Ok((next_key, commit_ts, value))) if next_key == key => {
+                        if commit_ts <= read_ts && (!self.seek.reverse || visible.is_none()) {
+                            visible = Some(value);
+                        }
+                    }
+                    Some(Ok(version)) => {
+                        self.seek.pending = Some(version);
                         break;
                     }
-                }
-                PointType::Closed => {}
-            }
-            if !key.starts_with(self.seek.end.key().as_str()) {
-                match key.as_str().partial_cmp(self.seek.end.key().as_str()) {
+                    Some(Err(error)) => return Some(Err(error)),
                     None => break,
-                    Some(a) => match a {
-                        Ordering::Less => {}
-                        Ordering::Equal => {}
-                        Ordering::Greater => break,
-                    },
                 }
             }
 
-            let value = String::from_utf8(value.to_vec()).expect("Found invalid UTF-8");
+            if let Some(value) = visible.and_then(|value| mvcc::decode_version_value(value.as_ref())) {
+                return Some(Ok((key, value)));
+            }
+        }
+    }
+
+    fn next_batch(&mut self) -> Option<Result<RecordBatch>> {
+        let table_column = self.table.get_table_column();
+        let read_ts = self.snapshot.read_ts();
 
-            rowids.push(value);
+        let mut rowids: Vec<String> = vec![];
+        loop {
+            let (key, value) = match self.next_visible() {
+                Some(Ok(key_value)) => key_value,
+                Some(Err(error)) => return Some(Err(error)),
+                None => break,
+            };
+
+            let rowid_prefix = util::dbkey::create_scan_rowid(self.table.clone()).key();
+            if self.table.option.without_rowid && key.starts_with(rowid_prefix.as_str()) {
+                // the rowid of the table without rowid is the primary key part of the rowid key
+                rowids.push(key.trim_start_matches(rowid_prefix.as_str()).to_string());
+            } else {
+                let value = String::from_utf8(value).expect("Found invalid UTF-8");
+                if !key.starts_with(rowid_prefix.as_str()) {
+                    // the index entry of a row deleted at the snapshot is skipped
+                    let rowid_key = util::dbkey::create_column_rowid_key(&self.table.option, value.as_str());
+                    match mvcc::get_visible(&self.sled_db, rowid_key.as_str(), read_ts) {
+                        Ok(Some(_)) => {}
+                        Ok(None) => continue,
+                        Err(mysql_error) => {
+                            return Some(Err(ArrowError::IoError(format!(
+                                "Error get from sled: '{}'",
+                                mysql_error.message()
+                            ))));
+                        }
+                    }
+                }
+                rowids.push(value);
+            }
 
             if rowids.len() == self.batch_size {
                 break;
@@ -171,6 +348,14 @@ impl Iterator for SledReader {
             return None;
         }
 
+        // a record batch without columns can not carry the row count
+        if self.projected_schema.fields().is_empty() {
+            return Some(Err(ArrowError::InvalidArgumentError(format!(
+                "The projection of the table '{}' has no columns",
+                self.table.option.full_table_name
+            ))));
+        }
+
         let mut struct_builder = StructBuilder::from_fields(
             self.projected_schema.clone().fields().clone(),
             rowids.len(),
@@ -203,37 +388,93 @@ impl Iterator for SledReader {
 
                 for rowid in rowids.clone() {
                     let db_key = util::dbkey::create_column_key(
-                        self.table.option.full_table_name.clone(),
+                        &self.table.option,
                         sparrow_column.store_id,
                         rowid.as_str(),
                     );
-                    let result = sled_db.get(db_key.clone());
+                    let result = mvcc::get_visible(&self.sled_db, db_key.as_str(), read_ts);
 
                     let mut db_value;
                     match result {
                         Ok(get_value) => match get_value {
-                            Some(store_value) => {
-                                let bytes = store_value.as_ref().to_vec();
+                            Some(bytes) => {
+                                self.bytes_scanned.fetch_add(db_key.len() + bytes.len(), Ordering::Relaxed);
                                 // value is null
                                 if bytes.len() == 1 && bytes[0] == 0x00 {
                                     db_value = None;
                                 } else {
-                                    db_value = Some(bytes)
+                                    match compression::decompress_column_value(bytes.as_slice()) {
+                                        Ok(bytes) => db_value = Some(bytes),
+                                        Err(mysql_error) => {
+                                            return Some(Err(ArrowError::IoError(mysql_error.message())));
+                                        }
+                                    }
                                 }
                             }
                             None => db_value = None,
                         },
-                        Err(error) => {
+                        Err(mysql_error) => {
                             return Some(Err(ArrowError::IoError(format!(
-                                "Error get key from sled, key: {:?}, error: {:?}",
-                                db_key, error
+                                "Error get key from sled, key: {:?}, error: {}",
+                                db_key, mysql_error.message()
                             ))));
                         }
                     }
 
                     match db_value {
                         Some(value) => match sql_data_type {
-                            SQLDataType::Char(_) => match std::str::from_utf8(value.as_ref()) {
+                            // the bytes are stored as the hex text
+                            _ if meta_util::is_binary_data_type(&sql_data_type) => {
+                                let bytes = match hex::decode(value.as_slice()) {
+                                    Ok(bytes) => bytes,
+                                    Err(error) => {
+                                        return Some(Err(ArrowError::CastError(format!(
+                                            "Error parsing '{:?}' as hex, error: {:?}",
+                                            value, error
+                                        ))));
+                                    }
+                                };
+                                let result = if meta_util::is_longblob_data_type(&sql_data_type) {
+                                    struct_builder.field_builder::<LargeBinaryBuilder>(i).unwrap().append_value(bytes.as_slice())
+                                } else {
+                                    struct_builder.field_builder::<BinaryBuilder>(i).unwrap().append_value(bytes.as_slice())
+                                };
+                                if let Err(e) = result {
+                                    return Some(Err(e));
+                                }
+                            }
+                            // the DATE, the DATETIME and the TIMESTAMP are stored as the text
+                            SQLDataType::Date | SQLDataType::Timestamp | SQLDataType::Custom(_) if meta_util::is_temporal_data_type(&sql_data_type) => {
+                                let datetime = std::str::from_utf8(value.as_ref())
+                                    .ok()
+                                    .and_then(util::date_function::parse_datetime)
+                                    .map(|(datetime, _)| datetime);
+                                let datetime = match datetime {
+                                    Some(datetime) => datetime,
+                                    None => {
+                                        return Some(Err(ArrowError::CastError(format!(
+                                            "Error parsing '{:?}' as {}, rowid: {}, column name: {}",
+                                            value, sql_data_type, rowid, column_name
+                                        ))));
+                                    }
+                                };
+                                let result = if sql_data_type == SQLDataType::Date {
+                                    struct_builder
+                                        .field_builder::<Date32Builder>(i)
+                                        .unwrap()
+                                        .append_value(util::date_function::date_to_days(datetime.date()))
+                                } else {
+                                    struct_builder
+                                        .field_builder::<TimestampMicrosecondBuilder>(i)
+                                        .unwrap()
+                                        .append_value(util::date_function::datetime_to_microseconds(datetime))
+                                };
+                                if let Err(e) = result {
+                                    return Some(Err(e));
+                                }
+                            }
+                            // the TIME is stored as the text
+                            SQLDataType::Char(_) | SQLDataType::Time | SQLDataType::Custom(_) => match std::str::from_utf8(value.as_ref()) {
                                 Ok(value) => {
                                     let result = struct_builder
                                         .field_builder::<StringBuilder>(i)
@@ -278,7 +519,26 @@ impl Iterator for SledReader {
                                     }
                                 }
                             }
-                            SQLDataType::Float(_) => {
+                            SQLDataType::TinyInt(_) => {
+                                let result = lexical::parse::<i8, _>(value.as_bytes());
+                                match result {
+                                    Ok(value) => {
+                                        let result = struct_builder
+                                            .field_builder::<Int8Builder>(i)
+                                            .unwrap()
+                                            .append_value(value);
+                                        if let Err(e) = result {
+                                            return Some(Err(e));
+                                        }
+                                    }
+                                    Err(err) => {
+                                        let content = String::from_utf8_lossy(value.as_ref()).to_string();
+                                        let error = format!("convert to tinyint error, rowid: {}, column name: {}, column value: {}, {}", rowid, column_name.clone(), content, err);
+                                        return Some(Err(ArrowError::ParseError(error)));
+                                    }
+                                }
+                            }
+                            SQLDataType::Float(_) | SQLDataType::Double => {
                                 let result = lexical::parse::<f64, _>(value.as_bytes());
                                 match result {
                                     Ok(value) => {
@@ -306,6 +566,33 @@ impl Iterator for SledReader {
                                     }
                                 }
                             }
+                            SQLDataType::Decimal(precision, scale) => {
+                                let (precision, scale) = decimal::precision_scale(precision, scale);
+                                let content = match std::str::from_utf8(value.as_ref()) {
+                                    Ok(value) => value,
+                                    Err(error) => {
+                                        return Some(Err(ArrowError::CastError(format!(
+                                            "Error parsing '{:?}' as decimal, error: {:?}",
+                                            value, error
+                                        ))));
+                                    }
+                                };
+                                match decimal::parse_decimal(content, precision, scale) {
+                                    Ok(value) => {
+                                        let result = struct_builder
+                                            .field_builder::<DecimalBuilder>(i)
+                                            .unwrap()
+                                            .append_value(value);
+                                        if let Err(e) = result {
+                                            return Some(Err(e));
+                                        }
+                                    }
+                                    Err(err) => {
+                                        let error = format!("convert to decimal error, rowid: {}, column name: {}, column value: {}, {:?}", rowid, column_name.clone(), content, err);
+                                        return Some(Err(ArrowError::ParseError(error)));
+                                    }
+                                }
+                            }
                             _ => {
                                 return Some(Err(ArrowError::CastError(format!(
                                     "Unsupported sql data type: {:?}",
@@ -314,7 +601,35 @@ impl Iterator for SledReader {
                             }
                         },
                         None => match sql_data_type {
-                            SQLDataType::Char(_) => {
+                            _ if meta_util::is_binary_data_type(&sql_data_type) => {
+                                let result = if meta_util::is_longblob_data_type(&sql_data_type) {
+                                    struct_builder.field_builder::<LargeBinaryBuilder>(i).unwrap().append_null()
+                                } else {
+                                    struct_builder.field_builder::<BinaryBuilder>(i).unwrap().append_null()
+                                };
+                                if let Err(e) = result {
+                                    return Some(Err(e));
+                                }
+                            }
+                            SQLDataType::Date => {
+                                let result = struct_builder
+                                    .field_builder::<Date32Builder>(i)
+                                    .unwrap()
+                                    .append_null();
+                                if let Err(e) = result {
+                                    return Some(Err(e));
+                                }
+                            }
+                            SQLDataType::Timestamp | SQLDataType::Custom(_) if meta_util::is_temporal_data_type(&sql_data_type) => {
+                                let result = struct_builder
+                                    .field_builder::<TimestampMicrosecondBuilder>(i)
+                                    .unwrap()
+                                    .append_null();
+                                if let Err(e) = result {
+                                    return Some(Err(e));
+                                }
+                            }
+                            SQLDataType::Char(_) | SQLDataType::Time | SQLDataType::Custom(_) => {
                                 let result = struct_builder
                                     .field_builder::<StringBuilder>(i)
                                     .unwrap()
@@ -332,7 +647,16 @@ impl Iterator for SledReader {
                                     return Some(Err(e));
                                 }
                             }
-                            SQLDataType::Float(_) => {
+                            SQLDataType::TinyInt(_) => {
+                                let result = struct_builder
+                                    .field_builder::<Int8Builder>(i)
+                                    .unwrap()
+                                    .append_null();
+                                if let Err(e) = result {
+                                    return Some(Err(e));
+                                }
+                            }
+                            SQLDataType::Float(_) | SQLDataType::Double => {
                                 let result = struct_builder
                                     .field_builder::<Float64Builder>(i)
                                     .unwrap()
@@ -341,6 +665,15 @@ impl Iterator for SledReader {
                                     return Some(Err(e));
                                 }
                             }
+                            SQLDataType::Decimal(_, _) => {
+                                let result = struct_builder
+                                    .field_builder::<DecimalBuilder>(i)
+                                    .unwrap()
+                                    .append_null();
+                                if let Err(e) = result {
+                                    return Some(Err(e));
+                                }
+                            }
                             _ => {
                                 return Some(Err(ArrowError::CastError(format!(
                                     "Unsupported sql data type: {:?}",
@@ -353,9 +686,12 @@ impl Iterator for SledReader {
             }
         }
 
+        // build the record batch with the projected schema, so the columns are checked against it,
+        // a row whose columns are all NULL is still a valid row
         let struct_array = struct_builder.finish();
-        let record_batch = RecordBatch::from(&struct_array);
+        let columns = struct_array.columns().into_iter().cloned().collect();
+        let record_batch = RecordBatch::try_new(self.projected_schema.clone(), columns);
 
-        Some(Ok(record_batch))
+        Some(record_batch)
     }
 }
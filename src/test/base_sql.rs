@@ -1,13 +1,35 @@
 #[cfg(test)]
 mod tests {
-    use crate::core::output::{CoreOutput, FinalCount};
-    use crate::mysql::error::MysqlResult;
-    use crate::mysql::{message, metadata};
-    use crate::test::test_util::create_execution;
-    use arrow::datatypes::{DataType, Field, Schema};
+    use crate::config::def::MyConfig;
+    use crate::core::core_util;
+    use crate::core::execution::Execution;
+    use crate::core::front_end;
+    use crate::core::output::{CoreOutput, FinalCount, SessionStateChange};
+    use crate::core::plan_rewrite::{PlanRewrite, PlanRewriteContext};
+    use crate::execute_impl::create_index::IndexBuild;
+    use crate::meta::meta_def::{SparrowColumnDef, TableOptionDef};
+    use crate::meta::{initial, meta_const, meta_util};
+    use crate::mysql::error::{MysqlError, MysqlResult};
+    use crate::store::engine::engine_util::{StoreEngineFactory, TableEngineFactory};
+    use crate::mysql::{auth, message, metadata, mysql_error_code, packet, request, response, tls};
+    use crate::postgres;
+    use crate::test::test_util::{create_execution, create_execution_with_config, open_execution};
+    use crate::util;
+    use crate::util::convert::ToIdent;
+    use crate::util::schema_statement;
+    use arrow::array::{as_primitive_array, as_string_array};
+    use arrow::datatypes::{Int64Type, UInt64Type};
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
     use arrow::record_batch::RecordBatch;
+    use datafusion::logical_plan::{col, lit, LogicalPlan, LogicalPlanBuilder};
+    use datafusion::optimizer::utils;
+    use datafusion::scalar::ScalarValue;
     use datafusion::{assert_batches_eq, assert_batches_sorted_eq};
+    use sqlparser::ast::{ColumnOption, DataType as SQLDataType};
     use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn show_databases() -> MysqlResult<()> {
@@ -279,4 +301,6225 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn update_info_message() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user values (1, 'lucy'), (2, 'lily')")
+            .await?;
+
+        let result = core_execution
+            .execute_query("update user set name = 'lucy'")
+            .await?;
+        let mut count = 0;
+        let mut message = "".to_string();
+        match result {
+            CoreOutput::FinalCount(f) => {
+                count = f.affect_rows;
+                message = f.message;
+            }
+            _ => {}
+        }
+        assert_eq!(1, count);
+        assert_eq!("Rows matched: 2  Changed: 1  Warnings: 0", message);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn use_schema_session_state() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+
+        let result = core_execution.execute_query("use test").await?;
+        let mut session_state_changes = vec![];
+        match result {
+            CoreOutput::FinalCount(f) => session_state_changes = f.session_state_changes,
+            _ => {}
+        }
+        assert_eq!(
+            vec![SessionStateChange::Schema("test".to_string())],
+            session_state_changes
+        );
+
+        let payload = message::ok_message_with_session_state(
+            0,
+            0,
+            metadata::StatusFlags::SERVER_STATUS_AUTOCOMMIT,
+            0,
+            "".to_string(),
+            session_state_changes,
+        );
+        let status = metadata::StatusFlags::SERVER_STATUS_AUTOCOMMIT
+            | metadata::StatusFlags::SERVER_SESSION_STATE_CHANGED;
+        let mut expected = vec![0x00, 0x00, 0x00];
+        expected.extend_from_slice(&u16::from(status).to_le_bytes());
+        expected.extend_from_slice(&[0x00, 0x00, 0x00]);
+        // session state info: total length, type, data length, schema name
+        expected.extend_from_slice(&[0x07, metadata::SESSION_TRACK_SCHEMA, 0x05, 0x04]);
+        expected.extend_from_slice("test".as_bytes());
+        assert_eq!(expected, payload.bytes);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn like_escape() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user values (1, 'a%b'), (2, 'axb'), (3, 'a_b')")
+            .await?;
+
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a%b  |",
+            "+----+------+",
+        ];
+
+        let result = core_execution
+            .execute_query("select id, name from user where name like 'a\\%b'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select id, name from user where name like 'a|%b' escape '|'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_batches_eq!(expected, &results);
+
+        // the literal prefix of the pattern, with the escaped wildcard, seeks the index
+        core_execution
+            .execute_query("create unique index idx_name on user (name)")
+            .await?;
+        core_execution
+            .execute_query("insert into user values (4, '%ab'), (5, 'a%bc')")
+            .await?;
+        let result = core_execution
+            .execute_query("explain format=tree select id, name from user where name like 'a\\%b%'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let tree = as_string_array(results[0].column(0)).value(0).to_string();
+        assert!(tree.contains("seek=index range scan on idx_name"));
+
+        let result = core_execution
+            .execute_query("select id, name from user where name like 'a\\%b%'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a%b  |",
+            "| 5  | a%bc |",
+            "+----+------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the leading escaped wildcard is a literal char, the other names are not in the range
+        let result = core_execution
+            .execute_query("explain format=tree select id, name from user where name like '\\%a%'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let tree = as_string_array(results[0].column(0)).value(0).to_string();
+        assert!(tree.contains("seek=index range scan on idx_name"));
+
+        let result = core_execution
+            .execute_query("select id, name from user where name like '\\%a%'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 4  | %ab  |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn show_prepared_statements() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+
+        core_execution.com_stmt_prepare("select id from user").await?;
+        core_execution.com_stmt_prepare("select name from user").await?;
+
+        let result = core_execution.execute_query("show prepared statements").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---------+-----------------------+-------------+",
+            "| Stmt_id | Sql                   | Param_count |",
+            "+---------+-----------------------+-------------+",
+            "| 1       | select id from user   | 0           |",
+            "| 2       | select name from user | 0           |",
+            "+---------+-----------------------+-------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_limit() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+
+        let result = core_execution.execute_query("select * from user limit -1").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1064, mysql_error.error_number()),
+            Ok(_) => panic!("a negative limit must be rejected"),
+        }
+
+        let result = core_execution.execute_query("select * from user limit 'abc'").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1064, mysql_error.error_number()),
+            Ok(_) => panic!("a non-numeric limit must be rejected"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn without_rowid() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id)) with (without_rowid = true)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (2, 'b'), (1, 'a')")
+            .await?;
+
+        let result = core_execution.execute_query("insert into user (id, name) values (1, 'c')").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("a duplicate primary key must be rejected"),
+        }
+
+        core_execution.execute_query("delete from user where id = 1").await?;
+
+        let result = core_execution.execute_query("select id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("create table log (id int, message char) with (without_rowid = true)").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1173, mysql_error.error_number()),
+            Ok(_) => panic!("a table without rowid requires a primary key"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn index_nulls() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, UNIQUE (name)) with (index_nulls = true)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, NULL), (3, NULL)")
+            .await?;
+        core_execution.execute_query("delete from user where id = 3").await?;
+
+        let result = core_execution.execute_query("select id from user where name is null").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn com_ping_keeps_session_state() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution.com_stmt_prepare("select id from user").await?;
+
+        for id in 0..100 {
+            let result = core_execution.com_ping()?;
+            match result {
+                CoreOutput::FinalCount(final_count) => {
+                    assert_eq!(0, final_count.affect_rows);
+                    assert!(final_count.session_state_changes.is_empty());
+                }
+                _ => panic!("COM_PING must answer OK"),
+            }
+            if id % 10 == 0 {
+                core_execution
+                    .execute_query(format!("insert into user (id, name) values ({}, 'a')", id).as_str())
+                    .await?;
+            }
+        }
+
+        let result = core_execution.execute_query("select count(*) as c from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| c  |",
+            "+----+",
+            "| 10 |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("show prepared statements").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---------+---------------------+-------------+",
+            "| Stmt_id | Sql                 | Param_count |",
+            "+---------+---------------------+-------------+",
+            "| 1       | select id from user | 0           |",
+            "+---------+---------------------+-------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn all_null_row() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (NULL, NULL)")
+            .await?;
+
+        let result = core_execution.execute_query("select id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "|    |      |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let rows = core_util::convert_record_to_scalar_value(results[0].clone());
+        let payload = message::row_message(rows[0].clone());
+        assert_eq!(vec![0xfb, 0xfb], payload.bytes);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rowid_only_projection() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (NULL, NULL)")
+            .await?;
+
+        // no column is referenced, the scan only reads the rowid
+        let result = core_execution.execute_query("select 1 as c from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---+",
+            "| c |",
+            "+---+",
+            "| 1 |",
+            "| 1 |",
+            "+---+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let mut rows = vec![];
+        for record_batch in results {
+            rows.extend(core_util::convert_record_to_scalar_value(record_batch));
+        }
+        assert_eq!(2, rows.len());
+        let payload = message::row_message(rows[0].clone());
+        assert_eq!(vec![0x01, b'1'], payload.bytes);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_without_where() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, UNIQUE (name))")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, 'b'), (3, 'c')")
+            .await?;
+
+        let result = core_execution.execute_query("delete from user").await?;
+        match result {
+            CoreOutput::FinalCount(final_count) => {
+                assert_eq!(3, final_count.affect_rows);
+                assert_eq!("", final_count.message);
+            }
+            _ => panic!("delete must return the affected rows"),
+        }
+
+        let result = core_execution.execute_query("select count(*) as c from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---+",
+            "| c |",
+            "+---+",
+            "| 0 |",
+            "+---+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the unique index is emptied too
+        core_execution
+            .execute_query("insert into user (id, name) values (4, 'a')")
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn client_max_packet_size() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        assert_eq!(1024, core_execution.set_client_max_packet_size(1024));
+        assert_eq!(67108864, core_execution.set_client_max_packet_size(u32::MAX));
+
+        // the payload longer than the max packet size of the client is not sent
+        let mut packet_message = packet::PacketMessage::new();
+        packet_message.with_max_packet_size(4);
+        let payload = response::ResponsePayload { bytes: (1..=10).collect() };
+        let result = packet_message.create(payload);
+        assert_eq!(mysql_error_code::ER_NET_PACKET_TOO_LARGE as u16, result.unwrap_err().error_number());
+        let payload = response::ResponsePayload { bytes: (1..=4).collect() };
+        let bytes = packet_message.create(payload)?;
+        assert_eq!(vec![0x04, 0x00, 0x00, 0x00, 1, 2, 3, 4], bytes.to_vec());
+
+        // the packets are always split at the max payload length
+        let mut packet_message = packet::PacketMessage::new();
+        let payload = response::ResponsePayload { bytes: vec![1; packet::MAX_PAYLOAD_LENGTH + 2] };
+        let bytes = packet_message.create(payload)?;
+        assert_eq!(packet::MAX_PAYLOAD_LENGTH + 2 + 2 * packet::HEADER_LENGTH, bytes.len());
+        assert_eq!(&[0xff, 0xff, 0xff, 0x00], &bytes[..4]);
+        assert_eq!(&[0x02, 0x00, 0x00, 0x01, 1, 1], &bytes[4 + packet::MAX_PAYLOAD_LENGTH..]);
+
+        // an empty packet ends the payload of exactly the max payload length
+        packet_message.sequence_init();
+        let payload = response::ResponsePayload { bytes: vec![1; packet::MAX_PAYLOAD_LENGTH] };
+        let bytes = packet_message.create(payload)?;
+        assert_eq!(packet::MAX_PAYLOAD_LENGTH + 2 * packet::HEADER_LENGTH, bytes.len());
+        assert_eq!(&[0x00, 0x00, 0x00, 0x01], &bytes[4 + packet::MAX_PAYLOAD_LENGTH..]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn range_scan_unbounded() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, UNIQUE (id))")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (-3, 'a'), (1, 'b'), (5, 'c'), (10, 'd'), (11, 'e'), (100, 'f'), (150, 'g')")
+            .await?;
+        // the index follows the new value
+        core_execution
+            .execute_query("update user set id = 20 where id = 5")
+            .await?;
+
+        let result = core_execution.execute_query("select id from user where id > 10 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----+",
+            "| id  |",
+            "+-----+",
+            "| 11  |",
+            "| 20  |",
+            "| 100 |",
+            "| 150 |",
+            "+-----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("select id from user where id < 100 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| -3 |",
+            "| 1  |",
+            "| 10 |",
+            "| 11 |",
+            "| 20 |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[test]
+    fn packet_sequence_id() {
+        let mut packet_message = packet::PacketMessage::new();
+
+        // the client command is the first packet of the sequence
+        packet_message.sequence_init();
+        packet_message.sequence_receive(0);
+        let payload = response::ResponsePayload { bytes: vec![1; packet::MAX_PAYLOAD_LENGTH + 1] };
+        let bytes = packet_message.create(payload).unwrap();
+        packet_message.sequence_increase();
+        let sequence_ids: Vec<u8> = vec![bytes[3], bytes[packet::HEADER_LENGTH + packet::MAX_PAYLOAD_LENGTH + 3]];
+        assert_eq!(vec![1, 2], sequence_ids);
+        assert_eq!(3, packet_message.sequence_id());
+
+        // the next command resets the sequence
+        packet_message.sequence_init();
+        packet_message.sequence_receive(0);
+        let payload = response::ResponsePayload { bytes: vec![0x00] };
+        let bytes = packet_message.create(payload).unwrap();
+        assert_eq!(1, bytes[3]);
+
+        // a result set with more than 255 packets wraps the sequence id
+        for _ in 0..255 {
+            packet_message.sequence_increase();
+        }
+        assert_eq!(0, packet_message.sequence_id());
+    }
+
+    #[tokio::test]
+    async fn utf8mb4_emoji() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a\u{1F600}b')")
+            .await?;
+
+        let result = core_execution.execute_query("select name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(1, results.len());
+
+        let schema = results[0].schema();
+        let column = metadata::Column::from(schema.field(0));
+        assert_eq!(metadata::CHARACTER_SET_UTF8MB4_0900_AI_CI, column.character_set());
+
+        // the 4-byte character is stored and sent back unchanged
+        let rows = core_util::convert_record_to_scalar_value(results[0].clone());
+        let payload = message::row_message(rows[0].clone());
+        let expected: Vec<u8> = vec![0x06, b'a', 0xf0, 0x9f, 0x98, 0x80, b'b'];
+        assert_eq!(expected, payload.bytes);
+
+        Ok(())
+    }
+
+    struct TenantFilter {
+        tenant_id: i64,
+    }
+
+    impl PlanRewrite for TenantFilter {
+        fn name(&self) -> &str {
+            "tenant_filter"
+        }
+
+        fn rewrite(&self, logical_plan: &LogicalPlan, context: &PlanRewriteContext) -> MysqlResult<LogicalPlan> {
+            match logical_plan {
+                LogicalPlan::TableScan { projected_schema, .. }
+                if projected_schema.field_with_unqualified_name("tenant_id").is_ok() => {
+                    LogicalPlanBuilder::from(logical_plan.clone())
+                        .filter(col("tenant_id").eq(lit(self.tenant_id)))
+                        .and_then(|builder| builder.build())
+                        .map_err(MysqlError::from)
+                }
+                _ => {
+                    let expr = logical_plan.expressions();
+                    let new_inputs = logical_plan
+                        .inputs()
+                        .iter()
+                        .map(|input| self.rewrite(input, context))
+                        .collect::<MysqlResult<Vec<_>>>()?;
+                    utils::from_plan(logical_plan, &expr, &new_inputs).map_err(MysqlError::from)
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_rewrite_filter() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, tenant_id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, tenant_id, name) values (1, 1, 'a'), (2, 2, 'b'), (3, 1, 'c')")
+            .await?;
+
+        core_execution
+            .global_context()
+            .lock()
+            .unwrap()
+            .plan_rewrite
+            .register(Arc::new(TenantFilter { tenant_id: 1 }));
+
+        let result = core_execution.execute_query("select id, tenant_id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-----------+------+",
+            "| id | tenant_id | name |",
+            "+----+-----------+------+",
+            "| 1  | 1         | a    |",
+            "| 3  | 1         | c    |",
+            "+----+-----------+------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tenant_isolation() -> MysqlResult<()> {
+        let mut my_config = MyConfig::default();
+        my_config.server.tenant_column = Some("tenant_id".to_string());
+        my_config.server.tenants.insert("root".to_string(), "1".to_string());
+        let mut tenant_a = create_execution_with_config(my_config).await?;
+
+        tenant_a.execute_query("create schema test").await?;
+        tenant_a.set_default_schema("test").await?;
+        tenant_a
+            .execute_query("create table user (id int, tenant_id int, name char)")
+            .await?;
+        // the tenant is bound to the user at auth, the client can not change it
+        tenant_a.set_process_user("root", "127.0.0.1");
+        let result = tenant_a.execute_query("set tenant_id = 2").await;
+        assert_eq!(mysql_error_code::ER_VARIABLE_IS_READONLY as u16, result.unwrap_err().error_number());
+        tenant_a
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, 'b')")
+            .await?;
+
+        let mut tenant_b = Execution::new(tenant_a.global_context());
+        tenant_b.try_init()?;
+        tenant_b.set_default_schema("test").await?;
+        tenant_b.set_tenant_id("2");
+        tenant_b
+            .execute_query("insert into user (id, name) values (3, 'c')")
+            .await?;
+
+        // the tenant column is filled in by the insert
+        let result = tenant_a.execute_query("select id, tenant_id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-----------+------+",
+            "| id | tenant_id | name |",
+            "+----+-----------+------+",
+            "| 1  | 1         | a    |",
+            "| 2  | 1         | b    |",
+            "+----+-----------+------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the filter applies even if the tenant column is not selected
+        let result = tenant_b.execute_query("select name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+",
+            "| name |",
+            "+------+",
+            "| c    |",
+            "+------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the rows of another tenant can not be written
+        let result = tenant_b
+            .execute_query("insert into user (id, tenant_id, name) values (4, 1, 'd')")
+            .await;
+        assert!(result.is_err());
+
+        // delete without where only removes the rows of the tenant
+        tenant_b.execute_query("delete from user").await?;
+        let result = tenant_a.execute_query("select id from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 1  |",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the user without a tenant can not use the table
+        tenant_a.execute_query("create user 'dev'@'%'").await?;
+        tenant_a.execute_query("grant select, delete on test.* to 'dev'@'%'").await?;
+        let mut no_tenant = Execution::new(tenant_a.global_context());
+        no_tenant.try_init()?;
+        no_tenant.set_default_schema("test").await?;
+        no_tenant.set_process_user("dev", "127.0.0.1");
+        let result = no_tenant.execute_query("select name from user").await;
+        assert_eq!(mysql_error_code::ER_SPECIFIC_ACCESS_DENIED_ERROR as u16, result.unwrap_err().error_number());
+        let result = no_tenant.execute_query("delete from user").await;
+        assert_eq!(mysql_error_code::ER_SPECIFIC_ACCESS_DENIED_ERROR as u16, result.unwrap_err().error_number());
+
+        // the tenant id follows the type of the tenant column
+        assert_eq!(ScalarValue::Int32(Some(5)), core_util::convert_tenant_id_to_scalar_value(&DataType::Int32, "tenant_id", "5")?);
+        assert_eq!(ScalarValue::Utf8(Some("a".to_string())), core_util::convert_tenant_id_to_scalar_value(&DataType::Utf8, "tenant_id", "a")?);
+        let result = core_util::convert_tenant_id_to_scalar_value(&DataType::UInt64, "tenant_id", "a");
+        assert_eq!(1366, result.unwrap_err().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tenant_isolation_upsert() -> MysqlResult<()> {
+        let mut my_config = MyConfig::default();
+        my_config.server.tenant_column = Some("tenant_id".to_string());
+        my_config.server.tenants.insert("root".to_string(), "1".to_string());
+        let mut tenant_a = create_execution_with_config(my_config).await?;
+
+        tenant_a.execute_query("create schema test").await?;
+        tenant_a.set_default_schema("test").await?;
+        tenant_a
+            .execute_query("create table user (id int, tenant_id int, name char, PRIMARY KEY(id))")
+            .await?;
+        tenant_a.set_process_user("root", "127.0.0.1");
+        tenant_a
+            .execute_query("insert into user (id, name) values (1, 'a')")
+            .await?;
+
+        let mut tenant_b = Execution::new(tenant_a.global_context());
+        tenant_b.try_init()?;
+        tenant_b.set_default_schema("test").await?;
+        tenant_b.set_tenant_id("2");
+        tenant_b
+            .execute_query("insert into user (id, name) values (2, 'b')")
+            .await?;
+
+        // the key held by a row of another tenant is a duplicate entry, that row is neither replaced nor updated
+        let result = tenant_b.execute_query("replace into user (id, name) values (1, 'x')").await;
+        assert_eq!(mysql_error_code::ER_DUP_ENTRY as u16, result.unwrap_err().error_number());
+        let result = tenant_b
+            .execute_query("insert into user (id, name) values (1, 'x') on duplicate key update name = 'y'")
+            .await;
+        assert_eq!(mysql_error_code::ER_DUP_ENTRY as u16, result.unwrap_err().error_number());
+
+        // the rows of the tenant itself are still replaced and updated
+        tenant_b.execute_query("replace into user (id, name) values (2, 'c')").await?;
+        tenant_b
+            .execute_query("insert into user (id, name) values (2, 'x') on duplicate key update name = 'd'")
+            .await?;
+
+        let result = tenant_a.execute_query("select id, tenant_id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-----------+------+",
+            "| id | tenant_id | name |",
+            "+----+-----------+------+",
+            "| 1  | 1         | a    |",
+            "+----+-----------+------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let result = tenant_b.execute_query("select id, tenant_id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-----------+------+",
+            "| id | tenant_id | name |",
+            "+----+-----------+------+",
+            "| 2  | 2         | d    |",
+            "+----+-----------+------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reverse_scan() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        // the rowid of the table without rowid is the primary key, so the rowid order is known
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id)) with (without_rowid = true, reverse_scan = true)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (2, 'b'), (1, 'a'), (3, 'c')")
+            .await?;
+
+        let result = core_execution.execute_query("select * from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 3  | c    |",
+            "| 2  | b    |",
+            "| 1  | a    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    fn handshake_response_bytes(capability_flags: metadata::CapabilityFlags, auth_plugin_name: &str) -> Vec<u8> {
+        let mut payload = vec![];
+        payload.extend_from_slice(&capability_flags.bits().to_le_bytes());
+        payload.extend_from_slice(&16777216u32.to_le_bytes());
+        payload.push(metadata::CHARACTER_SET_UTF8MB4_0900_AI_CI);
+        payload.extend_from_slice(&[0; 23]);
+        payload.extend_from_slice(b"root\0");
+        payload.push(20);
+        payload.extend_from_slice(&[0x01; 20]);
+        payload.extend_from_slice(auth_plugin_name.as_bytes());
+        payload.push(0);
+
+        let mut bytes = vec![payload.len() as u8, 0x00, 0x00, 0x01];
+        bytes.extend_from_slice(payload.as_slice());
+        bytes
+    }
+
+    #[test]
+    fn auth_switch_request() {
+        let capability_flags = metadata::CapabilityFlags::CLIENT_PROTOCOL_41
+            | metadata::CapabilityFlags::CLIENT_SECURE_CONNECTION
+            | metadata::CapabilityFlags::CLIENT_PLUGIN_AUTH;
+
+        let request_payload = request::RequestPayload::new(handshake_response_bytes(capability_flags, "sha256_password"));
+        let handshake_response = request_payload.get_handshake_response().unwrap();
+        assert_eq!("root", handshake_response.username);
+        assert_eq!(vec![0x01; 20], handshake_response.auth_response);
+        assert_eq!(Some("sha256_password".to_string()), handshake_response.auth_plugin_name);
+
+        // the client is switched to the plugin of the server, with a new 20 bytes scramble
+        let scramble = auth::create_scramble();
+        let payload = message::auth_switch_message(&handshake_response, &scramble).unwrap();
+        let mut expected = vec![0xfe];
+        expected.extend_from_slice(b"mysql_native_password\0");
+        assert_eq!(expected.as_slice(), &payload.bytes[..expected.len()]);
+        assert_eq!(&scramble[..], &payload.bytes[expected.len()..expected.len() + 20]);
+        assert_eq!(expected.len() + 20 + 1, payload.bytes.len());
+        assert_eq!(Some(&0x00), payload.bytes.last());
+
+        // the auth response after the switch is the whole packet payload
+        let request_payload = request::RequestPayload::new(vec![0x14, 0x00, 0x00, 0x03, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]);
+        assert_eq!((1..=20).collect::<Vec<u8>>(), request_payload.get_auth_switch_response());
+
+        // the client already using the plugin of the server is not switched
+        let request_payload = request::RequestPayload::new(handshake_response_bytes(capability_flags, "mysql_native_password"));
+        let handshake_response = request_payload.get_handshake_response().unwrap();
+        assert!(message::auth_switch_message(&handshake_response, &scramble).is_none());
+
+        // caching_sha2_password is done by the server
+        let request_payload = request::RequestPayload::new(handshake_response_bytes(capability_flags, "caching_sha2_password"));
+        let handshake_response = request_payload.get_handshake_response().unwrap();
+        assert!(message::auth_switch_message(&handshake_response, &scramble).is_none());
+    }
+
+    #[tokio::test]
+    async fn authenticate_empty_password() -> MysqlResult<()> {
+        let core_execution = create_execution().await?;
+
+        initial::add_def_mysql_user(core_execution.global_context(), "dev", "")?;
+        core_execution.authenticate("dev", "127.0.0.1", &[])?;
+
+        // the user with a password can not log in without it
+        let result = core_execution.authenticate("root", "127.0.0.1", &[]);
+        match result {
+            Err(mysql_error) => assert_eq!(1045, mysql_error.error_number()),
+            Ok(_) => panic!("the empty password of root must be rejected"),
+        }
+
+        // the anonymous user is only accepted when it is added
+        let result = core_execution.authenticate("", "127.0.0.1", &[]);
+        assert!(result.is_err());
+        initial::add_def_mysql_user(core_execution.global_context(), "", "")?;
+        core_execution.authenticate("", "127.0.0.1", &[])?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn authenticate_wrong_password() -> MysqlResult<()> {
+        let core_execution = create_execution().await?;
+
+        let auth_response = auth::scramble_native_password(core_execution.scramble(), "123456");
+        core_execution.authenticate("root", "127.0.0.1", auth_response.as_slice())?;
+
+        // the auth response of another connection can not be replayed
+        let other_execution = create_execution().await?;
+        assert_ne!(core_execution.scramble(), other_execution.scramble());
+        let result = other_execution.authenticate("root", "127.0.0.1", auth_response.as_slice());
+        assert_eq!(1045, result.unwrap_err().error_number());
+
+        let auth_response = auth::scramble_native_password(core_execution.scramble(), "654321");
+        let result = core_execution.authenticate("root", "127.0.0.1", auth_response.as_slice());
+        match result {
+            Err(mysql_error) => {
+                assert_eq!(1045, mysql_error.error_number());
+                assert_eq!("28000", mysql_error.sql_state());
+            }
+            Ok(_) => panic!("a wrong password must be rejected"),
+        }
+
+        assert_eq!("*6BB4837EB74329105EE4568DDA7DC67ED2CA2AD9", auth::native_password_hash("123456"));
+
+        // root gets the initial password of the config
+        let mut my_config = MyConfig::default();
+        my_config.server.initial_root_password = Some("secret".to_string());
+        let core_execution = create_execution_with_config(my_config).await?;
+        let auth_response = auth::scramble_native_password(core_execution.scramble(), "secret");
+        core_execution.authenticate("root", "127.0.0.1", auth_response.as_slice())?;
+        let auth_response = auth::scramble_native_password(core_execution.scramble(), "123456");
+        assert!(core_execution.authenticate("root", "127.0.0.1", auth_response.as_slice()).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn auto_increment_after_restart() -> MysqlResult<()> {
+        let test_id = Uuid::new_v4()
+            .to_simple()
+            .encode_lower(&mut Uuid::encode_buffer())
+            .to_string();
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int AUTO_INCREMENT, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution
+            .execute_query("insert into user (name) values ('a'), ('b')")
+            .await?;
+        // the store is closed with the execution
+        drop(core_execution);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("insert into user (name) values ('c')")
+            .await?;
+
+        // the saved value is behind the rows, the repair on startup moves it past them
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name)?;
+        meta_util::save_auto_increment(core_execution.global_context(), &table_def, 1)?;
+        drop(core_execution);
+
+        my_config.server.repair_auto_increment = true;
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("insert into user (name) values ('d')")
+            .await?;
+
+        let result = core_execution.execute_query("select id, name from user order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "| 4  | d    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn table_constraints() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, tenant int, name char, PRIMARY KEY(id), CONSTRAINT uk_name UNIQUE (tenant, name))")
+            .await?;
+
+        let result = core_execution
+            .execute_query("select constraint_name, constraint_type, enforced from information_schema.table_constraints where table_schema = 'test' and table_name = 'user'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----------------+-----------------+----------+",
+            "| constraint_name | constraint_type | enforced |",
+            "+-----------------+-----------------+----------+",
+            "| PRIMARY         | PRIMARY KEY     | YES      |",
+            "| uk_name         | UNIQUE          | YES      |",
+            "+-----------------+-----------------+----------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_read_ahead() -> MysqlResult<()> {
+        let test_id = Uuid::new_v4()
+            .to_simple()
+            .encode_lower(&mut Uuid::encode_buffer())
+            .to_string();
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id))")
+            .await?;
+        for part in 0..10 {
+            let values = (0..500)
+                .map(|i| format!("({}, 'name{}')", part * 500 + i, part * 500 + i))
+                .collect::<Vec<_>>()
+                .join(", ");
+            core_execution
+                .execute_query(format!("insert into user (id, name) values {}", values).as_str())
+                .await?;
+        }
+        drop(core_execution);
+
+        let mut scan_rows = vec![];
+        let mut read_ahead_batches = vec![];
+        for read_ahead in vec![0, 4] {
+            my_config.engine.sled.read_ahead = read_ahead;
+            let mut core_execution = open_execution(my_config.clone()).await?;
+            core_execution.set_default_schema("test").await?;
+
+            let result = core_execution.execute_query("select id, name from user").await?;
+
+            let mut results: Vec<RecordBatch> = vec![];
+            match result {
+                CoreOutput::ResultSet(r) => results = r.record_batches,
+                _ => {}
+            }
+            let rows: usize = results.iter().map(|batch| batch.num_rows()).sum();
+            scan_rows.push(rows);
+
+            // the batches read ahead are in the profile of the scan
+            let result = core_execution.execute_query("show profile").await?;
+            let mut results: Vec<RecordBatch> = vec![];
+            match result {
+                CoreOutput::ResultSet(r) => results = r.record_batches,
+                _ => {}
+            }
+            let operators = as_string_array(results[0].column(0));
+            let batches_read_ahead = as_primitive_array::<UInt64Type>(results[0].column(3));
+            let scan_index = (0..results[0].num_rows())
+                .find(|row_index| operators.value(*row_index).trim() == "SledExec")
+                .unwrap();
+            read_ahead_batches.push(batches_read_ahead.value(scan_index));
+            drop(core_execution);
+        }
+        assert_eq!(vec![5000, 5000], scan_rows);
+        // without the read ahead the batches are read by the consumer
+        assert_eq!(0, read_ahead_batches[0]);
+        assert!(read_ahead_batches[1] > 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn order_group_by() -> MysqlResult<()> {
+        let mut my_config = MyConfig::default();
+        my_config.server.order_group_by = true;
+        let mut core_execution = create_execution_with_config(my_config).await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'c'), (2, 'a'), (3, 'b'), (4, 'c'), (5, 'a'), (6, 'd')")
+            .await?;
+
+        let result = core_execution
+            .execute_query("select name, sum(id) as total from user group by name")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+-------+",
+            "| name | total |",
+            "+------+-------+",
+            "| a    | 7     |",
+            "| b    | 3     |",
+            "| c    | 5     |",
+            "| d    | 6     |",
+            "+------+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the order by of the query wins
+        let result = core_execution
+            .execute_query("select name, sum(id) as total from user group by name order by total")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+-------+",
+            "| name | total |",
+            "+------+-------+",
+            "| b    | 3     |",
+            "| c    | 5     |",
+            "| d    | 6     |",
+            "| a    | 7     |",
+            "+------+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn analyze_table_statistics() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (7, 'b'), (-3, 'a'), (42, NULL)")
+            .await?;
+
+        let result = core_execution.execute_query("analyze table user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----------+---------+----------+----------+",
+            "| Table     | Op      | Msg_type | Msg_text |",
+            "+-----------+---------+----------+----------+",
+            "| test.user | analyze | status   | OK       |",
+            "+-----------+---------+----------+----------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name)?;
+        let schema = table_def.to_schema();
+        let projection = vec![schema.index_of("id").unwrap(), schema.index_of("name").unwrap()];
+        let table_provider = TableEngineFactory::try_new_with_table(core_execution.global_context(), table_def)?.table_provider();
+        let exec = table_provider.scan(&Some(projection), 1024, &[], None).await.map_err(MysqlError::from)?;
+
+        let statistics = exec.statistics();
+        assert_eq!(Some(3), statistics.num_rows);
+        assert!(!statistics.is_exact);
+        let column_statistics = statistics.column_statistics.unwrap();
+        assert_eq!(Some(ScalarValue::Int64(Some(-3))), column_statistics[0].min_value);
+        assert_eq!(Some(ScalarValue::Int64(Some(42))), column_statistics[0].max_value);
+        assert_eq!(Some(0), column_statistics[0].null_count);
+        assert_eq!(Some(ScalarValue::Utf8(Some("a".to_string()))), column_statistics[1].min_value);
+        assert_eq!(Some(1), column_statistics[1].null_count);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn show_create_database() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution
+            .execute_query("create database shop default character set = utf8 collate utf8_general_ci")
+            .await?;
+
+        let result = core_execution.execute_query("show create database if not exists shop").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----------+-----------------------------------------------------------------------------------------+",
+            "| Database | Create Database                                                                         |",
+            "+----------+-----------------------------------------------------------------------------------------+",
+            "| shop     | CREATE DATABASE IF NOT EXISTS `shop` DEFAULT CHARACTER SET utf8 COLLATE utf8_general_ci |",
+            "+----------+-----------------------------------------------------------------------------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the output is parsed again to the same options
+        let statement = schema_statement::parse_create_schema(
+            "CREATE DATABASE IF NOT EXISTS `shop` DEFAULT CHARACTER SET utf8 COLLATE utf8_general_ci",
+        ).unwrap();
+        assert!(statement.if_not_exists);
+        assert_eq!("shop", statement.schema_name.to_string());
+        assert_eq!(Some("utf8".to_string()), statement.character_set_name);
+        assert_eq!(Some("utf8_general_ci".to_string()), statement.collation_name);
+        core_execution
+            .execute_query("CREATE DATABASE IF NOT EXISTS `shop` DEFAULT CHARACTER SET utf8 COLLATE utf8_general_ci")
+            .await?;
+
+        let result = core_execution.execute_query("create database shop").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1007, mysql_error.error_number()),
+            Ok(_) => panic!("the database exists"),
+        }
+        let result = core_execution.execute_query("create database other character set latin1").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1115, mysql_error.error_number()),
+            Ok(_) => panic!("the character set is unknown"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn trailing_semicolon_and_empty_query() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test;").await?;
+        core_execution.execute_query("use test ;").await?;
+        core_execution
+            .execute_query("create table user (id int, name char);")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a') ; ;\n")
+            .await?;
+
+        let result = core_execution.execute_query("  select id, name from user;  ").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        for sql in vec!["", "   ", ";", " ; \n"] {
+            let result = core_execution.execute_query(sql).await;
+            match result {
+                Err(mysql_error) => assert_eq!(1065, mysql_error.error_number()),
+                Ok(_) => panic!("the empty query must be rejected"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn multiple_statements_in_one_query() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+
+        // without CLIENT_MULTI_STATEMENTS nothing of the query is executed
+        let result = core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'); select 2")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1064, mysql_error.error_number()),
+            Ok(_) => panic!("the multiple statements must be rejected"),
+        }
+
+        // the statements are executed one by one, the error stops the statements after it
+        core_execution.set_client_multi_statements(true);
+        let result = core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'); select count(*) from user; select id from no_table; select 3;")
+            .await?;
+        let outputs = match result {
+            CoreOutput::MultiStatement(outputs) => outputs,
+            _ => panic!("the query must answer the outputs of its statements"),
+        };
+        assert_eq!(3, outputs.len());
+        let mut outputs = outputs.into_iter();
+        match outputs.next() {
+            Some(Ok(CoreOutput::FinalCount(FinalCount { affect_rows, .. }))) => assert_eq!(1, affect_rows),
+            _ => panic!("the insert must answer the count"),
+        }
+        let mut results: Vec<RecordBatch> = vec![];
+        match outputs.next() {
+            Some(Ok(CoreOutput::ResultSet(r))) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----------------+",
+            "| COUNT(UInt8(1)) |",
+            "+-----------------+",
+            "| 1               |",
+            "+-----------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        match outputs.next() {
+            Some(Err(mysql_error)) => assert_eq!(1146, mysql_error.error_number()),
+            _ => panic!("the unknown table must fail"),
+        }
+
+        // a single statement answers its own output
+        let result = core_execution.execute_query("select count(*) from user;").await?;
+        assert!(matches!(result, CoreOutput::ResultSet(_)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn column_compression() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table doc (id int, body char) with (compression = 'lz4', compression_threshold = 64)")
+            .await?;
+
+        let body = "sparrow ".repeat(1000);
+        core_execution
+            .execute_query(format!("insert into doc (id, body) values (1, '{}'), (2, 'short')", body).as_str())
+            .await?;
+
+        let result = core_execution.execute_query("select _rowid, body from doc where id = 1").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let rowid = as_string_array(results[0].column(0)).value(0).to_string();
+        assert_eq!(body, as_string_array(results[0].column(1)).value(0));
+
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "doc");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        let store_id = table_def.get_table_column().get_sparrow_column("body".to_ident()).unwrap().store_id;
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        let column_key = util::dbkey::create_column_key(&table_def.option, store_id, rowid.as_str());
+        let stored_value = store_engine.get_key(column_key)?.unwrap();
+        assert!(stored_value.len() < body.len());
+
+        // the value is compressed again by the update
+        let new_body = "ebike ".repeat(1000);
+        core_execution
+            .execute_query(format!("update doc set body = '{}' where id = 1", new_body).as_str())
+            .await?;
+        let result = core_execution.execute_query("select id, body from doc order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(new_body, as_string_array(results[0].column(1)).value(0));
+        assert_eq!("short", as_string_array(results[0].column(1)).value(1));
+
+        let result = core_execution
+            .execute_query("create table archive (id int, body char) with (compression = 'gzip')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1478, mysql_error.error_number()),
+            Ok(_) => panic!("the compression is not supported"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn check_meta_on_startup() -> MysqlResult<()> {
+        let test_id = Uuid::new_v4()
+            .to_simple()
+            .encode_lower(&mut Uuid::encode_buffer())
+            .to_string();
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
+        my_config.server.check_meta = true;
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        assert!(meta_util::check_meta(core_execution.global_context())?.is_empty());
+
+        // the metadata of an unclean shutdown, a column past the max store id and a table without columns
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name)?;
+        let sql_column = meta_util::create_sql_column("age", SQLDataType::Int(None), ColumnOption::Null);
+        initial::add_information_schema_columns(
+            core_execution.global_context(),
+            table_def.option.clone(),
+            vec![SparrowColumnDef::new(9, 3, sql_column)],
+        )?;
+        let mut table_option = TableOptionDef::new(meta_const::CATALOG_NAME, "test", "ghost");
+        table_option.with_table_type(meta_const::VALUE_OF_TABLE_OPTION_TABLE_TYPE_BASE_TABLE);
+        table_option.with_engine("unknown");
+        initial::add_information_schema_tables(core_execution.global_context(), table_option)?;
+        drop(core_execution);
+
+        let result = open_execution(my_config.clone()).await;
+        match result {
+            Err(mysql_error) => {
+                assert_eq!(1105, mysql_error.error_number());
+                let message = mysql_error.message();
+                assert!(message.contains("table def.test.ghost: the engine 'unknown' can not be resolved"));
+                assert!(message.contains("table def.test.ghost: no columns found"));
+                assert!(message.contains("table def.test.user: the store id 9 of column age is out of the column max store id 2"));
+            }
+            Ok(_) => panic!("the inconsistent metadata must be reported"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replace_locate_instr() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table t (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into t (id, name) values (1, 'foobarbar'), (2, null)")
+            .await?;
+
+        let result = core_execution
+            .execute_query("select id, replace(name, 'bar', 'X') as r, locate('bar', name) as l, locate('bar', name, 5) as l5, instr(name, 'xbar') as i, instr(name, 'BAR') as u from t order by id")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------+---+----+---+---+",
+            "| id | r     | l | l5 | i | u |",
+            "+----+-------+---+----+---+---+",
+            "| 1  | fooXX | 4 | 7  | 0 | 4 |",
+            "| 2  |       |   |    |   |   |",
+            "+----+-------+---+----+---+---+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select replace('www.mysql.com', 'w', 'Ww') as r, replace('abc', '', 'x') as e, locate('é', 'café') as c")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------------------+-----+---+",
+            "| r                | e   | c |",
+            "+------------------+-----+---+",
+            "| WwWwWw.mysql.com | abc | 4 |",
+            "+------------------+-----+---+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn select_decimal_exact_string() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table t (id int, amount decimal(20,4))")
+            .await?;
+        core_execution
+            .execute_query("insert into t (id, amount) values (1, 12.5), (2, -1234567890123456.1234), (3, '3.14159'), (4, null)")
+            .await?;
+
+        let result = core_execution.execute_query("select id, amount from t order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------------------------+",
+            "| id | amount                 |",
+            "+----+------------------------+",
+            "| 1  | 12.5000                |",
+            "| 2  | -1234567890123456.1234 |",
+            "| 3  | 3.1416                 |",
+            "| 4  |                        |",
+            "+----+------------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let rows = core_util::convert_record_to_scalar_value(results[0].clone());
+        let payload = message::row_message(rows[0].clone());
+        assert_eq!(b"\x011\x0712.5000".to_vec(), payload.bytes);
+        let payload = message::row_message(rows[1].clone());
+        assert_eq!(b"\x012\x16-1234567890123456.1234".to_vec(), payload.bytes);
+
+        let result = core_execution
+            .execute_query("insert into t (id, amount) values (5, 12345678901234567)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1264, mysql_error.error_number()),
+            Ok(_) => panic!("the value out of the precision must be rejected"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cast_as_decimal() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        let result = core_execution
+            .execute_query("select cast(1.005 as decimal(10,2)) as a, cast(-2.345 as decimal(10,2)) as b, cast(12.344 as decimal(10,2)) as c, cast('7' as decimal(10,2)) as d")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+-------+-------+------+",
+            "| a    | b     | c     | d    |",
+            "+------+-------+-------+------+",
+            "| 1.01 | -2.35 | 12.34 | 7.00 |",
+            "+------+-------+-------+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the strict sql mode rejects the value out of the precision
+        let result = core_execution
+            .execute_query("select cast(123456789.1 as decimal(10,2)) as a")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1264, mysql_error.error_number()),
+            Ok(_) => panic!("the value out of the precision must be rejected"),
+        }
+
+        // without the strict sql mode the value is clamped
+        core_execution.execute_query("set sql_mode = ''").await?;
+        let result = core_execution
+            .execute_query("select cast(123456789.1 as decimal(10,2)) as a, cast(-123456789.1 as decimal(10,2)) as b")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-------------+--------------+",
+            "| a           | b            |",
+            "+-------------+--------------+",
+            "| 99999999.99 | -99999999.99 |",
+            "+-------------+--------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn max_execution_time() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table t (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into t (id, name) values (1, 'a'), (2, 'b'), (3, 'c')")
+            .await?;
+        core_execution
+            .execute_query("create table big (id int, name char)")
+            .await?;
+        let values = (0..500).map(|i| format!("({}, 'a')", i)).collect::<Vec<_>>().join(", ");
+        core_execution
+            .execute_query(format!("insert into big (id, name) values {}", values).as_str())
+            .await?;
+
+        // the join of 250000 rows runs much longer than the max execution time
+        core_execution.execute_query("set max_execution_time = 10").await?;
+        let result = core_execution
+            .execute_query("select a.id, b.id from big a join big b on a.name = b.name")
+            .await;
+        match result {
+            Err(mysql_error) => {
+                assert_eq!(3024, mysql_error.error_number());
+                assert_eq!("70100", mysql_error.sql_state());
+                assert_eq!(
+                    "Query execution was interrupted, maximum statement execution time exceeded",
+                    mysql_error.message()
+                );
+
+                // the error packet is the whole response of the query
+                let payload = message::error_message(
+                    mysql_error.error_number(),
+                    mysql_error.sql_state().as_str(),
+                    mysql_error.message().as_str(),
+                );
+                assert_eq!(vec![0xff, 0xd0, 0x0b], payload.bytes[0..3].to_vec());
+                assert_eq!(b"#70100".to_vec(), payload.bytes[3..9].to_vec());
+            }
+            Ok(_) => panic!("the query running longer than the max execution time must be interrupted"),
+        }
+
+        // the session is usable for the next query
+        let result = core_execution.execute_query("select id, name from t order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        core_execution.execute_query("set max_execution_time = 0").await?;
+        let result = core_execution
+            .execute_query("select count(*) as c from big a join big b on a.name = b.name")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+--------+",
+            "| c      |",
+            "+--------+",
+            "| 250000 |",
+            "+--------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn explain_format_tree() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("create table orders (id int, user_id int)")
+            .await?;
+
+        let result = core_execution
+            .execute_query("explain format=tree select user.name, orders.id from user join orders on user.id = orders.user_id")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(1, results.len());
+        assert_eq!(1, results[0].num_rows());
+        assert_eq!("EXPLAIN", results[0].schema().field(0).name());
+
+        let tree = as_string_array(results[0].column(0)).value(0).to_string();
+        let lines: Vec<&str> = tree.lines().collect();
+        assert!(lines[0].starts_with("-> ProjectionExec"));
+
+        // the scans are nested under the join
+        let indent = |line: &str| line.len() - line.trim_start().len();
+        let join_index = lines.iter().position(|line| line.trim_start().starts_with("-> HashJoinExec")).unwrap();
+        let scan_lines: Vec<&str> = lines.iter().filter(|line| line.trim_start().starts_with("-> SledExec")).cloned().collect();
+        assert_eq!(2, scan_lines.len());
+        assert!(scan_lines.iter().any(|line| line.contains("table=def.test.user")));
+        assert!(scan_lines.iter().any(|line| line.contains("table=def.test.orders")));
+        for scan_line in scan_lines {
+            assert!(indent(scan_line) > indent(lines[join_index]));
+            assert!(scan_line.contains("seek=full table scan"));
+        }
+
+        let result = core_execution
+            .execute_query("explain format=json select id from user")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1235, mysql_error.error_number()),
+            Ok(_) => panic!("the json format is not supported"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_index_online() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, 'b'), (3, 'c')")
+            .await?;
+
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let mut index_build = IndexBuild::start(
+            core_execution.global_context(),
+            full_table_name.clone(),
+            "idx_name",
+            vec!["name".to_ident()],
+        )?;
+        // a part of the rows written before the start are indexed
+        assert!(!index_build.backfill_batch(2)?);
+
+        // the rows written during the build have their index entries too
+        core_execution
+            .execute_query("insert into user (id, name) values (4, 'd'), (5, 'e')")
+            .await?;
+
+        while !index_build.backfill_batch(2)? {}
+        index_build.finish()?;
+
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        let index_key_list = store_engine.scan_prefix_keys(
+            format!("{}idx_name/", util::dbkey::scan_table_index_key(&table_def.option)),
+            None,
+            100,
+        )?;
+        assert_eq!(5, index_key_list.len());
+        assert!(core_execution.global_context().lock().unwrap().building_index_map.is_empty());
+
+        core_execution
+            .execute_query("create unique index idx_id on user (id)")
+            .await?;
+
+        let result = core_execution
+            .execute_query("explain format=tree select id from user where name = 'e'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let tree = as_string_array(results[0].column(0)).value(0).to_string();
+        assert!(tree.contains("seek=index range scan on idx_name"));
+
+        let result = core_execution
+            .execute_query("select id, name from user where name = 'e' or name = 'a'")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 5  | e    |",
+            "+----+------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("insert into user (id, name) values (6, 'a')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the index is unique"),
+        }
+
+        let result = core_execution
+            .execute_query("create unique index idx_id on user (name)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1061, mysql_error.error_number()),
+            Ok(_) => panic!("the index name is taken"),
+        }
+
+        let result = core_execution
+            .execute_query("create unique index idx_name_again on user (NAME)")
+            .await;
+        match result {
+            Err(mysql_error) => {
+                assert_eq!(1061, mysql_error.error_number());
+                assert!(mysql_error.message().contains("idx_name"));
+            }
+            Ok(_) => panic!("the index has the columns of idx_name"),
+        }
+
+        let result = core_execution
+            .execute_query("create index idx_name_id on user (name, id)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1235, mysql_error.error_number()),
+            Ok(_) => panic!("the index which is not unique is not supported"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn derived_table_current_schema() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema other").await?;
+        core_execution.set_default_schema("other").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'x'), (2, 'y')")
+            .await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, 'b')")
+            .await?;
+
+        // the table of the subquery is in the current schema, as the table of the top-level query
+        let result = core_execution
+            .execute_query("select t.name from (select id, name from user) as t where t.id = 2")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+",
+            "| name |",
+            "+------+",
+            "| b    |",
+            "+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select t.name from (select id, name from orders) as t")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1146, mysql_error.error_number()),
+            Ok(_) => panic!("the table of the subquery does not exist"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn open_invalid_data_path() -> MysqlResult<()> {
+        // the data path is under a file, the directory can not be created
+        let file_path = std::env::temp_dir().join(format!("ebike_{}", Uuid::new_v4().to_simple()));
+        std::fs::write(&file_path, b"").unwrap();
+        let data_path = file_path.join("sled").to_string_lossy().to_string();
+
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = data_path.clone();
+        let result = open_execution(my_config).await;
+        std::fs::remove_file(&file_path).unwrap();
+        match result {
+            Err(mysql_error) => {
+                assert_eq!(1016, mysql_error.error_number());
+                assert!(mysql_error.message().contains(data_path.as_str()));
+            }
+            Ok(_) => panic!("the data path is not a directory"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn boolean_column() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, active boolean, verified bool)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, active, verified) values (1, true, false), (2, false, 1)")
+            .await?;
+
+        // TRUE and FALSE are stored as 1 and 0 of TINYINT(1)
+        let result = core_execution
+            .execute_query("select id, active, verified from user")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+--------+----------+",
+            "| id | active | verified |",
+            "+----+--------+----------+",
+            "| 1  | 1      | 0        |",
+            "| 2  | 0      | 1        |",
+            "+----+--------+----------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+        assert_eq!(&DataType::Int8, results[0].schema().field(1).data_type());
+
+        let result = core_execution.execute_query("show create table user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let create_table = as_string_array(results[0].column(1)).value(0).to_string();
+        assert!(create_table.contains("active TINYINT(1)"));
+        assert!(create_table.contains("verified TINYINT(1)"));
+
+        let result = core_execution
+            .execute_query("insert into user (id, active) values (3, 128)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1264, mysql_error.error_number()),
+            Ok(_) => panic!("128 is out of the range of TINYINT"),
+        }
+
+        let result = core_execution
+            .execute_query("create table orders (id int, status tinyint(4))")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1235, mysql_error.error_number()),
+            Ok(_) => panic!("only TINYINT(1) is supported"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn composite_primary_key() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user_role (user_id int, role_id int, note char, PRIMARY KEY(user_id, role_id))")
+            .await?;
+        core_execution
+            .execute_query("insert into user_role (user_id, role_id, note) values (1, 1, 'a'), (1, 2, 'b'), (2, 1, 'c')")
+            .await?;
+
+        // the same user_id or role_id alone is not a duplicate, the whole key is
+        let result = core_execution
+            .execute_query("insert into user_role (user_id, role_id, note) values (1, 2, 'd')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the composite primary key is duplicated"),
+        }
+
+        let result = core_execution
+            .execute_query("insert into user_role (user_id, note) values (3, 'e')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1048, mysql_error.error_number()),
+            Ok(_) => panic!("the columns of the primary key are not null"),
+        }
+
+        let result = core_execution
+            .execute_query("explain format=tree select note from user_role where user_id = 1 and role_id = 2")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let tree = as_string_array(results[0].column(0)).value(0).to_string();
+        assert!(tree.contains("seek=index range scan on PRIMARY"));
+
+        let result = core_execution
+            .execute_query("select note from user_role where user_id = 1 and role_id = 2")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+",
+            "| note |",
+            "+------+",
+            "| b    |",
+            "+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("create table orders (id int PRIMARY KEY, user_id int, PRIMARY KEY(id, user_id))")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1068, mysql_error.error_number()),
+            Ok(_) => panic!("the table has two primary keys"),
+        }
+
+        let result = core_execution
+            .execute_query("create table orders (id int, PRIMARY KEY(id, user_id))")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1072, mysql_error.error_number()),
+            Ok(_) => panic!("the column of the primary key does not exist"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn show_config() -> MysqlResult<()> {
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.read_ahead = 4;
+        my_config.server.max_execution_time = 1500;
+        let mut core_execution = create_execution_with_config(my_config).await?;
+
+        let result = core_execution.execute_query("show config").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(1, results.len());
+
+        let names = as_string_array(results[0].column(0));
+        let values = as_string_array(results[0].column(1));
+        let config_value = |name: &str| {
+            (0..results[0].num_rows())
+                .find(|row_index| names.value(*row_index) == name)
+                .map(|row_index| values.value(row_index).to_string())
+        };
+        // the configured values are shown, not the defaults
+        assert_eq!(Some("4".to_string()), config_value("engine.sled.read_ahead"));
+        assert_eq!(Some("1500".to_string()), config_value("server.max_execution_time"));
+        assert_eq!(Some("sled".to_string()), config_value("server.engines"));
+        assert!(config_value("engine.sled.data_path").is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn date_functions() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table orders (id int, created char, shipped char)")
+            .await?;
+        core_execution
+            .execute_query("insert into orders (id, created, shipped) values (1, '2024-01-30', '2024-02-02 10:00:00'), (2, '2024-01-31', '2024-03-31 09:00:00')")
+            .await?;
+
+        // the days are added across the month boundary, the months end on the last day of the month
+        let result = core_execution
+            .execute_query("select id, date_add(created, interval 3 day) as due, date_add(created, interval 1 month) as next_month, datediff(shipped, created) as days, timestampdiff(month, created, shipped) as months from orders")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------------+------------+------+--------+",
+            "| id | due        | next_month | days | months |",
+            "+----+------------+------------+------+--------+",
+            "| 1  | 2024-02-02 | 2024-02-29 | 3    | 0      |",
+            "| 2  | 2024-02-03 | 2024-02-29 | 60   | 2      |",
+            "+----+------------+------------+------+--------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select date_sub('2024-03-01 00:30:00', interval 45 minute) as value")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---------------------+",
+            "| value               |",
+            "+---------------------+",
+            "| 2024-02-29 23:45:00 |",
+            "+---------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn collate_expression() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'cherry'), (2, 'Banana'), (3, 'apple')")
+            .await?;
+
+        // the binary collation sorts the uppercase letters first
+        let result = core_execution
+            .execute_query("select name from user order by name collate utf8mb4_bin")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+--------+",
+            "| name   |",
+            "+--------+",
+            "| Banana |",
+            "| apple  |",
+            "| cherry |",
+            "+--------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select name from user order by name collate utf8mb4_0900_ai_ci")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+--------+",
+            "| name   |",
+            "+--------+",
+            "| apple  |",
+            "| Banana |",
+            "| cherry |",
+            "+--------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the collation of one side is the collation of the comparison
+        let result = core_execution
+            .execute_query("select id from user where name = 'BANANA' collate utf8mb4_0900_ai_ci")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select id from user where name = 'BANANA' collate utf8mb4_bin")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let row_count: usize = results.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(0, row_count);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn warning_count_and_last_insert_id() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int AUTO_INCREMENT, name char, PRIMARY KEY(id))")
+            .await?;
+
+        // without the strict sql mode the value which is not a number is a warning
+        core_execution.execute_query("set sql_mode = ''").await?;
+        core_execution
+            .execute_query("select cast('abc' as decimal(4,2)) as value")
+            .await?;
+        let result = core_execution
+            .execute_query("select @@warning_count as warnings, @@error_count as errors")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----------+--------+",
+            "| warnings | errors |",
+            "+----------+--------+",
+            "| 1        | 0      |",
+            "+----------+--------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the next statement clears the warnings
+        let result = core_execution
+            .execute_query("insert into user (name) values ('a'), ('b')")
+            .await?;
+        match result {
+            CoreOutput::FinalCount(final_count) => assert_eq!(1, final_count.last_insert_id),
+            _ => panic!("the insert returns the count"),
+        }
+        let result = core_execution
+            .execute_query("select @@warning_count as warnings, last_insert_id() as id")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----------+----+",
+            "| warnings | id |",
+            "+----------+----+",
+            "| 0        | 1  |",
+            "+----------+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the error of the last statement is counted
+        let result = core_execution.execute_query("select * from orders").await;
+        assert!(result.is_err());
+        let result = core_execution
+            .execute_query("select @@warning_count as warnings, @@error_count as errors")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----------+--------+",
+            "| warnings | errors |",
+            "+----------+--------+",
+            "| 1        | 1      |",
+            "+----------+--------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn no_database_selected() -> MysqlResult<()> {
+        let test_id = Uuid::new_v4()
+            .to_simple()
+            .encode_lower(&mut Uuid::encode_buffer())
+            .to_string();
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution
+            .execute_query("create table test.user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into test.user (id, name) values (1, 'a')")
+            .await?;
+
+        // the unqualified table without USE is not looked up in any database
+        for sql in vec![
+            "select id from user",
+            "insert into user (id, name) values (2, 'b')",
+            "update user set name = 'c'",
+            "delete from user",
+        ] {
+            match core_execution.execute_query(sql).await {
+                Err(mysql_error) => {
+                    assert_eq!(1046, mysql_error.error_number());
+                    assert_eq!("No database selected", mysql_error.message());
+                }
+                Ok(_) => panic!("no database is selected for '{}'", sql),
+            }
+        }
+        drop(core_execution);
+
+        // the configured default database is the one of the new sessions
+        my_config.server.default_schema = Some("test".to_string());
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        let result = core_execution.execute_query("select id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_data_skipped_rows() -> MysqlResult<()> {
+        let load_path = std::env::temp_dir().join(format!("ebike_load_{}", Uuid::new_v4().to_simple()));
+        std::fs::create_dir_all(&load_path).unwrap();
+        std::fs::write(load_path.join("fruit.csv"), "id,name,price\n1,apple,1.50\n2,pear,abc\n3,plum,\\N\n").unwrap();
+
+        let mut my_config = MyConfig::default();
+        my_config.server.secure_file_priv = Some(load_path.to_string_lossy().to_string());
+        let mut core_execution = create_execution_with_config(my_config).await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table fruit (id int, name char, price decimal(10,2))")
+            .await?;
+
+        // the strict sql mode stops at the bad row
+        let sql = "load data infile 'fruit.csv' into table fruit fields terminated by ',' ignore 1 lines";
+        let result = core_execution.execute_query(sql).await;
+        match result {
+            Err(mysql_error) => assert_eq!(1366, mysql_error.error_number()),
+            Ok(_) => panic!("the bad row must be rejected in the strict sql mode"),
+        }
+        core_execution.execute_query("delete from fruit").await?;
+
+        // without it the bad row is skipped with a warning
+        core_execution.execute_query("set sql_mode = ''").await?;
+        let result = core_execution.execute_query(sql).await?;
+        let mut count = 0;
+        let mut message = "".to_string();
+        match result {
+            CoreOutput::FinalCount(f) => {
+                count = f.affect_rows;
+                message = f.message;
+            }
+            _ => {}
+        }
+        assert_eq!(2, count);
+        assert_eq!("Records: 3  Deleted: 0  Skipped: 1  Warnings: 1", message);
+
+        let result = core_execution.execute_query("select id, name, price from fruit").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------+-------+",
+            "| id | name  | price |",
+            "+----+-------+-------+",
+            "| 1  | apple | 1.50  |",
+            "| 3  | plum  |       |",
+            "+----+-------+-------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the files out of the secure_file_priv directory are refused
+        let outside_path = load_path.with_extension("csv");
+        std::fs::write(&outside_path, "4,fig,2.00\n").unwrap();
+        let sql = format!("load data infile '{}' into table fruit", outside_path.to_string_lossy());
+        let result = core_execution.execute_query(sql.as_str()).await;
+        std::fs::remove_file(&outside_path).unwrap();
+        std::fs::remove_dir_all(&load_path).unwrap();
+        match result {
+            Err(mysql_error) => assert_eq!(1290, mysql_error.error_number()),
+            Ok(_) => panic!("the file is out of the secure_file_priv directory"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn show_tables_limit() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        for table_number in 1..=8 {
+            let sql = format!("create table t{} (id int)", table_number);
+            core_execution.execute_query(sql.as_str()).await?;
+        }
+
+        let result = core_execution.execute_query("show tables limit 5").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let row_count: usize = results.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(5, row_count);
+
+        let result = core_execution.execute_query("show tables limit 6, 5").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----------------+",
+            "| Tables_in_test |",
+            "+----------------+",
+            "| t7             |",
+            "| t8             |",
+            "+----------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn current_date_keyword() -> MysqlResult<()> {
+        let test_id = Uuid::new_v4()
+            .to_simple()
+            .encode_lower(&mut Uuid::encode_buffer())
+            .to_string();
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+
+        // the keyword is the same as the function
+        let result = core_execution
+            .execute_query("select current_date = curdate() as a, length(current_date) as b, length(current_timestamp) as c")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+----+----+",
+            "| a    | b  | c  |",
+            "+------+----+----+",
+            "| true | 10 | 19 |",
+            "+------+----+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        core_execution
+            .execute_query("create table event (id int, name char, created char default current_date)")
+            .await?;
+        core_execution
+            .execute_query("insert into event (id, name) values (1, 'a')")
+            .await?;
+        // the default is kept with the table
+        drop(core_execution);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("insert into event (id, name) values (2, 'b')")
+            .await?;
+        let result = core_execution
+            .execute_query("select id, created = current_date as today from event")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------+",
+            "| id | today |",
+            "+----+-------+",
+            "| 1  | true  |",
+            "| 2  | true  |",
+            "+----+-------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn com_stmt_execute_null_param() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, 'b')")
+            .await?;
+
+        let statement_id = match core_execution.com_stmt_prepare("select id from user where name = ? or id = ?").await? {
+            CoreOutput::ComStmtPrepare(stmt_prepare) => stmt_prepare.statement_id,
+            _ => panic!("COM_STMT_PREPARE must answer the statement"),
+        };
+
+        // the first parameter is NULL in the null bitmap and has no value, the second is the BIGINT 2
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&statement_id.to_le_bytes());
+        bytes.push(0x00);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.push(0b0000_0001);
+        bytes.push(0x01);
+        bytes.extend_from_slice(&[253, 0x00, 8, 0x00]);
+        bytes.extend_from_slice(&2i64.to_le_bytes());
+
+        let result = core_execution.com_stmt_execute(bytes).await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        // name = NULL matches no row
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn show_profile() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table user (id int, name char)").await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, 'b'), (3, 'c')")
+            .await?;
+
+        // no SELECT yet
+        let result = core_execution.execute_query("show profile").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(0, results.iter().map(|batch| batch.num_rows()).sum::<usize>());
+
+        core_execution.execute_query("select id, name from user where id > 1").await?;
+        // the SHOW statements do not replace the profile of the SELECT
+        core_execution.execute_query("show tables").await?;
+
+        let result = core_execution.execute_query("show profile").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(1, results.len());
+
+        let operators = as_string_array(results[0].column(0));
+        let rows = as_primitive_array::<UInt64Type>(results[0].column(1));
+        let bytes_scanned = as_primitive_array::<UInt64Type>(results[0].column(2));
+        let batches_read_ahead = as_primitive_array::<UInt64Type>(results[0].column(3));
+        let durations = as_string_array(results[0].column(4));
+        let row_count = results[0].num_rows();
+
+        let scan_index = (0..row_count)
+            .find(|row_index| operators.value(*row_index).trim() == "SledExec")
+            .unwrap();
+        assert_eq!(3, rows.value(scan_index));
+        assert!(bytes_scanned.value(scan_index) > 0);
+        // the batches are read on demand without the read ahead of the config
+        assert_eq!(0, batches_read_ahead.value(scan_index));
+        assert!(durations.value(scan_index).parse::<f64>().is_ok());
+
+        assert_eq!("total", operators.value(row_count - 1));
+        let elapsed = durations.value(row_count - 1).parse::<f64>().unwrap();
+        assert!(elapsed > 0.0);
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn session_variables_from_config() -> MysqlResult<()> {
+        let mut my_config = MyConfig::default();
+        my_config.server.session_variables.insert("sql_mode".to_string(), "strict_all_tables".to_string());
+        my_config.server.session_variables.insert("autocommit".to_string(), "OFF".to_string());
+        my_config.server.session_variables.insert("time_zone".to_string(), "+08:00".to_string());
+        my_config.server.session_variables.insert("character_set".to_string(), "latin1".to_string());
+        let mut core_execution = create_execution_with_config(my_config).await?;
+
+        // the session has the configured values without SET
+        let result = core_execution
+            .execute_query("select @@sql_mode, @@autocommit, @@session.time_zone, @@character_set_results")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!("STRICT_ALL_TABLES", as_string_array(results[0].column(0)).value(0));
+        assert_eq!(0, as_primitive_array::<Int64Type>(results[0].column(1)).value(0));
+        assert_eq!("+08:00", as_string_array(results[0].column(2)).value(0));
+        assert_eq!("latin1", as_string_array(results[0].column(3)).value(0));
+
+        core_execution.execute_query("set time_zone = '+00:00'").await?;
+        let result = core_execution.execute_query("select @@time_zone").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!("+00:00", as_string_array(results[0].column(0)).value(0));
+
+        // the sessions without the config have the default sql mode
+        let mut core_execution = create_execution().await?;
+        let result = core_execution.execute_query("select @@sql_mode").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(meta_const::DEFAULT_SQL_MODE, as_string_array(results[0].column(0)).value(0));
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn timestamp_time_zone() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table event (id int, created timestamp, happened datetime)")
+            .await?;
+
+        core_execution.execute_query("set time_zone = '+08:00'").await?;
+        core_execution
+            .execute_query("insert into event (id, created, happened) values (1, '2024-01-31 10:00:00', '2024-01-31 10:00:00')")
+            .await?;
+
+        // the TIMESTAMP is read in the time zone of the session, the DATETIME is as written
+        core_execution.execute_query("set time_zone = '+00:00'").await?;
+        let result = core_execution.execute_query("select id, created, happened from event").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+---------------------+---------------------+",
+            "| id | created             | happened            |",
+            "+----+---------------------+---------------------+",
+            "| 1  | 2024-01-31 02:00:00 | 2024-01-31 10:00:00 |",
+            "+----+---------------------+---------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        core_execution.execute_query("set time_zone = '-05:00'").await?;
+        let result = core_execution.execute_query("select created from event where id = 1").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---------------------+",
+            "| created             |",
+            "+---------------------+",
+            "| 2024-01-30 21:00:00 |",
+            "+---------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("set time_zone = 'Mars/Olympus'").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1298, mysql_error.error_number()),
+            Ok(_) => panic!("the time zone is not valid"),
+        }
+
+        let result = core_execution
+            .execute_query("insert into event (id, created) values (2, '2040-01-01 00:00:00')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1292, mysql_error.error_number()),
+            Ok(_) => panic!("the TIMESTAMP is out of range"),
+        }
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn zerofill_column() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table item (id int primary key, code int(5) zerofill)")
+            .await?;
+        core_execution.execute_query("insert into item (id, code) values (1, 42)").await?;
+
+        let result = core_execution.execute_query("select code from item").await?;
+        let result_set = match result {
+            CoreOutput::ResultSet(result_set) => result_set,
+            _ => panic!("the select has no result set"),
+        };
+        let columns = result_set
+            .schema_ref
+            .fields()
+            .iter()
+            .map(metadata::Column::from)
+            .collect::<Vec<_>>();
+        assert!(columns[0].flags().contains(metadata::ColumnFlags::ZEROFILL_FLAG));
+
+        let rows = core_util::convert_record_to_scalar_value(result_set.record_batches[0].clone());
+        let row = rows[0]
+            .iter()
+            .zip(columns.iter())
+            .map(|(scalar_value, column)| column.format_text_value(scalar_value.clone()))
+            .collect::<Vec<_>>();
+        let payload = message::row_message(row);
+        assert_eq!(b"\x0500042".to_vec(), payload.bytes);
+
+        // ZEROFILL is only supported on the INT columns
+        let result = core_execution
+            .execute_query("create table price (id int, amount decimal(10, 2) zerofill)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1235, mysql_error.error_number()),
+            Ok(_) => panic!("ZEROFILL on a DECIMAL column should fail"),
+        }
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn insert_batch_atomic() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table item (id int primary key, name char)")
+            .await?;
+        core_execution.execute_query("insert into item (id, name) values (5, 'e')").await?;
+
+        // the middle row duplicates a row of the table, the rows around it are not written
+        let result = core_execution
+            .execute_query("insert into item (id, name) values (1, 'a'), (5, 'b'), (2, 'c')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the duplicate entry should fail"),
+        }
+
+        // the middle row duplicates the first row of the statement
+        let result = core_execution
+            .execute_query("insert into item (id, name) values (3, 'a'), (3, 'b'), (4, 'c')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the duplicate entry should fail"),
+        }
+
+        let result = core_execution.execute_query("select id, name from item").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 5  | e    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn select_rowid_alias() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table item (id int auto_increment primary key, name char)")
+            .await?;
+        core_execution.execute_query("insert into item (name) values ('a'), ('b')").await?;
+
+        let result = core_execution.execute_query("select _rowid, id, name from item where name = 'a'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!("_rowid", results[0].schema().field(0).name());
+        let rowid = as_string_array(results[0].column(0)).value(0).to_string();
+        // the rowid is not the auto increment primary key
+        assert_ne!("1", rowid);
+
+        let result = core_execution
+            .execute_query(format!("delete from item where _rowid = '{}'", rowid).as_str())
+            .await?;
+        match result {
+            CoreOutput::FinalCount(FinalCount { affect_rows, .. }) => assert_eq!(1, affect_rows),
+            _ => panic!("the delete has no count"),
+        }
+
+        // select * does not return the rowid
+        let result = core_execution.execute_query("select * from item").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn com_stmt_bind_and_binary_row() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table user (id int, name char)").await?;
+
+        let statement_id = match core_execution.com_stmt_prepare("insert into user (id, name) values (?, ?)").await? {
+            CoreOutput::ComStmtPrepare(stmt_prepare) => {
+                assert_eq!(2, stmt_prepare.params.len());
+                stmt_prepare.statement_id
+            }
+            _ => panic!("COM_STMT_PREPARE must answer the statement"),
+        };
+
+        // the INT 3 and the string with a quote
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&statement_id.to_le_bytes());
+        bytes.push(0x00);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.push(0b0000_0000);
+        bytes.push(0x01);
+        bytes.extend_from_slice(&[3, 0x00, 254, 0x00]);
+        bytes.extend_from_slice(&3i32.to_le_bytes());
+        bytes.push(3);
+        bytes.extend_from_slice(b"a'b");
+        match core_execution.com_stmt_execute(bytes).await? {
+            CoreOutput::FinalCount(FinalCount { affect_rows, .. }) => assert_eq!(1, affect_rows),
+            _ => panic!("the insert must answer the count"),
+        }
+
+        // the types of the first execution are kept
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&statement_id.to_le_bytes());
+        bytes.push(0x00);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.push(0b0000_0010);
+        bytes.push(0x00);
+        bytes.extend_from_slice(&4i32.to_le_bytes());
+        core_execution.com_stmt_execute(bytes).await?;
+
+        let result = core_execution.execute_query("select id, name from user order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 3  | a'b  |",
+            "| 4  |      |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the wrong number of values
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&statement_id.to_le_bytes());
+        bytes.push(0x00);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        let result = core_execution.com_stmt_execute(bytes).await;
+        match result {
+            Err(mysql_error) => assert_eq!(1835, mysql_error.error_number()),
+            _ => panic!("the packet without the values must fail"),
+        }
+
+        match core_execution.com_stmt_reset(&statement_id.to_le_bytes()).await? {
+            CoreOutput::FinalCount(FinalCount { affect_rows, .. }) => assert_eq!(0, affect_rows),
+            _ => panic!("COM_STMT_RESET must answer OK"),
+        }
+        core_execution.com_stmt_close(&statement_id.to_le_bytes()).await?;
+        let result = core_execution.com_stmt_reset(&statement_id.to_le_bytes()).await;
+        match result {
+            Err(mysql_error) => assert_eq!(1243, mysql_error.error_number()),
+            _ => panic!("the closed statement must be unknown"),
+        }
+
+        // the header, the null bitmap from the bit 2, the BIGINT and the string, the NULL has no value
+        let payload = message::binary_row_message(vec![
+            ScalarValue::Int64(Some(4)),
+            ScalarValue::Utf8(None),
+            ScalarValue::Utf8(Some("ab".to_string())),
+        ], &[]);
+        assert_eq!(b"\x00\x08\x04\x00\x00\x00\x00\x00\x00\x00\x02ab".to_vec(), payload.bytes);
+
+        Ok(())
+    }
+    #[test]
+    fn ssl_capability() {
+        // the lower capability flags follow the version, the connection id and the first auth plugin data
+        let lower_capability_flags = |payload: response::ResponsePayload| u16::from_le_bytes([payload.bytes[21], payload.bytes[22]]) as u32;
+        let ssl = metadata::CapabilityFlags::CLIENT_SSL.bits();
+        let scramble = auth::create_scramble();
+        assert_eq!(ssl, lower_capability_flags(message::handshark_message(true, 1, &scramble)) & ssl);
+        assert_eq!(0, lower_capability_flags(message::handshark_message(false, 1, &scramble)) & ssl);
+
+        // the scramble is sent in two parts
+        let payload = message::handshark_message(false, 1, &scramble);
+        assert_eq!(&scramble[..8], &payload.bytes[12..20]);
+        assert!(!scramble.contains(&0) && !scramble.contains(&b'$'));
+
+        let mut config_server = MyConfig::default().server;
+        assert!(tls::load_tls_acceptor(&config_server).unwrap().is_none());
+
+        config_server.ssl_cert = Some("./server-cert.pem".to_string());
+        let result = tls::load_tls_acceptor(&config_server);
+        match result {
+            Err(mysql_error) => assert_eq!(2026, mysql_error.error_number()),
+            _ => panic!("the certificate without the key must fail"),
+        }
+    }
+    #[tokio::test]
+    async fn authenticate_sha2_password() -> MysqlResult<()> {
+        let core_execution = create_execution().await?;
+
+        // no digest is cached before the first full auth
+        let auth_response = auth::scramble_sha2_password(core_execution.scramble(), "123456");
+        assert_eq!(32, auth_response.len());
+        assert!(!core_execution.fast_authenticate_sha2_password("root", "127.0.0.1", auth_response.as_slice())?);
+
+        let result = core_execution.authenticate_password("root", "127.0.0.1", b"654321");
+        match result {
+            Err(mysql_error) => assert_eq!(1045, mysql_error.error_number()),
+            Ok(_) => panic!("the wrong password must be rejected"),
+        }
+        core_execution.authenticate_password("root", "127.0.0.1", auth::read_clear_password(b"123456\0").as_slice())?;
+
+        // the full auth caches the digest for the fast auth
+        assert!(core_execution.fast_authenticate_sha2_password("root", "127.0.0.1", auth_response.as_slice())?);
+        let auth_response = auth::scramble_sha2_password(core_execution.scramble(), "654321");
+        assert!(!core_execution.fast_authenticate_sha2_password("root", "127.0.0.1", auth_response.as_slice())?);
+
+        // the cached digest does not accept the auth response of another connection
+        let other_execution = Execution::new(core_execution.global_context());
+        let auth_response = auth::scramble_sha2_password(core_execution.scramble(), "123456");
+        assert!(!other_execution.fast_authenticate_sha2_password("root", "127.0.0.1", auth_response.as_slice())?);
+        let auth_response = auth::scramble_sha2_password(other_execution.scramble(), "123456");
+        assert!(other_execution.fast_authenticate_sha2_password("root", "127.0.0.1", auth_response.as_slice())?);
+        assert!(!core_execution.fast_authenticate_sha2_password("root", "127.0.0.1", auth_response.as_slice())?);
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn com_stmt_send_long_data() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table user (id int, name varchar(100))").await?;
+
+        let statement_id = match core_execution.com_stmt_prepare("insert into user (id, name) values (?, ?)").await? {
+            CoreOutput::ComStmtPrepare(stmt_prepare) => stmt_prepare.statement_id,
+            _ => panic!("COM_STMT_PREPARE must answer the statement"),
+        };
+
+        // the value of the second parameter in two chunks
+        for chunk in [b"hello ".as_ref(), b"world".as_ref()] {
+            let mut bytes = vec![];
+            bytes.extend_from_slice(&statement_id.to_le_bytes());
+            bytes.extend_from_slice(&1u16.to_le_bytes());
+            bytes.extend_from_slice(chunk);
+            core_execution.com_stmt_send_long_data(bytes.as_slice()).await?;
+        }
+
+        // the parameter of the long data has its type but no value
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&statement_id.to_le_bytes());
+        bytes.push(0x00);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.push(0b0000_0000);
+        bytes.push(0x01);
+        bytes.extend_from_slice(&[3, 0x00, 252, 0x00]);
+        bytes.extend_from_slice(&1i32.to_le_bytes());
+        core_execution.com_stmt_execute(bytes.clone()).await?;
+
+        // the long data is used by one execution only
+        let result = core_execution.com_stmt_execute(bytes).await;
+        assert!(result.is_err());
+
+        let result = core_execution.execute_query("select id, name from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------------+",
+            "| id | name        |",
+            "+----+-------------+",
+            "| 1  | hello world |",
+            "+----+-------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+    #[test]
+    fn compressed_packet() {
+        // the short packet is not compressed, the uncompressed length is 0
+        let mut compressed_packet = packet::CompressedPacket::new();
+        let bytes = compressed_packet.compress(&[0x01, 0x00, 0x00, 0x00, 0x0e]);
+        assert_eq!(vec![0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x0e], bytes.to_vec());
+
+        let mut packets = vec![0x66, 0x00, 0x00, 0x00, 0x03];
+        packets.extend_from_slice("select 1 from t where name = 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa'".as_bytes());
+        let bytes = compressed_packet.compress(packets.as_slice());
+        assert!(bytes.len() < packets.len());
+        // the sequence id of the second compressed packet, the length before the compression
+        assert_eq!(1, bytes[3]);
+        assert_eq!(packets.len(), u32::from_le_bytes([bytes[4], bytes[5], bytes[6], 0]) as usize);
+
+        let mut compressed_packet = packet::CompressedPacket::new();
+        let max_allowed_packet = packet::DEFAULT_MAX_ALLOWED_PACKET;
+        assert_eq!(packets, compressed_packet.decompress(bytes.as_ref(), max_allowed_packet).unwrap());
+        assert!(compressed_packet.decompress(&bytes[..10], max_allowed_packet).is_err());
+
+        // the payload inflating beyond its length before the compression is malformed
+        let mut short_length = bytes.to_vec();
+        short_length[4..7].copy_from_slice(&[0x10, 0x00, 0x00]);
+        let result = compressed_packet.decompress(short_length.as_slice(), max_allowed_packet);
+        assert_eq!(mysql_error_code::CR_MALFORMED_PACKET as u16, result.unwrap_err().error_number());
+
+        // the length before the compression is checked against max_allowed_packet before the inflation
+        let result = compressed_packet.decompress(bytes.as_ref(), 16);
+        assert_eq!(mysql_error_code::ER_NET_PACKET_TOO_LARGE as u16, result.unwrap_err().error_number());
+    }
+    #[tokio::test]
+    async fn load_data_local_infile() -> MysqlResult<()> {
+        let sql = "load data local infile 'fruit.csv' into table fruit fields terminated by ','";
+
+        // refused unless the server allows it
+        let mut core_execution = create_execution().await?;
+        let result = core_execution.execute_query(sql).await;
+        match result {
+            Err(mysql_error) => assert_eq!(3948, mysql_error.error_number()),
+            _ => panic!("LOAD DATA LOCAL must be disabled by default"),
+        }
+
+        let mut my_config = MyConfig::default();
+        my_config.server.local_infile = true;
+        let mut core_execution = create_execution_with_config(my_config).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table fruit (id int, name varchar(10))").await?;
+
+        let statement = match core_execution.execute_query(sql).await? {
+            CoreOutput::LoadDataLocalInfile(statement) => statement,
+            _ => panic!("the file must be requested from the client"),
+        };
+        assert_eq!("fruit.csv", statement.file_name);
+
+        // the rows are loaded as the packets of the file are received, a row may be split over the packets
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let load = core_execution.start_load_data_local(statement.clone(), receiver);
+        sender.send(Ok(b"1,apple\n2,".to_vec())).await.unwrap();
+        sender.send(Ok(b"pear\n".to_vec())).await.unwrap();
+        drop(sender);
+        let result = core_execution.finish_load_data_local(load).await?;
+        match result {
+            CoreOutput::FinalCount(f) => {
+                assert_eq!(2, f.affect_rows);
+                assert_eq!("Records: 2  Deleted: 0  Skipped: 0  Warnings: 0", f.message);
+            }
+            _ => panic!("LOAD DATA must answer the count"),
+        }
+
+        let result = core_execution.execute_query("select id, name from fruit").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------+",
+            "| id | name  |",
+            "+----+-------+",
+            "| 1  | apple |",
+            "| 2  | pear  |",
+            "+----+-------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the connection lost in the middle of the file fails the load
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let load = core_execution.start_load_data_local(statement, receiver);
+        sender.send(Ok(b"3,plum\n".to_vec())).await.unwrap();
+        sender.send(Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "Lost connection"))).await.unwrap();
+        drop(sender);
+        let result = core_execution.finish_load_data_local(load).await;
+        assert_eq!(1024, result.unwrap_err().error_number());
+
+        // the packets of the file may be split over the reads
+        assert_eq!(None, packet::complete_packet_length(&[0x03, 0x00, 0x00, 0x02, b'1'], packet::HEADER_LENGTH));
+        assert_eq!(Some(7), packet::complete_packet_length(&[0x03, 0x00, 0x00, 0x02, b'1', b',', b'a', 0x00], packet::HEADER_LENGTH));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn com_stmt_fetch_cursor() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table user (id int, name varchar(100))").await?;
+        core_execution.execute_query("insert into user (id, name) values (1, 'a'), (2, 'b'), (3, 'c')").await?;
+
+        // the wildcard of COM_FIELD_LIST
+        match core_execution.com_field_list("user", "NA%").await? {
+            CoreOutput::ComFieldList(_, _, columns) => {
+                let column_names = columns.iter().map(|column_def| column_def.sql_column.name.value.clone()).collect::<Vec<_>>();
+                assert_eq!(vec!["name".to_string()], column_names);
+            }
+            _ => panic!("COM_FIELD_LIST must answer the columns"),
+        }
+
+        let statement_id = match core_execution.com_stmt_prepare("select id, name from user order by id").await? {
+            CoreOutput::ComStmtPrepare(stmt_prepare) => stmt_prepare.statement_id,
+            _ => panic!("COM_STMT_PREPARE must answer the statement"),
+        };
+
+        let fetch_bytes = |num_rows: u32| {
+            let mut bytes = vec![];
+            bytes.extend_from_slice(&statement_id.to_le_bytes());
+            bytes.extend_from_slice(&num_rows.to_le_bytes());
+            bytes
+        };
+
+        // no cursor before the execution
+        let result = core_execution.com_stmt_fetch(fetch_bytes(2).as_slice()).await;
+        assert_eq!(1421, result.err().unwrap().error_number());
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&statement_id.to_le_bytes());
+        bytes.push(metadata::CURSOR_TYPE_READ_ONLY);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        match core_execution.com_stmt_execute(bytes).await? {
+            CoreOutput::ComStmtCursor(schema_ref) => assert_eq!(2, schema_ref.fields().len()),
+            _ => panic!("COM_STMT_EXECUTE must open the cursor"),
+        }
+
+        match core_execution.com_stmt_fetch(fetch_bytes(2).as_slice()).await? {
+            CoreOutput::ComStmtFetch(stmt_fetch) => {
+                assert_eq!(2, stmt_fetch.rows.len());
+                assert_eq!(ScalarValue::Utf8(Some("a".to_string())), stmt_fetch.rows[0][1]);
+                assert!(!stmt_fetch.last_row_sent);
+            }
+            _ => panic!("COM_STMT_FETCH must answer the rows"),
+        }
+        match core_execution.com_stmt_fetch(fetch_bytes(2).as_slice()).await? {
+            CoreOutput::ComStmtFetch(stmt_fetch) => {
+                assert_eq!(1, stmt_fetch.rows.len());
+                assert_eq!(ScalarValue::Utf8(Some("c".to_string())), stmt_fetch.rows[0][1]);
+                assert!(stmt_fetch.last_row_sent);
+            }
+            _ => panic!("COM_STMT_FETCH must answer the rows"),
+        }
+
+        // the cursor is closed after its last row
+        let result = core_execution.com_stmt_fetch(fetch_bytes(2).as_slice()).await;
+        assert_eq!(1421, result.err().unwrap().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn kill_and_processlist() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        assert_eq!(1, core_execution.connection_id());
+
+        // the running statement is shown with its sql
+        let result = core_execution.execute_query("select id, command, info from information_schema.processlist").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+---------+--------------------------------------------------------------+",
+            "| id | command | info                                                         |",
+            "+----+---------+--------------------------------------------------------------+",
+            "| 1  | Query   | select id, command, info from information_schema.processlist |",
+            "+----+---------+--------------------------------------------------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("kill query 99").await;
+        assert_eq!(1094, result.err().unwrap().error_number());
+
+        // KILL QUERY keeps the connection, KILL CONNECTION closes it
+        core_execution.execute_query("kill query 1").await?;
+        assert!(!core_execution.connection_token().is_cancelled());
+        core_execution.execute_query("kill 1").await?;
+        assert!(core_execution.connection_token().is_cancelled());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reset_connection_and_change_user() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table user (id int, name varchar(100))").await?;
+        let statement_id = match core_execution.com_stmt_prepare("select id from user").await? {
+            CoreOutput::ComStmtPrepare(stmt_prepare) => stmt_prepare.statement_id,
+            _ => panic!("COM_STMT_PREPARE must answer the statement"),
+        };
+
+        core_execution.reset_connection()?;
+
+        // the database and the prepared statements of the session are cleared
+        let result = core_execution.execute_query("select id from user").await;
+        assert_eq!(1046, result.err().unwrap().error_number());
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&statement_id.to_le_bytes());
+        bytes.push(0x00);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        let result = core_execution.com_stmt_execute(bytes).await;
+        assert_eq!(1243, result.err().unwrap().error_number());
+
+        // the user, the auth response, the database, the character set and the auth plugin
+        let mut bytes = vec![0x00, 0x00, 0x00, 0x00, 0x11];
+        bytes.extend_from_slice(b"root\0");
+        bytes.push(20);
+        bytes.extend_from_slice(&[7; 20]);
+        bytes.extend_from_slice(b"test\0");
+        bytes.extend_from_slice(&[0xff, 0x00]);
+        bytes.extend_from_slice(b"mysql_native_password\0");
+        let capability_flags = metadata::CapabilityFlags::CLIENT_PROTOCOL_41
+            | metadata::CapabilityFlags::CLIENT_SECURE_CONNECTION
+            | metadata::CapabilityFlags::CLIENT_PLUGIN_AUTH;
+        let change_user = request::RequestPayload::new(bytes).get_change_user(capability_flags)?;
+        assert_eq!("root", change_user.username);
+        assert_eq!(vec![7; 20], change_user.auth_response);
+        assert_eq!(Some("test".to_string()), change_user.database);
+        assert_eq!(Some("mysql_native_password".to_string()), change_user.auth_plugin_name);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn system_variables() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        // the integers and the booleans are read as numbers
+        let result = core_execution.execute_query("select @@max_allowed_packet, @@global.autocommit, @@transaction_isolation").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(67108864, as_primitive_array::<Int64Type>(results[0].column(0)).value(0));
+        assert_eq!(1, as_primitive_array::<Int64Type>(results[0].column(1)).value(0));
+        assert_eq!("REPEATABLE-READ", as_string_array(results[0].column(2)).value(0));
+
+        let result = core_execution.execute_query("select @@no_such_variable").await;
+        assert_eq!(1193, result.err().unwrap().error_number());
+        let result = core_execution.execute_query("set max_allowed_packet = 1048576").await;
+        assert_eq!(1229, result.err().unwrap().error_number());
+        let result = core_execution.execute_query("set global version = '1.0'").await;
+        assert_eq!(1238, result.err().unwrap().error_number());
+        let result = core_execution.execute_query("set autocommit = 2").await;
+        assert_eq!(1231, result.err().unwrap().error_number());
+        let result = core_execution.execute_query("set transaction_isolation = 'READ COMMITTED'").await;
+        assert_eq!(1231, result.err().unwrap().error_number());
+
+        // the session value does not change the global one
+        core_execution
+            .execute_query("set global max_allowed_packet = 1048576, session transaction_isolation = 'read-committed', @@autocommit = off")
+            .await?;
+        let result = core_execution
+            .execute_query("select @@max_allowed_packet, @@transaction_isolation, @@global.transaction_isolation")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(1048576, as_primitive_array::<Int64Type>(results[0].column(0)).value(0));
+        assert_eq!("READ-COMMITTED", as_string_array(results[0].column(1)).value(0));
+        assert_eq!("REPEATABLE-READ", as_string_array(results[0].column(2)).value(0));
+
+        let result = core_execution.execute_query("show variables like 'transaction\\_%'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----------------------+----------------+",
+            "| Variable_name         | Value          |",
+            "+-----------------------+----------------+",
+            "| transaction_isolation | READ-COMMITTED |",
+            "| transaction_read_only | OFF            |",
+            "+-----------------------+----------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("show global variables like 'transaction_isolation'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----------------------+-----------------+",
+            "| Variable_name         | Value           |",
+            "+-----------------------+-----------------+",
+            "| transaction_isolation | REPEATABLE-READ |",
+            "+-----------------------+-----------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("show session variables where variable_name in ('autocommit', 'max_allowed_packet')")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+--------------------+---------+",
+            "| Variable_name      | Value   |",
+            "+--------------------+---------+",
+            "| autocommit         | OFF     |",
+            "| max_allowed_packet | 1048576 |",
+            "+--------------------+---------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // DEFAULT gives the session the global value back
+        core_execution.execute_query("set transaction_isolation = default").await?;
+        let result = core_execution.execute_query("select @@session.transaction_isolation").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!("REPEATABLE-READ", as_string_array(results[0].column(0)).value(0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn user_variables() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table user (id int, name varchar(100))").await?;
+        core_execution.execute_query("insert into user values (1, 'lucy'), (2, 'li''ly'), (3, 'tom')").await?;
+
+        // the expressions read the variables set before them, the names are case insensitive
+        core_execution.execute_query("set @min = 2, @max := @min + 1, @Name = 'li''ly'").await?;
+        let result = core_execution
+            .execute_query("select id, name from user where id between @min and @MAX and name = @name")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------+",
+            "| id | name  |",
+            "+----+-------+",
+            "| 2  | li'ly |",
+            "+----+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        core_execution.execute_query("select name, id - 10 into @found, @negative from user where id = 3").await?;
+        let result = core_execution.execute_query("select @found, 1-@negative").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!("tom", as_string_array(results[0].column(0)).value(0));
+        assert_eq!(8, as_primitive_array::<Int64Type>(results[0].column(1)).value(0));
+
+        // no row keeps the values with a warning
+        core_execution.execute_query("select name into @found from user where id = 99").await?;
+        let result = core_execution.execute_query("select @found, @not_set").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!("tom", as_string_array(results[0].column(0)).value(0));
+        assert_eq!(None, core_util::array_value_to_text(results[0].column(1), 0)?);
+
+        let result = core_execution.execute_query("select name into @found from user").await;
+        assert_eq!(1172, result.err().unwrap().error_number());
+        let result = core_execution.execute_query("select id, name into @found from user where id = 1").await;
+        assert_eq!(1222, result.err().unwrap().error_number());
+
+        // the variables are cleared with the session
+        core_execution.reset_connection()?;
+        let result = core_execution.execute_query("select @found").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(None, core_util::array_value_to_text(results[0].column(0), 0)?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn session_connect_attrs() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        let capability_flags = metadata::CapabilityFlags::CLIENT_PROTOCOL_41
+            | metadata::CapabilityFlags::CLIENT_SECURE_CONNECTION
+            | metadata::CapabilityFlags::CLIENT_PLUGIN_AUTH
+            | metadata::CapabilityFlags::CLIENT_CONNECT_ATTRS;
+        let mut bytes = handshake_response_bytes(capability_flags, "mysql_native_password");
+        let mut attrs = vec![];
+        for (attr_name, attr_value) in [("_client_version", "8.0.25"), ("program_name", "mysql")] {
+            attrs.push(attr_name.len() as u8);
+            attrs.extend_from_slice(attr_name.as_bytes());
+            attrs.push(attr_value.len() as u8);
+            attrs.extend_from_slice(attr_value.as_bytes());
+        }
+        bytes.push(attrs.len() as u8);
+        bytes.extend_from_slice(attrs.as_slice());
+        bytes[0] = (bytes.len() - 4) as u8;
+
+        let request_payload = request::RequestPayload::new(bytes.clone());
+        let handshake_response = request_payload.get_handshake_response().unwrap();
+        assert_eq!(Some("mysql_native_password".to_string()), handshake_response.auth_plugin_name);
+        assert_eq!(
+            vec![("_client_version".to_string(), "8.0.25".to_string()), ("program_name".to_string(), "mysql".to_string())],
+            handshake_response.connect_attrs
+        );
+
+        // the attributes cut short are rejected
+        bytes.pop();
+        bytes[0] = (bytes.len() - 4) as u8;
+        assert!(request::RequestPayload::new(bytes).get_handshake_response().is_err());
+
+        core_execution.set_connect_attrs(handshake_response.connect_attrs);
+        let result = core_execution.execute_query("select processlist_id, attr_name, attr_value, ordinal_position from information_schema.session_connect_attrs").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----------------+-----------------+------------+------------------+",
+            "| processlist_id | attr_name       | attr_value | ordinal_position |",
+            "+----------------+-----------------+------------+------------------+",
+            "| 1              | _client_version | 8.0.25     | 0                |",
+            "| 1              | program_name    | mysql      | 1                |",
+            "+----------------+-----------------+------------+------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn show_warnings() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table user (id int, name varchar(100))").await?;
+        core_execution.execute_query("insert into user (id, name) values (1, 'a'), (2, 'b')").await?;
+
+        // the warnings are counted in the EOF of the rows and shown by SHOW WARNINGS
+        core_execution.execute_query("set sql_mode = ''").await?;
+        let result = core_execution.execute_query("select cast('abc' as decimal(4,2)) as value").await?;
+        match result {
+            CoreOutput::ResultSet(result_set) => assert_eq!(1, result_set.warning_count),
+            _ => panic!("the select returns the rows"),
+        }
+        let result = core_execution.execute_query("show warnings").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---------+------+------------------------------------------+",
+            "| Level   | Code | Message                                  |",
+            "+---------+------+------------------------------------------+",
+            "| Warning | 1292 | Truncated incorrect DECIMAL value: 'abc' |",
+            "+---------+------+------------------------------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // SHOW WARNINGS keeps the warnings, SHOW ERRORS has none of them
+        let result = core_execution.execute_query("show errors").await?;
+        match result {
+            CoreOutput::ResultSet(r) => assert!(r.record_batches.is_empty()),
+            _ => panic!("show errors returns the rows"),
+        }
+        let result = core_execution.execute_query("show count(*) warnings").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-------------------------+",
+            "| @@session.warning_count |",
+            "+-------------------------+",
+            "| 1                       |",
+            "+-------------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the error of the last statement is a condition too
+        let result = core_execution.execute_query("select * from orders").await;
+        let error_number = result.err().unwrap().error_number();
+        let result = core_execution.execute_query("show errors limit 1").await?;
+        match result {
+            CoreOutput::ResultSet(r) => {
+                let record_batch = r.record_batches.get(0).unwrap();
+                assert_eq!(1, record_batch.num_rows());
+                assert_eq!("Error", as_string_array(record_batch.column(0)).value(0));
+                assert_eq!(error_number as u64, as_primitive_array::<UInt64Type>(record_batch.column(1)).value(0));
+            }
+            _ => panic!("show errors returns the rows"),
+        }
+
+        // the next statement clears them, the OK packet has the warnings of the statement
+        let result = core_execution.execute_query("update user set name = 'b' where id > 0").await?;
+        match result {
+            CoreOutput::FinalCount(final_count) => {
+                assert_eq!(1, final_count.affect_rows);
+                assert_eq!(0, final_count.warning_count);
+            }
+            _ => panic!("the update returns the count"),
+        }
+        let result = core_execution.execute_query("show warnings").await?;
+        match result {
+            CoreOutput::ResultSet(r) => assert!(r.record_batches.is_empty()),
+            _ => panic!("show warnings returns the rows"),
+        }
+
+        // with CLIENT_FOUND_ROWS the affected rows are the matched rows
+        core_execution.set_client_found_rows(true);
+        let result = core_execution.execute_query("update user set name = 'b' where id > 0").await?;
+        match result {
+            CoreOutput::FinalCount(final_count) => assert_eq!(2, final_count.affect_rows),
+            _ => panic!("the update returns the count"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn packet_reassembly() -> MysqlResult<()> {
+        let core_execution = create_execution().await?;
+        assert_eq!(67108864, core_execution.max_allowed_packet());
+
+        // the payload of the max payload length is continued by the next packet of the sequence
+        let mut bytes = vec![0xff, 0xff, 0xff, 0x00, 0x03];
+        bytes.extend(std::iter::repeat(b'a').take(packet::MAX_PAYLOAD_LENGTH - 1));
+        bytes.extend_from_slice(&[0x02, 0x00, 0x00, 0x01, b'b', b'c']);
+        // the next command
+        bytes.extend_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x0e]);
+
+        let mut packet_reader = packet::PacketReader::new();
+        for chunk in bytes.chunks(65536) {
+            assert_eq!(None, packet_reader.next_payload(None)?);
+            packet_reader.push(chunk);
+        }
+        let payload = packet_reader.next_payload(None)?.unwrap();
+        assert_eq!(packet::HEADER_LENGTH + packet::MAX_PAYLOAD_LENGTH + 2, payload.len());
+        let request_payload = request::RequestPayload::new(payload);
+        assert_eq!(1, request_payload.get_sequence_id());
+        assert_eq!(0x03, request_payload.get_command_id()?);
+        let sql = request_payload.get_query_sql();
+        assert_eq!(packet::MAX_PAYLOAD_LENGTH + 1, sql.len());
+        assert_eq!(b"abc", &sql[sql.len() - 3..]);
+        assert_eq!(Some(vec![0x01, 0x00, 0x00, 0x00, 0x0e]), packet_reader.next_payload(None)?);
+        assert_eq!(None, packet_reader.next_payload(None)?);
+
+        // the payload longer than max_allowed_packet is rejected by its header, before its bytes are received
+        let mut packet_reader = packet::PacketReader::new();
+        packet_reader.with_max_allowed_packet(1024);
+        packet_reader.push(&[0x01, 0x04, 0x00, 0x00, 0x03]);
+        let result = packet_reader.next_payload(None);
+        assert_eq!(mysql_error_code::ER_NET_PACKET_TOO_LARGE as u16, result.err().unwrap().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn wait_timeout_and_close() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        assert_eq!(std::time::Duration::from_secs(28800), core_execution.wait_timeout());
+
+        core_execution.execute_query("set wait_timeout = 5").await?;
+        assert_eq!(std::time::Duration::from_secs(5), core_execution.wait_timeout());
+
+        // the interactive client starts with the global interactive_timeout
+        core_execution.execute_query("set global interactive_timeout = 100").await?;
+        core_execution.set_client_interactive(true);
+        core_execution.reset_connection()?;
+        assert_eq!(std::time::Duration::from_secs(100), core_execution.wait_timeout());
+
+        // the closed connection leaves the process list, its prepared statements are released
+        core_execution.com_stmt_prepare("select 1").await?;
+        core_execution.close();
+        let processes = core_execution.global_context().lock().unwrap().process_list.processes();
+        assert!(processes.iter().all(|process| process.id != core_execution.connection_id()));
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.push(0x00);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        let result = core_execution.com_stmt_execute(bytes).await;
+        assert_eq!(1243, result.err().unwrap().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn user_privileges() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table t1 (id int, PRIMARY KEY(id))").await?;
+        core_execution.execute_query("insert into t1 values (1)").await?;
+
+        core_execution.execute_query("create user 'app'@'%' identified by 'secret'").await?;
+        let result = core_execution.execute_query("create user 'app'@'%'").await;
+        assert_eq!(mysql_error_code::ER_CANNOT_USER as u16, result.err().unwrap().error_number());
+        core_execution.execute_query("grant select, insert on test.* to 'app'@'%'").await?;
+        core_execution.execute_query("revoke insert on test.* from 'app'@'%'").await?;
+        core_execution.execute_query("create view v1 as select id from t1").await?;
+        let other_execution = Execution::new(core_execution.global_context());
+        other_execution.set_process_user("root", "127.0.0.1");
+
+        // the statements of app are checked against its privileges
+        core_execution.set_process_user("app", "127.0.0.1");
+        core_execution.execute_query("select id from t1").await?;
+        let result = core_execution.execute_query("insert into t1 values (2)").await;
+        assert_eq!(mysql_error_code::ER_TABLEACCESS_DENIED_ERROR as u16, result.err().unwrap().error_number());
+        let result = core_execution.execute_query("delete from t1").await;
+        assert_eq!(mysql_error_code::ER_TABLEACCESS_DENIED_ERROR as u16, result.err().unwrap().error_number());
+        let result = core_execution.execute_query("create user dev").await;
+        assert_eq!(mysql_error_code::ER_SPECIFIC_ACCESS_DENIED_ERROR as u16, result.err().unwrap().error_number());
+        let denied_statements = vec![
+            ("create table t2 (id int, PRIMARY KEY(id))", mysql_error_code::ER_TABLEACCESS_DENIED_ERROR),
+            ("drop table t1", mysql_error_code::ER_TABLEACCESS_DENIED_ERROR),
+            ("alter table t1 add column age int", mysql_error_code::ER_TABLEACCESS_DENIED_ERROR),
+            ("alter table t1 drop column id", mysql_error_code::ER_TABLEACCESS_DENIED_ERROR),
+            ("create unique index idx_id on t1 (id)", mysql_error_code::ER_TABLEACCESS_DENIED_ERROR),
+            ("drop index idx_id on t1", mysql_error_code::ER_TABLEACCESS_DENIED_ERROR),
+            ("drop view v1", mysql_error_code::ER_TABLEACCESS_DENIED_ERROR),
+            ("create schema app", mysql_error_code::ER_DBACCESS_DENIED_ERROR),
+            ("drop schema test", mysql_error_code::ER_DBACCESS_DENIED_ERROR),
+            ("set global interactive_timeout = 100", mysql_error_code::ER_SPECIFIC_ACCESS_DENIED_ERROR),
+        ];
+        for (sql, error_code) in denied_statements {
+            let result = core_execution.execute_query(sql).await;
+            assert_eq!(error_code as u16, result.err().unwrap().error_number(), "{}", sql);
+        }
+
+        // app sees and kills only its own connections without PROCESS and SUPER
+        let result = core_execution.execute_query("select user from information_schema.processlist").await?;
+        let expected = vec![
+            "+------+",
+            "| user |",
+            "+------+",
+            "| app  |",
+            "+------+",
+        ];
+        match result {
+            CoreOutput::ResultSet(result_set) => assert_batches_eq!(expected, &result_set.record_batches),
+            _ => panic!("the processlist must return a result set"),
+        }
+        let result = core_execution.execute_query(format!("kill {}", other_execution.connection_id()).as_str()).await;
+        assert_eq!(mysql_error_code::ER_KILL_DENIED_ERROR as u16, result.err().unwrap().error_number());
+        assert!(!other_execution.connection_token().is_cancelled());
+        let result = core_execution.com_process_kill(&other_execution.connection_id().to_le_bytes());
+        assert_eq!(mysql_error_code::ER_KILL_DENIED_ERROR as u16, result.err().unwrap().error_number());
+
+        let result = core_execution.execute_query("show grants").await?;
+        let expected = vec![
+            "+---------------------------------------+",
+            "| Grants for app@%                      |",
+            "+---------------------------------------+",
+            "| GRANT USAGE ON *.* TO `app`@`%`       |",
+            "| GRANT SELECT ON `test`.* TO `app`@`%` |",
+            "+---------------------------------------+",
+        ];
+        match result {
+            CoreOutput::ResultSet(result_set) => assert_batches_eq!(expected, &result_set.record_batches),
+            _ => panic!("show grants must return a result set"),
+        }
+
+        // root drops app and its grants
+        core_execution.set_process_user("root", "127.0.0.1");
+        core_execution.execute_query("drop user 'app'@'%'").await?;
+        let result = core_execution.execute_query("drop user if exists 'app'@'%'").await?;
+        match result {
+            CoreOutput::FinalCount(final_count) => assert_eq!(1, final_count.warning_count),
+            _ => panic!("drop user must return a count"),
+        }
+        core_execution.set_process_user("app", "127.0.0.1");
+        let result = core_execution.execute_query("select id from t1").await;
+        assert_eq!(mysql_error_code::ER_TABLEACCESS_DENIED_ERROR as u16, result.err().unwrap().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn postgres_simple_query() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table t1 (id int, name char, PRIMARY KEY(id))").await?;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let global_context = core_execution.global_context();
+        tokio::spawn(front_end::serve(listener, move |socket| {
+            postgres::handle::Handle::new(socket, global_context.clone(), None)
+        }));
+
+        fn frontend_message(message_type: u8, body: &[u8]) -> Vec<u8> {
+            let mut bytes = vec![message_type];
+            bytes.extend_from_slice(&((body.len() + 4) as i32).to_be_bytes());
+            bytes.extend_from_slice(body);
+            bytes
+        }
+        async fn read_message(client: &mut TcpStream) -> (u8, Vec<u8>) {
+            let mut header = [0; 5];
+            client.read_exact(&mut header).await.unwrap();
+            let length = i32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+            let mut body = vec![0; length as usize - 4];
+            client.read_exact(&mut body).await.unwrap();
+            (header[0], body)
+        }
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let mut startup = postgres::message::PROTOCOL_VERSION.to_be_bytes().to_vec();
+        startup.extend_from_slice(b"user\0root\0database\0test\0application_name\0psql\0\0");
+        let mut bytes = ((startup.len() + 4) as i32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(startup.as_slice());
+        client.write_all(bytes.as_slice()).await.unwrap();
+
+        // root has a password, it is sent in clear text
+        assert_eq!((b'R', 3i32.to_be_bytes().to_vec()), read_message(&mut client).await);
+        client.write_all(frontend_message(b'p', b"123456\0").as_slice()).await.unwrap();
+        assert_eq!((b'R', 0i32.to_be_bytes().to_vec()), read_message(&mut client).await);
+        loop {
+            let (message_type, body) = read_message(&mut client).await;
+            if message_type == b'Z' {
+                assert_eq!(b"I".to_vec(), body);
+                break;
+            }
+            assert_eq!(b'S', message_type);
+        }
+
+        let sql = "insert into t1 values (1, 'a'), (2, 'b'); select id, name from t1 where id = 2\0";
+        client.write_all(frontend_message(b'Q', sql.as_bytes()).as_slice()).await.unwrap();
+        assert_eq!((b'C', b"INSERT 0 2\0".to_vec()), read_message(&mut client).await);
+        let (message_type, body) = read_message(&mut client).await;
+        assert_eq!(b'T', message_type);
+        assert_eq!(2, i16::from_be_bytes([body[0], body[1]]));
+        let mut row = 2i16.to_be_bytes().to_vec();
+        row.extend_from_slice(&[0, 0, 0, 1, b'2', 0, 0, 0, 1, b'b']);
+        assert_eq!((b'D', row), read_message(&mut client).await);
+        assert_eq!((b'C', b"SELECT 1\0".to_vec()), read_message(&mut client).await);
+        assert_eq!(b'Z', read_message(&mut client).await.0);
+
+        // the error ends the statements of the query, the session is ready for the next query
+        client.write_all(frontend_message(b'Q', b"select * from t2; select 1\0").as_slice()).await.unwrap();
+        assert_eq!(b'E', read_message(&mut client).await.0);
+        assert_eq!(b'Z', read_message(&mut client).await.0);
+        client.write_all(frontend_message(b'X', b"").as_slice()).await.unwrap();
+
+        assert_eq!("DELETE 3", postgres::message::command_tag(" delete from t1", 3));
+        assert_eq!("CREATE TABLE", postgres::message::command_tag("create table t3 (id int)", 0));
+        assert_eq!(
+            Some(postgres::message::StartupMessage::SslRequest),
+            postgres::message::parse_startup_message(&postgres::message::SSL_REQUEST_CODE.to_be_bytes())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_results() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table t1 (id int, name char, PRIMARY KEY(id))").await?;
+        core_execution.execute_query("insert into t1 values (1, 'a'), (2, 'b')").await?;
+        core_execution.set_client_stream_results(true);
+
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "+----+------+",
+        ];
+        let result = core_execution.execute_query("select id, name from t1 order by id").await?;
+        let result_set = match result {
+            CoreOutput::ResultStream(result_stream) => result_stream.collect().await?,
+            _ => panic!("the select must return a stream"),
+        };
+        assert_batches_eq!(expected, &result_set.record_batches);
+
+        // the rows of the multi-statement query are collected, the statements after a select run before they are sent
+        core_execution.set_client_multi_statements(true);
+        let result = core_execution.execute_query("select id, name from t1 order by id; delete from t1").await?;
+        match result {
+            CoreOutput::MultiStatement(outputs) => match &outputs[0] {
+                Ok(CoreOutput::ResultSet(result_set)) => assert_batches_eq!(expected, &result_set.record_batches),
+                _ => panic!("the rows of the select must be collected"),
+            },
+            _ => panic!("the query must return the outputs of its statements"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn transaction() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table t1 (id int, name char, PRIMARY KEY(id))").await?;
+
+        // the rows of the rolled back transaction are not written
+        core_execution.execute_query("begin").await?;
+        core_execution.execute_query("insert into t1 values (1, 'a')").await?;
+        assert!(core_execution.in_transaction());
+        core_execution.execute_query("rollback").await?;
+        assert!(!core_execution.in_transaction());
+        core_execution.execute_query("start transaction").await?;
+        core_execution.execute_query("insert into t1 values (2, 'b')").await?;
+        core_execution.execute_query("commit").await?;
+
+        // with autocommit off the first statement opens the transaction, turning autocommit on commits it
+        core_execution.execute_query("set autocommit = 0").await?;
+        assert!(!core_execution.autocommit());
+        core_execution.execute_query("insert into t1 values (3, 'c')").await?;
+        assert!(core_execution.in_transaction());
+        core_execution.execute_query("set autocommit = 1").await?;
+        assert!(!core_execution.in_transaction());
+
+        // the deleted rows are kept by the rollback
+        core_execution.execute_query("begin").await?;
+        let result = core_execution.execute_query("delete from t1").await?;
+        match result {
+            CoreOutput::FinalCount(final_count) => assert_eq!(2, final_count.affect_rows),
+            _ => panic!("the delete must return its count"),
+        }
+        core_execution.execute_query("rollback").await?;
+
+        let result = core_execution.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn snapshot_isolation() -> MysqlResult<()> {
+        let mut session_a = create_execution().await?;
+        session_a.execute_query("create schema test").await?;
+        session_a.set_default_schema("test").await?;
+        session_a.execute_query("create table t1 (id int, name char, PRIMARY KEY(id))").await?;
+        session_a.execute_query("insert into t1 values (1, 'a')").await?;
+
+        let mut session_b = Execution::new(session_a.global_context());
+        session_b.try_init()?;
+        session_b.set_default_schema("test").await?;
+
+        // the transaction reads the rows committed before it began
+        session_a.execute_query("begin").await?;
+        session_b.execute_query("insert into t1 values (2, 'b')").await?;
+        session_b.execute_query("update t1 set name = 'x' where id = 1").await?;
+        let result = session_a.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        session_a.execute_query("commit").await?;
+
+        let result = session_a.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | x    |",
+            "| 2  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the row committed by another transaction after the snapshot can not be written
+        session_a.execute_query("begin").await?;
+        session_a.execute_query("update t1 set name = 'y' where id = 2").await?;
+        session_b.execute_query("update t1 set name = 'z' where id = 2").await?;
+        let result = session_a.execute_query("commit").await;
+        match result {
+            Ok(_) => panic!("the commit must conflict"),
+            Err(mysql_error) => assert_eq!(1213, mysql_error.error_number()),
+        }
+        assert!(!session_a.in_transaction());
+
+        // without a snapshot only the last version of the rows is kept
+        let sled_mvcc = session_a.global_context().lock().unwrap().engine.sled_mvcc.clone().unwrap();
+        assert!(sled_mvcc.collect_garbage()? > 0);
+        assert_eq!(0, sled_mvcc.collect_garbage()?);
+
+        let result = session_b.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | x    |",
+            "| 2  | z    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the keys checked by the statements of the transaction are read at its snapshot
+        session_a.execute_query("begin").await?;
+        session_b.execute_query("delete from t1 where id = 1").await?;
+        let result = session_a.execute_query("insert into t1 values (1, 'q')").await;
+        assert_eq!(1062, result.unwrap_err().error_number());
+        session_a.execute_query("rollback").await?;
+        session_a.execute_query("insert into t1 values (1, 'q')").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn row_locking() -> MysqlResult<()> {
+        let mut session_a = create_execution().await?;
+        session_a.execute_query("create schema test").await?;
+        session_a.set_default_schema("test").await?;
+        session_a.execute_query("create table t1 (id int, name char, PRIMARY KEY(id))").await?;
+        session_a.execute_query("insert into t1 values (1, 'a'), (2, 'b')").await?;
+
+        let mut session_b = Execution::new(session_a.global_context());
+        session_b.try_init()?;
+        session_b.set_default_schema("test").await?;
+        session_b.execute_query("set innodb_lock_wait_timeout = 1").await?;
+
+        // the row read by FOR UPDATE is locked until the transaction ends, the other rows are not
+        session_a.execute_query("begin").await?;
+        let result = session_a.execute_query("select id, name from t1 where id = 1 for update").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        let result = session_b.execute_query("update t1 set name = 'x' where id = 1").await;
+        match result {
+            Ok(_) => panic!("the update must wait for the lock"),
+            Err(mysql_error) => assert_eq!(1205, mysql_error.error_number()),
+        }
+        session_b.execute_query("update t1 set name = 'y' where id = 2").await?;
+        session_a.execute_query("commit").await?;
+        session_b.execute_query("update t1 set name = 'x' where id = 1").await?;
+
+        // the second transaction closing the cycle of waits is rolled back, the first one gets the lock
+        session_a.execute_query("begin").await?;
+        session_a.execute_query("update t1 set name = 'c' where id = 1").await?;
+        session_b.execute_query("begin").await?;
+        session_b.execute_query("delete from t1 where id = 2").await?;
+        let (result_a, result_b) = tokio::join!(session_a.execute_query("update t1 set name = 'd' where id = 2"), async {
+            // the first transaction is waiting when the second one asks for the lock
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            session_b.execute_query("select id from t1 where id = 1 lock in share mode").await
+        });
+        result_a?;
+        match result_b {
+            Ok(_) => panic!("the transaction must be the deadlock victim"),
+            Err(mysql_error) => assert_eq!(1213, mysql_error.error_number()),
+        }
+        assert!(!session_b.in_transaction());
+        session_a.execute_query("commit").await?;
+
+        let result = session_b.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | c    |",
+            "| 2  | d    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn index_maintenance() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id), UNIQUE (name))")
+            .await?;
+        core_execution.execute_query("insert into user values (1, 'a'), (2, 'b')").await?;
+
+        // the values of the deleted and the updated rows are free again
+        core_execution.execute_query("delete from user where id = 1").await?;
+        core_execution.execute_query("update user set name = 'c' where id = 2").await?;
+        core_execution.execute_query("insert into user values (3, 'a'), (4, 'b')").await?;
+        let result = core_execution.execute_query("insert into user values (5, 'c')").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the updated value must be indexed"),
+        }
+
+        // the primary key and the unique entry of each of the rows
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        let index_keys = store_engine.scan_prefix_keys(util::dbkey::scan_table_index_key(&table_def.option), None, 100)?;
+        assert_eq!(6, index_keys.len());
+
+        let result = core_execution.execute_query("select id, name from user where name = 'b'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 4  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn drop_index() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution.execute_query("insert into user values (1, 'a'), (2, 'b')").await?;
+        core_execution.execute_query("create unique index idx_name on user (name)").await?;
+        let result = core_execution.execute_query("insert into user values (3, 'a')").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the index is unique"),
+        }
+
+        // the entries of the index are deleted with it, the values are not unique anymore
+        core_execution.execute_query("drop index idx_name on user").await?;
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        assert!(!table_def.get_constraints().iter().any(|table_constraint| meta_util::constraint_name(table_constraint) == Some("idx_name".to_string())));
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        let index_prefix = format!("{}idx_name/", util::dbkey::scan_table_index_key(&table_def.option));
+        assert!(store_engine.scan_prefix_keys(index_prefix, None, 10)?.is_empty());
+        core_execution.execute_query("insert into user values (3, 'a')").await?;
+
+        let result = core_execution.execute_query("select id from user where name = 'a' order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 1  |",
+            "| 3  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the index can be built again, ALTER TABLE drops it too
+        let result = core_execution.execute_query("create unique index idx_name on user (name)").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the values are not unique"),
+        }
+        core_execution.execute_query("create unique index idx_name_id on user (name, id)").await?;
+        core_execution.execute_query("alter table user drop index idx_name_id").await?;
+        let result = core_execution.execute_query("drop index idx_name_id on user").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1091, mysql_error.error_number()),
+            Ok(_) => panic!("the index is dropped already"),
+        }
+        let result = core_execution.execute_query("drop index primary on user").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1235, mysql_error.error_number()),
+            Ok(_) => panic!("the primary key can not be dropped"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unique_constraint() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution.execute_query("insert into user values (1, 'a'), (2, 'b')").await?;
+        core_execution.execute_query("create unique index idx_name on user (name)").await?;
+
+        // the values of the conflicting key are in the error, none of the rows is written
+        let duplicates = vec![
+            ("insert into user values (1, 'c')", "Duplicate entry '1' for key 'user.PRIMARY'"),
+            ("insert into user values (3, 'c'), (4, 'c')", "Duplicate entry 'c' for key 'user.idx_name'"),
+            ("update user set name = 'a' where id = 2", "Duplicate entry 'a' for key 'user.idx_name'"),
+            ("update user set id = 1 where id = 2", "Duplicate entry '1' for key 'user.PRIMARY'"),
+            ("update user set name = 'c'", "Duplicate entry 'c' for key 'user.idx_name'"),
+        ];
+        for (sql, message) in duplicates {
+            match core_execution.execute_query(sql).await {
+                Err(mysql_error) => {
+                    assert_eq!(1062, mysql_error.error_number());
+                    assert_eq!("23000", mysql_error.sql_state());
+                    assert_eq!(message, mysql_error.message());
+                }
+                Ok(_) => panic!("{} must be a duplicate", sql),
+            }
+        }
+
+        // the value left by the updated row is free again
+        core_execution.execute_query("update user set name = 'c' where id = 2").await?;
+        core_execution.execute_query("insert into user values (3, 'b')").await?;
+
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        let index_keys = store_engine.scan_prefix_keys(util::dbkey::scan_table_index_key(&table_def.option), None, 100)?;
+        assert_eq!(6, index_keys.len());
+
+        let result = core_execution.execute_query("select id, name from user order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | c    |",
+            "| 3  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn information_schema_auto_increment() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int AUTO_INCREMENT, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution.execute_query("create table item (id int, PRIMARY KEY(id))").await?;
+        core_execution.execute_query("insert into user (name) values ('a'), ('b')").await?;
+        core_execution.execute_query("insert into user values (10, 'c')").await?;
+
+        // the next value of the table, NULL for the table without an auto increment column
+        let result = core_execution
+            .execute_query("select table_name, auto_increment from information_schema.tables where table_schema = 'test' order by table_name")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------------+----------------+",
+            "| table_name | auto_increment |",
+            "+------------+----------------+",
+            "| item       |                |",
+            "| user       | 11             |",
+            "+------------+----------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upsert() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, cnt int, PRIMARY KEY(id))")
+            .await?;
+        core_execution.execute_query("create unique index idx_name on user (name)").await?;
+        core_execution.execute_query("insert into user values (1, 'a', 0), (2, 'b', 0)").await?;
+
+        // one affected row for the inserted row and one more for every row deleted, two for the updated row
+        let statements = vec![
+            ("replace into user values (1, 'c', 5)", 2, "Records: 1  Duplicates: 1  Warnings: 0"),
+            ("replace into user (id, name, cnt) values (3, 'b', 0)", 2, "Records: 1  Duplicates: 1  Warnings: 0"),
+            ("replace into user values (1, 'b', 7)", 3, "Records: 1  Duplicates: 1  Warnings: 0"),
+            (
+                "insert into user values (1, 'x', 0) on duplicate key update cnt = cnt + 1, name = values(name)",
+                2,
+                "Records: 1  Duplicates: 1  Warnings: 0",
+            ),
+            ("insert into user values (1, 'y', 0) on duplicate key update name = 'x'", 0, "Records: 1  Duplicates: 1  Warnings: 0"),
+            (
+                "insert into user values (4, 'd', 0), (4, 'e', 0) on duplicate key update cnt = user.cnt + 10",
+                3,
+                "Records: 2  Duplicates: 1  Warnings: 0",
+            ),
+        ];
+        for (sql, affect_rows, message) in statements {
+            match core_execution.execute_query(sql).await? {
+                CoreOutput::FinalCount(f) => {
+                    assert_eq!(affect_rows, f.affect_rows, "{}", sql);
+                    assert_eq!(message, f.message, "{}", sql);
+                }
+                _ => panic!("{} must return the affected rows", sql),
+            }
+        }
+
+        let result = core_execution.execute_query("select id, name, cnt from user order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+-----+",
+            "| id | name | cnt |",
+            "+----+------+-----+",
+            "| 1  | x    | 8   |",
+            "| 4  | d    | 10  |",
+            "+----+------+-----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the updated row must not take the key of another row
+        core_execution.execute_query("insert into user values (5, 'e', 0)").await?;
+        match core_execution.execute_query("insert into user values (5, 'f', 0) on duplicate key update name = 'x'").await {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the assignment must be a duplicate"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn insert_select() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution.execute_query("insert into user values (1, 'a'), (2, 'b'), (3, 'c')").await?;
+        core_execution
+            .execute_query("create table user2 (id int, name char, PRIMARY KEY(id))")
+            .await?;
+
+        let statements = vec![
+            ("insert into user2 select id, name from user where id > 1", 2),
+            ("insert into user2 (name, id) select name, id + 10 from user", 3),
+            ("create table user3 as select id, name from user where id < 3", 2),
+        ];
+        for (sql, affect_rows) in statements {
+            match core_execution.execute_query(sql).await? {
+                CoreOutput::FinalCount(f) => assert_eq!(affect_rows, f.affect_rows, "{}", sql),
+                _ => panic!("{} must return the affected rows", sql),
+            }
+        }
+
+        let result = core_execution.execute_query("select id, name from user2 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "| 11 | a    |",
+            "| 12 | b    |",
+            "| 13 | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the table created from the query has the columns of its output
+        let result = core_execution.execute_query("select id, name from user3 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // a duplicated row fails the statement, the table created by it is dropped
+        match core_execution.execute_query("insert into user2 select id, name from user").await {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the selected rows must be duplicates"),
+        }
+        match core_execution.execute_query("create table user4 (name char, PRIMARY KEY(name)) as select name from user2").await {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("the selected rows must be duplicates"),
+        }
+        assert!(core_execution.execute_query("select name from user4").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_expression() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, cnt int, PRIMARY KEY(id))")
+            .await?;
+        core_execution.execute_query("insert into user values (1, 'a', 0), (2, 'b', 5), (3, 'c', NULL)").await?;
+
+        // the values are evaluated against the columns of every row
+        let statements = vec![
+            ("update user set cnt = cnt + 1, name = concat(name, 'x') where id > 1", "Rows matched: 2  Changed: 2  Warnings: 0"),
+            ("update user set cnt = id * 10 where id = 1", "Rows matched: 1  Changed: 1  Warnings: 0"),
+            ("update user set name = NULL where id = 2", "Rows matched: 1  Changed: 1  Warnings: 0"),
+            ("update user set cnt = cnt where id < 3", "Rows matched: 2  Changed: 0  Warnings: 0"),
+        ];
+        for (sql, message) in statements {
+            match core_execution.execute_query(sql).await? {
+                CoreOutput::FinalCount(f) => assert_eq!(message, f.message, "{}", sql),
+                _ => panic!("{} must return the affected rows", sql),
+            }
+        }
+
+        let result = core_execution.execute_query("select id, name, cnt from user order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+-----+",
+            "| id | name | cnt |",
+            "+----+------+-----+",
+            "| 1  | a    | 10  |",
+            "| 2  |      | 6   |",
+            "| 3  | cx   |     |",
+            "+----+------+-----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        match core_execution.execute_query("update user set age = 1").await {
+            Err(mysql_error) => assert_eq!(1054, mysql_error.error_number()),
+            Ok(_) => panic!("the column must not exist"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_limit_and_truncate() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table item (id int auto_increment primary key, name char)")
+            .await?;
+        core_execution
+            .execute_query("insert into item (name) values ('e'), ('d'), ('c'), ('b'), ('a')")
+            .await?;
+
+        // the first rows in the order are deleted
+        let statements = vec![
+            ("delete from item order by name limit 2", 2),
+            ("delete from item where id > 1 order by id desc limit 1", 1),
+            ("delete from item where id < 2 limit 5", 1),
+        ];
+        for (sql, affect_rows) in statements {
+            match core_execution.execute_query(sql).await? {
+                CoreOutput::FinalCount(f) => assert_eq!(affect_rows, f.affect_rows, "{}", sql),
+                _ => panic!("{} must return the affected rows", sql),
+            }
+        }
+
+        let result = core_execution.execute_query("select id, name from item").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | d    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the rows and their index entries are gone, the auto increment value starts again
+        core_execution.execute_query("truncate table item").await?;
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "item");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        let index_keys = store_engine.scan_prefix_keys(util::dbkey::scan_table_index_key(&table_def.option), None, 100)?;
+        assert_eq!(0, index_keys.len());
+
+        core_execution.execute_query("insert into item (name) values ('f')").await?;
+        let result = core_execution.execute_query("select id, name from item").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | f    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn modify_and_change_column() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table score (id int primary key, name char, score char)")
+            .await?;
+        core_execution.execute_query("create unique index idx_score on score (score)").await?;
+        core_execution
+            .execute_query("insert into score (id, name, score) values (1, 'a', '1.4'), (2, 'b', '2.6'), (3, 'c', null)")
+            .await?;
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "score");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        let store_id = table_def.get_table_column().get_sparrow_column("score".to_ident()).unwrap().store_id;
+
+        // the stored values are cast to the new type
+        match core_execution.execute_query("alter table score modify column score decimal(10,2) null").await? {
+            CoreOutput::FinalCount(f) => assert_eq!(3, f.affect_rows),
+            _ => panic!("alter table must return the affected rows"),
+        }
+        let result = core_execution.execute_query("select id, score from score order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------+",
+            "| id | score |",
+            "+----+-------+",
+            "| 1  | 1.40  |",
+            "| 2  | 2.60  |",
+            "| 3  |       |",
+            "+----+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the renamed column keeps its store id, its index entries move with the rounded values
+        core_execution.execute_query("alter table score change score points int null").await?;
+        let serial_number = core_execution
+            .global_context()
+            .lock()
+            .unwrap()
+            .meta_data
+            .get_serial_number_map(full_table_name)
+            .unwrap()
+            .get(&"points".to_ident())
+            .cloned();
+        assert_eq!(Some(store_id as usize), serial_number);
+        let result = core_execution.execute_query("select id, points from score where points = 3").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+--------+",
+            "| id | points |",
+            "+----+--------+",
+            "| 2  | 3      |",
+            "+----+--------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        let result = core_execution.execute_query("insert into score (id, name, points) values (4, 'd', 1)").await;
+        assert_eq!(1062, result.unwrap_err().error_number());
+
+        // nothing changes when a value can not be cast
+        let result = core_execution.execute_query("alter table score modify name int").await;
+        assert_eq!(1366, result.unwrap_err().error_number());
+        let result = core_execution.execute_query("alter table score change name points char").await;
+        assert_eq!(1060, result.unwrap_err().error_number());
+        let result = core_execution.execute_query("select name from score where id = 1").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+",
+            "| name |",
+            "+------+",
+            "| a    |",
+            "+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_column_position_and_default() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution.execute_query("create table member (id int, name char)").await?;
+        core_execution
+            .execute_query("insert into member (id, name) values (1, 'a'), (2, 'b')")
+            .await?;
+
+        // the rows written before the column have its default
+        core_execution
+            .execute_query("alter table member add column age int default 18 after id")
+            .await?;
+        core_execution.execute_query("alter table member add code char first").await?;
+        core_execution.execute_query("alter table member add column level char default 'gold'").await?;
+        core_execution
+            .execute_query("insert into member (id, name, age) values (3, 'c', 20)")
+            .await?;
+
+        let result = core_execution.execute_query("select * from member order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+----+-----+------+-------+",
+            "| code | id | age | name | level |",
+            "+------+----+-----+------+-------+",
+            "|      | 1  | 18  | a    | gold  |",
+            "|      | 2  | 18  | b    | gold  |",
+            "|      | 3  | 20  | c    | gold  |",
+            "+------+----+-----+------+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("alter table member add column score int after grade").await;
+        assert_eq!(1054, result.unwrap_err().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rename_table() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.execute_query("create schema archive").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table account (id int auto_increment primary key, name char, CONSTRAINT uk_name UNIQUE (name))")
+            .await?;
+        core_execution.execute_query("insert into account (name) values ('a'), ('b')").await?;
+        core_execution.execute_query("create table account_log (id int, message char)").await?;
+
+        // the rows, their index entries and the auto increment value move with the table
+        core_execution.execute_query("rename table account to member").await?;
+        let result = core_execution.execute_query("select * from account").await;
+        assert_eq!(1146, result.unwrap_err().error_number());
+        core_execution.execute_query("insert into member (name) values ('c')").await?;
+        let result = core_execution.execute_query("insert into member (name) values ('a')").await;
+        assert_eq!(1062, result.unwrap_err().error_number());
+        let result = core_execution.execute_query("select id, name from member where name = 'c'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("rename table member to account_log").await;
+        assert_eq!(1050, result.unwrap_err().error_number());
+        let result = core_execution.execute_query("alter table member rename to missing.member").await;
+        assert_eq!(1049, result.unwrap_err().error_number());
+
+        // the tables swap their names through a temporary one, then the table moves to another schema
+        core_execution
+            .execute_query("rename table member to tmp, account_log to member, tmp to account_log")
+            .await?;
+        core_execution.execute_query("alter table account_log rename as archive.account").await?;
+        let result = core_execution.execute_query("select count(*) from member").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----------------+",
+            "| COUNT(UInt8(1)) |",
+            "+-----------------+",
+            "| 0               |",
+            "+-----------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        let result = core_execution
+            .execute_query("select table_name, constraint_name from information_schema.table_constraints where table_schema = 'archive' order by constraint_name")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------------+-----------------+",
+            "| table_name | constraint_name |",
+            "+------------+-----------------+",
+            "| account    | PRIMARY         |",
+            "| account    | uk_name         |",
+            "+------------+-----------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        let result = core_execution.execute_query("select id, name from archive.account order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn migrate_table_id_after_restart() -> MysqlResult<()> {
+        let test_id = Uuid::new_v4()
+            .to_simple()
+            .encode_lower(&mut Uuid::encode_buffer())
+            .to_string();
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int AUTO_INCREMENT, name char, PRIMARY KEY(id), UNIQUE KEY uk_name(name))")
+            .await?;
+        core_execution
+            .execute_query("insert into user (name) values ('a'), ('b')")
+            .await?;
+
+        // the table is keyed by its full table name as the tables created before the table ids
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        assert!(table_def.option.table_id > 0);
+        let mut legacy_table_option = table_def.option.clone();
+        legacy_table_option.with_table_id(0);
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        meta_util::move_table_keys(store_engine.as_ref(), &table_def.option, &legacy_table_option)?;
+        initial::save_information_schema_table_id(core_execution.global_context(), &table_def.option, 0)?;
+        drop(store_engine);
+        drop(core_execution);
+
+        my_config.server.migrate_table_id = true;
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.set_default_schema("test").await?;
+
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name)?;
+        assert!(table_def.option.table_id > 0);
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        assert!(store_engine.scan_prefix_keys(util::dbkey::scan_record_rowid(&legacy_table_option), None, 1)?.is_empty());
+        drop(store_engine);
+
+        core_execution
+            .execute_query("insert into user (name) values ('c')")
+            .await?;
+        let error_number = core_execution
+            .execute_query("insert into user (name) values ('a')")
+            .await
+            .unwrap_err()
+            .error_number();
+        assert_eq!(1062, error_number);
+
+        let result = core_execution.execute_query("select id, name from user where name = 'b' or id > 2 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn migrate_table_id_after_interrupted_move() -> MysqlResult<()> {
+        let test_id = Uuid::new_v4()
+            .to_simple()
+            .encode_lower(&mut Uuid::encode_buffer())
+            .to_string();
+        let mut my_config = MyConfig::default();
+        my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
+
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table user (id int, name char, PRIMARY KEY(id))")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name) values (1, 'a'), (2, 'b'), (3, 'c')")
+            .await?;
+
+        let full_table_name = meta_util::create_full_table_name(meta_const::CATALOG_NAME, "test", "user");
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name.clone())?;
+        let mut legacy_table_option = table_def.option.clone();
+        legacy_table_option.with_table_id(0);
+        let store_engine = StoreEngineFactory::try_new_with_table(core_execution.global_context(), table_def.clone())?;
+        meta_util::move_table_keys(store_engine.as_ref(), &table_def.option, &legacy_table_option)?;
+        initial::save_information_schema_table_id(core_execution.global_context(), &table_def.option, 0)?;
+
+        // the migration stops after the id is saved and the first row is moved
+        let table_id = meta_util::next_table_id(core_execution.global_context())?;
+        let schema_engine = StoreEngineFactory::try_new_schema_engine(core_execution.global_context())?;
+        schema_engine.put_key(util::dbkey::create_migrating_table_id(&legacy_table_option), table_id.to_string().as_bytes())?;
+        let mut migrated_table_option = legacy_table_option.clone();
+        migrated_table_option.with_table_id(table_id);
+        let prefix = util::dbkey::scan_record_rowid(&legacy_table_option);
+        let new_prefix = util::dbkey::scan_record_rowid(&migrated_table_option);
+        for key in store_engine.scan_prefix_keys(prefix.clone(), None, 1)? {
+            let value = store_engine.get_key(key.clone())?;
+            store_engine.write_keys(vec![(format!("{}{}", new_prefix, &key[prefix.len()..]), value), (key, None)])?;
+        }
+        drop(schema_engine);
+        drop(store_engine);
+        drop(core_execution);
+
+        // the restart resumes the migration with the same id, the row moved before is not lost
+        my_config.server.migrate_table_id = true;
+        let mut core_execution = open_execution(my_config.clone()).await?;
+        core_execution.set_default_schema("test").await?;
+
+        let table_def = meta_util::get_table(core_execution.global_context(), full_table_name)?;
+        assert_eq!(table_id, table_def.option.table_id);
+        let schema_engine = StoreEngineFactory::try_new_schema_engine(core_execution.global_context())?;
+        assert_eq!(None, schema_engine.get_key(util::dbkey::create_migrating_table_id(&legacy_table_option))?);
+        assert!(schema_engine.scan_prefix_keys(prefix, None, 1)?.is_empty());
+        drop(schema_engine);
+
+        let result = core_execution.execute_query("select id, name from user order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn numeric_index_range() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table item (id int, price float, name char, PRIMARY KEY(id), UNIQUE KEY uk_price(price))")
+            .await?;
+        core_execution
+            .execute_query("insert into item (id, price, name) values (-5, -2.5, 'a'), (9, 0.5, 'b'), (10, 10, 'c'), (100, 9.75, 'd')")
+            .await?;
+
+        // 10 is after 9 and 100 after 10 in the index keys, as numbers and not as text
+        let result = core_execution
+            .execute_query("explain format=tree select name from item where id between 9 and 100")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let tree = as_string_array(results[0].column(0)).value(0).to_string();
+        assert!(tree.contains("seek=index range scan on PRIMARY"));
+
+        let result = core_execution
+            .execute_query("select id, name from item where id between 9 and 100 order by id")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----+------+",
+            "| id  | name |",
+            "+-----+------+",
+            "| 9   | b    |",
+            "| 10  | c    |",
+            "| 100 | d    |",
+            "+-----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the negative floats are before the positive ones
+        let result = core_execution
+            .execute_query("explain format=tree select name from item where price > 0.25")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let tree = as_string_array(results[0].column(0)).value(0).to_string();
+        assert!(tree.contains("seek=index range scan on uk_price"));
+
+        let result = core_execution
+            .execute_query("select id, name from item where price > -3.5 and price <= 9.75 order by id")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----+------+",
+            "| id  | name |",
+            "+-----+------+",
+            "| -5  | a    |",
+            "| 9   | b    |",
+            "| 100 | d    |",
+            "+-----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the unique key of the float is checked on the encoded value
+        let error_number = core_execution
+            .execute_query("insert into item (id, price, name) values (11, 10.0, 'e')")
+            .await
+            .unwrap_err()
+            .error_number();
+        assert_eq!(1062, error_number);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn temporal_column_types() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table moment (id int primary key, day date, clock time, happened datetime, created timestamp)")
+            .await?;
+        core_execution.execute_query("set time_zone = '+00:00'").await?;
+        core_execution
+            .execute_query("insert into moment (id, day, clock, happened, created) values (1, '2024-01-31 10:00:00', '10:20:30', '2024-01-31 10:00:00', '2024-01-31 10:00:00')")
+            .await?;
+        core_execution
+            .execute_query("insert into moment (id, day, clock, happened, created) values (2, '2024-02-01', '08:05', '2024-02-01 08:05:00', '2024-02-01 08:05:00')")
+            .await?;
+
+        // the DATE keeps the day, the TIME keeps the seconds
+        let result = core_execution.execute_query("select id, day, clock from moment where day >= '2024-02-01'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------------+----------+",
+            "| id | day        | clock    |",
+            "+----+------------+----------+",
+            "| 2  | 2024-02-01 | 08:05:00 |",
+            "+----+------------+----------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the DATETIME is compared with the text as a timestamp
+        let result = core_execution.execute_query("select id from moment where happened > '2024-01-31 12:00:00'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the temporal columns are sent with their mysql type, the TIMESTAMP too in the session time zone
+        let result = core_execution.execute_query("select day, clock as c, happened, created from moment").await?;
+        let result_set = match result {
+            CoreOutput::ResultSet(result_set) => result_set,
+            _ => panic!("the select has no result set"),
+        };
+        // the DATE is the date of arrow and the DATETIME the timestamp, the TIMESTAMP is the text in the session time zone
+        let data_types = result_set
+            .schema_ref
+            .fields()
+            .iter()
+            .map(|field| field.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![DataType::Date32, DataType::Utf8, DataType::Timestamp(TimeUnit::Microsecond, None), DataType::Utf8],
+            data_types
+        );
+        let column_types = result_set
+            .schema_ref
+            .fields()
+            .iter()
+            .map(|field| metadata::Column::from(field).column_type())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                metadata::MysqlType::MYSQL_TYPE_DATE,
+                metadata::MysqlType::MYSQL_TYPE_TIME,
+                metadata::MysqlType::MYSQL_TYPE_DATETIME,
+                metadata::MysqlType::MYSQL_TYPE_TIMESTAMP,
+            ],
+            column_types
+        );
+
+        // the binary DATE is the year, the month and the day, the binary TIME has no microseconds
+        let payload = message::binary_row_message(
+            vec![
+                ScalarValue::Date32(Some(19753)),
+                ScalarValue::Utf8(Some("10:20:30".to_string())),
+                util::date_function::text_to_temporal_value("2024-01-31 10:00:00", &DataType::Timestamp(TimeUnit::Microsecond, None)).unwrap(),
+            ],
+            &column_types,
+        );
+        assert_eq!(
+            b"\x00\x00\x04\xe8\x07\x01\x1f\x08\x00\x00\x00\x00\x00\x0a\x14\x1e\x07\xe8\x07\x01\x1f\x0a\x00\x00".to_vec(),
+            payload.bytes
+        );
+
+        let result = core_execution
+            .execute_query("insert into moment (id, day) values (3, '2024-02-30')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1292, mysql_error.error_number()),
+            Ok(_) => panic!("the DATE is not valid"),
+        }
+        let result = core_execution
+            .execute_query("insert into moment (id, clock) values (3, '25:00:00')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1292, mysql_error.error_number()),
+            Ok(_) => panic!("the TIME is not valid"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn decimal_index_key() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table account (id int primary key, balance decimal(10,2), unique key uk_balance (balance))")
+            .await?;
+        core_execution
+            .execute_query("insert into account (id, balance) values (1, 12.5), (2, -3.25), (3, '0'), (4, 100)")
+            .await?;
+
+        // 12.50 is the key of 12.5
+        let result = core_execution
+            .execute_query("insert into account (id, balance) values (5, '12.50')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("12.50 should be a duplicate of 12.5"),
+        }
+
+        let result = core_execution.execute_query("select id, balance from account order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+---------+",
+            "| id | balance |",
+            "+----+---------+",
+            "| 1  | 12.50   |",
+            "| 2  | -3.25   |",
+            "| 3  | 0.00    |",
+            "| 4  | 100.00  |",
+            "+----+---------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the binary row sends the decimal as its text
+        let rows = core_util::convert_record_to_scalar_value(results[0].clone());
+        let column_types = [metadata::MysqlType::MYSQL_TYPE_LONGLONG, metadata::MysqlType::MYSQL_TYPE_NEWDECIMAL];
+        let payload = message::binary_row_message(rows[1].clone(), &column_types);
+        assert_eq!(b"\x00\x00\x02\x00\x00\x00\x00\x00\x00\x00\x05-3.25".to_vec(), payload.bytes);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn binary_column() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table file (id int primary key, data varbinary(16), body blob, unique key uk_data (data))")
+            .await?;
+        core_execution
+            .execute_query("insert into file (id, data, body) values (1, X'00FF61', 'abc'), (2, null, X'0A')")
+            .await?;
+
+        // the key of the hex literal is its bytes
+        let result = core_execution
+            .execute_query("insert into file (id, data) values (3, X'00ff61')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1062, mysql_error.error_number()),
+            Ok(_) => panic!("X'00ff61' should be a duplicate of X'00FF61'"),
+        }
+
+        // the hex literal must have the pairs of the hex digits
+        let result = core_execution
+            .execute_query("insert into file (id, data) values (3, X'0G')")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1064, mysql_error.error_number()),
+            Ok(_) => panic!("X'0G' should not be a hex literal"),
+        }
+
+        core_execution.execute_query("update file set data = X'0000' where id = 2").await?;
+
+        let result = core_execution.execute_query("select id, data, body from file order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+
+        // the bytes are read back as they were written, the zero and the 0xFF bytes as well
+        let rows = core_util::convert_record_to_scalar_value(results[0].clone());
+        assert_eq!(
+            vec![
+                ScalarValue::Int64(Some(1)),
+                ScalarValue::Binary(Some(vec![0x00, 0xFF, 0x61])),
+                ScalarValue::Binary(Some(b"abc".to_vec())),
+            ],
+            rows[0]
+        );
+        assert_eq!(
+            vec![
+                ScalarValue::Int64(Some(2)),
+                ScalarValue::Binary(Some(vec![0x00, 0x00])),
+                ScalarValue::Binary(Some(vec![0x0A])),
+            ],
+            rows[1]
+        );
+
+        let payload = message::row_message(rows[0].clone());
+        assert_eq!(b"\x011\x03\x00\xFFa\x03abc".to_vec(), payload.bytes);
+
+        let column = metadata::Column::from(results[0].schema().field(1));
+        assert_eq!(metadata::CHARACTER_SET_BINARY, column.character_set());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn numeric_result_types() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+        core_execution
+            .execute_query("create table metric (id int primary key, active boolean, ratio float, total double)")
+            .await?;
+        core_execution
+            .execute_query("insert into metric (id, active, ratio, total) values (1, 1, 1.5, 2.25), (2, 0, null, -0.5)")
+            .await?;
+
+        let result = core_execution
+            .execute_query("select id, active, ratio, total, total > 0 as positive, cast(id as smallint) as small, cast(ratio as float) as single from metric order by id")
+            .await?;
+        let result_set = match result {
+            CoreOutput::ResultSet(result_set) => result_set,
+            _ => panic!("the select has no result set"),
+        };
+        let column_types = result_set
+            .schema_ref
+            .fields()
+            .iter()
+            .map(|field| metadata::Column::from(field).column_type())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                metadata::MysqlType::MYSQL_TYPE_LONGLONG,
+                metadata::MysqlType::MYSQL_TYPE_TINY,
+                metadata::MysqlType::MYSQL_TYPE_DOUBLE,
+                metadata::MysqlType::MYSQL_TYPE_DOUBLE,
+                metadata::MysqlType::MYSQL_TYPE_TINY,
+                metadata::MysqlType::MYSQL_TYPE_SHORT,
+                metadata::MysqlType::MYSQL_TYPE_FLOAT,
+            ],
+            column_types
+        );
+
+        let rows = core_util::convert_record_to_scalar_value(result_set.record_batches[0].clone());
+        assert_eq!(
+            vec![
+                ScalarValue::Int64(Some(2)),
+                ScalarValue::Int8(Some(0)),
+                ScalarValue::Float64(None),
+                ScalarValue::Float64(Some(-0.5)),
+                ScalarValue::Boolean(Some(false)),
+                ScalarValue::Int16(Some(2)),
+                ScalarValue::Float32(None),
+            ],
+            rows[1]
+        );
+
+        // the boolean is sent as 1 or 0
+        let payload = message::row_message(rows[0].clone());
+        assert_eq!(b"\x011\x011\x031.5\x042.25\x011\x011\x031.5".to_vec(), payload.bytes);
+
+        // the binary DOUBLE is the little endian IEEE 754 value
+        let payload = message::binary_row_message(rows[1].clone(), &column_types);
+        assert_eq!(
+            b"\x00\x10\x01\x02\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\xe0\xbf\x00\x02\x00".to_vec(),
+            payload.bytes
+        );
+
+        // the unsigned columns are sent with the UNSIGNED flag
+        let schema = Arc::new(Schema::new(vec![Field::new("count", DataType::UInt32, true)]));
+        let record_batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(arrow::array::UInt32Array::from(vec![Some(7), None]))],
+        )
+        .unwrap();
+        let rows = core_util::convert_record_to_scalar_value(record_batch);
+        assert_eq!(vec![vec![ScalarValue::UInt32(Some(7))], vec![ScalarValue::UInt32(None)]], rows);
+        let column = metadata::Column::from(schema.field(0));
+        assert!(column.flags().contains(metadata::ColumnFlags::UNSIGNED_FLAG));
+        assert_eq!(b"\x017".to_vec(), message::row_message(rows[0].clone()).bytes);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn character_set_and_collation() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+
+        let result = core_execution.execute_query("show collation like 'utf8mb4%'").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+--------------------+---------+-----+---------+----------+---------+---------------+",
+            "| Collation          | Charset | Id  | Default | Compiled | Sortlen | Pad_attribute |",
+            "+--------------------+---------+-----+---------+----------+---------+---------------+",
+            "| utf8mb4_0900_ai_ci | utf8mb4 | 255 | Yes     | Yes      | 0       | NO PAD        |",
+            "| utf8mb4_bin        | utf8mb4 | 46  |         | Yes      | 1       | PAD SPACE     |",
+            "| utf8mb4_general_ci | utf8mb4 | 45  |         | Yes      | 1       | PAD SPACE     |",
+            "| utf8mb4_unicode_ci | utf8mb4 | 224 |         | Yes      | 8       | PAD SPACE     |",
+            "+--------------------+---------+-----+---------+----------+---------+---------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("show character set").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---------+-----------------------+--------------------+--------+",
+            "| Charset | Description           | Default collation  | Maxlen |",
+            "+---------+-----------------------+--------------------+--------+",
+            "| binary  | Binary pseudo charset | binary             | 1      |",
+            "| utf8    | UTF-8 Unicode         | utf8_general_ci    | 3      |",
+            "| utf8mb4 | UTF-8 Unicode         | utf8mb4_0900_ai_ci | 4      |",
+            "+---------+-----------------------+--------------------+--------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the text columns without a character set or a collation have the default collation of the table
+        core_execution
+            .execute_query("create table user (id int, name char character set utf8, code char collate utf8mb4_bin, tag char) default charset=utf8mb4 collate=utf8mb4_general_ci")
+            .await?;
+        core_execution
+            .execute_query("insert into user (id, name, code, tag) values (1, 'Apple', 'Apple', 'Apple  '), (2, 'apple', 'apple', 'apple')")
+            .await?;
+
+        let result = core_execution.execute_query("select id, name, code, tag from user").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let schema = results[0].schema();
+        let character_sets: Vec<u8> = schema.fields().iter().map(|field| metadata::Column::from(field).character_set()).collect();
+        assert_eq!(vec![metadata::CHARACTER_SET_BINARY, 33, 46, 45], character_sets);
+
+        // the PAD SPACE collation ignores the trailing spaces, the NO PAD one does not
+        let result = core_execution
+            .execute_query("select id from user where tag = 'APPLE' collate utf8mb4_general_ci order by id")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 1  |",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select id from user where tag = 'APPLE' collate utf8mb4_0900_ai_ci order by id")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the tables without a character set have the collation of their schema
+        core_execution
+            .execute_query("create database shop default character set utf8")
+            .await?;
+        core_execution.execute_query("create table shop.item (name char)").await?;
+        core_execution.execute_query("insert into shop.item (name) values ('a')").await?;
+        let result = core_execution.execute_query("select name from shop.item").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        assert_eq!(33, metadata::Column::from(results[0].schema().field(0)).character_set());
+
+        let result = core_execution
+            .execute_query("create table other (name char character set latin1)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1115, mysql_error.error_number()),
+            Ok(_) => panic!("latin1 should be an unknown character set"),
+        }
+        let result = core_execution
+            .execute_query("create table other (name char character set utf8 collate utf8mb4_bin)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1253, mysql_error.error_number()),
+            Ok(_) => panic!("utf8mb4_bin should not be a collation of utf8"),
+        }
+        let result = core_execution
+            .execute_query("create table other (name char collate utf8mb4_unknown_ci)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1273, mysql_error.error_number()),
+            Ok(_) => panic!("utf8mb4_unknown_ci should be an unknown collation"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn foreign_key_constraints() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+
+        core_execution.execute_query("create table parent (id int primary key, name char)").await?;
+        core_execution
+            .execute_query("create table child (id int primary key, parent_id int, foreign key (parent_id) references parent (id) on delete cascade)")
+            .await?;
+        core_execution
+            .execute_query("create table note (id int primary key, parent_id int, constraint fk_note foreign key (parent_id) references parent (id) on delete set null)")
+            .await?;
+        core_execution
+            .execute_query("create table guard (id int primary key, parent_id int, foreign key (parent_id) references parent (id))")
+            .await?;
+
+        let result = core_execution
+            .execute_query("create table other (id int primary key, parent_name char, foreign key (parent_name) references parent (name))")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1822, mysql_error.error_number()),
+            Ok(_) => panic!("the referenced column name should have no index"),
+        }
+        let result = core_execution
+            .execute_query("create table other (id int primary key, parent_id int not null, foreign key (parent_id) references parent (id) on delete set null)")
+            .await;
+        match result {
+            Err(mysql_error) => assert_eq!(1830, mysql_error.error_number()),
+            Ok(_) => panic!("the column of SET NULL should not be NOT NULL"),
+        }
+
+        core_execution.execute_query("insert into parent (id, name) values (1, 'a'), (2, 'b'), (3, 'c')").await?;
+        let result = core_execution.execute_query("insert into child (id, parent_id) values (1, 9)").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1452, mysql_error.error_number()),
+            Ok(_) => panic!("the child row should refer to no parent row"),
+        }
+        core_execution.execute_query("insert into child (id, parent_id) values (1, 1), (2, 2)").await?;
+        core_execution.execute_query("insert into note (id, parent_id) values (1, 1), (2, 2)").await?;
+        core_execution.execute_query("insert into guard (id, parent_id) values (1, 3)").await?;
+
+        let result = core_execution.execute_query("update child set parent_id = 5 where id = 1").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1452, mysql_error.error_number()),
+            Ok(_) => panic!("the updated child row should refer to no parent row"),
+        }
+        let result = core_execution.execute_query("delete from parent where id = 3").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1451, mysql_error.error_number()),
+            Ok(_) => panic!("the parent row should be referred to by the guard row"),
+        }
+
+        // the child rows are deleted with their parent row, the note rows refer to no row
+        core_execution.execute_query("delete from parent where id = 2").await?;
+        let result = core_execution.execute_query("select id, parent_id from child order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-----------+",
+            "| id | parent_id |",
+            "+----+-----------+",
+            "| 1  | 1         |",
+            "+----+-----------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("select id, parent_id from note order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-----------+",
+            "| id | parent_id |",
+            "+----+-----------+",
+            "| 1  | 1         |",
+            "| 2  |           |",
+            "+----+-----------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select constraint_name, unique_constraint_name, update_rule, delete_rule, table_name, referenced_table_name from information_schema.referential_constraints where constraint_schema = 'test' order by constraint_name")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----------------+------------------------+-------------+-------------+------------+-----------------------+",
+            "| constraint_name | unique_constraint_name | update_rule | delete_rule | table_name | referenced_table_name |",
+            "+-----------------+------------------------+-------------+-------------+------------+-----------------------+",
+            "| child_ibfk_1    | PRIMARY                | NO ACTION   | CASCADE     | child      | parent                |",
+            "| fk_note         | PRIMARY                | NO ACTION   | SET NULL    | note       | parent                |",
+            "| guard_ibfk_1    | PRIMARY                | NO ACTION   | NO ACTION   | guard      | parent                |",
+            "+-----------------+------------------------+-------------+-------------+------------+-----------------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("drop table parent").await;
+        match result {
+            Err(mysql_error) => assert_eq!(3730, mysql_error.error_number()),
+            Ok(_) => panic!("the parent table should be referred to by the child tables"),
+        }
+        let result = core_execution.execute_query("truncate table parent").await;
+        match result {
+            Err(mysql_error) => assert_eq!(1701, mysql_error.error_number()),
+            Ok(_) => panic!("the parent table should be referred to by the child tables"),
+        }
+
+        // the rows are found by the indexes, the key of the value 'a' is the leading part of the one of 'a/b'
+        core_execution.execute_query("create table tag (name varchar(32) primary key)").await?;
+        core_execution
+            .execute_query("create table tagged (id int primary key, tag_name varchar(32), foreign key (tag_name) references tag (name) on update cascade on delete cascade)")
+            .await?;
+        core_execution.execute_query("create unique index tagged_tag on tagged (tag_name, id)").await?;
+        core_execution.execute_query("insert into tag (name) values ('a'), ('a/b')").await?;
+        core_execution.execute_query("insert into tagged (id, tag_name) values (1, 'a/b'), (2, 'a'), (3, 'a')").await?;
+        let result = core_execution.execute_query("insert into tagged (id, tag_name) values (4, 'a/')").await;
+        assert_eq!(1452, result.unwrap_err().error_number());
+        core_execution.execute_query("update tag set name = 'c' where name = 'a'").await?;
+        core_execution.execute_query("delete from tag where name = 'a/b'").await?;
+        let result = core_execution.execute_query("select id, tag_name from tagged order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+----------+",
+            "| id | tag_name |",
+            "+----+----------+",
+            "| 2  | c        |",
+            "| 3  | c        |",
+            "+----+----------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_and_drop_views() -> MysqlResult<()> {
+        let mut core_execution = create_execution().await?;
+
+        core_execution.execute_query("create schema test").await?;
+        core_execution.set_default_schema("test").await?;
+
+        core_execution.execute_query("create table t1 (id int primary key, name varchar(32))").await?;
+        core_execution.execute_query("insert into t1 (id, name) values (1, 'a'), (2, 'b'), (3, 'c')").await?;
+        core_execution.execute_query("create view v1 as select id, name from t1 where id > 1").await?;
+        core_execution.execute_query("create view v2 (a, b) as select id, name from v1 where id < 3").await?;
+
+        let result = core_execution.execute_query("select id, name from v1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the rows inserted after the view was created are read by the view
+        core_execution.execute_query("insert into t1 (id, name) values (0, 'z')").await?;
+        let result = core_execution.execute_query("select a, b from v2").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 2 | b |",
+            "+---+---+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("create view v1 as select id from t1").await;
+        assert_eq!(1050, result.unwrap_err().error_number());
+        let result = core_execution.execute_query("create view v3 (a) as select id, name from t1").await;
+        assert_eq!(1353, result.unwrap_err().error_number());
+        let result = core_execution.execute_query("create or replace view t1 as select id from t1").await;
+        assert_eq!(1347, result.unwrap_err().error_number());
+
+        core_execution.execute_query("create or replace view v1 as select name from t1 where id = 0").await?;
+        let result = core_execution.execute_query("select name from v1").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+------+",
+            "| name |",
+            "+------+",
+            "| z    |",
+            "+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution.execute_query("show full tables").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----------------+------------+",
+            "| Tables_in_test | Table_type |",
+            "+----------------+------------+",
+            "| t1             | BASE TABLE |",
+            "| v1             | VIEW       |",
+            "| v2             | VIEW       |",
+            "+----------------+------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = core_execution
+            .execute_query("select table_name, view_definition, check_option, is_updatable from information_schema.views where table_schema = 'test' order by table_name")
+            .await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+            "+------------+-----------------------------------------------+--------------+--------------+",
+            "| table_name | view_definition                               | check_option | is_updatable |",
+            "+------------+-----------------------------------------------+--------------+--------------+",
+            "| v1         | SELECT name FROM def.test.t1 WHERE id = 0     | NONE         | NO           |",
+            "| v2         | SELECT id, name FROM def.test.v1 WHERE id < 3 | NONE         | NO           |",
+            "+------------+-----------------------------------------------+--------------+--------------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        core_execution.execute_query("drop view v2").await?;
+        let result = core_execution.execute_query("drop view v2").await;
+        assert_eq!(1051, result.unwrap_err().error_number());
+        core_execution.execute_query("drop view if exists v2").await?;
+        let result = core_execution.execute_query("drop view t1").await;
+        assert_eq!(1347, result.unwrap_err().error_number());
+        let result = core_execution.execute_query("select a from v2").await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn temporary_tables() -> MysqlResult<()> {
+        let mut session_a = create_execution().await?;
+        session_a.execute_query("create schema test").await?;
+        session_a.set_default_schema("test").await?;
+        session_a.execute_query("create table t1 (id int primary key, name varchar(32))").await?;
+        session_a.execute_query("insert into t1 (id, name) values (1, 'a')").await?;
+
+        let mut session_b = Execution::new(session_a.global_context());
+        session_b.try_init()?;
+        session_b.set_default_schema("test").await?;
+
+        // the temporary table hides the table of the same name in its session only
+        session_a
+            .execute_query("create temporary table t1 (id int primary key, name varchar(32) not null default 'x')")
+            .await?;
+        session_a.execute_query("insert into t1 (id, name) values (3, 'c'), (2, 'b')").await?;
+        session_a.execute_query("insert into t1 (id) values (4)").await?;
+        let result = session_a.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "| 4  | x    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = session_b.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        let result = session_b.execute_query("insert into t1 (id) values (2)").await;
+        assert!(result.is_ok());
+
+        let result = session_a.execute_query("insert into t1 (id, name) values (5, 'e'), (2, 'f')").await;
+        assert_eq!(1062, result.unwrap_err().error_number());
+        let result = session_a.execute_query("insert into t1 (id, name) values (6, null)").await;
+        assert_eq!(1048, result.unwrap_err().error_number());
+        let result = session_a.execute_query("create temporary table t1 (id int)").await;
+        assert_eq!(1050, result.unwrap_err().error_number());
+        let result = session_a.execute_query("update t1 set name = 'y' where id = 2").await;
+        assert_eq!(1235, result.unwrap_err().error_number());
+        let result = session_a.execute_query("create view v1 as select id from t1").await;
+        assert_eq!(1352, result.unwrap_err().error_number());
+
+        // the temporary table reads the rows of the other tables
+        session_a.execute_query("create temporary table tmp (id int, name varchar(32))").await?;
+        session_a.execute_query("insert into tmp select id, name from t1 where id < 4").await?;
+        let result = session_a.execute_query("select count(*) as cnt from tmp").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----+",
+            "| cnt |",
+            "+-----+",
+            "| 2   |",
+            "+-----+",
+        ];
+        assert_batches_eq!(expected, &results);
+        let result = session_b.execute_query("select id from tmp").await;
+        assert!(result.is_err());
+
+        // the table is seen again after its temporary table is dropped
+        session_a.execute_query("drop temporary table t1").await?;
+        let result = session_a.execute_query("drop temporary table t1").await;
+        assert_eq!(1051, result.unwrap_err().error_number());
+        let result = session_a.execute_query("select id, name from t1 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  |      |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the temporary tables are dropped with the session state
+        session_a.reset_connection()?;
+        session_a.set_default_schema("test").await?;
+        let result = session_a.execute_query("select id from tmp").await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn common_table_expressions() -> MysqlResult<()> {
+        let mut session = create_execution().await?;
+        session.execute_query("create schema test").await?;
+        session.set_default_schema("test").await?;
+        session.execute_query("create table t1 (id int primary key, parent_id int, name varchar(32))").await?;
+        session
+            .execute_query("insert into t1 (id, parent_id, name) values (1, null, 'root'), (2, 1, 'a'), (3, 1, 'b'), (4, 2, 'c')")
+            .await?;
+
+        let result = session.execute_query("with c as (select id, name from t1 where parent_id = 1) select id, name from c order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 2  | a    |",
+            "| 3  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the expressions read the ones before them by their column lists
+        let result = session.execute_query("with c (cid) as (select id from t1), d as (select cid from c where cid > 2) select cid from d order by cid").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+-----+",
+            "| cid |",
+            "+-----+",
+            "| 3   |",
+            "| 4   |",
+            "+-----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = session.execute_query("with recursive seq (n) as (select 1 union all select n + 1 from seq where n < 5) select n from seq order by n").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---+",
+            "| n |",
+            "+---+",
+            "| 1 |",
+            "| 2 |",
+            "| 3 |",
+            "| 4 |",
+            "| 5 |",
+            "+---+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = session.execute_query("with recursive tree (id, depth) as (select id, 0 from t1 where parent_id is null union all select t1.id, tree.depth + 1 from t1 join tree on t1.parent_id = tree.id) select id, depth from tree order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+-------+",
+            "| id | depth |",
+            "+----+-------+",
+            "| 1  | 0     |",
+            "| 2  | 1     |",
+            "| 3  | 1     |",
+            "| 4  | 2     |",
+            "+----+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        session.execute_query("set cte_max_recursion_depth = 3").await?;
+        let result = session
+            .execute_query("with recursive seq (n) as (select 1 union all select n + 1 from seq where n < 5) select n from seq")
+            .await;
+        assert_eq!(3636, result.unwrap_err().error_number());
+        let result = session.execute_query("with recursive r as (select 1 from r) select * from r").await;
+        assert_eq!(3573, result.unwrap_err().error_number());
+        let result = session.execute_query("with c (a, b) as (select id from t1) select a from c").await;
+        assert_eq!(1353, result.unwrap_err().error_number());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_operations() -> MysqlResult<()> {
+        let mut session = create_execution().await?;
+        session.execute_query("create schema test").await?;
+        session.set_default_schema("test").await?;
+        session.execute_query("create table t1 (id int primary key, name varchar(32))").await?;
+        session.execute_query("create table t2 (id bigint primary key, name varchar(32))").await?;
+        session.execute_query("insert into t1 (id, name) values (1, 'a'), (2, 'b')").await?;
+        session.execute_query("insert into t2 (id, name) values (2, 'b'), (3, 'c')").await?;
+
+        // the INT and the BIGINT columns are both BIGINT
+        let result = session.execute_query("select id, name from t1 union select id, name from t2 order by id").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = session.execute_query("select id, name from t1 union all select id, name from t2 order by id, name").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "+----+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the columns are named by the first SELECT
+        let result = session.execute_query("select id as k, name from t1 union select id, name from t2 order by k desc limit 2").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+---+------+",
+            "| k | name |",
+            "+---+------+",
+            "| 3 | c    |",
+            "| 2 | b    |",
+            "+---+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = session.execute_query("select id from t1 intersect select id from t2").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 2  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let result = session.execute_query("select id from t1 except select id from t2").await?;
+        let mut results: Vec<RecordBatch> = vec![];
+        match result {
+            CoreOutput::ResultSet(r) => results = r.record_batches,
+            _ => {}
+        }
+        let expected = vec![
+            "+----+",
+            "| id |",
+            "+----+",
+            "| 1  |",
+            "+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // NULL is not distinct from NULL
+        for (sql, row_count) in [
+            ("select null intersect select null", 1),
+            ("select null except select null", 0),
+            ("select 1, null intersect select 1, null", 1),
+            ("select 1, null except select 1, 2", 1),
+        ] {
+            let result = session.execute_query(sql).await?;
+            let mut results: Vec<RecordBatch> = vec![];
+            match result {
+                CoreOutput::ResultSet(r) => results = r.record_batches,
+                _ => {}
+            }
+            assert_eq!(row_count, results.iter().map(|batch| batch.num_rows()).sum::<usize>(), "{}", sql);
+        }
+
+        let result = session.execute_query("select id, name from t1 union select id from t2").await;
+        assert_eq!(1222, result.unwrap_err().error_number());
+        let result = session.execute_query("select id from t1 union select id from t3").await;
+        assert_eq!(1146, result.unwrap_err().error_number());
+
+        Ok(())
+    }
+
+    #[test]
+    fn short_command_packets() -> MysqlResult<()> {
+        let malformed = mysql_error_code::ER_MALFORMED_PACKET as u16;
+
+        // the payload without a command
+        let request_payload = request::RequestPayload::new(vec![0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(malformed, request_payload.get_command_id().unwrap_err().error_number());
+
+        // the commands shorter than their fixed fields
+        let request_payload = request::RequestPayload::new(vec![0x03, 0x00, 0x00, 0x00, 0x19, 0x01, 0x00]);
+        assert_eq!(0x19, request_payload.get_command_id()?);
+        assert_eq!(malformed, request_payload.get_stmt_close().unwrap_err().error_number());
+        assert_eq!(malformed, request_payload.get_stmt_reset().unwrap_err().error_number());
+        assert_eq!(malformed, request_payload.get_stmt_execute().unwrap_err().error_number());
+        assert_eq!(malformed, request_payload.get_stmt_send_long_data().unwrap_err().error_number());
+        assert_eq!(malformed, request_payload.get_stmt_fetch().unwrap_err().error_number());
+        assert_eq!(malformed, request_payload.get_process_kill().unwrap_err().error_number());
+        assert_eq!(1, request_payload.get_set_option()?);
+
+        let request_payload = request::RequestPayload::new(vec![0x05, 0x00, 0x00, 0x00, 0x19, 0x01, 0x00, 0x00, 0x00]);
+        assert_eq!(&[0x01, 0x00, 0x00, 0x00], request_payload.get_stmt_close()?);
+
+        Ok(())
+    }
 }
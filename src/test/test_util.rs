@@ -14,16 +14,29 @@ use tokio::net::TcpListener;
 use uuid::Uuid;
 
 pub async fn create_execution() -> MysqlResult<Execution> {
-    let mut my_config = MyConfig::default();
+    create_execution_with_config(MyConfig::default()).await
+}
+
+pub async fn create_execution_with_config(mut my_config: MyConfig) -> MysqlResult<Execution> {
     let test_id = Uuid::new_v4()
         .to_simple()
         .encode_lower(&mut Uuid::encode_buffer())
         .to_string();
     my_config.engine.sled.data_path = format!("./data/test/sled/{}", test_id);
 
+    open_execution(my_config).await
+}
+
+/// Open the execution on the data path of the config, the data of an earlier execution is kept
+pub async fn open_execution(mut my_config: MyConfig) -> MysqlResult<Execution> {
+    // the tests log in as root with this password
+    if my_config.server.initial_root_password.is_none() {
+        my_config.server.initial_root_password = Some("123456".to_string());
+    }
+
     let global_context = Arc::new(Mutex::new(GlobalContext::new_with_config(
         my_config.clone(),
-    )));
+    )?));
 
     let result = meta_util::init_meta(global_context.clone()).await;
     if let Err(mysql_error) = result {
@@ -64,6 +77,19 @@ pub async fn create_execution() -> MysqlResult<Execution> {
         }
     }
 
+    // view def
+    meta_util::load_all_view(global_context.clone())?;
+
+    if my_config.server.repair_auto_increment {
+        meta_util::repair_auto_increment(global_context.clone())?;
+    }
+    if my_config.server.migrate_table_id {
+        meta_util::migrate_table_id(global_context.clone())?;
+    }
+    if my_config.server.check_meta {
+        meta_util::verify_meta(global_context.clone())?;
+    }
+
     let mut core_execution = Execution::new(global_context.clone());
 
     let result = core_execution.try_init();
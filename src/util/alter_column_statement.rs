@@ -0,0 +1,132 @@
+use sqlparser::ast::ObjectName;
+use sqlparser::tokenizer::Token;
+
+use crate::util::index_statement;
+use crate::util::schema_statement::Tokens;
+
+/// `ALTER TABLE tbl_name MODIFY [COLUMN] col_name column_definition` and
+/// `ALTER TABLE tbl_name CHANGE [COLUMN] old_col_name new_col_name column_definition`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModifyColumnStatement {
+    pub table_name: ObjectName,
+    /// The column being changed, the new name is the one of the column definition
+    pub column_name: String,
+    /// The column definition with the name of the column, as the one of CREATE TABLE
+    pub column_sql: String,
+}
+
+/// Where the column is added, the column is the last one without a position
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnPosition {
+    First,
+    After(String),
+}
+
+/// `ALTER TABLE tbl_name ADD [COLUMN] column_definition {FIRST | AFTER col_name}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddColumnStatement {
+    pub table_name: ObjectName,
+    /// The column definition, as the one of CREATE TABLE
+    pub column_sql: String,
+    pub position: ColumnPosition,
+}
+
+/// The sql parser does not know the position of ADD COLUMN, None if the sql is not an ADD COLUMN with a position
+pub fn parse_add_column(sql: &str) -> Option<AddColumnStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["ALTER", "TABLE"]) {
+        return None;
+    }
+    let table_name = index_statement::next_object_name(&mut tokens)?;
+    if !tokens.next_word_is("ADD") {
+        return None;
+    }
+    tokens.next_word_is("COLUMN");
+    let (column_sql, position) = if tokens.last_words_are(&["FIRST"]) {
+        (tokens.remaining_sql(), ColumnPosition::First)
+    } else if tokens.contains_words(&["AFTER"]) {
+        let column_sql = tokens.next_sql_until_words(&["AFTER"])?;
+        let column_name = tokens.next_name()?;
+        if !tokens.is_end() {
+            return None;
+        }
+        (column_sql, ColumnPosition::After(column_name))
+    } else {
+        return None;
+    };
+    if column_sql.is_empty() {
+        return None;
+    }
+
+    Some(AddColumnStatement {
+        table_name,
+        column_sql,
+        position,
+    })
+}
+
+/// The sql parser knows neither MODIFY nor CHANGE of ALTER TABLE, None if the sql is not one of them
+pub fn parse_modify_column(sql: &str) -> Option<ModifyColumnStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["ALTER", "TABLE"]) {
+        return None;
+    }
+    let table_name = index_statement::next_object_name(&mut tokens)?;
+    let column_name = if tokens.next_word_is("MODIFY") {
+        tokens.next_word_is("COLUMN");
+        match tokens.peek_token()? {
+            Token::Word(word) => word.value.clone(),
+            _ => return None,
+        }
+    } else if tokens.next_word_is("CHANGE") {
+        tokens.next_word_is("COLUMN");
+        tokens.next_name()?
+    } else {
+        return None;
+    };
+    let column_sql = tokens.remaining_sql();
+    if column_sql.is_empty() {
+        return None;
+    }
+
+    Some(ModifyColumnStatement {
+        table_name,
+        column_name,
+        column_sql,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::ast::Ident;
+
+    #[test]
+    fn parse() {
+        let statement = parse_modify_column("alter table test.user modify column age decimal(10, 2) null;").unwrap();
+        assert_eq!(ObjectName(vec![Ident::new("test"), Ident::new("user")]), statement.table_name);
+        assert_eq!("age", statement.column_name);
+        assert_eq!("age decimal ( 10 , 2 ) null", statement.column_sql);
+
+        let statement = parse_modify_column("ALTER TABLE user CHANGE name user_name char DEFAULT 'a'").unwrap();
+        assert_eq!("name", statement.column_name);
+        assert_eq!("user_name char DEFAULT 'a'", statement.column_sql);
+
+        assert_eq!(None, parse_modify_column("alter table user change column name"));
+        assert_eq!(None, parse_modify_column("alter table user add column age int"));
+        assert_eq!(None, parse_modify_column("alter table user modify"));
+
+        let statement = parse_add_column("alter table test.user add column age int default 18 after name;").unwrap();
+        assert_eq!(ObjectName(vec![Ident::new("test"), Ident::new("user")]), statement.table_name);
+        assert_eq!("age int default 18", statement.column_sql);
+        assert_eq!(ColumnPosition::After("name".to_string()), statement.position);
+
+        let statement = parse_add_column("ALTER TABLE user ADD code char FIRST").unwrap();
+        assert_eq!("code char", statement.column_sql);
+        assert_eq!(ColumnPosition::First, statement.position);
+
+        assert_eq!(None, parse_add_column("alter table user add column age int"));
+        assert_eq!(None, parse_add_column("alter table user add column age int after name, add column code char"));
+        assert_eq!(None, parse_add_column("alter table user drop column age"));
+    }
+}
@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+/// A character set of SHOW CHARACTER SET, the text is always stored as utf8,
+/// the character set only names the collations which compare it
+#[derive(Debug, PartialEq)]
+pub struct CharacterSet {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default_collation: &'static str,
+    pub max_length: u8,
+}
+
+/// A collation of SHOW COLLATION, the id is the one sent in the column definitions
+#[derive(Debug, PartialEq)]
+pub struct Collation {
+    pub name: &'static str,
+    pub character_set: &'static str,
+    pub id: u8,
+    pub is_default: bool,
+    pub sort_length: u8,
+    /// The PAD SPACE collations ignore the trailing spaces, the NO PAD ones do not
+    pub pad_space: bool,
+    pub case_insensitive: bool,
+}
+
+/// In the order of SHOW CHARACTER SET, by the name
+pub const CHARACTER_SETS: &[CharacterSet] = &[
+    CharacterSet { name: "binary", description: "Binary pseudo charset", default_collation: "binary", max_length: 1 },
+    CharacterSet { name: "utf8", description: "UTF-8 Unicode", default_collation: "utf8_general_ci", max_length: 3 },
+    CharacterSet { name: "utf8mb4", description: "UTF-8 Unicode", default_collation: "utf8mb4_0900_ai_ci", max_length: 4 },
+];
+
+/// In the order of SHOW COLLATION, by the name
+pub const COLLATIONS: &[Collation] = &[
+    Collation { name: "binary", character_set: "binary", id: 63, is_default: true, sort_length: 1, pad_space: false, case_insensitive: false },
+    Collation { name: "utf8_bin", character_set: "utf8", id: 83, is_default: false, sort_length: 1, pad_space: true, case_insensitive: false },
+    Collation { name: "utf8_general_ci", character_set: "utf8", id: 33, is_default: true, sort_length: 1, pad_space: true, case_insensitive: true },
+    Collation { name: "utf8_unicode_ci", character_set: "utf8", id: 192, is_default: false, sort_length: 8, pad_space: true, case_insensitive: true },
+    Collation { name: "utf8mb4_0900_ai_ci", character_set: "utf8mb4", id: 255, is_default: true, sort_length: 0, pad_space: false, case_insensitive: true },
+    Collation { name: "utf8mb4_bin", character_set: "utf8mb4", id: 46, is_default: false, sort_length: 1, pad_space: true, case_insensitive: false },
+    Collation { name: "utf8mb4_general_ci", character_set: "utf8mb4", id: 45, is_default: false, sort_length: 1, pad_space: true, case_insensitive: true },
+    Collation { name: "utf8mb4_unicode_ci", character_set: "utf8mb4", id: 224, is_default: false, sort_length: 8, pad_space: true, case_insensitive: true },
+];
+
+/// The collation of the text columns without a character set or a collation
+pub const DEFAULT_COLLATION: &str = "utf8mb4_0900_ai_ci";
+
+pub fn find_character_set(name: &str) -> Option<&'static CharacterSet> {
+    let name = name.to_lowercase();
+    CHARACTER_SETS.iter().find(|character_set| character_set.name == name)
+}
+
+pub fn find_collation(name: &str) -> Option<&'static Collation> {
+    let name = name.to_lowercase();
+    COLLATIONS.iter().find(|collation| collation.name == name)
+}
+
+pub fn default_collation() -> &'static Collation {
+    find_collation(DEFAULT_COLLATION).unwrap()
+}
+
+/// The collation of the CHARACTER SET and the COLLATE of a column, a table or a schema,
+/// the default collation of the character set if the COLLATE is not given, None if neither is given
+pub fn resolve_collation(character_set_name: Option<&str>, collation_name: Option<&str>) -> MysqlResult<Option<&'static Collation>> {
+    let character_set = match character_set_name {
+        Some(character_set_name) => match find_character_set(character_set_name) {
+            Some(character_set) => Some(character_set),
+            None => {
+                return Err(MysqlError::new_server_error(
+                    1115,
+                    "42000",
+                    format!("Unknown character set: '{}'", character_set_name).as_str(),
+                ));
+            }
+        },
+        None => None,
+    };
+    let collation = match (collation_name, character_set) {
+        (Some(collation_name), _) => match find_collation(collation_name) {
+            Some(collation) => collation,
+            None => {
+                return Err(MysqlError::new_server_error(
+                    1273,
+                    "HY000",
+                    format!("Unknown collation: '{}'", collation_name).as_str(),
+                ));
+            }
+        },
+        (None, Some(character_set)) => find_collation(character_set.default_collation).unwrap(),
+        (None, None) => return Ok(None),
+    };
+
+    if let Some(character_set) = character_set {
+        if collation.character_set != character_set.name {
+            return Err(MysqlError::new_server_error(
+                1253,
+                "42000",
+                format!("COLLATION '{}' is not valid for CHARACTER SET '{}'", collation.name, character_set.name).as_str(),
+            ));
+        }
+    }
+    Ok(Some(collation))
+}
+
+/// The key of the string by which the collation compares and sorts it, two strings are equal
+/// in the collation if their keys are equal. The case-insensitive collations fold the case
+/// and the PAD SPACE collations remove the trailing spaces
+pub fn sort_key(collation: &Collation, value: &str) -> String {
+    let value = if collation.pad_space { value.trim_end_matches(' ') } else { value };
+    if collation.case_insensitive {
+        value.chars().flat_map(|c| c.to_lowercase()).collect()
+    } else {
+        value.to_string()
+    }
+}
+
+/// The order of the strings in the collation
+pub fn compare(collation: &Collation, left: &str, right: &str) -> Ordering {
+    if !collation.case_insensitive && !collation.pad_space {
+        return left.as_bytes().cmp(right.as_bytes());
+    }
+    sort_key(collation, left).cmp(&sort_key(collation, right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        assert_eq!(None, resolve_collation(None, None).unwrap());
+        assert_eq!(Some("utf8_general_ci"), resolve_collation(Some("UTF8"), None).unwrap().map(|collation| collation.name));
+        assert_eq!(Some("utf8mb4_bin"), resolve_collation(None, Some("utf8mb4_bin")).unwrap().map(|collation| collation.name));
+        assert_eq!(1115, resolve_collation(Some("latin1"), None).unwrap_err().error_number());
+        assert_eq!(1273, resolve_collation(None, Some("utf8mb4_xx")).unwrap_err().error_number());
+        assert_eq!(1253, resolve_collation(Some("utf8"), Some("utf8mb4_bin")).unwrap_err().error_number());
+    }
+
+    #[test]
+    fn compare_strings() {
+        let general_ci = find_collation("utf8mb4_general_ci").unwrap();
+        let bin = find_collation("utf8mb4_bin").unwrap();
+        let ai_ci = find_collation("utf8mb4_0900_ai_ci").unwrap();
+        let binary = find_collation("binary").unwrap();
+
+        assert_eq!(Ordering::Equal, compare(general_ci, "Apple", "aPPLE"));
+        assert_eq!(Ordering::Less, compare(general_ci, "apple", "Banana"));
+        assert_eq!(Ordering::Greater, compare(bin, "apple", "Banana"));
+        // the PAD SPACE collations ignore the trailing spaces, the NO PAD ones do not
+        assert_eq!(Ordering::Equal, compare(bin, "a ", "a"));
+        assert_eq!(Ordering::Greater, compare(ai_ci, "a ", "a"));
+        assert_eq!(Ordering::Greater, compare(binary, "a ", "a"));
+        assert_eq!("straße", sort_key(general_ci, "STRAßE  "));
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use sqlparser::ast::{Expr as SQLExpr, FunctionArg, Ident, ObjectName, Query, Select, SelectItem, SetExpr, TableAlias, TableFactor, TableWithJoins};
+use uuid::Uuid;
+
+use crate::meta::meta_const;
+
+/// The table keeping the rows of the common table expression while its query runs,
+/// the name is unique so the expressions of the same name of the nested queries do not meet
+pub fn create_cte_table_name(cte_name: &str) -> ObjectName {
+    let table_name = format!(
+        "{}_{}",
+        cte_name,
+        Uuid::new_v4().to_simple().encode_lower(&mut Uuid::encode_buffer())
+    );
+    ObjectName(vec![
+        Ident::new(meta_const::CATALOG_NAME),
+        Ident::new(meta_const::SCHEMA_NAME_OF_COMMON_TABLE_EXPRESSION),
+        Ident::new(table_name),
+    ])
+}
+
+/// The name of the common table expression whose rows the table keeps
+pub fn cut_out_cte_name(cte_table_name: &str) -> &str {
+    match cte_table_name.rsplit_once('_') {
+        Some((cte_name, _)) => cte_name,
+        None => cte_table_name,
+    }
+}
+
+/// Rename the one-part table names of the query found in the map, by their lower case, to the tables keeping
+/// the rows of the common table expressions. The references keep the expression name as their alias.
+/// The expressions of a nested WITH hide the ones of the same names
+pub fn rename_cte_references(query: &Query, cte_table_name_map: &HashMap<String, ObjectName>) -> Query {
+    let mut cte_table_name_map = cte_table_name_map.clone();
+    let mut new_query = query.clone();
+    if let Some(with) = &mut new_query.with {
+        let recursive = with.recursive;
+        for cte in with.cte_tables.iter_mut() {
+            let cte_name = cte.alias.name.value.to_lowercase();
+            if recursive {
+                cte_table_name_map.remove(&cte_name);
+            }
+            cte.query = rename_cte_references(&cte.query, &cte_table_name_map);
+            cte_table_name_map.remove(&cte_name);
+        }
+    }
+    new_query.body = rename_set_expr(&query.body, &cte_table_name_map);
+    for order_by_expr in new_query.order_by.iter_mut() {
+        rename_expr(&mut order_by_expr.expr, &cte_table_name_map);
+    }
+    new_query
+}
+
+fn rename_set_expr(set_expr: &SetExpr, cte_table_name_map: &HashMap<String, ObjectName>) -> SetExpr {
+    match set_expr {
+        SetExpr::Select(select) => {
+            let mut new_select = select.clone();
+            for table_with_joins in new_select.from.iter_mut() {
+                rename_table_with_joins(table_with_joins, cte_table_name_map);
+            }
+            for expr in select_exprs_mut(&mut new_select) {
+                rename_expr(expr, cte_table_name_map);
+            }
+            SetExpr::Select(new_select)
+        }
+        SetExpr::Query(query) => SetExpr::Query(Box::new(rename_cte_references(query, cte_table_name_map))),
+        SetExpr::SetOperation { op, all, left, right } => SetExpr::SetOperation {
+            op: op.clone(),
+            all: *all,
+            left: Box::new(rename_set_expr(left, cte_table_name_map)),
+            right: Box::new(rename_set_expr(right, cte_table_name_map)),
+        },
+        _ => set_expr.clone(),
+    }
+}
+
+/// The subqueries of the expression are renamed too, `WHERE id IN (SELECT id FROM cte)`
+fn rename_expr(expr: &mut SQLExpr, cte_table_name_map: &HashMap<String, ObjectName>) {
+    let mut subqueries = vec![];
+    expr_subqueries_mut(expr, &mut subqueries);
+    for subquery in subqueries {
+        *subquery = rename_cte_references(subquery, cte_table_name_map);
+    }
+}
+
+/// The expressions of the SELECT list, the WHERE, the GROUP BY and the HAVING
+fn select_exprs_mut(select: &mut Select) -> Vec<&mut SQLExpr> {
+    let mut exprs = vec![];
+    for select_item in select.projection.iter_mut() {
+        match select_item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => exprs.push(expr),
+            _ => {}
+        }
+    }
+    exprs.extend(select.selection.iter_mut());
+    exprs.extend(select.group_by.iter_mut());
+    exprs.extend(select.having.iter_mut());
+    exprs
+}
+
+fn select_exprs(select: &Select) -> Vec<&SQLExpr> {
+    let mut exprs = vec![];
+    for select_item in select.projection.iter() {
+        match select_item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => exprs.push(expr),
+            _ => {}
+        }
+    }
+    exprs.extend(select.selection.iter());
+    exprs.extend(select.group_by.iter());
+    exprs.extend(select.having.iter());
+    exprs
+}
+
+/// The outermost subqueries of the expression
+fn expr_subqueries_mut<'a>(expr: &'a mut SQLExpr, subqueries: &mut Vec<&'a mut Query>) {
+    match expr {
+        SQLExpr::InSubquery { expr, subquery, .. } => {
+            expr_subqueries_mut(expr, subqueries);
+            subqueries.push(subquery);
+        }
+        SQLExpr::Exists(subquery) | SQLExpr::Subquery(subquery) => subqueries.push(subquery),
+        SQLExpr::BinaryOp { left, right, .. } => {
+            expr_subqueries_mut(left, subqueries);
+            expr_subqueries_mut(right, subqueries);
+        }
+        SQLExpr::UnaryOp { expr, .. }
+        | SQLExpr::Nested(expr)
+        | SQLExpr::Cast { expr, .. }
+        | SQLExpr::Collate { expr, .. }
+        | SQLExpr::IsNull(expr)
+        | SQLExpr::IsNotNull(expr) => expr_subqueries_mut(expr, subqueries),
+        SQLExpr::InList { expr, list, .. } => {
+            expr_subqueries_mut(expr, subqueries);
+            for item in list.iter_mut() {
+                expr_subqueries_mut(item, subqueries);
+            }
+        }
+        SQLExpr::Between { expr, low, high, .. } => {
+            expr_subqueries_mut(expr, subqueries);
+            expr_subqueries_mut(low, subqueries);
+            expr_subqueries_mut(high, subqueries);
+        }
+        SQLExpr::Case { operand, conditions, results, else_result } => {
+            for expr in operand.iter_mut().chain(else_result.iter_mut()) {
+                expr_subqueries_mut(expr, subqueries);
+            }
+            for expr in conditions.iter_mut().chain(results.iter_mut()) {
+                expr_subqueries_mut(expr, subqueries);
+            }
+        }
+        SQLExpr::Function(function) => {
+            for arg in function.args.iter_mut() {
+                if let FunctionArg::Unnamed(expr) = arg {
+                    expr_subqueries_mut(expr, subqueries);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn expr_subqueries<'a>(expr: &'a SQLExpr, subqueries: &mut Vec<&'a Query>) {
+    match expr {
+        SQLExpr::InSubquery { expr, subquery, .. } => {
+            expr_subqueries(expr, subqueries);
+            subqueries.push(subquery);
+        }
+        SQLExpr::Exists(subquery) | SQLExpr::Subquery(subquery) => subqueries.push(subquery),
+        SQLExpr::BinaryOp { left, right, .. } => {
+            expr_subqueries(left, subqueries);
+            expr_subqueries(right, subqueries);
+        }
+        SQLExpr::UnaryOp { expr, .. }
+        | SQLExpr::Nested(expr)
+        | SQLExpr::Cast { expr, .. }
+        | SQLExpr::Collate { expr, .. }
+        | SQLExpr::IsNull(expr)
+        | SQLExpr::IsNotNull(expr) => expr_subqueries(expr, subqueries),
+        SQLExpr::InList { expr, list, .. } => {
+            expr_subqueries(expr, subqueries);
+            for item in list.iter() {
+                expr_subqueries(item, subqueries);
+            }
+        }
+        SQLExpr::Between { expr, low, high, .. } => {
+            expr_subqueries(expr, subqueries);
+            expr_subqueries(low, subqueries);
+            expr_subqueries(high, subqueries);
+        }
+        SQLExpr::Case { operand, conditions, results, else_result } => {
+            for expr in operand.iter().chain(else_result.iter()) {
+                expr_subqueries(expr, subqueries);
+            }
+            for expr in conditions.iter().chain(results.iter()) {
+                expr_subqueries(expr, subqueries);
+            }
+        }
+        SQLExpr::Function(function) => {
+            for arg in function.args.iter() {
+                if let FunctionArg::Unnamed(expr) = arg {
+                    expr_subqueries(expr, subqueries);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rename_table_with_joins(table_with_joins: &mut TableWithJoins, cte_table_name_map: &HashMap<String, ObjectName>) {
+    rename_table_factor(&mut table_with_joins.relation, cte_table_name_map);
+    for join in table_with_joins.joins.iter_mut() {
+        rename_table_factor(&mut join.relation, cte_table_name_map);
+    }
+}
+
+fn rename_table_factor(table_factor: &mut TableFactor, cte_table_name_map: &HashMap<String, ObjectName>) {
+    match table_factor {
+        TableFactor::Table { name, alias, .. } => {
+            if name.0.len() != 1 {
+                return;
+            }
+            if let Some(cte_table_name) = cte_table_name_map.get(&name.0[0].value.to_lowercase()) {
+                if alias.is_none() {
+                    *alias = Some(TableAlias {
+                        name: name.0[0].clone(),
+                        columns: vec![],
+                    });
+                }
+                *name = cte_table_name.clone();
+            }
+        }
+        TableFactor::Derived { subquery, .. } => {
+            *subquery = Box::new(rename_cte_references(subquery, cte_table_name_map));
+        }
+        TableFactor::NestedJoin(table_with_joins) => rename_table_with_joins(table_with_joins, cte_table_name_map),
+        _ => {}
+    }
+}
+
+/// The query, one of its derived tables or one of its subqueries reads the table
+pub fn query_reads_table(query: &Query, table_name: &ObjectName) -> bool {
+    if let Some(with) = &query.with {
+        if with.cte_tables.iter().any(|cte| query_reads_table(&cte.query, table_name)) {
+            return true;
+        }
+    }
+    set_expr_reads_table(&query.body, table_name)
+        || query.order_by.iter().any(|order_by_expr| expr_reads_table(&order_by_expr.expr, table_name))
+}
+
+fn expr_reads_table(expr: &SQLExpr, table_name: &ObjectName) -> bool {
+    let mut subqueries = vec![];
+    expr_subqueries(expr, &mut subqueries);
+    subqueries.into_iter().any(|subquery| query_reads_table(subquery, table_name))
+}
+
+fn set_expr_reads_table(set_expr: &SetExpr, table_name: &ObjectName) -> bool {
+    match set_expr {
+        SetExpr::Select(select) => {
+            select.from.iter().any(|table_with_joins| {
+                table_factor_reads_table(&table_with_joins.relation, table_name)
+                    || table_with_joins.joins.iter().any(|join| table_factor_reads_table(&join.relation, table_name))
+            }) || select_exprs(select).into_iter().any(|expr| expr_reads_table(expr, table_name))
+        }
+        SetExpr::Query(query) => query_reads_table(query, table_name),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_reads_table(left, table_name) || set_expr_reads_table(right, table_name)
+        }
+        _ => false,
+    }
+}
+
+fn table_factor_reads_table(table_factor: &TableFactor, table_name: &ObjectName) -> bool {
+    match table_factor {
+        TableFactor::Table { name, .. } => name == table_name,
+        TableFactor::Derived { subquery, .. } => query_reads_table(subquery, table_name),
+        TableFactor::NestedJoin(table_with_joins) => {
+            table_factor_reads_table(&table_with_joins.relation, table_name)
+                || table_with_joins.joins.iter().any(|join| table_factor_reads_table(&join.relation, table_name))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::ast::Statement;
+    use sqlparser::dialect::GenericDialect;
+    use sqlparser::parser::Parser;
+
+    fn parse_query(sql: &str) -> Query {
+        match Parser::parse_sql(&GenericDialect {}, sql).unwrap().remove(0) {
+            Statement::Query(query) => *query,
+            _ => panic!("not a query"),
+        }
+    }
+
+    #[test]
+    fn rename() {
+        let cte_table_name = ObjectName(vec![Ident::new("def"), Ident::new("#cte"), Ident::new("t_1")]);
+        let mut cte_table_name_map = HashMap::new();
+        cte_table_name_map.insert("t".to_string(), cte_table_name.clone());
+
+        assert_eq!("t", cut_out_cte_name(cte_table_name.0[2].value.as_str()));
+        assert_eq!("my_cte", cut_out_cte_name(create_cte_table_name("my_cte").0[2].value.as_str()));
+
+        let query = parse_query("select * from T join test.t on 1 = 1 join (select * from t as x) as d on 1 = 1");
+        let query = rename_cte_references(&query, &cte_table_name_map);
+        assert!(query_reads_table(&query, &cte_table_name));
+        assert!(query_reads_table(&query, &ObjectName(vec![Ident::new("test"), Ident::new("t")])));
+        assert!(!query_reads_table(&query, &ObjectName(vec![Ident::new("T")])));
+        assert!(!query_reads_table(&query, &ObjectName(vec![Ident::new("t")])));
+
+        // the nested WITH hides the expression of the same name
+        let query = parse_query("select * from (with t as (select 1) select * from t) as d");
+        let query = rename_cte_references(&query, &cte_table_name_map);
+        assert!(!query_reads_table(&query, &cte_table_name));
+        let query = parse_query("select * from (with u as (select * from t) select * from u) as d");
+        let query = rename_cte_references(&query, &cte_table_name_map);
+        assert!(query_reads_table(&query, &cte_table_name));
+
+        // the subqueries of the expressions
+        let query = parse_query("select (select max(id) from t) from test.t where id in (select id from t) or exists (select 1 from t)");
+        assert!(!query_reads_table(&query, &cte_table_name));
+        let query = rename_cte_references(&query, &cte_table_name_map);
+        assert!(query_reads_table(&query, &cte_table_name));
+        assert!(!query_reads_table(&query, &ObjectName(vec![Ident::new("t")])));
+        let query = parse_query("select id from test.t where not (id > 1 and id in (select id from t))");
+        let query = rename_cte_references(&query, &cte_table_name_map);
+        assert!(!query_reads_table(&query, &ObjectName(vec![Ident::new("t")])));
+        assert!(query_reads_table(&query, &cte_table_name));
+    }
+}
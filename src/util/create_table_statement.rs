@@ -0,0 +1,529 @@
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// `CREATE TABLE` with the `ZEROFILL` attribute on its columns
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZerofillStatement {
+    /// The sql of the CREATE TABLE without the ZEROFILL
+    pub statement_sql: String,
+    pub column_names: Vec<String>,
+}
+
+/// The CHARACTER SET of the columns and the default CHARACTER SET and COLLATE of the table of CREATE TABLE
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharsetStatement {
+    /// The sql of the CREATE TABLE without the CHARACTER SET of the columns and the options of the table
+    pub statement_sql: String,
+    /// The names of the columns with the names of their character sets
+    pub column_character_sets: Vec<(String, String)>,
+    pub character_set_name: Option<String>,
+    pub collation_name: Option<String>,
+}
+
+/// A FOREIGN KEY of CREATE TABLE as it is written, its names are resolved by the table
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForeignKeyClause {
+    pub constraint_name: Option<String>,
+    pub column_names: Vec<String>,
+    /// The name of the referenced table, with its schema if it is given
+    pub referenced_table_name: Vec<String>,
+    pub referenced_column_names: Vec<String>,
+    /// The rules of ON DELETE and ON UPDATE in upper case, as `SET NULL`
+    pub on_delete: Option<String>,
+    pub on_update: Option<String>,
+}
+
+/// `CREATE TABLE` with FOREIGN KEY constraints
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForeignKeyStatement {
+    /// The sql of the CREATE TABLE without the FOREIGN KEY constraints
+    pub statement_sql: String,
+    pub foreign_keys: Vec<ForeignKeyClause>,
+}
+
+fn is_word(token: &Token, value: &str) -> bool {
+    matches!(token, Token::Word(word) if word.quote_style.is_none() && word.value.to_uppercase() == value)
+}
+
+fn name_of(token: &Token) -> Option<String> {
+    match token {
+        Token::Word(word) => Some(word.value.to_lowercase()),
+        Token::SingleQuotedString(value) => Some(value.to_lowercase()),
+        _ => None,
+    }
+}
+
+/// `[DEFAULT] {CHARACTER SET | CHARSET | COLLATE} [=] name` at the position of the tokens which are not whitespaces,
+/// with the position after it and whether it is the COLLATE
+fn charset_option_at(tokens: &[&Token], position: usize) -> Option<(usize, bool, String)> {
+    let mut position = position;
+    if is_word(tokens.get(position)?, "DEFAULT") {
+        position += 1;
+    }
+    let is_collate = if is_word(tokens.get(position)?, "COLLATE") {
+        position += 1;
+        true
+    } else if is_word(tokens.get(position)?, "CHARSET") {
+        position += 1;
+        false
+    } else if is_word(tokens.get(position)?, "CHARACTER") && is_word(tokens.get(position + 1)?, "SET") {
+        position += 2;
+        false
+    } else {
+        return None;
+    };
+    if tokens.get(position)? == &&Token::Eq {
+        position += 1;
+    }
+    let name = name_of(tokens.get(position)?)?;
+    Some((position + 1, is_collate, name))
+}
+
+/// `(name, ...)` at the position of the tokens which are not whitespaces, with the position after it
+fn name_list_at(tokens: &[&Token], position: usize) -> Option<(usize, Vec<String>)> {
+    if tokens.get(position)? != &&Token::LParen {
+        return None;
+    }
+    let mut position = position + 1;
+    let mut names = vec![];
+    loop {
+        match tokens.get(position)? {
+            Token::Word(word) => names.push(word.value.clone()),
+            _ => return None,
+        }
+        position += 1;
+        match tokens.get(position)? {
+            Token::Comma => position += 1,
+            Token::RParen => return Some((position + 1, names)),
+            _ => return None,
+        }
+    }
+}
+
+/// `ON {DELETE | UPDATE} {RESTRICT | CASCADE | SET NULL | SET DEFAULT | NO ACTION}` at the position of the tokens
+/// which are not whitespaces, with the position after it, whether it is the ON DELETE and the rule
+fn referential_action_at(tokens: &[&Token], position: usize) -> Option<(usize, bool, String)> {
+    if !is_word(tokens.get(position)?, "ON") {
+        return None;
+    }
+    let is_delete = if is_word(tokens.get(position + 1)?, "DELETE") {
+        true
+    } else if is_word(tokens.get(position + 1)?, "UPDATE") {
+        false
+    } else {
+        return None;
+    };
+    let position = position + 2;
+    let first = tokens.get(position)?;
+    if is_word(first, "RESTRICT") || is_word(first, "CASCADE") {
+        return Some((position + 1, is_delete, first.to_string().to_uppercase()));
+    }
+    let second = tokens.get(position + 1)?;
+    if (is_word(first, "SET") && (is_word(second, "NULL") || is_word(second, "DEFAULT")))
+        || (is_word(first, "NO") && is_word(second, "ACTION"))
+    {
+        let rule = format!("{} {}", first, second).to_uppercase();
+        return Some((position + 2, is_delete, rule));
+    }
+    None
+}
+
+/// `[CONSTRAINT [name]] FOREIGN KEY [index_name] (column, ...) REFERENCES table (column, ...) [ON ...]` from the
+/// position to the end of the tokens which are not whitespaces, None if the tokens are another definition
+fn foreign_key_at(tokens: &[&Token], position: usize, end: usize) -> Option<ForeignKeyClause> {
+    let mut position = position;
+    let mut constraint_name = None;
+    if is_word(tokens.get(position)?, "CONSTRAINT") {
+        position += 1;
+        if !is_word(tokens.get(position)?, "FOREIGN") {
+            match tokens.get(position)? {
+                Token::Word(word) => constraint_name = Some(word.value.clone()),
+                _ => return None,
+            }
+            position += 1;
+        }
+    }
+    if !is_word(tokens.get(position)?, "FOREIGN") || !is_word(tokens.get(position + 1)?, "KEY") {
+        return None;
+    }
+    position += 2;
+    // the name of the index of the foreign key, the referenced table is found by its own indexes
+    if let Token::Word(_) = tokens.get(position)? {
+        position += 1;
+    }
+    let (next_position, column_names) = name_list_at(tokens, position)?;
+    position = next_position;
+
+    if !is_word(tokens.get(position)?, "REFERENCES") {
+        return None;
+    }
+    position += 1;
+    let mut referenced_table_name = vec![];
+    loop {
+        match tokens.get(position)? {
+            Token::Word(word) => referenced_table_name.push(word.value.clone()),
+            _ => return None,
+        }
+        position += 1;
+        if tokens.get(position)? != &&Token::Period {
+            break;
+        }
+        position += 1;
+    }
+    let (next_position, referenced_column_names) = name_list_at(tokens, position)?;
+    position = next_position;
+
+    let mut on_delete = None;
+    let mut on_update = None;
+    while position < end {
+        let (next_position, is_delete, rule) = referential_action_at(tokens, position)?;
+        if is_delete {
+            on_delete = Some(rule);
+        } else {
+            on_update = Some(rule);
+        }
+        position = next_position;
+    }
+    if position != end {
+        return None;
+    }
+
+    Some(ForeignKeyClause {
+        constraint_name,
+        column_names,
+        referenced_table_name,
+        referenced_column_names,
+        on_delete,
+        on_update,
+    })
+}
+
+fn tokens_to_sql(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+            _ => token.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+/// The sql parser does not know the ZEROFILL of the columns, it is removed from the sql
+/// and the names of its columns are kept. None if the sql is not a CREATE TABLE with a ZEROFILL
+pub fn parse_zerofill(sql: &str) -> Option<ZerofillStatement> {
+    let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, sql).tokenize().ok()?;
+
+    let mut words = tokens.iter().filter(|token| !matches!(token, Token::Whitespace(_)));
+    if !is_word(words.next()?, "CREATE") || !is_word(words.next()?, "TABLE") {
+        return None;
+    }
+
+    let mut new_tokens = vec![];
+    let mut column_names = vec![];
+    let mut depth = 0;
+    // the name of the column being defined, it is the first token of the column definition
+    let mut column_name: Option<String> = None;
+    let mut column_start = false;
+    for token in tokens {
+        match &token {
+            Token::Whitespace(_) => {}
+            Token::LParen => {
+                depth += 1;
+                column_start = depth == 1;
+            }
+            Token::RParen => {
+                depth -= 1;
+            }
+            Token::Comma if depth == 1 => {
+                column_start = true;
+            }
+            Token::Word(word) if depth == 1 && column_start => {
+                column_name = Some(word.value.clone());
+                column_start = false;
+            }
+            _ if depth == 1 && is_word(&token, "ZEROFILL") => {
+                if let Some(column_name) = &column_name {
+                    column_names.push(column_name.clone());
+                }
+                continue;
+            }
+            _ => {
+                column_start = false;
+            }
+        }
+        new_tokens.push(token);
+    }
+    if column_names.is_empty() {
+        return None;
+    }
+
+    Some(ZerofillStatement {
+        statement_sql: tokens_to_sql(&new_tokens),
+        column_names,
+    })
+}
+
+/// The sql parser does not know the CHARACTER SET of the columns and the CHARACTER SET and the COLLATE
+/// of the table, they are removed from the sql and kept. The COLLATE of the columns is known by the parser.
+/// None if the sql is not a CREATE TABLE with them
+pub fn parse_charset(sql: &str) -> Option<CharsetStatement> {
+    let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, sql).tokenize().ok()?;
+
+    // the indexes of the tokens which are not whitespaces
+    let indexes: Vec<usize> = (0..tokens.len())
+        .filter(|index| !matches!(tokens[*index], Token::Whitespace(_)))
+        .collect();
+    let words: Vec<&Token> = indexes.iter().map(|index| &tokens[*index]).collect();
+    if !is_word(words.first()?, "CREATE") || !is_word(words.get(1)?, "TABLE") {
+        return None;
+    }
+
+    let mut removed = vec![false; tokens.len()];
+    let mut column_character_sets = vec![];
+    let mut character_set_name = None;
+    let mut collation_name = None;
+    let mut depth = 0;
+    let mut body_closed = false;
+    let mut column_name: Option<String> = None;
+    let mut column_start = false;
+    let mut position = 0;
+    while position < words.len() {
+        let token = words[position];
+        let option = if depth == 1 && !column_start && !is_word(token, "DEFAULT") && !is_word(token, "COLLATE") {
+            charset_option_at(&words, position)
+        } else if depth == 0 && body_closed {
+            charset_option_at(&words, position)
+        } else {
+            None
+        };
+        if let Some((next_position, is_collate, name)) = option {
+            if depth == 1 {
+                column_character_sets.push((column_name.clone()?, name));
+            } else if is_collate {
+                collation_name = Some(name);
+            } else {
+                character_set_name = Some(name);
+            }
+            for removed_index in indexes[position]..=indexes[next_position - 1] {
+                removed[removed_index] = true;
+            }
+            position = next_position;
+            continue;
+        }
+
+        match token {
+            Token::LParen => {
+                depth += 1;
+                column_start = depth == 1;
+            }
+            Token::RParen => {
+                depth -= 1;
+                body_closed = depth == 0;
+            }
+            Token::Comma if depth == 1 => {
+                column_start = true;
+            }
+            Token::Word(word) if depth == 1 && column_start => {
+                column_name = Some(word.value.clone());
+                column_start = false;
+            }
+            _ => {
+                column_start = false;
+            }
+        }
+        position += 1;
+    }
+    if column_character_sets.is_empty() && character_set_name.is_none() && collation_name.is_none() {
+        return None;
+    }
+
+    let new_tokens = tokens
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !removed[*index])
+        .map(|(_, token)| token)
+        .collect::<Vec<_>>();
+    Some(CharsetStatement {
+        statement_sql: tokens_to_sql(&new_tokens).trim().to_string(),
+        column_character_sets,
+        character_set_name,
+        collation_name,
+    })
+}
+
+/// The sql parser does not know the FOREIGN KEY constraints of the table, they are removed from the sql
+/// and kept. None if the sql is not a CREATE TABLE with them
+pub fn parse_foreign_key(sql: &str) -> Option<ForeignKeyStatement> {
+    let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, sql).tokenize().ok()?;
+
+    // the indexes of the tokens which are not whitespaces
+    let indexes: Vec<usize> = (0..tokens.len())
+        .filter(|index| !matches!(tokens[*index], Token::Whitespace(_)))
+        .collect();
+    let words: Vec<&Token> = indexes.iter().map(|index| &tokens[*index]).collect();
+    if !is_word(words.first()?, "CREATE") || !is_word(words.get(1)?, "TABLE") {
+        return None;
+    }
+
+    // the definitions of the body of the table by their first positions and the positions after them
+    let body_start = words.iter().position(|token| token == &&Token::LParen)?;
+    let mut elements = vec![];
+    let mut depth = 0;
+    let mut element_start = body_start + 1;
+    for position in body_start + 1..words.len() {
+        match words[position] {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => {
+                elements.push((element_start, position));
+                break;
+            }
+            Token::RParen => depth -= 1,
+            Token::Comma if depth == 0 => {
+                elements.push((element_start, position));
+                element_start = position + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let mut removed = vec![false; tokens.len()];
+    let mut foreign_keys = vec![];
+    // whether a definition before is kept, the comma before a foreign key is removed with it if so,
+    // else the comma after it
+    let mut kept_before = false;
+    for (element_index, (start, end)) in elements.iter().enumerate() {
+        let foreign_key = if start < end { foreign_key_at(&words, *start, *end) } else { None };
+        let foreign_key = match foreign_key {
+            Some(foreign_key) => foreign_key,
+            None => {
+                kept_before = true;
+                continue;
+            }
+        };
+        foreign_keys.push(foreign_key);
+
+        let (first, last) = if kept_before {
+            (indexes[start - 1], indexes[end - 1])
+        } else if element_index + 1 < elements.len() {
+            // the whitespaces after the comma go with it
+            (indexes[*start], indexes[end + 1] - 1)
+        } else {
+            (indexes[*start], indexes[end - 1])
+        };
+        for removed_index in first..=last {
+            removed[removed_index] = true;
+        }
+    }
+    if foreign_keys.is_empty() {
+        return None;
+    }
+
+    let new_tokens = tokens
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !removed[*index])
+        .map(|(_, token)| token)
+        .collect::<Vec<_>>();
+    Some(ForeignKeyStatement {
+        statement_sql: tokens_to_sql(&new_tokens).trim().to_string(),
+        foreign_keys,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Some(ZerofillStatement {
+                statement_sql: "create table t1 (id int primary key, `code` INT(5)  not null default 1, name char default 'a''b')".to_string(),
+                column_names: vec!["code".to_string()],
+            }),
+            parse_zerofill("create table t1 (id int primary key, `code` INT(5) ZEROFILL not null default 1, name char default 'a''b')")
+        );
+        assert_eq!(
+            Some(vec!["a".to_string(), "b".to_string()]),
+            parse_zerofill("CREATE TABLE t1 (a int zerofill, b int(3) zerofill)").map(|statement| statement.column_names)
+        );
+        assert_eq!(None, parse_zerofill("create table t1 (a int, zerofill int)"));
+        assert_eq!(None, parse_zerofill("select 'zerofill'"));
+    }
+
+    #[test]
+    fn charset() {
+        assert_eq!(
+            Some(CharsetStatement {
+                statement_sql: "create table t1 (id int, name char  collate utf8_bin not null, charset char)".to_string(),
+                column_character_sets: vec![("name".to_string(), "utf8".to_string())],
+                character_set_name: Some("utf8mb4".to_string()),
+                collation_name: Some("utf8mb4_bin".to_string()),
+            }),
+            parse_charset(
+                "create table t1 (id int, name char CHARACTER SET utf8 collate utf8_bin not null, charset char) DEFAULT CHARSET=utf8mb4 COLLATE = utf8mb4_bin"
+            )
+        );
+        assert_eq!(
+            Some(CharsetStatement {
+                statement_sql: "CREATE TABLE t1 (a char)".to_string(),
+                column_character_sets: vec![],
+                character_set_name: Some("utf8".to_string()),
+                collation_name: None,
+            }),
+            parse_charset("CREATE TABLE t1 (a char) character set 'utf8'")
+        );
+        assert_eq!(None, parse_charset("create table t1 (a char collate utf8mb4_bin, charset int)"));
+        assert_eq!(None, parse_charset("select 'charset'"));
+    }
+
+    #[test]
+    fn foreign_key() {
+        assert_eq!(
+            Some(ForeignKeyStatement {
+                statement_sql: "create table child (id int primary key, pid int, name char, unique (name))".to_string(),
+                foreign_keys: vec![ForeignKeyClause {
+                    constraint_name: Some("fk_parent".to_string()),
+                    column_names: vec!["pid".to_string()],
+                    referenced_table_name: vec!["test".to_string(), "parent".to_string()],
+                    referenced_column_names: vec!["id".to_string()],
+                    on_delete: Some("CASCADE".to_string()),
+                    on_update: Some("SET NULL".to_string()),
+                }],
+            }),
+            parse_foreign_key(
+                "create table child (id int primary key, pid int, CONSTRAINT `fk_parent` FOREIGN KEY (pid) REFERENCES test.parent (id) ON DELETE CASCADE ON UPDATE SET NULL, name char, unique (name))"
+            )
+        );
+        assert_eq!(
+            Some(ForeignKeyStatement {
+                statement_sql: "CREATE TABLE c (a int, b int)".to_string(),
+                foreign_keys: vec![
+                    ForeignKeyClause {
+                        constraint_name: None,
+                        column_names: vec!["a".to_string(), "b".to_string()],
+                        referenced_table_name: vec!["p".to_string()],
+                        referenced_column_names: vec!["x".to_string(), "y".to_string()],
+                        on_delete: None,
+                        on_update: None,
+                    },
+                    ForeignKeyClause {
+                        constraint_name: None,
+                        column_names: vec!["b".to_string()],
+                        referenced_table_name: vec!["q".to_string()],
+                        referenced_column_names: vec!["z".to_string()],
+                        on_delete: Some("NO ACTION".to_string()),
+                        on_update: None,
+                    },
+                ],
+            }),
+            parse_foreign_key("CREATE TABLE c (FOREIGN KEY idx (a, b) REFERENCES p(x, y), CONSTRAINT FOREIGN KEY (b) REFERENCES q(z) ON DELETE NO ACTION, a int, b int)")
+        );
+        assert_eq!(None, parse_foreign_key("create table t1 (a int, constraint c1 unique (a))"));
+        assert_eq!(None, parse_foreign_key("select 'foreign key'"));
+    }
+}
@@ -0,0 +1,436 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, TimestampMicrosecondArray};
+use arrow::datatypes::DataType;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use datafusion::scalar::ScalarValue;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace};
+
+use crate::meta::meta_const;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntervalUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl IntervalUnit {
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit.to_uppercase().as_str() {
+            "SECOND" => Some(IntervalUnit::Second),
+            "MINUTE" => Some(IntervalUnit::Minute),
+            "HOUR" => Some(IntervalUnit::Hour),
+            "DAY" => Some(IntervalUnit::Day),
+            "WEEK" => Some(IntervalUnit::Week),
+            "MONTH" => Some(IntervalUnit::Month),
+            "QUARTER" => Some(IntervalUnit::Quarter),
+            "YEAR" => Some(IntervalUnit::Year),
+            _ => None,
+        }
+    }
+
+    /// The seconds of the unit, None for the units counted in months
+    fn seconds(&self) -> Option<i64> {
+        match self {
+            IntervalUnit::Second => Some(1),
+            IntervalUnit::Minute => Some(60),
+            IntervalUnit::Hour => Some(3600),
+            IntervalUnit::Day => Some(86400),
+            IntervalUnit::Week => Some(604800),
+            _ => None,
+        }
+    }
+
+    fn months(&self) -> Option<i64> {
+        match self {
+            IntervalUnit::Month => Some(1),
+            IntervalUnit::Quarter => Some(3),
+            IntervalUnit::Year => Some(12),
+            _ => None,
+        }
+    }
+}
+
+/// The DATE `2024-01-31` or the DATETIME `2024-01-31 10:20:30[.fraction]` of mysql,
+/// and whether it has the time part
+pub fn parse_datetime(text: &str) -> Option<(NaiveDateTime, bool)> {
+    let text = text.trim();
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f") {
+        return Some((datetime, true));
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .map(|date| (date.and_hms(0, 0, 0), false))
+}
+
+pub fn format_datetime(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+}
+
+/// The TIME `10:20:30[.fraction]` or `10:20` of mysql, the time of a DATETIME too.
+/// Only the times of a day are supported, not the hours beyond 24 nor the negative times
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    let text = text.trim();
+    if let Ok(time) = NaiveTime::parse_from_str(text, "%H:%M:%S%.f") {
+        return Some(time);
+    }
+    if let Ok(time) = NaiveTime::parse_from_str(text, "%H:%M") {
+        return Some(time);
+    }
+    match parse_datetime(text) {
+        Some((datetime, true)) => Some(datetime.time()),
+        _ => None,
+    }
+}
+
+pub fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+pub fn format_time(time: NaiveTime) -> String {
+    time.format("%H:%M:%S%.f").to_string()
+}
+
+/// The DATE of the arrow arrays is the days since 1970-01-01
+pub fn date_to_days(date: NaiveDate) -> i32 {
+    date.signed_duration_since(NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32
+}
+
+/// The DATETIME of the arrow arrays is the microseconds since 1970-01-01 00:00:00, without a time zone
+pub fn datetime_to_microseconds(datetime: NaiveDateTime) -> i64 {
+    datetime.timestamp() * 1_000_000 + datetime.timestamp_subsec_micros() as i64
+}
+
+fn microseconds_to_datetime(microseconds: i64) -> Option<NaiveDateTime> {
+    NaiveDateTime::from_timestamp_opt(microseconds.div_euclid(1_000_000), (microseconds.rem_euclid(1_000_000) * 1000) as u32)
+}
+
+/// The value of the DATETIME and the TIMESTAMP columns
+pub fn timestamp_scalar_value(datetime: NaiveDateTime) -> ScalarValue {
+    let array: ArrayRef = Arc::new(TimestampMicrosecondArray::from_vec(vec![datetime_to_microseconds(datetime)], None));
+    ScalarValue::try_from_array(&array, 0).unwrap()
+}
+
+/// The value of the DATE or the DATETIME and the TIMESTAMP columns of the text as mysql writes it, None if it is not a date
+pub fn text_to_temporal_value(text: &str, data_type: &DataType) -> Option<ScalarValue> {
+    let (datetime, _) = parse_datetime(text)?;
+    match data_type {
+        DataType::Date32 => Some(ScalarValue::Date32(Some(date_to_days(datetime.date())))),
+        DataType::Timestamp(_, _) => Some(timestamp_scalar_value(datetime)),
+        _ => None,
+    }
+}
+
+/// The datetime of the value of a date or a timestamp of the arrow arrays, and whether it has the time part
+pub fn scalar_value_to_datetime(scalar_value: &ScalarValue) -> Option<(NaiveDateTime, bool)> {
+    let datetime = match scalar_value {
+        ScalarValue::Date32(Some(days)) => {
+            let date = NaiveDate::from_ymd(1970, 1, 1).checked_add_signed(Duration::days(*days as i64))?;
+            return Some((date.and_hms(0, 0, 0), false));
+        }
+        ScalarValue::Date64(Some(milliseconds)) => {
+            let datetime = microseconds_to_datetime(milliseconds.checked_mul(1000)?)?;
+            return Some((datetime.date().and_hms(0, 0, 0), false));
+        }
+        ScalarValue::TimestampSecond(Some(seconds), ..) => microseconds_to_datetime(seconds.checked_mul(1_000_000)?),
+        ScalarValue::TimestampMillisecond(Some(milliseconds), ..) => microseconds_to_datetime(milliseconds.checked_mul(1000)?),
+        ScalarValue::TimestampMicrosecond(Some(microseconds), ..) => microseconds_to_datetime(*microseconds),
+        ScalarValue::TimestampNanosecond(Some(nanoseconds), ..) => microseconds_to_datetime(nanoseconds.div_euclid(1000)),
+        _ => None,
+    };
+    datetime.map(|datetime| (datetime, true))
+}
+
+/// The text of the value of a date or a timestamp as mysql shows it, `2024-01-31` or `2024-01-31 10:20:30[.fraction]`
+pub fn format_temporal_value(scalar_value: &ScalarValue) -> Option<String> {
+    scalar_value_to_datetime(scalar_value).map(|(datetime, has_time)| {
+        if has_time {
+            format_datetime(datetime)
+        } else {
+            format_date(datetime.date())
+        }
+    })
+}
+
+/// Add the months, the day past the end of the new month is the last day of it,
+/// so 2024-01-31 plus one month is 2024-02-29
+fn add_months(datetime: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let total_months = datetime.year() as i64 * 12 + datetime.month0() as i64 + months;
+    let year = total_months.div_euclid(12) as i32;
+    let month = total_months.rem_euclid(12) as u32 + 1;
+
+    let first_of_next_month = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }?;
+    let last_day = first_of_next_month.pred_opt()?.day();
+    let date = NaiveDate::from_ymd_opt(year, month, datetime.day().min(last_day))?;
+    Some(date.and_time(datetime.time()))
+}
+
+/// DATE_ADD(date, INTERVAL value unit) of mysql, NULL for the invalid date.
+/// The DATE plus the days, weeks, months, quarters or years is a DATE, the others are a DATETIME
+pub fn date_add(text: &str, value: i64, unit: IntervalUnit) -> Option<String> {
+    let (datetime, has_time) = parse_datetime(text)?;
+    let new_datetime = match (unit.seconds(), unit.months()) {
+        (Some(seconds), _) => datetime.checked_add_signed(Duration::seconds(value.checked_mul(seconds)?))?,
+        (_, Some(months)) => add_months(datetime, value.checked_mul(months)?)?,
+        _ => return None,
+    };
+
+    let date_unit = !matches!(unit, IntervalUnit::Second | IntervalUnit::Minute | IntervalUnit::Hour);
+    if !has_time && date_unit {
+        Some(new_datetime.format("%Y-%m-%d").to_string())
+    } else {
+        Some(format_datetime(new_datetime))
+    }
+}
+
+/// DATEDIFF(a, b) of mysql, the days from b to a, the time parts are ignored
+pub fn datediff(a: &str, b: &str) -> Option<i64> {
+    let (a, _) = parse_datetime(a)?;
+    let (b, _) = parse_datetime(b)?;
+    Some((a.date() - b.date()).num_days())
+}
+
+/// TIMESTAMPDIFF(unit, a, b) of mysql, the whole units from a to b.
+/// A month is counted when the day and the time of b reach the ones of a
+pub fn timestampdiff(unit: IntervalUnit, a: &str, b: &str) -> Option<i64> {
+    let (a, _) = parse_datetime(a)?;
+    let (b, _) = parse_datetime(b)?;
+
+    if let Some(seconds) = unit.seconds() {
+        return Some((b - a).num_seconds() / seconds);
+    }
+
+    let mut months = (b.year() as i64 * 12 + b.month() as i64) - (a.year() as i64 * 12 + a.month() as i64);
+    let a_rest = (a.day(), a.time());
+    let b_rest = (b.day(), b.time());
+    if months > 0 && b_rest < a_rest {
+        months -= 1;
+    } else if months < 0 && b_rest > a_rest {
+        months += 1;
+    }
+    Some(months / unit.months()?)
+}
+
+fn next_token(tokens: &[Token], index: usize) -> Option<&Token> {
+    tokens[index + 1..].iter().find(|token| !matches!(token, Token::Whitespace(_)))
+}
+
+/// The udf of the current date and time function, the keyword forms are without the parentheses
+fn current_time_function_name(upper_value: &str, is_call: bool) -> Option<&'static str> {
+    match upper_value {
+        "CURRENT_TIMESTAMP" | "LOCALTIME" | "LOCALTIMESTAMP" => Some(meta_const::FUNCTION_NAME_MYSQL_NOW),
+        "NOW" if is_call => Some(meta_const::FUNCTION_NAME_MYSQL_NOW),
+        "CURRENT_DATE" => Some(meta_const::FUNCTION_NAME_MYSQL_CURRENT_DATE),
+        "CURDATE" if is_call => Some(meta_const::FUNCTION_NAME_MYSQL_CURRENT_DATE),
+        "CURRENT_TIME" => Some(meta_const::FUNCTION_NAME_MYSQL_CURRENT_TIME),
+        "CURTIME" if is_call => Some(meta_const::FUNCTION_NAME_MYSQL_CURRENT_TIME),
+        _ => None,
+    }
+}
+
+/// CURRENT_DATE of mysql, `2024-01-31`
+pub fn current_date(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%d").to_string()
+}
+
+/// CURRENT_TIME of mysql, `10:20:30`
+pub fn current_time(datetime: NaiveDateTime) -> String {
+    datetime.format("%H:%M:%S").to_string()
+}
+
+/// NOW() and CURRENT_TIMESTAMP of mysql, `2024-01-31 10:20:30`
+pub fn current_timestamp(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// The interval and the unit of the date functions are not expressions DataFusion plans,
+/// rewrite `DATE_ADD(d, INTERVAL n unit)` to `mysql_date_add(d, n, 'unit')`, the same for DATE_SUB,
+/// and `TIMESTAMPDIFF(unit, a, b)` to `mysql_timestampdiff('unit', a, b)` before parsing.
+/// The keywords `CURRENT_DATE`, `CURRENT_TIME` and `CURRENT_TIMESTAMP` are the calls of the same udfs as
+/// `CURDATE()`, `CURTIME()` and `NOW()`
+pub fn rewrite_date_functions(sql: &str) -> String {
+    let dialect = GenericDialect {};
+    let tokens = match Tokenizer::new(&dialect, sql).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return sql.to_string(),
+    };
+
+    let mut new_tokens = vec![];
+    let mut changed = false;
+    let mut depth = 0;
+    // the depths of the parentheses of the DATE_ADD and DATE_SUB calls
+    let mut date_add_depths: Vec<usize> = vec![];
+    // the depth of the INTERVAL being rewritten
+    let mut interval_depth = None;
+    let mut quote_next_unit = false;
+    let mut skip_whitespace = false;
+    for index in 0..tokens.len() {
+        let token = tokens[index].clone();
+        if skip_whitespace && matches!(token, Token::Whitespace(_)) {
+            continue;
+        }
+        skip_whitespace = false;
+        match &token {
+            Token::Word(word) if word.quote_style.is_none() => {
+                let upper_value = word.value.to_uppercase();
+                let is_call = matches!(next_token(&tokens, index), Some(Token::LParen));
+                let is_qualified = matches!(
+                    new_tokens.iter().rev().find(|token| !matches!(token, Token::Whitespace(_))),
+                    Some(Token::Period)
+                );
+                if let Some(function_name) = current_time_function_name(upper_value.as_str(), is_call) {
+                    if !is_qualified {
+                        new_tokens.push(Token::make_word(function_name, None));
+                        if !is_call {
+                            new_tokens.push(Token::LParen);
+                            new_tokens.push(Token::RParen);
+                        }
+                        changed = true;
+                        continue;
+                    }
+                }
+                if is_call && (upper_value == "DATE_ADD" || upper_value == "DATE_SUB") {
+                    let function_name = if upper_value == "DATE_ADD" {
+                        meta_const::FUNCTION_NAME_MYSQL_DATE_ADD
+                    } else {
+                        meta_const::FUNCTION_NAME_MYSQL_DATE_SUB
+                    };
+                    new_tokens.push(Token::make_word(function_name, None));
+                    date_add_depths.push(depth + 1);
+                    changed = true;
+                    continue;
+                }
+                if is_call && upper_value == "TIMESTAMPDIFF" {
+                    new_tokens.push(Token::make_word(meta_const::FUNCTION_NAME_MYSQL_TIMESTAMPDIFF, None));
+                    quote_next_unit = true;
+                    changed = true;
+                    continue;
+                }
+                if quote_next_unit {
+                    new_tokens.push(Token::SingleQuotedString(upper_value));
+                    quote_next_unit = false;
+                    continue;
+                }
+                if upper_value == "INTERVAL" && date_add_depths.last() == Some(&depth) {
+                    interval_depth = Some(depth);
+                    skip_whitespace = true;
+                    continue;
+                }
+                if interval_depth == Some(depth) && IntervalUnit::parse(upper_value.as_str()).is_some() {
+                    if let Some(Token::RParen) = next_token(&tokens, index) {
+                        while let Some(Token::Whitespace(_)) = new_tokens.last() {
+                            new_tokens.pop();
+                        }
+                        new_tokens.push(Token::Comma);
+                        new_tokens.push(Token::Whitespace(Whitespace::Space));
+                        new_tokens.push(Token::SingleQuotedString(upper_value));
+                        interval_depth = None;
+                        continue;
+                    }
+                }
+            }
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                if date_add_depths.last() == Some(&depth) {
+                    date_add_depths.pop();
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+        new_tokens.push(token);
+    }
+
+    if !changed {
+        return sql.to_string();
+    }
+
+    new_tokens.iter().map(|token| match token {
+        Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+        _ => token.to_string(),
+    }).collect::<Vec<String>>().join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::TimeUnit;
+
+    use super::*;
+
+    #[test]
+    fn add_interval() {
+        assert_eq!(Some("2024-02-02".to_string()), date_add("2024-01-30", 3, IntervalUnit::Day));
+        assert_eq!(Some("2024-02-29".to_string()), date_add("2024-01-31", 1, IntervalUnit::Month));
+        assert_eq!(Some("2023-02-28".to_string()), date_add("2024-02-29", -1, IntervalUnit::Year));
+        assert_eq!(Some("2024-01-01 01:30:00".to_string()), date_add("2023-12-31 23:30:00", 2, IntervalUnit::Hour));
+        assert_eq!(Some("2024-01-30 00:00:10".to_string()), date_add("2024-01-30", 10, IntervalUnit::Second));
+        assert_eq!(None, date_add("2024-02-30", 1, IntervalUnit::Day));
+    }
+
+    #[test]
+    fn diff() {
+        assert_eq!(Some(2), datediff("2024-03-01 23:59:59", "2024-02-28"));
+        assert_eq!(Some(-2), datediff("2024-02-28", "2024-03-01"));
+        assert_eq!(Some(0), timestampdiff(IntervalUnit::Month, "2024-01-31", "2024-02-29"));
+        assert_eq!(Some(1), timestampdiff(IntervalUnit::Month, "2024-01-29", "2024-02-29"));
+        assert_eq!(Some(-1), timestampdiff(IntervalUnit::Month, "2024-02-29", "2024-01-29"));
+        assert_eq!(Some(90), timestampdiff(IntervalUnit::Minute, "2024-01-01 10:00:00", "2024-01-01 11:30:59"));
+    }
+
+    #[test]
+    fn rewrite_date() {
+        assert_eq!(
+            "select mysql_date_add(created, 1 + 2, 'DAY') from t",
+            rewrite_date_functions("select date_add(created, interval 1 + 2 day) from t")
+        );
+        assert_eq!(
+            "select mysql_timestampdiff('MONTH', a, b), datediff(a, b) from t",
+            rewrite_date_functions("select timestampdiff(month, a, b), datediff(a, b) from t")
+        );
+        assert_eq!(
+            "select mysql_current_date(), mysql_now(), mysql_current_time(), t.current_date from t",
+            rewrite_date_functions("select current_date, now(), CURTIME(), t.current_date from t")
+        );
+    }
+
+    #[test]
+    fn parse_temporal() {
+        assert_eq!(Some("10:20:30".to_string()), parse_time("10:20:30").map(format_time));
+        assert_eq!(Some("10:20:00".to_string()), parse_time(" 10:20 ").map(format_time));
+        assert_eq!(Some("10:20:30.500".to_string()), parse_time("2024-01-31 10:20:30.5").map(format_time));
+        assert_eq!(None, parse_time("2024-01-31"));
+        assert_eq!(None, parse_time("25:00:00"));
+        assert_eq!(Some("2024-01-31".to_string()), parse_datetime("2024-01-31 10:20:30").map(|(datetime, _)| format_date(datetime.date())));
+    }
+
+    #[test]
+    fn temporal_value() {
+        let date = text_to_temporal_value("2024-01-31 10:20:30", &DataType::Date32);
+        assert_eq!(Some(ScalarValue::Date32(Some(19753))), date);
+        assert_eq!(Some("2024-01-31".to_string()), date.as_ref().and_then(format_temporal_value));
+
+        let timestamp = text_to_temporal_value("1969-12-31 23:59:59.5", &DataType::Timestamp(TimeUnit::Microsecond, None)).unwrap();
+        assert_eq!(Some("1969-12-31 23:59:59.500".to_string()), format_temporal_value(&timestamp));
+        assert_eq!(None, text_to_temporal_value("2024-02-30", &DataType::Date32));
+        assert_eq!(None, format_temporal_value(&ScalarValue::Utf8(Some("2024-01-31".to_string()))));
+    }
+
+    #[test]
+    fn current() {
+        let datetime = NaiveDate::from_ymd(2024, 1, 31).and_hms_milli(10, 20, 30, 500);
+        assert_eq!("2024-01-31", current_date(datetime));
+        assert_eq!("10:20:30", current_time(datetime));
+        assert_eq!("2024-01-31 10:20:30", current_timestamp(datetime));
+    }
+}
@@ -4,13 +4,14 @@ use datafusion::logical_plan::{Expr};
 use crate::core::core_util;
 use crate::store::reader::reader_util::{RangeValue, PointType, TableIndex, RangePoint};
 use datafusion::scalar::ScalarValue;
-use crate::mysql::error::{MysqlResult, MysqlError};
-use crate::meta::meta_const::MYSQL_ERROR_CODE_UNKNOWN_ERROR;
-use sqlparser::ast::{ObjectName, Ident};
+use crate::mysql::error::MysqlResult;
+use sqlparser::ast::{DataType as SQLDataType, ObjectName, Ident};
 use crate::meta::meta_const;
-use crate::meta::meta_def::{TableDef, TableIndexDef};
+use crate::meta::meta_util;
+use crate::meta::meta_def::{IndexDef, SparrowColumnDef, TableDef, TableIndexDef, TableOptionDef};
 use std::collections::HashMap;
 use crate::util::convert::ToIdent;
+use crate::util::{date_function, decimal};
 
 pub fn scan_column_name(schema_name: &str) -> Box<[u8]> {
     let mut k = String::from("/Schema/ColumnName/ToIndex");
@@ -65,11 +66,21 @@ pub fn create_table_key(schema_name: String) -> Box<[u8]> {
     key(k.as_bytes())
 }
 
-pub fn create_record_rowid(full_table_name: ObjectName, uuid: &str) -> String {
+/// The last table id handed out, the ids of the tables are never reused
+pub fn create_current_table_id() -> String {
+    String::from("/Schema/TableId/Current")
+}
+
+/// The table id chosen for the table migrating to the table ids, kept until all its keys are moved
+pub fn create_migrating_table_id(table_option: &TableOptionDef) -> String {
+    format!("/Schema/TableId/Migrating/{}", table_option.full_table_name)
+}
+
+pub fn create_record_rowid(table_option: &TableOptionDef, uuid: &str) -> String {
     let mut k = String::from("/Table/rowid");
 
     k.push_str("/");
-    k.push_str(full_table_name.to_string().as_str());
+    k.push_str(table_option.key_name().as_str());
 
     k.push_str("/");
     k.push_str(uuid);
@@ -77,11 +88,11 @@ pub fn create_record_rowid(full_table_name: ObjectName, uuid: &str) -> String {
     k
 }
 
-pub fn create_column_rowid_key(full_table_name: ObjectName, uuid: &str) -> String {
+pub fn create_column_rowid_key(table_option: &TableOptionDef, uuid: &str) -> String {
     let mut k = String::from("/Table/rowid");
 
     k.push_str("/");
-    k.push_str(full_table_name.to_string().as_str());
+    k.push_str(table_option.key_name().as_str());
 
     k.push_str("/");
     k.push_str(uuid);
@@ -89,11 +100,11 @@ pub fn create_column_rowid_key(full_table_name: ObjectName, uuid: &str) -> Strin
     k
 }
 
-pub fn create_column_key(full_table_name: ObjectName, orm_id: i64, uuid: &str) -> String {
+pub fn create_column_key(table_option: &TableOptionDef, orm_id: i64, uuid: &str) -> String {
     let mut k = String::from("/Table/index/column");
 
     k.push_str("/");
-    k.push_str(full_table_name.to_string().as_str());
+    k.push_str(table_option.key_name().as_str());
 
     k.push_str("/");
     k.push_str(orm_id.to_string().as_str());
@@ -104,6 +115,17 @@ pub fn create_column_key(full_table_name: ObjectName, orm_id: i64, uuid: &str) -
     k
 }
 
+/// The primary key part of the primary index key, used as the rowid of a table without rowid
+pub fn create_rowid_with_primary_key(table_option: &TableOptionDef, primary_index: &IndexDef) -> String {
+    let mut prefix = String::from("/Table/index/key/");
+    prefix.push_str(table_option.key_name().as_str());
+    prefix.push_str("/");
+    prefix.push_str(primary_index.index_name.as_str());
+    prefix.push_str("/");
+
+    primary_index.index_key.trim_start_matches(prefix.as_str()).to_string()
+}
+
 pub fn parse_record_rowid(key: String) -> Result<String> {
     let v: Vec<&str> = key.split("/").collect();
     if v.len() < 6 {
@@ -115,10 +137,77 @@ pub fn parse_record_rowid(key: String) -> Result<String> {
     Ok(v[5].to_string())
 }
 
-pub fn scan_record_rowid(full_table_name: ObjectName) -> String {
+pub fn create_auto_increment_key(table_option: &TableOptionDef) -> String {
+    let mut k = String::from("/Table/auto_increment/");
+
+    k.push_str(table_option.key_name().as_str());
+
+    k
+}
+
+pub fn create_table_statistics_key(table_option: &TableOptionDef) -> String {
+    let mut k = String::from("/Table/statistics/");
+
+    k.push_str(table_option.key_name().as_str());
+
+    k
+}
+
+/// The prefixes of the keys of the rows, their versions are kept by their commit timestamps for the snapshots
+pub const VERSIONED_KEY_PREFIXES: [&str; 2] = ["/Table/rowid/", "/Table/index/"];
+
+/// The prefix of the versions of the keys, the version key is the prefix, the key and the commit timestamp
+pub const VERSION_KEY_PREFIX: &str = "/Version";
+
+pub fn is_versioned_key(key: &str) -> bool {
+    VERSIONED_KEY_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+}
+
+/// The version of the key committed at the timestamp, the timestamp has a fixed width
+/// so the versions of a key are in the order of their commits
+pub fn create_version_key(key: &str, commit_ts: u64) -> String {
+    format!("{}{}@{:020}", VERSION_KEY_PREFIX, key, commit_ts)
+}
+
+/// The prefix of all the versions of the key
+pub fn scan_version_key(key: &str) -> String {
+    format!("{}{}@", VERSION_KEY_PREFIX, key)
+}
+
+/// The key and the commit timestamp of the version key
+pub fn parse_version_key(version_key: &str) -> Option<(String, u64)> {
+    let key = version_key.strip_prefix(VERSION_KEY_PREFIX)?;
+    let (key, commit_ts) = key.rsplit_once('@')?;
+    Some((key.to_string(), commit_ts.parse::<u64>().ok()?))
+}
+
+/// The timestamp of the last commit, the next commit takes the one after it
+pub fn create_commit_ts_key() -> String {
+    String::from("/System/mvcc/commit_ts")
+}
+
+pub fn scan_record_rowid(table_option: &TableOptionDef) -> String {
     let mut k = String::from("/Table/rowid/");
 
-    k.push_str(full_table_name.to_string().as_str());
+    k.push_str(table_option.key_name().as_str());
+    k.push_str("/");
+
+    k
+}
+
+pub fn scan_column_key(table_option: &TableOptionDef) -> String {
+    let mut k = String::from("/Table/index/column/");
+
+    k.push_str(table_option.key_name().as_str());
+    k.push_str("/");
+
+    k
+}
+
+pub fn scan_table_index_key(table_option: &TableOptionDef) -> String {
+    let mut k = String::from("/Table/index/key/");
+
+    k.push_str(table_option.key_name().as_str());
     k.push_str("/");
 
     k
@@ -126,58 +215,246 @@ pub fn scan_record_rowid(full_table_name: ObjectName) -> String {
 
 pub fn create_table_index_key(table: TableDef, table_index: TableIndexDef, column_value_map: HashMap<Ident, ScalarValue>) -> MysqlResult<String> {
     let mut k = String::from("/Table/index/key/");
-    k.push_str(table.option.full_table_name.to_string().as_str());
+    k.push_str(table.option.key_name().as_str());
     k.push_str("/");
     k.push_str(table_index.index_name.as_str());
     k.push_str("/");
 
+    let mut column_values = vec![];
     for column_name in table_index.column_name_list {
         let sparrow_column = table.column.get_sparrow_column(column_name.clone()).unwrap();
         let column_value = column_value_map.get(&column_name).unwrap();
+        column_values.push((sparrow_column, column_value.clone()));
+    }
+    k.push_str(create_index_column_values(&column_values).as_str());
+
+    Ok(k)
+}
 
+/// The part of the index key after the index name, the values of the leading columns of the index
+pub fn create_index_column_values(column_values: &[(SparrowColumnDef, ScalarValue)]) -> String {
+    let mut k = String::new();
+    for (sparrow_column, column_value) in column_values {
         let column_store_id = sparrow_column.store_id;
 
         k.push_str(column_store_id.to_string().as_str());
         k.push_str("/");
 
-        match column_value.clone() {
-            ScalarValue::Int32(limit) => {
-                if let Some(value) = limit {
-                    let new_value = (value as u64) ^ meta_const::SIGN_MASK;
-                    k.push_str("1/");
-                    k.push_str(new_value.to_string().as_str());
-                    k.push_str("/");
-                } else {
-                    k.push_str("0/");
-                }
+        match encode_index_value(&sparrow_column.sql_column.data_type, column_value) {
+            Some(value) => {
+                k.push_str("1/");
+                k.push_str(value.as_str());
+                k.push_str("/");
             }
-            ScalarValue::Int64(limit) => {
-                if let Some(value) = limit {
-                    let new_value = (value as u64) ^ meta_const::SIGN_MASK;
-                    k.push_str("1/");
-                    k.push_str(new_value.to_string().as_str());
-                    k.push_str("/");
-                } else {
-                    k.push_str("0/");
-                }
+            None => {
+                k.push_str("0/");
+            }
+        }
+    }
+
+    k
+}
+
+/// How the values of a column are ordered in the index keys, the keys are compared as bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum IndexKeyType {
+    /// The integer with the sign bit flipped in 20 digits, the negative values are before the positive ones
+    Integer,
+    /// The bits of the float flipped as the ones of the integer, all of them for a negative value
+    Float,
+    /// The DATETIME and the TIMESTAMP are stored as text of a fixed width, `2024-01-31 10:20:30`
+    Datetime,
+    /// The DATE is stored as `2024-01-31`
+    Date,
+    /// The unscaled value of the scale of the column with the sign bit flipped in 39 digits
+    Decimal(usize),
+    /// The bytes in the upper case hex, as they are stored
+    Binary,
+    Text,
+}
+
+fn index_key_type(sql_data_type: &SQLDataType) -> IndexKeyType {
+    match sql_data_type {
+        SQLDataType::TinyInt(_) | SQLDataType::Int(_) => IndexKeyType::Integer,
+        SQLDataType::Float(_) | SQLDataType::Double => IndexKeyType::Float,
+        SQLDataType::Timestamp => IndexKeyType::Datetime,
+        SQLDataType::Date => IndexKeyType::Date,
+        SQLDataType::Decimal(precision, scale) => IndexKeyType::Decimal(decimal::precision_scale(precision, scale).1),
+        SQLDataType::Custom(_) if meta_util::is_datetime_data_type(sql_data_type) => IndexKeyType::Datetime,
+        _ if meta_util::is_binary_data_type(sql_data_type) => IndexKeyType::Binary,
+        _ => IndexKeyType::Text,
+    }
+}
+
+/// The values of the column are kept as their text in the index keys, a LIKE prefix is a range of the keys
+pub fn is_text_index_key(sql_data_type: &SQLDataType) -> bool {
+    matches!(index_key_type(sql_data_type), IndexKeyType::Text)
+}
+
+fn encode_integer(value: i64) -> String {
+    format!("{:020}", (value as u64) ^ meta_const::SIGN_MASK)
+}
+
+fn encode_float(value: f64) -> String {
+    // -0 is 0
+    let bits = if value == 0.0 { 0 } else { value.to_bits() };
+    let bits = if bits & meta_const::SIGN_MASK == 0 {
+        bits ^ meta_const::SIGN_MASK
+    } else {
+        !bits
+    };
+    format!("{:020}", bits)
+}
+
+fn encode_decimal(unscaled: i128) -> String {
+    format!("{:039}", (unscaled as u128) ^ meta_const::DECIMAL_SIGN_MASK)
+}
+
+fn scalar_value_to_i64(scalar_value: &ScalarValue) -> Option<i64> {
+    match scalar_value {
+        ScalarValue::Int8(Some(value)) => Some(*value as i64),
+        ScalarValue::Int16(Some(value)) => Some(*value as i64),
+        ScalarValue::Int32(Some(value)) => Some(*value as i64),
+        ScalarValue::Int64(Some(value)) => Some(*value),
+        ScalarValue::UInt8(Some(value)) => Some(*value as i64),
+        ScalarValue::UInt16(Some(value)) => Some(*value as i64),
+        ScalarValue::UInt32(Some(value)) => Some(*value as i64),
+        ScalarValue::UInt64(Some(value)) => i64::try_from(*value).ok(),
+        ScalarValue::Utf8(Some(value)) => value.trim().parse::<i64>().ok(),
+        ScalarValue::Float32(_) | ScalarValue::Float64(_) => scalar_value_to_f64(scalar_value)
+            .filter(|value| value.fract() == 0.0 && *value >= i64::MIN as f64 && *value < i64::MAX as f64)
+            .map(|value| value as i64),
+        _ => None,
+    }
+}
+
+fn scalar_value_to_f64(scalar_value: &ScalarValue) -> Option<f64> {
+    let value = match scalar_value {
+        ScalarValue::Int8(Some(value)) => *value as f64,
+        ScalarValue::Int16(Some(value)) => *value as f64,
+        ScalarValue::Int32(Some(value)) => *value as f64,
+        ScalarValue::Int64(Some(value)) => *value as f64,
+        ScalarValue::UInt8(Some(value)) => *value as f64,
+        ScalarValue::UInt16(Some(value)) => *value as f64,
+        ScalarValue::UInt32(Some(value)) => *value as f64,
+        ScalarValue::UInt64(Some(value)) => *value as f64,
+        ScalarValue::Float32(Some(value)) => *value as f64,
+        ScalarValue::Float64(Some(value)) => *value,
+        ScalarValue::Utf8(Some(value)) => value.trim().parse::<f64>().ok()?,
+        _ => return None,
+    };
+    Some(value).filter(|value| !value.is_nan())
+}
+
+/// The bytes of the value of a binary column, the text is its utf8 bytes
+fn scalar_value_to_bytes(scalar_value: &ScalarValue) -> Option<&[u8]> {
+    match scalar_value {
+        ScalarValue::Binary(Some(value)) | ScalarValue::LargeBinary(Some(value)) => Some(value.as_slice()),
+        ScalarValue::Utf8(Some(value)) => Some(value.as_bytes()),
+        _ => None,
+    }
+}
+
+/// The unscaled value of the DECIMAL of the scale, and whether the value has no digits past the scale
+fn scalar_value_to_decimal(scalar_value: &ScalarValue, scale: usize) -> Option<(i128, bool)> {
+    let text = match scalar_value {
+        ScalarValue::Decimal128(Some(value), _, value_scale) => {
+            if *value_scale <= scale {
+                let factor = 10_i128.checked_pow((scale - value_scale) as u32)?;
+                return value.checked_mul(factor).map(|value| (value, true));
             }
-            ScalarValue::Utf8(limit) => {
-                if let Some(value) = limit {
-                    k.push_str("1/");
-                    k.push_str(value.as_str());
-                    k.push_str("/");
+            decimal::format_decimal(*value, *value_scale)
+        }
+        ScalarValue::Utf8(Some(value)) => value.trim().to_string(),
+        ScalarValue::Float32(_) | ScalarValue::Float64(_) => {
+            scalar_value_to_f64(scalar_value).filter(|value| value.is_finite())?.to_string()
+        }
+        _ => scalar_value_to_i64(scalar_value)?.to_string(),
+    };
+    let unscaled = decimal::parse_decimal(text.as_str(), decimal::MAX_PRECISION, scale).ok()?;
+    // the text with an exponent is taken as rounded
+    let exact = match text.split_once('.') {
+        _ if text.contains(|c| c == 'e' || c == 'E') => false,
+        Some((_, fraction)) => fraction.chars().skip(scale).all(|c| c == '0'),
+        None => true,
+    };
+    Some((unscaled, exact))
+}
+
+/// The value of the column in the index key, the keys are in the order of the values of the column. The value
+/// which is not one of the type of the column is kept as its text. None for NULL
+pub fn encode_index_value(sql_data_type: &SQLDataType, scalar_value: &ScalarValue) -> Option<String> {
+    if scalar_value.is_null() {
+        return None;
+    }
+    let value = match index_key_type(sql_data_type) {
+        IndexKeyType::Integer => scalar_value_to_i64(scalar_value).map(encode_integer),
+        IndexKeyType::Float => scalar_value_to_f64(scalar_value).map(encode_float),
+        IndexKeyType::Datetime => date_function::scalar_value_to_datetime(scalar_value).map(|(datetime, _)| date_function::format_datetime(datetime)),
+        IndexKeyType::Date => date_function::scalar_value_to_datetime(scalar_value).map(|(datetime, _)| date_function::format_date(datetime.date())),
+        IndexKeyType::Decimal(scale) => scalar_value_to_decimal(scalar_value, scale).map(|(value, _)| encode_decimal(value)),
+        IndexKeyType::Binary => scalar_value_to_bytes(scalar_value).map(hex::encode_upper),
+        IndexKeyType::Text => None,
+    };
+    match (value, scalar_value) {
+        (Some(value), _) => Some(value),
+        (None, ScalarValue::Utf8(Some(value))) => Some(value.clone()),
+        (None, scalar_value) => Some(scalar_value.to_string()),
+    }
+}
+
+/// The bound of a range over the index keys of the column. The value between two values of the column is
+/// moved to the closest one inside the range, `id > 10.5` starts at 11, the value rounded to the scale of a DECIMAL
+/// column moves the bound one step outside, and a DATE bounds all the day of a DATETIME column. None if the value is not comparable with the values of the column, the side is not bounded
+pub fn encode_index_bound(sql_data_type: &SQLDataType, scalar_value: &ScalarValue, point_type: PointType, is_start: bool) -> Option<(String, PointType)> {
+    match index_key_type(sql_data_type) {
+        IndexKeyType::Integer => {
+            if let Some(value) = scalar_value_to_i64(scalar_value) {
+                return Some((encode_integer(value), point_type));
+            }
+            let value = scalar_value_to_f64(scalar_value)?;
+            let value = if is_start { value.ceil() } else { value.floor() };
+            // the value out of the integers saturates, the bound takes the last integer
+            Some((encode_integer(value as i64), PointType::Closed))
+        }
+        IndexKeyType::Float => scalar_value_to_f64(scalar_value).map(|value| (encode_float(value), point_type)),
+        IndexKeyType::Datetime => match scalar_value {
+            ScalarValue::Utf8(Some(value)) => Some((value.clone(), point_type)),
+            _ => {
+                let (datetime, has_time) = date_function::scalar_value_to_datetime(scalar_value)?;
+                let date = datetime.date();
+                if has_time {
+                    Some((date_function::format_datetime(datetime), point_type))
+                } else if is_start {
+                    Some((date_function::format_datetime(date.and_hms(0, 0, 0)), PointType::Closed))
                 } else {
-                    k.push_str("0/");
+                    let next_date = date.succ_opt()?;
+                    Some((date_function::format_datetime(next_date.and_hms(0, 0, 0)), PointType::Open))
                 }
             }
-            _ => return Err(MysqlError::new_global_error(
-                MYSQL_ERROR_CODE_UNKNOWN_ERROR,
-                format!("Unsupported convert scalar value to string: {:?}", column_value).as_str(),
-            )),
+        },
+        IndexKeyType::Date => match scalar_value {
+            ScalarValue::Utf8(Some(value)) => Some((value.clone(), point_type)),
+            // the day of a time is in the range, the time past the start of the day is after the day
+            _ => date_function::scalar_value_to_datetime(scalar_value).map(|(datetime, has_time)| {
+                let point_type = if has_time { PointType::Closed } else { point_type };
+                (date_function::format_date(datetime.date()), point_type)
+            }),
+        },
+        IndexKeyType::Decimal(scale) => {
+            let (value, exact) = scalar_value_to_decimal(scalar_value, scale)?;
+            if exact {
+                return Some((encode_decimal(value), point_type));
+            }
+            let value = if is_start { value.checked_sub(1)? } else { value.checked_add(1)? };
+            Some((encode_decimal(value), PointType::Closed))
         }
+        IndexKeyType::Binary => scalar_value_to_bytes(scalar_value).map(|value| (hex::encode_upper(value), point_type)),
+        IndexKeyType::Text => match scalar_value {
+            ScalarValue::Utf8(Some(value)) => Some((value.clone(), point_type)),
+            _ => None,
+        },
     }
-
-    Ok(k)
 }
 
 #[derive(Debug, Clone)]
@@ -214,23 +491,21 @@ impl CreateScanKey {
 }
 
 pub fn create_scan_rowid(table: TableDef) -> CreateScanKey {
-    let full_table_name = table.option.full_table_name;
-
     let mut scan_key = CreateScanKey::new("/Table/rowid/");
-    scan_key.add_key(full_table_name.to_string().as_str());
+    scan_key.add_key(table.option.key_name().as_str());
 
     scan_key
 }
 
-pub fn create_scan_index(table: TableDef, table_index: TableIndex) -> (CreateScanKey, CreateScanKey) {
-    let full_table_name = table.option.full_table_name;
+pub fn create_scan_index(table: TableDef, table_index: TableIndex) -> MysqlResult<(CreateScanKey, CreateScanKey)> {
+    let key_name = table.option.key_name();
     let index_name = table_index.index_name;
     let column_range_list = table_index.column_range_list;
 
     let mut start = CreateScanKey::new("/Table/index/key/");
     let mut end = CreateScanKey::new("/Table/index/key/");
-    start.add_key(full_table_name.to_string().as_str());
-    end.add_key(full_table_name.to_string().as_str());
+    start.add_key(key_name.as_str());
+    end.add_key(key_name.as_str());
 
     start.add_key(index_name.as_str());
     end.add_key(index_name.as_str());
@@ -239,9 +514,15 @@ pub fn create_scan_index(table: TableDef, table_index: TableIndex) -> (CreateSca
         let column_name = column_range.column_name;
         let sparrow_column = table.column.get_sparrow_column(column_name.to_ident()).unwrap();
         let column_store_id = sparrow_column.store_id;
+        let sql_data_type = sparrow_column.sql_column.data_type;
 
-        start.add_key(column_store_id.to_string().as_str());
-        end.add_key(column_store_id.to_string().as_str());
+        // an infinity side adds nothing, it runs to the start or the end of the prefix
+        if column_range.range.start != RangePoint::Infinity {
+            start.add_key(column_store_id.to_string().as_str());
+        }
+        if column_range.range.end != RangePoint::Infinity {
+            end.add_key(column_store_id.to_string().as_str());
+        }
 
         match column_range.range.start {
             RangePoint::Infinity => {}
@@ -253,9 +534,10 @@ pub fn create_scan_index(table: TableDef, table_index: TableIndex) -> (CreateSca
             }
             RangePoint::NotNullValue(scalar_value, point_type) => {
                 start.add_key("1");
-                let value = scalar_value.to_string();
-                start.add_key(value.as_str());
-                start.change_interval(point_type);
+                if let Some((value, point_type)) = encode_index_bound(&sql_data_type, &scalar_value, point_type, true) {
+                    start.add_key(value.as_str());
+                    start.change_interval(point_type);
+                }
             }
         }
 
@@ -269,14 +551,15 @@ pub fn create_scan_index(table: TableDef, table_index: TableIndex) -> (CreateSca
             }
             RangePoint::NotNullValue(scalar_value, point_type) => {
                 end.add_key("1");
-                let value = scalar_value.to_string();
-                end.add_key(value.as_str());
-                end.change_interval(point_type);
+                if let Some((value, point_type)) = encode_index_bound(&sql_data_type, &scalar_value, point_type, false) {
+                    end.add_key(value.as_str());
+                    end.change_interval(point_type);
+                }
             }
         }
     }
 
-    (start, end)
+    Ok((start, end))
 }
 
 
@@ -0,0 +1,160 @@
+/// The precision and scale of DECIMAL without them, as in mysql
+pub const DEFAULT_PRECISION: usize = 10;
+pub const DEFAULT_SCALE: usize = 0;
+/// The largest precision of DECIMAL which fits the unscaled value in an i128
+pub const MAX_PRECISION: usize = 38;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecimalError {
+    /// The text is not a number
+    Invalid,
+    /// The number has more integer digits than the precision allows
+    OutOfRange,
+}
+
+/// The precision and scale of the DECIMAL column, the defaults for the missing ones
+pub fn precision_scale(precision: &Option<u64>, scale: &Option<u64>) -> (usize, usize) {
+    (
+        precision.map(|precision| precision as usize).unwrap_or(DEFAULT_PRECISION),
+        scale.map(|scale| scale as usize).unwrap_or(DEFAULT_SCALE),
+    )
+}
+
+/// The text of the decimal from the unscaled value and the scale,
+/// the fraction always has `scale` digits, so `12.5` of DECIMAL(20,4) is `12.5000`
+pub fn format_decimal(unscaled: i128, scale: usize) -> String {
+    let mut digits = unscaled.unsigned_abs().to_string();
+    if digits.len() <= scale {
+        digits = format!("{}{}", "0".repeat(scale + 1 - digits.len()), digits);
+    }
+    let (integer_part, fraction_part) = digits.split_at(digits.len() - scale);
+
+    let mut text = String::new();
+    if unscaled < 0 {
+        text.push('-');
+    }
+    text.push_str(integer_part);
+    if scale > 0 {
+        text.push('.');
+        text.push_str(fraction_part);
+    }
+    text
+}
+
+/// The largest unscaled value of the precision, 999.99 of DECIMAL(5,2) is 99999
+pub fn max_unscaled(precision: usize) -> i128 {
+    10_i128.pow(precision.min(MAX_PRECISION) as u32) - 1
+}
+
+/// Parse the text to the unscaled value of the scale,
+/// the digits past the scale are rounded half away from zero as in mysql
+pub fn parse_decimal(text: &str, precision: usize, scale: usize) -> Result<i128, DecimalError> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let (mantissa, exponent) = match text.find(|c| c == 'e' || c == 'E') {
+        Some(index) => {
+            let exponent = text[index + 1..].parse::<i64>().map_err(|_| DecimalError::Invalid)?;
+            (&text[..index], exponent)
+        }
+        None => (text, 0),
+    };
+    let (integer_part, fraction_part) = match mantissa.find('.') {
+        Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+        None => (mantissa, ""),
+    };
+    if integer_part.is_empty() && fraction_part.is_empty() {
+        return Err(DecimalError::Invalid);
+    }
+    if !integer_part.chars().chain(fraction_part.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(DecimalError::Invalid);
+    }
+
+    // all the digits, and the position of the decimal point in them
+    let digits: Vec<u8> = integer_part.bytes().chain(fraction_part.bytes()).map(|c| c - b'0').collect();
+    let point = integer_part.len() as i64 + exponent;
+    if digits.iter().all(|digit| *digit == 0) {
+        return Ok(0);
+    }
+
+    let integer_digits = point.max(0) as usize;
+    let mut unscaled: i128 = 0;
+    let mut significant_digits = 0;
+    for position in 0..integer_digits + scale {
+        let digit = match position as i64 + point - integer_digits as i64 {
+            index if index >= 0 && (index as usize) < digits.len() => digits[index as usize] as i128,
+            _ => 0,
+        };
+        if significant_digits > 0 || digit > 0 {
+            significant_digits += 1;
+        }
+        if significant_digits > precision.min(MAX_PRECISION) {
+            return Err(DecimalError::OutOfRange);
+        }
+        unscaled = unscaled * 10 + digit;
+    }
+
+    // the first digit past the scale decides the rounding
+    let round_index = point + scale as i64;
+    if round_index >= 0 && (round_index as usize) < digits.len() && digits[round_index as usize] >= 5 {
+        unscaled += 1;
+    }
+    if unscaled > max_unscaled(precision) {
+        return Err(DecimalError::OutOfRange);
+    }
+
+    Ok(if negative { -unscaled } else { unscaled })
+}
+
+/// CAST(... AS DECIMAL(p,s)) of the text, the value out of the precision is clamped to the largest one
+/// and the text which is not a number is 0, unless the strict sql mode rejects them
+pub fn cast_decimal(text: &str, precision: usize, scale: usize, strict: bool) -> Result<i128, DecimalError> {
+    match parse_decimal(text, precision, scale) {
+        Err(DecimalError::OutOfRange) if !strict => {
+            if text.trim_start().starts_with('-') {
+                Ok(-max_unscaled(precision))
+            } else {
+                Ok(max_unscaled(precision))
+            }
+        }
+        Err(DecimalError::Invalid) if !strict => Ok(0),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        assert_eq!("12.5000", format_decimal(125000, 4));
+        assert_eq!("-0.05", format_decimal(-5, 2));
+        assert_eq!("7", format_decimal(7, 0));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(125000), parse_decimal("12.5", 20, 4));
+        assert_eq!(Ok(124), parse_decimal("1.235", 10, 2));
+        assert_eq!(Ok(-124), parse_decimal("-1.235", 10, 2));
+        assert_eq!(Ok(150), parse_decimal("1.5e2", 10, 0));
+        assert_eq!(Ok(10000), parse_decimal("99.995", 5, 2));
+        assert_eq!(Ok(5), parse_decimal("0.5e-1", 10, 2));
+        assert_eq!(Err(DecimalError::OutOfRange), parse_decimal("99.995", 4, 2));
+        assert_eq!(Err(DecimalError::OutOfRange), parse_decimal("1000", 5, 2));
+        assert_eq!(Err(DecimalError::Invalid), parse_decimal("abc", 10, 2));
+    }
+
+    #[test]
+    fn cast() {
+        assert_eq!(Ok(101), cast_decimal("1.005", 10, 2, true));
+        assert_eq!(Ok(9999), cast_decimal("1234.5", 4, 2, false));
+        assert_eq!(Ok(-9999), cast_decimal("-1234.5", 4, 2, false));
+        assert_eq!(Err(DecimalError::OutOfRange), cast_decimal("1234.5", 4, 2, true));
+        assert_eq!(Ok(0), cast_decimal("abc", 4, 2, false));
+    }
+}
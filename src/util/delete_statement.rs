@@ -0,0 +1,66 @@
+use sqlparser::ast::ObjectName;
+
+use crate::util::index_statement;
+use crate::util::schema_statement::Tokens;
+
+/// `DELETE FROM tbl_name [WHERE where_condition] [ORDER BY ...] [LIMIT row_count]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeleteLimitStatement {
+    /// The statement as `SELECT * FROM tbl_name ...`, its WHERE clause, ORDER BY and LIMIT are the ones of the DELETE
+    pub select_sql: String,
+}
+
+/// `TRUNCATE [TABLE] tbl_name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TruncateTableStatement {
+    pub table_name: ObjectName,
+}
+
+/// The sql parser knows neither ORDER BY nor LIMIT of DELETE, None if the sql is not a DELETE with one of them
+pub fn parse_delete_limit(sql: &str) -> Option<DeleteLimitStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["DELETE", "FROM"]) {
+        return None;
+    }
+    if !tokens.contains_words(&["ORDER", "BY"]) && !tokens.contains_words(&["LIMIT"]) {
+        return None;
+    }
+
+    Some(DeleteLimitStatement {
+        select_sql: format!("SELECT * FROM {}", tokens.remaining_sql()),
+    })
+}
+
+/// None if the sql is not a TRUNCATE TABLE statement
+pub fn parse_truncate_table(sql: &str) -> Option<TruncateTableStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("TRUNCATE") {
+        return None;
+    }
+    tokens.next_word_is("TABLE");
+    let table_name = index_statement::next_object_name(&mut tokens)?;
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(TruncateTableStatement { table_name })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::ast::Ident;
+
+    #[test]
+    fn parse() {
+        let statement = parse_delete_limit("delete from user where id > 1 order by name desc limit 2;").unwrap();
+        assert_eq!("SELECT * FROM user where id > 1 order by name desc limit 2", statement.select_sql);
+        assert_eq!(None, parse_delete_limit("delete from user where id in (select id from t1 limit 1)"));
+        assert_eq!(None, parse_delete_limit("delete from user where name = 'limit'"));
+
+        let table_name = ObjectName(vec![Ident::new("test"), Ident::new("user")]);
+        assert_eq!(Some(TruncateTableStatement { table_name: table_name.clone() }), parse_truncate_table("truncate table test.user"));
+        assert_eq!(Some(TruncateTableStatement { table_name }), parse_truncate_table("TRUNCATE test.user;"));
+        assert_eq!(None, parse_truncate_table("truncate table user where id = 1"));
+    }
+}
@@ -0,0 +1,81 @@
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// `EXPLAIN FORMAT = format_name statement`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExplainFormatStatement {
+    /// The format name in upper case
+    pub format: String,
+    /// The sql of the explained statement
+    pub statement_sql: String,
+}
+
+fn skip_whitespace(tokens: &[Token], index: &mut usize) {
+    while let Some(Token::Whitespace(_)) = tokens.get(*index) {
+        *index += 1;
+    }
+}
+
+fn next_word(tokens: &[Token], index: &mut usize) -> Option<String> {
+    skip_whitespace(tokens, index);
+    match tokens.get(*index)? {
+        Token::Word(word) if word.quote_style.is_none() => {
+            *index += 1;
+            Some(word.value.to_uppercase())
+        }
+        _ => None,
+    }
+}
+
+/// The sql parser does not know the FORMAT of EXPLAIN,
+/// None if the sql is not an EXPLAIN FORMAT = ... statement
+pub fn parse_explain_format(sql: &str) -> Option<ExplainFormatStatement> {
+    let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, sql).tokenize().ok()?;
+
+    let mut index = 0;
+    if next_word(&tokens, &mut index)? != "EXPLAIN" || next_word(&tokens, &mut index)? != "FORMAT" {
+        return None;
+    }
+    skip_whitespace(&tokens, &mut index);
+    match tokens.get(index)? {
+        Token::Eq => index += 1,
+        _ => return None,
+    }
+    let format = next_word(&tokens, &mut index)?;
+
+    let statement_sql = tokens[index..]
+        .iter()
+        .map(|token| match token {
+            Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+            _ => token.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("");
+
+    Some(ExplainFormatStatement {
+        format,
+        statement_sql: statement_sql.trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explain_format() {
+        assert_eq!(
+            Some(ExplainFormatStatement {
+                format: "TREE".to_string(),
+                statement_sql: "select * from t where name = 'a''b'".to_string(),
+            }),
+            parse_explain_format("explain format=tree select * from t where name = 'a''b'")
+        );
+        assert_eq!(
+            Some("TRADITIONAL".to_string()),
+            parse_explain_format("EXPLAIN FORMAT = traditional select 1").map(|statement| statement.format)
+        );
+        assert_eq!(None, parse_explain_format("explain select * from t"));
+    }
+}
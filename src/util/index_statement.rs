@@ -0,0 +1,72 @@
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser::tokenizer::Token;
+
+use crate::util::schema_statement::Tokens;
+
+/// `DROP INDEX index_name ON tbl_name` and `ALTER TABLE tbl_name DROP {INDEX | KEY} index_name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropIndexStatement {
+    pub index_name: String,
+    pub table_name: ObjectName,
+}
+
+/// The sql parser does not know the table of DROP INDEX, None if the sql is not a DROP INDEX statement
+pub fn parse_drop_index(sql: &str) -> Option<DropIndexStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    let statement = if tokens.next_words_are(&["DROP", "INDEX"]) {
+        let index_name = tokens.next_name()?;
+        if !tokens.next_word_is("ON") {
+            return None;
+        }
+        let table_name = next_object_name(&mut tokens)?;
+        DropIndexStatement { index_name, table_name }
+    } else if tokens.next_words_are(&["ALTER", "TABLE"]) {
+        let table_name = next_object_name(&mut tokens)?;
+        if !tokens.next_words_are(&["DROP", "INDEX"]) && !tokens.next_words_are(&["DROP", "KEY"]) {
+            return None;
+        }
+        let index_name = tokens.next_name()?;
+        DropIndexStatement { index_name, table_name }
+    } else {
+        return None;
+    };
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(statement)
+}
+
+/// `tbl_name` or `db_name.tbl_name`
+pub(crate) fn next_object_name(tokens: &mut Tokens) -> Option<ObjectName> {
+    let mut idents = vec![Ident::new(tokens.next_name()?)];
+    if tokens.next_token_is(&Token::Period) {
+        idents.push(Ident::new(tokens.next_name()?));
+    }
+    Some(ObjectName(idents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let statement = DropIndexStatement {
+            index_name: "idx_name".to_string(),
+            table_name: ObjectName(vec![Ident::new("test"), Ident::new("user")]),
+        };
+        assert_eq!(Some(statement.clone()), parse_drop_index("drop index idx_name on test.user"));
+        assert_eq!(Some(statement), parse_drop_index("ALTER TABLE test.user DROP KEY idx_name;"));
+        assert_eq!(
+            Some(DropIndexStatement {
+                index_name: "idx_name".to_string(),
+                table_name: ObjectName(vec![Ident::new("user")]),
+            }),
+            parse_drop_index("alter table user drop index idx_name")
+        );
+        assert_eq!(None, parse_drop_index("drop index idx_name"));
+        assert_eq!(None, parse_drop_index("alter table user drop column name"));
+        assert_eq!(None, parse_drop_index("drop table user"));
+    }
+}
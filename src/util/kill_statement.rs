@@ -0,0 +1,47 @@
+use crate::util::schema_statement::Tokens;
+
+/// `KILL [CONNECTION | QUERY] processlist_id`
+#[derive(Clone, Debug, PartialEq)]
+pub struct KillStatement {
+    pub connection_id: u32,
+    /// KILL QUERY interrupts the statement of the connection, the connection is kept
+    pub query_only: bool,
+}
+
+/// The sql parser does not know KILL, None if the sql is not a KILL statement
+pub fn parse_kill(sql: &str) -> Option<KillStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("KILL") {
+        return None;
+    }
+    let query_only = if tokens.next_word_is("QUERY") {
+        true
+    } else {
+        tokens.next_word_is("CONNECTION");
+        false
+    };
+    let connection_id = tokens.next_number()?;
+    if !tokens.is_end() || connection_id > u32::MAX as u64 {
+        return None;
+    }
+
+    Some(KillStatement {
+        connection_id: connection_id as u32,
+        query_only,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some(KillStatement { connection_id: 12, query_only: false }), parse_kill("kill 12"));
+        assert_eq!(Some(KillStatement { connection_id: 12, query_only: false }), parse_kill("KILL CONNECTION 12;"));
+        assert_eq!(Some(KillStatement { connection_id: 3, query_only: true }), parse_kill("kill query 3"));
+        assert_eq!(None, parse_kill("kill query"));
+        assert_eq!(None, parse_kill("kill 3 4"));
+        assert_eq!(None, parse_kill("select 'kill 3'"));
+    }
+}
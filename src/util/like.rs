@@ -0,0 +1,208 @@
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// The escape character of the LIKE pattern if no ESCAPE clause is given
+pub const DEFAULT_ESCAPE_CHAR: char = '\\';
+
+#[derive(Clone, Debug, PartialEq)]
+enum LikeToken {
+    Literal(char),
+    /// `%`, matches any sequence of characters
+    AnyString,
+    /// `_`, matches exactly one character
+    AnyChar,
+}
+
+fn tokenize_pattern(pattern: &str, escape_char: char) -> Vec<LikeToken> {
+    let mut tokens = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == escape_char {
+            match chars.next() {
+                Some(next_char) => tokens.push(LikeToken::Literal(next_char)),
+                // a trailing escape char matches itself
+                None => tokens.push(LikeToken::Literal(c)),
+            }
+        } else if c == '%' {
+            tokens.push(LikeToken::AnyString);
+        } else if c == '_' {
+            tokens.push(LikeToken::AnyChar);
+        } else {
+            tokens.push(LikeToken::Literal(c));
+        }
+    }
+    tokens
+}
+
+/// Match the value with the LIKE pattern, escaped wildcards are treated literally
+pub fn like_match(value: &str, pattern: &str, escape_char: char) -> bool {
+    let tokens = tokenize_pattern(pattern, escape_char);
+    let value: Vec<char> = value.chars().collect();
+
+    let mut value_index = 0;
+    let mut token_index = 0;
+    // the position of the last `%` and the value index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while value_index < value.len() {
+        match tokens.get(token_index) {
+            Some(LikeToken::AnyString) => {
+                backtrack = Some((token_index, value_index));
+                token_index += 1;
+                continue;
+            }
+            Some(LikeToken::AnyChar) => {
+                token_index += 1;
+                value_index += 1;
+                continue;
+            }
+            Some(LikeToken::Literal(c)) if *c == value[value_index] => {
+                token_index += 1;
+                value_index += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((any_string_index, any_string_value_index)) => {
+                token_index = any_string_index + 1;
+                value_index = any_string_value_index + 1;
+                backtrack = Some((any_string_index, value_index));
+            }
+            None => return false,
+        }
+    }
+
+    tokens[token_index..].iter().all(|token| *token == LikeToken::AnyString)
+}
+
+/// The literal prefix of the LIKE pattern, up to the first unescaped wildcard.
+/// An empty prefix means the pattern can not be used to seek an index.
+pub fn like_pattern_prefix(pattern: &str, escape_char: char) -> String {
+    let mut prefix = String::new();
+    for token in tokenize_pattern(pattern, escape_char) {
+        match token {
+            LikeToken::Literal(c) => prefix.push(c),
+            _ => break,
+        }
+    }
+    prefix
+}
+
+/// The LIKE pattern with a custom escape char converted to the default escape char
+pub fn convert_pattern_escape(pattern: &str, escape_char: char) -> String {
+    let mut new_pattern = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == escape_char {
+            match chars.next() {
+                Some(next_char) => {
+                    new_pattern.push(DEFAULT_ESCAPE_CHAR);
+                    new_pattern.push(next_char);
+                }
+                None => {
+                    new_pattern.push(DEFAULT_ESCAPE_CHAR);
+                    new_pattern.push(c);
+                }
+            }
+        } else if c == DEFAULT_ESCAPE_CHAR {
+            new_pattern.push(DEFAULT_ESCAPE_CHAR);
+            new_pattern.push(c);
+        } else {
+            new_pattern.push(c);
+        }
+    }
+    new_pattern
+}
+
+/// The sql parser does not know the `ESCAPE` clause of LIKE,
+/// rewrite `LIKE 'a|%b' ESCAPE '|'` to `LIKE 'a\%b'` before parsing.
+pub fn rewrite_like_escape(sql: &str) -> String {
+    let dialect = GenericDialect {};
+    let tokens = match Tokenizer::new(&dialect, sql).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return sql.to_string(),
+    };
+
+    let is_word = |token: &Token, value: &str| match token {
+        Token::Word(word) => word.quote_style.is_none() && word.value.to_uppercase() == value,
+        _ => false,
+    };
+    let next_not_whitespace = |tokens: &Vec<Token>, mut index: usize| {
+        while index < tokens.len() {
+            match tokens[index] {
+                Token::Whitespace(_) => index += 1,
+                _ => return Some(index),
+            }
+        }
+        None
+    };
+
+    let mut new_tokens = vec![];
+    let mut changed = false;
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index].clone();
+        if is_word(&token, "LIKE") {
+            let pattern_index = next_not_whitespace(&tokens, index + 1);
+            let escape_index = pattern_index.and_then(|i| next_not_whitespace(&tokens, i + 1));
+            let escape_char_index = escape_index.and_then(|i| next_not_whitespace(&tokens, i + 1));
+            if let (Some(pattern_index), Some(escape_index), Some(escape_char_index)) = (pattern_index, escape_index, escape_char_index) {
+                match (&tokens[pattern_index], &tokens[escape_char_index]) {
+                    (Token::SingleQuotedString(pattern), Token::SingleQuotedString(escape_char))
+                    if is_word(&tokens[escape_index], "ESCAPE") && escape_char.chars().count() == 1 => {
+                        let escape_char = escape_char.chars().next().unwrap();
+                        new_tokens.push(token);
+                        new_tokens.extend_from_slice(&tokens[index + 1..pattern_index]);
+                        new_tokens.push(Token::SingleQuotedString(convert_pattern_escape(pattern, escape_char)));
+                        index = escape_char_index + 1;
+                        changed = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        new_tokens.push(token);
+        index += 1;
+    }
+
+    if !changed {
+        return sql.to_string();
+    }
+
+    new_tokens.iter().map(|token| match token {
+        Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+        _ => token.to_string(),
+    }).collect::<Vec<String>>().join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_match_escape() {
+        assert!(like_match("a%b", "a\\%b", DEFAULT_ESCAPE_CHAR));
+        assert!(!like_match("axb", "a\\%b", DEFAULT_ESCAPE_CHAR));
+        assert!(like_match("a_b", "a|_b", '|'));
+        assert!(!like_match("axb", "a|_b", '|'));
+        assert!(like_match("abcd", "a%d", DEFAULT_ESCAPE_CHAR));
+        assert!(like_match("abcd", "_b%", DEFAULT_ESCAPE_CHAR));
+    }
+
+    #[test]
+    fn like_prefix() {
+        assert_eq!("a%b", like_pattern_prefix("a\\%b%", DEFAULT_ESCAPE_CHAR));
+        assert_eq!("", like_pattern_prefix("%ab", DEFAULT_ESCAPE_CHAR));
+        assert_eq!("%", like_pattern_prefix("|%_", '|'));
+    }
+
+    #[test]
+    fn rewrite_escape() {
+        assert_eq!(
+            "select * from t where name LIKE 'a\\%b'",
+            rewrite_like_escape("select * from t where name LIKE 'a|%b' ESCAPE '|'")
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use std::io::BufRead;
+
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser::tokenizer::Token;
+
+use crate::util::schema_statement::Tokens;
+
+/// What is done with the rows which duplicate the unique keys of the table
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoadDataDuplicate {
+    Error,
+    Replace,
+    Ignore,
+}
+
+/// The format of the fields and the lines of the file
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadDataFormat {
+    pub fields_terminated_by: String,
+    pub fields_enclosed_by: Option<char>,
+    pub fields_escaped_by: Option<char>,
+    pub lines_starting_by: String,
+    pub lines_terminated_by: String,
+}
+
+/// `FIELDS TERMINATED BY '\t' ENCLOSED BY '' ESCAPED BY '\\' LINES TERMINATED BY '\n' STARTING BY ''`, as in mysql
+impl ::std::default::Default for LoadDataFormat {
+    fn default() -> Self {
+        Self {
+            fields_terminated_by: "\t".to_string(),
+            fields_enclosed_by: None,
+            fields_escaped_by: Some('\\'),
+            lines_starting_by: "".to_string(),
+            lines_terminated_by: "\n".to_string(),
+        }
+    }
+}
+
+/// `LOAD DATA [LOCAL] INFILE 'file_name' [REPLACE | IGNORE] INTO TABLE tbl_name
+/// [{FIELDS | COLUMNS} [TERMINATED BY 'string'] [[OPTIONALLY] ENCLOSED BY 'char'] [ESCAPED BY 'char']]
+/// [LINES [STARTING BY 'string'] [TERMINATED BY 'string']] [IGNORE number {LINES | ROWS}] [(col_name, ...)]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadDataStatement {
+    pub local: bool,
+    pub file_name: String,
+    pub duplicate: LoadDataDuplicate,
+    pub table_name: ObjectName,
+    pub format: LoadDataFormat,
+    pub ignore_lines: u64,
+    pub columns: Vec<Ident>,
+}
+
+/// The string of the statement with the escape sequences of mysql, `'\t'` is the tab
+fn unescape_string(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => text.push('\t'),
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('0') => text.push('\0'),
+            Some(c) => text.push(c),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// The character of ENCLOSED BY and ESCAPED BY, the empty string is none
+fn next_char(tokens: &mut Tokens) -> Option<Option<char>> {
+    let value = unescape_string(tokens.next_string()?.as_str());
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Some(None),
+        (Some(c), None) => Some(Some(c)),
+        _ => None,
+    }
+}
+
+/// The sql parser does not know LOAD DATA,
+/// None if the sql is not a LOAD DATA statement
+pub fn parse_load_data(sql: &str) -> Option<LoadDataStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["LOAD", "DATA"]) {
+        return None;
+    }
+    let local = tokens.next_word_is("LOCAL");
+    if !tokens.next_word_is("INFILE") {
+        return None;
+    }
+    let file_name = tokens.next_string()?;
+    let duplicate = if tokens.next_word_is("REPLACE") {
+        LoadDataDuplicate::Replace
+    } else if tokens.next_word_is("IGNORE") {
+        LoadDataDuplicate::Ignore
+    } else {
+        LoadDataDuplicate::Error
+    };
+    if !tokens.next_words_are(&["INTO", "TABLE"]) {
+        return None;
+    }
+    let mut table_name = vec![Ident::new(tokens.next_name()?)];
+    while tokens.next_token_is(&Token::Period) {
+        table_name.push(Ident::new(tokens.next_name()?));
+    }
+
+    let mut format = LoadDataFormat::default();
+    if tokens.next_word_is("FIELDS") || tokens.next_word_is("COLUMNS") {
+        loop {
+            if tokens.next_words_are(&["TERMINATED", "BY"]) {
+                format.fields_terminated_by = unescape_string(tokens.next_string()?.as_str());
+            } else if tokens.next_words_are(&["OPTIONALLY", "ENCLOSED", "BY"]) || tokens.next_words_are(&["ENCLOSED", "BY"]) {
+                format.fields_enclosed_by = next_char(&mut tokens)?;
+            } else if tokens.next_words_are(&["ESCAPED", "BY"]) {
+                format.fields_escaped_by = next_char(&mut tokens)?;
+            } else {
+                break;
+            }
+        }
+    }
+    if tokens.next_word_is("LINES") {
+        loop {
+            if tokens.next_words_are(&["STARTING", "BY"]) {
+                format.lines_starting_by = unescape_string(tokens.next_string()?.as_str());
+            } else if tokens.next_words_are(&["TERMINATED", "BY"]) {
+                format.lines_terminated_by = unescape_string(tokens.next_string()?.as_str());
+            } else {
+                break;
+            }
+        }
+    }
+    if format.fields_terminated_by.is_empty() || format.lines_terminated_by.is_empty() {
+        // the rows of the fixed width are not supported
+        return None;
+    }
+
+    let mut ignore_lines = 0;
+    if tokens.next_word_is("IGNORE") {
+        ignore_lines = tokens.next_number()?;
+        if !(tokens.next_word_is("LINES") || tokens.next_word_is("ROWS")) {
+            return None;
+        }
+    }
+
+    let mut columns = vec![];
+    if tokens.next_token_is(&Token::LParen) {
+        loop {
+            columns.push(Ident::new(tokens.next_name()?));
+            if tokens.next_token_is(&Token::RParen) {
+                break;
+            }
+            if !tokens.next_token_is(&Token::Comma) {
+                return None;
+            }
+        }
+    }
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(LoadDataStatement {
+        local,
+        file_name,
+        duplicate,
+        table_name: ObjectName(table_name),
+        format,
+        ignore_lines,
+        columns,
+    })
+}
+
+fn starts_with_at(chars: &[char], index: usize, pattern: &[char]) -> bool {
+    !pattern.is_empty() && chars.len() >= index + pattern.len() && chars[index..index + pattern.len()] == *pattern
+}
+
+/// Reads the rows of the file one by one, the file is not read into the memory at once
+pub struct LoadDataReader<R: BufRead> {
+    reader: R,
+    format: LoadDataFormat,
+    /// The bytes of the line being read, an enclosed field may hold the line terminator
+    buffer: Vec<u8>,
+    line_number: u64,
+}
+
+impl<R: BufRead> LoadDataReader<R> {
+    pub fn new(reader: R, format: LoadDataFormat) -> Self {
+        Self {
+            reader,
+            format,
+            buffer: vec![],
+            line_number: 0,
+        }
+    }
+
+    /// The next row and its line number, the NULL field is None, the empty lines are skipped
+    pub fn next_row(&mut self) -> std::io::Result<Option<(u64, Vec<Option<String>>)>> {
+        let terminator = self.format.lines_terminated_by.clone().into_bytes();
+        let last_byte = terminator[terminator.len() - 1];
+        loop {
+            let size = self.reader.read_until(last_byte, &mut self.buffer)?;
+            let at_end = size == 0;
+            if at_end && self.buffer.is_empty() {
+                return Ok(None);
+            }
+            if !at_end && !self.buffer.ends_with(terminator.as_slice()) {
+                continue;
+            }
+
+            let line_size = if self.buffer.ends_with(terminator.as_slice()) {
+                self.buffer.len() - terminator.len()
+            } else {
+                self.buffer.len()
+            };
+            let line = String::from_utf8_lossy(&self.buffer[..line_size]).to_string();
+            let fields = match self.split_fields(line.as_str(), at_end) {
+                // the line terminator is in an enclosed field, the line goes on
+                None => continue,
+                Some(fields) => fields,
+            };
+            self.buffer.clear();
+            self.line_number += 1;
+            if !fields.is_empty() {
+                return Ok(Some((self.line_number, fields)));
+            }
+        }
+    }
+
+    /// The fields of the line, none for the line to skip,
+    /// None if the line ends in an enclosed field which is closed in the next lines
+    fn split_fields(&self, line: &str, at_end: bool) -> Option<Vec<Option<String>>> {
+        let mut chars: Vec<char> = line.chars().collect();
+        if !self.format.lines_starting_by.is_empty() {
+            // the text before the prefix is skipped, and the line without it
+            let prefix: Vec<char> = self.format.lines_starting_by.chars().collect();
+            match (0..chars.len()).find(|index| starts_with_at(&chars, *index, &prefix)) {
+                None => return Some(vec![]),
+                Some(index) => chars = chars[index + prefix.len()..].to_vec(),
+            }
+        }
+        if chars.is_empty() {
+            return Some(vec![]);
+        }
+
+        let terminator: Vec<char> = self.format.fields_terminated_by.chars().collect();
+        let escape = self.format.fields_escaped_by;
+        let mut fields = vec![];
+        let mut index = 0;
+        loop {
+            let mut value = String::new();
+            let mut escaped_null = false;
+            match self.format.fields_enclosed_by {
+                Some(quote) if chars.get(index) == Some(&quote) => {
+                    index += 1;
+                    loop {
+                        match chars.get(index) {
+                            // the field is not closed yet
+                            None if at_end => break,
+                            None => return None,
+                            Some(c) if Some(*c) == escape && index + 1 < chars.len() => {
+                                value.push(unescape_char(chars[index + 1]));
+                                index += 2;
+                            }
+                            Some(c) if *c == quote && chars.get(index + 1) == Some(&quote) => {
+                                value.push(quote);
+                                index += 2;
+                            }
+                            Some(c) if *c == quote && (index + 1 == chars.len() || starts_with_at(&chars, index + 1, &terminator)) => {
+                                index += 1;
+                                break;
+                            }
+                            Some(c) => {
+                                value.push(*c);
+                                index += 1;
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    let start = index;
+                    while index < chars.len() && !starts_with_at(&chars, index, &terminator) {
+                        if Some(chars[index]) == escape && index + 1 < chars.len() {
+                            value.push(unescape_char(chars[index + 1]));
+                            index += 2;
+                        } else {
+                            value.push(chars[index]);
+                            index += 1;
+                        }
+                    }
+                    escaped_null = matches!(escape, Some(escape) if chars[start..index] == [escape, 'N']);
+                }
+            }
+
+            if escaped_null {
+                fields.push(None);
+            } else {
+                fields.push(Some(value));
+            }
+            if index >= chars.len() {
+                break;
+            }
+            index += terminator.len();
+        }
+
+        Some(fields)
+    }
+}
+
+/// The character after the escape character, `\t` is the tab
+fn unescape_char(c: char) -> char {
+    match c {
+        '0' => '\0',
+        'b' => '\u{8}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'Z' => '\u{1a}',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_rows(text: &str, format: LoadDataFormat) -> Vec<(u64, Vec<Option<String>>)> {
+        let mut reader = LoadDataReader::new(text.as_bytes(), format);
+        let mut rows = vec![];
+        while let Some(row) = reader.next_row().unwrap() {
+            rows.push(row);
+        }
+        rows
+    }
+
+    #[test]
+    fn parse() {
+        let statement = parse_load_data(
+            "LOAD DATA INFILE 'fruit.csv' IGNORE INTO TABLE test.fruit FIELDS TERMINATED BY ',' OPTIONALLY ENCLOSED BY '\"' LINES TERMINATED BY '\\r\\n' IGNORE 1 LINES (id, name)",
+        ).unwrap();
+        assert_eq!("fruit.csv", statement.file_name);
+        assert_eq!(LoadDataDuplicate::Ignore, statement.duplicate);
+        assert_eq!("test.fruit", statement.table_name.to_string());
+        assert_eq!(",", statement.format.fields_terminated_by);
+        assert_eq!(Some('"'), statement.format.fields_enclosed_by);
+        assert_eq!("\r\n", statement.format.lines_terminated_by);
+        assert_eq!(1, statement.ignore_lines);
+        assert_eq!(vec![Ident::new("id"), Ident::new("name")], statement.columns);
+        assert_eq!(None, parse_load_data("LOAD DATA INFILE 'fruit.csv' INTO fruit"));
+    }
+
+    #[test]
+    fn read() {
+        let rows = read_rows("1\tapple\n2\t\\N\n\n3\tpl\\tum", LoadDataFormat::default());
+        assert_eq!(
+            vec![
+                (1, vec![Some("1".to_string()), Some("apple".to_string())]),
+                (2, vec![Some("2".to_string()), None]),
+                (4, vec![Some("3".to_string()), Some("pl\tum".to_string())]),
+            ],
+            rows
+        );
+
+        let format = LoadDataFormat {
+            fields_terminated_by: ",".to_string(),
+            fields_enclosed_by: Some('"'),
+            ..LoadDataFormat::default()
+        };
+        let rows = read_rows("1,\"a,\"\"b\"\"\nc\"\n", format);
+        assert_eq!(vec![(1, vec![Some("1".to_string()), Some("a,\"b\"\nc".to_string())])], rows);
+    }
+}
@@ -1,2 +1,28 @@
+pub mod alter_column_statement;
+pub mod collation;
+pub mod common_table_expression;
 pub mod convert;
-pub mod dbkey;
\ No newline at end of file
+pub mod create_table_statement;
+pub mod date_function;
+pub mod dbkey;
+pub mod delete_statement;
+pub mod explain_statement;
+pub mod index_statement;
+pub mod kill_statement;
+pub mod decimal;
+pub mod like;
+pub mod load_data_statement;
+pub mod multi_statement;
+pub mod placeholder;
+pub mod rename_table_statement;
+pub mod rowid_alias;
+pub mod schema_statement;
+pub mod set_statement;
+pub mod show_statement;
+pub mod string_function;
+pub mod temporary_table_statement;
+pub mod time_zone;
+pub mod transaction_statement;
+pub mod upsert_statement;
+pub mod user_statement;
+pub mod user_variable;
\ No newline at end of file
@@ -0,0 +1,33 @@
+use crate::util::placeholder;
+
+/// The statements of the query of CLIENT_MULTI_STATEMENTS, split at the `;` outside of the quoted strings,
+/// the quoted identifiers and the comments. The empty statements are skipped
+pub fn split_statements(sql: &str) -> Vec<&str> {
+    let mut statements = vec![];
+    let mut start = 0;
+    for offset in placeholder::unquoted_offsets(sql, b';') {
+        statements.push(&sql[start..offset]);
+        start = offset + 1;
+    }
+    statements.push(&sql[start..]);
+
+    statements
+        .into_iter()
+        .filter(|statement| !statement.trim().is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            vec!["insert into t values ('a;b')", " select `c;` from t ", " -- x;\n select 1"],
+            split_statements("insert into t values ('a;b'); select `c;` from t ; -- x;\n select 1;")
+        );
+        assert_eq!(vec!["select 1"], split_statements("select 1"));
+        assert_eq!(0, split_statements(" ; ").len());
+    }
+}
@@ -0,0 +1,131 @@
+use sqlparser::ast::{Expr as SQLExpr, UnaryOperator, Value};
+
+use crate::mysql::error::{MysqlError, MysqlResult};
+
+/// The byte offsets of the `?` placeholders of the sql, the `?` in the quoted strings,
+/// the quoted identifiers and the comments are not placeholders
+pub fn placeholder_offsets(sql: &str) -> Vec<usize> {
+    unquoted_offsets(sql, b'?')
+}
+
+/// The byte offsets of the character outside of the quoted strings, the quoted identifiers and the comments.
+/// The quotes follow the sql parser, a doubled quote is the quote itself and the backslash is not an escape
+pub fn unquoted_offsets(sql: &str, target: u8) -> Vec<usize> {
+    let bytes = sql.as_bytes();
+    let mut offsets = vec![];
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            quote @ b'\'' | quote @ b'"' | quote @ b'`' => {
+                index += 1;
+                while index < bytes.len() {
+                    if bytes[index] == quote {
+                        // the doubled quote is the quote itself
+                        if index + 1 < bytes.len() && bytes[index + 1] == quote {
+                            index += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    index += 1;
+                }
+            }
+            b'-' if bytes.get(index + 1) == Some(&b'-') => {
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    index += 1;
+                }
+            }
+            b'#' => {
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    index += 1;
+                }
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                index += 2;
+                while index + 1 < bytes.len() && !(bytes[index] == b'*' && bytes[index + 1] == b'/') {
+                    index += 1;
+                }
+                index += 1;
+            }
+            byte if byte == target => offsets.push(index),
+            _ => {}
+        }
+        index += 1;
+    }
+    offsets
+}
+
+/// The literal of the bound value in the sql, quoted by the same rule as unquoted_offsets
+fn literal_sql(value: &SQLExpr) -> MysqlResult<String> {
+    match value {
+        SQLExpr::Value(Value::Null) => Ok("NULL".to_string()),
+        SQLExpr::Value(Value::Boolean(value)) => Ok(if *value { "1" } else { "0" }.to_string()),
+        SQLExpr::Value(Value::Number(value, _)) => Ok(value.to_string()),
+        SQLExpr::Value(Value::SingleQuotedString(value)) => Ok(format!("'{}'", value.replace("'", "''"))),
+        SQLExpr::UnaryOp { op: UnaryOperator::Minus, expr } => Ok(format!("-{}", literal_sql(expr)?)),
+        _ => Err(MysqlError::new_global_error(
+            1105,
+            format!("Unknown error. The parameter is not a literal value, value: {:?}", value).as_str(),
+        )),
+    }
+}
+
+/// Replace the placeholders of the prepared sql with the literals of the bound values, in order
+pub fn bind_placeholders(sql: &str, values: &[SQLExpr]) -> MysqlResult<String> {
+    let offsets = placeholder_offsets(sql);
+    if offsets.len() != values.len() {
+        return Err(MysqlError::new_server_error(
+            1210,
+            "HY000",
+            "Incorrect arguments to mysqld_stmt_execute",
+        ));
+    }
+
+    let mut bound_sql = String::with_capacity(sql.len());
+    let mut start = 0;
+    for (offset, value) in offsets.iter().zip(values.iter()) {
+        bound_sql.push_str(&sql[start..*offset]);
+        bound_sql.push_str(literal_sql(value)?.as_str());
+        start = offset + 1;
+    }
+    bound_sql.push_str(&sql[start..]);
+    Ok(bound_sql)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        assert_eq!(vec![33, 43], placeholder_offsets("select id from user where name = ? or id = ?"));
+        assert_eq!(
+            vec![35],
+            placeholder_offsets("select '?', `a?`, \"it''s?\" /* ? */ ? -- ?")
+        );
+        // the backslash does not escape the quote
+        assert_eq!(vec![13], placeholder_offsets("select 'a\\', ?"));
+    }
+
+    #[test]
+    fn bind() {
+        let values = vec![
+            SQLExpr::Value(Value::SingleQuotedString("it's".to_string())),
+            SQLExpr::Value(Value::Null),
+            SQLExpr::Value(Value::Number("-3".to_string(), false)),
+        ];
+        assert_eq!(
+            "insert into t values ('it''s', NULL, -3)",
+            bind_placeholders("insert into t values (?, ?, ?)", &values).unwrap()
+        );
+        // the bound string ending with a backslash does not hide the statements after it
+        let values = vec![SQLExpr::Value(Value::SingleQuotedString("a\\".to_string()))];
+        let sql = bind_placeholders("select ?; select ';'", &values).unwrap();
+        assert_eq!("select 'a\\'; select ';'", sql);
+        assert_eq!(vec![12], unquoted_offsets(sql.as_str(), b';'));
+        assert_eq!(
+            1210,
+            bind_placeholders("select ?", &[]).unwrap_err().error_number()
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use sqlparser::ast::ObjectName;
+use sqlparser::tokenizer::Token;
+
+use crate::util::index_statement;
+use crate::util::schema_statement::Tokens;
+
+/// `RENAME TABLE tbl_name TO new_tbl_name [, tbl_name2 TO new_tbl_name2] ...` and
+/// `ALTER TABLE tbl_name RENAME [TO | AS] new_tbl_name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenameTableStatement {
+    /// The tables and their new names, renamed one after the other
+    pub table_names: Vec<(ObjectName, ObjectName)>,
+}
+
+/// The sql parser knows neither RENAME TABLE nor RENAME of ALTER TABLE, None if the sql is not one of them
+pub fn parse_rename_table(sql: &str) -> Option<RenameTableStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    let mut table_names = vec![];
+    if tokens.next_words_are(&["RENAME", "TABLE"]) {
+        loop {
+            let table_name = index_statement::next_object_name(&mut tokens)?;
+            if !tokens.next_word_is("TO") {
+                return None;
+            }
+            let new_table_name = index_statement::next_object_name(&mut tokens)?;
+            table_names.push((table_name, new_table_name));
+            if !tokens.next_token_is(&Token::Comma) {
+                break;
+            }
+        }
+    } else if tokens.next_words_are(&["ALTER", "TABLE"]) {
+        let table_name = index_statement::next_object_name(&mut tokens)?;
+        if !tokens.next_word_is("RENAME") {
+            return None;
+        }
+        if !tokens.next_word_is("TO") {
+            tokens.next_word_is("AS");
+        }
+        let new_table_name = index_statement::next_object_name(&mut tokens)?;
+        table_names.push((table_name, new_table_name));
+    } else {
+        return None;
+    }
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(RenameTableStatement { table_names })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::ast::Ident;
+
+    #[test]
+    fn parse() {
+        let statement = parse_rename_table("rename table test.user to test.customer, t1 to t2;").unwrap();
+        assert_eq!(
+            vec![
+                (
+                    ObjectName(vec![Ident::new("test"), Ident::new("user")]),
+                    ObjectName(vec![Ident::new("test"), Ident::new("customer")]),
+                ),
+                (ObjectName(vec![Ident::new("t1")]), ObjectName(vec![Ident::new("t2")])),
+            ],
+            statement.table_names
+        );
+
+        let statement = parse_rename_table("ALTER TABLE user RENAME AS customer").unwrap();
+        assert_eq!(
+            vec![(ObjectName(vec![Ident::new("user")]), ObjectName(vec![Ident::new("customer")]))],
+            statement.table_names
+        );
+        let statement = parse_rename_table("alter table user rename customer").unwrap();
+        assert_eq!(ObjectName(vec![Ident::new("customer")]), statement.table_names[0].1);
+
+        assert_eq!(None, parse_rename_table("rename table user"));
+        assert_eq!(None, parse_rename_table("rename table user to customer,"));
+        assert_eq!(None, parse_rename_table("alter table user rename column name to user_name"));
+        assert_eq!(None, parse_rename_table("alter table user rename index idx_name to idx_user_name"));
+        assert_eq!(None, parse_rename_table("rename user a to b"));
+    }
+}
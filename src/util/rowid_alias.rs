@@ -0,0 +1,102 @@
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace};
+
+use crate::meta::meta_const;
+
+fn is_word(token: &Token, value: &str) -> bool {
+    matches!(token, Token::Word(word) if word.quote_style.is_none() && word.value.to_uppercase() == value)
+}
+
+/// `_rowid` is the rowid of the row under a name which does not clash with the columns,
+/// it is rewritten to the hidden rowid column before parsing. As an item of the select list
+/// it is selected as `rowid AS _rowid`, so the result column keeps the name
+pub fn rewrite_rowid_alias(sql: &str) -> String {
+    if !sql.to_lowercase().contains(meta_const::COLUMN_ROWID_ALIAS) {
+        return sql.to_string();
+    }
+
+    let dialect = GenericDialect {};
+    let tokens = match Tokenizer::new(&dialect, sql).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return sql.to_string(),
+    };
+
+    let mut new_tokens = vec![];
+    let mut changed = false;
+    let mut depth = 0;
+    // the depths of the parentheses whose tokens are in a select list
+    let mut select_list_depths: Vec<usize> = vec![];
+    for index in 0..tokens.len() {
+        let token = tokens[index].clone();
+        match &token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                select_list_depths.retain(|select_list_depth| *select_list_depth < depth);
+                depth = depth.saturating_sub(1);
+            }
+            Token::Word(_) if is_word(&token, "SELECT") => select_list_depths.push(depth),
+            Token::Word(_) if is_word(&token, "FROM") => {
+                select_list_depths.retain(|select_list_depth| *select_list_depth != depth);
+            }
+            Token::Word(word) if word.quote_style.is_none() && word.value.to_lowercase() == meta_const::COLUMN_ROWID_ALIAS => {
+                let next_token = tokens[index + 1..].iter().find(|token| !matches!(token, Token::Whitespace(_)));
+                let is_select_item = select_list_depths.last() == Some(&depth)
+                    && match next_token {
+                        None | Some(Token::Comma) | Some(Token::SemiColon) => true,
+                        Some(next_token) => is_word(next_token, "FROM"),
+                    };
+
+                new_tokens.push(Token::make_word(meta_const::COLUMN_ROWID, None));
+                if is_select_item {
+                    new_tokens.push(Token::Whitespace(Whitespace::Space));
+                    new_tokens.push(Token::make_keyword("AS"));
+                    new_tokens.push(Token::Whitespace(Whitespace::Space));
+                    new_tokens.push(Token::make_word(meta_const::COLUMN_ROWID_ALIAS, None));
+                }
+                changed = true;
+                continue;
+            }
+            _ => {}
+        }
+        new_tokens.push(token);
+    }
+
+    if !changed {
+        return sql.to_string();
+    }
+
+    new_tokens.iter().map(|token| match token {
+        Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+        _ => token.to_string(),
+    }).collect::<Vec<String>>().join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrite() {
+        assert_eq!(
+            "select rowid AS _rowid, name from t where rowid = 'a''b'",
+            rewrite_rowid_alias("select _rowid, name from t where _rowid = 'a''b'")
+        );
+        assert_eq!(
+            "SELECT t.rowid AS _rowid FROM t ORDER BY rowid",
+            rewrite_rowid_alias("SELECT t._rowid FROM t ORDER BY _rowid")
+        );
+        assert_eq!(
+            "select rowid as id from t",
+            rewrite_rowid_alias("select _rowid as id from t")
+        );
+        assert_eq!(
+            "select name from t where rowid in (select rowid AS _rowid from t)",
+            rewrite_rowid_alias("select name from t where _rowid in (select _rowid from t)")
+        );
+        assert_eq!(
+            "delete from t where rowid = 'x'",
+            rewrite_rowid_alias("delete from t where _rowid = 'x'")
+        );
+        assert_eq!("select '_rowid' from t", rewrite_rowid_alias("select '_rowid' from t"));
+    }
+}
@@ -0,0 +1,277 @@
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// `CREATE {DATABASE | SCHEMA} [IF NOT EXISTS] db_name [[DEFAULT] {CHARACTER SET | CHARSET} [=] charset_name] [[DEFAULT] COLLATE [=] collation_name]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateSchemaStatement {
+    pub schema_name: ObjectName,
+    pub if_not_exists: bool,
+    pub character_set_name: Option<String>,
+    pub collation_name: Option<String>,
+}
+
+/// `SHOW CREATE {DATABASE | SCHEMA} [IF NOT EXISTS] db_name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowCreateSchemaStatement {
+    pub schema_name: ObjectName,
+    pub if_not_exists: bool,
+}
+
+/// `USE db_name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct UseStatement {
+    pub schema_name: String,
+}
+
+/// The tokens of the statement without the whitespaces and the trailing semicolons
+pub(crate) struct Tokens {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokens {
+    pub(crate) fn new(sql: &str) -> Option<Self> {
+        let dialect = GenericDialect {};
+        let mut tokens: Vec<Token> = Tokenizer::new(&dialect, sql)
+            .tokenize()
+            .ok()?
+            .into_iter()
+            .filter(|token| !matches!(token, Token::Whitespace(_)))
+            .collect();
+        while let Some(Token::SemiColon) = tokens.last() {
+            tokens.pop();
+        }
+        Some(Self { tokens, index: 0 })
+    }
+
+    pub(crate) fn is_end(&self) -> bool {
+        self.index >= self.tokens.len()
+    }
+
+    /// Consume the keyword if it is the next token
+    pub(crate) fn next_word_is(&mut self, value: &str) -> bool {
+        match self.tokens.get(self.index) {
+            Some(Token::Word(word)) if word.quote_style.is_none() && word.value.to_uppercase() == value => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consume the keywords if they are all the next tokens
+    pub(crate) fn next_words_are(&mut self, values: &[&str]) -> bool {
+        let index = self.index;
+        if values.iter().all(|value| self.next_word_is(value)) {
+            return true;
+        }
+        self.index = index;
+        false
+    }
+
+    /// Remove the keywords if they are all the last tokens
+    pub(crate) fn last_words_are(&mut self, values: &[&str]) -> bool {
+        if self.tokens.len() < self.index + values.len() {
+            return false;
+        }
+        let start = self.tokens.len() - values.len();
+        let matched = self.tokens[start..].iter().zip(values.iter()).all(|(token, value)| match token {
+            Token::Word(word) => word.quote_style.is_none() && word.value.to_uppercase() == *value,
+            _ => false,
+        });
+        if matched {
+            self.tokens.truncate(start);
+        }
+        matched
+    }
+
+    pub(crate) fn next_eq(&mut self) {
+        if let Some(Token::Eq) = self.tokens.get(self.index) {
+            self.index += 1;
+        }
+    }
+
+    pub(crate) fn next_name(&mut self) -> Option<String> {
+        let name = match self.tokens.get(self.index)? {
+            Token::Word(word) => word.value.clone(),
+            Token::SingleQuotedString(value) => value.clone(),
+            _ => return None,
+        };
+        self.index += 1;
+        Some(name)
+    }
+
+    /// Consume the token if it is the next one
+    pub(crate) fn next_token_is(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.index) == Some(token) {
+            self.index += 1;
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn peek_token(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    pub(crate) fn next_token(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index)?.clone();
+        self.index += 1;
+        Some(token)
+    }
+
+    /// The sql of the tokens not consumed yet
+    pub(crate) fn remaining_sql(&mut self) -> String {
+        let sql = Self::tokens_sql(&self.tokens[self.index..]);
+        self.index = self.tokens.len();
+        sql
+    }
+
+    /// The sql of the tokens before the keywords, the tokens and the keywords are consumed.
+    /// None if the keywords are not in the tokens not consumed yet
+    pub(crate) fn next_sql_until_words(&mut self, values: &[&str]) -> Option<String> {
+        let start = (self.index..self.tokens.len()).find(|start| {
+            values.iter().enumerate().all(|(offset, value)| match self.tokens.get(start + offset) {
+                Some(Token::Word(word)) => word.quote_style.is_none() && word.value.to_uppercase() == *value,
+                _ => false,
+            })
+        })?;
+        let sql = Self::tokens_sql(&self.tokens[self.index..start]);
+        self.index = start + values.len();
+        Some(sql)
+    }
+
+    /// Whether the keywords are in the tokens not consumed yet, outside the parentheses
+    pub(crate) fn contains_words(&self, values: &[&str]) -> bool {
+        let mut depth = 0;
+        for start in self.index..self.tokens.len() {
+            match &self.tokens[start] {
+                Token::LParen => depth += 1,
+                Token::RParen => depth -= 1,
+                _ => {}
+            }
+            let matched = values.iter().enumerate().all(|(offset, value)| match self.tokens.get(start + offset) {
+                Some(Token::Word(word)) => word.quote_style.is_none() && word.value.to_uppercase() == *value,
+                _ => false,
+            });
+            if depth == 0 && matched {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn tokens_sql(tokens: &[Token]) -> String {
+        tokens
+            .iter()
+            .map(|token| match token {
+                Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+                _ => token.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub(crate) fn next_string(&mut self) -> Option<String> {
+        match self.tokens.get(self.index)? {
+            Token::SingleQuotedString(value) => {
+                let value = value.clone();
+                self.index += 1;
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn next_number(&mut self) -> Option<u64> {
+        match self.tokens.get(self.index)? {
+            Token::Number(value, _) => {
+                let number = value.parse::<u64>().ok()?;
+                self.index += 1;
+                Some(number)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The sql parser does not know the charset and the collation of CREATE DATABASE,
+/// None if the sql is not a CREATE DATABASE statement
+pub fn parse_create_schema(sql: &str) -> Option<CreateSchemaStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("CREATE") || !(tokens.next_word_is("DATABASE") || tokens.next_word_is("SCHEMA")) {
+        return None;
+    }
+    let if_not_exists = tokens.next_words_are(&["IF", "NOT", "EXISTS"]);
+    let schema_name = tokens.next_name()?;
+
+    let mut character_set_name = None;
+    let mut collation_name = None;
+    while !tokens.is_end() {
+        tokens.next_word_is("DEFAULT");
+        if tokens.next_words_are(&["CHARACTER", "SET"]) || tokens.next_word_is("CHARSET") {
+            tokens.next_eq();
+            character_set_name = Some(tokens.next_name()?.to_lowercase());
+        } else if tokens.next_word_is("COLLATE") {
+            tokens.next_eq();
+            collation_name = Some(tokens.next_name()?.to_lowercase());
+        } else {
+            return None;
+        }
+    }
+
+    Some(CreateSchemaStatement {
+        schema_name: ObjectName(vec![Ident::new(schema_name)]),
+        if_not_exists,
+        character_set_name,
+        collation_name,
+    })
+}
+
+/// The sql parser does not know SHOW CREATE DATABASE,
+/// None if the sql is not a SHOW CREATE DATABASE statement
+pub fn parse_show_create_schema(sql: &str) -> Option<ShowCreateSchemaStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["SHOW", "CREATE"]) || !(tokens.next_word_is("DATABASE") || tokens.next_word_is("SCHEMA")) {
+        return None;
+    }
+    let if_not_exists = tokens.next_words_are(&["IF", "NOT", "EXISTS"]);
+    let schema_name = tokens.next_name()?;
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(ShowCreateSchemaStatement {
+        schema_name: ObjectName(vec![Ident::new(schema_name)]),
+        if_not_exists,
+    })
+}
+
+/// The sql parser does not know USE, None if the sql is not a USE statement
+pub fn parse_use(sql: &str) -> Option<UseStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("USE") {
+        return None;
+    }
+    let schema_name = tokens.next_name()?;
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(UseStatement { schema_name })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_use_statement() {
+        assert_eq!(Some(UseStatement { schema_name: "test".to_string() }), parse_use("use test"));
+        assert_eq!(Some(UseStatement { schema_name: "my db".to_string() }), parse_use("USE `my db`;"));
+        assert_eq!(None, parse_use("use"));
+        assert_eq!(None, parse_use("use test other"));
+        assert_eq!(None, parse_use("select 'use test'"));
+    }
+}
@@ -0,0 +1,143 @@
+use sqlparser::tokenizer::Token;
+
+use crate::util::schema_statement::Tokens;
+
+/// `name = value` of SET
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemVariableAssignment {
+    pub name: String,
+    /// Set by SET GLOBAL or `@@global.name`
+    pub global: bool,
+    /// None is DEFAULT
+    pub value: Option<String>,
+}
+
+/// `SET [GLOBAL | SESSION | LOCAL] name = value [, [GLOBAL | SESSION | LOCAL] name = value] ...`,
+/// the name can be `@@name`, `@@global.name`, `@@session.name` or `@@local.name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetVariableStatement {
+    pub assignments: Vec<SystemVariableAssignment>,
+}
+
+fn next_value(tokens: &mut Tokens) -> Option<Option<String>> {
+    let value = match tokens.next_token()? {
+        Token::SingleQuotedString(value) => value,
+        Token::Number(value, _) => value,
+        Token::Minus => match tokens.next_token()? {
+            Token::Number(value, _) => format!("-{}", value),
+            _ => return None,
+        },
+        Token::Word(word) if word.quote_style.is_none() && word.value.to_uppercase() == "DEFAULT" => return Some(None),
+        Token::Word(word) if !word.value.starts_with('@') => word.value,
+        _ => return None,
+    };
+    Some(Some(value))
+}
+
+/// The SET of the sql parser only sets one variable and does not know its scope,
+/// None if the sql is not a SET of system variables with literal values
+pub fn parse_set_variable(sql: &str) -> Option<SetVariableStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("SET") {
+        return None;
+    }
+
+    // the scope keyword is kept by the next assignments without one
+    let mut global = false;
+    let mut assignments = vec![];
+    loop {
+        if tokens.next_word_is("GLOBAL") {
+            global = true;
+        } else if tokens.next_word_is("SESSION") || tokens.next_word_is("LOCAL") {
+            global = false;
+        }
+
+        let mut assignment_global = global;
+        let mut name = match tokens.next_token()? {
+            Token::Word(word) => word.value,
+            _ => return None,
+        };
+        if let Some(scope) = name.strip_prefix("@@").map(|scope| scope.to_string()) {
+            if tokens.next_token_is(&Token::Period) {
+                assignment_global = match scope.to_uppercase().as_str() {
+                    "GLOBAL" => true,
+                    "SESSION" | "LOCAL" => false,
+                    _ => return None,
+                };
+                name = match tokens.next_token()? {
+                    Token::Word(word) => word.value,
+                    _ => return None,
+                };
+            } else {
+                name = scope;
+            }
+        }
+        // the user variables are set by the SET of the sql parser
+        if name.is_empty() || name.starts_with('@') {
+            return None;
+        }
+
+        if !tokens.next_token_is(&Token::Eq) {
+            return None;
+        }
+        let value = next_value(&mut tokens)?;
+        assignments.push(SystemVariableAssignment {
+            name,
+            global: assignment_global,
+            value,
+        });
+
+        if tokens.is_end() {
+            break;
+        }
+        if !tokens.next_token_is(&Token::Comma) {
+            return None;
+        }
+    }
+
+    Some(SetVariableStatement { assignments })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignment(name: &str, global: bool, value: Option<&str>) -> SystemVariableAssignment {
+        SystemVariableAssignment {
+            name: name.to_string(),
+            global,
+            value: value.map(|value| value.to_string()),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Some(SetVariableStatement { assignments: vec![assignment("autocommit", false, Some("0"))] }),
+            parse_set_variable("set autocommit = 0;")
+        );
+        assert_eq!(
+            Some(SetVariableStatement {
+                assignments: vec![
+                    assignment("max_allowed_packet", true, Some("1048576")),
+                    assignment("wait_timeout", true, Some("60")),
+                    assignment("time_zone", false, Some("+08:00")),
+                    assignment("sql_mode", false, None),
+                ]
+            }),
+            parse_set_variable("SET GLOBAL max_allowed_packet = 1048576, wait_timeout = 60, @@session.time_zone = '+08:00', SESSION sql_mode = DEFAULT")
+        );
+        assert_eq!(
+            Some(SetVariableStatement { assignments: vec![assignment("transaction_isolation", true, Some("READ-COMMITTED"))] }),
+            parse_set_variable("set @@global.transaction_isolation = 'READ-COMMITTED'")
+        );
+        assert_eq!(
+            Some(SetVariableStatement { assignments: vec![assignment("net_write_timeout", false, Some("-1"))] }),
+            parse_set_variable("set @@net_write_timeout = -1")
+        );
+        assert_eq!(None, parse_set_variable("set @a = 1"));
+        assert_eq!(None, parse_set_variable("set max_execution_time = 10 * 2"));
+        assert_eq!(None, parse_set_variable("set session transaction isolation level read committed"));
+        assert_eq!(None, parse_set_variable("select 1"));
+    }
+}
@@ -0,0 +1,263 @@
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::util::schema_statement::Tokens;
+
+/// `SHOW ... LIMIT [offset,] row_count` or `SHOW ... LIMIT row_count OFFSET offset`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowLimitStatement {
+    /// The sql of the SHOW statement without the LIMIT
+    pub statement_sql: String,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+/// `SHOW [GLOBAL | SESSION] VARIABLES [LIKE 'pattern' | WHERE expr]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowVariablesStatement {
+    /// SHOW GLOBAL VARIABLES shows the global values, the others the values of the session
+    pub global: bool,
+    pub filter: Option<ShowVariablesFilter>,
+}
+
+/// `SHOW {WARNINGS | ERRORS}` or `SHOW COUNT(*) {WARNINGS | ERRORS}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowWarningsStatement {
+    /// SHOW ERRORS only shows the errors
+    pub errors_only: bool,
+    /// SHOW COUNT(*) shows the number of the conditions, as @@warning_count and @@error_count
+    pub count: bool,
+}
+
+/// `SHOW {CHARACTER SET | CHARSET | COLLATION} [LIKE 'pattern']`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowCharsetStatement {
+    /// SHOW COLLATION shows the collations, the others the character sets
+    pub collation: bool,
+    /// The pattern of the names
+    pub pattern: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShowVariablesFilter {
+    /// The pattern of the names
+    Like(String),
+    /// The sql of the condition on the Variable_name and the Value columns
+    Where(String),
+}
+
+fn number(token: &Token) -> Option<usize> {
+    match token {
+        Token::Number(value, _) => value.parse::<usize>().ok(),
+        _ => None,
+    }
+}
+
+fn is_word(token: &Token, value: &str) -> bool {
+    matches!(token, Token::Word(word) if word.quote_style.is_none() && word.value.to_uppercase() == value)
+}
+
+/// The sql parser does not know the LIMIT of SHOW,
+/// None if the sql is not a SHOW statement with a LIMIT
+pub fn parse_show_limit(sql: &str) -> Option<ShowLimitStatement> {
+    let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, sql).tokenize().ok()?;
+
+    // the indexes of the tokens which are not whitespaces
+    let indexes: Vec<usize> = (0..tokens.len())
+        .filter(|index| !matches!(tokens[*index], Token::Whitespace(_)))
+        .collect();
+    if !is_word(&tokens[*indexes.first()?], "SHOW") {
+        return None;
+    }
+
+    let tail: Vec<&Token> = indexes.iter().rev().take(4).rev().map(|index| &tokens[*index]).collect();
+    let (limit_position, limit, offset) = match tail.as_slice() {
+        [_, _, limit_word, row_count] if is_word(limit_word, "LIMIT") => (indexes.len() - 2, number(row_count)?, 0),
+        [limit_word, offset, Token::Comma, row_count] if is_word(limit_word, "LIMIT") => {
+            (indexes.len() - 4, number(row_count)?, number(offset)?)
+        }
+        [limit_word, row_count, offset_word, offset] if is_word(limit_word, "LIMIT") && is_word(offset_word, "OFFSET") => {
+            (indexes.len() - 4, number(row_count)?, number(offset)?)
+        }
+        _ => return None,
+    };
+    if limit_position < 2 {
+        return None;
+    }
+
+    let statement_sql = tokens[..indexes[limit_position]]
+        .iter()
+        .map(|token| match token {
+            Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+            _ => token.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("");
+
+    Some(ShowLimitStatement {
+        statement_sql: statement_sql.trim().to_string(),
+        limit,
+        offset,
+    })
+}
+
+/// The sql parser does not know the scope of SHOW VARIABLES,
+/// None if the sql is not a SHOW VARIABLES statement
+pub fn parse_show_variables(sql: &str) -> Option<ShowVariablesStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("SHOW") {
+        return None;
+    }
+    let global = if tokens.next_word_is("GLOBAL") {
+        true
+    } else {
+        tokens.next_word_is("SESSION") || tokens.next_word_is("LOCAL");
+        false
+    };
+    if !tokens.next_word_is("VARIABLES") {
+        return None;
+    }
+
+    let filter = if tokens.next_word_is("LIKE") {
+        Some(ShowVariablesFilter::Like(tokens.next_string()?))
+    } else if tokens.next_word_is("WHERE") {
+        let condition = tokens.remaining_sql();
+        if condition.is_empty() {
+            return None;
+        }
+        Some(ShowVariablesFilter::Where(condition))
+    } else {
+        None
+    };
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(ShowVariablesStatement { global, filter })
+}
+
+/// None if the sql is not a SHOW CHARACTER SET or a SHOW COLLATION statement
+pub fn parse_show_charset(sql: &str) -> Option<ShowCharsetStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("SHOW") {
+        return None;
+    }
+    let collation = if tokens.next_word_is("COLLATION") {
+        true
+    } else if tokens.next_words_are(&["CHARACTER", "SET"]) || tokens.next_word_is("CHARSET") {
+        false
+    } else {
+        return None;
+    };
+
+    let pattern = if tokens.next_word_is("LIKE") {
+        Some(tokens.next_string()?)
+    } else {
+        None
+    };
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(ShowCharsetStatement { collation, pattern })
+}
+
+/// None if the sql is not a SHOW WARNINGS or a SHOW ERRORS statement, the LIMIT is parsed by parse_show_limit
+pub fn parse_show_warnings(sql: &str) -> Option<ShowWarningsStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_word_is("SHOW") {
+        return None;
+    }
+    let count = tokens.next_word_is("COUNT");
+    if count && !(tokens.next_token_is(&Token::LParen) && tokens.next_token_is(&Token::Mul) && tokens.next_token_is(&Token::RParen)) {
+        return None;
+    }
+    let errors_only = if tokens.next_word_is("ERRORS") {
+        true
+    } else if tokens.next_word_is("WARNINGS") {
+        false
+    } else {
+        return None;
+    };
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(ShowWarningsStatement { errors_only, count })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Some(ShowLimitStatement {
+                statement_sql: "SHOW TABLES".to_string(),
+                limit: 5,
+                offset: 0,
+            }),
+            parse_show_limit("SHOW TABLES LIMIT 5")
+        );
+        assert_eq!(
+            Some(ShowLimitStatement {
+                statement_sql: "show variables like 'a''b'".to_string(),
+                limit: 3,
+                offset: 10,
+            }),
+            parse_show_limit("show variables like 'a''b' limit 10, 3")
+        );
+        assert_eq!(Some(2), parse_show_limit("show databases limit 1 offset 2").map(|statement| statement.offset));
+        assert_eq!(None, parse_show_limit("select * from t limit 5"));
+        assert_eq!(None, parse_show_limit("show tables"));
+    }
+
+    #[test]
+    fn parse_variables() {
+        assert_eq!(Some(ShowVariablesStatement { global: false, filter: None }), parse_show_variables("show variables;"));
+        assert_eq!(
+            Some(ShowVariablesStatement {
+                global: true,
+                filter: Some(ShowVariablesFilter::Like("max\\_%".to_string())),
+            }),
+            parse_show_variables("SHOW GLOBAL VARIABLES LIKE 'max\\_%'")
+        );
+        assert_eq!(
+            Some(ShowVariablesStatement {
+                global: false,
+                filter: Some(ShowVariablesFilter::Where("Variable_name = 'it''s'".to_string())),
+            }),
+            parse_show_variables("show session variables where Variable_name = 'it''s'")
+        );
+        assert_eq!(None, parse_show_variables("show variables where"));
+        assert_eq!(None, parse_show_variables("show variables like"));
+        assert_eq!(None, parse_show_variables("show status"));
+    }
+
+    #[test]
+    fn parse_warnings() {
+        assert_eq!(Some(ShowWarningsStatement { errors_only: false, count: false }), parse_show_warnings("SHOW WARNINGS;"));
+        assert_eq!(Some(ShowWarningsStatement { errors_only: true, count: false }), parse_show_warnings("show errors"));
+        assert_eq!(Some(ShowWarningsStatement { errors_only: false, count: true }), parse_show_warnings("show count(*) warnings"));
+        assert_eq!(None, parse_show_warnings("show count(id) errors"));
+        assert_eq!(None, parse_show_warnings("show warnings limit 1"));
+        assert_eq!(None, parse_show_warnings("show variables"));
+    }
+
+    #[test]
+    fn parse_charset() {
+        assert_eq!(Some(ShowCharsetStatement { collation: false, pattern: None }), parse_show_charset("SHOW CHARACTER SET"));
+        assert_eq!(
+            Some(ShowCharsetStatement { collation: false, pattern: Some("utf8%".to_string()) }),
+            parse_show_charset("show charset like 'utf8%';")
+        );
+        assert_eq!(
+            Some(ShowCharsetStatement { collation: true, pattern: Some("utf8mb4\\_%".to_string()) }),
+            parse_show_charset("SHOW COLLATION LIKE 'utf8mb4\\_%'")
+        );
+        assert_eq!(None, parse_show_charset("show collation where Charset = 'utf8'"));
+        assert_eq!(None, parse_show_charset("show character"));
+    }
+}
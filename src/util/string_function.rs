@@ -0,0 +1,103 @@
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::meta::meta_const;
+
+/// REPLACE(str, from_str, to_str) of mysql, the match is case-sensitive
+/// and an empty from_str returns the str as is
+pub fn replace(value: &str, from: &str, to: &str) -> String {
+    if from.is_empty() {
+        return value.to_string();
+    }
+    value.replace(from, to)
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// LOCATE(substr, str, pos) of mysql, the 1-based character position of the first substr in the str
+/// at or after the pos, 0 if not found. The match is case-insensitive as the default collation.
+pub fn locate(sub: &str, value: &str, position: i64) -> i64 {
+    if position < 1 {
+        return 0;
+    }
+
+    let sub_chars: Vec<char> = sub.chars().map(fold_case).collect();
+    let value_chars: Vec<char> = value.chars().map(fold_case).collect();
+    let start = (position - 1) as usize;
+    if start + sub_chars.len() > value_chars.len() {
+        return 0;
+    }
+
+    for i in start..=value_chars.len() - sub_chars.len() {
+        if value_chars[i..i + sub_chars.len()] == sub_chars[..] {
+            return i as i64 + 1;
+        }
+    }
+    0
+}
+
+/// DataFusion has a builtin `replace` which shadows the udf,
+/// rewrite the `REPLACE(` function calls to the udf of mysql before parsing.
+/// The `REPLACE INTO` statement is not a function call and is kept.
+pub fn rewrite_replace_function(sql: &str) -> String {
+    let dialect = GenericDialect {};
+    let tokens = match Tokenizer::new(&dialect, sql).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return sql.to_string(),
+    };
+
+    let mut new_tokens = vec![];
+    let mut changed = false;
+    for index in 0..tokens.len() {
+        let token = tokens[index].clone();
+        if let Token::Word(word) = &token {
+            if word.quote_style.is_none() && word.value.to_uppercase() == "REPLACE" {
+                let next_token = tokens[index + 1..].iter().find(|token| !matches!(token, Token::Whitespace(_)));
+                if let Some(Token::LParen) = next_token {
+                    new_tokens.push(Token::make_word(meta_const::FUNCTION_NAME_MYSQL_REPLACE, None));
+                    changed = true;
+                    continue;
+                }
+            }
+        }
+        new_tokens.push(token);
+    }
+
+    if !changed {
+        return sql.to_string();
+    }
+
+    new_tokens.iter().map(|token| match token {
+        Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+        _ => token.to_string(),
+    }).collect::<Vec<String>>().join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_position() {
+        assert_eq!(4, locate("bar", "foobarbar", 1));
+        assert_eq!(7, locate("bar", "foobarbar", 5));
+        assert_eq!(0, locate("xbar", "foobar", 1));
+        assert_eq!(4, locate("é", "café", 1));
+        assert_eq!(1, locate("", "abc", 1));
+        assert_eq!(0, locate("bar", "foobar", 0));
+    }
+
+    #[test]
+    fn rewrite_replace() {
+        assert_eq!(
+            "select mysql_replace(name, 'a''b', 'c') from t",
+            rewrite_replace_function("select replace(name, 'a''b', 'c') from t")
+        );
+        assert_eq!(
+            "replace into t (id) values (1)",
+            rewrite_replace_function("replace into t (id) values (1)")
+        );
+    }
+}
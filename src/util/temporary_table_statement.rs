@@ -0,0 +1,56 @@
+use sqlparser::ast::ObjectName;
+use sqlparser::tokenizer::Token;
+
+use crate::util::index_statement;
+use crate::util::schema_statement::Tokens;
+
+/// `DROP TEMPORARY TABLE [IF EXISTS] tbl_name [, tbl_name] ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropTemporaryTableStatement {
+    pub table_names: Vec<ObjectName>,
+    pub if_exists: bool,
+}
+
+/// The sql parser does not know TEMPORARY of DROP TABLE, None if the sql is not a DROP TEMPORARY TABLE statement
+pub fn parse_drop_temporary_table(sql: &str) -> Option<DropTemporaryTableStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["DROP", "TEMPORARY", "TABLE"]) {
+        return None;
+    }
+    let if_exists = tokens.next_words_are(&["IF", "EXISTS"]);
+    let mut table_names = vec![index_statement::next_object_name(&mut tokens)?];
+    while tokens.next_token_is(&Token::Comma) {
+        table_names.push(index_statement::next_object_name(&mut tokens)?);
+    }
+    // RESTRICT and CASCADE do nothing
+    if !tokens.next_word_is("RESTRICT") {
+        tokens.next_word_is("CASCADE");
+    }
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(DropTemporaryTableStatement { table_names, if_exists })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::ast::Ident;
+
+    #[test]
+    fn parse() {
+        let statement = DropTemporaryTableStatement {
+            table_names: vec![ObjectName(vec![Ident::new("t1")]), ObjectName(vec![Ident::new("test"), Ident::new("t2")])],
+            if_exists: true,
+        };
+        assert_eq!(Some(statement), parse_drop_temporary_table("DROP TEMPORARY TABLE IF EXISTS t1, test.t2;"));
+        let statement = DropTemporaryTableStatement {
+            table_names: vec![ObjectName(vec![Ident::new("t1")])],
+            if_exists: false,
+        };
+        assert_eq!(Some(statement), parse_drop_temporary_table("drop temporary table t1 restrict"));
+        assert_eq!(None, parse_drop_temporary_table("drop table t1"));
+        assert_eq!(None, parse_drop_temporary_table("drop temporary table t1 t2"));
+    }
+}
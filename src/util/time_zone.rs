@@ -0,0 +1,95 @@
+use chrono::{FixedOffset, Local, NaiveDateTime, TimeZone};
+
+use crate::util::date_function;
+
+/// The time zone of mysql, `SYSTEM`, `UTC` or an offset from `-13:59` to `+14:00`.
+/// The named time zones need the time zone tables, they are not supported
+#[derive(Clone, Debug, PartialEq)]
+pub enum MysqlTimeZone {
+    /// The time zone of the server, the offset changes with the daylight saving time
+    System,
+    Offset(FixedOffset),
+}
+
+impl MysqlTimeZone {
+    /// The UTC time of the local time in this time zone,
+    /// None if the local time does not exist, as in the gap of the daylight saving time
+    pub fn to_utc(&self, datetime: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            MysqlTimeZone::System => Local.from_local_datetime(&datetime).earliest().map(|datetime| datetime.naive_utc()),
+            MysqlTimeZone::Offset(offset) => offset.from_local_datetime(&datetime).single().map(|datetime| datetime.naive_utc()),
+        }
+    }
+
+    /// The local time in this time zone of the UTC time
+    pub fn from_utc(&self, datetime: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            MysqlTimeZone::System => Local.from_utc_datetime(&datetime).naive_local(),
+            MysqlTimeZone::Offset(offset) => offset.from_utc_datetime(&datetime).naive_local(),
+        }
+    }
+}
+
+pub fn parse_time_zone(text: &str) -> Option<MysqlTimeZone> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("SYSTEM") {
+        return Some(MysqlTimeZone::System);
+    }
+    if text.eq_ignore_ascii_case("UTC") {
+        return Some(MysqlTimeZone::Offset(FixedOffset::east(0)));
+    }
+
+    let (sign, offset) = match text.chars().next()? {
+        '+' => (1, &text[1..]),
+        '-' => (-1, &text[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = offset.split_once(':')?;
+    if hours.is_empty() || hours.len() > 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours = hours.parse::<i32>().ok()?;
+    let minutes = minutes.parse::<i32>().ok()?;
+    if minutes > 59 {
+        return None;
+    }
+    let seconds = sign * (hours * 3600 + minutes * 60);
+    if seconds < -(13 * 3600 + 59 * 60) || seconds > 14 * 3600 {
+        return None;
+    }
+    Some(MysqlTimeZone::Offset(FixedOffset::east(seconds)))
+}
+
+/// CONVERT_TZ(dt, from_tz, to_tz) of mysql, None if the datetime or a time zone is not valid
+pub fn convert_tz(text: &str, from_time_zone: &str, to_time_zone: &str) -> Option<String> {
+    let (datetime, _) = date_function::parse_datetime(text)?;
+    let from_time_zone = parse_time_zone(from_time_zone)?;
+    let to_time_zone = parse_time_zone(to_time_zone)?;
+
+    let datetime = from_time_zone.to_utc(datetime)?;
+    Some(date_function::format_datetime(to_time_zone.from_utc(datetime)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some(MysqlTimeZone::System), parse_time_zone("system"));
+        assert_eq!(Some(MysqlTimeZone::Offset(FixedOffset::east(0))), parse_time_zone("UTC"));
+        assert_eq!(Some(MysqlTimeZone::Offset(FixedOffset::east(8 * 3600))), parse_time_zone("+08:00"));
+        assert_eq!(Some(MysqlTimeZone::Offset(FixedOffset::west(5 * 3600 + 30 * 60))), parse_time_zone("-5:30"));
+        assert_eq!(None, parse_time_zone("+14:01"));
+        assert_eq!(None, parse_time_zone("08:00"));
+        assert_eq!(None, parse_time_zone("Europe/Paris"));
+    }
+
+    #[test]
+    fn convert() {
+        assert_eq!(Some("2024-01-31 02:00:00".to_string()), convert_tz("2024-01-31 10:00:00", "+08:00", "+00:00"));
+        assert_eq!(Some("2024-01-30 21:30:00".to_string()), convert_tz("2024-01-31 02:00:00", "UTC", "-04:30"));
+        assert_eq!(None, convert_tz("2024-01-31 10:00:00", "+08:00", "Europe/Paris"));
+        assert_eq!(None, convert_tz("not a date", "+08:00", "UTC"));
+    }
+}
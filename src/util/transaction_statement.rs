@@ -0,0 +1,147 @@
+use sqlparser::tokenizer::Token;
+
+use crate::store::lock::LockMode;
+use crate::util::schema_statement::Tokens;
+
+/// `BEGIN [WORK]`, `START TRANSACTION [characteristic [, characteristic] ...]`, `COMMIT [WORK]` and `ROLLBACK [WORK]`
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionStatement {
+    Begin,
+    Commit,
+    Rollback,
+}
+
+/// None if the sql is not a transaction statement, AND CHAIN and RELEASE are not supported
+pub fn parse_transaction(sql: &str) -> Option<TransactionStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    let statement = if tokens.next_word_is("BEGIN") {
+        tokens.next_word_is("WORK");
+        TransactionStatement::Begin
+    } else if tokens.next_words_are(&["START", "TRANSACTION"]) {
+        // the transactions are read write with a snapshot of the committed rows, the characteristics are accepted
+        loop {
+            let characteristic = tokens.next_words_are(&["READ", "ONLY"])
+                || tokens.next_words_are(&["READ", "WRITE"])
+                || tokens.next_words_are(&["WITH", "CONSISTENT", "SNAPSHOT"]);
+            if !characteristic || !tokens.next_token_is(&Token::Comma) {
+                break;
+            }
+        }
+        TransactionStatement::Begin
+    } else if tokens.next_word_is("COMMIT") {
+        tokens.next_word_is("WORK");
+        TransactionStatement::Commit
+    } else if tokens.next_word_is("ROLLBACK") {
+        tokens.next_word_is("WORK");
+        TransactionStatement::Rollback
+    } else {
+        return None;
+    };
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(statement)
+}
+
+/// The statements of the meta data and of the users commit the transaction of the session before they run,
+/// they are written at once and are not rolled back. The temporary tables are of the session, creating and
+/// dropping them do not commit
+pub fn causes_implicit_commit(sql: &str) -> bool {
+    let mut tokens = match Tokens::new(sql) {
+        Some(tokens) => tokens,
+        None => return false,
+    };
+    if tokens.next_words_are(&["CREATE", "TEMPORARY"]) || tokens.next_words_are(&["DROP", "TEMPORARY"]) {
+        return false;
+    }
+    ["CREATE", "DROP", "ALTER", "TRUNCATE", "RENAME", "GRANT", "REVOKE"]
+        .iter()
+        .any(|word| tokens.next_word_is(word))
+}
+
+/// The statements writing the rows of the tables, LOAD DATA writes its rows at once
+pub fn is_row_write(sql: &str) -> bool {
+    let mut tokens = match Tokens::new(sql) {
+        Some(tokens) => tokens,
+        None => return false,
+    };
+    ["INSERT", "REPLACE", "UPDATE", "DELETE"]
+        .iter()
+        .any(|word| tokens.next_word_is(word))
+}
+
+/// `SELECT ... FOR UPDATE`, `SELECT ... FOR SHARE` and `SELECT ... LOCK IN SHARE MODE`, the sql of the SELECT
+/// without its locking clause and the lock of the rows it reads. NOWAIT and SKIP LOCKED are not supported
+pub fn parse_locking_read(sql: &str) -> Option<(String, LockMode)> {
+    let mut tokens = Tokens::new(sql)?;
+    match tokens.peek_token() {
+        Some(Token::Word(word)) if word.quote_style.is_none() && word.value.to_uppercase() == "SELECT" => {}
+        _ => return None,
+    }
+    let lock_mode = if tokens.last_words_are(&["FOR", "UPDATE"]) {
+        LockMode::Exclusive
+    } else if tokens.last_words_are(&["FOR", "SHARE"]) || tokens.last_words_are(&["LOCK", "IN", "SHARE", "MODE"]) {
+        LockMode::Shared
+    } else {
+        return None;
+    };
+
+    Some((tokens.remaining_sql(), lock_mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some(TransactionStatement::Begin), parse_transaction("begin"));
+        assert_eq!(Some(TransactionStatement::Begin), parse_transaction("BEGIN WORK;"));
+        assert_eq!(Some(TransactionStatement::Begin), parse_transaction("start transaction"));
+        assert_eq!(
+            Some(TransactionStatement::Begin),
+            parse_transaction("start transaction with consistent snapshot, read write")
+        );
+        assert_eq!(Some(TransactionStatement::Commit), parse_transaction("commit work"));
+        assert_eq!(Some(TransactionStatement::Rollback), parse_transaction("ROLLBACK;"));
+        assert_eq!(None, parse_transaction("commit and chain"));
+        assert_eq!(None, parse_transaction("rollback to savepoint a"));
+        assert_eq!(None, parse_transaction("start transaction read"));
+        assert_eq!(None, parse_transaction("select 'begin'"));
+    }
+
+    #[test]
+    fn implicit_commit() {
+        assert!(causes_implicit_commit("create table t1 (id int)"));
+        assert!(causes_implicit_commit("TRUNCATE t1;"));
+        assert!(causes_implicit_commit("grant select on *.* to 'u1'"));
+        assert!(!causes_implicit_commit("insert into t1 values (1)"));
+        assert!(!causes_implicit_commit("select 'drop'"));
+        assert!(!causes_implicit_commit("create temporary table t1 (id int)"));
+    }
+
+    #[test]
+    fn row_write() {
+        assert!(is_row_write("insert into t1 values (1)"));
+        assert!(is_row_write("DELETE FROM t1"));
+        assert!(!is_row_write("load data infile 'a.csv' into table t1"));
+        assert!(!is_row_write("select 'update'"));
+    }
+
+    #[test]
+    fn locking_read() {
+        assert_eq!(
+            Some(("SELECT * FROM t1 WHERE id = 1".to_string(), LockMode::Exclusive)),
+            parse_locking_read("SELECT * FROM t1 WHERE id = 1 FOR UPDATE;")
+        );
+        assert_eq!(
+            Some(("select name FROM t1".to_string(), LockMode::Shared)),
+            parse_locking_read("select name FROM t1 lock in share mode")
+        );
+        assert_eq!(Some(LockMode::Shared), parse_locking_read("select 1 for share").map(|(_, lock_mode)| lock_mode));
+        assert_eq!(None, parse_locking_read("select * from t1 for update nowait"));
+        assert_eq!(None, parse_locking_read("select 'for update'"));
+        assert_eq!(None, parse_locking_read("update t1 set name = 'a' for update"));
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use sqlparser::ast::{Assignment, Statement};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::util::schema_statement::Tokens;
+use crate::util::user_variable;
+
+/// What the row which duplicates a unique key of the table does
+#[derive(Clone, Debug, PartialEq)]
+pub enum OnDuplicate {
+    /// `REPLACE`, the rows holding the keys are deleted before the row is inserted
+    Replace,
+    /// `ON DUPLICATE KEY UPDATE assignment_list`, the assignments are applied to the row holding the key
+    Update(String),
+}
+
+/// `REPLACE [INTO] tbl_name ...` and `INSERT [INTO] tbl_name ... ON DUPLICATE KEY UPDATE assignment_list`
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpsertStatement {
+    /// The statement as a plain INSERT
+    pub insert_sql: String,
+    pub on_duplicate: OnDuplicate,
+}
+
+/// The sql parser knows neither REPLACE nor ON DUPLICATE KEY UPDATE, None if the sql is not one of them
+pub fn parse_upsert(sql: &str) -> Option<UpsertStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if tokens.next_word_is("REPLACE") {
+        return Some(UpsertStatement {
+            insert_sql: format!("INSERT {}", tokens.remaining_sql()),
+            on_duplicate: OnDuplicate::Replace,
+        });
+    }
+    if !tokens.next_word_is("INSERT") {
+        return None;
+    }
+    let insert_sql = format!("INSERT {}", tokens.next_sql_until_words(&["ON", "DUPLICATE", "KEY", "UPDATE"])?);
+    let assignments_sql = tokens.remaining_sql();
+    if assignments_sql.is_empty() {
+        return None;
+    }
+
+    Some(UpsertStatement {
+        insert_sql,
+        on_duplicate: OnDuplicate::Update(assignments_sql),
+    })
+}
+
+/// The assignments of ON DUPLICATE KEY UPDATE for one row with constant values. The columns are replaced
+/// by the literals of the values of the row holding the key, `VALUES(col_name)` by the literal of the value
+/// the row would have inserted, NULL for a column which is not in the table. The literals are keyed by the
+/// lowercase column names
+pub fn parse_duplicate_assignments(
+    assignments_sql: &str,
+    row_values: &HashMap<String, String>,
+    insert_values: &HashMap<String, String>,
+) -> MysqlResult<Vec<Assignment>> {
+    let dialect = GenericDialect {};
+    let tokens: Vec<Token> = Tokenizer::new(&dialect, assignments_sql)
+        .tokenize()
+        .map_err(|tokenizer_error| syntax_error(format!("{:?}", tokenizer_error)))?
+        .into_iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_)))
+        .collect();
+
+    let mut sql = vec![];
+    let mut index = 0;
+    let mut depth = 0;
+    // the column assigned is the first word of every assignment
+    let mut next_is_assigned_column = true;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            Token::Comma if depth == 0 => next_is_assigned_column = true,
+            Token::Word(_) if next_is_assigned_column => {
+                next_is_assigned_column = false;
+                sql.push(user_variable::token_sql(token));
+                index += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match (token, tokens.get(index + 1), tokens.get(index + 2), tokens.get(index + 3)) {
+            (Token::Word(function), Some(Token::LParen), Some(Token::Word(column_name)), Some(Token::RParen))
+                if function.quote_style.is_none() && function.value.to_uppercase() == "VALUES" =>
+            {
+                let literal = insert_values.get(&column_name.value.to_lowercase());
+                sql.push(literal.cloned().unwrap_or_else(|| "NULL".to_string()));
+                index += 4;
+            }
+            // the column qualified by the table name
+            (Token::Word(_), Some(Token::Period), Some(Token::Word(column_name)), _) if row_values.contains_key(&column_name.value.to_lowercase()) => {
+                sql.push(row_values[&column_name.value.to_lowercase()].clone());
+                index += 3;
+            }
+            (Token::Word(column_name), next_token, _, _) if next_token != Some(&Token::LParen) && row_values.contains_key(&column_name.value.to_lowercase()) => {
+                sql.push(row_values[&column_name.value.to_lowercase()].clone());
+                index += 1;
+            }
+            _ => {
+                sql.push(user_variable::token_sql(token));
+                index += 1;
+            }
+        }
+    }
+
+    // the assignments are parsed as the ones of an UPDATE
+    let update_sql = format!("UPDATE t SET {}", sql.join(" "));
+    let statements = Parser::parse_sql(&dialect, update_sql.as_str())
+        .map_err(|parser_error| syntax_error(format!("{:?}", parser_error)))?;
+    match statements.into_iter().next() {
+        Some(Statement::Update { assignments, .. }) => Ok(assignments),
+        _ => Err(syntax_error(assignments_sql.to_string())),
+    }
+}
+
+fn syntax_error(error: String) -> MysqlError {
+    let message = format!(
+        "You have an error in your SQL syntax; check the manual that corresponds to your MySQL server version for the right syntax to use near 'ON DUPLICATE KEY UPDATE', error: {}",
+        error
+    );
+    log::error!("{}", message);
+    MysqlError::new_server_error(1064, "42000", message.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let statement = parse_upsert("replace into user (id, name) values (1, 'a');").unwrap();
+        assert_eq!(OnDuplicate::Replace, statement.on_duplicate);
+        assert_eq!("INSERT into user ( id , name ) values ( 1 , 'a' )", statement.insert_sql);
+
+        let statement = parse_upsert("insert into user values (1, 'on duplicate key update') on duplicate key update name = values(name)").unwrap();
+        assert_eq!(OnDuplicate::Update("name = values ( name )".to_string()), statement.on_duplicate);
+        assert_eq!("INSERT into user values ( 1 , 'on duplicate key update' )", statement.insert_sql);
+
+        assert_eq!(None, parse_upsert("insert into user values (1, 'a')"));
+        assert_eq!(None, parse_upsert("insert into user values (1, 'a') on duplicate key update"));
+        assert_eq!(None, parse_upsert("select replace('a', 'b', 'c')"));
+    }
+
+    #[test]
+    fn duplicate_assignments() -> MysqlResult<()> {
+        let mut row_values = HashMap::new();
+        row_values.insert("name".to_string(), "'a'".to_string());
+        row_values.insert("count".to_string(), "3".to_string());
+        let mut insert_values = HashMap::new();
+        insert_values.insert("name".to_string(), "'b'".to_string());
+        let assignments = parse_duplicate_assignments(
+            "name = concat(name, VALUES(name)), count = user.count + 1, id = values(id)",
+            &row_values,
+            &insert_values,
+        )?;
+        let assignments = assignments.iter().map(|assignment| assignment.to_string()).collect::<Vec<String>>();
+        assert_eq!(vec!["name = concat('a', 'b')", "count = 3 + 1", "id = NULL"], assignments);
+
+        assert_eq!(1064, parse_duplicate_assignments("name = ", &row_values, &insert_values).unwrap_err().error_number());
+
+        Ok(())
+    }
+}
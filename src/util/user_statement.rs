@@ -0,0 +1,328 @@
+use sqlparser::tokenizer::Token;
+
+use crate::core::privilege::{Account, GrantLevel, Privilege, PRIVILEGES};
+use crate::util::schema_statement::Tokens;
+
+/// A user of CREATE USER with its password
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserSpecification {
+    pub account: Account,
+    pub password: Option<String>,
+}
+
+/// `CREATE USER [IF NOT EXISTS] user [IDENTIFIED BY 'password'] [, user [IDENTIFIED BY 'password']] ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateUserStatement {
+    pub if_not_exists: bool,
+    pub users: Vec<UserSpecification>,
+}
+
+/// `DROP USER [IF EXISTS] user [, user] ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropUserStatement {
+    pub if_exists: bool,
+    pub accounts: Vec<Account>,
+}
+
+/// `SET PASSWORD [FOR user] = {'password' | PASSWORD('password')}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetPasswordStatement {
+    /// None for the user of the session
+    pub account: Option<Account>,
+    pub password: String,
+}
+
+/// `GRANT priv_type [, priv_type] ... ON {*.* | db_name.* | *} TO user [, user] ... [WITH GRANT OPTION]`
+/// and `REVOKE priv_type [, priv_type] ... ON {*.* | db_name.* | *} FROM user [, user] ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrantStatement {
+    pub revoke: bool,
+    /// ALL is all the privileges of the level, GRANT OPTION is one of them with WITH GRANT OPTION
+    pub privileges: Vec<Privilege>,
+    pub level: GrantLevel,
+    pub accounts: Vec<Account>,
+}
+
+/// `SHOW GRANTS [FOR user]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowGrantsStatement {
+    /// None for the user of the session
+    pub account: Option<Account>,
+}
+
+/// `user[@host]`, the user and the host are names or strings, the host is % without it.
+/// The tokenizer reads `@host` and an unquoted `user@host` as one word
+fn next_account(tokens: &mut Tokens) -> Option<Account> {
+    let (user, host) = match tokens.next_token()? {
+        Token::Word(word) if word.quote_style.is_none() && word.value.contains('@') => {
+            let at_index = word.value.find('@')?;
+            (word.value[..at_index].to_string(), Some(word.value[at_index + 1..].to_string()))
+        }
+        Token::Word(word) => (word.value, None),
+        Token::SingleQuotedString(value) => (value, None),
+        _ => return None,
+    };
+    let host = match host {
+        Some(host) => Some(host),
+        None => match tokens.peek_token() {
+            Some(Token::Word(word)) if word.quote_style.is_none() && word.value.starts_with('@') => {
+                let host = word.value[1..].to_string();
+                tokens.next_token();
+                Some(host)
+            }
+            _ => None,
+        },
+    };
+    let host = match host {
+        // the host is quoted, it is the next token
+        Some(host) if host.is_empty() => tokens.next_name()?,
+        Some(host) => host,
+        None => "%".to_string(),
+    };
+    Some(Account::new(user.as_str(), host.as_str()))
+}
+
+fn next_accounts(tokens: &mut Tokens) -> Option<Vec<Account>> {
+    let mut accounts = vec![next_account(tokens)?];
+    while tokens.next_token_is(&Token::Comma) {
+        accounts.push(next_account(tokens)?);
+    }
+    Some(accounts)
+}
+
+/// The sql parser does not know the account statements, None if the sql is not a CREATE USER statement
+pub fn parse_create_user(sql: &str) -> Option<CreateUserStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["CREATE", "USER"]) {
+        return None;
+    }
+    let if_not_exists = tokens.next_words_are(&["IF", "NOT", "EXISTS"]);
+
+    let mut users = vec![];
+    loop {
+        let account = next_account(&mut tokens)?;
+        let password = if tokens.next_words_are(&["IDENTIFIED", "BY"]) {
+            Some(tokens.next_string()?)
+        } else {
+            None
+        };
+        users.push(UserSpecification { account, password });
+        if !tokens.next_token_is(&Token::Comma) {
+            break;
+        }
+    }
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(CreateUserStatement { if_not_exists, users })
+}
+
+/// None if the sql is not a DROP USER statement
+pub fn parse_drop_user(sql: &str) -> Option<DropUserStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["DROP", "USER"]) {
+        return None;
+    }
+    let if_exists = tokens.next_words_are(&["IF", "EXISTS"]);
+    let accounts = next_accounts(&mut tokens)?;
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(DropUserStatement { if_exists, accounts })
+}
+
+/// None if the sql is not a SET PASSWORD statement
+pub fn parse_set_password(sql: &str) -> Option<SetPasswordStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["SET", "PASSWORD"]) {
+        return None;
+    }
+    let account = if tokens.next_word_is("FOR") {
+        Some(next_account(&mut tokens)?)
+    } else {
+        None
+    };
+    if !tokens.next_token_is(&Token::Eq) {
+        return None;
+    }
+    let password = if tokens.next_word_is("PASSWORD") {
+        if !tokens.next_token_is(&Token::LParen) {
+            return None;
+        }
+        let password = tokens.next_string()?;
+        if !tokens.next_token_is(&Token::RParen) {
+            return None;
+        }
+        password
+    } else {
+        tokens.next_string()?
+    };
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(SetPasswordStatement { account, password })
+}
+
+/// The privileges of GRANT and REVOKE before ON, ALL is expanded when the level is known
+fn next_privileges(tokens: &mut Tokens) -> Option<(Vec<Privilege>, bool)> {
+    let mut privileges = vec![];
+    let mut all = false;
+    loop {
+        if tokens.next_word_is("ALL") {
+            tokens.next_word_is("PRIVILEGES");
+            all = true;
+        } else if !tokens.next_word_is("USAGE") {
+            // the names of two words first, CREATE USER is not CREATE
+            let mut names = PRIVILEGES.iter().collect::<Vec<_>>();
+            names.sort_by_key(|privilege| !privilege.name().contains(' '));
+            let privilege = names.into_iter().find(|privilege| {
+                let words = privilege.name().split(' ').collect::<Vec<_>>();
+                tokens.next_words_are(words.as_slice())
+            })?;
+            privileges.push(*privilege);
+        }
+        if !tokens.next_token_is(&Token::Comma) {
+            break;
+        }
+    }
+    Some((privileges, all))
+}
+
+fn next_grant_level(tokens: &mut Tokens) -> Option<GrantLevel> {
+    if tokens.next_token_is(&Token::Mul) {
+        if !tokens.next_token_is(&Token::Period) {
+            return Some(GrantLevel::DefaultSchema);
+        }
+        if !tokens.next_token_is(&Token::Mul) {
+            return None;
+        }
+        return Some(GrantLevel::Global);
+    }
+    let schema_name = tokens.next_name()?;
+    // the grants on the tables are not supported
+    if !tokens.next_token_is(&Token::Period) || !tokens.next_token_is(&Token::Mul) {
+        return None;
+    }
+    Some(GrantLevel::Schema(schema_name))
+}
+
+/// None if the sql is not a GRANT or a REVOKE statement
+pub fn parse_grant(sql: &str) -> Option<GrantStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    let revoke = if tokens.next_word_is("REVOKE") {
+        true
+    } else if tokens.next_word_is("GRANT") {
+        false
+    } else {
+        return None;
+    };
+    let (mut privileges, all) = next_privileges(&mut tokens)?;
+    if !tokens.next_word_is("ON") {
+        return None;
+    }
+    let level = next_grant_level(&mut tokens)?;
+    if !tokens.next_word_is(if revoke { "FROM" } else { "TO" }) {
+        return None;
+    }
+    let accounts = next_accounts(&mut tokens)?;
+    if !revoke && tokens.next_words_are(&["WITH", "GRANT", "OPTION"]) {
+        privileges.push(Privilege::Grant);
+    }
+    if !tokens.is_end() {
+        return None;
+    }
+
+    if all {
+        let all_privileges = PRIVILEGES
+            .iter()
+            .filter(|privilege| **privilege != Privilege::Grant)
+            .filter(|privilege| level == GrantLevel::Global || privilege.is_schema_privilege());
+        privileges.extend(all_privileges);
+    }
+    privileges.dedup();
+
+    Some(GrantStatement {
+        revoke,
+        privileges,
+        level,
+        accounts,
+    })
+}
+
+/// None if the sql is not a SHOW GRANTS statement
+pub fn parse_show_grants(sql: &str) -> Option<ShowGrantsStatement> {
+    let mut tokens = Tokens::new(sql)?;
+    if !tokens.next_words_are(&["SHOW", "GRANTS"]) {
+        return None;
+    }
+    let mut account = None;
+    if tokens.next_word_is("FOR") {
+        if tokens.next_word_is("CURRENT_USER") {
+            if tokens.next_token_is(&Token::LParen) && !tokens.next_token_is(&Token::RParen) {
+                return None;
+            }
+        } else {
+            account = Some(next_account(&mut tokens)?);
+        }
+    }
+    if !tokens.is_end() {
+        return None;
+    }
+
+    Some(ShowGrantsStatement { account })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_account() {
+        let statement = parse_create_user("create user 'app'@'192.168.%' identified by 'secret', dev").unwrap();
+        assert!(!statement.if_not_exists);
+        assert_eq!(Account::new("app", "192.168.%"), statement.users[0].account);
+        assert_eq!(Some("secret".to_string()), statement.users[0].password);
+        assert_eq!(Account::new("dev", "%"), statement.users[1].account);
+        assert_eq!(None, statement.users[1].password);
+
+        let statement = parse_drop_user("DROP USER IF EXISTS app@localhost, 'dev'@localhost;").unwrap();
+        assert!(statement.if_exists);
+        assert_eq!(vec![Account::new("app", "localhost"), Account::new("dev", "localhost")], statement.accounts);
+
+        let statement = parse_set_password("set password for app@'%' = password('secret')").unwrap();
+        assert_eq!(Some(Account::new("app", "%")), statement.account);
+        assert_eq!("secret", statement.password);
+        assert_eq!(None, parse_set_password("set password = 'secret'").unwrap().account);
+
+        assert_eq!(None, parse_create_user("create user"));
+        assert_eq!(None, parse_set_password("set password_history = 1"));
+    }
+
+    #[test]
+    fn parse_grants() {
+        let statement = parse_grant("GRANT SELECT, INSERT ON test.* TO 'app'@'%' WITH GRANT OPTION").unwrap();
+        assert!(!statement.revoke);
+        assert_eq!(vec![Privilege::Select, Privilege::Insert, Privilege::Grant], statement.privileges);
+        assert_eq!(GrantLevel::Schema("test".to_string()), statement.level);
+        assert_eq!(vec![Account::new("app", "%")], statement.accounts);
+
+        let statement = parse_grant("grant create user, create on *.* to app").unwrap();
+        assert_eq!(vec![Privilege::CreateUser, Privilege::Create], statement.privileges);
+        assert_eq!(GrantLevel::Global, statement.level);
+
+        let statement = parse_grant("revoke all privileges on * from app").unwrap();
+        assert!(statement.revoke);
+        assert_eq!(GrantLevel::DefaultSchema, statement.level);
+        assert!(statement.privileges.iter().all(|privilege| privilege.is_schema_privilege()));
+
+        assert_eq!(None, parse_grant("grant select on test.t1 to app"));
+        assert_eq!(None, parse_grant("grant select on *.* from app"));
+
+        assert_eq!(None, parse_show_grants("show grants").unwrap().account);
+        assert_eq!(None, parse_show_grants("show grants for current_user()").unwrap().account);
+        assert_eq!(Some(Account::new("app", "%")), parse_show_grants("show grants for 'app'").unwrap().account);
+    }
+}
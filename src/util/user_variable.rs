@@ -0,0 +1,205 @@
+use sqlparser::dialect::GenericDialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// `@name = expr` of SET
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserVariableAssignment {
+    /// The lower case name without the `@`
+    pub name: String,
+    /// The sql of the expression, it is evaluated by a SELECT
+    pub expr_sql: String,
+}
+
+/// `SET @name = expr [, @name = expr] ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetUserVariableStatement {
+    pub assignments: Vec<UserVariableAssignment>,
+}
+
+/// `SELECT ... INTO @name [, @name] ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectIntoStatement {
+    /// The sql of the SELECT without the INTO
+    pub select_sql: String,
+    pub variable_names: Vec<String>,
+}
+
+/// The lower case name of the user variable `@name`, None if the token is not a user variable
+pub fn user_variable_name(token: &Token) -> Option<String> {
+    match token {
+        Token::Word(word) if word.quote_style.is_none() && word.value.len() > 1 && word.value.starts_with('@') => {
+            if word.value.starts_with("@@") {
+                return None;
+            }
+            Some(word.value[1..].to_lowercase())
+        }
+        _ => None,
+    }
+}
+
+/// The sql of the token, the quotes of the strings are escaped again
+pub fn token_sql(token: &Token) -> String {
+    match token {
+        Token::SingleQuotedString(value) => format!("'{}'", value.replace("'", "''")),
+        _ => token.to_string(),
+    }
+}
+
+fn tokens_sql(tokens: &[Token]) -> String {
+    tokens.iter().map(token_sql).collect::<Vec<String>>().join("").trim().to_string()
+}
+
+fn is_word(token: &Token, value: &str) -> bool {
+    matches!(token, Token::Word(word) if word.quote_style.is_none() && word.value.to_uppercase() == value)
+}
+
+/// The tokens of the statement without the trailing semicolons
+fn tokenize(sql: &str) -> Option<Vec<Token>> {
+    let dialect = GenericDialect {};
+    let mut tokens = Tokenizer::new(&dialect, sql).tokenize().ok()?;
+    while let Some(Token::SemiColon) | Some(Token::Whitespace(_)) = tokens.last() {
+        tokens.pop();
+    }
+    Some(tokens)
+}
+
+fn next_not_whitespace(tokens: &[Token], mut index: usize) -> Option<usize> {
+    while index < tokens.len() {
+        match tokens[index] {
+            Token::Whitespace(_) => index += 1,
+            _ => return Some(index),
+        }
+    }
+    None
+}
+
+/// The tokens split at the commas out of the parentheses
+fn split_commas(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            Token::Comma if depth == 0 => {
+                parts.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// The sql parser does not know the user variables as the targets of SET,
+/// None if the sql is not a SET of user variables only
+pub fn parse_set_user_variable(sql: &str) -> Option<SetUserVariableStatement> {
+    let tokens = tokenize(sql)?;
+    let set_index = next_not_whitespace(&tokens, 0)?;
+    if !is_word(&tokens[set_index], "SET") {
+        return None;
+    }
+
+    let mut assignments = vec![];
+    for part in split_commas(&tokens[set_index + 1..]) {
+        let name_index = next_not_whitespace(part, 0)?;
+        let name = user_variable_name(&part[name_index])?;
+        // `@name = expr` or `@name := expr`
+        let mut eq_index = next_not_whitespace(part, name_index + 1)?;
+        if part[eq_index] == Token::Colon {
+            eq_index += 1;
+        }
+        if part.get(eq_index) != Some(&Token::Eq) {
+            return None;
+        }
+        let expr_sql = tokens_sql(&part[eq_index + 1..]);
+        if expr_sql.is_empty() {
+            return None;
+        }
+        assignments.push(UserVariableAssignment { name, expr_sql });
+    }
+
+    Some(SetUserVariableStatement { assignments })
+}
+
+/// The sql parser does not know SELECT ... INTO @var,
+/// None if the sql is not a SELECT into user variables
+pub fn parse_select_into(sql: &str) -> Option<SelectIntoStatement> {
+    let tokens = tokenize(sql)?;
+    let select_index = next_not_whitespace(&tokens, 0)?;
+    if !is_word(&tokens[select_index], "SELECT") {
+        return None;
+    }
+
+    let mut depth = 0;
+    for index in select_index + 1..tokens.len() {
+        match &tokens[index] {
+            Token::LParen => depth += 1,
+            Token::RParen => depth -= 1,
+            token if depth == 0 && is_word(token, "INTO") => {
+                let mut variable_names = vec![];
+                let mut end = index + 1;
+                loop {
+                    let name_index = next_not_whitespace(&tokens, end)?;
+                    variable_names.push(user_variable_name(&tokens[name_index])?);
+                    end = name_index + 1;
+                    match next_not_whitespace(&tokens, end) {
+                        Some(comma_index) if tokens[comma_index] == Token::Comma => end = comma_index + 1,
+                        _ => break,
+                    }
+                }
+                let select_sql = format!("{} {}", tokens_sql(&tokens[..index]), tokens_sql(&tokens[end..]));
+                return Some(SelectIntoStatement {
+                    select_sql: select_sql.trim().to_string(),
+                    variable_names,
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_set() {
+        assert_eq!(
+            Some(SetUserVariableStatement {
+                assignments: vec![
+                    UserVariableAssignment { name: "a".to_string(), expr_sql: "1".to_string() },
+                    UserVariableAssignment { name: "total".to_string(), expr_sql: "concat('x,''y', @a)".to_string() },
+                    UserVariableAssignment { name: "c".to_string(), expr_sql: "(select max(id) from t)".to_string() },
+                ]
+            }),
+            parse_set_user_variable("SET @a = 1, @Total = concat('x,''y', @a), @c := (select max(id) from t);")
+        );
+        assert_eq!(None, parse_set_user_variable("set @a = 1, autocommit = 0"));
+        assert_eq!(None, parse_set_user_variable("set @@autocommit = 0"));
+        assert_eq!(None, parse_set_user_variable("set @a ="));
+    }
+
+    #[test]
+    fn parse_into() {
+        assert_eq!(
+            Some(SelectIntoStatement {
+                select_sql: "select id, name from t where id = (select 1) limit 1".to_string(),
+                variable_names: vec!["id".to_string(), "name".to_string()],
+            }),
+            parse_select_into("select id, name into @id, @Name from t where id = (select 1) limit 1")
+        );
+        assert_eq!(
+            Some(SelectIntoStatement {
+                select_sql: "select count(*) from t".to_string(),
+                variable_names: vec!["count".to_string()],
+            }),
+            parse_select_into("select count(*) from t into @count;")
+        );
+        assert_eq!(None, parse_select_into("select * into outfile '/tmp/t' from t"));
+        assert_eq!(None, parse_select_into("select @a from t"));
+    }
+}
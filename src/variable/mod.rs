@@ -1,2 +1,3 @@
 pub mod system;
+pub mod system_variable;
 pub mod user_defined;
\ No newline at end of file
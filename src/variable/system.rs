@@ -17,52 +17,74 @@
 use std::sync::{Mutex, Arc};
 
 /// System variable provider
-use datafusion::error::{Result};
+use datafusion::error::{DataFusionError, Result};
 use datafusion::variable::VarProvider;
 use datafusion::scalar::ScalarValue;
 
 use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
+use crate::meta::meta_const;
+use crate::variable::system_variable;
 
 /// System variable
 pub struct SystemVar {
     global_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
 }
 
 impl SystemVar {
     /// new system variable
-    pub fn new(global_context: Arc<Mutex<GlobalContext>>) -> Self {
+    pub fn new(global_context: Arc<Mutex<GlobalContext>>, session_context: SessionContext) -> Self {
         Self {
-            global_context
+            global_context,
+            session_context,
+        }
+    }
+
+    /// The variables of the state of the session, they are not in the variables of the server
+    fn get_session_state(&self, name: &str) -> Option<ScalarValue> {
+        let value = match name {
+            meta_const::SYSTEM_VARIABLE_WARNING_COUNT => *self.session_context.warning_count.lock().unwrap(),
+            meta_const::SYSTEM_VARIABLE_ERROR_COUNT => *self.session_context.error_count.lock().unwrap(),
+            meta_const::SYSTEM_VARIABLE_LAST_INSERT_ID | meta_const::SYSTEM_VARIABLE_IDENTITY => {
+                *self.session_context.last_insert_id.lock().unwrap()
+            }
+            _ => return None,
+        };
+        Some(ScalarValue::Int64(Some(value as i64)))
+    }
+
+    /// The session value of the system variable set by SET or the config, None reads the global value
+    fn get_session_variable(&self, name: &str) -> Option<ScalarValue> {
+        let value = self.session_context.get_session_variable(name)?;
+        match system_variable::get_system_variable_def(name) {
+            Some(variable_def) => Some(variable_def.to_scalar_value(value.as_str())),
+            None => Some(ScalarValue::Utf8(Some(value))),
         }
     }
 }
 
 impl VarProvider for SystemVar {
-    /// get system variable value
-    fn get_value(&self, mut var_names: Vec<String>) -> Result<ScalarValue> {
-        let value;
-        let mut is_session = false;
-        if var_names.len() > 1 {
-            if var_names[0].eq("@@session") {
-                is_session = true;
-            }
-            let avar_names: Vec<_> = var_names.drain(1..).collect();
-            value = avar_names.join(".");
+    /// get system variable value, `@@global.name` reads the global value,
+    /// `@@session.name`, `@@local.name` and `@@name` read the session value if the session has one
+    fn get_value(&self, var_names: Vec<String>) -> Result<ScalarValue> {
+        let (scope, name) = if var_names.len() > 1 {
+            (var_names[0].trim_start_matches("@@").to_lowercase(), var_names[1..].join("."))
         } else {
-            let a: String = var_names.join(".");
-            value = a.trim_start_matches("@@").to_string();
-        }
+            ("".to_string(), var_names.join(".").trim_start_matches("@@").to_string())
+        };
+        let name = name.to_lowercase();
 
-        let b = self.global_context.lock().unwrap();
-        let result = b.variable.get_variable(value.as_str());
-        match result {
-            None => {
-                let s = format!("{}-{}", env!("CARGO_PKG_VERSION").to_string(), var_names.concat());
-                Ok(ScalarValue::Utf8(Option::from(s)))
+        if scope != "global" {
+            if let Some(value) = self.get_session_state(name.as_str()) {
+                return Ok(value);
             }
-            Some(value) => {
-                Ok(value.clone())
+            if let Some(value) = self.get_session_variable(name.as_str()) {
+                return Ok(value);
             }
         }
+
+        let value = self.global_context.lock().unwrap().variable.get_variable(name.as_str());
+        value.ok_or_else(|| DataFusionError::External(Box::new(system_variable::unknown_variable_error(name.as_str()))))
     }
 }
@@ -0,0 +1,212 @@
+use datafusion::scalar::ScalarValue;
+
+use crate::meta::meta_const;
+use crate::mysql::error::{MysqlError, MysqlResult};
+use crate::util::time_zone;
+
+/// The type of the value of a system variable, the value set is checked and normalized with it
+#[derive(Clone, Debug, PartialEq)]
+pub enum SystemVariableType {
+    /// ON or OFF, read as 1 or 0
+    Boolean,
+    /// The values out of the range are clamped to it
+    Integer { min: i64, max: i64 },
+    /// One of the values, case insensitive
+    Enumeration(&'static [&'static str]),
+    /// SYSTEM, UTC or an offset
+    TimeZone,
+    String,
+}
+
+/// A system variable of the server
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemVariableDef {
+    pub name: &'static str,
+    pub variable_type: SystemVariableType,
+    /// The global value when the server has no value for it
+    pub default_value: &'static str,
+    /// The variable has no session value, SET GLOBAL sets it
+    pub global_only: bool,
+    pub read_only: bool,
+}
+
+const TRANSACTION_ISOLATION_LEVELS: &[&str] = &["READ-UNCOMMITTED", "READ-COMMITTED", "REPEATABLE-READ", "SERIALIZABLE"];
+
+const fn session(name: &'static str, variable_type: SystemVariableType, default_value: &'static str) -> SystemVariableDef {
+    SystemVariableDef {
+        name,
+        variable_type,
+        default_value,
+        global_only: false,
+        read_only: false,
+    }
+}
+
+const fn global(name: &'static str, variable_type: SystemVariableType, default_value: &'static str) -> SystemVariableDef {
+    SystemVariableDef {
+        name,
+        variable_type,
+        default_value,
+        global_only: true,
+        read_only: false,
+    }
+}
+
+const fn read_only(name: &'static str, variable_type: SystemVariableType, default_value: &'static str) -> SystemVariableDef {
+    SystemVariableDef {
+        name,
+        variable_type,
+        default_value,
+        global_only: true,
+        read_only: true,
+    }
+}
+
+/// The system variables known by the server, the ones probed by the drivers and the ORMs at connect included
+pub const SYSTEM_VARIABLES: &[SystemVariableDef] = &[
+    session("auto_increment_increment", SystemVariableType::Integer { min: 1, max: 65535 }, "1"),
+    session(meta_const::SYSTEM_VARIABLE_AUTOCOMMIT, SystemVariableType::Boolean, "ON"),
+    session(meta_const::SYSTEM_VARIABLE_CHARACTER_SET_CLIENT, SystemVariableType::String, "utf8mb4"),
+    session(meta_const::SYSTEM_VARIABLE_CHARACTER_SET_CONNECTION, SystemVariableType::String, "utf8mb4"),
+    session(meta_const::SYSTEM_VARIABLE_CHARACTER_SET_RESULTS, SystemVariableType::String, "utf8mb4"),
+    session("character_set_server", SystemVariableType::String, "utf8mb4"),
+    session("collation_connection", SystemVariableType::String, "utf8mb4_0900_ai_ci"),
+    session("collation_server", SystemVariableType::String, "utf8mb4_0900_ai_ci"),
+    session(meta_const::SYSTEM_VARIABLE_CTE_MAX_RECURSION_DEPTH, SystemVariableType::Integer { min: 0, max: 4294967295 }, "1000"),
+    global("init_connect", SystemVariableType::String, ""),
+    session(meta_const::SYSTEM_VARIABLE_INNODB_LOCK_WAIT_TIMEOUT, SystemVariableType::Integer { min: 1, max: 1073741824 }, "50"),
+    session(meta_const::SYSTEM_VARIABLE_INTERACTIVE_TIMEOUT, SystemVariableType::Integer { min: 1, max: 31536000 }, "28800"),
+    read_only("license", SystemVariableType::String, "Apache-2.0"),
+    read_only("lower_case_table_names", SystemVariableType::Integer { min: 0, max: 2 }, "0"),
+    global(meta_const::SYSTEM_VARIABLE_MAX_ALLOWED_PACKET, SystemVariableType::Integer { min: 1024, max: 1073741824 }, "67108864"),
+    session(meta_const::SYSTEM_VARIABLE_MAX_EXECUTION_TIME, SystemVariableType::Integer { min: 0, max: 4294967295 }, "0"),
+    session("net_buffer_length", SystemVariableType::Integer { min: 1024, max: 1048576 }, "16384"),
+    session("net_write_timeout", SystemVariableType::Integer { min: 1, max: 31536000 }, "60"),
+    read_only("performance_schema", SystemVariableType::Boolean, "OFF"),
+    session("sql_select_limit", SystemVariableType::Integer { min: 0, max: i64::MAX }, "9223372036854775807"),
+    session(meta_const::SYSTEM_VARIABLE_SQL_MODE, SystemVariableType::String, meta_const::DEFAULT_SQL_MODE),
+    read_only("system_time_zone", SystemVariableType::String, "UTC"),
+    session(meta_const::SYSTEM_VARIABLE_TIME_ZONE, SystemVariableType::TimeZone, "SYSTEM"),
+    session("transaction_isolation", SystemVariableType::Enumeration(TRANSACTION_ISOLATION_LEVELS), "REPEATABLE-READ"),
+    session("transaction_read_only", SystemVariableType::Boolean, "OFF"),
+    read_only("version", SystemVariableType::String, "8.0.25"),
+    read_only("version_comment", SystemVariableType::String, "sparrow"),
+    session(meta_const::SYSTEM_VARIABLE_WAIT_TIMEOUT, SystemVariableType::Integer { min: 1, max: 31536000 }, "28800"),
+];
+
+pub fn get_system_variable_def(name: &str) -> Option<&'static SystemVariableDef> {
+    SYSTEM_VARIABLES.iter().find(|variable_def| variable_def.name.eq_ignore_ascii_case(name))
+}
+
+pub fn unknown_variable_error(name: &str) -> MysqlError {
+    MysqlError::new_server_error(1193, "HY000", format!("Unknown system variable '{}'", name).as_str())
+}
+
+/// The definition of the variable set by SET, SET GLOBAL if global
+pub fn get_settable_variable_def(name: &str, global: bool) -> MysqlResult<&'static SystemVariableDef> {
+    let variable_def = get_system_variable_def(name).ok_or_else(|| unknown_variable_error(name))?;
+    if variable_def.read_only {
+        return Err(MysqlError::new_server_error(
+            1238,
+            "HY000",
+            format!("Variable '{}' is a read only variable", variable_def.name).as_str(),
+        ));
+    }
+    if variable_def.global_only && !global {
+        return Err(MysqlError::new_server_error(
+            1229,
+            "HY000",
+            format!("Variable '{}' is a GLOBAL variable and should be set with SET GLOBAL", variable_def.name).as_str(),
+        ));
+    }
+    Ok(variable_def)
+}
+
+fn wrong_value_error(name: &str, value: &str) -> MysqlError {
+    MysqlError::new_server_error(
+        1231,
+        "42000",
+        format!("Variable '{}' can't be set to the value of '{}'", name, value).as_str(),
+    )
+}
+
+impl SystemVariableDef {
+    /// The value kept for SET, ON or OFF for the booleans, the clamped number for the integers
+    /// and the upper case value for the enumerations
+    pub fn parse_value(&self, value: &str) -> MysqlResult<String> {
+        match &self.variable_type {
+            SystemVariableType::Boolean => match value.to_uppercase().as_str() {
+                "1" | "ON" | "TRUE" => Ok("ON".to_string()),
+                "0" | "OFF" | "FALSE" => Ok("OFF".to_string()),
+                _ => Err(wrong_value_error(self.name, value)),
+            },
+            SystemVariableType::Integer { min, max } => match value.parse::<i128>() {
+                Ok(number) => Ok(number.max(*min as i128).min(*max as i128).to_string()),
+                Err(_) => Err(MysqlError::new_server_error(
+                    1232,
+                    "42000",
+                    format!("Incorrect argument type to variable '{}'", self.name).as_str(),
+                )),
+            },
+            SystemVariableType::Enumeration(values) => values
+                .iter()
+                .find(|enumeration| enumeration.eq_ignore_ascii_case(value))
+                .map(|enumeration| enumeration.to_string())
+                .ok_or_else(|| wrong_value_error(self.name, value)),
+            SystemVariableType::TimeZone => match time_zone::parse_time_zone(value) {
+                Some(_) => Ok(value.to_string()),
+                None => Err(MysqlError::new_server_error(
+                    1298,
+                    "HY000",
+                    format!("Unknown or incorrect time zone: '{}'", value).as_str(),
+                )),
+            },
+            SystemVariableType::String => Ok(value.to_string()),
+        }
+    }
+
+    /// The value read by SELECT @@name, the booleans and the integers are numbers
+    pub fn to_scalar_value(&self, value: &str) -> ScalarValue {
+        match &self.variable_type {
+            SystemVariableType::Boolean => {
+                let value = if value.eq_ignore_ascii_case("ON") || value == "1" { 1 } else { 0 };
+                ScalarValue::Int64(Some(value))
+            }
+            SystemVariableType::Integer { .. } => match value.parse::<i64>() {
+                Ok(number) => ScalarValue::Int64(Some(number)),
+                Err(_) => ScalarValue::Utf8(Some(value.to_string())),
+            },
+            _ => ScalarValue::Utf8(Some(value.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let autocommit = get_system_variable_def("AUTOCOMMIT").unwrap();
+        assert_eq!("OFF", autocommit.parse_value("0").unwrap());
+        assert_eq!(1231, autocommit.parse_value("2").unwrap_err().error_number());
+        assert_eq!(ScalarValue::Int64(Some(1)), autocommit.to_scalar_value("ON"));
+
+        let max_allowed_packet = get_system_variable_def("max_allowed_packet").unwrap();
+        assert_eq!("1024", max_allowed_packet.parse_value("1").unwrap());
+        assert_eq!(1232, max_allowed_packet.parse_value("a lot").unwrap_err().error_number());
+
+        let transaction_isolation = get_system_variable_def("transaction_isolation").unwrap();
+        assert_eq!("READ-COMMITTED", transaction_isolation.parse_value("read-committed").unwrap());
+        assert_eq!(1231, transaction_isolation.parse_value("READ COMMITTED").unwrap_err().error_number());
+
+        let time_zone = get_system_variable_def("time_zone").unwrap();
+        assert_eq!(1298, time_zone.parse_value("Mars/Olympus").unwrap_err().error_number());
+
+        assert_eq!(None, get_system_variable_def("no_such_variable"));
+        assert_eq!(1193, get_settable_variable_def("no_such_variable", false).unwrap_err().error_number());
+        assert_eq!(1238, get_settable_variable_def("version", true).unwrap_err().error_number());
+        assert_eq!(1229, get_settable_variable_def("max_allowed_packet", false).unwrap_err().error_number());
+        assert!(get_settable_variable_def("max_allowed_packet", true).is_ok());
+    }
+}
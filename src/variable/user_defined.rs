@@ -16,32 +16,31 @@
 // under the License.
 
 //! System variable provider
-use std::sync::{Mutex, Arc};
-
 use datafusion::error::{Result};
 use datafusion::variable::VarProvider;
 use datafusion::scalar::ScalarValue;
 
-use crate::core::global_context::GlobalContext;
+use crate::core::session_context::SessionContext;
 
 /// user defined variable
 pub struct UserDefinedVar {
-    core_context: Arc<Mutex<GlobalContext>>,
+    session_context: SessionContext,
 }
 
 impl UserDefinedVar {
     /// new user defined variable
-    pub fn new(core_context: Arc<Mutex<GlobalContext>>) -> Self {
+    pub fn new(session_context: SessionContext) -> Self {
         Self {
-            core_context
+            session_context
         }
     }
 }
 
 impl VarProvider for UserDefinedVar {
-    /// Get user defined variable value
+    /// Get user defined variable value, the variables which are not set are NULL
     fn get_value(&self, var_names: Vec<String>) -> Result<ScalarValue> {
-        let s = format!("{}-{}", "user-defined-var".to_string(), var_names.concat());
-        Ok(ScalarValue::Utf8(Option::from(s)))
+        let name = var_names.join(".").trim_start_matches('@').to_lowercase();
+        let value = self.session_context.user_variables.lock().unwrap().get(name.as_str()).cloned();
+        Ok(value.unwrap_or(ScalarValue::Utf8(None)))
     }
 }